@@ -0,0 +1,195 @@
+//! Per-instance timings of the hot paths: generators, Naive Bayes and
+//! Hoeffding Tree training and prediction, the classification evaluator,
+//! and ARFF parsing.
+//!
+//! Run with `cargo bench --features bench`, optionally followed by `--` and
+//! a substring of the benchmark names to run only those, e.g.
+//! `cargo bench --features bench -- naive_bayes`.
+
+use rivu::classifiers::attribute_class_observers::GaussianNumericAttributeClassObserver;
+use rivu::classifiers::hoeffding_tree::LeafPredictionOption;
+use rivu::classifiers::hoeffding_tree::split_criteria::GiniSplitCriterion;
+use rivu::classifiers::{Classifier, HoeffdingTree, NaiveBayes};
+use rivu::core::instances::Instance;
+use rivu::evaluation::{BasicClassificationEvaluator, BasicEstimator, PerformanceEvaluator};
+use rivu::streams::Stream;
+use rivu::streams::arff::ArffFileStream;
+use rivu::streams::generators::{
+    AgrawalFunction, AgrawalGenerator, AssetNegotiationGenerator, AssetRule, SeaFunction,
+    SeaGenerator,
+};
+use std::fmt::Write as _;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Instances each learner benchmark cycles through.
+const DATA_SIZE: usize = 10_000;
+/// Samples timed per benchmark, after a warm-up of the same length.
+const SAMPLES: usize = 15;
+/// Rough duration of one sample.
+const SAMPLE_TIME: Duration = Duration::from_millis(100);
+
+type MakeLearner = fn() -> Box<dyn Classifier>;
+
+fn main() {
+    let filter: Option<String> = std::env::args().skip(1).find(|a| !a.starts_with("--"));
+    let run = |name: &str| filter.as_deref().is_none_or(|f| name.contains(f));
+
+    if run("generators/sea") {
+        let mut sea = SeaGenerator::new(SeaFunction::F1, false, 10, None, 1).unwrap();
+        bench("generators/sea", || black_box(sea.next_instance()));
+    }
+    if run("generators/sea_fast_rng") {
+        let mut sea = SeaGenerator::new(SeaFunction::F1, false, 10, None, 1)
+            .unwrap()
+            .with_fast_rng();
+        bench("generators/sea_fast_rng", || black_box(sea.next_instance()));
+    }
+    if run("generators/agrawal") {
+        let mut agrawal = AgrawalGenerator::new(AgrawalFunction::F1, false, 0.05, None, 1).unwrap();
+        bench("generators/agrawal", || black_box(agrawal.next_instance()));
+    }
+    if run("generators/asset") {
+        let mut asset = AssetNegotiationGenerator::new(AssetRule::R1, false, 0.05, 1).unwrap();
+        bench("generators/asset", || black_box(asset.next_instance()));
+    }
+
+    let data = sea_data(DATA_SIZE);
+    let header = Arc::new(
+        SeaGenerator::new(SeaFunction::F1, false, 10, None, 1)
+            .unwrap()
+            .header()
+            .clone(),
+    );
+    let learners: [(&str, MakeLearner); 2] = [
+        ("naive_bayes", || Box::new(NaiveBayes::new())),
+        ("hoeffding_tree", || Box::new(hoeffding_tree())),
+    ];
+    for (name, make) in learners {
+        let train = format!("{name}/train");
+        if run(&train) {
+            let mut learner = make();
+            learner.set_model_context(header.clone());
+            let mut next = cycle(&data);
+            bench(&train, || learner.train_on_instance(next()));
+        }
+        let predict = format!("{name}/predict");
+        if run(&predict) {
+            let mut learner = make();
+            learner.set_model_context(header.clone());
+            for instance in &data {
+                learner.train_on_instance(instance.as_ref());
+            }
+            let mut votes = Vec::new();
+            let mut next = cycle(&data);
+            bench(&predict, || {
+                learner.get_votes_into(next(), &mut votes);
+                black_box(&votes);
+            });
+        }
+    }
+
+    if run("evaluator/add_result") {
+        let mut evaluator =
+            BasicClassificationEvaluator::<BasicEstimator>::new(2, true, true, true, true);
+        let votes = [0.3, 0.7];
+        let mut next = cycle(&data);
+        bench("evaluator/add_result", || {
+            evaluator.add_result(next(), &votes)
+        });
+    }
+
+    if run("arff/parse") {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sea.arff");
+        std::fs::write(&path, sea_arff(&data)).unwrap();
+        let mut stream = ArffFileStream::new(path, 3).unwrap();
+        bench("arff/parse", || {
+            if !stream.has_more_instances() {
+                stream.restart().unwrap();
+            }
+            black_box(stream.next_instance())
+        });
+    }
+}
+
+/// Times `f` over [`SAMPLES`] samples and prints the median time per call.
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    let mut calls = 1u64;
+    let warmup = Instant::now();
+    while warmup.elapsed() < SAMPLE_TIME {
+        for _ in 0..calls {
+            black_box(f());
+        }
+        calls *= 2;
+    }
+    let per_sample =
+        (calls as f64 * SAMPLE_TIME.as_secs_f64() / warmup.elapsed().as_secs_f64()).max(1.0) as u64;
+
+    let mut nanos: Vec<f64> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..per_sample {
+                black_box(f());
+            }
+            start.elapsed().as_nanos() as f64 / per_sample as f64
+        })
+        .collect();
+    nanos.sort_by(f64::total_cmp);
+    let median = nanos[SAMPLES / 2];
+    let spread = (nanos[SAMPLES - 1] - nanos[0]) / 2.0;
+    println!(
+        "{name:<26} {median:>10.1} ns/instance (± {spread:.1})  {:>12.0} instances/s",
+        1e9 / median
+    );
+}
+
+/// Returns the instances of `data` one after another, starting over at the
+/// end.
+fn cycle<'a>(data: &'a [Box<dyn Instance>]) -> impl FnMut() -> &'a dyn Instance {
+    let mut i = 0;
+    move || {
+        let instance = data[i].as_ref();
+        i = (i + 1) % data.len();
+        instance
+    }
+}
+
+fn sea_data(n: usize) -> Vec<Box<dyn Instance>> {
+    let mut sea = SeaGenerator::new(SeaFunction::F1, false, 10, None, 7).unwrap();
+    (0..n).filter_map(|_| sea.next_instance()).collect()
+}
+
+fn sea_arff(data: &[Box<dyn Instance>]) -> String {
+    let mut text = String::from("@relation sea\n");
+    for i in 1..=3 {
+        let _ = writeln!(text, "@attribute attrib{i} numeric");
+    }
+    text.push_str("@attribute class {groupA,groupB}\n@data\n");
+    for instance in data {
+        let value = |i| instance.value_at_index(i).unwrap_or(f64::NAN);
+        let class = if value(3) == 0.0 { "groupA" } else { "groupB" };
+        let _ = writeln!(text, "{},{},{},{class}", value(0), value(1), value(2));
+    }
+    text
+}
+
+/// A Hoeffding Tree with MOA's default parameters and the Gini criterion.
+fn hoeffding_tree() -> HoeffdingTree {
+    HoeffdingTree::new(
+        usize::MAX,
+        Box::new(GaussianNumericAttributeClassObserver::new()),
+        1000,
+        200,
+        Box::new(GiniSplitCriterion::new()),
+        1e-7,
+        0.05,
+        false,
+        false,
+        false,
+        false,
+        LeafPredictionOption::AdaptiveNaiveBayes,
+        None,
+    )
+}
@@ -0,0 +1,16 @@
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use std::sync::Arc;
+
+/// Online, unsupervised anomaly detector.
+///
+/// Detectors see the feature vector of each instance (every attribute but
+/// the class); labels, when present, are only used for evaluation.
+pub trait AnomalyDetector {
+    fn set_model_context(&mut self, header: Arc<InstanceHeader>);
+
+    /// Anomaly score of `instance` in `[0, 1]`; higher is more anomalous.
+    fn score_instance(&self, instance: &dyn Instance) -> f64;
+
+    fn train_on_instance(&mut self, instance: &dyn Instance);
+}
@@ -0,0 +1,332 @@
+use crate::anomaly::AnomalyDetector;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::error::RivuError;
+use crate::evaluation::ClusteringEvaluator;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::io::Error;
+use std::sync::Arc;
+
+/// Streaming Half-Space Trees (Tan, Ting & Liu, 2011).
+///
+/// An ensemble of `n_trees` random, complete binary trees of depth
+/// `height`. Each node splits a random feature at the middle of its randomly
+/// perturbed work range. Nodes count the instances of the latest window and
+/// keep the counts of the previous (reference) window; every `window_size`
+/// instances the latest counts become the reference. An instance's score
+/// sums, over trees, the reference mass of the deepest node it reaches
+/// (stopping early where the mass drops below `size_limit`) scaled by
+/// `2^depth`; the result is inverted and normalized so that `1` is the most
+/// anomalous.
+///
+/// Features are scaled to `[0, 1]` with the running minimum and maximum of
+/// each feature. Until the first window is complete every score is `0`.
+pub struct HalfSpaceTrees {
+    n_trees: usize,
+    height: usize,
+    window_size: u64,
+    size_limit: f64,
+    seed: u64,
+
+    header: Option<Arc<InstanceHeader>>,
+    trees: Vec<Tree>,
+    mins: Vec<f64>,
+    maxs: Vec<f64>,
+    seen_in_window: u64,
+    has_reference: bool,
+}
+
+/// Complete binary tree stored in heap order: node `i` has children
+/// `2i + 1` and `2i + 2`; the last level is made of leaves.
+struct Tree {
+    split_feature: Vec<usize>,
+    split_value: Vec<f64>,
+    reference: Vec<f64>,
+    latest: Vec<f64>,
+}
+
+impl HalfSpaceTrees {
+    pub fn new(n_trees: usize, height: usize, window_size: u64, seed: u64) -> Result<Self, Error> {
+        if n_trees == 0 {
+            return Err(RivuError::config("n_trees must be > 0").into());
+        }
+        if height == 0 || height > 30 {
+            return Err(RivuError::config("height must be in [1, 30]").into());
+        }
+        if window_size == 0 {
+            return Err(RivuError::config("window_size must be > 0").into());
+        }
+        Ok(Self {
+            n_trees,
+            height,
+            window_size,
+            size_limit: 0.1 * window_size as f64,
+            seed,
+            header: None,
+            trees: Vec::new(),
+            mins: Vec::new(),
+            maxs: Vec::new(),
+            seen_in_window: 0,
+            has_reference: false,
+        })
+    }
+
+    fn build_trees(&mut self, dims: usize) {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let nodes = (1usize << (self.height + 1)) - 1;
+        let internal = (1usize << self.height) - 1;
+        self.trees = (0..self.n_trees)
+            .map(|_| {
+                // Work range of each dimension: [s - 2 max(s, 1 - s), s + 2 max(s, 1 - s)].
+                let mut ranges: Vec<(f64, f64)> = (0..dims)
+                    .map(|_| {
+                        let s: f64 = rng.random();
+                        let half = 2.0 * s.max(1.0 - s);
+                        (s - half, s + half)
+                    })
+                    .collect();
+                let mut split_feature = vec![0; internal];
+                let mut split_value = vec![0.0; internal];
+                grow(
+                    0,
+                    internal,
+                    &mut ranges,
+                    &mut split_feature,
+                    &mut split_value,
+                    &mut rng,
+                );
+                Tree {
+                    split_feature,
+                    split_value,
+                    reference: vec![0.0; nodes],
+                    latest: vec![0.0; nodes],
+                }
+            })
+            .collect();
+        self.mins = vec![f64::INFINITY; dims];
+        self.maxs = vec![f64::NEG_INFINITY; dims];
+    }
+
+    fn scaled_features(&self, instance: &dyn Instance) -> Option<Vec<f64>> {
+        let x = ClusteringEvaluator::features_of(instance);
+        if x.len() != self.mins.len() {
+            return None;
+        }
+        Some(
+            x.iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    if !v.is_finite() {
+                        return 0.5;
+                    }
+                    let span = self.maxs[i] - self.mins[i];
+                    if span > 0.0 {
+                        ((v - self.mins[i]) / span).clamp(0.0, 1.0)
+                    } else {
+                        0.5
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn max_score(&self) -> f64 {
+        self.n_trees as f64 * self.window_size as f64 * (1u64 << self.height) as f64
+    }
+}
+
+fn grow(
+    node: usize,
+    internal: usize,
+    ranges: &mut [(f64, f64)],
+    split_feature: &mut [usize],
+    split_value: &mut [f64],
+    rng: &mut StdRng,
+) {
+    if node >= internal || ranges.is_empty() {
+        return;
+    }
+    let q = rng.random_range(0..ranges.len());
+    let (lo, hi) = ranges[q];
+    let mid = (lo + hi) / 2.0;
+    split_feature[node] = q;
+    split_value[node] = mid;
+
+    ranges[q] = (lo, mid);
+    grow(
+        2 * node + 1,
+        internal,
+        ranges,
+        split_feature,
+        split_value,
+        rng,
+    );
+    ranges[q] = (mid, hi);
+    grow(
+        2 * node + 2,
+        internal,
+        ranges,
+        split_feature,
+        split_value,
+        rng,
+    );
+    ranges[q] = (lo, hi);
+}
+
+impl Tree {
+    /// Heap indices of the nodes visited by `x`, root first.
+    fn path(&self, x: &[f64]) -> Vec<usize> {
+        let internal = self.split_feature.len();
+        let mut node = 0usize;
+        let mut path = vec![node];
+        while node < internal && !x.is_empty() {
+            let goes_left = x[self.split_feature[node]] < self.split_value[node];
+            node = 2 * node + if goes_left { 1 } else { 2 };
+            path.push(node);
+        }
+        path
+    }
+}
+
+impl AnomalyDetector for HalfSpaceTrees {
+    fn set_model_context(&mut self, header: Arc<InstanceHeader>) {
+        let dims = header.number_of_attributes().saturating_sub(1);
+        self.header = Some(header);
+        self.build_trees(dims);
+        self.seen_in_window = 0;
+        self.has_reference = false;
+    }
+
+    fn score_instance(&self, instance: &dyn Instance) -> f64 {
+        if !self.has_reference {
+            return 0.0;
+        }
+        let Some(x) = self.scaled_features(instance) else {
+            return 0.0;
+        };
+
+        let mut score = 0.0;
+        for tree in &self.trees {
+            let mut depth = 0;
+            let mut mass = 0.0;
+            for node in tree.path(&x) {
+                mass = tree.reference[node];
+                if mass <= self.size_limit {
+                    break;
+                }
+                depth += 1;
+            }
+            score += mass * (1u64 << depth.min(self.height)) as f64;
+        }
+        (1.0 - score / self.max_score()).clamp(0.0, 1.0)
+    }
+
+    fn train_on_instance(&mut self, instance: &dyn Instance) {
+        if self.trees.is_empty() {
+            let dims = ClusteringEvaluator::features_of(instance).len();
+            self.build_trees(dims);
+        }
+        for (i, v) in ClusteringEvaluator::features_of(instance)
+            .iter()
+            .enumerate()
+        {
+            if i < self.mins.len() && v.is_finite() {
+                self.mins[i] = self.mins[i].min(*v);
+                self.maxs[i] = self.maxs[i].max(*v);
+            }
+        }
+        let Some(x) = self.scaled_features(instance) else {
+            return;
+        };
+
+        for tree in &mut self.trees {
+            for node in tree.path(&x) {
+                tree.latest[node] += 1.0;
+            }
+        }
+
+        self.seen_in_window += 1;
+        if self.seen_in_window == self.window_size {
+            for tree in &mut self.trees {
+                std::mem::swap(&mut tree.reference, &mut tree.latest);
+                tree.latest.iter_mut().for_each(|m| *m = 0.0);
+            }
+            self.seen_in_window = 0;
+            self.has_reference = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+
+    fn header() -> Arc<InstanceHeader> {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())) as AttributeRef,
+            Arc::new(NumericAttribute::new("y".into())) as AttributeRef,
+            Arc::new(NumericAttribute::new("class".into())) as AttributeRef,
+        ];
+        Arc::new(InstanceHeader::new("points".into(), attrs, 2))
+    }
+
+    fn point(h: &Arc<InstanceHeader>, x: f64, y: f64) -> DenseInstance {
+        DenseInstance::new(Arc::clone(h), vec![x, y, 0.0], 1.0)
+    }
+
+    fn trained() -> (HalfSpaceTrees, Arc<InstanceHeader>) {
+        let h = header();
+        let mut hst = HalfSpaceTrees::new(25, 8, 200, 1).unwrap();
+        hst.set_model_context(Arc::clone(&h));
+        let mut rng = StdRng::seed_from_u64(5);
+        // Bounding points fix the scaling range; the bulk sits in one corner.
+        hst.train_on_instance(&point(&h, 0.0, 0.0));
+        hst.train_on_instance(&point(&h, 10.0, 10.0));
+        for _ in 0..598 {
+            let x = rng.random::<f64>();
+            let y = rng.random::<f64>();
+            hst.train_on_instance(&point(&h, x, y));
+        }
+        (hst, h)
+    }
+
+    #[test]
+    fn ctor_guards() {
+        assert!(HalfSpaceTrees::new(0, 5, 10, 1).is_err());
+        assert!(HalfSpaceTrees::new(5, 0, 10, 1).is_err());
+        assert!(HalfSpaceTrees::new(5, 5, 0, 1).is_err());
+    }
+
+    #[test]
+    fn scores_are_zero_until_the_first_window() {
+        let h = header();
+        let mut hst = HalfSpaceTrees::new(5, 4, 10, 1).unwrap();
+        hst.set_model_context(Arc::clone(&h));
+        for i in 0..9 {
+            hst.train_on_instance(&point(&h, i as f64, 0.0));
+        }
+        assert_eq!(hst.score_instance(&point(&h, 100.0, 100.0)), 0.0);
+        hst.train_on_instance(&point(&h, 9.0, 0.0));
+        assert!(hst.has_reference);
+    }
+
+    #[test]
+    fn outliers_score_higher_than_inliers() {
+        let (hst, h) = trained();
+        let inlier = hst.score_instance(&point(&h, 0.5, 0.5));
+        let outlier = hst.score_instance(&point(&h, 9.0, 9.0));
+        assert!((0.0..=1.0).contains(&inlier));
+        assert!(outlier > inlier, "outlier {outlier} <= inlier {inlier}");
+    }
+
+    #[test]
+    fn same_seed_gives_same_scores() {
+        let (a, h) = trained();
+        let (b, _) = trained();
+        let p = point(&h, 3.0, 7.0);
+        assert_eq!(a.score_instance(&p), b.score_instance(&p));
+    }
+}
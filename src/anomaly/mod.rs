@@ -0,0 +1,5 @@
+mod anomaly_detector;
+mod half_space_trees;
+
+pub use anomaly_detector::AnomalyDetector;
+pub use half_space_trees::HalfSpaceTrees;
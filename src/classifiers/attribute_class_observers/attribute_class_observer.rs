@@ -9,6 +9,18 @@ pub trait AttributeClassObserver {
         att_val: f64,
         class_val: usize,
     ) -> Option<f64>;
+    /// Adds `ln P(att_val | class)` to `log_scores[class]` for every class,
+    /// with `-inf` for classes the observer has no estimate for. This is the
+    /// Naive Bayes hot path, called once per attribute rather than once per
+    /// attribute and class.
+    fn add_log_likelihoods(&self, att_val: f64, log_scores: &mut [f64]) {
+        for (class_val, score) in log_scores.iter_mut().enumerate() {
+            *score += self
+                .probability_of_attribute_value_given_class(att_val, class_val)
+                .unwrap_or(0.0)
+                .ln();
+        }
+    }
     fn get_best_evaluated_split_suggestion(
         &self,
         criterion: &dyn SplitCriterion,
@@ -3,7 +3,10 @@ use crate::classifiers::conditional_tests::attribute_split_suggestion::Attribute
 use crate::classifiers::hoeffding_tree::instance_conditional_test::NumericAttributeBinaryTest;
 use crate::classifiers::hoeffding_tree::split_criteria::SplitCriterion;
 use crate::core::estimators::gaussian_estimator::GaussianEstimator;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GaussianNumericAttributeClassObserver {
     min_value_observed_per_class: Vec<f64>,
     max_value_observed_per_class: Vec<f64>,
@@ -35,6 +38,11 @@ impl GaussianNumericAttributeClassObserver {
         }
     }
 
+    /// Estimated distribution of the attribute, per class.
+    pub(crate) fn estimators_per_class(&self) -> &[Option<GaussianEstimator>] {
+        &self.attribute_value_distribution_per_class
+    }
+
     fn get_split_point_suggestions(&self) -> Vec<f64> {
         let mut min_val = f64::INFINITY;
         let mut max_val = f64::NEG_INFINITY;
@@ -72,10 +80,22 @@ impl GaussianNumericAttributeClassObserver {
         suggestions
     }
 
-    fn get_class_dists_resulting_from_binary_split(&self, split_value: f64) -> Vec<Vec<f64>> {
+    /// Fills `dists` with the class distributions left and right of
+    /// `split_value`, reusing its rows.
+    fn class_dists_resulting_from_binary_split_into(
+        &self,
+        split_value: f64,
+        dists: &mut Vec<Vec<f64>>,
+    ) {
         let num_classes = self.attribute_value_distribution_per_class.len();
-        let mut lhs = vec![0.0; num_classes];
-        let mut rhs = vec![0.0; num_classes];
+        dists.resize_with(2, Vec::new);
+        for dist in dists.iter_mut() {
+            dist.clear();
+            dist.resize(num_classes, 0.0);
+        }
+        let [lhs, rhs] = dists.as_mut_slice() else {
+            unreachable!("binary split has two branches");
+        };
 
         for (class_idx, est_opt) in self
             .attribute_value_distribution_per_class
@@ -95,7 +115,6 @@ impl GaussianNumericAttributeClassObserver {
                 }
             }
         }
-        vec![lhs, rhs]
     }
 }
 
@@ -145,6 +164,18 @@ impl AttributeClassObserver for GaussianNumericAttributeClassObserver {
         }
     }
 
+    fn add_log_likelihoods(&self, att_val: f64, log_scores: &mut [f64]) {
+        let estimators = &self.attribute_value_distribution_per_class;
+        for (class_val, score) in log_scores.iter_mut().enumerate() {
+            *score += match estimators.get(class_val) {
+                Some(Some(est)) if !att_val.is_nan() && est.get_total_weight_observed() > 0.0 => {
+                    est.log_probability_density(att_val)
+                }
+                _ => f64::NEG_INFINITY,
+            };
+        }
+    }
+
     fn get_best_evaluated_split_suggestion(
         &self,
         criterion: &dyn SplitCriterion,
@@ -154,9 +185,10 @@ impl AttributeClassObserver for GaussianNumericAttributeClassObserver {
     ) -> Option<AttributeSplitSuggestion> {
         let split_points = self.get_split_point_suggestions();
         let mut best: Option<AttributeSplitSuggestion> = None;
+        let mut post_dists = Vec::with_capacity(2);
 
         for split_value in split_points {
-            let post_dists = self.get_class_dists_resulting_from_binary_split(split_value);
+            self.class_dists_resulting_from_binary_split_into(split_value, &mut post_dists);
             let merit = criterion.get_merit_of_split(pre_split_dist, &post_dists);
 
             if best.is_none() || merit > best.as_ref().unwrap().get_merit() {
@@ -166,7 +198,7 @@ impl AttributeClassObserver for GaussianNumericAttributeClassObserver {
                         split_value,
                         true,
                     ))),
-                    post_dists,
+                    post_dists.clone(),
                     merit,
                 ));
             }
@@ -1,7 +1,9 @@
 pub use attribute_class_observer::AttributeClassObserver;
 pub use gaussian_numeric_attribute_class_observer::GaussianNumericAttributeClassObserver;
 pub use nominal_attribute_class_observer::NominalAttributeClassObserver;
+pub use observer_state::AttributeClassObserverState;
 pub mod attribute_class_observer;
 pub mod gaussian_numeric_attribute_class_observer;
 pub mod nominal_attribute_class_observer;
 pub mod null_attribute_class_observer;
+mod observer_state;
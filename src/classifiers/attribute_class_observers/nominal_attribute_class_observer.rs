@@ -4,8 +4,10 @@ use crate::classifiers::hoeffding_tree::instance_conditional_test::{
     NominalAttributeBinaryTest, NominalAttributeMultiwayTest,
 };
 use crate::classifiers::hoeffding_tree::split_criteria::SplitCriterion;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NominalAttributeClassObserver {
     total_weight_observed: f64,
     missing_weight_observed: f64,
@@ -115,6 +117,20 @@ impl AttributeClassObserver for NominalAttributeClassObserver {
         Some((count + 1.0) / (sum + k))
     }
 
+    fn add_log_likelihoods(&self, att_val: f64, log_scores: &mut [f64]) {
+        let rows = &self.attribute_value_distribution_per_class;
+        for (class_val, score) in log_scores.iter_mut().enumerate() {
+            *score += match rows.get(class_val) {
+                Some(row) if !att_val.is_nan() && !row.is_empty() => {
+                    let count = row.get(att_val as usize).copied().unwrap_or(0.0);
+                    let sum: f64 = row.iter().sum();
+                    ((count + 1.0) / (sum + row.len() as f64)).ln()
+                }
+                _ => f64::NEG_INFINITY,
+            };
+        }
+    }
+
     fn get_best_evaluated_split_suggestion(
         &self,
         criterion: &dyn SplitCriterion,
@@ -1,8 +1,10 @@
 use crate::classifiers::attribute_class_observers::AttributeClassObserver;
 use crate::classifiers::conditional_tests::attribute_split_suggestion::AttributeSplitSuggestion;
 use crate::classifiers::hoeffding_tree::split_criteria::SplitCriterion;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NullAttributeClassObserver {}
 
 impl NullAttributeClassObserver {
@@ -0,0 +1,97 @@
+use crate::classifiers::attribute_class_observers::null_attribute_class_observer::NullAttributeClassObserver;
+use crate::classifiers::attribute_class_observers::{
+    AttributeClassObserver, GaussianNumericAttributeClassObserver, NominalAttributeClassObserver,
+};
+use serde::{Deserialize, Serialize};
+
+/// Serializable form of the concrete attribute class observers.
+///
+/// Observers are stored behind `Box<dyn AttributeClassObserver>`, so
+/// persistence goes through this enum instead of serializing trait objects.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "state", rename_all = "kebab-case")]
+pub enum AttributeClassObserverState {
+    Nominal(NominalAttributeClassObserver),
+    GaussianNumeric(GaussianNumericAttributeClassObserver),
+    Null(NullAttributeClassObserver),
+}
+
+impl AttributeClassObserverState {
+    /// Captures the state of `observer`, or `None` for unknown observer types.
+    pub fn from_observer(observer: &dyn AttributeClassObserver) -> Option<Self> {
+        let any = observer.as_any();
+        if let Some(o) = any.downcast_ref::<NominalAttributeClassObserver>() {
+            return Some(Self::Nominal(o.clone()));
+        }
+        if let Some(o) = any.downcast_ref::<GaussianNumericAttributeClassObserver>() {
+            return Some(Self::GaussianNumeric(o.clone()));
+        }
+        if let Some(o) = any.downcast_ref::<NullAttributeClassObserver>() {
+            return Some(Self::Null(o.clone()));
+        }
+        None
+    }
+
+    /// Rebuilds the boxed observer.
+    pub fn into_observer(self) -> Box<dyn AttributeClassObserver> {
+        match self {
+            Self::Nominal(o) => Box::new(o),
+            Self::GaussianNumeric(o) => Box::new(o),
+            Self::Null(o) => Box::new(o),
+        }
+    }
+
+    /// Captures a learner's per-attribute observers, or `None` if any of
+    /// them has an unknown type.
+    pub fn capture_all(
+        observers: &[Option<Box<dyn AttributeClassObserver>>],
+    ) -> Option<Vec<Option<Self>>> {
+        observers
+            .iter()
+            .map(|o| match o {
+                None => Some(None),
+                Some(obs) => Self::from_observer(obs.as_ref()).map(Some),
+            })
+            .collect()
+    }
+
+    pub fn restore_all(states: Vec<Option<Self>>) -> Vec<Option<Box<dyn AttributeClassObserver>>> {
+        states
+            .into_iter()
+            .map(|s| s.map(Self::into_observer))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut obs = GaussianNumericAttributeClassObserver::new();
+        obs.observe_attribute_class(1.5, 0, 1.0);
+        obs.observe_attribute_class(2.5, 1, 2.0);
+
+        let state = AttributeClassObserverState::from_observer(&obs).unwrap();
+        let json = serde_json::to_string(&state).unwrap();
+        let back: AttributeClassObserverState = serde_json::from_str(&json).unwrap();
+        let rebuilt = back.into_observer();
+
+        for (x, c) in [(1.5, 0), (2.5, 1), (2.0, 0)] {
+            assert_eq!(
+                obs.probability_of_attribute_value_given_class(x, c),
+                rebuilt.probability_of_attribute_value_given_class(x, c)
+            );
+        }
+    }
+
+    #[test]
+    fn nominal_state_is_tagged() {
+        let mut obs = NominalAttributeClassObserver::new();
+        obs.observe_attribute_class(1.0, 0, 1.0);
+        let state = AttributeClassObserverState::from_observer(&obs).unwrap();
+        let v = serde_json::to_value(&state).unwrap();
+        assert_eq!(v.get("type").and_then(|t| t.as_str()), Some("nominal"));
+    }
+}
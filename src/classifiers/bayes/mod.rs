@@ -1,3 +1,3 @@
 mod naive_bayes;
 
-pub use naive_bayes::NaiveBayes;
+pub use naive_bayes::{NaiveBayes, NaiveBayesState};
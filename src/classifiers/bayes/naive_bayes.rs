@@ -1,12 +1,26 @@
 use crate::classifiers::attribute_class_observers::{
-    AttributeClassObserver, GaussianNumericAttributeClassObserver, NominalAttributeClassObserver,
+    AttributeClassObserver, AttributeClassObserverState, GaussianNumericAttributeClassObserver,
+    NominalAttributeClassObserver,
 };
 use crate::classifiers::classifier::Classifier;
+use crate::classifiers::{ClassifierState, LearnerState};
 use crate::core::attributes::NominalAttribute;
 use crate::core::instance_header::InstanceHeader;
 use crate::core::instances::Instance;
+use crate::error::RivuError;
+use crate::evaluation::{Measurement, MetricKey};
+use serde::{Deserialize, Serialize};
+use std::io::Error;
+use std::path::Path;
 use std::sync::Arc;
 
+/// Serializable snapshot of a trained [`NaiveBayes`] model.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NaiveBayesState {
+    pub observed_class_distribution: Vec<f64>,
+    pub attribute_observers: Vec<Option<AttributeClassObserverState>>,
+}
+
 pub struct NaiveBayes {
     header: Option<Arc<InstanceHeader>>,
     observed_class_distribution: Vec<f64>,
@@ -22,6 +36,40 @@ impl NaiveBayes {
         }
     }
 
+    /// Captures the class distribution and attribute observers.
+    pub fn state(&self) -> Result<NaiveBayesState, Error> {
+        let attribute_observers =
+            AttributeClassObserverState::capture_all(&self.attribute_observers)
+                .ok_or_else(|| RivuError::model("unknown attribute observer type"))?;
+        Ok(NaiveBayesState {
+            observed_class_distribution: self.observed_class_distribution.clone(),
+            attribute_observers,
+        })
+    }
+
+    /// Rebuilds a model saved with [`state`](Self::state), bound to `header`
+    /// if given.
+    pub fn from_state(state: NaiveBayesState, header: Option<Arc<InstanceHeader>>) -> Self {
+        Self {
+            header,
+            observed_class_distribution: state.observed_class_distribution,
+            attribute_observers: AttributeClassObserverState::restore_all(
+                state.attribute_observers,
+            ),
+        }
+    }
+
+    /// Loads a model saved with [`Classifier::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        match ClassifierState::load(path)? {
+            ClassifierState {
+                header,
+                learner: LearnerState::NaiveBayes(state),
+            } => Ok(Self::from_state(state, header.map(Arc::new))),
+            other => Err(other.wrong_learner("naive-bayes")),
+        }
+    }
+
     #[inline]
     fn ensure_observers_length(&mut self, num_model_atts: usize) {
         if self.attribute_observers.len() < num_model_atts {
@@ -49,47 +97,78 @@ impl NaiveBayes {
         }
     }
 
+    /// Class votes proportional to the prior times the likelihood of each
+    /// attribute value, as in MOA.
+    ///
+    /// Scores are accumulated across all classes at once, one attribute at a
+    /// time, as log-likelihoods. The votes are their exponentials; when every
+    /// product underflows to zero they are scaled by the largest, so the
+    /// ranking of the classes survives many attributes.
     pub fn do_naive_bayes_prediction(
         instance: &dyn Instance,
         observed_class_distribution: &[f64],
         attribute_observers: &[Option<Box<dyn AttributeClassObserver>>],
     ) -> Vec<f64> {
-        {
-            let mut votes = vec![0.0; observed_class_distribution.len()];
-            let observed_class_sum: f64 = observed_class_distribution.iter().copied().sum();
-
-            for class_index in 0..votes.len() {
-                let mut score = observed_class_distribution[class_index] / observed_class_sum;
+        let mut votes = Vec::new();
+        Self::naive_bayes_prediction_into(
+            instance,
+            observed_class_distribution,
+            attribute_observers,
+            &mut votes,
+        );
+        votes
+    }
 
-                for att_index in 0..(instance.number_of_attributes() - 1) {
-                    let inst_att_index = Self::model_att_index_to_instance_att_index(
-                        att_index,
-                        instance.class_index(),
-                    );
+    /// [`do_naive_bayes_prediction`](Self::do_naive_bayes_prediction) into
+    /// `votes`, reusing its allocation.
+    pub fn naive_bayes_prediction_into(
+        instance: &dyn Instance,
+        observed_class_distribution: &[f64],
+        attribute_observers: &[Option<Box<dyn AttributeClassObserver>>],
+        votes: &mut Vec<f64>,
+    ) {
+        let observed_class_sum: f64 = observed_class_distribution.iter().copied().sum();
+        votes.clear();
+        // Before any weight is seen there are no priors to score with.
+        if observed_class_sum <= 0.0 {
+            votes.resize(observed_class_distribution.len(), 0.0);
+            return;
+        }
+        votes.extend(
+            observed_class_distribution
+                .iter()
+                .map(|w| (w / observed_class_sum).ln()),
+        );
+        let log_scores = votes;
 
-                    let is_missing = instance.is_missing_at_index(inst_att_index).unwrap_or(true);
+        for att_index in 0..(instance.number_of_attributes() - 1) {
+            let inst_att_index =
+                Self::model_att_index_to_instance_att_index(att_index, instance.class_index());
 
-                    if is_missing {
-                        continue;
-                    };
+            let is_missing = instance.is_missing_at_index(inst_att_index).unwrap_or(true);
+            if is_missing {
+                continue;
+            };
 
-                    let Some(Some(obs)) = attribute_observers.get(att_index) else {
-                        continue;
-                    };
+            let Some(Some(obs)) = attribute_observers.get(att_index) else {
+                continue;
+            };
 
-                    let Some(x) = instance.value_at_index(inst_att_index) else {
-                        continue;
-                    };
+            let Some(x) = instance.value_at_index(inst_att_index) else {
+                continue;
+            };
 
-                    let p = obs
-                        .probability_of_attribute_value_given_class(x, class_index)
-                        .unwrap_or(0.0);
+            obs.add_log_likelihoods(x, log_scores);
+        }
 
-                    score *= p;
-                }
-                votes[class_index] = score;
-            }
-            votes
+        let max = log_scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let shift = if max.is_finite() && max.exp() == 0.0 {
+            max
+        } else {
+            0.0
+        };
+        for score in log_scores.iter_mut() {
+            *score = (*score - shift).exp();
         }
     }
 }
@@ -103,6 +182,22 @@ impl Classifier for NaiveBayes {
         )
     }
 
+    fn get_votes_into(&self, instance: &dyn Instance, votes: &mut Vec<f64>) {
+        NaiveBayes::naive_bayes_prediction_into(
+            instance,
+            &self.observed_class_distribution,
+            &self.attribute_observers,
+            votes,
+        );
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        vec![Measurement::new(
+            MetricKey::MODEL_TRAINING_WEIGHT,
+            self.observed_class_distribution.iter().sum(),
+        )]
+    }
+
     fn set_model_context(&mut self, header: Arc<InstanceHeader>) {
         let num_classes = header.number_of_classes();
         let num_model_atts = header.number_of_attributes().saturating_sub(1);
@@ -116,6 +211,13 @@ impl Classifier for NaiveBayes {
             .resize_with(num_model_atts, || None);
     }
 
+    fn snapshot_state(&self) -> Result<ClassifierState, Error> {
+        Ok(ClassifierState::new(
+            self.header.as_deref(),
+            LearnerState::NaiveBayes(self.state()?),
+        ))
+    }
+
     fn train_on_instance(&mut self, instance: &dyn Instance) {
         let header = match self.header.as_ref() {
             Some(header) => header.clone(),
@@ -319,6 +421,15 @@ mod tests {
         assert!(approx(votes[1], 0.15, EPS));
     }
 
+    #[test]
+    fn model_measurements_report_training_weight() {
+        let mut nb = NaiveBayes::new();
+        nb.observed_class_distribution = vec![4.0, 6.5];
+        let m = nb.model_measurements();
+        assert_eq!(m[0].name, "model_training_weight");
+        assert!(approx(m[0].value, 10.5, EPS));
+    }
+
     #[test]
     fn missing_attribute_is_ignored_in_votes() {
         let mut nb = NaiveBayes::new();
@@ -364,6 +475,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn votes_keep_the_ranking_when_every_likelihood_underflows() {
+        let mut nb = NaiveBayes::new();
+        nb.observed_class_distribution = vec![3.0, 3.0];
+        let mut gobs = GaussianNumericAttributeClassObserver::new();
+        for &x in &[-1.0, 0.0, 1.0] {
+            gobs.observe_attribute_class(x, 0, 1.0);
+            gobs.observe_attribute_class(x + 1.0, 1, 1.0);
+        }
+        nb.attribute_observers = vec![Some(Box::new(gobs))];
+
+        let far = TestInstance::new(vec![100.0, f64::NAN], 1, None, 1.0);
+        let votes = nb.get_votes_for_instance(&far);
+        assert_eq!(votes[1], 1.0, "votes={votes:?}");
+        assert!(votes[0] > 0.0 && votes[0] < votes[1], "votes={votes:?}");
+    }
+
     #[test]
     fn no_observers_means_votes_are_priors() {
         let mut nb = NaiveBayes::new();
@@ -499,4 +627,25 @@ mod tests {
         let v1 = nb.get_votes_for_instance(&near_c1);
         assert!(v1[1] > v1[0], "waiting C1 > C0; votes={:?}", v1);
     }
+
+    #[test]
+    fn save_model_writes_priors_and_observers_as_json() {
+        let a0 = nominal_attr_ref("A0", &["0", "1"]);
+        let class_attr = nominal_attr_ref("C", &["c0", "c1"]);
+        let header = InstanceHeader::new("rel".into(), vec![a0, class_attr], 1);
+
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(Arc::new(header));
+        let inst = TestInstance::new(vec![1.0, f64::NAN], 1, Some(1.0), 1.0);
+        nb.train_on_instance(&inst);
+
+        let mut buf = Vec::new();
+        nb.save_model(&mut buf).unwrap();
+        let state: NaiveBayesState = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(state.observed_class_distribution, vec![0.0, 1.0]);
+        assert!(matches!(
+            state.attribute_observers.as_slice(),
+            [Some(AttributeClassObserverState::Nominal(_))]
+        ));
+    }
 }
@@ -1,9 +1,121 @@
+use crate::classifiers::ClassifierState;
+use crate::classifiers::introspection::describe;
+use crate::classifiers::model_file::write_model_file;
+use crate::classifiers::onnx::write_onnx;
+use crate::classifiers::pmml::write_pmml;
 use crate::core::instance_header::InstanceHeader;
 use crate::core::instances::Instance;
+use crate::error::RivuError;
+use crate::evaluation::Measurement;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{Error, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 pub trait Classifier {
     fn get_votes_for_instance(&self, instance: &dyn Instance) -> Vec<f64>;
     fn set_model_context(&mut self, header: Arc<InstanceHeader>);
     fn train_on_instance(&mut self, instance: &dyn Instance);
+
+    /// Writes the votes for `instance` into `votes`, so that evaluation loops
+    /// can reuse one buffer instead of allocating per instance. Default:
+    /// replaces `votes` with [`get_votes_for_instance`](Self::get_votes_for_instance).
+    fn get_votes_into(&self, instance: &dyn Instance, votes: &mut Vec<f64>) {
+        *votes = self.get_votes_for_instance(instance);
+    }
+
+    /// Votes for each instance of `batch`, in order. Default: one
+    /// [`get_votes_for_instance`](Self::get_votes_for_instance) call per
+    /// instance; learners that can score a batch at once override it.
+    fn get_votes_for_batch(&self, batch: &[Box<dyn Instance>]) -> Vec<Vec<f64>> {
+        batch
+            .iter()
+            .map(|instance| self.get_votes_for_instance(instance.as_ref()))
+            .collect()
+    }
+
+    /// Trains on each instance of `batch`, in order. Default: one
+    /// [`train_on_instance`](Self::train_on_instance) call per instance, so
+    /// overrides must leave the model as that sequence would.
+    fn train_on_batch(&mut self, batch: &[Box<dyn Instance>]) {
+        for instance in batch {
+            self.train_on_instance(instance.as_ref());
+        }
+    }
+
+    /// Descriptive statistics of the current model, such as its size or the
+    /// training weight seen. Default: none.
+    fn model_measurements(&self) -> Vec<Measurement> {
+        Vec::new()
+    }
+
+    /// Snapshot of the trained model and its header, from which
+    /// [`ClassifierState::into_classifier`] rebuilds it.
+    ///
+    /// Learners without persistence support return `ErrorKind::Unsupported`.
+    fn snapshot_state(&self) -> Result<ClassifierState, Error> {
+        Err(RivuError::unsupported("this learner does not support saving models").into())
+    }
+
+    /// Structural dump of what the model learned, for visualization and
+    /// debugging; see [`introspection`](crate::classifiers::introspection).
+    fn to_json(&self) -> Result<Value, Error> {
+        Ok(describe(&self.snapshot_state()?))
+    }
+
+    /// Writes the [`snapshot_state`](Classifier::snapshot_state) to `writer`.
+    fn save_model(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.snapshot_state()?.write(writer)
+    }
+
+    /// Saves the model to `path` as a versioned
+    /// [model file](crate::classifiers::model_file), to be read back with
+    /// [`load_classifier`](crate::classifiers::load_classifier) or the
+    /// learner's own `load`.
+    ///
+    /// The model is encoded in memory and written next to `path` before
+    /// being renamed over it, so a learner that cannot be saved, or a failed
+    /// write, leaves an existing file at `path` as it was. The same holds
+    /// for the exports.
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        replace_file(path, write_model_file, &self.snapshot_state()?)
+    }
+
+    /// Exports the model to `path` as a [PMML](crate::classifiers::pmml)
+    /// document, for scoring engines outside Rivu.
+    fn export_pmml(&self, path: &Path) -> Result<(), Error> {
+        replace_file(path, write_pmml, &self.snapshot_state()?)
+    }
+
+    /// Exports the model to `path` as an [ONNX](crate::classifiers::onnx)
+    /// model, for standard inference runtimes.
+    fn export_onnx(&self, path: &Path) -> Result<(), Error> {
+        replace_file(path, write_onnx, &self.snapshot_state()?)
+    }
+}
+
+/// Writes `state` with `write` into memory, then to a sibling of `path`
+/// that is renamed over `path` once complete; the sibling is removed if
+/// writing it fails.
+fn replace_file(
+    path: &Path,
+    write: fn(&mut dyn Write, &ClassifierState) -> Result<(), Error>,
+    state: &ClassifierState,
+) -> Result<(), Error> {
+    let mut document = Vec::new();
+    write(&mut document, state)?;
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    let written = (|| {
+        let mut file = File::create(&partial)?;
+        file.write_all(&document)?;
+        file.sync_all()?;
+        fs::rename(&partial, path)
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    written
 }
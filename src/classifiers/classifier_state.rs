@@ -0,0 +1,191 @@
+use crate::classifiers::hoeffding_tree::HoeffdingTreeState;
+use crate::classifiers::model_file::read_model_file;
+use crate::classifiers::{
+    Classifier, HoeffdingAdaptiveTree, HoeffdingAdaptiveTreeState, HoeffdingTree, NaiveBayes,
+    NaiveBayesState, TargetMean, TargetMeanState,
+};
+use crate::core::instance_header::InstanceHeader;
+use crate::error::RivuError;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Error, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Learner-specific part of a [`ClassifierState`], tagged with the learner
+/// kind under `learner`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "learner", rename_all = "kebab-case")]
+pub enum LearnerState {
+    NaiveBayes(NaiveBayesState),
+    HoeffdingTree(HoeffdingTreeState),
+    HoeffdingAdaptiveTree(HoeffdingAdaptiveTreeState),
+    TargetMean(TargetMeanState),
+}
+
+impl LearnerState {
+    /// The `learner` tag, e.g. `naive-bayes`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LearnerState::NaiveBayes(_) => "naive-bayes",
+            LearnerState::HoeffdingTree(_) => "hoeffding-tree",
+            LearnerState::HoeffdingAdaptiveTree(_) => "hoeffding-adaptive-tree",
+            LearnerState::TargetMean(_) => "target-mean",
+        }
+    }
+}
+
+/// Everything needed to rebuild a trained classifier: the learner's state and
+/// the header it was trained on. Returned by
+/// [`Classifier::snapshot_state`], written as JSON by
+/// [`Classifier::save_model`] and as a model file by [`Classifier::save`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClassifierState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<InstanceHeader>,
+    #[serde(flatten)]
+    pub learner: LearnerState,
+}
+
+impl ClassifierState {
+    pub fn new(header: Option<&InstanceHeader>, learner: LearnerState) -> Self {
+        Self {
+            header: header.cloned(),
+            learner,
+        }
+    }
+
+    /// Writes the state as bare JSON; model files wrap it with
+    /// [`write_model_file`](crate::classifiers::model_file::write_model_file).
+    pub fn write(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        serde_json::to_writer(writer, self).map_err(Error::other)
+    }
+
+    pub fn read(reader: impl Read) -> Result<Self, Error> {
+        serde_json::from_reader(reader)
+            .map_err(|e| RivuError::model(format!("invalid saved model: {e}")).into())
+    }
+
+    /// Reads a model file saved with [`Classifier::save`], in any format
+    /// version up to the current one.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        read_model_file(BufReader::new(File::open(path)?)).map(|(_, state)| state)
+    }
+
+    /// Rebuilds the classifier, bound to the saved header if there is one.
+    pub fn into_classifier(self) -> Result<Box<dyn Classifier>, Error> {
+        let header = self.header.map(Arc::new);
+        Ok(match self.learner {
+            LearnerState::NaiveBayes(s) => Box::new(NaiveBayes::from_state(s, header)),
+            LearnerState::HoeffdingTree(s) => Box::new(HoeffdingTree::from_state(s, header)?),
+            LearnerState::HoeffdingAdaptiveTree(s) => {
+                Box::new(HoeffdingAdaptiveTree::from_state(s, header)?)
+            }
+            LearnerState::TargetMean(s) => Box::new(TargetMean::from_state(s)),
+        })
+    }
+
+    pub(crate) fn wrong_learner(&self, expected: &str) -> Error {
+        RivuError::model(format!(
+            "saved model is a {}, not a {expected}",
+            self.learner.name()
+        ))
+        .into()
+    }
+}
+
+/// Loads a classifier of any kind saved with [`Classifier::save`].
+pub fn load_classifier(path: impl AsRef<Path>) -> Result<Box<dyn Classifier>, Error> {
+    ClassifierState::load(path)?.into_classifier()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::hoeffding_tree::LeafPredictionOption;
+    use crate::streams::Stream;
+    use crate::streams::generators::{AgrawalFunction, AgrawalGenerator};
+    use crate::testing::OracleClassifier;
+
+    fn trained(mut learner: Box<dyn Classifier>) -> (Box<dyn Classifier>, AgrawalGenerator) {
+        let mut stream = AgrawalGenerator::new(AgrawalFunction::F2, true, 0.05, None, 7).unwrap();
+        learner.set_model_context(Arc::new(stream.header().clone()));
+        for _ in 0..3000 {
+            let inst = stream.next_instance().unwrap();
+            learner.train_on_instance(inst.as_ref());
+        }
+        (learner, stream)
+    }
+
+    fn assert_same_votes(a: &dyn Classifier, b: &dyn Classifier, stream: &mut AgrawalGenerator) {
+        for _ in 0..200 {
+            let inst = stream.next_instance().unwrap();
+            assert_eq!(
+                a.get_votes_for_instance(inst.as_ref()),
+                b.get_votes_for_instance(inst.as_ref())
+            );
+        }
+    }
+
+    #[test]
+    fn saved_learners_predict_like_the_originals() {
+        let dir = tempfile::tempdir().unwrap();
+        let learners: Vec<Box<dyn Classifier>> = vec![
+            Box::new(NaiveBayes::new()),
+            Box::new(HoeffdingTree::new_with_only_leaf_prediction(
+                LeafPredictionOption::AdaptiveNaiveBayes,
+            )),
+        ];
+        for (i, learner) in learners.into_iter().enumerate() {
+            let (learner, mut stream) = trained(learner);
+            let path = dir.path().join(format!("model-{i}.json"));
+            learner.save(&path).unwrap();
+            let loaded = load_classifier(&path).unwrap();
+            assert_same_votes(learner.as_ref(), loaded.as_ref(), &mut stream);
+        }
+    }
+
+    #[test]
+    fn a_failed_save_leaves_the_existing_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.json");
+        let (learner, _) = trained(Box::new(NaiveBayes::new()));
+        learner.save(&path).unwrap();
+        let saved = std::fs::read(&path).unwrap();
+
+        let oracle = OracleClassifier::default();
+        assert!(oracle.save(&path).is_err());
+        assert!(oracle.export_pmml(&path).is_err());
+        assert!(oracle.export_onnx(&path).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), saved);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn grown_trees_keep_their_structure_and_keep_learning() {
+        let tree = HoeffdingTree::new_with_only_leaf_prediction(LeafPredictionOption::NaiveBayes);
+        let (tree, mut stream) = trained(Box::new(tree));
+        let state = tree.snapshot_state().unwrap();
+        let LearnerState::HoeffdingTree(s) = &state.learner else {
+            panic!("expected a tree state");
+        };
+        assert!(s.decision_node_count > 0);
+        assert_eq!(
+            s.nodes.len(),
+            s.decision_node_count + s.active_leaf_node_count + s.inactive_leaf_node_count
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tree.json");
+        tree.save(&path).unwrap();
+        let mut loaded = HoeffdingTree::load(&path).unwrap();
+        assert_same_votes(tree.as_ref(), &loaded, &mut stream);
+        assert_eq!(
+            tree.model_measurements()[..4],
+            loaded.model_measurements()[..4]
+        );
+        let inst = stream.next_instance().unwrap();
+        loaded.train_on_instance(inst.as_ref());
+        assert!(NaiveBayes::load(&path).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+mod target_mean;
+
+pub use target_mean::{TargetMean, TargetMeanState};
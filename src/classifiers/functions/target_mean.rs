@@ -0,0 +1,110 @@
+use crate::classifiers::classifier::Classifier;
+use crate::classifiers::{ClassifierState, LearnerState};
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::evaluation::{Measurement, MetricKey};
+use serde::{Deserialize, Serialize};
+use std::io::Error;
+use std::sync::Arc;
+
+/// Serializable snapshot of a trained [`TargetMean`] model.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TargetMeanState {
+    pub weight_seen: f64,
+    pub sum_target: f64,
+}
+
+/// Baseline regressor for a numeric class: predicts the weighted mean of the
+/// targets seen so far (0 before any training).
+#[derive(Debug, Default)]
+pub struct TargetMean {
+    state: TargetMeanState,
+}
+
+impl TargetMean {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> TargetMeanState {
+        self.state.clone()
+    }
+
+    pub fn from_state(state: TargetMeanState) -> Self {
+        Self { state }
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.state.weight_seen > 0.0 {
+            self.state.sum_target / self.state.weight_seen
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Classifier for TargetMean {
+    /// A single entry holding the predicted value.
+    fn get_votes_for_instance(&self, _instance: &dyn Instance) -> Vec<f64> {
+        vec![self.mean()]
+    }
+
+    fn set_model_context(&mut self, _header: Arc<InstanceHeader>) {
+        self.state = TargetMeanState::default();
+    }
+
+    fn train_on_instance(&mut self, instance: &dyn Instance) {
+        let Some(y) = instance.class_value().filter(|y| y.is_finite()) else {
+            return;
+        };
+        let w = instance.weight();
+        if w > 0.0 {
+            self.state.weight_seen += w;
+            self.state.sum_target += w * y;
+        }
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        vec![Measurement::new(
+            MetricKey::MODEL_TRAINING_WEIGHT,
+            self.state.weight_seen,
+        )]
+    }
+
+    fn snapshot_state(&self) -> Result<ClassifierState, Error> {
+        Ok(ClassifierState::new(
+            None,
+            LearnerState::TargetMean(self.state()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+
+    #[test]
+    fn predicts_the_weighted_mean_of_seen_targets() {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NumericAttribute::new("y".into())),
+        ];
+        let h = Arc::new(InstanceHeader::new("reg".into(), attrs, 1));
+        let mut tm = TargetMean::new();
+        tm.set_model_context(Arc::clone(&h));
+        let probe = DenseInstance::new(Arc::clone(&h), vec![0.0, f64::NAN], 1.0);
+        assert_eq!(tm.get_votes_for_instance(&probe), vec![0.0]);
+
+        for (y, w) in [(2.0, 1.0), (5.0, 2.0), (f64::NAN, 1.0)] {
+            tm.train_on_instance(&DenseInstance::new(Arc::clone(&h), vec![0.0, y], w));
+        }
+        assert_eq!(tm.get_votes_for_instance(&probe), vec![4.0]);
+
+        let mut buf = Vec::new();
+        tm.save_model(&mut buf).unwrap();
+        let state: TargetMeanState = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(state, tm.state());
+    }
+}
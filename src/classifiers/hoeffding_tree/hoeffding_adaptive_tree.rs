@@ -0,0 +1,891 @@
+use crate::classifiers::attribute_class_observers::{
+    AttributeClassObserver, AttributeClassObserverState,
+};
+use crate::classifiers::conditional_tests::attribute_split_suggestion::AttributeSplitSuggestion;
+use crate::classifiers::hoeffding_tree::HoeffdingTree;
+use crate::classifiers::hoeffding_tree::hoeffding_tree::class_observer_for;
+use crate::classifiers::hoeffding_tree::instance_conditional_test::InstanceConditionalTest;
+use crate::classifiers::hoeffding_tree::leaf_prediction_option::LeafPredictionOption;
+use crate::classifiers::hoeffding_tree::split_criteria::{GiniSplitCriterion, SplitCriterion};
+use crate::classifiers::hoeffding_tree::tree_state::{
+    AdaptiveNodeState, HoeffdingAdaptiveTreeState, LearningNodeState, unknown,
+};
+use crate::classifiers::{Classifier, ClassifierState, LearnerState, NaiveBayes};
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::drift::Adwin;
+use crate::error::RivuError;
+use crate::evaluation::{Measurement, MetricKey, argmax};
+use std::io::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Errors a node and its alternate must both have seen before the two are
+/// compared.
+const MIN_ERROR_WIDTH: u64 = 300;
+/// Confidence of the test that decides between a node and its alternate.
+const SWITCH_DELTA: f64 = 0.05;
+
+/// Hoeffding Adaptive Tree (Bifet & Gavaldà, 2009), after MOA's
+/// `HoeffdingAdaptiveTree`.
+///
+/// Grows like a [`HoeffdingTree`], but every node keeps an [`Adwin`]
+/// window over the errors of the predictions made through it. When the
+/// error of a decision node rises significantly, an alternate subtree
+/// starts growing from a fresh leaf in its place, trained on the same
+/// instances. Once both have seen enough instances, the alternate replaces
+/// the node if its error is significantly lower, and is discarded if it is
+/// significantly higher. Branches that stop fitting the concept are thus
+/// regrown without restarting the whole tree.
+///
+/// Predictions only use the main tree. Memory management (deactivating
+/// leaves) is not implemented; the tree is bounded by its adaptation
+/// instead.
+pub struct HoeffdingAdaptiveTree {
+    root: Option<AdaNode>,
+    header: Option<Arc<InstanceHeader>>,
+    options: Options,
+    counts: Counts,
+    training_weight_seen: f64,
+}
+
+/// Growth options shared by every node.
+struct Options {
+    grace_period: usize,
+    split_criterion: Box<dyn SplitCriterion>,
+    split_confidence: f64,
+    tie_threshold: f64,
+    binary_splits: bool,
+    no_pre_prune: bool,
+    leaf_prediction: LeafPredictionOption,
+    nb_threshold: Option<usize>,
+}
+
+/// Alternate subtrees started, switched in, and discarded so far.
+#[derive(Debug, Default)]
+struct Counts {
+    alternates: usize,
+    switched: usize,
+    pruned: usize,
+}
+
+enum AdaNode {
+    Leaf(AdaLeaf),
+    Split(AdaSplit),
+}
+
+struct AdaLeaf {
+    class_counts: Vec<f64>,
+    weight_at_last_split_evaluation: f64,
+    attribute_observers: Vec<Option<Box<dyn AttributeClassObserver>>>,
+    mc_correct_weight: f64,
+    nb_correct_weight: f64,
+    error: Adwin,
+}
+
+struct AdaSplit {
+    test: Box<dyn InstanceConditionalTest>,
+    /// Class counts of the leaf the split replaced.
+    class_counts: Vec<f64>,
+    children: Vec<AdaNode>,
+    error: Adwin,
+    alternate: Option<Box<AdaNode>>,
+}
+
+impl HoeffdingAdaptiveTree {
+    /// A tree with MOA's defaults: grace period 200, Gini splits with
+    /// confidence `1e-7` and tie threshold 0.05, multiway nominal splits,
+    /// and adaptive Naive Bayes leaves.
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            header: None,
+            options: Options {
+                grace_period: 200,
+                split_criterion: Box::new(GiniSplitCriterion::new()),
+                split_confidence: 1e-7,
+                tie_threshold: 0.05,
+                binary_splits: false,
+                no_pre_prune: false,
+                leaf_prediction: LeafPredictionOption::AdaptiveNaiveBayes,
+                nb_threshold: Some(0),
+            },
+            counts: Counts::default(),
+            training_weight_seen: 0.0,
+        }
+    }
+
+    /// Weight a leaf must see between split attempts.
+    pub fn with_grace_period(mut self, grace_period: usize) -> Self {
+        self.options.grace_period = grace_period;
+        self
+    }
+
+    pub fn with_split_criterion(mut self, criterion: Box<dyn SplitCriterion>) -> Self {
+        self.options.split_criterion = criterion;
+        self
+    }
+
+    /// Allowed error of a split decision.
+    pub fn with_split_confidence(mut self, confidence: f64) -> Self {
+        self.options.split_confidence = confidence;
+        self
+    }
+
+    /// Merit difference below which a tie is broken by splitting.
+    pub fn with_tie_threshold(mut self, threshold: f64) -> Self {
+        self.options.tie_threshold = threshold;
+        self
+    }
+
+    pub fn with_binary_splits(mut self, binary: bool) -> Self {
+        self.options.binary_splits = binary;
+        self
+    }
+
+    /// Skips the check that splitting beats not splitting at all.
+    pub fn with_no_pre_prune(mut self, no_pre_prune: bool) -> Self {
+        self.options.no_pre_prune = no_pre_prune;
+        self
+    }
+
+    pub fn with_leaf_prediction(mut self, leaf_prediction: LeafPredictionOption) -> Self {
+        self.options.leaf_prediction = leaf_prediction;
+        self
+    }
+
+    /// Weight a Naive Bayes leaf must see before predicting with Naive
+    /// Bayes rather than its majority class; `None` never does.
+    pub fn with_nb_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.options.nb_threshold = threshold;
+        self
+    }
+
+    /// Alternate subtrees currently growing in the tree.
+    pub fn alternate_trees(&self) -> usize {
+        self.root.as_ref().map_or(0, AdaNode::alternates)
+    }
+
+    /// Alternate subtrees that replaced the node they grew beside.
+    pub fn switched_alternate_trees(&self) -> usize {
+        self.counts.switched
+    }
+
+    /// Alternate subtrees discarded for doing worse than their node.
+    pub fn pruned_alternate_trees(&self) -> usize {
+        self.counts.pruned
+    }
+
+    pub fn calc_byte_size(&self) -> usize {
+        size_of::<Self>() + self.root.as_ref().map_or(0, AdaNode::byte_size)
+    }
+
+    /// Captures the tree, every alternate subtree, and their error windows.
+    pub fn state(&self) -> Result<HoeffdingAdaptiveTreeState, Error> {
+        let options = &self.options;
+        let mut nodes = Vec::new();
+        if let Some(root) = &self.root {
+            root.capture(&mut nodes)?;
+        }
+        Ok(HoeffdingAdaptiveTreeState {
+            leaf_prediction: options.leaf_prediction,
+            nb_threshold: options.nb_threshold,
+            grace_period: options.grace_period,
+            split_criterion: options
+                .split_criterion
+                .state()
+                .ok_or_else(|| unknown("split criterion"))?,
+            split_confidence: options.split_confidence,
+            tie_threshold: options.tie_threshold,
+            binary_splits: options.binary_splits,
+            no_pre_prune: options.no_pre_prune,
+            training_weight_seen: self.training_weight_seen,
+            alternate_trees_started: self.counts.alternates,
+            switched_alternate_trees: self.counts.switched,
+            pruned_alternate_trees: self.counts.pruned,
+            nodes,
+        })
+    }
+
+    pub fn from_state(
+        state: HoeffdingAdaptiveTreeState,
+        header: Option<Arc<InstanceHeader>>,
+    ) -> Result<Self, Error> {
+        let root = if state.nodes.is_empty() {
+            None
+        } else {
+            Some(AdaNode::restore(state.nodes)?)
+        };
+        Ok(Self {
+            root,
+            header,
+            options: Options {
+                grace_period: state.grace_period,
+                split_criterion: state.split_criterion.into_criterion(),
+                split_confidence: state.split_confidence,
+                tie_threshold: state.tie_threshold,
+                binary_splits: state.binary_splits,
+                no_pre_prune: state.no_pre_prune,
+                leaf_prediction: state.leaf_prediction,
+                nb_threshold: state.nb_threshold,
+            },
+            counts: Counts {
+                alternates: state.alternate_trees_started,
+                switched: state.switched_alternate_trees,
+                pruned: state.pruned_alternate_trees,
+            },
+            training_weight_seen: state.training_weight_seen,
+        })
+    }
+
+    /// Loads a tree saved with [`Classifier::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        match ClassifierState::load(path)? {
+            ClassifierState {
+                header,
+                learner: LearnerState::HoeffdingAdaptiveTree(state),
+            } => Self::from_state(state, header.map(Arc::new)),
+            other => Err(other.wrong_learner("hoeffding-adaptive-tree")),
+        }
+    }
+}
+
+impl Default for HoeffdingAdaptiveTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Classifier for HoeffdingAdaptiveTree {
+    fn get_votes_for_instance(&self, instance: &dyn Instance) -> Vec<f64> {
+        let mut votes = Vec::new();
+        self.get_votes_into(instance, &mut votes);
+        votes
+    }
+
+    fn get_votes_into(&self, instance: &dyn Instance, votes: &mut Vec<f64>) {
+        votes.clear();
+        match &self.root {
+            Some(root) => root.votes_into(instance, &self.options, votes),
+            None => votes.resize(instance.number_of_classes(), 0.0),
+        }
+    }
+
+    fn set_model_context(&mut self, header: Arc<InstanceHeader>) {
+        self.header = Some(header);
+    }
+
+    fn snapshot_state(&self) -> Result<ClassifierState, Error> {
+        Ok(ClassifierState::new(
+            self.header.as_deref(),
+            LearnerState::HoeffdingAdaptiveTree(self.state()?),
+        ))
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        let (nodes, leaves) = self.root.as_ref().map_or((0, 0), AdaNode::size);
+        vec![
+            Measurement::new(MetricKey::MODEL_TRAINING_WEIGHT, self.training_weight_seen),
+            Measurement::new(MetricKey::TREE_SIZE_NODES, nodes as f64),
+            Measurement::new(MetricKey::TREE_SIZE_LEAVES, leaves as f64),
+            Measurement::new(MetricKey::ACTIVE_LEAVES, leaves as f64),
+            Measurement::new("alternate_trees", self.alternate_trees() as f64),
+            Measurement::new("switched_alternate_trees", self.counts.switched as f64),
+            Measurement::new("pruned_alternate_trees", self.counts.pruned as f64),
+            Measurement::new(MetricKey::MODEL_BYTES, self.calc_byte_size() as f64),
+        ]
+    }
+
+    fn train_on_instance(&mut self, instance: &dyn Instance) {
+        if instance.weight() <= 0.0 || instance.is_class_missing() {
+            return;
+        }
+        self.root
+            .get_or_insert_with(|| AdaNode::Leaf(AdaLeaf::new(Vec::new())))
+            .learn(instance, &self.options, &mut self.counts);
+        self.training_weight_seen += instance.weight();
+    }
+}
+
+impl AdaNode {
+    fn votes_into(&self, instance: &dyn Instance, options: &Options, votes: &mut Vec<f64>) {
+        match self {
+            AdaNode::Leaf(leaf) => leaf.votes_into(instance, options, votes),
+            AdaNode::Split(split) => match split.child_for(instance) {
+                Some(child) => child.votes_into(instance, options, votes),
+                None => votes.clone_from(&split.class_counts),
+            },
+        }
+    }
+
+    /// 1 when the subtree under this node mispredicts `instance`, 0 when
+    /// it is right.
+    fn error_on(&self, instance: &dyn Instance, options: &Options) -> f64 {
+        let class = instance.class_value().map_or(0, |c| c as usize);
+        let mut votes = Vec::new();
+        self.votes_into(instance, options, &mut votes);
+        if argmax(&votes) == Some(class) {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Predicts `instance` with the subtree under this node and learns it.
+    fn learn(&mut self, instance: &dyn Instance, options: &Options, counts: &mut Counts) {
+        let error = self.error_on(instance, options);
+        self.learn_with(instance, error, options, counts);
+    }
+
+    /// Records `error`, the outcome of predicting `instance` through this
+    /// node, and trains on it: at a leaf, by updating its statistics and
+    /// splitting it when the grace period is over; at a decision node, by
+    /// managing its alternate and passing the instance down both. A child
+    /// reached by the instance made the same prediction, so it is handed
+    /// the same `error`.
+    fn learn_with(
+        &mut self,
+        instance: &dyn Instance,
+        error: f64,
+        options: &Options,
+        counts: &mut Counts,
+    ) {
+        let split = match self {
+            AdaNode::Leaf(leaf) => {
+                leaf.error.set_input(error);
+                leaf.learn(instance, options);
+                if let Some(split) = leaf.try_split(options) {
+                    *self = AdaNode::Split(split);
+                }
+                return;
+            }
+            AdaNode::Split(split) => split,
+        };
+
+        let before = split.error.estimation();
+        if split.error.set_input(error) && split.error.estimation() > before {
+            split.alternate = Some(Box::new(AdaNode::Leaf(AdaLeaf::new(Vec::new()))));
+            counts.alternates += 1;
+        } else if let Some(alternate) = &split.alternate {
+            let (n, alt_n) = (split.error.width(), alternate.error().width());
+            if n > MIN_ERROR_WIDTH && alt_n > MIN_ERROR_WIDTH {
+                let rate = split.error.estimation();
+                let alt_rate = alternate.error().estimation();
+                let bound = (2.0
+                    * rate
+                    * (1.0 - rate)
+                    * (2.0 / SWITCH_DELTA).ln()
+                    * (1.0 / n as f64 + 1.0 / alt_n as f64))
+                    .sqrt();
+                if bound < rate - alt_rate {
+                    counts.switched += 1;
+                    if let Some(alternate) = split.alternate.take() {
+                        *self = *alternate;
+                    }
+                    let error = self.error_on(instance, options);
+                    return self.learn_below(instance, error, options, counts);
+                } else if bound < alt_rate - rate {
+                    split.alternate = None;
+                    counts.pruned += 1;
+                }
+            }
+        }
+        self.learn_below(instance, error, options, counts);
+    }
+
+    /// Trains the alternate and the child of a decision node on `instance`,
+    /// which this node mispredicted when `error` is 1; a leaf learns it in
+    /// full.
+    fn learn_below(
+        &mut self,
+        instance: &dyn Instance,
+        error: f64,
+        options: &Options,
+        counts: &mut Counts,
+    ) {
+        let AdaNode::Split(split) = self else {
+            return self.learn_with(instance, error, options, counts);
+        };
+        if let Some(alternate) = split.alternate.as_mut() {
+            alternate.learn(instance, options, counts);
+        }
+        let Some(branch) = split.test.branch_for_instance(instance) else {
+            return;
+        };
+        if branch >= split.children.len() {
+            // The node predicted from its own counts; the new leaf predicts
+            // for itself.
+            split
+                .children
+                .resize_with(branch + 1, || AdaNode::Leaf(AdaLeaf::new(Vec::new())));
+            return split.children[branch].learn(instance, options, counts);
+        }
+        split.children[branch].learn_with(instance, error, options, counts);
+    }
+
+    fn error(&self) -> &Adwin {
+        match self {
+            AdaNode::Leaf(leaf) => &leaf.error,
+            AdaNode::Split(split) => &split.error,
+        }
+    }
+
+    /// Nodes and leaves of the main tree.
+    fn size(&self) -> (usize, usize) {
+        match self {
+            AdaNode::Leaf(_) => (1, 1),
+            AdaNode::Split(split) => split
+                .children
+                .iter()
+                .map(AdaNode::size)
+                .fold((1, 0), |(n, l), (cn, cl)| (n + cn, l + cl)),
+        }
+    }
+
+    fn alternates(&self) -> usize {
+        match self {
+            AdaNode::Leaf(_) => 0,
+            AdaNode::Split(split) => {
+                let own = split
+                    .alternate
+                    .as_ref()
+                    .map_or(0, |alternate| 1 + alternate.alternates());
+                own + split
+                    .children
+                    .iter()
+                    .map(AdaNode::alternates)
+                    .sum::<usize>()
+            }
+        }
+    }
+
+    /// Appends this node and everything below it to `nodes`, parents before
+    /// their children and alternates.
+    fn capture(&self, nodes: &mut Vec<AdaptiveNodeState>) -> Result<(), Error> {
+        let split = match self {
+            AdaNode::Leaf(leaf) => {
+                let attribute_observers =
+                    AttributeClassObserverState::capture_all(&leaf.attribute_observers)
+                        .ok_or_else(|| unknown("attribute observer"))?;
+                nodes.push(AdaptiveNodeState::Leaf {
+                    node: LearningNodeState {
+                        observed_class_distribution: leaf.class_counts.clone(),
+                        weight_seen_at_last_split_evaluation: leaf.weight_at_last_split_evaluation,
+                        is_initialized: !attribute_observers.is_empty(),
+                        attribute_observers,
+                    },
+                    mc_correct_weight: leaf.mc_correct_weight,
+                    nb_correct_weight: leaf.nb_correct_weight,
+                    error: leaf.error.clone(),
+                });
+                return Ok(());
+            }
+            AdaNode::Split(split) => split,
+        };
+        let position = nodes.len();
+        nodes.push(AdaptiveNodeState::Split {
+            observed_class_distribution: split.class_counts.clone(),
+            split_test: split.test.state().ok_or_else(|| unknown("split test"))?,
+            children: Vec::new(),
+            error: split.error.clone(),
+            alternate: None,
+        });
+        let mut children = Vec::with_capacity(split.children.len());
+        for child in &split.children {
+            children.push(nodes.len());
+            child.capture(nodes)?;
+        }
+        let alternate = match &split.alternate {
+            Some(alternate) => {
+                let index = nodes.len();
+                alternate.capture(nodes)?;
+                Some(index)
+            }
+            None => None,
+        };
+        if let AdaptiveNodeState::Split {
+            children: c,
+            alternate: a,
+            ..
+        } = &mut nodes[position]
+        {
+            *c = children;
+            *a = alternate;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the tree written by [`capture`](Self::capture) and returns
+    /// its root.
+    fn restore(nodes: Vec<AdaptiveNodeState>) -> Result<AdaNode, Error> {
+        let invalid = |msg: String| -> Error { RivuError::model(msg).into() };
+        let mut built: Vec<Option<AdaNode>> = (0..nodes.len()).map(|_| None).collect();
+        // Everything a node refers to comes after it, so building from the
+        // back finds it ready.
+        for (index, state) in nodes.into_iter().enumerate().rev() {
+            let mut take = |at: usize| {
+                (at > index)
+                    .then(|| built.get_mut(at).and_then(Option::take))
+                    .flatten()
+                    .ok_or_else(|| invalid(format!("node {index} refers to an invalid node {at}")))
+            };
+            let node = match state {
+                AdaptiveNodeState::Split {
+                    observed_class_distribution,
+                    split_test,
+                    children,
+                    error,
+                    alternate,
+                } => AdaNode::Split(AdaSplit {
+                    test: split_test.into_test(),
+                    class_counts: observed_class_distribution,
+                    children: children
+                        .into_iter()
+                        .map(&mut take)
+                        .collect::<Result<_, _>>()?,
+                    error,
+                    alternate: alternate.map(take).transpose()?.map(Box::new),
+                }),
+                AdaptiveNodeState::Leaf {
+                    node,
+                    mc_correct_weight,
+                    nb_correct_weight,
+                    error,
+                } => AdaNode::Leaf(AdaLeaf {
+                    class_counts: node.observed_class_distribution,
+                    weight_at_last_split_evaluation: node.weight_seen_at_last_split_evaluation,
+                    attribute_observers: AttributeClassObserverState::restore_all(
+                        node.attribute_observers,
+                    ),
+                    mc_correct_weight,
+                    nb_correct_weight,
+                    error,
+                }),
+            };
+            built[index] = Some(node);
+        }
+        let mut built = built.into_iter();
+        let root = built
+            .next()
+            .flatten()
+            .ok_or_else(|| invalid("saved tree has no nodes".into()))?;
+        if built.any(|n| n.is_some()) {
+            return Err(invalid("saved tree has unreachable nodes".into()));
+        }
+        Ok(root)
+    }
+
+    fn byte_size(&self) -> usize {
+        match self {
+            AdaNode::Leaf(leaf) => {
+                size_of::<Self>()
+                    + leaf.class_counts.len() * size_of::<f64>()
+                    + leaf
+                        .attribute_observers
+                        .iter()
+                        .flatten()
+                        .map(|o| o.estimate_size_bytes())
+                        .sum::<usize>()
+            }
+            AdaNode::Split(split) => {
+                size_of::<Self>()
+                    + split.test.calc_byte_size()
+                    + split.class_counts.len() * size_of::<f64>()
+                    + split.children.iter().map(AdaNode::byte_size).sum::<usize>()
+                    + split.alternate.as_ref().map_or(0, |a| a.byte_size())
+            }
+        }
+    }
+}
+
+impl AdaSplit {
+    fn child_for(&self, instance: &dyn Instance) -> Option<&AdaNode> {
+        self.children.get(self.test.branch_for_instance(instance)?)
+    }
+}
+
+impl AdaLeaf {
+    fn new(class_counts: Vec<f64>) -> Self {
+        Self {
+            weight_at_last_split_evaluation: class_counts.iter().sum(),
+            class_counts,
+            attribute_observers: Vec::new(),
+            mc_correct_weight: 0.0,
+            nb_correct_weight: 0.0,
+            error: Adwin::default(),
+        }
+    }
+
+    fn weight_seen(&self) -> f64 {
+        self.class_counts.iter().sum()
+    }
+
+    fn votes_into(&self, instance: &dyn Instance, options: &Options, votes: &mut Vec<f64>) {
+        let naive_bayes = match options.leaf_prediction {
+            LeafPredictionOption::MajorityClass => false,
+            LeafPredictionOption::NaiveBayes => options
+                .nb_threshold
+                .is_some_and(|t| self.weight_seen() >= t as f64),
+            LeafPredictionOption::AdaptiveNaiveBayes => {
+                self.mc_correct_weight <= self.nb_correct_weight
+            }
+        };
+        if naive_bayes {
+            NaiveBayes::naive_bayes_prediction_into(
+                instance,
+                &self.class_counts,
+                &self.attribute_observers,
+                votes,
+            );
+        } else {
+            votes.clone_from(&self.class_counts);
+        }
+    }
+
+    fn learn(&mut self, instance: &dyn Instance, options: &Options) {
+        let attributes = instance.number_of_attributes() - 1;
+        if self.attribute_observers.is_empty() {
+            self.attribute_observers = (0..attributes).map(|_| None).collect();
+        }
+        let Some(class) = instance.class_value().map(|c| c as usize) else {
+            return;
+        };
+        let weight = instance.weight();
+
+        if options.leaf_prediction == LeafPredictionOption::AdaptiveNaiveBayes {
+            if argmax(&self.class_counts) == Some(class) {
+                self.mc_correct_weight += weight;
+            }
+            let nb = NaiveBayes::do_naive_bayes_prediction(
+                instance,
+                &self.class_counts,
+                &self.attribute_observers,
+            );
+            if argmax(&nb) == Some(class) {
+                self.nb_correct_weight += weight;
+            }
+        }
+
+        if class >= self.class_counts.len() {
+            self.class_counts.resize(class + 1, 0.0);
+        }
+        self.class_counts[class] += weight;
+
+        for i in 0..attributes {
+            let index =
+                HoeffdingTree::model_attribute_index_to_instance_attribute_index(i, instance);
+            if self.attribute_observers[i].is_none()
+                && let Some(attribute) = instance.attribute_at_index(index)
+            {
+                self.attribute_observers[i] = Some(class_observer_for(attribute));
+            }
+            if let (Some(observer), Some(value)) = (
+                self.attribute_observers[i].as_mut(),
+                instance.value_at_index(index),
+            ) {
+                observer.observe_attribute_class(value, class, weight);
+            }
+        }
+    }
+
+    /// The decision node this leaf should become, once a grace period has
+    /// passed since it was last evaluated and the Hoeffding bound separates
+    /// the best split from the runner-up.
+    fn try_split(&mut self, options: &Options) -> Option<AdaSplit> {
+        let weight_seen = self.weight_seen();
+        if weight_seen - self.weight_at_last_split_evaluation < options.grace_period as f64 {
+            return None;
+        }
+        self.weight_at_last_split_evaluation = weight_seen;
+        if self.class_counts.iter().filter(|&&c| c > 0.0).count() < 2 {
+            return None;
+        }
+
+        let criterion = options.split_criterion.as_ref();
+        let mut suggestions = self.split_suggestions(options);
+        suggestions.sort_by(|a, b| a.get_merit().total_cmp(&b.get_merit()));
+        let best = suggestions.last()?;
+        if let [.., second, _] = suggestions.as_slice() {
+            let range = criterion.get_range_of_merit(&self.class_counts);
+            let bound = hoeffding_bound(range, options.split_confidence, weight_seen);
+            if best.get_merit() - second.get_merit() <= bound && bound >= options.tie_threshold {
+                return None;
+            }
+        }
+
+        let test = best.get_split_test()?.clone_box();
+        let children = (0..best.number_of_splits())
+            .map(|i| {
+                AdaNode::Leaf(AdaLeaf::new(
+                    best.resulting_class_distribution_from_split(i),
+                ))
+            })
+            .collect();
+        Some(AdaSplit {
+            test,
+            class_counts: self.class_counts.clone(),
+            children,
+            error: Adwin::default(),
+            alternate: None,
+        })
+    }
+
+    /// The best split of each attribute, and not splitting at all unless
+    /// pre-pruning is off.
+    fn split_suggestions(&self, options: &Options) -> Vec<AttributeSplitSuggestion> {
+        let criterion = options.split_criterion.as_ref();
+        let pre = &self.class_counts;
+        let mut suggestions = Vec::new();
+        if !options.no_pre_prune {
+            let merit = criterion.get_merit_of_split(pre, std::slice::from_ref(pre));
+            suggestions.push(AttributeSplitSuggestion::new(
+                None,
+                vec![pre.clone()],
+                merit,
+            ));
+        }
+        for (i, observer) in self.attribute_observers.iter().enumerate() {
+            let Some(observer) = observer else {
+                continue;
+            };
+            suggestions.extend(observer.get_best_evaluated_split_suggestion(
+                criterion,
+                pre,
+                i,
+                options.binary_splits,
+            ));
+        }
+        suggestions
+    }
+}
+
+/// Deviation of a mean of `n` observations with values spanning `range`
+/// that is exceeded with probability at most `confidence`.
+fn hoeffding_bound(range: f64, confidence: f64, n: f64) -> f64 {
+    let confidence = if confidence == 0.0 { 1e-7 } else { confidence };
+    (range * range * (1.0 / confidence).ln() / (2.0 * n)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::instances::DenseInstance;
+    use crate::streams::Stream;
+    use crate::streams::generators::{SeaFunction, SeaGenerator};
+
+    /// Test-then-train accuracy of `tree` over `n` instances of `stream`,
+    /// with the class flipped when `flip` is set.
+    fn prequential(
+        tree: &mut dyn Classifier,
+        stream: &mut SeaGenerator,
+        n: usize,
+        flip: bool,
+    ) -> f64 {
+        let header = Arc::new(stream.header().clone());
+        let mut correct = 0;
+        for _ in 0..n {
+            let mut values = stream.next_instance().unwrap().to_vec();
+            if flip {
+                values[3] = 1.0 - values[3];
+            }
+            let instance = DenseInstance::new(Arc::clone(&header), values, 1.0);
+            let votes = tree.get_votes_for_instance(&instance);
+            if argmax(&votes) == instance.class_value().map(|c| c as usize) {
+                correct += 1;
+            }
+            tree.train_on_instance(&instance);
+        }
+        correct as f64 / n as f64
+    }
+
+    #[test]
+    fn learns_a_stationary_concept() {
+        let mut stream = SeaGenerator::new(SeaFunction::F1, false, 0, None, 1).unwrap();
+        let mut tree = HoeffdingAdaptiveTree::new().with_grace_period(100);
+        tree.set_model_context(Arc::new(stream.header().clone()));
+        prequential(&mut tree, &mut stream, 5000, false);
+        assert!(prequential(&mut tree, &mut stream, 2000, false) > 0.95);
+
+        let (nodes, leaves) = tree.root.as_ref().unwrap().size();
+        assert!(nodes > leaves && leaves > 1);
+        let m = tree.model_measurements();
+        assert_eq!(m[1].value, nodes as f64);
+        assert_eq!(m[0].value, 7000.0);
+    }
+
+    #[test]
+    fn an_abrupt_drift_is_recovered_from_by_switching_subtrees() {
+        let mut stream = SeaGenerator::new(SeaFunction::F1, false, 0, None, 2).unwrap();
+        let header = Arc::new(stream.header().clone());
+        let mut hat = HoeffdingAdaptiveTree::new()
+            .with_grace_period(100)
+            .with_leaf_prediction(LeafPredictionOption::MajorityClass);
+        let mut vfdt =
+            HoeffdingTree::new_with_only_leaf_prediction(LeafPredictionOption::MajorityClass);
+        hat.set_model_context(Arc::clone(&header));
+        vfdt.set_model_context(header);
+
+        let mut replay = SeaGenerator::new(SeaFunction::F1, false, 0, None, 2).unwrap();
+        prequential(&mut hat, &mut stream, 10_000, false);
+        prequential(&mut vfdt, &mut replay, 10_000, false);
+        prequential(&mut hat, &mut stream, 5000, true);
+        prequential(&mut vfdt, &mut replay, 5000, true);
+
+        let hat_after = prequential(&mut hat, &mut stream, 2000, true);
+        let vfdt_after = prequential(&mut vfdt, &mut replay, 2000, true);
+        assert!(hat.switched_alternate_trees() > 0);
+        assert!(
+            hat_after > 0.8 && hat_after > vfdt_after + 0.3,
+            "HAT {hat_after}, VFDT {vfdt_after}"
+        );
+    }
+
+    #[test]
+    fn saved_trees_keep_their_alternates_and_keep_adapting_alike() {
+        let mut stream = SeaGenerator::new(SeaFunction::F1, false, 0, None, 4).unwrap();
+        let mut tree = HoeffdingAdaptiveTree::new().with_grace_period(100);
+        tree.set_model_context(Arc::new(stream.header().clone()));
+        prequential(&mut tree, &mut stream, 10_000, false);
+        prequential(&mut tree, &mut stream, 200, true);
+        assert!(tree.alternate_trees() > 0);
+        assert_eq!(tree.switched_alternate_trees(), 0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hat.json");
+        tree.save(&path).unwrap();
+        let mut loaded = HoeffdingAdaptiveTree::load(&path).unwrap();
+        assert_eq!(loaded.alternate_trees(), tree.alternate_trees());
+        assert_eq!(tree.model_measurements(), loaded.model_measurements());
+        let dump = loaded.to_json().unwrap();
+        assert_eq!(dump, tree.to_json().unwrap());
+        assert_eq!(dump["learner"], "hoeffding-adaptive-tree");
+        assert_eq!(dump["alternate_trees"], tree.alternate_trees());
+
+        // The error windows came back too, so both trees switch and prune
+        // their alternates on the same instances.
+        let mut replay = SeaGenerator::new(SeaFunction::F1, false, 0, None, 4).unwrap();
+        for _ in 0..10_200 {
+            replay.next_instance();
+        }
+        let a = prequential(&mut tree, &mut stream, 3000, true);
+        let b = prequential(&mut loaded, &mut replay, 3000, true);
+        assert_eq!(a, b);
+        assert!(loaded.switched_alternate_trees() > 0);
+        assert_eq!(tree.model_measurements(), loaded.model_measurements());
+        assert!(crate::classifiers::NaiveBayes::load(&path).is_err());
+    }
+
+    #[test]
+    fn untrained_trees_and_ignored_instances() {
+        let stream = SeaGenerator::new(SeaFunction::F1, false, 0, None, 3).unwrap();
+        let header = Arc::new(stream.header().clone());
+        let mut tree = HoeffdingAdaptiveTree::default();
+        let instance = DenseInstance::new(Arc::clone(&header), vec![1.0, 2.0, 3.0, 1.0], 0.0);
+        assert_eq!(tree.get_votes_for_instance(&instance), vec![0.0, 0.0]);
+        tree.train_on_instance(&instance);
+        let unlabeled = DenseInstance::new(header, vec![1.0, 2.0, 3.0, f64::NAN], 1.0);
+        tree.train_on_instance(&unlabeled);
+        assert!(tree.root.is_none());
+        assert_eq!(tree.alternate_trees(), 0);
+    }
+}
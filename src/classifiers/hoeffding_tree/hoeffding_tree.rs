@@ -1,7 +1,9 @@
 use crate::classifiers::Classifier;
 use crate::classifiers::attribute_class_observers::{
-    AttributeClassObserver, GaussianNumericAttributeClassObserver, NominalAttributeClassObserver,
+    AttributeClassObserver, AttributeClassObserverState, GaussianNumericAttributeClassObserver,
+    NominalAttributeClassObserver,
 };
+use crate::classifiers::classifier_state::{ClassifierState, LearnerState};
 use crate::classifiers::conditional_tests::attribute_split_suggestion::AttributeSplitSuggestion;
 use crate::classifiers::hoeffding_tree::instance_conditional_test::InstanceConditionalTest;
 use crate::classifiers::hoeffding_tree::leaf_prediction_option::LeafPredictionOption;
@@ -11,14 +13,30 @@ use crate::classifiers::hoeffding_tree::nodes::{
 };
 use crate::classifiers::hoeffding_tree::split_criteria::GiniSplitCriterion;
 use crate::classifiers::hoeffding_tree::split_criteria::SplitCriterion;
+use crate::classifiers::hoeffding_tree::tree_state::{
+    HoeffdingTreeState, capture_nodes, restore_nodes, unknown,
+};
+use crate::core::attributes::{Attribute, NominalAttribute};
 use crate::core::instance_header::InstanceHeader;
 use crate::core::instances::Instance;
+use crate::evaluation::{Measurement, MetricKey};
 use std::cell::{Ref, RefCell};
 use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::io::Error;
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 
+/// Class observer a tree leaf keeps for `attribute`; see
+/// [`HoeffdingTree::new_class_observer_for`].
+pub(crate) fn class_observer_for(attribute: &dyn Attribute) -> Box<dyn AttributeClassObserver> {
+    match attribute.as_any().downcast_ref::<NominalAttribute>() {
+        Some(nominal) if !nominal.is_ordinal() => Box::new(NominalAttributeClassObserver::new()),
+        _ => Box::new(GaussianNumericAttributeClassObserver::new()),
+    }
+}
+
 pub struct HoeffdingTree {
     tree_root: Option<Rc<RefCell<dyn Node>>>,
     decision_node_count: usize,
@@ -116,6 +134,92 @@ impl HoeffdingTree {
         }
     }
 
+    /// Captures the options, the growth bookkeeping, and every node.
+    pub fn state(&self) -> Result<HoeffdingTreeState, Error> {
+        Ok(HoeffdingTreeState {
+            leaf_prediction: self.leaf_prediction_option,
+            nb_threshold: self.nb_threshold_option,
+            grace_period: self.grace_period_option,
+            split_criterion: self
+                .split_criterion_option
+                .state()
+                .ok_or_else(|| unknown("split criterion"))?,
+            numeric_estimator: AttributeClassObserverState::from_observer(
+                self.numeric_estimator.as_ref(),
+            )
+            .ok_or_else(|| unknown("numeric estimator"))?,
+            split_confidence: self.split_confidence_option,
+            tie_threshold: self.tie_threshold_option,
+            binary_splits: self.binary_splits_option,
+            no_pre_prune: self.no_pre_prune_option,
+            remove_poor_attributes: self.remove_poor_atts_option,
+            max_byte_size: self.max_byte_size_option,
+            stop_mem_management: self.stop_mem_management_option,
+            memory_estimate_period: self.memory_estimate_period_option,
+            growth_allowed: self.growth_allowed,
+            training_weight_seen: self.training_weight_seen_by_model,
+            decision_node_count: self.decision_node_count,
+            active_leaf_node_count: self.active_leaf_node_count,
+            inactive_leaf_node_count: self.inactive_leaf_node_count,
+            active_leaf_byte_size_estimate: self.active_leaf_byte_size_estimate,
+            inactive_leaf_byte_size_estimate: self.inactive_leaf_byte_size_estimate,
+            byte_size_estimate_overhead_fraction: self.byte_size_estimate_overhead_fraction,
+            nodes: match &self.tree_root {
+                Some(root) => capture_nodes(root)?,
+                None => Vec::new(),
+            },
+        })
+    }
+
+    /// Rebuilds a tree saved with [`state`](Self::state), ready to predict
+    /// and to keep learning on instances of `header`.
+    pub fn from_state(
+        state: HoeffdingTreeState,
+        header: Option<Arc<InstanceHeader>>,
+    ) -> Result<Self, Error> {
+        let tree_root = if state.nodes.is_empty() {
+            None
+        } else {
+            Some(restore_nodes(state.nodes)?)
+        };
+        Ok(Self {
+            tree_root,
+            decision_node_count: state.decision_node_count,
+            active_leaf_node_count: state.active_leaf_node_count,
+            inactive_leaf_node_count: state.inactive_leaf_node_count,
+            growth_allowed: state.growth_allowed,
+            header,
+            numeric_estimator: state.numeric_estimator.into_observer(),
+            training_weight_seen_by_model: state.training_weight_seen,
+            leaf_prediction_option: state.leaf_prediction,
+            nb_threshold_option: state.nb_threshold,
+            grace_period_option: state.grace_period,
+            split_criterion_option: state.split_criterion.into_criterion(),
+            no_pre_prune_option: state.no_pre_prune,
+            binary_splits_option: state.binary_splits,
+            split_confidence_option: state.split_confidence,
+            tie_threshold_option: state.tie_threshold,
+            remove_poor_atts_option: state.remove_poor_attributes,
+            active_leaf_byte_size_estimate: state.active_leaf_byte_size_estimate,
+            inactive_leaf_byte_size_estimate: state.inactive_leaf_byte_size_estimate,
+            byte_size_estimate_overhead_fraction: state.byte_size_estimate_overhead_fraction,
+            max_byte_size_option: state.max_byte_size,
+            stop_mem_management_option: state.stop_mem_management,
+            memory_estimate_period_option: state.memory_estimate_period,
+        })
+    }
+
+    /// Loads a tree saved with [`Classifier::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        match ClassifierState::load(path)? {
+            ClassifierState {
+                header,
+                learner: LearnerState::HoeffdingTree(state),
+            } => Self::from_state(state, header.map(Arc::new)),
+            other => Err(other.wrong_learner("hoeffding-tree")),
+        }
+    }
+
     pub fn set_nb_threshold(&mut self, threshold: usize) {
         self.nb_threshold_option = Some(threshold);
     }
@@ -173,6 +277,17 @@ impl HoeffdingTree {
         Box::new(GaussianNumericAttributeClassObserver::new())
     }
 
+    /// Observer for `attribute`: nominal for unordered nominal attributes,
+    /// numeric otherwise. Ordinal attributes are observed through their
+    /// value indices, so they split on thresholds (`index <= t`) rather
+    /// than on equality with one value.
+    pub fn new_class_observer_for(
+        &self,
+        attribute: &dyn Attribute,
+    ) -> Box<dyn AttributeClassObserver> {
+        class_observer_for(attribute)
+    }
+
     pub fn compute_hoeffding_bound(&self, range: f64, confidence: f64, n: f64) -> f64 {
         if confidence == 0.0 {
             return (((range * range) * (1.0 / 0.0000001f64).ln()) / (2.0 * n)).sqrt();
@@ -632,6 +747,13 @@ impl HoeffdingTree {
 
 impl Classifier for HoeffdingTree {
     fn get_votes_for_instance(&self, instance: &dyn Instance) -> Vec<f64> {
+        let mut votes = Vec::new();
+        self.get_votes_into(instance, &mut votes);
+        votes
+    }
+
+    fn get_votes_into(&self, instance: &dyn Instance, votes: &mut Vec<f64>) {
+        votes.clear();
         if let Some(root_arc) = &self.tree_root {
             let root_guard = root_arc.borrow();
             let found_node =
@@ -642,13 +764,10 @@ impl Classifier for HoeffdingTree {
                 .or_else(|| found_node.get_parent().map(|p| p));
             if let Some(n_arc) = node_arc {
                 let node_guard = n_arc.borrow();
-                return node_guard.get_class_votes(instance, self);
+                node_guard.class_votes_into(instance, self, votes);
             }
-
-            Vec::new()
         } else {
-            let num_classes = instance.number_of_classes();
-            vec![0.0; num_classes]
+            votes.resize(instance.number_of_classes(), 0.0);
         }
     }
 
@@ -656,6 +775,30 @@ impl Classifier for HoeffdingTree {
         self.header = Some(header);
     }
 
+    fn snapshot_state(&self) -> Result<ClassifierState, Error> {
+        Ok(ClassifierState::new(
+            self.header.as_deref(),
+            LearnerState::HoeffdingTree(self.state()?),
+        ))
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        let leaves = self.active_leaf_node_count + self.inactive_leaf_node_count;
+        vec![
+            Measurement::new(
+                MetricKey::MODEL_TRAINING_WEIGHT,
+                self.training_weight_seen_by_model,
+            ),
+            Measurement::new(
+                MetricKey::TREE_SIZE_NODES,
+                (self.decision_node_count + leaves) as f64,
+            ),
+            Measurement::new(MetricKey::TREE_SIZE_LEAVES, leaves as f64),
+            Measurement::new(MetricKey::ACTIVE_LEAVES, self.active_leaf_node_count as f64),
+            Measurement::new(MetricKey::MODEL_BYTES, self.calc_byte_size() as f64),
+        ]
+    }
+
     fn train_on_instance(&mut self, instance: &dyn Instance) {
         if self.training_weight_seen_by_model == 6528.0 {
             println!("Second Split")
@@ -791,7 +934,7 @@ impl Classifier for HoeffdingTree {
 mod tests {
     use super::*;
     use crate::classifiers::conditional_tests::attribute_split_suggestion::AttributeSplitSuggestion;
-    use crate::core::attributes::{Attribute, NominalAttribute};
+    use crate::core::attributes::{Attribute, AttributeRef, NominalAttribute};
     use crate::core::instances::DenseInstance;
     use crate::testing::header_binary;
     use std::collections::HashMap;
@@ -1466,6 +1609,26 @@ mod tests {
         assert_eq!(votes, vec![3.0, 1.0]);
     }
 
+    #[test]
+    fn test_get_votes_into_overwrites_the_buffer() {
+        let mut tree =
+            HoeffdingTree::new_with_only_leaf_prediction(LeafPredictionOption::MajorityClass);
+        let instance = DummyInstance {
+            weight: 1.0,
+            class_val: 0,
+            num_classes: 2,
+        };
+        let mut votes = vec![9.0; 5];
+        tree.get_votes_into(&instance, &mut votes);
+        assert_eq!(votes, vec![0.0, 0.0]);
+
+        tree.tree_root = Some(Rc::new(RefCell::new(InactiveLearningNode::new(vec![
+            3.0, 1.0,
+        ]))));
+        tree.get_votes_into(&instance, &mut votes);
+        assert_eq!(votes, tree.get_votes_for_instance(&instance));
+    }
+
     #[test]
     fn test_train_on_instance_initializes_tree_root() {
         let mut tree =
@@ -1541,4 +1704,64 @@ mod tests {
         assert!(guard.as_any().is::<ActiveLearningNode>());
         assert_eq!(tree.decision_node_count, 0);
     }
+
+    fn train_on_four_levels(size: NominalAttribute) -> HoeffdingTree {
+        let levels = size.values.len();
+        let labels = vec!["small".to_string(), "large".to_string()];
+        let map = labels
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, v)| (v, i))
+            .collect();
+        let class: AttributeRef =
+            Arc::new(NominalAttribute::with_values("class".into(), labels, map));
+        let header = Arc::new(InstanceHeader::new(
+            "sizes".into(),
+            vec![Arc::new(size) as AttributeRef, class],
+            1,
+        ));
+        let mut tree =
+            HoeffdingTree::new_with_only_leaf_prediction(LeafPredictionOption::MajorityClass);
+        tree.binary_splits_option = false;
+        tree.set_model_context(header.clone());
+        for i in 0..2000 {
+            let level = i % levels;
+            let label = if level >= 2 { 1.0 } else { 0.0 };
+            let inst = DenseInstance::new(header.clone(), vec![level as f64, label], 1.0);
+            tree.train_on_instance(&inst);
+        }
+        tree
+    }
+
+    fn root_children(tree: &HoeffdingTree) -> usize {
+        let root = tree.tree_root.as_ref().unwrap().borrow();
+        root.as_any()
+            .downcast_ref::<SplitNode>()
+            .expect("root should have split")
+            .num_children()
+    }
+
+    #[test]
+    fn ordinal_attributes_split_on_a_threshold() {
+        let levels: Vec<String> = ["xs", "s", "m", "l"].map(String::from).into();
+        let tree =
+            HoeffdingTree::new_with_only_leaf_prediction(LeafPredictionOption::MajorityClass);
+        let ordinal = NominalAttribute::ordinal("size".into(), levels.clone());
+        assert!(
+            tree.new_class_observer_for(&ordinal)
+                .as_any()
+                .is::<GaussianNumericAttributeClassObserver>()
+        );
+
+        assert_eq!(root_children(&train_on_four_levels(ordinal)), 2);
+        let map = levels
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, v)| (v, i))
+            .collect();
+        let unordered = NominalAttribute::with_values("size".into(), levels, map);
+        assert_eq!(root_children(&train_on_four_levels(unordered)), 4);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::classifiers::hoeffding_tree::tree_state::SplitTestState;
 use crate::core::instances::Instance;
 
 pub trait InstanceConditionalTest {
@@ -7,6 +8,12 @@ pub trait InstanceConditionalTest {
     fn get_atts_test_depends_on(&self) -> Vec<usize>;
     fn calc_byte_size(&self) -> usize;
     fn clone_box(&self) -> Box<dyn InstanceConditionalTest>;
+
+    /// Serializable form of this test, or `None` for tests that cannot be
+    /// saved.
+    fn state(&self) -> Option<SplitTestState> {
+        None
+    }
 }
 
 impl Clone for Box<dyn InstanceConditionalTest> {
@@ -1,4 +1,5 @@
 use crate::classifiers::hoeffding_tree::instance_conditional_test::instance_conditional_test::InstanceConditionalTest;
+use crate::classifiers::hoeffding_tree::tree_state::SplitTestState;
 use crate::core::instances::Instance;
 
 #[derive(Clone)]
@@ -43,6 +44,12 @@ impl InstanceConditionalTest for NominalAttributeMultiwayTest {
     fn clone_box(&self) -> Box<dyn InstanceConditionalTest> {
         Box::new(self.clone())
     }
+
+    fn state(&self) -> Option<SplitTestState> {
+        Some(SplitTestState::NominalMultiway {
+            attribute_index: self.attribute_index,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,5 @@
 use crate::classifiers::hoeffding_tree::instance_conditional_test::instance_conditional_test::InstanceConditionalTest;
+use crate::classifiers::hoeffding_tree::tree_state::SplitTestState;
 use crate::core::instances::Instance;
 
 #[derive(Clone)]
@@ -56,6 +57,14 @@ impl InstanceConditionalTest for NumericAttributeBinaryTest {
     fn clone_box(&self) -> Box<dyn InstanceConditionalTest> {
         Box::new(self.clone())
     }
+
+    fn state(&self) -> Option<SplitTestState> {
+        Some(SplitTestState::NumericBinary {
+            attribute_index: self.attribute_index,
+            attribute_value: self.attribute_value,
+            equals_passes_test: self.equals_passes_test,
+        })
+    }
 }
 
 #[cfg(test)]
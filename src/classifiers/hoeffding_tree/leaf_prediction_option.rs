@@ -1,3 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum LeafPredictionOption {
     MajorityClass,
     NaiveBayes,
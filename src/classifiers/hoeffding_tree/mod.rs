@@ -1,8 +1,15 @@
+mod hoeffding_adaptive_tree;
 mod hoeffding_tree;
 pub mod instance_conditional_test;
 mod leaf_prediction_option;
 mod nodes;
 pub mod split_criteria;
+mod tree_state;
 
+pub use hoeffding_adaptive_tree::HoeffdingAdaptiveTree;
 pub use hoeffding_tree::HoeffdingTree;
 pub use leaf_prediction_option::LeafPredictionOption;
+pub use tree_state::{
+    AdaptiveNodeState, HoeffdingAdaptiveTreeState, HoeffdingTreeState, LearningNodeState,
+    NodeState, SplitCriterionState, SplitTestState,
+};
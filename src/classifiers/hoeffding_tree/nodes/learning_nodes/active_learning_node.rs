@@ -1,12 +1,14 @@
-use crate::classifiers::attribute_class_observers::AttributeClassObserver;
 use crate::classifiers::attribute_class_observers::null_attribute_class_observer::NullAttributeClassObserver;
+use crate::classifiers::attribute_class_observers::{
+    AttributeClassObserver, AttributeClassObserverState,
+};
 use crate::classifiers::conditional_tests::attribute_split_suggestion::AttributeSplitSuggestion;
 use crate::classifiers::hoeffding_tree::hoeffding_tree::HoeffdingTree;
 use crate::classifiers::hoeffding_tree::nodes::LearningNode;
 use crate::classifiers::hoeffding_tree::nodes::found_node::FoundNode;
 use crate::classifiers::hoeffding_tree::nodes::node::Node;
 use crate::classifiers::hoeffding_tree::split_criteria::SplitCriterion;
-use crate::core::attributes::NominalAttribute;
+use crate::classifiers::hoeffding_tree::tree_state::LearningNodeState;
 use crate::core::instances::Instance;
 use std::any::Any;
 use std::cell::RefCell;
@@ -30,6 +32,29 @@ impl ActiveLearningNode {
         }
     }
 
+    pub(crate) fn from_state(state: LearningNodeState) -> Self {
+        Self {
+            observed_class_distribution: state.observed_class_distribution,
+            weight_seen_at_last_split_evaluation: state.weight_seen_at_last_split_evaluation,
+            attribute_observers: AttributeClassObserverState::restore_all(
+                state.attribute_observers,
+            ),
+            is_initialized: state.is_initialized,
+        }
+    }
+
+    /// The leaf statistics, or `None` if an observer has an unknown type.
+    pub(crate) fn state(&self) -> Option<LearningNodeState> {
+        Some(LearningNodeState {
+            observed_class_distribution: self.observed_class_distribution.clone(),
+            weight_seen_at_last_split_evaluation: self.weight_seen_at_last_split_evaluation,
+            attribute_observers: AttributeClassObserverState::capture_all(
+                &self.attribute_observers,
+            )?,
+            is_initialized: self.is_initialized,
+        })
+    }
+
     pub fn get_weight_seen(&self) -> f64 {
         self.observed_class_distribution.iter().sum()
     }
@@ -123,8 +148,13 @@ impl Node for ActiveLearningNode {
         self.observed_class_distribution.clone()
     }
 
-    fn get_class_votes(&self, instance: &dyn Instance, hoeffding_tree: &HoeffdingTree) -> Vec<f64> {
-        self.observed_class_distribution.clone()
+    fn class_votes_into(
+        &self,
+        _instance: &dyn Instance,
+        _hoeffding_tree: &HoeffdingTree,
+        votes: &mut Vec<f64>,
+    ) {
+        votes.clone_from(&self.observed_class_distribution);
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -186,13 +216,8 @@ impl LearningNode for ActiveLearningNode {
 
             if self.attribute_observers[i].is_none() {
                 if let Some(attribute) = instance.attribute_at_index(instance_attribute_index) {
-                    let observer: Box<dyn AttributeClassObserver> =
-                        if attribute.as_any().is::<NominalAttribute>() {
-                            hoeffding_tree.new_nominal_class_observer()
-                        } else {
-                            hoeffding_tree.new_numeric_class_observer()
-                        };
-                    self.attribute_observers[i] = Some(observer);
+                    self.attribute_observers[i] =
+                        Some(hoeffding_tree.new_class_observer_for(attribute));
                 }
             }
 
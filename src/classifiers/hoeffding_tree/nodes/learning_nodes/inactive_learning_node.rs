@@ -46,8 +46,13 @@ impl Node for InactiveLearningNode {
         self.observed_class_distribution.clone()
     }
 
-    fn get_class_votes(&self, instance: &dyn Instance, hoeffding_tree: &HoeffdingTree) -> Vec<f64> {
-        self.observed_class_distribution.clone()
+    fn class_votes_into(
+        &self,
+        _instance: &dyn Instance,
+        _hoeffding_tree: &HoeffdingTree,
+        votes: &mut Vec<f64>,
+    ) {
+        votes.clone_from(&self.observed_class_distribution);
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -1,12 +1,14 @@
 use crate::classifiers::NaiveBayes;
-use crate::classifiers::attribute_class_observers::AttributeClassObserver;
+use crate::classifiers::attribute_class_observers::{
+    AttributeClassObserver, AttributeClassObserverState,
+};
 use crate::classifiers::conditional_tests::attribute_split_suggestion::AttributeSplitSuggestion;
 use crate::classifiers::hoeffding_tree::hoeffding_tree::HoeffdingTree;
 use crate::classifiers::hoeffding_tree::nodes::LearningNode;
 use crate::classifiers::hoeffding_tree::nodes::Node;
 use crate::classifiers::hoeffding_tree::nodes::found_node::FoundNode;
 use crate::classifiers::hoeffding_tree::split_criteria::SplitCriterion;
-use crate::core::attributes::NominalAttribute;
+use crate::classifiers::hoeffding_tree::tree_state::LearningNodeState;
 use crate::core::instances::Instance;
 use std::any::Any;
 use std::cell::RefCell;
@@ -19,6 +21,8 @@ pub struct LearningNodeNBAdaptive {
     is_initialized: bool,
     mc_correct_weight: f64,
     nb_correct_weight: f64,
+    /// Reused for the Naive Bayes prediction made on every training instance.
+    nb_votes: Vec<f64>,
 }
 
 impl LearningNodeNBAdaptive {
@@ -31,9 +35,42 @@ impl LearningNodeNBAdaptive {
             is_initialized: false,
             mc_correct_weight: 0.0,
             nb_correct_weight: 0.0,
+            nb_votes: Vec::new(),
         }
     }
 
+    pub(crate) fn from_state(
+        state: LearningNodeState,
+        mc_correct_weight: f64,
+        nb_correct_weight: f64,
+    ) -> Self {
+        Self {
+            observed_class_distribution: state.observed_class_distribution,
+            weight_seen_at_last_split_evaluation: state.weight_seen_at_last_split_evaluation,
+            attribute_observers: AttributeClassObserverState::restore_all(
+                state.attribute_observers,
+            ),
+            is_initialized: state.is_initialized,
+            mc_correct_weight,
+            nb_correct_weight,
+            nb_votes: Vec::new(),
+        }
+    }
+
+    /// The leaf statistics with the weights each prediction strategy got
+    /// right, or `None` if an observer has an unknown type.
+    pub(crate) fn state(&self) -> Option<(LearningNodeState, f64, f64)> {
+        let state = LearningNodeState {
+            observed_class_distribution: self.observed_class_distribution.clone(),
+            weight_seen_at_last_split_evaluation: self.weight_seen_at_last_split_evaluation,
+            attribute_observers: AttributeClassObserverState::capture_all(
+                &self.attribute_observers,
+            )?,
+            is_initialized: self.is_initialized,
+        };
+        Some((state, self.mc_correct_weight, self.nb_correct_weight))
+    }
+
     pub fn get_weight_seen(&self) -> f64 {
         self.observed_class_distribution.iter().sum()
     }
@@ -78,13 +115,8 @@ impl LearningNodeNBAdaptive {
 
             if self.attribute_observers[i].is_none() {
                 if let Some(attribute) = instance.attribute_at_index(instance_attribute_index) {
-                    let observer: Box<dyn AttributeClassObserver> =
-                        if attribute.as_any().is::<NominalAttribute>() {
-                            hoeffding_tree.new_nominal_class_observer()
-                        } else {
-                            hoeffding_tree.new_numeric_class_observer()
-                        };
-                    self.attribute_observers[i] = Some(observer);
+                    self.attribute_observers[i] =
+                        Some(hoeffding_tree.new_class_observer_for(attribute));
                 }
             }
 
@@ -163,19 +195,22 @@ impl Node for LearningNodeNBAdaptive {
         self.observed_class_distribution.clone()
     }
 
-    fn get_class_votes(
+    fn class_votes_into(
         &self,
         instance: &dyn Instance,
         _hoeffding_tree: &HoeffdingTree,
-    ) -> Vec<f64> {
+        votes: &mut Vec<f64>,
+    ) {
         if self.mc_correct_weight > self.nb_correct_weight {
-            return self.observed_class_distribution.clone();
+            votes.clone_from(&self.observed_class_distribution);
+            return;
         }
-        NaiveBayes::do_naive_bayes_prediction(
+        NaiveBayes::naive_bayes_prediction_into(
             instance,
             &self.observed_class_distribution,
             &self.attribute_observers,
-        )
+            votes,
+        );
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -226,13 +261,17 @@ impl LearningNode for LearningNodeNBAdaptive {
                 }
             }
 
-            let nb_prediction = NaiveBayes::do_naive_bayes_prediction(
+            let mut nb_votes = std::mem::take(&mut self.nb_votes);
+            NaiveBayes::naive_bayes_prediction_into(
                 instance,
                 &self.observed_class_distribution,
                 &self.attribute_observers,
+                &mut nb_votes,
             );
+            let predicted_nb = Self::max_index(&nb_votes);
+            self.nb_votes = nb_votes;
 
-            if let Some(predicted_nb) = Self::max_index(&nb_prediction) {
+            if let Some(predicted_nb) = predicted_nb {
                 if predicted_nb == true_class as usize {
                     self.nb_correct_weight += weight;
                 }
@@ -16,7 +16,19 @@ pub trait Node: Any {
         parent_branch: isize,
     ) -> FoundNode;
     fn get_observed_class_distribution_at_leaves_reachable_through_this_node(&self) -> Vec<f64>;
-    fn get_class_votes(&self, instance: &dyn Instance, hoeffding_tree: &HoeffdingTree) -> Vec<f64>;
+    fn get_class_votes(&self, instance: &dyn Instance, hoeffding_tree: &HoeffdingTree) -> Vec<f64> {
+        let mut votes = Vec::new();
+        self.class_votes_into(instance, hoeffding_tree, &mut votes);
+        votes
+    }
+    /// Writes the class votes for `instance` into `votes`, reusing its
+    /// allocation.
+    fn class_votes_into(
+        &self,
+        instance: &dyn Instance,
+        hoeffding_tree: &HoeffdingTree,
+        votes: &mut Vec<f64>,
+    );
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
     fn observed_class_distribution_is_pure(&self) -> bool;
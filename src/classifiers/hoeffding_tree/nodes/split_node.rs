@@ -59,6 +59,10 @@ impl SplitNode {
     pub fn num_children(&self) -> usize {
         self.children.len()
     }
+
+    pub fn split_test(&self) -> &dyn InstanceConditionalTest {
+        self.split_test.as_ref()
+    }
 }
 
 impl Node for SplitNode {
@@ -109,8 +113,13 @@ impl Node for SplitNode {
         sum_observed_class_distribution_at_leaves
     }
 
-    fn get_class_votes(&self, instance: &dyn Instance, hoeffding_tree: &HoeffdingTree) -> Vec<f64> {
-        self.observed_class_distribution.clone()
+    fn class_votes_into(
+        &self,
+        _instance: &dyn Instance,
+        _hoeffding_tree: &HoeffdingTree,
+        votes: &mut Vec<f64>,
+    ) {
+        votes.clone_from(&self.observed_class_distribution);
     }
 
     fn as_any(&self) -> &dyn Any {
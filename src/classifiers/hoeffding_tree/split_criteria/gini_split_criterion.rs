@@ -1,4 +1,5 @@
 use crate::classifiers::hoeffding_tree::split_criteria::split_criterion::SplitCriterion;
+use crate::classifiers::hoeffding_tree::tree_state::SplitCriterionState;
 
 pub struct GiniSplitCriterion {}
 
@@ -45,4 +46,8 @@ impl SplitCriterion for GiniSplitCriterion {
 
         1.0 - gini
     }
+
+    fn state(&self) -> Option<SplitCriterionState> {
+        Some(SplitCriterionState::Gini)
+    }
 }
@@ -1,3 +1,5 @@
+use crate::classifiers::hoeffding_tree::tree_state::SplitCriterionState;
+
 pub trait SplitCriterion {
     fn get_range_of_merit(&self, pre_split_distribution: &Vec<f64>) -> f64;
     fn get_merit_of_split(
@@ -5,4 +7,10 @@ pub trait SplitCriterion {
         pre_split_distribution: &[f64],
         post_split_dists: &[Vec<f64>],
     ) -> f64;
+
+    /// Serializable form of this criterion, or `None` for criteria that
+    /// cannot be saved.
+    fn state(&self) -> Option<SplitCriterionState> {
+        None
+    }
 }
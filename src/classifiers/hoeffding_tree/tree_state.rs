@@ -0,0 +1,310 @@
+use crate::classifiers::attribute_class_observers::AttributeClassObserverState;
+use crate::classifiers::hoeffding_tree::LeafPredictionOption;
+use crate::classifiers::hoeffding_tree::instance_conditional_test::{
+    InstanceConditionalTest, NominalAttributeBinaryTest, NominalAttributeMultiwayTest,
+    NumericAttributeBinaryTest,
+};
+use crate::classifiers::hoeffding_tree::nodes::{
+    ActiveLearningNode, InactiveLearningNode, LearningNodeNB, LearningNodeNBAdaptive, Node,
+    SplitNode,
+};
+use crate::classifiers::hoeffding_tree::split_criteria::{GiniSplitCriterion, SplitCriterion};
+use crate::drift::Adwin;
+use crate::error::RivuError;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::io::Error;
+use std::rc::Rc;
+
+/// Serializable form of the split tests held by split nodes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SplitTestState {
+    NominalMultiway {
+        attribute_index: usize,
+    },
+    NominalBinary {
+        attribute_index: usize,
+        attribute_value: usize,
+    },
+    NumericBinary {
+        attribute_index: usize,
+        attribute_value: f64,
+        equals_passes_test: bool,
+    },
+}
+
+impl SplitTestState {
+    pub fn into_test(self) -> Box<dyn InstanceConditionalTest> {
+        match self {
+            Self::NominalMultiway { attribute_index } => {
+                Box::new(NominalAttributeMultiwayTest::new(attribute_index))
+            }
+            Self::NominalBinary {
+                attribute_index,
+                attribute_value,
+            } => Box::new(NominalAttributeBinaryTest::new(
+                attribute_index,
+                attribute_value,
+            )),
+            Self::NumericBinary {
+                attribute_index,
+                attribute_value,
+                equals_passes_test,
+            } => Box::new(NumericAttributeBinaryTest::new(
+                attribute_index,
+                attribute_value,
+                equals_passes_test,
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SplitCriterionState {
+    Gini,
+}
+
+impl SplitCriterionState {
+    pub fn into_criterion(self) -> Box<dyn SplitCriterion> {
+        match self {
+            Self::Gini => Box::new(GiniSplitCriterion::new()),
+        }
+    }
+}
+
+/// Statistics of a leaf that learns: its class distribution and the
+/// observers it evaluates splits with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LearningNodeState {
+    pub observed_class_distribution: Vec<f64>,
+    pub weight_seen_at_last_split_evaluation: f64,
+    pub attribute_observers: Vec<Option<AttributeClassObserverState>>,
+    pub is_initialized: bool,
+}
+
+/// One node of a saved tree. Split nodes refer to their children by
+/// position in [`HoeffdingTreeState::nodes`], which keeps the format flat
+/// however deep the tree grows.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum NodeState {
+    Split {
+        observed_class_distribution: Vec<f64>,
+        split_test: SplitTestState,
+        children: Vec<Option<usize>>,
+    },
+    ActiveLearning(LearningNodeState),
+    InactiveLearning {
+        observed_class_distribution: Vec<f64>,
+    },
+    LearningNb(LearningNodeState),
+    LearningNbAdaptive {
+        #[serde(flatten)]
+        node: LearningNodeState,
+        mc_correct_weight: f64,
+        nb_correct_weight: f64,
+    },
+}
+
+/// Serializable snapshot of a [`HoeffdingTree`](super::HoeffdingTree): its
+/// options, growth bookkeeping, and every node in depth-first order with
+/// the root first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HoeffdingTreeState {
+    pub leaf_prediction: LeafPredictionOption,
+    pub nb_threshold: Option<usize>,
+    pub grace_period: usize,
+    pub split_criterion: SplitCriterionState,
+    pub numeric_estimator: AttributeClassObserverState,
+    pub split_confidence: f64,
+    pub tie_threshold: f64,
+    pub binary_splits: bool,
+    pub no_pre_prune: bool,
+    pub remove_poor_attributes: bool,
+    pub max_byte_size: usize,
+    pub stop_mem_management: bool,
+    pub memory_estimate_period: usize,
+    pub growth_allowed: bool,
+    pub training_weight_seen: f64,
+    pub decision_node_count: usize,
+    pub active_leaf_node_count: usize,
+    pub inactive_leaf_node_count: usize,
+    pub active_leaf_byte_size_estimate: f64,
+    pub inactive_leaf_byte_size_estimate: f64,
+    pub byte_size_estimate_overhead_fraction: f64,
+    pub nodes: Vec<NodeState>,
+}
+
+/// One node of a saved
+/// [`HoeffdingAdaptiveTree`](super::HoeffdingAdaptiveTree), with the
+/// [`Adwin`] window over the errors of its predictions. As in
+/// [`NodeState`], children and alternates are referred to by position in
+/// [`HoeffdingAdaptiveTreeState::nodes`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum AdaptiveNodeState {
+    Split {
+        observed_class_distribution: Vec<f64>,
+        split_test: SplitTestState,
+        children: Vec<usize>,
+        error: Adwin,
+        alternate: Option<usize>,
+    },
+    Leaf {
+        #[serde(flatten)]
+        node: LearningNodeState,
+        mc_correct_weight: f64,
+        nb_correct_weight: f64,
+        error: Adwin,
+    },
+}
+
+/// Serializable snapshot of a
+/// [`HoeffdingAdaptiveTree`](super::HoeffdingAdaptiveTree): its options, the
+/// alternate subtree counts, and every node of the main tree and of the
+/// alternates in depth-first order with the root first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HoeffdingAdaptiveTreeState {
+    pub leaf_prediction: LeafPredictionOption,
+    pub nb_threshold: Option<usize>,
+    pub grace_period: usize,
+    pub split_criterion: SplitCriterionState,
+    pub split_confidence: f64,
+    pub tie_threshold: f64,
+    pub binary_splits: bool,
+    pub no_pre_prune: bool,
+    pub training_weight_seen: f64,
+    pub alternate_trees_started: usize,
+    pub switched_alternate_trees: usize,
+    pub pruned_alternate_trees: usize,
+    pub nodes: Vec<AdaptiveNodeState>,
+}
+
+pub(super) fn unknown(what: &str) -> Error {
+    RivuError::model(format!("cannot save a tree with an unknown {what} type")).into()
+}
+
+/// Flattens the subtree under `root` into [`NodeState`]s, parents before
+/// their children.
+pub(super) fn capture_nodes(root: &Rc<RefCell<dyn Node>>) -> Result<Vec<NodeState>, Error> {
+    let mut nodes = Vec::new();
+    capture_node(root, &mut nodes)?;
+    Ok(nodes)
+}
+
+fn capture_node(node: &Rc<RefCell<dyn Node>>, nodes: &mut Vec<NodeState>) -> Result<(), Error> {
+    let guard = node.borrow();
+    let any = guard.as_any();
+    let observers = || unknown("attribute observer");
+    let state = if let Some(split) = any.downcast_ref::<SplitNode>() {
+        let split_test = split
+            .split_test()
+            .state()
+            .ok_or_else(|| unknown("split test"))?;
+        let position = nodes.len();
+        nodes.push(NodeState::Split {
+            observed_class_distribution: split.get_observed_class_distribution().clone(),
+            split_test,
+            children: Vec::new(),
+        });
+        let mut children = Vec::with_capacity(split.num_children());
+        for i in 0..split.num_children() {
+            children.push(match split.get_child(i) {
+                Some(child) => {
+                    let index = nodes.len();
+                    capture_node(&child, nodes)?;
+                    Some(index)
+                }
+                None => None,
+            });
+        }
+        if let NodeState::Split { children: c, .. } = &mut nodes[position] {
+            *c = children;
+        }
+        return Ok(());
+    } else if let Some(n) = any.downcast_ref::<ActiveLearningNode>() {
+        NodeState::ActiveLearning(n.state().ok_or_else(observers)?)
+    } else if let Some(n) = any.downcast_ref::<InactiveLearningNode>() {
+        NodeState::InactiveLearning {
+            observed_class_distribution: n.get_observed_class_distribution().clone(),
+        }
+    } else if let Some(n) = any.downcast_ref::<LearningNodeNB>() {
+        NodeState::LearningNb(n.state().ok_or_else(observers)?)
+    } else if let Some(n) = any.downcast_ref::<LearningNodeNBAdaptive>() {
+        let (node, mc_correct_weight, nb_correct_weight) = n.state().ok_or_else(observers)?;
+        NodeState::LearningNbAdaptive {
+            node,
+            mc_correct_weight,
+            nb_correct_weight,
+        }
+    } else {
+        return Err(unknown("node"));
+    };
+    nodes.push(state);
+    Ok(())
+}
+
+/// Rebuilds the tree written by [`capture_nodes`] and returns its root.
+pub(super) fn restore_nodes(nodes: Vec<NodeState>) -> Result<Rc<RefCell<dyn Node>>, Error> {
+    let invalid = |msg: String| -> Error { RivuError::model(msg).into() };
+    let count = nodes.len();
+    let mut built: Vec<Option<Rc<RefCell<dyn Node>>>> = (0..count).map(|_| None).collect();
+    // Children always come after their parent, so building from the back
+    // finds every child ready.
+    for (index, state) in nodes.into_iter().enumerate().rev() {
+        let node: Rc<RefCell<dyn Node>> = match state {
+            NodeState::Split {
+                observed_class_distribution,
+                split_test,
+                children,
+            } => {
+                let mut split = SplitNode::new(
+                    split_test.into_test(),
+                    observed_class_distribution,
+                    Some(children.len()),
+                );
+                for (branch, child) in children.into_iter().enumerate() {
+                    let Some(child) = child else { continue };
+                    let node = (child > index)
+                        .then(|| built.get_mut(child).and_then(Option::take))
+                        .flatten()
+                        .ok_or_else(|| {
+                            invalid(format!("node {index} has an invalid child {child}"))
+                        })?;
+                    split.set_child(branch, node);
+                }
+                Rc::new(RefCell::new(split))
+            }
+            NodeState::ActiveLearning(s) => {
+                Rc::new(RefCell::new(ActiveLearningNode::from_state(s)))
+            }
+            NodeState::InactiveLearning {
+                observed_class_distribution,
+            } => Rc::new(RefCell::new(InactiveLearningNode::new(
+                observed_class_distribution,
+            ))),
+            NodeState::LearningNb(s) => Rc::new(RefCell::new(LearningNodeNB::from_state(s))),
+            NodeState::LearningNbAdaptive {
+                node,
+                mc_correct_weight,
+                nb_correct_weight,
+            } => Rc::new(RefCell::new(LearningNodeNBAdaptive::from_state(
+                node,
+                mc_correct_weight,
+                nb_correct_weight,
+            ))),
+        };
+        built[index] = Some(node);
+    }
+    let mut built = built.into_iter();
+    let root = built
+        .next()
+        .flatten()
+        .ok_or_else(|| invalid("saved tree has no nodes".into()))?;
+    if built.any(|n| n.is_some()) {
+        return Err(invalid("saved tree has unreachable nodes".into()));
+    }
+    Ok(root)
+}
@@ -0,0 +1,485 @@
+//! Structural JSON dumps of trained models.
+//!
+//! Unlike the saved [`ClassifierState`], which is built to be loaded back,
+//! [`describe`] is built to be read: attributes and classes are named from
+//! the header, trees are nested instead of flattened, split tests are spelled
+//! out as conditions, and Naive Bayes estimators are reported as priors,
+//! per-class means and standard deviations, and value probabilities. Models
+//! without a header fall back to `att_<index>` and the class index.
+
+use crate::classifiers::attribute_class_observers::{
+    AttributeClassObserver, AttributeClassObserverState,
+};
+use crate::classifiers::hoeffding_tree::{
+    AdaptiveNodeState, HoeffdingAdaptiveTreeState, HoeffdingTreeState, LeafPredictionOption,
+    NodeState, SplitTestState,
+};
+use crate::classifiers::{ClassifierState, LearnerState, NaiveBayesState, TargetMeanState};
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use serde_json::{Map, Value, json};
+
+/// Names of attributes, their values, and the classes, as far as the header
+/// (if any) knows them.
+struct Names<'a> {
+    header: Option<&'a InstanceHeader>,
+}
+
+impl Names<'_> {
+    fn attribute(&self, index: usize) -> String {
+        self.header
+            .and_then(|h| h.attributes.get(index))
+            .map_or_else(|| format!("att_{index}"), |a| a.name())
+    }
+
+    fn nominal(&self, index: usize) -> Option<&NominalAttribute> {
+        self.header?
+            .attributes
+            .get(index)?
+            .as_any()
+            .downcast_ref::<NominalAttribute>()
+    }
+
+    fn value(&self, index: usize, value: usize) -> String {
+        self.nominal(index)
+            .and_then(|n| n.values.get(value))
+            .cloned()
+            .unwrap_or_else(|| value.to_string())
+    }
+
+    fn class(&self, class: usize) -> String {
+        match self.header {
+            Some(h) => self.value(h.class_index(), class),
+            None => class.to_string(),
+        }
+    }
+
+    fn classes(&self, count: usize) -> Vec<String> {
+        (0..count).map(|c| self.class(c)).collect()
+    }
+
+    /// Index in the instance of the model's `m`-th attribute, which skips
+    /// the class.
+    fn instance_index(&self, m: usize) -> usize {
+        match self.header {
+            Some(h) if m >= h.class_index() => m + 1,
+            _ => m,
+        }
+    }
+
+    fn distribution(&self, weights: &[f64]) -> Value {
+        let map: Map<String, Value> = weights
+            .iter()
+            .enumerate()
+            .map(|(c, &w)| (self.class(c), json!(w)))
+            .collect();
+        Value::Object(map)
+    }
+}
+
+/// Describes the model held by `state` as nested JSON.
+pub fn describe(state: &ClassifierState) -> Value {
+    let names = Names {
+        header: state.header.as_ref(),
+    };
+    let mut dump = match &state.learner {
+        LearnerState::NaiveBayes(s) => naive_bayes(&names, s),
+        LearnerState::HoeffdingTree(s) => hoeffding_tree(&names, s),
+        LearnerState::HoeffdingAdaptiveTree(s) => hoeffding_adaptive_tree(&names, s),
+        LearnerState::TargetMean(s) => target_mean(s),
+    };
+    let mut out = Map::new();
+    out.insert("learner".into(), json!(state.learner.name()));
+    if let Some(h) = names.header {
+        out.insert("relation".into(), json!(h.relation_name()));
+        out.insert("class".into(), json!(names.attribute(h.class_index())));
+    }
+    out.append(dump.as_object_mut().expect("learner dumps are objects"));
+    Value::Object(out)
+}
+
+fn naive_bayes(names: &Names, state: &NaiveBayesState) -> Value {
+    let weights = &state.observed_class_distribution;
+    let total: f64 = weights.iter().sum();
+    let priors: Vec<f64> = weights
+        .iter()
+        .map(|&w| if total > 0.0 { w / total } else { 0.0 })
+        .collect();
+    let classes = names.classes(weights.len());
+
+    let attributes: Vec<Value> = state
+        .attribute_observers
+        .iter()
+        .enumerate()
+        .map(|(m, observer)| {
+            let index = names.instance_index(m);
+            let name = names.attribute(index);
+            match observer {
+                Some(AttributeClassObserverState::Nominal(obs)) => {
+                    let num_values = names
+                        .nominal(index)
+                        .map_or(obs.get_max_att_vals_observed(), |n| n.values.len());
+                    let per_class: Map<String, Value> = classes
+                        .iter()
+                        .enumerate()
+                        .map(|(c, label)| {
+                            let probabilities: Map<String, Value> = (0..num_values)
+                                .map(|v| {
+                                    let p =
+                                        obs.probability_of_attribute_value_given_class(v as f64, c);
+                                    (names.value(index, v), json!(p))
+                                })
+                                .collect();
+                            (label.clone(), Value::Object(probabilities))
+                        })
+                        .collect();
+                    json!({ "name": name, "kind": "nominal", "per_class": per_class })
+                }
+                Some(AttributeClassObserverState::GaussianNumeric(obs)) => {
+                    let per_class: Map<String, Value> = obs
+                        .estimators_per_class()
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(c, est)| {
+                            let est = est.as_ref()?;
+                            let stats = json!({
+                                "mean": est.get_mean(),
+                                "std_dev": est.get_std_dev(),
+                                "weight": est.get_total_weight_observed(),
+                            });
+                            Some((names.class(c), stats))
+                        })
+                        .collect();
+                    json!({ "name": name, "kind": "numeric", "per_class": per_class })
+                }
+                Some(AttributeClassObserverState::Null(_)) => {
+                    json!({ "name": name, "kind": "disabled" })
+                }
+                None => json!({ "name": name, "kind": "unobserved" }),
+            }
+        })
+        .collect();
+
+    json!({
+        "classes": classes,
+        "class_weights": names.distribution(weights),
+        "priors": names.distribution(&priors),
+        "attributes": attributes,
+    })
+}
+
+/// The condition that sends an instance down `branch` of `test`.
+fn branch_condition(names: &Names, test: &SplitTestState, branch: usize) -> String {
+    match *test {
+        SplitTestState::NominalMultiway { attribute_index } => format!(
+            "{} = {}",
+            names.attribute(attribute_index),
+            names.value(attribute_index, branch)
+        ),
+        SplitTestState::NominalBinary {
+            attribute_index,
+            attribute_value,
+        } => {
+            let index = names.instance_index(attribute_index);
+            let operator = if branch == 0 { "=" } else { "!=" };
+            format!(
+                "{} {operator} {}",
+                names.attribute(index),
+                names.value(index, attribute_value)
+            )
+        }
+        SplitTestState::NumericBinary {
+            attribute_index,
+            attribute_value,
+            equals_passes_test,
+        } => {
+            let operator = match (branch, equals_passes_test) {
+                (0, true) => "<=",
+                (0, false) => "<",
+                (_, true) => ">",
+                (_, false) => ">=",
+            };
+            format!(
+                "{} {operator} {attribute_value}",
+                names.attribute(attribute_index)
+            )
+        }
+    }
+}
+
+fn leaf(names: &Names, kind: &str, dist: &[f64]) -> Value {
+    let majority = dist
+        .iter()
+        .enumerate()
+        .fold(0, |best, (c, &w)| if w > dist[best] { c } else { best });
+    json!({
+        "type": "leaf",
+        "learning": kind,
+        "weight": dist.iter().sum::<f64>(),
+        "class_distribution": names.distribution(dist),
+        "majority_class": names.class(majority),
+    })
+}
+
+fn tree_node(names: &Names, nodes: &[NodeState], index: usize) -> (Value, usize) {
+    let Some(node) = nodes.get(index) else {
+        return (Value::Null, 0);
+    };
+    let leaf = |kind: &str, dist: &[f64]| leaf(names, kind, dist);
+    let value = match node {
+        NodeState::Split {
+            observed_class_distribution,
+            split_test,
+            children,
+        } => {
+            let mut depth = 0;
+            let branches: Vec<Value> = children
+                .iter()
+                .enumerate()
+                .map(|(branch, child)| {
+                    let node = child.map_or(Value::Null, |c| {
+                        let (node, d) = tree_node(names, nodes, c);
+                        depth = depth.max(d);
+                        node
+                    });
+                    json!({
+                        "condition": branch_condition(names, split_test, branch),
+                        "node": node,
+                    })
+                })
+                .collect();
+            let split = json!({
+                "type": "split",
+                "test": split_test,
+                "weight": observed_class_distribution.iter().sum::<f64>(),
+                "class_distribution": names.distribution(observed_class_distribution),
+                "branches": branches,
+            });
+            return (split, depth + 1);
+        }
+        NodeState::ActiveLearning(n) => leaf("active", &n.observed_class_distribution),
+        NodeState::InactiveLearning {
+            observed_class_distribution,
+        } => leaf("inactive", observed_class_distribution),
+        NodeState::LearningNb(n) => leaf("naive-bayes", &n.observed_class_distribution),
+        NodeState::LearningNbAdaptive {
+            node,
+            mc_correct_weight,
+            nb_correct_weight,
+        } => {
+            let mut leaf = leaf("adaptive-naive-bayes", &node.observed_class_distribution);
+            leaf["mc_correct_weight"] = json!(mc_correct_weight);
+            leaf["nb_correct_weight"] = json!(nb_correct_weight);
+            leaf
+        }
+    };
+    (value, 1)
+}
+
+fn hoeffding_tree(names: &Names, state: &HoeffdingTreeState) -> Value {
+    let (root, depth) = tree_node(names, &state.nodes, 0);
+    json!({
+        "leaf_prediction": state.leaf_prediction,
+        "training_weight": state.training_weight_seen,
+        "depth": depth,
+        "decision_nodes": state.decision_node_count,
+        "active_leaves": state.active_leaf_node_count,
+        "inactive_leaves": state.inactive_leaf_node_count,
+        "root": root,
+    })
+}
+
+/// Like [`tree_node`], with each node's error rate and the alternate
+/// subtree growing beside a decision node. The depth is the main tree's.
+fn adaptive_tree_node(
+    names: &Names,
+    state: &HoeffdingAdaptiveTreeState,
+    index: usize,
+) -> (Value, usize) {
+    let Some(node) = state.nodes.get(index) else {
+        return (Value::Null, 0);
+    };
+    match node {
+        AdaptiveNodeState::Split {
+            observed_class_distribution,
+            split_test,
+            children,
+            error,
+            alternate,
+        } => {
+            let mut depth = 0;
+            let branches: Vec<Value> = children
+                .iter()
+                .enumerate()
+                .map(|(branch, &child)| {
+                    let (node, d) = adaptive_tree_node(names, state, child);
+                    depth = depth.max(d);
+                    json!({
+                        "condition": branch_condition(names, split_test, branch),
+                        "node": node,
+                    })
+                })
+                .collect();
+            let alternate =
+                alternate.map_or(Value::Null, |a| adaptive_tree_node(names, state, a).0);
+            let split = json!({
+                "type": "split",
+                "test": split_test,
+                "weight": observed_class_distribution.iter().sum::<f64>(),
+                "class_distribution": names.distribution(observed_class_distribution),
+                "error_rate": error.estimation(),
+                "branches": branches,
+                "alternate": alternate,
+            });
+            (split, depth + 1)
+        }
+        AdaptiveNodeState::Leaf { node, error, .. } => {
+            let kind = match state.leaf_prediction {
+                LeafPredictionOption::MajorityClass => "active",
+                LeafPredictionOption::NaiveBayes => "naive-bayes",
+                LeafPredictionOption::AdaptiveNaiveBayes => "adaptive-naive-bayes",
+            };
+            let mut leaf = leaf(names, kind, &node.observed_class_distribution);
+            leaf["error_rate"] = json!(error.estimation());
+            (leaf, 1)
+        }
+    }
+}
+
+fn hoeffding_adaptive_tree(names: &Names, state: &HoeffdingAdaptiveTreeState) -> Value {
+    let (root, depth) = adaptive_tree_node(names, state, 0);
+    let alternates = state
+        .nodes
+        .iter()
+        .filter(|n| {
+            matches!(
+                n,
+                AdaptiveNodeState::Split {
+                    alternate: Some(_),
+                    ..
+                }
+            )
+        })
+        .count();
+    json!({
+        "leaf_prediction": state.leaf_prediction,
+        "training_weight": state.training_weight_seen,
+        "depth": depth,
+        "alternate_trees": alternates,
+        "switched_alternate_trees": state.switched_alternate_trees,
+        "pruned_alternate_trees": state.pruned_alternate_trees,
+        "root": root,
+    })
+}
+
+fn target_mean(state: &TargetMeanState) -> Value {
+    let mean = if state.weight_seen > 0.0 {
+        state.sum_target / state.weight_seen
+    } else {
+        0.0
+    };
+    json!({ "weight_seen": state.weight_seen, "mean": mean })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::{Classifier, HoeffdingTree, NaiveBayes, TargetMean};
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use crate::streams::Stream;
+    use crate::streams::generators::{AgrawalFunction, AgrawalGenerator};
+    use std::sync::Arc;
+
+    #[test]
+    fn naive_bayes_reports_priors_and_estimators_by_name() {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NominalAttribute::ordinal(
+                "colour".into(),
+                vec!["red".into(), "blue".into()],
+            )),
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NominalAttribute::ordinal(
+                "class".into(),
+                vec!["yes".into(), "no".into()],
+            )),
+        ];
+        let header = Arc::new(InstanceHeader::new("r".into(), attrs, 2));
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(Arc::clone(&header));
+        for row in [
+            [0.0, 1.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [1.0, 3.0, 0.0],
+            [1.0, 5.0, 1.0],
+        ] {
+            nb.train_on_instance(&DenseInstance::new(Arc::clone(&header), row.to_vec(), 1.0));
+        }
+
+        let dump = nb.to_json().unwrap();
+        assert_eq!(dump["learner"], "naive-bayes");
+        assert_eq!(dump["class"], "class");
+        assert_eq!(dump["priors"], json!({ "yes": 0.75, "no": 0.25 }));
+        assert_eq!(dump["attributes"][0]["per_class"]["yes"]["red"], 0.6);
+        assert_eq!(
+            dump["attributes"][1]["per_class"]["yes"],
+            json!({ "mean": 2.0, "std_dev": 1.0, "weight": 3.0 })
+        );
+    }
+
+    #[test]
+    fn trees_nest_their_nodes_under_named_conditions() {
+        let mut stream = AgrawalGenerator::new(AgrawalFunction::F2, true, 0.05, None, 7).unwrap();
+        let mut tree =
+            HoeffdingTree::new_with_only_leaf_prediction(LeafPredictionOption::AdaptiveNaiveBayes);
+        tree.set_model_context(Arc::new(stream.header().clone()));
+        for _ in 0..3000 {
+            let inst = stream.next_instance().unwrap();
+            tree.train_on_instance(inst.as_ref());
+        }
+
+        let dump = tree.to_json().unwrap();
+        let root = &dump["root"];
+        assert_eq!(root["type"], "split");
+        assert!(dump["depth"].as_u64().unwrap() >= 2);
+        let condition = root["branches"][0]["condition"].as_str().unwrap();
+        let attribute = condition.split(' ').next().unwrap();
+        assert!(stream.header().index_of_attribute(attribute).is_some());
+
+        fn leaves(node: &Value) -> usize {
+            match node["type"].as_str() {
+                Some("split") => node["branches"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|b| leaves(&b["node"]))
+                    .sum(),
+                Some("leaf") => {
+                    assert!(node.get("nb_correct_weight").is_some());
+                    1
+                }
+                _ => 0,
+            }
+        }
+        assert_eq!(
+            leaves(root) as u64,
+            dump["active_leaves"].as_u64().unwrap() + dump["inactive_leaves"].as_u64().unwrap()
+        );
+    }
+
+    #[test]
+    fn models_without_a_header_use_indices() {
+        let dump = TargetMean::new().to_json().unwrap();
+        assert_eq!(
+            dump,
+            json!({ "learner": "target-mean", "weight_seen": 0.0, "mean": 0.0 })
+        );
+
+        let names = Names { header: None };
+        let test = SplitTestState::NominalBinary {
+            attribute_index: 2,
+            attribute_value: 1,
+        };
+        assert_eq!(branch_condition(&names, &test, 1), "att_2 != 1");
+    }
+}
@@ -1,9 +1,19 @@
 pub mod attribute_class_observers;
 mod bayes;
 mod classifier;
+mod classifier_state;
 mod conditional_tests;
+mod functions;
 pub mod hoeffding_tree;
+pub mod introspection;
+pub mod model_file;
+pub mod onnx;
+pub mod pmml;
 
-pub use bayes::NaiveBayes;
+pub use bayes::{NaiveBayes, NaiveBayesState};
 pub use classifier::Classifier;
-pub use hoeffding_tree::HoeffdingTree;
+pub use classifier_state::{ClassifierState, LearnerState, load_classifier};
+pub use functions::{TargetMean, TargetMeanState};
+pub use hoeffding_tree::{
+    HoeffdingAdaptiveTree, HoeffdingAdaptiveTreeState, HoeffdingTree, HoeffdingTreeState,
+};
@@ -0,0 +1,291 @@
+//! Container format of saved models.
+//!
+//! A model file is a small binary frame around the JSON
+//! [`ClassifierState`], all integers little-endian:
+//!
+//! | Field | Size |
+//! | --- | --- |
+//! | Magic `RIVUMODL` | 8 bytes |
+//! | Format version | `u16` |
+//! | Rivu version that wrote the file | `u8` length + UTF-8 |
+//! | Learner tag (e.g. `naive-bayes`) | `u8` length + UTF-8 |
+//! | Header fingerprint, 0 without a header | `u64` |
+//! | Payload length | `u64` |
+//! | Payload (JSON) | rest |
+//!
+//! Readers accept every format version up to their own and reject newer ones.
+//! Bare JSON files, as written before the container existed, are read as
+//! version 0.
+
+use crate::classifiers::ClassifierState;
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::error::RivuError;
+use std::fs::File;
+use std::io::{BufReader, Error, ErrorKind, Read, Write};
+use std::path::Path;
+
+pub const MODEL_FILE_MAGIC: &[u8; 8] = b"RIVUMODL";
+
+/// Version of the container written by this build.
+pub const MODEL_FORMAT_VERSION: u16 = 1;
+
+/// What a model file says about itself, readable without parsing the model.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModelFileInfo {
+    pub format_version: u16,
+    /// Rivu version that wrote the file; empty for bare JSON files.
+    pub crate_version: String,
+    pub learner: String,
+    pub header_fingerprint: u64,
+}
+
+impl ModelFileInfo {
+    /// Whether the model was trained on data shaped like `header`, by
+    /// [`header_fingerprint`].
+    pub fn matches_header(&self, header: &InstanceHeader) -> bool {
+        self.header_fingerprint == header_fingerprint(header)
+    }
+}
+
+/// FNV-1a hash of what makes two headers compatible: attribute names,
+/// kinds, nominal values, the class index, and the labels. The relation name
+/// is left out, as in
+/// [`HeaderCompatibility`](crate::core::header_compatibility::HeaderCompatibility).
+pub fn header_fingerprint(header: &InstanceHeader) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for &b in bytes.iter().chain([0xff].iter()) {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
+    for attr in &header.attributes {
+        feed(attr.name().as_bytes());
+        match attr.as_any().downcast_ref::<NominalAttribute>() {
+            Some(n) => {
+                feed(if n.is_ordinal() {
+                    b"ordinal"
+                } else {
+                    b"nominal"
+                });
+                n.values.iter().for_each(|v| feed(v.as_bytes()));
+            }
+            None => feed(b"numeric"),
+        }
+    }
+    feed(&(header.class_index() as u64).to_le_bytes());
+    for &label in header.label_indices() {
+        feed(&(label as u64).to_le_bytes());
+    }
+    hash
+}
+
+fn short_string(w: &mut dyn Write, s: &str) -> Result<(), Error> {
+    let bytes = s.as_bytes();
+    let len = u8::try_from(bytes.len())
+        .map_err(|_| RivuError::model(format!("'{s}' is too long for a model file")))?;
+    w.write_all(&[len])?;
+    w.write_all(bytes)
+}
+
+/// Writes `state` in the current container format.
+pub fn write_model_file(w: &mut dyn Write, state: &ClassifierState) -> Result<(), Error> {
+    let payload = serde_json::to_vec(state).map_err(Error::other)?;
+    w.write_all(MODEL_FILE_MAGIC)?;
+    w.write_all(&MODEL_FORMAT_VERSION.to_le_bytes())?;
+    short_string(w, env!("CARGO_PKG_VERSION"))?;
+    short_string(w, state.learner.name())?;
+    let fingerprint = state.header.as_ref().map_or(0, header_fingerprint);
+    w.write_all(&fingerprint.to_le_bytes())?;
+    w.write_all(&(payload.len() as u64).to_le_bytes())?;
+    w.write_all(&payload)
+}
+
+fn corrupt(what: &str) -> Error {
+    RivuError::model(format!("corrupt model file: {what}")).into()
+}
+
+fn read_exact<const N: usize>(r: &mut dyn Read, what: &str) -> Result<[u8; N], Error> {
+    let mut buf = [0u8; N];
+    r.read_exact(&mut buf).map_err(|e| match e.kind() {
+        ErrorKind::UnexpectedEof => corrupt(&format!("truncated {what}")),
+        _ => e,
+    })?;
+    Ok(buf)
+}
+
+fn read_short_string(r: &mut dyn Read, what: &str) -> Result<String, Error> {
+    let [len] = read_exact::<1>(r, what)?;
+    let mut buf = vec![0u8; len as usize];
+    r.read_exact(&mut buf)
+        .map_err(|_| corrupt(&format!("truncated {what}")))?;
+    String::from_utf8(buf).map_err(|_| corrupt(&format!("{what} is not UTF-8")))
+}
+
+/// Reads the frame up to the payload, leaving `r` at its first byte.
+fn read_frame(r: &mut dyn Read) -> Result<(ModelFileInfo, u64), Error> {
+    let magic = read_exact::<8>(r, "magic bytes")?;
+    if &magic != MODEL_FILE_MAGIC {
+        return Err(RivuError::model("not a Rivu model file").into());
+    }
+    let format_version = u16::from_le_bytes(read_exact(r, "format version")?);
+    if format_version > MODEL_FORMAT_VERSION {
+        return Err(RivuError::unsupported(format!(
+            "model file format {format_version} is newer than this build supports \
+             ({MODEL_FORMAT_VERSION}); upgrade Rivu to load it"
+        ))
+        .into());
+    }
+    let crate_version = read_short_string(r, "Rivu version")?;
+    let learner = read_short_string(r, "learner tag")?;
+    let header_fingerprint = u64::from_le_bytes(read_exact(r, "header fingerprint")?);
+    let payload_len = u64::from_le_bytes(read_exact(r, "payload length")?);
+    let info = ModelFileInfo {
+        format_version,
+        crate_version,
+        learner,
+        header_fingerprint,
+    };
+    Ok((info, payload_len))
+}
+
+/// Reads a model file, checking that the frame agrees with the payload.
+pub fn read_model_file(mut r: impl Read) -> Result<(ModelFileInfo, ClassifierState), Error> {
+    let mut first = [0u8; 1];
+    r.read_exact(&mut first)
+        .map_err(|_| corrupt("the file is empty"))?;
+    let mut r = (&first[..]).chain(r);
+    if first[0] == b'{' {
+        let state = ClassifierState::read(r)?;
+        let info = ModelFileInfo {
+            format_version: 0,
+            crate_version: String::new(),
+            learner: state.learner.name().to_string(),
+            header_fingerprint: state.header.as_ref().map_or(0, header_fingerprint),
+        };
+        return Ok((info, state));
+    }
+
+    let (info, payload_len) = read_frame(&mut r)?;
+    let mut payload = Vec::new();
+    r.take(payload_len).read_to_end(&mut payload)?;
+    if payload.len() as u64 != payload_len {
+        return Err(corrupt("truncated payload"));
+    }
+    let state = ClassifierState::read(payload.as_slice())?;
+    if state.learner.name() != info.learner {
+        return Err(corrupt(&format!(
+            "tagged '{}' but holds a {}",
+            info.learner,
+            state.learner.name()
+        )));
+    }
+    if state.header.as_ref().map_or(0, header_fingerprint) != info.header_fingerprint {
+        return Err(corrupt("the header does not match its fingerprint"));
+    }
+    Ok((info, state))
+}
+
+/// Reads only the frame of the model file at `path`.
+pub fn read_model_info(path: impl AsRef<Path>) -> Result<ModelFileInfo, Error> {
+    let mut r = BufReader::new(File::open(path.as_ref())?);
+    let mut first = [0u8; 1];
+    r.read_exact(&mut first)
+        .map_err(|_| corrupt("the file is empty"))?;
+    if first[0] == b'{' {
+        return read_model_file((&first[..]).chain(r)).map(|(info, _)| info);
+    }
+    read_frame(&mut (&first[..]).chain(r)).map(|(info, _)| info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::{Classifier, LearnerState, NaiveBayes, TargetMean};
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use std::sync::Arc;
+
+    fn header(class: &str) -> Arc<InstanceHeader> {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NominalAttribute::ordinal(
+                class.into(),
+                vec!["a".into(), "b".into()],
+            )),
+        ];
+        Arc::new(InstanceHeader::new("r".into(), attrs, 1))
+    }
+
+    fn trained() -> (NaiveBayes, Vec<u8>) {
+        let h = header("c");
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(Arc::clone(&h));
+        for (x, c) in [(1.0, 0.0), (2.0, 1.0), (1.5, 0.0)] {
+            nb.train_on_instance(&DenseInstance::new(Arc::clone(&h), vec![x, c], 1.0));
+        }
+        let mut file = Vec::new();
+        write_model_file(&mut file, &nb.snapshot_state().unwrap()).unwrap();
+        (nb, file)
+    }
+
+    #[test]
+    fn frame_describes_the_model() {
+        let (nb, file) = trained();
+        assert_eq!(&file[..8], MODEL_FILE_MAGIC);
+
+        let (info, state) = read_model_file(file.as_slice()).unwrap();
+        assert_eq!(info.format_version, MODEL_FORMAT_VERSION);
+        assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.learner, "naive-bayes");
+        assert!(info.matches_header(&header("c")));
+        assert!(!info.matches_header(&header("class")));
+        let LearnerState::NaiveBayes(s) = state.learner else {
+            panic!("expected naive bayes");
+        };
+        assert_eq!(
+            s.observed_class_distribution,
+            nb.state().unwrap().observed_class_distribution
+        );
+
+        let mut bare = Vec::new();
+        TargetMean::new().save_model(&mut bare).unwrap();
+        let (info, _) = read_model_file(bare.as_slice()).unwrap();
+        assert_eq!((info.format_version, info.header_fingerprint), (0, 0));
+        assert_eq!(info.learner, "target-mean");
+    }
+
+    #[test]
+    fn rejects_newer_corrupt_and_foreign_files() {
+        let (_, file) = trained();
+        let err_of = |bytes: &[u8]| read_model_file(bytes).map(|_| ()).unwrap_err();
+
+        let mut newer = file.clone();
+        newer[8..10].copy_from_slice(&(MODEL_FORMAT_VERSION + 1).to_le_bytes());
+        let err = err_of(&newer);
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert!(err.to_string().contains("upgrade Rivu"));
+
+        assert!(matches!(
+            RivuError::from(err_of(&file[..file.len() - 3])),
+            RivuError::Model(m) if m.contains("truncated payload")
+        ));
+
+        let mut retagged = file.clone();
+        let tag_at = 10 + 1 + env!("CARGO_PKG_VERSION").len() + 1;
+        retagged[tag_at..tag_at + 5].copy_from_slice(b"xaive");
+        assert!(
+            err_of(&retagged)
+                .to_string()
+                .contains("tagged 'xaive-bayes'")
+        );
+
+        assert!(
+            err_of(b"PK\x03\x04 not a model")
+                .to_string()
+                .contains("not a Rivu model")
+        );
+        assert!(err_of(b"").to_string().contains("empty"));
+    }
+}
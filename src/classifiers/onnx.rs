@@ -0,0 +1,598 @@
+//! ONNX export of trained models.
+//!
+//! [`write_onnx`] turns a Naive Bayes [`ClassifierState`] into an ONNX model
+//! (IR version 7, opset 13) that standard inference runtimes can serve. The
+//! graph takes one `float` input, `input`, of shape `[N, M]`: the `M`
+//! non-class attributes of the header in order, nominal values given by their
+//! index and missing values as `NaN`. It returns `label`, the index of the
+//! predicted class (`int64`, `[N]`), and `probabilities`, the normalized
+//! class votes (`float`, `[N, C]`).
+//!
+//! Scores are summed in log space: the log prior of each class, plus per
+//! attribute the log Gaussian density (numeric) or a lookup of the log
+//! Laplace-smoothed probability (nominal). Missing attributes add nothing,
+//! as in [`NaiveBayes`](crate::classifiers::NaiveBayes). The class labels and
+//! input names are stored in the model metadata under `class_labels` and
+//! `input_names`, as JSON arrays.
+//!
+//! Rivu has no linear classifiers yet, so Naive Bayes is the only learner
+//! exported; others fail with `ErrorKind::Unsupported`.
+
+use crate::classifiers::attribute_class_observers::{
+    AttributeClassObserver, AttributeClassObserverState, GaussianNumericAttributeClassObserver,
+    NominalAttributeClassObserver,
+};
+use crate::classifiers::{ClassifierState, LearnerState, NaiveBayesState};
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::error::RivuError;
+use std::io::{Error, Write};
+
+pub const ONNX_IR_VERSION: u64 = 7;
+pub const ONNX_OPSET_VERSION: u64 = 13;
+
+const FLOAT: u64 = 1;
+const INT64: u64 = 7;
+
+/// Writes the Naive Bayes model in `state` as an ONNX model.
+pub fn write_onnx(w: &mut dyn Write, state: &ClassifierState) -> Result<(), Error> {
+    let LearnerState::NaiveBayes(nb) = &state.learner else {
+        return Err(RivuError::unsupported(format!(
+            "ONNX export is not available for {}",
+            state.learner.name()
+        ))
+        .into());
+    };
+    let header = state
+        .header
+        .as_ref()
+        .ok_or_else(|| RivuError::model("cannot export a model that was never given a header"))?;
+    let classes = header
+        .attributes
+        .get(header.class_index())
+        .and_then(|a| a.as_any().downcast_ref::<NominalAttribute>())
+        .ok_or_else(|| RivuError::model("ONNX export needs a nominal class attribute"))?;
+    w.write_all(&naive_bayes_model(header, classes, nb)?)
+}
+
+/// Protobuf wire encoding, written directly so the export needs no
+/// generated bindings.
+#[derive(Default)]
+struct Message(Vec<u8>);
+
+impl Message {
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.0.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        self.0.push(v as u8);
+    }
+
+    fn int(&mut self, field: u64, v: u64) -> &mut Self {
+        self.varint(field << 3);
+        self.varint(v);
+        self
+    }
+
+    fn bytes(&mut self, field: u64, bytes: &[u8]) -> &mut Self {
+        self.varint((field << 3) | 2);
+        self.varint(bytes.len() as u64);
+        self.0.extend_from_slice(bytes);
+        self
+    }
+
+    fn string(&mut self, field: u64, s: &str) -> &mut Self {
+        self.bytes(field, s.as_bytes())
+    }
+
+    fn message(&mut self, field: u64, m: Message) -> &mut Self {
+        self.bytes(field, &m.0)
+    }
+}
+
+fn tensor(name: &str, dims: &[i64], data_type: u64, raw: Vec<u8>) -> Message {
+    let mut t = Message::default();
+    for &d in dims {
+        t.int(1, d as u64);
+    }
+    t.int(2, data_type).string(8, name).bytes(9, &raw);
+    t
+}
+
+fn float_tensor(name: &str, dims: &[i64], values: &[f64]) -> Message {
+    let raw = values
+        .iter()
+        .flat_map(|&v| (v as f32).to_le_bytes())
+        .collect();
+    tensor(name, dims, FLOAT, raw)
+}
+
+fn int_tensor(name: &str, dims: &[i64], values: &[i64]) -> Message {
+    let raw = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    tensor(name, dims, INT64, raw)
+}
+
+/// Shape dimensions are either fixed or named, like the batch size `N`.
+enum Dim {
+    Fixed(usize),
+    Named(&'static str),
+}
+
+fn value_info(name: &str, elem_type: u64, dims: &[Dim]) -> Message {
+    let mut shape = Message::default();
+    for d in dims {
+        let mut dim = Message::default();
+        match d {
+            Dim::Fixed(v) => dim.int(1, *v as u64),
+            Dim::Named(p) => dim.string(2, p),
+        };
+        shape.message(1, dim);
+    }
+    let mut tensor_type = Message::default();
+    tensor_type.int(1, elem_type).message(2, shape);
+    let mut ty = Message::default();
+    ty.message(1, tensor_type);
+    let mut info = Message::default();
+    info.string(1, name).message(2, ty);
+    info
+}
+
+fn int_attribute(name: &str, v: i64) -> Message {
+    let mut a = Message::default();
+    a.string(1, name).int(3, v as u64).int(20, 2);
+    a
+}
+
+/// The graph under construction: its nodes and constant initializers.
+#[derive(Default)]
+struct Graph {
+    nodes: Vec<Message>,
+    initializers: Vec<Message>,
+}
+
+impl Graph {
+    fn node(&mut self, op: &str, inputs: &[&str], output: &str, attributes: Vec<Message>) {
+        let mut n = Message::default();
+        for i in inputs {
+            n.string(1, i);
+        }
+        n.string(2, output).string(3, output).string(4, op);
+        for a in attributes {
+            n.message(5, a);
+        }
+        self.nodes.push(n);
+    }
+
+    fn constant(&mut self, tensor: Message) {
+        self.initializers.push(tensor);
+    }
+}
+
+/// Adds the `[N, C]` log-likelihood of a numeric attribute held in `column`.
+fn gaussian_term(
+    g: &mut Graph,
+    m: usize,
+    column: &str,
+    obs: &GaussianNumericAttributeClassObserver,
+    num_classes: usize,
+) -> String {
+    let half_log_two_pi = 0.5 * (2.0 * std::f64::consts::PI).ln();
+    let mut mean = vec![0.0; num_classes];
+    let mut scale = vec![0.0; num_classes];
+    let mut offset = vec![f64::NEG_INFINITY; num_classes];
+    for (c, est) in obs
+        .estimators_per_class()
+        .iter()
+        .enumerate()
+        .take(num_classes)
+    {
+        let Some(est) = est.as_ref().filter(|e| e.get_total_weight_observed() > 0.0) else {
+            continue;
+        };
+        mean[c] = est.get_mean();
+        let std_dev = est.get_std_dev();
+        if std_dev > 0.0 {
+            scale[c] = -0.5 / est.get_variance();
+            offset[c] = -std_dev.ln() - half_log_two_pi;
+        } else {
+            // A point mass: density 1 at the mean and 0 elsewhere.
+            scale[c] = -f64::from(f32::MAX);
+            offset[c] = 0.0;
+        }
+    }
+    let c = num_classes as i64;
+    g.constant(float_tensor(&format!("mean_{m}"), &[c], &mean));
+    g.constant(float_tensor(&format!("scale_{m}"), &[c], &scale));
+    g.constant(float_tensor(&format!("offset_{m}"), &[c], &offset));
+
+    g.node(
+        "Sub",
+        &[column, &format!("mean_{m}")],
+        &format!("diff_{m}"),
+        vec![],
+    );
+    g.node(
+        "Mul",
+        &[&format!("diff_{m}"), &format!("diff_{m}")],
+        &format!("square_{m}"),
+        vec![],
+    );
+    g.node(
+        "Mul",
+        &[&format!("square_{m}"), &format!("scale_{m}")],
+        &format!("scaled_{m}"),
+        vec![],
+    );
+    g.node(
+        "Add",
+        &[&format!("scaled_{m}"), &format!("offset_{m}")],
+        &format!("term_{m}"),
+        vec![],
+    );
+    format!("term_{m}")
+}
+
+/// Adds the `[N, C]` log-probability of a nominal attribute held in
+/// `column`, looked up in a `[V + 1, C]` table whose last row covers
+/// values beyond the `V` the header lists.
+fn nominal_term(
+    g: &mut Graph,
+    m: usize,
+    column: &str,
+    obs: &NominalAttributeClassObserver,
+    num_values: usize,
+    num_classes: usize,
+) -> String {
+    let table: Vec<f64> = (0..=num_values)
+        .flat_map(|v| {
+            (0..num_classes).map(move |c| {
+                obs.probability_of_attribute_value_given_class(v as f64, c)
+                    .unwrap_or(0.0)
+                    .ln()
+            })
+        })
+        .collect();
+    let c = num_classes as i64;
+    g.constant(float_tensor(
+        &format!("table_{m}"),
+        &[num_values as i64 + 1, c],
+        &table,
+    ));
+    g.constant(int_tensor(
+        &format!("last_value_{m}"),
+        &[],
+        &[num_values as i64],
+    ));
+
+    g.node(
+        "Where",
+        &[&format!("missing_{m}"), "zero", column],
+        &format!("present_{m}"),
+        vec![],
+    );
+    g.node(
+        "Cast",
+        &[&format!("present_{m}")],
+        &format!("index_{m}"),
+        vec![int_attribute("to", INT64 as i64)],
+    );
+    g.node(
+        "Clip",
+        &[
+            &format!("index_{m}"),
+            "first_value",
+            &format!("last_value_{m}"),
+        ],
+        &format!("clipped_{m}"),
+        vec![],
+    );
+    g.node(
+        "Gather",
+        &[&format!("table_{m}"), &format!("clipped_{m}")],
+        &format!("lookup_{m}"),
+        vec![int_attribute("axis", 0)],
+    );
+    g.node(
+        "Reshape",
+        &[&format!("lookup_{m}"), "score_shape"],
+        &format!("term_{m}"),
+        vec![],
+    );
+    format!("term_{m}")
+}
+
+fn naive_bayes_model(
+    header: &InstanceHeader,
+    classes: &NominalAttribute,
+    state: &NaiveBayesState,
+) -> Result<Vec<u8>, Error> {
+    let num_classes = classes.values.len();
+    let class_index = header.class_index();
+    let num_inputs = header.attributes.len().saturating_sub(1);
+    let total: f64 = state.observed_class_distribution.iter().sum();
+    if total <= 0.0 {
+        return Err(RivuError::model("cannot export a model that has not been trained").into());
+    }
+
+    let mut g = Graph::default();
+    let priors: Vec<f64> = (0..num_classes)
+        .map(|c| {
+            let w = state.observed_class_distribution.get(c).copied();
+            (w.unwrap_or(0.0) / total).ln()
+        })
+        .collect();
+    g.constant(float_tensor("log_prior", &[num_classes as i64], &priors));
+    g.constant(float_tensor("zero", &[], &[0.0]));
+    g.constant(int_tensor("first_value", &[], &[0]));
+    g.constant(int_tensor("score_shape", &[2], &[-1, num_classes as i64]));
+
+    let mut terms = vec!["log_prior".to_string()];
+    for (m, observer) in state.attribute_observers.iter().enumerate() {
+        let index = if m < class_index { m } else { m + 1 };
+        let column = format!("column_{m}");
+        let Some(
+            observer @ (AttributeClassObserverState::GaussianNumeric(_)
+            | AttributeClassObserverState::Nominal(_)),
+        ) = observer
+        else {
+            continue;
+        };
+        g.constant(int_tensor(&format!("position_{m}"), &[1], &[m as i64]));
+        g.node(
+            "Gather",
+            &["input", &format!("position_{m}")],
+            &column,
+            vec![int_attribute("axis", 1)],
+        );
+        g.node("IsNaN", &[&column], &format!("missing_{m}"), vec![]);
+        let term = match observer {
+            AttributeClassObserverState::Nominal(obs) => {
+                let num_values = header
+                    .attributes
+                    .get(index)
+                    .and_then(|a| a.as_any().downcast_ref::<NominalAttribute>())
+                    .map_or(0, |n| n.values.len());
+                nominal_term(&mut g, m, &column, obs, num_values, num_classes)
+            }
+            AttributeClassObserverState::GaussianNumeric(obs) => {
+                gaussian_term(&mut g, m, &column, obs, num_classes)
+            }
+            AttributeClassObserverState::Null(_) => continue,
+        };
+        g.node(
+            "Where",
+            &[&format!("missing_{m}"), "zero", &term],
+            &format!("masked_{m}"),
+            vec![],
+        );
+        terms.push(format!("masked_{m}"));
+    }
+    if terms.len() == 1 {
+        return Err(RivuError::model("the model has no trained attributes to export").into());
+    }
+
+    let term_refs: Vec<&str> = terms.iter().map(String::as_str).collect();
+    g.node("Sum", &term_refs, "log_scores", vec![]);
+    g.node(
+        "Softmax",
+        &["log_scores"],
+        "probabilities",
+        vec![int_attribute("axis", 1)],
+    );
+    g.node(
+        "ArgMax",
+        &["log_scores"],
+        "label",
+        vec![int_attribute("axis", 1), int_attribute("keepdims", 0)],
+    );
+
+    let mut graph = Message::default();
+    for n in g.nodes {
+        graph.message(1, n);
+    }
+    graph.string(2, "naive-bayes");
+    for t in g.initializers {
+        graph.message(5, t);
+    }
+    graph.message(
+        11,
+        value_info("input", FLOAT, &[Dim::Named("N"), Dim::Fixed(num_inputs)]),
+    );
+    graph.message(12, value_info("label", INT64, &[Dim::Named("N")]));
+    graph.message(
+        12,
+        value_info(
+            "probabilities",
+            FLOAT,
+            &[Dim::Named("N"), Dim::Fixed(num_classes)],
+        ),
+    );
+
+    let input_names: Vec<String> = (0..header.attributes.len())
+        .filter(|&i| i != class_index)
+        .map(|i| header.attributes[i].name())
+        .collect();
+    let mut opset = Message::default();
+    opset.string(1, "").int(2, ONNX_OPSET_VERSION);
+    let mut model = Message::default();
+    model
+        .int(1, ONNX_IR_VERSION)
+        .string(2, "Rivu")
+        .string(3, env!("CARGO_PKG_VERSION"))
+        .message(7, graph)
+        .message(8, opset);
+    for (key, value) in [
+        ("class_labels", serde_json::to_string(&classes.values)),
+        ("input_names", serde_json::to_string(&input_names)),
+    ] {
+        let mut entry = Message::default();
+        entry
+            .string(1, key)
+            .string(2, &value.map_err(Error::other)?);
+        model.message(14, entry);
+    }
+    Ok(model.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::{Classifier, NaiveBayes, TargetMean};
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use std::io::ErrorKind;
+    use std::sync::Arc;
+
+    /// Fields of one protobuf message: varints and length-delimited bytes.
+    fn fields(mut bytes: &[u8]) -> Vec<(u64, Result<u64, &[u8]>)> {
+        fn varint(bytes: &mut &[u8]) -> u64 {
+            let mut v = 0;
+            for shift in (0..).step_by(7) {
+                let b = bytes[0];
+                *bytes = &bytes[1..];
+                v |= u64::from(b & 0x7f) << shift;
+                if b < 0x80 {
+                    break;
+                }
+            }
+            v
+        }
+        let mut out = Vec::new();
+        while !bytes.is_empty() {
+            let key = varint(&mut bytes);
+            if key & 7 == 0 {
+                out.push((key >> 3, Ok(varint(&mut bytes))));
+            } else {
+                let len = varint(&mut bytes) as usize;
+                out.push((key >> 3, Err(&bytes[..len])));
+                bytes = &bytes[len..];
+            }
+        }
+        out
+    }
+
+    fn sub<'a>(fields: &[(u64, Result<u64, &'a [u8]>)], field: u64) -> Vec<&'a [u8]> {
+        fields
+            .iter()
+            .filter(|(f, _)| *f == field)
+            .filter_map(|(_, v)| v.err())
+            .collect()
+    }
+
+    fn text(bytes: &[u8]) -> &str {
+        std::str::from_utf8(bytes).unwrap()
+    }
+
+    fn trained() -> NaiveBayes {
+        let colour = NominalAttribute::ordinal("colour".into(), vec!["red".into(), "blue".into()]);
+        let class = NominalAttribute::ordinal("class".into(), vec!["yes".into(), "no".into()]);
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(colour),
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(class),
+        ];
+        let header = Arc::new(InstanceHeader::new("r".into(), attrs, 2));
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(Arc::clone(&header));
+        for row in [
+            [0.0, 1.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [1.0, 3.0, 0.0],
+            [1.0, 5.0, 1.0],
+        ] {
+            nb.train_on_instance(&DenseInstance::new(Arc::clone(&header), row.to_vec(), 1.0));
+        }
+        nb
+    }
+
+    #[test]
+    fn writes_a_graph_over_the_non_class_attributes() {
+        let nb = trained();
+        let mut out = Vec::new();
+        write_onnx(&mut out, &nb.snapshot_state().unwrap()).unwrap();
+
+        let model = fields(&out);
+        assert_eq!(model[0], (1, Ok(ONNX_IR_VERSION)));
+        let graph = fields(sub(&model, 7)[0]);
+        let ops: Vec<&str> = sub(&graph, 1)
+            .into_iter()
+            .map(|n| text(sub(&fields(n), 4)[0]))
+            .collect();
+        assert_eq!(ops[..2], ["Gather", "IsNaN"]);
+        assert_eq!(ops.iter().filter(|&&op| op == "IsNaN").count(), 2);
+        assert_eq!(ops[ops.len() - 3..], ["Sum", "Softmax", "ArgMax"]);
+        let outputs: Vec<&str> = sub(&graph, 12)
+            .into_iter()
+            .map(|o| text(sub(&fields(o), 1)[0]))
+            .collect();
+        assert_eq!(outputs, ["label", "probabilities"]);
+
+        let metadata: Vec<(&str, &str)> = sub(&model, 14)
+            .into_iter()
+            .map(|e| {
+                let e = fields(e);
+                (text(sub(&e, 1)[0]), text(sub(&e, 2)[0]))
+            })
+            .collect();
+        assert_eq!(
+            metadata,
+            [
+                ("class_labels", r#"["yes","no"]"#),
+                ("input_names", r#"["colour","x"]"#)
+            ]
+        );
+    }
+
+    #[test]
+    fn initializers_hold_the_log_probabilities() {
+        let nb = trained();
+        let mut out = Vec::new();
+        write_onnx(&mut out, &nb.snapshot_state().unwrap()).unwrap();
+        let graph = fields(sub(&fields(&out), 7)[0]);
+        let floats = |name: &str| -> Vec<f32> {
+            let t = sub(&graph, 5)
+                .into_iter()
+                .map(fields)
+                .find(|t| text(sub(t, 8)[0]) == name)
+                .unwrap();
+            sub(&t, 9)[0]
+                .chunks(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                .collect()
+        };
+
+        assert_eq!(
+            floats("log_prior"),
+            [(0.75f64).ln() as f32, (0.25f64).ln() as f32]
+        );
+        // Rows red, blue, and unseen values; P(red | yes) = (2 + 1) / (3 + 2).
+        let table = floats("table_0");
+        assert_eq!(table.len(), 6);
+        assert_eq!(table[0], (0.6f64).ln() as f32);
+        assert_eq!(table[4], (0.2f64).ln() as f32);
+        assert_eq!(floats("mean_1"), [2.0, 5.0]);
+    }
+
+    #[test]
+    fn rejects_other_learners_and_untrained_models() {
+        let err = write_onnx(
+            &mut std::io::sink(),
+            &TargetMean::new().snapshot_state().unwrap(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(
+            trained()
+                .snapshot_state()
+                .unwrap()
+                .header
+                .map(Arc::new)
+                .unwrap(),
+        );
+        let err = write_onnx(&mut std::io::sink(), &nb.snapshot_state().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("not been trained"));
+    }
+}
@@ -0,0 +1,586 @@
+//! PMML export of trained models.
+//!
+//! [`write_pmml`] turns a [`ClassifierState`] into a PMML 4.4 document that
+//! scoring engines such as JPMML can evaluate:
+//!
+//! - Naive Bayes becomes a `NaiveBayesModel`. Nominal attributes are written
+//!   as `PairCounts` scaled so that `count / class count` is the observer's
+//!   Laplace-smoothed probability, and numeric attributes as one
+//!   `GaussianDistribution` per class.
+//! - A Hoeffding tree becomes a `TreeModel` with one `Node` per tree node.
+//!   Instances that miss the tested attribute, or reach a branch that has no
+//!   child yet, stop at the split node, as they do in Rivu. Leaves score
+//!   their majority class, so trees with Naive Bayes leaves export the
+//!   majority-class tree.
+//!
+//! The model needs its header, which holds the field names and values.
+
+use crate::classifiers::attribute_class_observers::{
+    AttributeClassObserver, AttributeClassObserverState,
+};
+use crate::classifiers::hoeffding_tree::{HoeffdingTreeState, NodeState, SplitTestState};
+use crate::classifiers::{ClassifierState, LearnerState, NaiveBayesState};
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::error::RivuError;
+use std::io::{Error, Write};
+
+pub const PMML_VERSION: &str = "4.4";
+
+/// Writes `state` as a PMML document.
+///
+/// Fails with `ErrorKind::Unsupported` for learners PMML export does not
+/// cover, and with a model error if the state has no header or a numeric
+/// class.
+pub fn write_pmml(w: &mut dyn Write, state: &ClassifierState) -> Result<(), Error> {
+    let header = state
+        .header
+        .as_ref()
+        .ok_or_else(|| RivuError::model("cannot export a model that was never given a header"))?;
+    let classes = nominal(header, header.class_index())
+        .ok_or_else(|| RivuError::model("PMML export needs a nominal class attribute"))?;
+
+    let mut doc = Document::default();
+    doc.open(&format!(
+        r#"PMML xmlns="http://www.dmg.org/PMML-4_4" version="{PMML_VERSION}""#
+    ));
+    doc.open("Header");
+    doc.empty(&format!(
+        r#"Application name="Rivu" version="{}""#,
+        env!("CARGO_PKG_VERSION")
+    ));
+    doc.close("Header");
+    data_dictionary(&mut doc, header);
+
+    match &state.learner {
+        LearnerState::NaiveBayes(s) => naive_bayes_model(&mut doc, header, classes, s),
+        LearnerState::HoeffdingTree(s) => tree_model(&mut doc, header, classes, s)?,
+        other => {
+            return Err(RivuError::unsupported(format!(
+                "PMML export is not available for {}",
+                other.name()
+            ))
+            .into());
+        }
+    }
+
+    doc.close("PMML");
+    w.write_all(doc.out.as_bytes())
+}
+
+/// Indented XML text, one element per line.
+#[derive(Default)]
+struct Document {
+    out: String,
+    depth: usize,
+}
+
+impl Document {
+    fn line(&mut self, text: &str) {
+        self.out.push_str(&"  ".repeat(self.depth));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn open(&mut self, tag: &str) {
+        self.line(&format!("<{tag}>"));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.line(&format!("</{name}>"));
+    }
+
+    fn empty(&mut self, tag: &str) {
+        self.line(&format!("<{tag}/>"));
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn nominal(header: &InstanceHeader, index: usize) -> Option<&NominalAttribute> {
+    header
+        .attributes
+        .get(index)?
+        .as_any()
+        .downcast_ref::<NominalAttribute>()
+}
+
+fn field_name(header: &InstanceHeader, index: usize) -> Result<String, Error> {
+    header
+        .attributes
+        .get(index)
+        .map(|a| escape(&a.name()))
+        .ok_or_else(|| {
+            RivuError::model(format!("model refers to missing attribute {index}")).into()
+        })
+}
+
+fn data_dictionary(doc: &mut Document, header: &InstanceHeader) {
+    doc.open(&format!(
+        r#"DataDictionary numberOfFields="{}""#,
+        header.attributes.len()
+    ));
+    for (i, attr) in header.attributes.iter().enumerate() {
+        let name = escape(&attr.name());
+        match nominal(header, i) {
+            Some(n) => {
+                let optype = if n.is_ordinal() {
+                    "ordinal"
+                } else {
+                    "categorical"
+                };
+                doc.open(&format!(
+                    r#"DataField name="{name}" optype="{optype}" dataType="string""#
+                ));
+                for v in &n.values {
+                    doc.empty(&format!(r#"Value value="{}""#, escape(v)));
+                }
+                doc.close("DataField");
+            }
+            None => doc.empty(&format!(
+                r#"DataField name="{name}" optype="continuous" dataType="double""#
+            )),
+        }
+    }
+    doc.close("DataDictionary");
+}
+
+fn mining_schema(doc: &mut Document, header: &InstanceHeader) {
+    doc.open("MiningSchema");
+    for (i, attr) in header.attributes.iter().enumerate() {
+        let name = escape(&attr.name());
+        if i == header.class_index() {
+            doc.empty(&format!(r#"MiningField name="{name}" usageType="target""#));
+        } else {
+            doc.empty(&format!(r#"MiningField name="{name}""#));
+        }
+    }
+    doc.close("MiningSchema");
+}
+
+fn target_value_counts(doc: &mut Document, classes: &NominalAttribute, counts: &[f64]) {
+    doc.open("TargetValueCounts");
+    for (c, label) in classes.values.iter().enumerate() {
+        doc.empty(&format!(
+            r#"TargetValueCount value="{}" count="{}""#,
+            escape(label),
+            counts.get(c).copied().unwrap_or(0.0)
+        ));
+    }
+    doc.close("TargetValueCounts");
+}
+
+fn naive_bayes_model(
+    doc: &mut Document,
+    header: &InstanceHeader,
+    classes: &NominalAttribute,
+    state: &NaiveBayesState,
+) {
+    let class_counts = &state.observed_class_distribution;
+    let class_weight = |c: usize| class_counts.get(c).copied().unwrap_or(0.0);
+    let class_index = header.class_index();
+
+    doc.open(
+        r#"NaiveBayesModel modelName="naive-bayes" functionName="classification" threshold="0""#,
+    );
+    mining_schema(doc, header);
+    doc.open("BayesInputs");
+    for (m, observer) in state.attribute_observers.iter().enumerate() {
+        let index = if m < class_index { m } else { m + 1 };
+        let Some(name) = header.attributes.get(index).map(|a| escape(&a.name())) else {
+            continue;
+        };
+        match observer {
+            Some(AttributeClassObserverState::Nominal(obs)) => {
+                let Some(values) = nominal(header, index) else {
+                    continue;
+                };
+                doc.open(&format!(r#"BayesInput fieldName="{name}""#));
+                for (v, value) in values.values.iter().enumerate() {
+                    doc.open(&format!(r#"PairCounts value="{}""#, escape(value)));
+                    let counts: Vec<f64> = (0..classes.values.len())
+                        .map(|c| {
+                            let p = obs
+                                .probability_of_attribute_value_given_class(v as f64, c)
+                                .unwrap_or(0.0);
+                            p * class_weight(c)
+                        })
+                        .collect();
+                    target_value_counts(doc, classes, &counts);
+                    doc.close("PairCounts");
+                }
+                doc.close("BayesInput");
+            }
+            Some(AttributeClassObserverState::GaussianNumeric(obs)) => {
+                doc.open(&format!(r#"BayesInput fieldName="{name}""#));
+                doc.open("TargetValueStats");
+                for (c, estimator) in obs.estimators_per_class().iter().enumerate() {
+                    let (Some(est), Some(label)) = (estimator, classes.values.get(c)) else {
+                        continue;
+                    };
+                    if est.get_total_weight_observed() <= 0.0 {
+                        continue;
+                    }
+                    doc.open(&format!(r#"TargetValueStat value="{}""#, escape(label)));
+                    doc.empty(&format!(
+                        r#"GaussianDistribution mean="{}" variance="{}""#,
+                        est.get_mean(),
+                        est.get_variance()
+                    ));
+                    doc.close("TargetValueStat");
+                }
+                doc.close("TargetValueStats");
+                doc.close("BayesInput");
+            }
+            Some(AttributeClassObserverState::Null(_)) | None => {}
+        }
+    }
+    doc.close("BayesInputs");
+    doc.open(&format!(
+        r#"BayesOutput fieldName="{}""#,
+        escape(&classes.name)
+    ));
+    target_value_counts(doc, classes, class_counts);
+    doc.close("BayesOutput");
+    doc.close("NaiveBayesModel");
+}
+
+/// The test that sends an instance down `branch` of a split.
+enum Predicate {
+    Always,
+    Never,
+    Simple {
+        field: String,
+        operator: &'static str,
+        value: String,
+    },
+}
+
+impl Predicate {
+    fn write(&self, doc: &mut Document) {
+        match self {
+            Predicate::Always => doc.empty("True"),
+            Predicate::Never => doc.empty("False"),
+            Predicate::Simple {
+                field,
+                operator,
+                value,
+            } => doc.empty(&format!(
+                r#"SimplePredicate field="{field}" operator="{operator}" value="{value}""#
+            )),
+        }
+    }
+}
+
+fn branch_predicate(
+    header: &InstanceHeader,
+    test: &SplitTestState,
+    branch: usize,
+) -> Result<Predicate, Error> {
+    let simple = |index: usize, operator, value: String| -> Result<Predicate, Error> {
+        Ok(Predicate::Simple {
+            field: field_name(header, index)?,
+            operator,
+            value,
+        })
+    };
+    let value_of = |index: usize, v: usize| -> Result<String, Error> {
+        nominal(header, index)
+            .and_then(|n| n.values.get(v))
+            .map(|v| escape(v))
+            .ok_or_else(|| {
+                RivuError::model(format!("attribute {index} has no nominal value {v}")).into()
+            })
+    };
+    match *test {
+        SplitTestState::NominalMultiway { attribute_index } => {
+            simple(attribute_index, "equal", value_of(attribute_index, branch)?)
+        }
+        SplitTestState::NominalBinary {
+            attribute_index,
+            attribute_value,
+        } => {
+            // Binary nominal tests index attributes as the model does,
+            // skipping the class.
+            let index = if attribute_index < header.class_index() {
+                attribute_index
+            } else {
+                attribute_index + 1
+            };
+            let operator = if branch == 0 { "equal" } else { "notEqual" };
+            simple(index, operator, value_of(index, attribute_value)?)
+        }
+        SplitTestState::NumericBinary {
+            attribute_index,
+            attribute_value,
+            equals_passes_test,
+        } => match nominal(header, attribute_index) {
+            // Ordinal attributes are split on their value index; compare
+            // against the last value that still goes left instead.
+            Some(n) => {
+                let last_left = if equals_passes_test {
+                    attribute_value.floor()
+                } else {
+                    attribute_value.ceil() - 1.0
+                };
+                if last_left < 0.0 {
+                    return Ok(if branch == 0 {
+                        Predicate::Never
+                    } else {
+                        Predicate::Always
+                    });
+                }
+                if last_left as usize + 1 >= n.values.len() {
+                    return Ok(if branch == 0 {
+                        Predicate::Always
+                    } else {
+                        Predicate::Never
+                    });
+                }
+                let operator = if branch == 0 {
+                    "lessOrEqual"
+                } else {
+                    "greaterThan"
+                };
+                simple(
+                    attribute_index,
+                    operator,
+                    value_of(attribute_index, last_left as usize)?,
+                )
+            }
+            None => {
+                let operator = match (branch, equals_passes_test) {
+                    (0, true) => "lessOrEqual",
+                    (0, false) => "lessThan",
+                    (_, true) => "greaterThan",
+                    (_, false) => "greaterOrEqual",
+                };
+                simple(attribute_index, operator, attribute_value.to_string())
+            }
+        },
+    }
+}
+
+fn class_distribution(node: &NodeState) -> &[f64] {
+    match node {
+        NodeState::Split {
+            observed_class_distribution,
+            ..
+        }
+        | NodeState::InactiveLearning {
+            observed_class_distribution,
+        } => observed_class_distribution,
+        NodeState::ActiveLearning(n) | NodeState::LearningNb(n) => &n.observed_class_distribution,
+        NodeState::LearningNbAdaptive { node, .. } => &node.observed_class_distribution,
+    }
+}
+
+fn tree_node(
+    doc: &mut Document,
+    header: &InstanceHeader,
+    classes: &NominalAttribute,
+    nodes: &[NodeState],
+    index: usize,
+    predicate: Predicate,
+) -> Result<(), Error> {
+    let node = nodes
+        .get(index)
+        .ok_or_else(|| RivuError::model(format!("saved tree has no node {index}")))?;
+    let dist = class_distribution(node);
+    let majority = dist
+        .iter()
+        .enumerate()
+        .fold(0, |best, (c, &w)| if w > dist[best] { c } else { best });
+    let score = classes
+        .values
+        .get(majority)
+        .map_or(String::new(), |v| escape(v));
+
+    doc.open(&format!(
+        r#"Node id="{index}" score="{score}" recordCount="{}""#,
+        dist.iter().sum::<f64>()
+    ));
+    predicate.write(doc);
+    for (c, label) in classes.values.iter().enumerate() {
+        doc.empty(&format!(
+            r#"ScoreDistribution value="{}" recordCount="{}""#,
+            escape(label),
+            dist.get(c).copied().unwrap_or(0.0)
+        ));
+    }
+    if let NodeState::Split {
+        split_test,
+        children,
+        ..
+    } = node
+    {
+        for (branch, child) in children.iter().enumerate() {
+            let Some(child) = *child else { continue };
+            let predicate = branch_predicate(header, split_test, branch)?;
+            tree_node(doc, header, classes, nodes, child, predicate)?;
+        }
+    }
+    doc.close("Node");
+    Ok(())
+}
+
+fn tree_model(
+    doc: &mut Document,
+    header: &InstanceHeader,
+    classes: &NominalAttribute,
+    state: &HoeffdingTreeState,
+) -> Result<(), Error> {
+    doc.open(
+        r#"TreeModel modelName="hoeffding-tree" functionName="classification" splitCharacteristic="multiSplit" missingValueStrategy="lastPrediction" noTrueChildStrategy="returnLastPrediction""#,
+    );
+    mining_schema(doc, header);
+    if state.nodes.is_empty() {
+        doc.empty(r#"Node score="" recordCount="0""#);
+    } else {
+        tree_node(doc, header, classes, &state.nodes, 0, Predicate::Always)?;
+    }
+    doc.close("TreeModel");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::hoeffding_tree::LeafPredictionOption;
+    use crate::classifiers::{Classifier, HoeffdingTree, NaiveBayes, TargetMean};
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use crate::streams::Stream;
+    use crate::streams::generators::{AgrawalFunction, AgrawalGenerator};
+    use std::io::ErrorKind;
+    use std::sync::Arc;
+
+    fn pmml_of(learner: &dyn Classifier) -> String {
+        let mut out = Vec::new();
+        write_pmml(&mut out, &learner.snapshot_state().unwrap()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn count_count(pmml: &str, line: &str) -> f64 {
+        let line = pmml.lines().find(|l| l.contains(line)).unwrap();
+        let start = line.find("count=\"").unwrap() + 7;
+        line[start..].split('"').next().unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn naive_bayes_counts_reproduce_the_smoothed_probabilities() {
+        let colour = NominalAttribute::with_values(
+            "colour".into(),
+            vec!["red".into(), "blue".into()],
+            [("red".into(), 0), ("blue".into(), 1)].into(),
+        );
+        let class = NominalAttribute::with_values(
+            "class".into(),
+            vec!["yes".into(), "no".into()],
+            [("yes".into(), 0), ("no".into(), 1)].into(),
+        );
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(colour),
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(class),
+        ];
+        let header = Arc::new(InstanceHeader::new("r".into(), attrs, 2));
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(Arc::clone(&header));
+        for row in [
+            [0.0, 1.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [1.0, 3.0, 0.0],
+            [1.0, 5.0, 1.0],
+        ] {
+            nb.train_on_instance(&DenseInstance::new(Arc::clone(&header), row.to_vec(), 1.0));
+        }
+
+        let pmml = pmml_of(&nb);
+        assert!(pmml.contains(r#"<DataField name="colour" optype="categorical""#));
+        assert!(pmml.contains(r#"<MiningField name="class" usageType="target"/>"#));
+        assert!(pmml.contains(r#"<GaussianDistribution mean="2" variance="1"/>"#));
+
+        // P(red | yes) = (2 + 1) / (3 + 2), written against 3 "yes" instances.
+        let section = &pmml[pmml.find(r#"PairCounts value="red""#).unwrap()..];
+        let red_yes = count_count(section, r#"value="yes""#);
+        let yes = count_count(&pmml[pmml.find("BayesOutput").unwrap()..], r#"value="yes""#);
+        assert_eq!(yes, 3.0);
+        assert!((red_yes / yes - 3.0 / 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn trees_export_one_node_per_tree_node() {
+        let mut stream = AgrawalGenerator::new(AgrawalFunction::F2, true, 0.05, None, 7).unwrap();
+        let mut tree =
+            HoeffdingTree::new_with_only_leaf_prediction(LeafPredictionOption::MajorityClass);
+        tree.set_model_context(Arc::new(stream.header().clone()));
+        for _ in 0..3000 {
+            let inst = stream.next_instance().unwrap();
+            tree.train_on_instance(inst.as_ref());
+        }
+        let state = tree.state().unwrap();
+        assert!(state.decision_node_count > 0);
+
+        let pmml = pmml_of(&tree);
+        assert_eq!(pmml.matches("<Node ").count(), state.nodes.len());
+        assert_eq!(pmml.matches("<True/>").count(), 1);
+        assert!(pmml.contains(r#"missingValueStrategy="lastPrediction""#));
+        assert!(pmml.contains("<SimplePredicate"));
+        assert!(pmml.trim_end().ends_with("</PMML>"));
+    }
+
+    #[test]
+    fn ordinal_thresholds_compare_against_a_value() {
+        let levels = vec!["low".into(), "mid".into(), "high".into()];
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NominalAttribute::ordinal("size".into(), levels)),
+            Arc::new(NominalAttribute::ordinal("c".into(), vec!["a".into()])),
+        ];
+        let header = InstanceHeader::new("r".into(), attrs, 1);
+        let test = |value, equals_passes_test| SplitTestState::NumericBinary {
+            attribute_index: 0,
+            attribute_value: value,
+            equals_passes_test,
+        };
+        let value = |p: Predicate| match p {
+            Predicate::Simple {
+                operator, value, ..
+            } => format!("{operator} {value}"),
+            Predicate::Always => "true".into(),
+            Predicate::Never => "false".into(),
+        };
+
+        let left = |t| value(branch_predicate(&header, &t, 0).unwrap());
+        assert_eq!(left(test(1.5, true)), "lessOrEqual mid");
+        assert_eq!(left(test(1.0, false)), "lessOrEqual low");
+        assert_eq!(left(test(0.0, false)), "false");
+        assert_eq!(left(test(2.0, true)), "true");
+        assert_eq!(
+            value(branch_predicate(&header, &test(1.5, true), 1).unwrap()),
+            "greaterThan mid"
+        );
+    }
+
+    #[test]
+    fn rejects_models_without_a_pmml_form() {
+        let mut state = TargetMean::new().snapshot_state().unwrap();
+        let err = write_pmml(&mut std::io::sink(), &state).unwrap_err();
+        assert!(err.to_string().contains("header"));
+
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NominalAttribute::ordinal("c".into(), vec!["a".into()])),
+        ];
+        state.header = Some(InstanceHeader::new("r".into(), attrs, 1));
+        let err = write_pmml(&mut std::io::sink(), &state).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+    }
+}
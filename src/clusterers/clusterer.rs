@@ -0,0 +1,22 @@
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use std::sync::Arc;
+
+/// Online clustering algorithm.
+///
+/// Clusterers work on the feature vector of each instance, i.e. every
+/// attribute except the class, as extracted by
+/// [`ClusteringEvaluator::features_of`](crate::evaluation::ClusteringEvaluator::features_of).
+/// The class value, if any, is never used for training.
+pub trait Clusterer {
+    fn set_model_context(&mut self, header: Arc<InstanceHeader>);
+    fn train_on_instance(&mut self, instance: &dyn Instance);
+
+    /// Centers of the summary the clusterer maintains online (e.g.
+    /// micro-clusters); cheap to call.
+    fn micro_cluster_centers(&self) -> Vec<Vec<f64>>;
+
+    /// Final clustering derived from the online summary; may run an offline
+    /// step such as k-means, so call it on an evaluation cadence.
+    fn macro_cluster_centers(&mut self) -> Vec<Vec<f64>>;
+}
@@ -0,0 +1,242 @@
+use crate::clusterers::kmeans::{squared_distance, weighted_kmeans};
+use crate::clusterers::{Clusterer, MicroCluster};
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::error::RivuError;
+use crate::evaluation::ClusteringEvaluator;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Number of most recent points the relevance stamp of a micro-cluster is
+/// based on.
+const RELEVANCE_POINTS: f64 = 100.0;
+
+/// CluStream (Aggarwal et al., 2003).
+///
+/// The online phase keeps at most `max_micro_clusters` [`MicroCluster`]s. A
+/// point is absorbed by its nearest micro-cluster when it falls within
+/// `kernel_radius_factor` times that cluster's radius (for singletons, the
+/// distance to the closest other micro-cluster); otherwise it opens a new
+/// micro-cluster, making room by deleting one whose relevance stamp is
+/// older than `horizon` points or, failing that, by merging the two closest
+/// ones. The offline phase runs weighted k-means++ over the micro-cluster
+/// centers to produce `k` macro-clusters.
+pub struct CluStream {
+    max_micro_clusters: usize,
+    k: usize,
+    kernel_radius_factor: f64,
+    horizon: u64,
+    seed: u64,
+
+    header: Option<Arc<InstanceHeader>>,
+    micro_clusters: Vec<MicroCluster>,
+    timestamp: u64,
+    rng: StdRng,
+}
+
+impl CluStream {
+    pub fn new(
+        max_micro_clusters: usize,
+        k: usize,
+        kernel_radius_factor: f64,
+        horizon: u64,
+        seed: u64,
+    ) -> Result<Self, Error> {
+        if k == 0 || max_micro_clusters < k {
+            return Err(RivuError::config("need 1 <= k <= max_micro_clusters").into());
+        }
+        if kernel_radius_factor.is_nan() || kernel_radius_factor <= 0.0 {
+            return Err(RivuError::config("kernel_radius_factor must be > 0").into());
+        }
+        if horizon == 0 {
+            return Err(RivuError::config("horizon must be > 0").into());
+        }
+        Ok(Self {
+            max_micro_clusters,
+            k,
+            kernel_radius_factor,
+            horizon,
+            seed,
+            header: None,
+            micro_clusters: Vec::with_capacity(max_micro_clusters),
+            timestamp: 0,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    pub fn micro_clusters(&self) -> &[MicroCluster] {
+        &self.micro_clusters
+    }
+
+    /// Clears the model, keeping the configuration and header.
+    pub fn reset(&mut self) {
+        self.micro_clusters.clear();
+        self.timestamp = 0;
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+
+    /// Maximum distance at which micro-cluster `i` absorbs a point.
+    fn boundary(&self, i: usize, centers: &[Vec<f64>]) -> f64 {
+        let mc = &self.micro_clusters[i];
+        if mc.weight > 1.0 {
+            return self.kernel_radius_factor * mc.radius();
+        }
+        let nearest_other = centers
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, c)| squared_distance(&centers[i], c).sqrt())
+            .fold(f64::INFINITY, f64::min);
+        if nearest_other.is_finite() {
+            nearest_other
+        } else {
+            0.0
+        }
+    }
+
+    /// Frees one slot, deleting a stale micro-cluster or merging the two
+    /// closest ones.
+    fn make_room(&mut self) {
+        let threshold = self.timestamp as f64 - self.horizon as f64;
+        let stale = self
+            .micro_clusters
+            .iter()
+            .enumerate()
+            .map(|(i, mc)| (i, mc.relevance_stamp(RELEVANCE_POINTS)))
+            .filter(|(_, stamp)| *stamp < threshold)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, _)) = stale {
+            self.micro_clusters.swap_remove(i);
+            return;
+        }
+
+        let centers: Vec<Vec<f64>> = self.micro_clusters.iter().map(|m| m.center()).collect();
+        let mut closest = (0, 1, f64::INFINITY);
+        for i in 0..centers.len() {
+            for j in i + 1..centers.len() {
+                let d = squared_distance(&centers[i], &centers[j]);
+                if d < closest.2 {
+                    closest = (i, j, d);
+                }
+            }
+        }
+        let (i, j, _) = closest;
+        let absorbed = self.micro_clusters.swap_remove(j);
+        self.micro_clusters[i].merge(&absorbed);
+    }
+}
+
+impl Clusterer for CluStream {
+    fn set_model_context(&mut self, header: Arc<InstanceHeader>) {
+        self.header = Some(header);
+    }
+
+    fn train_on_instance(&mut self, instance: &dyn Instance) {
+        let weight = instance.weight();
+        let point = ClusteringEvaluator::features_of(instance);
+        if weight <= 0.0 || point.iter().any(|v| !v.is_finite()) {
+            return;
+        }
+        self.timestamp += 1;
+        let now = self.timestamp as f64;
+
+        let centers: Vec<Vec<f64>> = self.micro_clusters.iter().map(|m| m.center()).collect();
+        if let Some((i, d2)) = ClusteringEvaluator::nearest_center(&point, &centers)
+            && d2.sqrt() <= self.boundary(i, &centers)
+        {
+            self.micro_clusters[i].insert(&point, weight, now);
+            return;
+        }
+
+        if self.micro_clusters.len() >= self.max_micro_clusters {
+            self.make_room();
+        }
+        self.micro_clusters
+            .push(MicroCluster::from_point(&point, weight, now));
+    }
+
+    fn micro_cluster_centers(&self) -> Vec<Vec<f64>> {
+        self.micro_clusters.iter().map(|m| m.center()).collect()
+    }
+
+    fn macro_cluster_centers(&mut self) -> Vec<Vec<f64>> {
+        let points = self.micro_cluster_centers();
+        let weights: Vec<f64> = self.micro_clusters.iter().map(|m| m.weight).collect();
+        weighted_kmeans(&points, &weights, self.k, &mut self.rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use rand::Rng;
+
+    fn header() -> Arc<InstanceHeader> {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())) as AttributeRef,
+            Arc::new(NumericAttribute::new("y".into())) as AttributeRef,
+            Arc::new(NumericAttribute::new("class".into())) as AttributeRef,
+        ];
+        Arc::new(InstanceHeader::new("points".into(), attrs, 2))
+    }
+
+    fn point(h: &Arc<InstanceHeader>, x: f64, y: f64) -> DenseInstance {
+        DenseInstance::new(Arc::clone(h), vec![x, y, f64::NAN], 1.0)
+    }
+
+    fn blobs(clusterer: &mut CluStream, n: usize) {
+        let h = header();
+        let mut rng = StdRng::seed_from_u64(3);
+        for i in 0..n {
+            let (cx, cy) = if i % 2 == 0 { (0.0, 0.0) } else { (10.0, 10.0) };
+            let x = cx + rng.random::<f64>() - 0.5;
+            let y = cy + rng.random::<f64>() - 0.5;
+            clusterer.train_on_instance(&point(&h, x, y));
+        }
+    }
+
+    #[test]
+    fn ctor_guards() {
+        assert!(CluStream::new(10, 0, 2.0, 100, 1).is_err());
+        assert!(CluStream::new(2, 3, 2.0, 100, 1).is_err());
+        assert!(CluStream::new(10, 2, 0.0, 100, 1).is_err());
+        assert!(CluStream::new(10, 2, 2.0, 0, 1).is_err());
+    }
+
+    #[test]
+    fn micro_clusters_stay_bounded() {
+        let mut c = CluStream::new(20, 2, 2.0, 1000, 1).unwrap();
+        blobs(&mut c, 2000);
+        assert!(c.micro_clusters().len() <= 20);
+        let total: f64 = c.micro_clusters().iter().map(|m| m.weight).sum();
+        assert!(total > 0.0 && total <= 2000.0);
+    }
+
+    #[test]
+    fn macro_clusters_find_separated_blobs() {
+        let mut c = CluStream::new(20, 2, 2.0, 10_000, 1).unwrap();
+        blobs(&mut c, 1000);
+        let mut centers = c.macro_cluster_centers();
+        assert_eq!(centers.len(), 2);
+        centers.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        assert!(centers[0].iter().all(|v| v.abs() < 1.0));
+        assert!(centers[1].iter().all(|v| (v - 10.0).abs() < 1.0));
+    }
+
+    #[test]
+    fn non_finite_points_are_ignored() {
+        let h = header();
+        let mut c = CluStream::new(5, 1, 2.0, 100, 1).unwrap();
+        c.train_on_instance(&point(&h, f64::NAN, 1.0));
+        assert!(c.micro_clusters().is_empty());
+        c.train_on_instance(&point(&h, 1.0, 1.0));
+        assert_eq!(c.micro_cluster_centers(), vec![vec![1.0, 1.0]]);
+
+        c.reset();
+        assert!(c.micro_clusters().is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::evaluation::ClusteringEvaluator;
+use rand::Rng;
+use rand::rngs::StdRng;
+
+/// Lloyd iterations of the offline k-means step.
+const KMEANS_ITERATIONS: usize = 10;
+
+/// Weighted k-means with k-means++ seeding. Returns the input points when
+/// there are at most `k` of them.
+pub(crate) fn weighted_kmeans(
+    points: &[Vec<f64>],
+    weights: &[f64],
+    k: usize,
+    rng: &mut StdRng,
+) -> Vec<Vec<f64>> {
+    if points.len() <= k {
+        return points.to_vec();
+    }
+
+    let mut centers: Vec<Vec<f64>> = Vec::with_capacity(k);
+    centers.push(points[pick_weighted(weights, rng)].clone());
+    while centers.len() < k {
+        let scores: Vec<f64> = points
+            .iter()
+            .zip(weights)
+            .map(|(p, w)| {
+                let d = ClusteringEvaluator::nearest_center(p, &centers).map_or(0.0, |(_, d)| d);
+                w * d
+            })
+            .collect();
+        if scores.iter().sum::<f64>() <= 0.0 {
+            break;
+        }
+        centers.push(points[pick_weighted(&scores, rng)].clone());
+    }
+
+    let dims = points[0].len();
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut sums = vec![vec![0.0; dims]; centers.len()];
+        let mut totals = vec![0.0; centers.len()];
+        for (p, w) in points.iter().zip(weights) {
+            let Some((c, _)) = ClusteringEvaluator::nearest_center(p, &centers) else {
+                continue;
+            };
+            totals[c] += w;
+            for (s, v) in sums[c].iter_mut().zip(p) {
+                *s += w * v;
+            }
+        }
+        let mut moved = false;
+        for (c, center) in centers.iter_mut().enumerate() {
+            if totals[c] <= 0.0 {
+                continue;
+            }
+            let next: Vec<f64> = sums[c].iter().map(|s| s / totals[c]).collect();
+            moved |= next != *center;
+            *center = next;
+        }
+        if !moved {
+            break;
+        }
+    }
+    centers
+}
+
+pub(crate) fn pick_weighted(weights: &[f64], rng: &mut StdRng) -> usize {
+    let total: f64 = weights.iter().sum();
+    let mut target = rng.random::<f64>() * total;
+    for (i, w) in weights.iter().enumerate() {
+        target -= w;
+        if target <= 0.0 {
+            return i;
+        }
+    }
+    weights.len() - 1
+}
+
+#[inline]
+pub(crate) fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
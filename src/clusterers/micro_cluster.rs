@@ -0,0 +1,128 @@
+/// Cluster feature vector of CluStream: weight, linear and squared sums of
+/// the points, and of their timestamps.
+///
+/// All statistics are additive, so two micro-clusters merge by summing them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicroCluster {
+    pub weight: f64,
+    pub linear_sum: Vec<f64>,
+    pub squared_sum: Vec<f64>,
+    pub time_sum: f64,
+    pub time_squared_sum: f64,
+}
+
+impl MicroCluster {
+    /// Micro-cluster holding the single point `x` seen at `timestamp`.
+    pub fn from_point(x: &[f64], weight: f64, timestamp: f64) -> Self {
+        Self {
+            weight,
+            linear_sum: x.iter().map(|v| v * weight).collect(),
+            squared_sum: x.iter().map(|v| v * v * weight).collect(),
+            time_sum: timestamp * weight,
+            time_squared_sum: timestamp * timestamp * weight,
+        }
+    }
+
+    pub fn insert(&mut self, x: &[f64], weight: f64, timestamp: f64) {
+        self.weight += weight;
+        for (i, v) in x.iter().enumerate() {
+            self.linear_sum[i] += v * weight;
+            self.squared_sum[i] += v * v * weight;
+        }
+        self.time_sum += timestamp * weight;
+        self.time_squared_sum += timestamp * timestamp * weight;
+    }
+
+    pub fn merge(&mut self, other: &MicroCluster) {
+        self.weight += other.weight;
+        for (a, b) in self.linear_sum.iter_mut().zip(&other.linear_sum) {
+            *a += b;
+        }
+        for (a, b) in self.squared_sum.iter_mut().zip(&other.squared_sum) {
+            *a += b;
+        }
+        self.time_sum += other.time_sum;
+        self.time_squared_sum += other.time_squared_sum;
+    }
+
+    pub fn center(&self) -> Vec<f64> {
+        self.linear_sum.iter().map(|v| v / self.weight).collect()
+    }
+
+    /// Root-mean-square deviation of the points from the center.
+    pub fn radius(&self) -> f64 {
+        let variance: f64 = self
+            .linear_sum
+            .iter()
+            .zip(&self.squared_sum)
+            .map(|(ls, ss)| {
+                let mean = ls / self.weight;
+                (ss / self.weight - mean * mean).max(0.0)
+            })
+            .sum();
+        (variance / self.linear_sum.len().max(1) as f64).sqrt()
+    }
+
+    /// Approximate time at which the most recent `m` points were seen,
+    /// assuming normally distributed timestamps (CluStream's relevance
+    /// stamp). Clusters with few points use their mean timestamp.
+    pub fn relevance_stamp(&self, m: f64) -> f64 {
+        let mean = self.time_sum / self.weight;
+        if self.weight < 2.0 * m {
+            return mean;
+        }
+        let variance = (self.time_squared_sum / self.weight - mean * mean).max(0.0);
+        let quantile = m / (2.0 * self.weight);
+        mean + variance.sqrt() * inverse_upper_tail(quantile)
+    }
+}
+
+/// `z` such that a standard normal exceeds it with probability `p`, using
+/// the Abramowitz–Stegun rational approximation (error below 4.5e-4).
+fn inverse_upper_tail(p: f64) -> f64 {
+    let p = p.clamp(1e-12, 0.5);
+    let t = (-2.0 * p.ln()).sqrt();
+    t - (2.515517 + 0.802853 * t + 0.010328 * t * t)
+        / (1.0 + 1.432788 * t + 0.189269 * t * t + 0.001308 * t * t * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn center_and_radius_follow_inserted_points() {
+        let mut mc = MicroCluster::from_point(&[0.0, 0.0], 1.0, 1.0);
+        mc.insert(&[2.0, 0.0], 1.0, 2.0);
+        assert_eq!(mc.center(), vec![1.0, 0.0]);
+        // Variance 1 along x and 0 along y, averaged over two dimensions.
+        assert!((mc.radius() - 0.5f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn merge_adds_statistics() {
+        let mut a = MicroCluster::from_point(&[0.0], 1.0, 1.0);
+        let b = MicroCluster::from_point(&[4.0], 3.0, 2.0);
+        a.merge(&b);
+        assert_eq!(a.weight, 4.0);
+        assert_eq!(a.center(), vec![3.0]);
+        assert_eq!(a.time_sum, 7.0);
+    }
+
+    #[test]
+    fn relevance_stamp_moves_towards_recent_points() {
+        let mut mc = MicroCluster::from_point(&[0.0], 1.0, 0.0);
+        for t in 1..100 {
+            mc.insert(&[0.0], 1.0, t as f64);
+        }
+        let mean = mc.time_sum / mc.weight;
+        assert!(mc.relevance_stamp(10.0) > mean);
+        assert_eq!(mc.relevance_stamp(100.0), mean);
+    }
+
+    #[test]
+    fn inverse_tail_matches_known_quantiles() {
+        assert!(inverse_upper_tail(0.5).abs() < 1e-3);
+        assert!((inverse_upper_tail(0.025) - 1.96).abs() < 1e-3);
+    }
+}
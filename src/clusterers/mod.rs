@@ -0,0 +1,10 @@
+mod clusterer;
+mod clustream;
+mod kmeans;
+mod micro_cluster;
+mod streamkm;
+
+pub use clusterer::Clusterer;
+pub use clustream::CluStream;
+pub use micro_cluster::MicroCluster;
+pub use streamkm::StreamKm;
@@ -0,0 +1,316 @@
+use crate::clusterers::Clusterer;
+use crate::clusterers::kmeans::{pick_weighted, squared_distance, weighted_kmeans};
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::error::RivuError;
+use crate::evaluation::ClusteringEvaluator;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::io::Error;
+use std::sync::Arc;
+
+/// k-means++ runs of the offline step; the one with the lowest cost wins.
+const KMEANS_RUNS: usize = 5;
+
+/// Weighted points, stored as parallel vectors.
+#[derive(Debug, Clone, Default)]
+struct Bucket {
+    points: Vec<Vec<f64>>,
+    weights: Vec<f64>,
+}
+
+impl Bucket {
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    fn push(&mut self, point: Vec<f64>, weight: f64) {
+        self.points.push(point);
+        self.weights.push(weight);
+    }
+
+    fn append(&mut self, other: Bucket) {
+        self.points.extend(other.points);
+        self.weights.extend(other.weights);
+    }
+}
+
+/// Leaf of a coreset tree: the points it holds and the one representing
+/// them.
+struct Leaf {
+    members: Vec<usize>,
+    center: usize,
+    cost: f64,
+}
+
+impl Leaf {
+    fn new(members: Vec<usize>, center: usize, bucket: &Bucket) -> Self {
+        let cost = members
+            .iter()
+            .map(|&i| {
+                bucket.weights[i] * squared_distance(&bucket.points[i], &bucket.points[center])
+            })
+            .sum();
+        Self {
+            members,
+            center,
+            cost,
+        }
+    }
+}
+
+/// StreamKM++ (Ackermann et al., 2012).
+///
+/// Points are summarized by merge-and-reduce: the newest `coreset_size`
+/// points fill a buffer, and a full buffer is reduced with the bucket
+/// above it into a coreset of `coreset_size` weighted points, cascading up
+/// like a binary counter. Memory therefore grows with the logarithm of the
+/// stream length only. Coresets are built with a coreset tree, which
+/// repeatedly picks a leaf in proportion to its cost and splits it around
+/// a point drawn with k-means++ probabilities, until it has `coreset_size`
+/// leaves. The offline phase reduces every bucket to one coreset and keeps
+/// the best of five weighted k-means++ runs over it as the `k`
+/// macro-clusters.
+///
+/// The coreset points are the online summary reported as micro-clusters.
+pub struct StreamKm {
+    coreset_size: usize,
+    k: usize,
+    seed: u64,
+
+    header: Option<Arc<InstanceHeader>>,
+    /// `buckets[0]` is the buffer of raw points; every other bucket is
+    /// empty or a coreset of `coreset_size` points.
+    buckets: Vec<Bucket>,
+    rng: StdRng,
+}
+
+impl StreamKm {
+    pub fn new(coreset_size: usize, k: usize, seed: u64) -> Result<Self, Error> {
+        if k == 0 || coreset_size < k {
+            return Err(RivuError::config("need 1 <= k <= coreset_size").into());
+        }
+        Ok(Self {
+            coreset_size,
+            k,
+            seed,
+            header: None,
+            buckets: vec![Bucket::default()],
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    /// Number of weighted points currently stored, over all buckets.
+    pub fn stored_points(&self) -> usize {
+        self.buckets.iter().map(Bucket::len).sum()
+    }
+
+    /// Clears the model, keeping the configuration and header.
+    pub fn reset(&mut self) {
+        self.buckets = vec![Bucket::default()];
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+
+    /// Moves the full buffer up, merging it with each full bucket it meets.
+    fn carry(&mut self) {
+        let mut carry = std::mem::take(&mut self.buckets[0]);
+        for level in 1.. {
+            if level == self.buckets.len() {
+                self.buckets.push(Bucket::default());
+            }
+            if self.buckets[level].is_empty() {
+                self.buckets[level] = carry;
+                return;
+            }
+            carry.append(std::mem::take(&mut self.buckets[level]));
+            carry = reduce(carry, self.coreset_size, &mut self.rng);
+        }
+    }
+
+    fn all_points(&self) -> Bucket {
+        let mut all = Bucket::default();
+        for bucket in &self.buckets {
+            all.append(bucket.clone());
+        }
+        all
+    }
+}
+
+impl Clusterer for StreamKm {
+    fn set_model_context(&mut self, header: Arc<InstanceHeader>) {
+        self.header = Some(header);
+    }
+
+    fn train_on_instance(&mut self, instance: &dyn Instance) {
+        let weight = instance.weight();
+        let point = ClusteringEvaluator::features_of(instance);
+        if weight <= 0.0 || point.iter().any(|v| !v.is_finite()) {
+            return;
+        }
+        self.buckets[0].push(point, weight);
+        if self.buckets[0].len() >= self.coreset_size {
+            self.carry();
+        }
+    }
+
+    fn micro_cluster_centers(&self) -> Vec<Vec<f64>> {
+        self.buckets
+            .iter()
+            .flat_map(|b| b.points.iter().cloned())
+            .collect()
+    }
+
+    fn macro_cluster_centers(&mut self) -> Vec<Vec<f64>> {
+        let coreset = reduce(self.all_points(), self.coreset_size, &mut self.rng);
+        let mut best: Option<(f64, Vec<Vec<f64>>)> = None;
+        for _ in 0..KMEANS_RUNS {
+            let centers = weighted_kmeans(&coreset.points, &coreset.weights, self.k, &mut self.rng);
+            let cost = cost(&coreset, &centers);
+            if best.as_ref().is_none_or(|(c, _)| cost < *c) {
+                best = Some((cost, centers));
+            }
+        }
+        best.map(|(_, centers)| centers).unwrap_or_default()
+    }
+}
+
+/// Coreset of at most `size` points of `bucket`, built with a coreset
+/// tree. Each coreset point is weighted by the points of its leaf, so the
+/// total weight is preserved.
+fn reduce(bucket: Bucket, size: usize, rng: &mut StdRng) -> Bucket {
+    if bucket.len() <= size {
+        return bucket;
+    }
+    let first = pick_weighted(&bucket.weights, rng);
+    let mut leaves = vec![Leaf::new((0..bucket.len()).collect(), first, &bucket)];
+    while leaves.len() < size {
+        let costs: Vec<f64> = leaves.iter().map(|l| l.cost).collect();
+        if costs.iter().sum::<f64>() <= 0.0 {
+            break;
+        }
+        let leaf = leaves.swap_remove(pick_weighted(&costs, rng));
+        let center = &bucket.points[leaf.center];
+        let scores: Vec<f64> = leaf
+            .members
+            .iter()
+            .map(|&i| bucket.weights[i] * squared_distance(&bucket.points[i], center))
+            .collect();
+        let new_center = leaf.members[pick_weighted(&scores, rng)];
+        let (stay, moved): (Vec<usize>, Vec<usize>) =
+            leaf.members.iter().copied().partition(|&i| {
+                let p = &bucket.points[i];
+                squared_distance(p, center) <= squared_distance(p, &bucket.points[new_center])
+            });
+        if moved.is_empty() {
+            leaves.push(leaf);
+            continue;
+        }
+        leaves.push(Leaf::new(stay, leaf.center, &bucket));
+        leaves.push(Leaf::new(moved, new_center, &bucket));
+    }
+
+    let mut coreset = Bucket::default();
+    for leaf in leaves {
+        let weight = leaf.members.iter().map(|&i| bucket.weights[i]).sum();
+        coreset.push(bucket.points[leaf.center].clone(), weight);
+    }
+    coreset
+}
+
+/// Weighted sum of squared distances from each point to its nearest center.
+fn cost(bucket: &Bucket, centers: &[Vec<f64>]) -> f64 {
+    bucket
+        .points
+        .iter()
+        .zip(&bucket.weights)
+        .map(|(p, w)| w * ClusteringEvaluator::nearest_center(p, centers).map_or(0.0, |(_, d)| d))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use rand::Rng;
+
+    fn header() -> Arc<InstanceHeader> {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())) as AttributeRef,
+            Arc::new(NumericAttribute::new("y".into())) as AttributeRef,
+            Arc::new(NumericAttribute::new("class".into())) as AttributeRef,
+        ];
+        Arc::new(InstanceHeader::new("points".into(), attrs, 2))
+    }
+
+    fn point(h: &Arc<InstanceHeader>, x: f64, y: f64) -> DenseInstance {
+        DenseInstance::new(Arc::clone(h), vec![x, y, f64::NAN], 1.0)
+    }
+
+    fn blobs(clusterer: &mut StreamKm, n: usize) {
+        let h = header();
+        let mut rng = StdRng::seed_from_u64(3);
+        for i in 0..n {
+            let (cx, cy) = match i % 3 {
+                0 => (0.0, 0.0),
+                1 => (10.0, 10.0),
+                _ => (0.0, 10.0),
+            };
+            let x = cx + rng.random::<f64>() - 0.5;
+            let y = cy + rng.random::<f64>() - 0.5;
+            clusterer.train_on_instance(&point(&h, x, y));
+        }
+    }
+
+    #[test]
+    fn ctor_guards() {
+        assert!(StreamKm::new(10, 0, 1).is_err());
+        assert!(StreamKm::new(2, 3, 1).is_err());
+        assert!(StreamKm::new(3, 3, 1).is_ok());
+    }
+
+    #[test]
+    fn storage_grows_logarithmically_and_keeps_the_total_weight() {
+        let mut c = StreamKm::new(50, 3, 1).unwrap();
+        blobs(&mut c, 10_000);
+        // 10,000 / 50 = 200 buffers fill up, so at most 8 coreset levels.
+        assert!(c.buckets.len() <= 9, "{} buckets", c.buckets.len());
+        assert!(c.stored_points() <= 50 * 9);
+        let total: f64 = c.buckets.iter().flat_map(|b| &b.weights).sum();
+        assert!((total - 10_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn macro_clusters_find_separated_blobs() {
+        let mut c = StreamKm::new(40, 3, 1).unwrap();
+        blobs(&mut c, 3000);
+        let mut centers = c.macro_cluster_centers();
+        assert_eq!(centers.len(), 3);
+        centers.sort_by(|a, b| (a[0] + a[1]).total_cmp(&(b[0] + b[1])));
+        let expected = [[0.0, 0.0], [0.0, 10.0], [10.0, 10.0]];
+        for (center, want) in centers.iter().zip(expected) {
+            assert!(
+                center.iter().zip(want).all(|(v, w)| (v - w).abs() < 1.0),
+                "{centers:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn non_finite_points_are_ignored() {
+        let h = header();
+        let mut c = StreamKm::new(5, 1, 1).unwrap();
+        c.train_on_instance(&point(&h, f64::NAN, 1.0));
+        assert_eq!(c.stored_points(), 0);
+        c.train_on_instance(&point(&h, 1.0, 1.0));
+        assert_eq!(c.micro_cluster_centers(), vec![vec![1.0, 1.0]]);
+        assert_eq!(c.macro_cluster_centers(), vec![vec![1.0, 1.0]]);
+
+        c.reset();
+        assert_eq!(c.stored_points(), 0);
+    }
+}
@@ -0,0 +1,275 @@
+//! Conversions between instances and Arrow [`RecordBatch`]es.
+//!
+//! A batch has one nullable column per attribute, in header order: `Float64`
+//! for numeric attributes and `Utf8` labels for nominal ones. Missing values
+//! are nulls. Weights are not stored, so instances read back have unit weight.
+
+use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::{DenseInstance, Instance};
+use crate::error::RivuError;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Float32Type, Float64Type, Int8Type, Int16Type, Int32Type, Int64Type, UInt8Type, UInt16Type,
+    UInt32Type, UInt64Type,
+};
+use arrow_array::{Array, ArrayRef, Float64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use std::collections::HashMap;
+use std::io::Error;
+use std::sync::Arc;
+
+fn nominal(header: &InstanceHeader, index: usize) -> Option<&NominalAttribute> {
+    header
+        .attribute_at_index(index)?
+        .as_any()
+        .downcast_ref::<NominalAttribute>()
+}
+
+/// Schema of the batches produced by [`to_record_batch`] for `header`.
+pub fn schema_for(header: &InstanceHeader) -> SchemaRef {
+    let fields: Vec<Field> = header
+        .attributes
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            let data_type = if nominal(header, i).is_some() {
+                DataType::Utf8
+            } else {
+                DataType::Float64
+            };
+            Field::new(a.name(), data_type, true)
+        })
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Packs `instances`, which must all match `header`, into one batch with the
+/// schema of [`schema_for`]. Nominal values outside the domain become nulls.
+pub fn to_record_batch(
+    header: &InstanceHeader,
+    instances: &[&dyn Instance],
+) -> Result<RecordBatch, Error> {
+    let columns: Vec<ArrayRef> = (0..header.number_of_attributes())
+        .map(|i| -> ArrayRef {
+            let values = instances
+                .iter()
+                .map(|inst| inst.value_at_index(i).unwrap_or(f64::NAN));
+            match nominal(header, i) {
+                Some(attr) => Arc::new(StringArray::from_iter(values.map(|v| {
+                    (v.is_finite() && v >= 0.0)
+                        .then(|| attr.values.get(v as usize))
+                        .flatten()
+                }))),
+                None => Arc::new(Float64Array::from_iter(
+                    values.map(|v| (!v.is_nan()).then_some(v)),
+                )),
+            }
+        })
+        .collect();
+    RecordBatch::try_new(schema_for(header), columns).map_err(Error::other)
+}
+
+/// Unpacks a batch whose columns follow `header`, as written by
+/// [`to_record_batch`]. Nominal columns may hold strings or booleans and
+/// numeric ones any integer or floating-point type.
+pub fn from_record_batch(
+    header: &Arc<InstanceHeader>,
+    batch: &RecordBatch,
+) -> Result<Vec<DenseInstance>, Error> {
+    let width = header.number_of_attributes();
+    if batch.num_columns() != width {
+        return Err(RivuError::parse(format!(
+            "record batch has {} columns but the header has {width} attributes",
+            batch.num_columns()
+        ))
+        .into());
+    }
+    let mut rows = vec![vec![f64::NAN; width]; batch.num_rows()];
+    for (col, array) in batch.columns().iter().enumerate() {
+        let attr = nominal(header, col);
+        for (row, values) in rows.iter_mut().enumerate() {
+            if array.is_null(row) {
+                continue;
+            }
+            values[col] = match attr {
+                Some(attr) => {
+                    let label = string_at(array, row)?;
+                    let index = attr.label_to_index.get(&label).ok_or_else(|| {
+                        RivuError::parse(format!(
+                            "unknown label '{label}' for attribute '{}'",
+                            attr.name
+                        ))
+                    })?;
+                    *index as f64
+                }
+                None => numeric_at(array, row)?,
+            };
+        }
+    }
+    Ok(rows
+        .into_iter()
+        .map(|values| DenseInstance::new(Arc::clone(header), values, 1.0))
+        .collect())
+}
+
+/// Builds a header for batches with `schema`: string and boolean columns
+/// become nominal attributes with their labels in order of first appearance
+/// across `batches`, integer and floating-point columns numeric ones. The
+/// class defaults to the last column.
+pub fn header_from_batches(
+    relation: String,
+    schema: &Schema,
+    batches: &[RecordBatch],
+    class_index: Option<usize>,
+) -> Result<InstanceHeader, Error> {
+    let width = schema.fields().len();
+    if width == 0 {
+        return Err(RivuError::parse("schema has no columns").into());
+    }
+    let class_index = class_index.unwrap_or(width - 1);
+    if class_index >= width {
+        return Err(RivuError::config(format!(
+            "class index {class_index} out of range for {width} columns"
+        ))
+        .into());
+    }
+
+    let mut attributes: Vec<AttributeRef> = Vec::with_capacity(width);
+    for (col, field) in schema.fields().iter().enumerate() {
+        match field.data_type() {
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Boolean => {
+                let mut labels: Vec<String> = Vec::new();
+                let mut map: HashMap<String, usize> = HashMap::new();
+                for array in batches.iter().map(|b| b.column(col)) {
+                    for row in (0..array.len()).filter(|&r| !array.is_null(r)) {
+                        let label = string_at(array, row)?;
+                        if !map.contains_key(&label) {
+                            map.insert(label.clone(), labels.len());
+                            labels.push(label);
+                        }
+                    }
+                }
+                attributes.push(Arc::new(NominalAttribute::with_values(
+                    field.name().clone(),
+                    labels,
+                    map,
+                )));
+            }
+            t if t.is_numeric() => {
+                attributes.push(Arc::new(NumericAttribute::new(field.name().clone())));
+            }
+            t => {
+                return Err(RivuError::unsupported(format!(
+                    "unsupported column type {t} for '{}'",
+                    field.name()
+                ))
+                .into());
+            }
+        }
+    }
+
+    let header = InstanceHeader::new(relation, attributes, class_index);
+    header.validate_class()?;
+    Ok(header)
+}
+
+fn string_at(array: &ArrayRef, row: usize) -> Result<String, Error> {
+    Ok(match array.data_type() {
+        DataType::Utf8 => array.as_string::<i32>().value(row).to_string(),
+        DataType::LargeUtf8 => array.as_string::<i64>().value(row).to_string(),
+        DataType::Boolean => array.as_boolean().value(row).to_string(),
+        t => {
+            return Err(
+                RivuError::unsupported(format!("unsupported nominal column type {t}")).into(),
+            );
+        }
+    })
+}
+
+fn numeric_at(array: &ArrayRef, row: usize) -> Result<f64, Error> {
+    Ok(match array.data_type() {
+        DataType::Float64 => array.as_primitive::<Float64Type>().value(row),
+        DataType::Float32 => array.as_primitive::<Float32Type>().value(row) as f64,
+        DataType::Int8 => array.as_primitive::<Int8Type>().value(row) as f64,
+        DataType::Int16 => array.as_primitive::<Int16Type>().value(row) as f64,
+        DataType::Int32 => array.as_primitive::<Int32Type>().value(row) as f64,
+        DataType::Int64 => array.as_primitive::<Int64Type>().value(row) as f64,
+        DataType::UInt8 => array.as_primitive::<UInt8Type>().value(row) as f64,
+        DataType::UInt16 => array.as_primitive::<UInt16Type>().value(row) as f64,
+        DataType::UInt32 => array.as_primitive::<UInt32Type>().value(row) as f64,
+        DataType::UInt64 => array.as_primitive::<UInt64Type>().value(row) as f64,
+        t => {
+            return Err(
+                RivuError::unsupported(format!("unsupported numeric column type {t}")).into(),
+            );
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{BooleanArray, Int32Array};
+
+    #[test]
+    fn instances_round_trip_through_a_record_batch() {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NominalAttribute::with_values(
+                "outlook".into(),
+                vec!["sunny".into(), "rainy".into()],
+                HashMap::from([("sunny".into(), 0), ("rainy".into(), 1)]),
+            )),
+            Arc::new(NumericAttribute::new("temp".into())),
+        ];
+        let header = Arc::new(InstanceHeader::new("weather".into(), attrs, 0));
+        let rows = [vec![1.0, 70.5], vec![f64::NAN, f64::NAN], vec![0.0, -3.0]];
+        let instances: Vec<DenseInstance> = rows
+            .iter()
+            .map(|r| DenseInstance::new(Arc::clone(&header), r.clone(), 1.0))
+            .collect();
+        let refs: Vec<&dyn Instance> = instances.iter().map(|i| i as &dyn Instance).collect();
+
+        let batch = to_record_batch(&header, &refs).unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Utf8);
+        assert_eq!(batch.column(0).as_string::<i32>().value(0), "rainy");
+        assert_eq!(batch.column(1).null_count(), 1);
+
+        let back = from_record_batch(&header, &batch).unwrap();
+        assert_eq!(back[0].values, rows[0]);
+        assert!(back[1].values.iter().all(|v| v.is_nan()));
+        assert_eq!(back[2].values, rows[2]);
+    }
+
+    #[test]
+    fn infers_headers_from_batches() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("n", DataType::Int32, true),
+            Field::new("flag", DataType::Boolean, true),
+        ]));
+        let batch = |n: Vec<Option<i32>>, flag: Vec<Option<bool>>| {
+            RecordBatch::try_new(
+                Arc::clone(&schema),
+                vec![
+                    Arc::new(Int32Array::from(n)),
+                    Arc::new(BooleanArray::from(flag)),
+                ],
+            )
+            .unwrap()
+        };
+        let batches = [
+            batch(vec![Some(1), None], vec![Some(true), None]),
+            batch(vec![Some(3)], vec![Some(false)]),
+        ];
+
+        let header = Arc::new(header_from_batches("b".into(), &schema, &batches, None).unwrap());
+        assert_eq!(header.class_index(), 1);
+        let flag = nominal(&header, 1).unwrap();
+        assert_eq!(flag.values, vec!["true", "false"]);
+        let second = from_record_batch(&header, &batches[1]).unwrap();
+        assert_eq!(second[0].values, vec![3.0, 1.0]);
+
+        assert!(header_from_batches("b".into(), &schema, &batches, Some(2)).is_err());
+    }
+}
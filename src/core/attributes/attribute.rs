@@ -1,3 +1,5 @@
+use crate::core::attributes::{NominalAttribute, NumericAttribute};
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::sync::Arc;
 
@@ -10,3 +12,35 @@ pub trait Attribute: Any + Send + Sync {
 
     fn arff_representation(&self) -> String;
 }
+
+/// Serialized form of an [`AttributeRef`], tagged with its kind. Only the
+/// built-in attribute types can be serialized.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub(crate) enum AttributeData {
+    Numeric(NumericAttribute),
+    Nominal(NominalAttribute),
+}
+
+impl AttributeData {
+    pub(crate) fn from_attribute(attr: &dyn Attribute) -> Result<Self, String> {
+        let any = attr.as_any();
+        if let Some(numeric) = any.downcast_ref::<NumericAttribute>() {
+            Ok(AttributeData::Numeric(numeric.clone()))
+        } else if let Some(nominal) = any.downcast_ref::<NominalAttribute>() {
+            Ok(AttributeData::Nominal(nominal.clone()))
+        } else {
+            Err(format!(
+                "attribute '{}' has a type that cannot be serialized",
+                attr.name()
+            ))
+        }
+    }
+
+    pub(crate) fn into_attribute(self) -> AttributeRef {
+        match self {
+            AttributeData::Numeric(a) => Arc::new(a),
+            AttributeData::Nominal(a) => Arc::new(a),
+        }
+    }
+}
@@ -3,6 +3,7 @@ mod nominal_attribute;
 mod numeric_attribute;
 
 pub use attribute::Attribute;
+pub(crate) use attribute::AttributeData;
 pub use attribute::AttributeRef;
 pub use nominal_attribute::NominalAttribute;
 pub use numeric_attribute::NumericAttribute;
@@ -1,12 +1,23 @@
 use crate::core::attributes::Attribute;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::collections::HashMap;
 
-#[derive(Clone)]
+/// Serialized as its name, values, and `ordered` and `dynamic` flags;
+/// `label_to_index` is rebuilt from the values when deserializing.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(into = "NominalAttributeData", from = "NominalAttributeData")]
 pub struct NominalAttribute {
     pub name: String,
     pub values: Vec<String>,
     pub label_to_index: HashMap<String, usize>,
+    /// Whether `values` are listed in a meaningful order (an ordinal
+    /// attribute, e.g. `{low, medium, high}`), so that value indices can be
+    /// compared like numbers.
+    pub ordered: bool,
+    /// Whether a stream may append labels it has not seen before to `values`
+    /// (see [`add_value`](Self::add_value)) instead of rejecting the row.
+    pub dynamic: bool,
 }
 
 impl NominalAttribute {
@@ -15,6 +26,8 @@ impl NominalAttribute {
             name,
             values: Vec::new(),
             label_to_index: HashMap::new(),
+            ordered: false,
+            dynamic: false,
         }
     }
 
@@ -27,9 +40,42 @@ impl NominalAttribute {
             name,
             values,
             label_to_index,
+            ordered: false,
+            dynamic: false,
         }
     }
 
+    /// An ordinal attribute whose values rank in the order given.
+    pub fn ordinal(name: String, values: Vec<String>) -> NominalAttribute {
+        let label_to_index = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.clone(), i))
+            .collect();
+        NominalAttribute {
+            name,
+            values,
+            label_to_index,
+            ordered: true,
+            dynamic: false,
+        }
+    }
+
+    pub fn is_ordinal(&self) -> bool {
+        self.ordered
+    }
+
+    /// Index of `label`, appending it to the values first if it is new.
+    pub fn add_value(&mut self, label: &str) -> usize {
+        if let Some(&index) = self.label_to_index.get(label) {
+            return index;
+        }
+        let index = self.values.len();
+        self.values.push(label.to_string());
+        self.label_to_index.insert(label.to_string(), index);
+        index
+    }
+
     pub fn get_attribute_values(&self) -> Vec<String> {
         self.values.clone()
     }
@@ -43,6 +89,36 @@ impl NominalAttribute {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+struct NominalAttributeData {
+    name: String,
+    values: Vec<String>,
+    #[serde(default)]
+    ordered: bool,
+    #[serde(default)]
+    dynamic: bool,
+}
+
+impl From<NominalAttribute> for NominalAttributeData {
+    fn from(a: NominalAttribute) -> Self {
+        Self {
+            name: a.name,
+            values: a.values,
+            ordered: a.ordered,
+            dynamic: a.dynamic,
+        }
+    }
+}
+
+impl From<NominalAttributeData> for NominalAttribute {
+    fn from(d: NominalAttributeData) -> Self {
+        let mut attr = NominalAttribute::ordinal(d.name, d.values);
+        attr.ordered = d.ordered;
+        attr.dynamic = d.dynamic;
+        attr
+    }
+}
+
 impl Attribute for NominalAttribute {
     fn name(&self) -> String {
         self.name.clone()
@@ -55,9 +131,10 @@ impl Attribute for NominalAttribute {
     fn arff_representation(&self) -> String {
         let nominal = self.as_any().downcast_ref::<NominalAttribute>().unwrap();
         format!(
-            "@attribute {} {{ {} }}",
+            "@attribute {} {{ {} }}{}",
             nominal.name(),
-            nominal.values.join(", ")
+            nominal.values.join(", "),
+            if nominal.ordered { " ordinal" } else { "" }
         )
     }
 }
@@ -1,9 +1,11 @@
 use crate::core::attributes::Attribute;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct NumericAttribute {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub values: Vec<u32>,
 }
 
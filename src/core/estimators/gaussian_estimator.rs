@@ -1,6 +1,11 @@
 use crate::utils::math::normal_probability;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
 
-#[derive(Clone, Debug, Default)]
+/// `sqrt(2π)`, the normalizing constant of the standard normal density.
+const SQRT_TAU: f64 = 2.506_628_274_631_000_7;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct GaussianEstimator {
     weight_sum: f64,
     mean: f64,
@@ -29,6 +34,10 @@ impl GaussianEstimator {
         }
     }
 
+    pub fn get_mean(&self) -> f64 {
+        self.mean
+    }
+
     pub fn get_variance(&self) -> f64 {
         if self.weight_sum > 1.0 {
             self.variance_sum / (self.weight_sum - 1.0)
@@ -80,23 +89,34 @@ impl GaussianEstimator {
     }
 
     pub fn probability_density(&self, value: f64) -> f64 {
-        let normal_const: f64 = (2.0 * std::f64::consts::PI).sqrt();
         if self.weight_sum > 0.0 {
-            let std_dev = self.get_std_dev();
-            if std_dev > 0.0 {
-                let diff = value - self.mean;
-                return (1.0 / (normal_const * std_dev))
-                    * ((-diff * diff) / (2.0 * std_dev * std_dev)).exp();
+            let variance = self.get_variance();
+            let diff = value - self.mean;
+            if variance > 0.0 {
+                return (-0.5 * diff * diff / variance).exp() / (SQRT_TAU * variance.sqrt());
             }
-            return if (value - self.mean).abs() == 0.0 {
-                1.0
-            } else {
-                0.0
-            };
+            return if diff == 0.0 { 1.0 } else { 0.0 };
         }
         0.0
     }
 
+    /// Natural log of [`probability_density`](Self::probability_density),
+    /// computed without the `exp`, so that sums of many of them don't
+    /// underflow the way products of the densities do.
+    pub fn log_probability_density(&self, value: f64) -> f64 {
+        if self.weight_sum > 0.0 {
+            let variance = self.get_variance();
+            let diff = value - self.mean;
+            if variance > 0.0 {
+                return -0.5 * (diff * diff / variance + (TAU * variance).ln());
+            }
+            if diff == 0.0 {
+                return 0.0;
+            }
+        }
+        f64::NEG_INFINITY
+    }
+
     pub fn estimate_size_bytes(&self) -> usize {
         size_of::<Self>()
     }
@@ -224,4 +244,27 @@ mod tests {
         assert!(approx_eq(g.probability_density(10.0), 1.0, 1e-12));
         assert!(approx_eq(g.probability_density(9.999999999), 0.0, 1e-12));
     }
+
+    #[test]
+    fn log_density_matches_the_log_of_the_density() {
+        let mut g = GaussianEstimator::new();
+        for &x in &[1.0, 2.0, 4.0, 7.0] {
+            g.add_observation(x, 1.0);
+        }
+        for &x in &[-3.0, 0.0, 3.5, 12.0] {
+            let expected = g.probability_density(x).ln();
+            assert!(approx_eq(g.log_probability_density(x), expected, 1e-12));
+        }
+        assert!(g.log_probability_density(1e10).is_finite());
+        assert_eq!(g.probability_density(1e10), 0.0);
+
+        let mut spike = GaussianEstimator::new();
+        spike.add_observation(10.0, 3.0);
+        assert_eq!(spike.log_probability_density(10.0), 0.0);
+        assert_eq!(spike.log_probability_density(9.0), f64::NEG_INFINITY);
+        assert_eq!(
+            GaussianEstimator::new().log_probability_density(0.0),
+            f64::NEG_INFINITY
+        );
+    }
 }
@@ -1 +1,2 @@
 pub mod gaussian_estimator;
+pub mod running_stats;
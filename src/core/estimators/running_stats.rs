@@ -0,0 +1,312 @@
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use serde::{Deserialize, Serialize};
+
+/// Weighted running count, mean, variance (Welford's update), minimum, and
+/// maximum of a numeric attribute. Missing values are only counted.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunningStats {
+    weight: f64,
+    mean: f64,
+    m2: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    missing_weight: f64,
+}
+
+impl RunningStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, value: f64, weight: f64) {
+        if weight <= 0.0 {
+            return;
+        }
+        if !value.is_finite() {
+            self.missing_weight += weight;
+            return;
+        }
+        self.weight += weight;
+        let delta = value - self.mean;
+        self.mean += weight * delta / self.weight;
+        self.m2 += weight * delta * (value - self.mean);
+        self.min = Some(self.min.map_or(value, |m| m.min(value)));
+        self.max = Some(self.max.map_or(value, |m| m.max(value)));
+    }
+
+    /// Combines the observations of `other` into these (Chan et al.).
+    pub fn merge(&mut self, other: &RunningStats) {
+        self.missing_weight += other.missing_weight;
+        if other.weight <= 0.0 {
+            return;
+        }
+        if self.weight <= 0.0 {
+            let missing = self.missing_weight;
+            *self = other.clone();
+            self.missing_weight = missing;
+            return;
+        }
+        let total = self.weight + other.weight;
+        let delta = other.mean - self.mean;
+        self.m2 += other.m2 + delta * delta * self.weight * other.weight / total;
+        self.mean += delta * other.weight / total;
+        self.weight = total;
+        self.min = combine(self.min, other.min, f64::min);
+        self.max = combine(self.max, other.max, f64::max);
+    }
+
+    /// Total weight of the non-missing values.
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub fn missing_weight(&self) -> f64 {
+        self.missing_weight
+    }
+
+    /// Mean of the values seen; 0 before any.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample variance (weight minus one in the denominator); 0 until more
+    /// than a unit of weight has been seen.
+    pub fn variance(&self) -> f64 {
+        if self.weight > 1.0 {
+            self.m2 / (self.weight - 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+}
+
+fn combine(a: Option<f64>, b: Option<f64>, f: fn(f64, f64) -> f64) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(f(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Weighted frequency of each value of a nominal attribute. The table grows
+/// when values beyond the known ones show up.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NominalCounts {
+    counts: Vec<f64>,
+    missing_weight: f64,
+}
+
+impl NominalCounts {
+    pub fn new(number_of_values: usize) -> Self {
+        Self {
+            counts: vec![0.0; number_of_values],
+            missing_weight: 0.0,
+        }
+    }
+
+    /// Counts the value stored as `value` (its index in the domain).
+    pub fn add(&mut self, value: f64, weight: f64) {
+        if weight <= 0.0 {
+            return;
+        }
+        if !value.is_finite() || value < 0.0 {
+            self.missing_weight += weight;
+            return;
+        }
+        let index = value as usize;
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0.0);
+        }
+        self.counts[index] += weight;
+    }
+
+    pub fn merge(&mut self, other: &NominalCounts) {
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0.0);
+        }
+        for (c, o) in self.counts.iter_mut().zip(&other.counts) {
+            *c += o;
+        }
+        self.missing_weight += other.missing_weight;
+    }
+
+    pub fn counts(&self) -> &[f64] {
+        &self.counts
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn missing_weight(&self) -> f64 {
+        self.missing_weight
+    }
+
+    /// Share of the non-missing weight taken by value `index`.
+    pub fn frequency(&self, index: usize) -> f64 {
+        let total = self.weight();
+        match self.counts.get(index) {
+            Some(&c) if total > 0.0 => c / total,
+            _ => 0.0,
+        }
+    }
+
+    /// Most frequent value, the first on ties; `None` before any value.
+    pub fn mode(&self) -> Option<usize> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|&(_, &c)| c > 0.0)
+            .fold(None, |best: Option<(usize, f64)>, (i, &c)| match best {
+                Some((_, b)) if b >= c => best,
+                _ => Some((i, c)),
+            })
+            .map(|(i, _)| i)
+    }
+}
+
+/// Running statistics of one attribute, by kind.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AttributeStats {
+    Numeric(RunningStats),
+    Nominal(NominalCounts),
+}
+
+impl AttributeStats {
+    pub fn add(&mut self, value: f64, weight: f64) {
+        match self {
+            AttributeStats::Numeric(s) => s.add(value, weight),
+            AttributeStats::Nominal(c) => c.add(value, weight),
+        }
+    }
+
+    pub fn as_numeric(&self) -> Option<&RunningStats> {
+        match self {
+            AttributeStats::Numeric(s) => Some(s),
+            AttributeStats::Nominal(_) => None,
+        }
+    }
+
+    pub fn as_nominal(&self) -> Option<&NominalCounts> {
+        match self {
+            AttributeStats::Nominal(c) => Some(c),
+            AttributeStats::Numeric(_) => None,
+        }
+    }
+}
+
+/// Running statistics of every attribute of a header, class included,
+/// updated one instance at a time with the instance weight.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeaderStats {
+    attributes: Vec<AttributeStats>,
+}
+
+impl HeaderStats {
+    /// Nominal attributes get a frequency table, everything else numeric
+    /// statistics.
+    pub fn new(header: &InstanceHeader) -> Self {
+        let attributes = header
+            .attributes
+            .iter()
+            .map(|a| match a.as_any().downcast_ref::<NominalAttribute>() {
+                Some(n) => AttributeStats::Nominal(NominalCounts::new(n.values.len())),
+                None => AttributeStats::Numeric(RunningStats::new()),
+            })
+            .collect();
+        Self { attributes }
+    }
+
+    pub fn update(&mut self, instance: &dyn Instance) {
+        let weight = instance.weight();
+        for (i, stats) in self.attributes.iter_mut().enumerate() {
+            stats.add(instance.value_at_index(i).unwrap_or(f64::NAN), weight);
+        }
+    }
+
+    pub fn attribute(&self, index: usize) -> Option<&AttributeStats> {
+        self.attributes.get(index)
+    }
+
+    pub fn attributes(&self) -> &[AttributeStats] {
+        &self.attributes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use std::sync::Arc;
+
+    const EPS: f64 = 1e-12;
+
+    #[test]
+    fn numeric_stats_match_batch_formulas_and_merge() {
+        let xs = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let mut all = RunningStats::new();
+        let (mut left, mut right) = (RunningStats::new(), RunningStats::new());
+        for (i, &x) in xs.iter().enumerate() {
+            all.add(x, 1.0);
+            let part = if i < 3 { &mut left } else { &mut right };
+            part.add(x, 1.0);
+        }
+        all.add(f64::NAN, 2.0);
+
+        assert_eq!(all.weight(), 8.0);
+        assert_eq!(all.missing_weight(), 2.0);
+        assert!((all.mean() - 5.0).abs() < EPS);
+        assert!((all.variance() - 32.0 / 7.0).abs() < EPS);
+        assert_eq!((all.min(), all.max()), (Some(2.0), Some(9.0)));
+
+        left.merge(&right);
+        assert!((left.mean() - all.mean()).abs() < EPS);
+        assert!((left.variance() - all.variance()).abs() < EPS);
+        assert_eq!((left.min(), left.max()), (Some(2.0), Some(9.0)));
+
+        let mut empty = RunningStats::new();
+        empty.merge(&left);
+        assert_eq!(empty, left);
+        assert_eq!(RunningStats::new().min(), None);
+    }
+
+    #[test]
+    fn header_stats_track_each_attribute_by_kind() {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NominalAttribute::ordinal(
+                "c".into(),
+                vec!["a".into(), "b".into()],
+            )),
+        ];
+        let header = Arc::new(InstanceHeader::new("r".into(), attrs, 1));
+        let mut stats = HeaderStats::new(&header);
+        for (x, c, w) in [(1.0, 0.0, 1.0), (3.0, 1.0, 3.0), (f64::NAN, 1.0, 1.0)] {
+            stats.update(&DenseInstance::new(Arc::clone(&header), vec![x, c], w));
+        }
+
+        let x = stats.attribute(0).unwrap().as_numeric().unwrap();
+        assert!((x.mean() - 2.5).abs() < EPS);
+        assert_eq!(x.missing_weight(), 1.0);
+
+        let c = stats.attribute(1).unwrap().as_nominal().unwrap();
+        assert_eq!(c.counts(), &[1.0, 4.0]);
+        assert!((c.frequency(1) - 0.8).abs() < EPS);
+        assert_eq!(c.mode(), Some(1));
+        assert_eq!(NominalCounts::new(2).mode(), None);
+    }
+}
@@ -0,0 +1,332 @@
+use crate::core::attributes::{Attribute, NominalAttribute, NumericAttribute};
+use crate::core::instance_header::InstanceHeader;
+use crate::error::RivuError;
+use std::fmt;
+use std::io::Error;
+
+/// One difference between an expected header and the header found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderMismatch {
+    AttributeCount {
+        expected: usize,
+        found: usize,
+    },
+    Name {
+        index: usize,
+        expected: String,
+        found: String,
+    },
+    Kind {
+        index: usize,
+        name: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The nominal values differ. Values are stored by position, so the same
+    /// labels in another order (`missing` and `extra` both empty) differ too.
+    Domain {
+        index: usize,
+        name: String,
+        missing: Vec<String>,
+        extra: Vec<String>,
+    },
+    ClassIndex {
+        expected: usize,
+        found: usize,
+    },
+    Labels {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+}
+
+impl fmt::Display for HeaderMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderMismatch::AttributeCount { expected, found } => {
+                write!(f, "expected {expected} attributes, found {found}")
+            }
+            HeaderMismatch::Name {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "attribute {index}: expected '{expected}', found '{found}'"
+            ),
+            HeaderMismatch::Kind {
+                index,
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "attribute {index} ('{name}'): expected {expected}, found {found}"
+            ),
+            HeaderMismatch::Domain {
+                index,
+                name,
+                missing,
+                extra,
+            } => {
+                write!(f, "attribute {index} ('{name}'): nominal values differ")?;
+                if missing.is_empty() && extra.is_empty() {
+                    return write!(f, " in order");
+                }
+                if !missing.is_empty() {
+                    write!(f, "; missing {{{}}}", missing.join(", "))?;
+                }
+                if !extra.is_empty() {
+                    write!(f, "; unexpected {{{}}}", extra.join(", "))?;
+                }
+                Ok(())
+            }
+            HeaderMismatch::ClassIndex { expected, found } => {
+                write!(f, "expected class index {expected}, found {found}")
+            }
+            HeaderMismatch::Labels { expected, found } => {
+                write!(f, "expected labels {expected:?}, found {found:?}")
+            }
+        }
+    }
+}
+
+/// Result of comparing the header a model was trained on with the header of
+/// the data it is about to see: attribute names, kinds, nominal domains, the
+/// class index, and the label set.
+///
+/// Relation names are not compared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderCompatibility {
+    mismatches: Vec<HeaderMismatch>,
+}
+
+fn kind(attr: &dyn Attribute) -> &'static str {
+    let any = attr.as_any();
+    if let Some(nominal) = any.downcast_ref::<NominalAttribute>() {
+        if nominal.is_ordinal() {
+            "ordinal"
+        } else {
+            "nominal"
+        }
+    } else if any.is::<NumericAttribute>() {
+        "numeric"
+    } else {
+        "unknown"
+    }
+}
+
+impl HeaderCompatibility {
+    pub fn check(expected: &InstanceHeader, found: &InstanceHeader) -> Self {
+        let mut mismatches = Vec::new();
+        let (n_expected, n_found) = (
+            expected.number_of_attributes(),
+            found.number_of_attributes(),
+        );
+        if n_expected != n_found {
+            mismatches.push(HeaderMismatch::AttributeCount {
+                expected: n_expected,
+                found: n_found,
+            });
+        }
+
+        for (index, (e, f)) in expected
+            .attributes
+            .iter()
+            .zip(&found.attributes)
+            .enumerate()
+        {
+            let name = e.name();
+            if name != f.name() {
+                mismatches.push(HeaderMismatch::Name {
+                    index,
+                    expected: name,
+                    found: f.name(),
+                });
+                continue;
+            }
+            let (kind_e, kind_f) = (kind(e.as_ref()), kind(f.as_ref()));
+            if kind_e != kind_f {
+                mismatches.push(HeaderMismatch::Kind {
+                    index,
+                    name,
+                    expected: kind_e,
+                    found: kind_f,
+                });
+                continue;
+            }
+            let nominal = |a: &dyn Attribute| {
+                a.as_any()
+                    .downcast_ref::<NominalAttribute>()
+                    .map(|n| n.values.clone())
+            };
+            if let (Some(ve), Some(vf)) = (nominal(e.as_ref()), nominal(f.as_ref()))
+                && ve != vf
+            {
+                mismatches.push(HeaderMismatch::Domain {
+                    index,
+                    name,
+                    missing: ve.iter().filter(|v| !vf.contains(v)).cloned().collect(),
+                    extra: vf.iter().filter(|v| !ve.contains(v)).cloned().collect(),
+                });
+            }
+        }
+
+        if expected.class_index() != found.class_index() {
+            mismatches.push(HeaderMismatch::ClassIndex {
+                expected: expected.class_index(),
+                found: found.class_index(),
+            });
+        } else if expected.label_indices() != found.label_indices() {
+            mismatches.push(HeaderMismatch::Labels {
+                expected: expected.label_indices().to_vec(),
+                found: found.label_indices().to_vec(),
+            });
+        }
+
+        Self { mismatches }
+    }
+
+    pub fn is_compatible(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn mismatches(&self) -> &[HeaderMismatch] {
+        &self.mismatches
+    }
+
+    /// `Ok` when compatible, otherwise an `InvalidInput` error carrying a
+    /// [`RivuError::IncompatibleHeader`] that lists every mismatch after
+    /// `context` (e.g. `test stream`).
+    pub fn into_result(self, context: &str) -> Result<(), Error> {
+        if self.is_compatible() {
+            return Ok(());
+        }
+        Err(RivuError::IncompatibleHeader {
+            context: context.to_string(),
+            compatibility: self,
+        }
+        .into())
+    }
+}
+
+impl fmt::Display for HeaderCompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mismatches.is_empty() {
+            return f.write_str("compatible");
+        }
+        for (i, m) in self.mismatches.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{m}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::AttributeRef;
+    use std::sync::Arc;
+
+    fn nominal(name: &str, values: &[&str]) -> AttributeRef {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let mut attr = NominalAttribute::ordinal(name.into(), values);
+        attr.ordered = false;
+        Arc::new(attr)
+    }
+
+    fn header(attrs: Vec<AttributeRef>, class_index: usize) -> InstanceHeader {
+        InstanceHeader::new("r".into(), attrs, class_index)
+    }
+
+    #[test]
+    fn identical_headers_are_compatible() {
+        let h = header(
+            vec![
+                Arc::new(NumericAttribute::new("x".into())),
+                nominal("c", &["a", "b"]),
+            ],
+            1,
+        );
+        let other = InstanceHeader::new("other".into(), h.attributes.clone(), 1);
+        let check = HeaderCompatibility::check(&h, &other);
+        assert!(check.is_compatible());
+        assert!(check.into_result("stream").is_ok());
+    }
+
+    #[test]
+    fn reports_every_difference() {
+        let expected = header(
+            vec![
+                Arc::new(NumericAttribute::new("x".into())),
+                nominal("color", &["red", "green"]),
+                nominal("c", &["a", "b"]),
+                nominal("d", &["a", "b"]),
+            ],
+            2,
+        );
+        let found = header(
+            vec![
+                nominal("x", &["1"]),
+                nominal("color", &["red", "blue"]),
+                nominal("c", &["b", "a"]),
+                nominal("e", &["a", "b"]),
+                Arc::new(NumericAttribute::new("extra".into())),
+            ],
+            3,
+        );
+        let check = HeaderCompatibility::check(&expected, &found);
+        assert_eq!(
+            check.mismatches(),
+            &[
+                HeaderMismatch::AttributeCount {
+                    expected: 4,
+                    found: 5
+                },
+                HeaderMismatch::Kind {
+                    index: 0,
+                    name: "x".into(),
+                    expected: "numeric",
+                    found: "nominal"
+                },
+                HeaderMismatch::Domain {
+                    index: 1,
+                    name: "color".into(),
+                    missing: vec!["green".into()],
+                    extra: vec!["blue".into()]
+                },
+                HeaderMismatch::Domain {
+                    index: 2,
+                    name: "c".into(),
+                    missing: vec![],
+                    extra: vec![]
+                },
+                HeaderMismatch::Name {
+                    index: 3,
+                    expected: "d".into(),
+                    found: "e".into()
+                },
+                HeaderMismatch::ClassIndex {
+                    expected: 2,
+                    found: 3
+                },
+            ]
+        );
+        let err = check.into_result("test stream").unwrap_err();
+        match RivuError::of(&err) {
+            Some(RivuError::IncompatibleHeader { compatibility, .. }) => {
+                assert_eq!(compatibility.mismatches().len(), 6)
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+        let err = err.to_string();
+        assert!(err.starts_with("test stream header is incompatible: expected 4 attributes"));
+        assert!(err.contains(
+            "attribute 1 ('color'): nominal values differ; missing {green}; unexpected {blue}"
+        ));
+        assert!(err.contains("attribute 2 ('c'): nominal values differ in order"));
+    }
+}
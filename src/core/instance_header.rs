@@ -1,10 +1,34 @@
-use crate::core::attributes::{Attribute, AttributeRef, NominalAttribute};
+use crate::core::attributes::{
+    Attribute, AttributeData, AttributeRef, NominalAttribute, NumericAttribute,
+};
+use crate::error::RivuError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
 use std::fmt;
+use std::io::Error;
 
+/// What a learner predicts: a label of a nominal class (classification) or
+/// the value of a numeric one (regression).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Nominal,
+    Numeric,
+}
+
+impl fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TargetKind::Nominal => "nominal",
+            TargetKind::Numeric => "numeric",
+        })
+    }
+}
+
+#[derive(Clone)]
 pub struct InstanceHeader {
     relation_name: String,
     pub attributes: Vec<AttributeRef>,
     class_index: usize,
+    label_indices: Vec<usize>,
 }
 
 impl InstanceHeader {
@@ -17,6 +41,23 @@ impl InstanceHeader {
             relation_name,
             attributes,
             class_index,
+            label_indices: vec![class_index],
+        }
+    }
+
+    /// Header whose target is the set of attributes at `label_indices`, one
+    /// binary attribute per label. The class index is the first label, so
+    /// single-target code sees that label as the class.
+    pub fn multi_label(
+        relation_name: String,
+        attributes: Vec<AttributeRef>,
+        label_indices: Vec<usize>,
+    ) -> InstanceHeader {
+        InstanceHeader {
+            relation_name,
+            attributes,
+            class_index: label_indices.first().copied().unwrap_or(0),
+            label_indices,
         }
     }
 
@@ -53,6 +94,58 @@ impl InstanceHeader {
         self.class_index
     }
 
+    /// Indices of the target attributes; just the class index unless the
+    /// header is multi-label.
+    pub fn label_indices(&self) -> &[usize] {
+        &self.label_indices
+    }
+
+    pub fn number_of_labels(&self) -> usize {
+        self.label_indices.len()
+    }
+
+    pub fn is_multi_label(&self) -> bool {
+        self.label_indices.len() > 1
+    }
+
+    pub fn is_label(&self, index: usize) -> bool {
+        self.label_indices.contains(&index)
+    }
+
+    /// Kind of the class attribute, or `None` when the class index is out of
+    /// range or the attribute is neither nominal nor numeric.
+    pub fn target_kind(&self) -> Option<TargetKind> {
+        let class = self.attributes.get(self.class_index)?.as_any();
+        if class.is::<NominalAttribute>() {
+            Some(TargetKind::Nominal)
+        } else if class.is::<NumericAttribute>() {
+            Some(TargetKind::Numeric)
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the class index points at a nominal or numeric attribute.
+    pub fn validate_class(&self) -> Result<(), Error> {
+        let Some(class) = self.attributes.get(self.class_index) else {
+            return Err(RivuError::config(format!(
+                "class index {} out of range for {} attributes",
+                self.class_index,
+                self.attributes.len()
+            ))
+            .into());
+        };
+        if self.target_kind().is_none() {
+            return Err(RivuError::config(format!(
+                "class attribute '{}' must be nominal or numeric",
+                class.name()
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Number of class labels; 0 for a numeric class.
     pub fn number_of_classes(&self) -> usize {
         if self.class_index < self.attributes.len() {
             if let Some(nominal_attr) = self.attributes[self.class_index]
@@ -71,7 +164,129 @@ impl fmt::Debug for InstanceHeader {
         f.debug_struct("InstanceHeader")
             .field("relation_name", &self.relation_name)
             .field("class_index", &self.class_index)
+            .field("label_indices", &self.label_indices)
             .field("n_attributes", &self.attributes.len())
             .finish()
     }
 }
+
+#[derive(Serialize, Deserialize)]
+struct InstanceHeaderData {
+    relation_name: String,
+    attributes: Vec<AttributeData>,
+    class_index: usize,
+    #[serde(default)]
+    label_indices: Vec<usize>,
+}
+
+/// Serialized as the relation name, the attributes tagged with their kind
+/// (`numeric` or `nominal`), the class index, and the label indices.
+impl Serialize for InstanceHeader {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|a| AttributeData::from_attribute(a.as_ref()))
+            .collect::<Result<_, _>>()
+            .map_err(ser::Error::custom)?;
+        InstanceHeaderData {
+            relation_name: self.relation_name.clone(),
+            attributes,
+            class_index: self.class_index,
+            label_indices: self.label_indices.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InstanceHeader {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = InstanceHeaderData::deserialize(deserializer)?;
+        let width = data.attributes.len();
+        let mut label_indices = data.label_indices;
+        if label_indices.is_empty() {
+            label_indices.push(data.class_index);
+        }
+        if let Some(i) = label_indices
+            .iter()
+            .chain([&data.class_index])
+            .find(|&&i| i >= width)
+        {
+            return Err(de::Error::custom(format!(
+                "attribute index {i} out of range for {width} attributes"
+            )));
+        }
+        Ok(InstanceHeader {
+            relation_name: data.relation_name,
+            attributes: data
+                .attributes
+                .into_iter()
+                .map(AttributeData::into_attribute)
+                .collect(),
+            class_index: data.class_index,
+            label_indices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::instances::{DenseInstance, Instance};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn header() -> InstanceHeader {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NominalAttribute::ordinal(
+                "size".into(),
+                vec!["small".into(), "large".into()],
+            )),
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NominalAttribute::ordinal(
+                "a".into(),
+                vec!["0".into(), "1".into()],
+            )),
+            Arc::new(NominalAttribute::ordinal(
+                "b".into(),
+                vec!["0".into(), "1".into()],
+            )),
+        ];
+        InstanceHeader::multi_label("r".into(), attrs, vec![2, 3])
+    }
+
+    #[test]
+    fn headers_round_trip_through_json() {
+        let h = header();
+        let v = serde_json::to_value(&h).unwrap();
+        assert_eq!(v["attributes"][0]["kind"], "nominal");
+        assert_eq!(v["attributes"][0]["ordered"], true);
+        assert_eq!(v["attributes"][1], json!({"kind": "numeric", "name": "x"}));
+
+        let back: InstanceHeader = serde_json::from_value(v).unwrap();
+        assert_eq!(back.relation_name(), "r");
+        assert_eq!(back.label_indices(), &[2, 3]);
+        assert_eq!(back.class_index(), 2);
+        let size = back.attributes[0]
+            .as_any()
+            .downcast_ref::<NominalAttribute>()
+            .unwrap();
+        assert!(size.is_ordinal());
+        assert_eq!(size.label_to_index["large"], 1);
+        assert_eq!(back.target_kind(), Some(TargetKind::Nominal));
+
+        let bad = json!({"relation_name": "r", "attributes": [], "class_index": 0});
+        assert!(serde_json::from_value::<InstanceHeader>(bad).is_err());
+    }
+
+    #[test]
+    fn dense_instances_keep_missing_values_and_weights() {
+        let inst = DenseInstance::new(Arc::new(header()), vec![1.0, f64::NAN, 0.0, 1.0], 2.5);
+        let text = serde_json::to_string(&inst).unwrap();
+        let back: DenseInstance = serde_json::from_str(&text).unwrap();
+        assert_eq!(back.weight(), 2.5);
+        assert_eq!(back.values[0], 1.0);
+        assert!(back.is_missing_at_index(1).unwrap());
+        assert_eq!(back.relevant_labels(), vec![1]);
+    }
+}
@@ -1,13 +1,18 @@
 use crate::core::attributes::{Attribute, NominalAttribute, NumericAttribute};
 use crate::core::instance_header::InstanceHeader;
 use crate::core::instances::instance::Instance;
+use crate::core::instances::instance_view::InstanceView;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::io::Error;
 use std::sync::Arc;
 
+#[derive(Debug)]
 pub struct DenseInstance {
     pub header: Arc<InstanceHeader>,
     pub values: Vec<f64>,
     pub weight: f64,
+    pub id: Option<u64>,
+    pub timestamp: Option<i64>,
 }
 
 impl DenseInstance {
@@ -16,8 +21,86 @@ impl DenseInstance {
             header,
             values,
             weight,
+            id: None,
+            timestamp: None,
         }
     }
+
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Borrows the instance as a read-only [`InstanceView`].
+    pub fn view(&self) -> InstanceView<'_> {
+        InstanceView::new(&self.header, &self.values, self.weight)
+    }
+}
+
+#[derive(Serialize)]
+struct DenseInstanceRef<'a> {
+    header: &'a InstanceHeader,
+    values: Vec<Option<f64>>,
+    weight: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct DenseInstanceData {
+    header: InstanceHeader,
+    values: Vec<Option<f64>>,
+    #[serde(default = "unit_weight")]
+    weight: f64,
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    timestamp: Option<i64>,
+}
+
+fn unit_weight() -> f64 {
+    1.0
+}
+
+/// Serialized with its header, so each instance stands on its own; missing
+/// values are written as nulls.
+impl Serialize for DenseInstance {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DenseInstanceRef {
+            header: &self.header,
+            values: self
+                .values
+                .iter()
+                .map(|&v| (!v.is_nan()).then_some(v))
+                .collect(),
+            weight: self.weight,
+            id: self.id,
+            timestamp: self.timestamp,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DenseInstance {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = DenseInstanceData::deserialize(deserializer)?;
+        let values = data
+            .values
+            .into_iter()
+            .map(|v| v.unwrap_or(f64::NAN))
+            .collect();
+        let mut instance = DenseInstance::new(Arc::new(data.header), values, data.weight);
+        instance.id = data.id;
+        instance.timestamp = data.timestamp;
+        Ok(instance)
+    }
 }
 
 impl Instance for DenseInstance {
@@ -137,4 +220,16 @@ impl Instance for DenseInstance {
     fn header(&self) -> &InstanceHeader {
         &self.header
     }
+
+    fn id(&self) -> Option<u64> {
+        self.id
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    fn into_dense(self: Box<Self>) -> Option<Box<DenseInstance>> {
+        Some(self)
+    }
 }
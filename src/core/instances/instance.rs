@@ -1,8 +1,9 @@
 use crate::core::attributes::Attribute;
 use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::DenseInstance;
 use std::io::Error;
 
-pub trait Instance {
+pub trait Instance: Send + Sync {
     fn weight(&self) -> f64;
 
     fn set_weight(&mut self, new_value: f64) -> Result<(), Error>;
@@ -21,15 +22,57 @@ pub trait Instance {
 
     fn class_index(&self) -> usize;
 
+    /// The target: the label index for a nominal class, the value itself for
+    /// a numeric one. `NaN` when missing.
     fn class_value(&self) -> Option<f64>;
 
     fn set_class_value(&mut self, new_value: f64) -> Result<(), Error>;
 
     fn is_class_missing(&self) -> bool;
 
+    /// Number of class labels; 0 for a numeric class.
     fn number_of_classes(&self) -> usize;
 
     fn to_vec(&self) -> Vec<f64>;
 
     fn header(&self) -> &InstanceHeader;
+
+    /// Identifier of the source record, when the stream knows one (e.g. the
+    /// row number in a file), so that results can be joined back to it.
+    fn id(&self) -> Option<u64> {
+        None
+    }
+
+    /// When the source record was produced or received, in milliseconds
+    /// since the Unix epoch, when the stream knows it.
+    fn timestamp(&self) -> Option<i64> {
+        None
+    }
+
+    /// The instance as a boxed [`DenseInstance`], when it is one, so that its
+    /// allocations can be reused (see [`InstancePool`](crate::core::instances::InstancePool)).
+    fn into_dense(self: Box<Self>) -> Option<Box<DenseInstance>> {
+        None
+    }
+
+    /// Values of the target attributes, in the order of
+    /// [`InstanceHeader::label_indices`].
+    fn label_values(&self) -> Vec<f64> {
+        self.header()
+            .label_indices()
+            .iter()
+            .map(|&i| self.value_at_index(i).unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    /// Positions (within the label list) of the labels that are set, i.e. whose
+    /// value is the second value of their binary domain (`1`).
+    fn relevant_labels(&self) -> Vec<usize> {
+        self.label_values()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v == 1.0)
+            .map(|(i, _)| i)
+            .collect()
+    }
 }
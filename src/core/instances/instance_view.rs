@@ -0,0 +1,147 @@
+use crate::core::attributes::Attribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::instance::Instance;
+use std::io::{Error, ErrorKind};
+
+/// Read-only [`Instance`] borrowing one row of a buffer owned elsewhere, such
+/// as the rows written by
+/// [`RowGenerator::fill_rows`](crate::streams::generators::RowGenerator::fill_rows)
+/// or values decoded by a file reader.
+///
+/// Built for prediction and evaluation, which only read the instance: nothing
+/// is copied, and the setters fail instead of writing through. Use
+/// [`to_vec`](Instance::to_vec) to get an owned copy of the values.
+#[derive(Debug, Clone, Copy)]
+pub struct InstanceView<'a> {
+    header: &'a InstanceHeader,
+    values: &'a [f64],
+    weight: f64,
+}
+
+impl<'a> InstanceView<'a> {
+    pub fn new(header: &'a InstanceHeader, values: &'a [f64], weight: f64) -> Self {
+        Self {
+            header,
+            values,
+            weight,
+        }
+    }
+
+    /// Unit weight views over consecutive rows of `values`, each as wide as
+    /// the header. A trailing partial row is ignored.
+    pub fn rows(
+        header: &'a InstanceHeader,
+        values: &'a [f64],
+    ) -> impl ExactSizeIterator<Item = InstanceView<'a>> + 'a {
+        values
+            .chunks_exact(header.number_of_attributes().max(1))
+            .map(move |row| InstanceView::new(header, row, 1.0))
+    }
+
+    pub fn values(&self) -> &'a [f64] {
+        self.values
+    }
+}
+
+fn read_only() -> Error {
+    Error::new(ErrorKind::Unsupported, "Instance view is read-only")
+}
+
+impl Instance for InstanceView<'_> {
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn set_weight(&mut self, _new_value: f64) -> Result<(), Error> {
+        Err(read_only())
+    }
+
+    fn value_at_index(&self, index: usize) -> Option<f64> {
+        self.values.get(index).copied()
+    }
+
+    fn set_value_at_index(&mut self, _index: usize, _new_value: f64) -> Result<(), Error> {
+        Err(read_only())
+    }
+
+    fn is_missing_at_index(&self, index: usize) -> Result<bool, Error> {
+        self.values
+            .get(index)
+            .map(|v| v.is_nan())
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Index out of bounds"))
+    }
+
+    fn attribute_at_index(&self, index: usize) -> Option<&dyn Attribute> {
+        self.header.attribute_at_index(index)
+    }
+
+    fn index_of_attribute(&self, attribute: &dyn Attribute) -> Option<usize> {
+        self.header.index_of_attribute(&attribute.name())
+    }
+
+    fn number_of_attributes(&self) -> usize {
+        self.header.number_of_attributes()
+    }
+
+    fn class_index(&self) -> usize {
+        self.header.class_index()
+    }
+
+    fn class_value(&self) -> Option<f64> {
+        self.value_at_index(self.header.class_index())
+    }
+
+    fn set_class_value(&mut self, _new_value: f64) -> Result<(), Error> {
+        Err(read_only())
+    }
+
+    fn is_class_missing(&self) -> bool {
+        self.class_value().is_some_and(f64::is_nan)
+    }
+
+    fn number_of_classes(&self) -> usize {
+        self.header.number_of_classes()
+    }
+
+    fn to_vec(&self) -> Vec<f64> {
+        self.values.to_vec()
+    }
+
+    fn header(&self) -> &InstanceHeader {
+        self.header
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::instances::DenseInstance;
+    use crate::streams::Stream;
+    use crate::streams::generators::{RowGenerator, SeaFunction, SeaGenerator};
+    use std::sync::Arc;
+
+    #[test]
+    fn views_read_rows_like_dense_instances() {
+        let mut g = SeaGenerator::new(SeaFunction::F1, true, 10, None, 3).unwrap();
+        let mut buf = Vec::new();
+        assert_eq!(g.fill_rows(5, &mut buf), 5);
+        let header = Arc::new(g.header().clone());
+
+        let views: Vec<InstanceView> = InstanceView::rows(&header, &buf).collect();
+        assert_eq!(views.len(), 5);
+        for (view, row) in views.iter().zip(buf.chunks_exact(4)) {
+            let dense = DenseInstance::new(Arc::clone(&header), row.to_vec(), 1.0);
+            assert_eq!(view.values().as_ptr(), row.as_ptr());
+            assert_eq!(view.to_vec(), dense.to_vec());
+            assert_eq!(view.class_value(), dense.class_value());
+            assert_eq!(view.number_of_classes(), dense.number_of_classes());
+            assert_eq!(view.value_at_index(4), None);
+            assert!(view.is_missing_at_index(4).is_err());
+        }
+
+        let mut view = views[0];
+        assert!(view.set_value_at_index(0, 1.0).is_err());
+        assert!(view.set_weight(2.0).is_err());
+        assert_eq!(view.values(), &buf[..4]);
+    }
+}
@@ -1,5 +1,11 @@
 pub mod dense_instance;
 pub mod instance;
+pub mod instance_view;
+pub mod normalized_instance;
+pub mod pool;
 
 pub use dense_instance::DenseInstance;
 pub use instance::Instance;
+pub use instance_view::InstanceView;
+pub use normalized_instance::{NormalizedInstance, Scaling};
+pub use pool::InstancePool;
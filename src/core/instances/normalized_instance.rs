@@ -0,0 +1,239 @@
+use crate::core::attributes::Attribute;
+use crate::core::estimators::running_stats::{AttributeStats, HeaderStats, RunningStats};
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::instance::Instance;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+
+/// Affine transform of each attribute, `(value - offset) * factor`, such as a
+/// min-max or z-score normalization fitted on running statistics.
+///
+/// The class attribute, the labels of a multi-label header, and nominal
+/// attributes are left as they are.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scaling {
+    offsets: Vec<f64>,
+    factors: Vec<f64>,
+}
+
+impl Scaling {
+    /// Leaves every value unchanged.
+    pub fn identity(number_of_attributes: usize) -> Self {
+        Self {
+            offsets: vec![0.0; number_of_attributes],
+            factors: vec![1.0; number_of_attributes],
+        }
+    }
+
+    /// Maps the range seen for each numeric attribute to `[0, 1]`. Constant
+    /// attributes, and those with no values yet, map to 0.
+    pub fn min_max(header: &InstanceHeader, stats: &HeaderStats) -> Self {
+        Self::fit(header, stats, |s| match (s.min(), s.max()) {
+            (Some(min), Some(max)) => (min, max - min),
+            _ => (0.0, 0.0),
+        })
+    }
+
+    /// Centers each numeric attribute on its mean and divides by its
+    /// standard deviation. Constant attributes map to 0.
+    pub fn standard(header: &InstanceHeader, stats: &HeaderStats) -> Self {
+        Self::fit(header, stats, |s| (s.mean(), s.std_dev()))
+    }
+
+    fn fit(
+        header: &InstanceHeader,
+        stats: &HeaderStats,
+        offset_and_span: impl Fn(&RunningStats) -> (f64, f64),
+    ) -> Self {
+        let mut scaling = Self::identity(header.number_of_attributes());
+        for (i, attr) in stats.attributes().iter().enumerate() {
+            if header.is_label(i) || i >= scaling.offsets.len() {
+                continue;
+            }
+            if let AttributeStats::Numeric(s) = attr {
+                let (offset, span) = offset_and_span(s);
+                scaling.offsets[i] = offset;
+                scaling.factors[i] = if span > f64::EPSILON { 1.0 / span } else { 0.0 };
+            }
+        }
+        scaling
+    }
+
+    /// `value` of attribute `index` after scaling; missing values stay
+    /// missing and indices beyond the transform are unchanged.
+    #[inline]
+    pub fn apply(&self, index: usize, value: f64) -> f64 {
+        match (self.offsets.get(index), self.factors.get(index)) {
+            (Some(offset), Some(factor)) => (value - offset) * factor,
+            _ => value,
+        }
+    }
+}
+
+/// Read-only [`Instance`] that scales the values of another one as they are
+/// read, so a learner can work in normalized space without building a
+/// modified copy of every instance.
+///
+/// Everything except the values is the wrapped instance's.
+#[derive(Clone, Copy)]
+pub struct NormalizedInstance<'a> {
+    inner: &'a dyn Instance,
+    scaling: &'a Scaling,
+}
+
+impl<'a> NormalizedInstance<'a> {
+    pub fn new(inner: &'a dyn Instance, scaling: &'a Scaling) -> Self {
+        Self { inner, scaling }
+    }
+
+    pub fn inner(&self) -> &'a dyn Instance {
+        self.inner
+    }
+}
+
+fn read_only() -> Error {
+    Error::new(ErrorKind::Unsupported, "Normalized instance is read-only")
+}
+
+impl Instance for NormalizedInstance<'_> {
+    fn weight(&self) -> f64 {
+        self.inner.weight()
+    }
+
+    fn set_weight(&mut self, _new_value: f64) -> Result<(), Error> {
+        Err(read_only())
+    }
+
+    fn value_at_index(&self, index: usize) -> Option<f64> {
+        self.inner
+            .value_at_index(index)
+            .map(|v| self.scaling.apply(index, v))
+    }
+
+    fn set_value_at_index(&mut self, _index: usize, _new_value: f64) -> Result<(), Error> {
+        Err(read_only())
+    }
+
+    fn is_missing_at_index(&self, index: usize) -> Result<bool, Error> {
+        self.inner.is_missing_at_index(index)
+    }
+
+    fn attribute_at_index(&self, index: usize) -> Option<&dyn Attribute> {
+        self.inner.attribute_at_index(index)
+    }
+
+    fn index_of_attribute(&self, attribute: &dyn Attribute) -> Option<usize> {
+        self.inner.index_of_attribute(attribute)
+    }
+
+    fn number_of_attributes(&self) -> usize {
+        self.inner.number_of_attributes()
+    }
+
+    fn class_index(&self) -> usize {
+        self.inner.class_index()
+    }
+
+    fn class_value(&self) -> Option<f64> {
+        self.inner.class_value()
+    }
+
+    fn set_class_value(&mut self, _new_value: f64) -> Result<(), Error> {
+        Err(read_only())
+    }
+
+    fn is_class_missing(&self) -> bool {
+        self.inner.is_class_missing()
+    }
+
+    fn number_of_classes(&self) -> usize {
+        self.inner.number_of_classes()
+    }
+
+    fn to_vec(&self) -> Vec<f64> {
+        (0..self.inner.number_of_attributes())
+            .map(|i| self.value_at_index(i).unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    fn header(&self) -> &InstanceHeader {
+        self.inner.header()
+    }
+
+    fn id(&self) -> Option<u64> {
+        self.inner.id()
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        self.inner.timestamp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use std::sync::Arc;
+
+    fn header() -> Arc<InstanceHeader> {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NumericAttribute::new("flat".into())),
+            Arc::new(NominalAttribute::ordinal(
+                "c".into(),
+                vec!["a".into(), "b".into(), "c".into()],
+            )),
+            Arc::new(NumericAttribute::new("y".into())),
+        ];
+        Arc::new(InstanceHeader::new("r".into(), attrs, 3))
+    }
+
+    fn fitted(h: &Arc<InstanceHeader>) -> HeaderStats {
+        let mut stats = HeaderStats::new(h);
+        for x in [2.0, 4.0, 6.0] {
+            stats.update(&DenseInstance::new(
+                Arc::clone(h),
+                vec![x, 5.0, 2.0, 10.0 * x],
+                1.0,
+            ));
+        }
+        stats
+    }
+
+    #[test]
+    fn min_max_scales_numeric_features_only() {
+        let h = header();
+        let scaling = Scaling::min_max(&h, &fitted(&h));
+        let raw = DenseInstance::new(Arc::clone(&h), vec![5.0, 5.0, 2.0, 50.0], 2.0).with_id(9);
+        let norm = NormalizedInstance::new(&raw, &scaling);
+
+        assert_eq!(norm.to_vec(), vec![0.75, 0.0, 2.0, 50.0]);
+        assert_eq!(norm.class_value(), Some(50.0));
+        assert_eq!((norm.weight(), norm.id()), (2.0, Some(9)));
+        assert_eq!(raw.values[0], 5.0);
+
+        let missing = DenseInstance::new(Arc::clone(&h), vec![f64::NAN; 4], 1.0);
+        let norm = NormalizedInstance::new(&missing, &scaling);
+        assert!(norm.is_missing_at_index(0).unwrap());
+        assert!(norm.value_at_index(0).unwrap().is_nan());
+    }
+
+    #[test]
+    fn standard_scaling_gives_z_scores() {
+        let h = header();
+        let scaling = Scaling::standard(&h, &fitted(&h));
+        let raw = DenseInstance::new(Arc::clone(&h), vec![6.0, 5.0, 0.0, 0.0], 1.0);
+        let mut norm = NormalizedInstance::new(&raw, &scaling);
+        // mean 4, sample standard deviation 2
+        assert!((norm.value_at_index(0).unwrap() - 1.0).abs() < 1e-12);
+        assert_eq!(norm.value_at_index(1), Some(0.0));
+        assert!(norm.set_value_at_index(0, 1.0).is_err());
+
+        let identity = Scaling::identity(4);
+        assert_eq!(
+            NormalizedInstance::new(&raw, &identity).to_vec(),
+            raw.values
+        );
+    }
+}
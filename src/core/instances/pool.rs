@@ -0,0 +1,115 @@
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::{DenseInstance, Instance};
+use std::sync::Arc;
+
+/// Free list of [`DenseInstance`]s sharing one header, so that a stream can
+/// hand out instances without allocating a box and a value buffer for each.
+///
+/// Instances come back through [`release`](Self::release), typically from
+/// [`Stream::recycle`](crate::streams::Stream::recycle) once the task is done
+/// with them. Instances of another header, or beyond `capacity`, are dropped.
+#[derive(Debug)]
+pub struct InstancePool {
+    header: Arc<InstanceHeader>,
+    // Boxed so that handing an instance out does not allocate a new box.
+    #[allow(clippy::vec_box)]
+    free: Vec<Box<DenseInstance>>,
+    capacity: usize,
+}
+
+impl InstancePool {
+    /// Number of free instances kept when no capacity is given.
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    pub fn new(header: Arc<InstanceHeader>) -> Self {
+        Self::with_capacity(header, Self::DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(header: Arc<InstanceHeader>, capacity: usize) -> Self {
+        Self {
+            header,
+            free: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// An instance with no values, unit weight, and no id or timestamp,
+    /// reused when one is free.
+    /// Fill its `values` in header order before handing it out.
+    pub fn acquire(&mut self) -> Box<DenseInstance> {
+        match self.free.pop() {
+            Some(mut instance) => {
+                instance.values.clear();
+                instance.weight = 1.0;
+                instance.id = None;
+                instance.timestamp = None;
+                instance
+            }
+            None => {
+                let width = self.header.number_of_attributes();
+                Box::new(DenseInstance::new(
+                    Arc::clone(&self.header),
+                    Vec::with_capacity(width),
+                    1.0,
+                ))
+            }
+        }
+    }
+
+    /// Takes `instance` back for reuse.
+    pub fn release(&mut self, instance: Box<dyn Instance>) {
+        if self.free.len() >= self.capacity {
+            return;
+        }
+        if let Some(dense) = instance.into_dense()
+            && Arc::ptr_eq(&dense.header, &self.header)
+        {
+            self.free.push(dense);
+        }
+    }
+
+    pub fn header(&self) -> &Arc<InstanceHeader> {
+        &self.header
+    }
+
+    /// Instances ready to be reused.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::dummies::header_binary;
+
+    #[test]
+    fn released_instances_are_reused_and_reset() {
+        let header = header_binary();
+        let mut pool = InstancePool::with_capacity(Arc::clone(&header), 1);
+
+        let mut first = pool.acquire();
+        first.values.push(1.0);
+        first.weight = 3.0;
+        let ptr = first.values.as_ptr();
+        pool.release(first);
+        pool.release(Box::new(DenseInstance::new(
+            Arc::clone(&header),
+            vec![0.0],
+            1.0,
+        )));
+        assert_eq!(pool.available(), 1);
+
+        let again = pool.acquire();
+        assert_eq!(again.values.as_ptr(), ptr);
+        assert!(again.values.is_empty());
+        assert_eq!(again.weight, 1.0);
+
+        pool.release(Box::new(DenseInstance::new(
+            header_binary(),
+            vec![0.0],
+            1.0,
+        )));
+        assert_eq!(pool.available(), 0);
+    }
+}
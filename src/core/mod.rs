@@ -1,4 +1,7 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
 pub mod attributes;
 pub mod estimators;
+pub mod header_compatibility;
 pub mod instance_header;
 pub mod instances;
@@ -0,0 +1,282 @@
+use crate::drift::ChangeDetector;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Buckets kept per row before the two oldest are merged into the next.
+const MAX_BUCKETS: usize = 5;
+/// Instances between checks for a cut.
+const CLOCK: u64 = 32;
+/// Instances each side of a cut needs before the cut is considered.
+const MIN_SUB_WINDOW: u64 = 5;
+/// Window length below which no cut is tried.
+const MIN_WINDOW: u64 = 10;
+
+/// Sum and variance of a run of `2^row` consecutive values.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Bucket {
+    total: f64,
+    variance: f64,
+}
+
+/// ADWIN, the adaptive windowing change detector (Bifet & Gavaldà, 2007).
+///
+/// Keeps a window of the most recent values, as many as are consistent
+/// with a stationary mean. Every [`CLOCK`] inputs it tries every split of
+/// the window into an older and a newer part, and drops the older part
+/// when the two means differ by more than a bound that holds with
+/// confidence `1 - delta`. The window is stored as an exponential
+/// histogram: row `i` holds up to five buckets summarizing `2^i` values
+/// each, so memory and time per input are logarithmic in the window
+/// length.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Adwin {
+    delta: f64,
+    /// `rows[i]` holds buckets of `2^i` values, newest at the back.
+    rows: Vec<VecDeque<Bucket>>,
+    width: u64,
+    total: f64,
+    variance: f64,
+    time: u64,
+    /// Whether the last input shrank the window.
+    change: bool,
+}
+
+impl Default for Adwin {
+    fn default() -> Self {
+        Self::new(0.002)
+    }
+}
+
+impl Adwin {
+    pub fn new(delta: f64) -> Self {
+        Self {
+            delta,
+            rows: Vec::new(),
+            width: 0,
+            total: 0.0,
+            variance: 0.0,
+            time: 0,
+            change: false,
+        }
+    }
+
+    /// Adds `value` to the window. Returns whether the window shrank, that
+    /// is, whether a change in the mean was detected.
+    pub fn set_input(&mut self, value: f64) -> bool {
+        self.insert(value);
+        self.time += 1;
+        self.change = false;
+        if !self.time.is_multiple_of(CLOCK) || self.width <= MIN_WINDOW {
+            return false;
+        }
+        while self.cut_oldest() {
+            self.change = true;
+        }
+        self.change
+    }
+
+    /// Mean of the values in the window, zero when it is empty.
+    pub fn estimation(&self) -> f64 {
+        if self.width == 0 {
+            0.0
+        } else {
+            self.total / self.width as f64
+        }
+    }
+
+    /// Values in the window.
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+
+    /// Variance of the values in the window.
+    pub fn variance(&self) -> f64 {
+        if self.width == 0 {
+            0.0
+        } else {
+            self.variance / self.width as f64
+        }
+    }
+
+    /// Empties the window, keeping `delta`.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.delta);
+    }
+
+    fn insert(&mut self, value: f64) {
+        if self.rows.is_empty() {
+            self.rows.push(VecDeque::new());
+        }
+        self.rows[0].push_back(Bucket {
+            total: value,
+            variance: 0.0,
+        });
+        self.width += 1;
+        if self.width > 1 {
+            let w = self.width as f64;
+            let d = value - self.total / (w - 1.0);
+            self.variance += (w - 1.0) * d * d / w;
+        }
+        self.total += value;
+        self.compress();
+    }
+
+    /// Merges the two oldest buckets of every full row into the next row.
+    fn compress(&mut self) {
+        let mut row = 0;
+        while row < self.rows.len() && self.rows[row].len() > MAX_BUCKETS {
+            let (Some(a), Some(b)) = (self.rows[row].pop_front(), self.rows[row].pop_front())
+            else {
+                break;
+            };
+            let n = (1u64 << row) as f64;
+            let d = a.total / n - b.total / n;
+            let merged = Bucket {
+                total: a.total + b.total,
+                variance: a.variance + b.variance + n * n * d * d / (2.0 * n),
+            };
+            if row + 1 == self.rows.len() {
+                self.rows.push(VecDeque::new());
+            }
+            self.rows[row + 1].push_back(merged);
+            row += 1;
+        }
+    }
+
+    /// Looks for a split, oldest buckets first, whose two sides have
+    /// significantly different means, and drops the oldest bucket if one is
+    /// found. Returns whether it did.
+    fn cut_oldest(&mut self) -> bool {
+        let (mut n0, mut u0) = (0u64, 0.0);
+        let (mut n1, mut u1) = (self.width, self.total);
+        for row in (0..self.rows.len()).rev() {
+            let size = 1u64 << row;
+            for bucket in &self.rows[row] {
+                n0 += size;
+                n1 -= size;
+                u0 += bucket.total;
+                u1 -= bucket.total;
+                if n0 > MIN_SUB_WINDOW
+                    && n1 > MIN_SUB_WINDOW
+                    && self.is_cut(n0, n1, u0 / n0 as f64 - u1 / n1 as f64)
+                {
+                    self.drop_oldest();
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether the difference `diff` between the means of the `n0` older
+    /// and `n1` newer values exceeds the ADWIN bound.
+    fn is_cut(&self, n0: u64, n1: u64, diff: f64) -> bool {
+        let dd = (2.0 * (self.width as f64).ln() / self.delta).ln();
+        let m = 1.0 / (n0 - MIN_SUB_WINDOW + 1) as f64 + 1.0 / (n1 - MIN_SUB_WINDOW + 1) as f64;
+        let epsilon = (2.0 * m * self.variance() * dd).sqrt() + 2.0 / 3.0 * dd * m;
+        diff.abs() > epsilon
+    }
+
+    fn drop_oldest(&mut self) {
+        let Some(row) = self.rows.iter().rposition(|r| !r.is_empty()) else {
+            return;
+        };
+        let Some(bucket) = self.rows[row].pop_front() else {
+            return;
+        };
+        let size = 1u64 << row;
+        self.width -= size;
+        self.total -= bucket.total;
+        if self.width == 0 {
+            self.variance = 0.0;
+        } else {
+            let (n, w) = (size as f64, self.width as f64);
+            let d = bucket.total / n - self.total / w;
+            self.variance -= bucket.variance + n * w * d * d / (n + w);
+            self.variance = self.variance.max(0.0);
+        }
+        if self.rows[row].is_empty() {
+            self.rows.truncate(row);
+        }
+    }
+}
+
+/// ADWIN keeps adapting its window rather than restarting on a change,
+/// and has no warning zone.
+impl ChangeDetector for Adwin {
+    fn add_element(&mut self, value: f64) {
+        self.set_input(value);
+    }
+
+    fn detected_change(&self) -> bool {
+        self.change
+    }
+
+    fn detected_warning(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {
+        Adwin::reset(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Index of every input at which `adwin` reported a change.
+    fn changes(adwin: &mut Adwin, values: impl Iterator<Item = f64>) -> Vec<usize> {
+        values
+            .enumerate()
+            .filter_map(|(i, v)| adwin.set_input(v).then_some(i))
+            .collect()
+    }
+
+    #[test]
+    fn window_statistics_match_the_values() {
+        let mut adwin = Adwin::default();
+        for v in [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0] {
+            adwin.set_input(v);
+        }
+        // 7 values fit in rows of 1, 2 and 4 without a cut.
+        assert_eq!(adwin.width(), 7);
+        assert!((adwin.estimation() - 4.0).abs() < 1e-12);
+        assert!((adwin.variance() - 4.0).abs() < 1e-12);
+
+        adwin.reset();
+        assert_eq!((adwin.width(), adwin.estimation()), (0, 0.0));
+    }
+
+    #[test]
+    fn a_stationary_stream_keeps_growing_the_window() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut adwin = Adwin::default();
+        let found = changes(
+            &mut adwin,
+            (0..5000).map(|_| f64::from(rng.random_bool(0.2))),
+        );
+        assert!(found.is_empty(), "false alarms at {found:?}");
+        assert_eq!(adwin.width(), 5000);
+        // Five buckets per row at most.
+        assert!(adwin.rows.iter().all(|r| r.len() <= MAX_BUCKETS));
+        assert!((adwin.estimation() - 0.2).abs() < 0.02);
+    }
+
+    #[test]
+    fn a_jump_in_the_mean_is_detected_and_the_old_values_dropped() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut adwin = Adwin::default();
+        let values = (0..4000).map(|i| {
+            let p = if i < 2000 { 0.2 } else { 0.8 };
+            f64::from(rng.random_bool(p))
+        });
+        let found = changes(&mut adwin, values);
+        let first = *found.first().expect("no change detected");
+        assert!((2000..2300).contains(&first), "detected at {first}");
+        assert!(adwin.width() < 2500);
+        assert!((adwin.estimation() - 0.8).abs() < 0.05);
+    }
+}
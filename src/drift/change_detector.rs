@@ -0,0 +1,18 @@
+/// Online detector of changes in the distribution of a stream of values,
+/// typically the 0/1 errors of a learner's predictions.
+///
+/// The change and warning flags describe the last value added. A detector
+/// that signalled a change starts over on its own with the next value, so
+/// callers only [`reset`](Self::reset) it to discard what it has seen.
+pub trait ChangeDetector: Send {
+    fn add_element(&mut self, value: f64);
+
+    /// Whether the last value completed a change.
+    fn detected_change(&self) -> bool;
+
+    /// Whether the last value puts the stream in the warning zone, where a
+    /// change may be building up.
+    fn detected_warning(&self) -> bool;
+
+    fn reset(&mut self);
+}
@@ -0,0 +1,153 @@
+use crate::drift::ChangeDetector;
+
+/// DDM, the drift detection method (Gama et al., 2004), after MOA's `DDM`.
+///
+/// Tracks the error rate `p` of a learner and its standard deviation
+/// `s = sqrt(p (1 - p) / n)`, and remembers both where `p + s` was lowest.
+/// Once `min_instances` errors have been seen, `p + s` rising past
+/// `p_min + warning_level * s_min` is a warning and past
+/// `p_min + out_control_level * s_min` a change. Values are 1 for an
+/// error and 0 for a correct prediction.
+#[derive(Debug, Clone)]
+pub struct Ddm {
+    min_instances: u64,
+    warning_level: f64,
+    out_control_level: f64,
+    n: u64,
+    p: f64,
+    s: f64,
+    p_min: f64,
+    s_min: f64,
+    ps_min: f64,
+    change: bool,
+    warning: bool,
+}
+
+impl Default for Ddm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Ddm {
+    /// A detector with MOA's defaults: 30 instances before any signal,
+    /// warnings at 2 and changes at 3 standard deviations.
+    pub fn new() -> Self {
+        Self {
+            min_instances: 30,
+            warning_level: 2.0,
+            out_control_level: 3.0,
+            n: 1,
+            p: 1.0,
+            s: 0.0,
+            p_min: f64::MAX,
+            s_min: f64::MAX,
+            ps_min: f64::MAX,
+            change: false,
+            warning: false,
+        }
+    }
+
+    pub fn with_min_instances(mut self, n: u64) -> Self {
+        self.min_instances = n;
+        self
+    }
+
+    /// Standard deviations above the minimum that raise a warning.
+    pub fn with_warning_level(mut self, level: f64) -> Self {
+        self.warning_level = level;
+        self
+    }
+
+    /// Standard deviations above the minimum that signal a change.
+    pub fn with_out_control_level(mut self, level: f64) -> Self {
+        self.out_control_level = level;
+        self
+    }
+
+    /// Error rate since the last change.
+    pub fn estimation(&self) -> f64 {
+        self.p
+    }
+}
+
+impl ChangeDetector for Ddm {
+    fn add_element(&mut self, value: f64) {
+        if self.change {
+            self.reset();
+        }
+        self.n += 1;
+        self.p += (value - self.p) / self.n as f64;
+        self.s = (self.p * (1.0 - self.p) / self.n as f64).sqrt();
+        self.change = false;
+        self.warning = false;
+        if self.n < self.min_instances {
+            return;
+        }
+
+        if self.p + self.s <= self.ps_min {
+            self.p_min = self.p;
+            self.s_min = self.s;
+            self.ps_min = self.p + self.s;
+        }
+        if self.n > self.min_instances
+            && self.p + self.s > self.p_min + self.out_control_level * self.s_min
+        {
+            self.change = true;
+        } else if self.p + self.s > self.p_min + self.warning_level * self.s_min {
+            self.warning = true;
+        }
+    }
+
+    fn detected_change(&self) -> bool {
+        self.change
+    }
+
+    fn detected_warning(&self) -> bool {
+        self.warning
+    }
+
+    fn reset(&mut self) {
+        *self = Self {
+            min_instances: self.min_instances,
+            warning_level: self.warning_level,
+            out_control_level: self.out_control_level,
+            ..Self::new()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::tests::{errors, signals};
+
+    #[test]
+    fn a_rising_error_rate_warns_then_changes() {
+        let values = errors(&[(0.1, 2000), (0.5, 2000)], 1);
+        let mut ddm = Ddm::new();
+        let (warnings, changes) = signals(&mut ddm, values.clone());
+        assert_eq!(changes.iter().filter(|&&c| c < 2000).count(), 0);
+        let first = *changes.first().expect("no change detected");
+        assert!((2000..2200).contains(&first), "detected at {first}");
+        assert!(warnings.iter().any(|&w| (2000..first).contains(&w)));
+
+        // The detector starts over with the value after the change.
+        let mut ddm = Ddm::new();
+        for &v in &values[..=first] {
+            ddm.add_element(v);
+        }
+        assert!(ddm.detected_change());
+        ddm.add_element(0.0);
+        assert!(!ddm.detected_change());
+        assert_eq!((ddm.n, ddm.estimation()), (2, 0.5));
+    }
+
+    #[test]
+    fn a_falling_error_rate_is_not_a_change() {
+        let mut ddm = Ddm::new();
+        let (_, changes) = signals(&mut ddm, errors(&[(0.2, 2000), (0.05, 2000)], 1));
+        assert!(changes.is_empty(), "changes at {changes:?}");
+        assert!(ddm.estimation() < 0.2);
+    }
+}
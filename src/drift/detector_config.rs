@@ -0,0 +1,87 @@
+use crate::drift::{Adwin, ChangeDetector, Ddm, Eddm};
+use crate::error::RivuError;
+use serde::{Deserialize, Serialize};
+
+fn default_delta() -> f64 {
+    0.002
+}
+
+fn default_min_instances() -> u64 {
+    30
+}
+
+fn default_warning_level() -> f64 {
+    2.0
+}
+
+fn default_out_control_level() -> f64 {
+    3.0
+}
+
+/// A change detector as written in a config file, watching the 0/1 errors
+/// of a learner during an evaluation run. Omitted parameters take each
+/// detector's defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ChangeDetectorConfig {
+    /// [`Adwin`] with confidence `1 - delta`.
+    Adwin {
+        #[serde(default = "default_delta")]
+        delta: f64,
+    },
+    /// [`Ddm`], signalling `out_control_level` standard deviations above
+    /// the lowest error rate seen.
+    Ddm {
+        #[serde(default = "default_min_instances")]
+        min_instances: u64,
+        #[serde(default = "default_warning_level")]
+        warning_level: f64,
+        #[serde(default = "default_out_control_level")]
+        out_control_level: f64,
+    },
+    /// [`Eddm`], which takes no parameters.
+    Eddm,
+}
+
+impl ChangeDetectorConfig {
+    pub fn validate(&self) -> Result<(), RivuError> {
+        match *self {
+            ChangeDetectorConfig::Adwin { delta } if !(delta > 0.0 && delta < 1.0) => Err(
+                RivuError::config(format!("adwin delta must be in (0, 1), got {delta}")),
+            ),
+            ChangeDetectorConfig::Ddm {
+                warning_level,
+                out_control_level,
+                ..
+            } if !(warning_level.is_finite()
+                && warning_level > 0.0
+                && out_control_level.is_finite()
+                && out_control_level >= warning_level) =>
+            {
+                Err(RivuError::config(format!(
+                    "ddm needs 0 < warning_level <= out_control_level, got \
+                     {warning_level} and {out_control_level}"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// A fresh detector with these parameters.
+    pub fn build(&self) -> Box<dyn ChangeDetector> {
+        match *self {
+            ChangeDetectorConfig::Adwin { delta } => Box::new(Adwin::new(delta)),
+            ChangeDetectorConfig::Ddm {
+                min_instances,
+                warning_level,
+                out_control_level,
+            } => Box::new(
+                Ddm::new()
+                    .with_min_instances(min_instances)
+                    .with_warning_level(warning_level)
+                    .with_out_control_level(out_control_level),
+            ),
+            ChangeDetectorConfig::Eddm => Box::new(Eddm::new()),
+        }
+    }
+}
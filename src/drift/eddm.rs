@@ -0,0 +1,134 @@
+use crate::drift::ChangeDetector;
+
+/// Instances, and errors, seen before any signal.
+const MIN_INSTANCES: u64 = 30;
+/// Ratio to the peak below which the distance between errors is a warning.
+const WARNING_LEVEL: f64 = 0.95;
+/// Ratio to the peak below which the distance between errors is a change.
+const OUT_CONTROL_LEVEL: f64 = 0.9;
+
+/// EDDM, the early drift detection method (Baena-García et al., 2006),
+/// after MOA's `EDDM`.
+///
+/// Rather than the error rate, tracks the mean distance `d` between two
+/// consecutive errors and its standard deviation `s`, remembering the
+/// largest `d + 2s`. As a learner improves the errors spread out; when
+/// they bunch up again, `d + 2s` falling below 95% of its peak is a
+/// warning and below 90% a change. Slow, gradual drifts show up earlier
+/// this way than in the error rate. Values are 1 for an error and 0 for
+/// a correct prediction.
+#[derive(Debug, Clone)]
+pub struct Eddm {
+    n: u64,
+    errors: u64,
+    last_error: u64,
+    mean: f64,
+    m2: f64,
+    peak: f64,
+    change: bool,
+    warning: bool,
+}
+
+impl Default for Eddm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Eddm {
+    pub fn new() -> Self {
+        Self {
+            n: 1,
+            errors: 0,
+            last_error: 0,
+            mean: 0.0,
+            m2: 0.0,
+            peak: 0.0,
+            change: false,
+            warning: false,
+        }
+    }
+
+    /// Mean distance between errors since the last change.
+    pub fn estimation(&self) -> f64 {
+        self.mean
+    }
+}
+
+impl ChangeDetector for Eddm {
+    fn add_element(&mut self, value: f64) {
+        if self.change {
+            self.reset();
+        }
+        self.change = false;
+        self.warning = false;
+        self.n += 1;
+        if value != 1.0 {
+            return;
+        }
+
+        self.errors += 1;
+        let distance = (self.n - 1 - self.last_error) as f64;
+        self.last_error = self.n - 1;
+        let old_mean = self.mean;
+        self.mean += (distance - self.mean) / self.errors as f64;
+        self.m2 += (distance - self.mean) * (distance - old_mean);
+        let m2s = self.mean + 2.0 * (self.m2 / self.errors as f64).sqrt();
+        if self.n < MIN_INSTANCES {
+            return;
+        }
+
+        if m2s > self.peak {
+            if self.n > MIN_INSTANCES {
+                self.peak = m2s;
+            }
+        } else if self.errors > MIN_INSTANCES {
+            let ratio = m2s / self.peak;
+            if ratio < OUT_CONTROL_LEVEL {
+                self.change = true;
+            } else if ratio < WARNING_LEVEL {
+                self.warning = true;
+            }
+        }
+    }
+
+    fn detected_change(&self) -> bool {
+        self.change
+    }
+
+    fn detected_warning(&self) -> bool {
+        self.warning
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::tests::{errors, signals};
+
+    #[test]
+    fn errors_bunching_up_signal_a_change() {
+        let mut eddm = Eddm::new();
+        let (warnings, changes) = signals(&mut eddm, errors(&[(0.1, 3000), (0.5, 1000)], 4));
+        assert_eq!(changes.iter().filter(|&&c| c < 3000).count(), 0);
+        let first = *changes.first().expect("no change detected");
+        assert!((3000..3300).contains(&first), "detected at {first}");
+        assert!(warnings.iter().any(|&w| (3000..first).contains(&w)));
+    }
+
+    #[test]
+    fn distances_between_errors_are_averaged() {
+        let mut eddm = Eddm::new();
+        // Errors at 2, 4 and 8 instances in: distances 2, 2 and 4.
+        for v in [0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0] {
+            eddm.add_element(v);
+        }
+        assert!((eddm.estimation() - 8.0 / 3.0).abs() < 1e-12);
+        eddm.reset();
+        assert_eq!(eddm.estimation(), 0.0);
+    }
+}
@@ -0,0 +1,67 @@
+mod adwin;
+mod change_detector;
+mod ddm;
+mod detector_config;
+mod eddm;
+
+pub use adwin::Adwin;
+pub use change_detector::ChangeDetector;
+pub use ddm::Ddm;
+pub use detector_config::ChangeDetectorConfig;
+pub use eddm::Eddm;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// 0/1 errors drawn at each `(rate, count)` of `segments` in turn.
+    pub(super) fn errors(segments: &[(f64, usize)], seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        segments
+            .iter()
+            .flat_map(|&(rate, n)| std::iter::repeat_n(rate, n))
+            .map(|rate| f64::from(rng.random_bool(rate)))
+            .collect()
+    }
+
+    /// Indices of the values at which `detector` warned and signalled a
+    /// change.
+    pub(super) fn signals(
+        detector: &mut dyn ChangeDetector,
+        values: Vec<f64>,
+    ) -> (Vec<usize>, Vec<usize>) {
+        let (mut warnings, mut changes) = (Vec::new(), Vec::new());
+        for (i, v) in values.into_iter().enumerate() {
+            detector.add_element(v);
+            if detector.detected_warning() {
+                warnings.push(i);
+            }
+            if detector.detected_change() {
+                changes.push(i);
+            }
+        }
+        (warnings, changes)
+    }
+
+    #[test]
+    fn every_detector_catches_an_abrupt_rise_in_the_error_rate() {
+        let detectors: Vec<Box<dyn ChangeDetector>> = vec![
+            Box::new(Adwin::default()),
+            Box::new(Ddm::new()),
+            Box::new(Eddm::new()),
+        ];
+        for mut detector in detectors {
+            let (_, changes) = signals(detector.as_mut(), errors(&[(0.1, 3000), (0.6, 1000)], 4));
+            assert!(
+                changes.iter().all(|&c| c >= 3000),
+                "false alarms: {changes:?}"
+            );
+            assert!(changes.iter().any(|&c| c < 3300), "late: {changes:?}");
+
+            detector.reset();
+            assert!(!detector.detected_change() && !detector.detected_warning());
+        }
+    }
+}
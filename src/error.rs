@@ -0,0 +1,120 @@
+//! Crate-level error type.
+//!
+//! The stream, instance, and task traits return [`std::io::Error`], so a
+//! [`RivuError`] travels inside one: it converts into an `io::Error` of the
+//! matching [`ErrorKind`] and is recovered with [`RivuError::of`] or
+//! `RivuError::from`, which lets callers match on what went wrong instead of
+//! on the message.
+
+use crate::core::header_compatibility::HeaderCompatibility;
+use std::io::{self, ErrorKind};
+use thiserror::Error;
+
+pub type Result<T, E = RivuError> = std::result::Result<T, E>;
+
+#[derive(Debug, Error)]
+pub enum RivuError {
+    /// A stream or file held data that could not be read as instances.
+    #[error("{0}")]
+    Parse(String),
+
+    /// A parameter or combination of parameters is invalid.
+    #[error("{0}")]
+    Config(String),
+
+    /// The data a stream or model is given does not match the header it
+    /// expects.
+    #[error("{context} header is incompatible: {compatibility}")]
+    IncompatibleHeader {
+        context: String,
+        compatibility: HeaderCompatibility,
+    },
+
+    /// A learner's state cannot be built, saved, or restored.
+    #[error("{0}")]
+    Model(String),
+
+    /// The operation is not available for this learner, task, or format.
+    #[error("{0}")]
+    Unsupported(String),
+
+    #[error(transparent)]
+    Io(io::Error),
+}
+
+impl RivuError {
+    pub fn parse(message: impl Into<String>) -> Self {
+        RivuError::Parse(message.into())
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        RivuError::Config(message.into())
+    }
+
+    pub fn model(message: impl Into<String>) -> Self {
+        RivuError::Model(message.into())
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        RivuError::Unsupported(message.into())
+    }
+
+    /// The [`ErrorKind`] this error has once converted into an `io::Error`.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            RivuError::Parse(_) => ErrorKind::InvalidData,
+            RivuError::Config(_) | RivuError::IncompatibleHeader { .. } => ErrorKind::InvalidInput,
+            RivuError::Model(_) => ErrorKind::Other,
+            RivuError::Unsupported(_) => ErrorKind::Unsupported,
+            RivuError::Io(e) => e.kind(),
+        }
+    }
+
+    /// The `RivuError` carried by `err`, if it was built from one.
+    pub fn of(err: &io::Error) -> Option<&RivuError> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl From<RivuError> for io::Error {
+    fn from(err: RivuError) -> Self {
+        match err {
+            RivuError::Io(e) => e,
+            other => io::Error::new(other.kind(), other),
+        }
+    }
+}
+
+/// Unwraps a `RivuError` carried by `err`; any other error becomes
+/// [`RivuError::Io`].
+impl From<io::Error> for RivuError {
+    fn from(err: io::Error) -> Self {
+        if RivuError::of(&err).is_none() {
+            return RivuError::Io(err);
+        }
+        let inner = err.into_inner().expect("checked above");
+        *inner.downcast::<RivuError>().expect("checked above")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn survives_a_round_trip_through_io_errors() {
+        let err: io::Error = RivuError::config("k must be > 0").into();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "k must be > 0");
+        assert!(matches!(RivuError::of(&err), Some(RivuError::Config(_))));
+        assert!(matches!(RivuError::from(err), RivuError::Config(m) if m == "k must be > 0"));
+
+        let plain = io::Error::new(ErrorKind::NotFound, "no such file");
+        assert!(RivuError::of(&plain).is_none());
+        let wrapped = RivuError::from(plain);
+        assert_eq!(wrapped.kind(), ErrorKind::NotFound);
+        let back: io::Error = wrapped.into();
+        assert_eq!(back.kind(), ErrorKind::NotFound);
+        assert!(back.get_ref().is_some_and(|e| !e.is::<RivuError>()));
+    }
+}
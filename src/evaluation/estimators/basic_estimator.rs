@@ -1,9 +1,10 @@
 use crate::evaluation::estimators::Estimator;
+use serde::{Deserialize, Serialize};
 
 /// Streaming mean estimator: `mean = sum / len`.
 ///
 /// Suitable for bounded inputs (e.g., 0/1 accuracy indicators).
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct BasicEstimator {
     len: f64,
     sum: f64,
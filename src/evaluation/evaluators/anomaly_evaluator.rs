@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+
+use crate::core::instances::Instance;
+use crate::evaluation::{Measurement, MetricKey};
+
+/// Sliding-window evaluator of anomaly scores against ground-truth labels.
+///
+/// Keeps the last `window_size` `(score, is_anomaly)` pairs, where an
+/// instance is an anomaly when its class equals `positive_class`, and
+/// reports:
+/// - **mean_score**: mean score over the window (also without labels);
+/// - **roc_auc**: area under the ROC curve (ties count one half);
+/// - **average_precision**: area under the precision/recall curve;
+/// - **precision / recall / f1** of the rule `score >= threshold`.
+///
+/// Instances whose class is missing only count towards `mean_score`. The
+/// label-based metrics are `NaN` until the window holds both classes.
+pub struct AnomalyEvaluator {
+    window_size: usize,
+    positive_class: usize,
+    threshold: f64,
+    scores: VecDeque<f64>,
+    labeled: VecDeque<(f64, bool)>,
+}
+
+impl AnomalyEvaluator {
+    pub fn new(window_size: usize, positive_class: usize, threshold: f64) -> Self {
+        Self {
+            window_size: window_size.max(1),
+            positive_class,
+            threshold,
+            scores: VecDeque::new(),
+            labeled: VecDeque::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.scores.clear();
+        self.labeled.clear();
+    }
+
+    /// Whether `instance` is labeled as an anomaly, or `None` without label.
+    pub fn is_anomaly(&self, instance: &dyn Instance) -> Option<bool> {
+        if instance.is_class_missing() {
+            return None;
+        }
+        let y = instance.class_value()?;
+        y.is_finite().then_some(y as usize == self.positive_class)
+    }
+
+    pub fn add_result(&mut self, instance: &dyn Instance, score: f64) {
+        if !score.is_finite() {
+            return;
+        }
+        push_bounded(&mut self.scores, score, self.window_size);
+        if let Some(label) = self.is_anomaly(instance) {
+            push_bounded(&mut self.labeled, (score, label), self.window_size);
+        }
+    }
+
+    /// Area under the ROC curve over the labeled window.
+    pub fn roc_auc(&self) -> f64 {
+        let mut pairs: Vec<(f64, bool)> = self.labeled.iter().copied().collect();
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let positives = pairs.iter().filter(|(_, y)| *y).count() as f64;
+        let negatives = pairs.len() as f64 - positives;
+        if positives == 0.0 || negatives == 0.0 {
+            return f64::NAN;
+        }
+
+        // Mann-Whitney U with average ranks for ties.
+        let mut rank_sum = 0.0;
+        let mut i = 0;
+        while i < pairs.len() {
+            let mut j = i;
+            while j < pairs.len() && pairs[j].0 == pairs[i].0 {
+                j += 1;
+            }
+            let avg_rank = (i + 1 + j) as f64 / 2.0;
+            rank_sum += avg_rank * pairs[i..j].iter().filter(|(_, y)| *y).count() as f64;
+            i = j;
+        }
+        (rank_sum - positives * (positives + 1.0) / 2.0) / (positives * negatives)
+    }
+
+    /// Average precision (step-wise area under precision/recall) over the
+    /// labeled window.
+    pub fn average_precision(&self) -> f64 {
+        let mut pairs: Vec<(f64, bool)> = self.labeled.iter().copied().collect();
+        pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let positives = pairs.iter().filter(|(_, y)| *y).count() as f64;
+        if positives == 0.0 || positives == pairs.len() as f64 {
+            return f64::NAN;
+        }
+
+        let mut tp = 0.0;
+        let mut ap = 0.0;
+        let mut i = 0;
+        while i < pairs.len() {
+            let mut j = i;
+            let mut tp_here = 0.0;
+            while j < pairs.len() && pairs[j].0 == pairs[i].0 {
+                if pairs[j].1 {
+                    tp_here += 1.0;
+                }
+                j += 1;
+            }
+            tp += tp_here;
+            ap += tp_here / positives * (tp / j as f64);
+            i = j;
+        }
+        ap
+    }
+
+    pub fn performance(&self) -> Vec<Measurement> {
+        let mean = if self.scores.is_empty() {
+            f64::NAN
+        } else {
+            self.scores.iter().sum::<f64>() / self.scores.len() as f64
+        };
+        let mut m = vec![Measurement::new(MetricKey::MEAN_SCORE, mean)];
+        if self.labeled.is_empty() {
+            return m;
+        }
+
+        let (mut tp, mut fp, mut fneg) = (0.0, 0.0, 0.0);
+        for &(score, label) in &self.labeled {
+            match (score >= self.threshold, label) {
+                (true, true) => tp += 1.0,
+                (true, false) => fp += 1.0,
+                (false, true) => fneg += 1.0,
+                (false, false) => {}
+            }
+        }
+        let precision = ratio(tp, tp + fp);
+        let recall = ratio(tp, tp + fneg);
+        let f1 = ratio(2.0 * precision * recall, precision + recall);
+
+        m.push(Measurement::new(MetricKey::ROC_AUC, self.roc_auc()));
+        m.push(Measurement::new(
+            MetricKey::AVERAGE_PRECISION,
+            self.average_precision(),
+        ));
+        m.push(Measurement::new(MetricKey::PRECISION, precision));
+        m.push(Measurement::new(MetricKey::RECALL, recall));
+        m.push(Measurement::new(MetricKey::F1, f1));
+        m
+    }
+}
+
+fn push_bounded<T>(q: &mut VecDeque<T>, v: T, cap: usize) {
+    if q.len() == cap {
+        q.pop_front();
+    }
+    q.push_back(v);
+}
+
+fn ratio(a: f64, b: f64) -> f64 {
+    if b > 0.0 { a / b } else { f64::NAN }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::instances::DenseInstance;
+    use crate::testing::header_binary;
+
+    fn inst(label: f64) -> DenseInstance {
+        DenseInstance::new(header_binary(), vec![label], 1.0)
+    }
+
+    fn get(perf: &[Measurement], name: &str) -> Option<f64> {
+        perf.iter().find(|m| m.name == name).map(|m| m.value)
+    }
+
+    #[test]
+    fn perfect_ranking_has_unit_auc_and_ap() {
+        let mut ev = AnomalyEvaluator::new(100, 1, 0.5);
+        for (label, score) in [(0.0, 0.1), (0.0, 0.2), (1.0, 0.8), (1.0, 0.9)] {
+            ev.add_result(&inst(label), score);
+        }
+        let perf = ev.performance();
+        assert_eq!(get(&perf, "roc_auc"), Some(1.0));
+        assert_eq!(get(&perf, "average_precision"), Some(1.0));
+        assert_eq!(get(&perf, "precision"), Some(1.0));
+        assert_eq!(get(&perf, "recall"), Some(1.0));
+        assert!((get(&perf, "mean_score").unwrap() - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ties_count_half() {
+        let mut ev = AnomalyEvaluator::new(100, 1, 0.5);
+        ev.add_result(&inst(0.0), 0.5);
+        ev.add_result(&inst(1.0), 0.5);
+        assert!((ev.roc_auc() - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn window_forgets_old_results() {
+        let mut ev = AnomalyEvaluator::new(2, 1, 0.5);
+        ev.add_result(&inst(1.0), 0.0);
+        ev.add_result(&inst(0.0), 0.9);
+        assert_eq!(ev.roc_auc(), 0.0);
+        ev.add_result(&inst(1.0), 1.0);
+        assert_eq!(ev.roc_auc(), 1.0);
+    }
+
+    #[test]
+    fn unlabeled_or_single_class_windows() {
+        let mut ev = AnomalyEvaluator::new(10, 1, 0.5);
+        ev.add_result(&inst(f64::NAN), 0.3);
+        let perf = ev.performance();
+        assert_eq!(perf.len(), 1);
+        assert_eq!(get(&perf, "mean_score"), Some(0.3));
+
+        ev.add_result(&inst(0.0), 0.2);
+        assert!(ev.roc_auc().is_nan());
+        assert!(ev.average_precision().is_nan());
+    }
+}
@@ -1,34 +1,113 @@
+use crate::core::attributes::NominalAttribute;
 use crate::core::instances::Instance;
-use crate::evaluation::{Estimator, Measurement, PerformanceEvaluator};
+use crate::evaluation::evaluators::performance_evaluator::unsupported_state;
+use crate::evaluation::{
+    BasicEstimator, ConfusionMatrix, Estimator, EvaluatorState, Measurement, MetricKey,
+    PerformanceEvaluator,
+};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::io::Error;
 
 /// Basic online classifier evaluator.
 ///
 /// Tracks:
 /// - overall accuracy (`weight_correct`);
-/// - marginals of true (`row_kappa`) and predicted (`col_kappa`) classes for Cohen’s κ;
-/// - per-class precision and recall (macro-averaged in `performance()`);
+/// - per-class counts (`classes`) giving the marginals of true and predicted
+///   classes for Cohen’s κ and per-class precision and recall
+///   (macro-averaged in `performance()`);
+/// - the weighted [`ConfusionMatrix`] of true against predicted classes;
 /// - two baselines:
 ///   - **no-change** (predict last true class): `weight_correct_no_change`;
 ///   - **majority** (predict most frequent class so far): `weight_majority`.
 ///
-/// All updates are **online** and unbounded. This implementation uses
-/// simple streaming means; denominators are the number of updates
-/// (for precision/recall only when applicable to that class).
+/// All updates are **online** and unbounded, and touch only the true and
+/// predicted classes, so an update costs the same for any number of
+/// classes. This implementation uses simple streaming means; denominators
+/// are the number of updates (for precision/recall, the number of
+/// instances predicted as, or belonging to, that class).
+///
+/// The sums behind κ's chance agreement and the macro-averaged precision
+/// and recall are maintained along with the counts, so `performance()`
+/// only loops over the classes for the per-class metrics.
+///
+/// The evaluator serializes with its estimators, so a checkpointed run
+/// resumes its cumulative metrics where it stopped.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BasicClassificationEvaluator<E: Estimator + Default> {
     weight_correct: E,
-    row_kappa: Vec<E>,
-    col_kappa: Vec<E>,
-    precision: Vec<E>,
-    recall: Vec<E>,
-    num_classes: usize,
+    classes: Vec<ClassCounts>,
+    instances: f64,
     weight_correct_no_change: E,
     weight_majority: E,
     last_true_class: Option<usize>,
+    majority: Option<usize>,
+    /// Σ over classes of predicted weight × true weight.
+    chance_agreement: f64,
+    macro_precision: MacroMean,
+    macro_recall: MacroMean,
     total_weight: f64,
     show_pr_summary: bool,
     show_precision_per_class: bool,
     show_recall_per_class: bool,
     show_f1_per_class: bool,
+    #[serde(default)]
+    confusion: ConfusionMatrix,
+}
+
+/// What one class has accumulated: how many instances were predicted as it
+/// and belong to it, with their weights, and the weight predicted right.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct ClassCounts {
+    predicted: f64,
+    predicted_weight: f64,
+    actual: f64,
+    actual_weight: f64,
+    correct_weight: f64,
+}
+
+impl ClassCounts {
+    /// Weight predicted right per instance predicted as this class, `NaN`
+    /// while there is none.
+    fn precision(&self) -> f64 {
+        ratio(self.correct_weight, self.predicted)
+    }
+
+    /// Weight predicted right per instance of this class, `NaN` while there
+    /// is none.
+    fn recall(&self) -> f64 {
+        ratio(self.correct_weight, self.actual)
+    }
+}
+
+/// Running mean of a per-class metric over the classes where it is
+/// defined, updated one class at a time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct MacroMean {
+    sum: f64,
+    classes: usize,
+}
+
+impl MacroMean {
+    /// Replaces one class's value `old` (`NaN` if it was undefined) with
+    /// `new`.
+    fn replace(&mut self, old: f64, new: f64) {
+        if old.is_finite() {
+            self.sum += new - old;
+        } else {
+            self.sum += new;
+            self.classes += 1;
+        }
+    }
+
+    fn value(&self) -> f64 {
+        ratio(self.sum, self.classes as f64)
+    }
+}
+
+#[inline]
+fn ratio(num: f64, den: f64) -> f64 {
+    if den > 0.0 { num / den } else { f64::NAN }
 }
 
 impl<E: Estimator + Default> BasicClassificationEvaluator<E> {
@@ -39,22 +118,23 @@ impl<E: Estimator + Default> BasicClassificationEvaluator<E> {
         show_recall_per_class: bool,
         show_f1_per_class: bool,
     ) -> Self {
-        let make_vec = || (0..num_classes).map(|_| E::default()).collect::<Vec<_>>();
         Self {
             weight_correct: E::default(),
-            row_kappa: make_vec(),
-            col_kappa: make_vec(),
-            precision: make_vec(),
-            recall: make_vec(),
-            num_classes,
+            classes: vec![ClassCounts::default(); num_classes],
+            instances: 0.0,
             weight_correct_no_change: E::default(),
             weight_majority: E::default(),
             last_true_class: None,
+            majority: None,
+            chance_agreement: 0.0,
+            macro_precision: MacroMean::default(),
+            macro_recall: MacroMean::default(),
             total_weight: 0.0,
             show_pr_summary,
             show_precision_per_class,
             show_recall_per_class,
             show_f1_per_class,
+            confusion: ConfusionMatrix::default(),
         }
     }
 
@@ -78,57 +158,18 @@ impl<E: Estimator + Default> BasicClassificationEvaluator<E> {
         best
     }
 
-    #[inline]
-    fn majority_class(&self) -> Option<usize> {
-        let mut best = None;
-        let mut best_value = f64::NEG_INFINITY;
-        for (i, estimate) in self.col_kappa.iter().enumerate() {
-            let p = estimate.estimation();
-            if !p.is_finite() {
-                continue;
-            }
-            if best.is_none() || p > best_value {
-                best = Some(i);
-                best_value = p;
-            }
-        }
-        best
-    }
-
-    #[inline]
-    fn init_vectors(&mut self, k: usize) {
-        let make_vec = || (0..k).map(|_| E::default()).collect::<Vec<_>>();
-        self.row_kappa = make_vec();
-        self.col_kappa = make_vec();
-        self.precision = make_vec();
-        self.recall = make_vec();
-        self.num_classes = k;
-    }
-
     #[inline]
     fn ensure_initialized(&mut self, k_hint: usize) {
-        if k_hint == 0 {
-            return;
-        }
-        if self.num_classes == 0 {
-            self.init_vectors(k_hint);
-            return;
-        }
-        if k_hint > self.num_classes {
-            let add = k_hint - self.num_classes;
-            self.row_kappa.extend((0..add).map(|_| E::default()));
-            self.col_kappa.extend((0..add).map(|_| E::default()));
-            self.precision.extend((0..add).map(|_| E::default()));
-            self.recall.extend((0..add).map(|_| E::default()));
-            self.num_classes = k_hint;
+        if k_hint > self.classes.len() {
+            self.classes.resize(k_hint, ClassCounts::default());
         }
     }
 }
 
-impl<E: Estimator + Default> PerformanceEvaluator for BasicClassificationEvaluator<E> {
+impl<E: Estimator + Default + 'static> PerformanceEvaluator for BasicClassificationEvaluator<E> {
     fn reset(&mut self) {
         *self = Self::new(
-            self.num_classes,
+            self.classes.len(),
             self.show_pr_summary,
             self.show_precision_per_class,
             self.show_recall_per_class,
@@ -136,7 +177,7 @@ impl<E: Estimator + Default> PerformanceEvaluator for BasicClassificationEvaluat
         )
     }
 
-    fn add_result(&mut self, example: &dyn Instance, class_votes: Vec<f64>) {
+    fn add_result(&mut self, example: &dyn Instance, class_votes: &[f64]) {
         let Some(yf) = example.class_value() else {
             return;
         };
@@ -148,7 +189,7 @@ impl<E: Estimator + Default> PerformanceEvaluator for BasicClassificationEvaluat
         let k_hint = class_votes.len().max(y + 1);
         self.ensure_initialized(k_hint);
 
-        let Some(yhat) = Self::argmax(&class_votes) else {
+        let Some(yhat) = Self::argmax(class_votes) else {
             return;
         };
 
@@ -166,59 +207,86 @@ impl<E: Estimator + Default> PerformanceEvaluator for BasicClassificationEvaluat
                 .add(if prev == y { w } else { 0.0 });
         }
 
-        if let Some(maj) = self.majority_class() {
+        if let Some(maj) = self.majority {
             self.weight_majority.add(if maj == y { w } else { 0.0 });
         }
 
-        for (c, est) in self.row_kappa.iter_mut().enumerate() {
-            est.add(if c == yhat { w } else { 0.0 });
+        self.chance_agreement += if yhat == y {
+            let c = &self.classes[y];
+            w * (c.predicted_weight + c.actual_weight + w)
+        } else {
+            w * (self.classes[yhat].actual_weight + self.classes[y].predicted_weight)
+        };
+        let old_precision = self.classes[yhat].precision();
+        let old_recall = self.classes[y].recall();
+
+        self.instances += 1.0;
+        let predicted = &mut self.classes[yhat];
+        predicted.predicted += 1.0;
+        predicted.predicted_weight += w;
+        let actual = &mut self.classes[y];
+        actual.actual += 1.0;
+        actual.actual_weight += w;
+        if yhat == y {
+            actual.correct_weight += w;
         }
-        for (c, est) in self.col_kappa.iter_mut().enumerate() {
-            est.add(if c == y { w } else { 0.0 });
+        self.macro_precision
+            .replace(old_precision, self.classes[yhat].precision());
+        self.macro_recall
+            .replace(old_recall, self.classes[y].recall());
+
+        // Only class `y` gained weight, so it either overtakes the majority
+        // (the lowest-indexed class on ties) or leaves it unchanged.
+        let yw = self.classes[y].actual_weight;
+        let overtakes = self.majority.is_none_or(|m| {
+            let mw = self.classes[m].actual_weight;
+            yw > mw || (yw == mw && y < m)
+        });
+        if overtakes {
+            self.majority = Some(y);
         }
 
-        for (c, est) in self.precision.iter_mut().enumerate() {
-            if c == yhat {
-                est.add(if yhat == y { w } else { 0.0 });
-            } else {
-                est.add(f64::NAN);
-            }
-        }
-        for (c, est) in self.recall.iter_mut().enumerate() {
-            if c == y {
-                est.add(if yhat == y { w } else { 0.0 });
-            } else {
-                est.add(f64::NAN);
-            }
+        let header = example.header();
+        if self.confusion.labels.is_empty()
+            && let Some(class) = header
+                .attribute_at_index(header.class_index())
+                .and_then(|a| a.as_any().downcast_ref::<NominalAttribute>())
+        {
+            self.confusion.labels = class.values.clone();
         }
+        self.confusion.add(y, yhat, w);
 
         self.last_true_class = Some(y);
     }
 
+    fn confusion_matrix(&self) -> Option<&ConfusionMatrix> {
+        Some(&self.confusion)
+    }
+
+    fn snapshot_state(&self) -> Result<EvaluatorState, Error> {
+        (self as &dyn Any)
+            .downcast_ref::<BasicClassificationEvaluator<BasicEstimator>>()
+            .map(|ev| EvaluatorState::BasicClassification(ev.clone()))
+            .ok_or_else(unsupported_state)
+    }
+
     fn performance(&self) -> Vec<Measurement> {
         let mut m = vec![Measurement::new(
-            "accuracy",
+            MetricKey::ACCURACY,
             self.weight_correct.estimation(),
         )];
 
         if self.total_weight <= 0.0 {
             m.extend([
-                Measurement::new("kappa", 0.0),
-                Measurement::new("kappa_t", 0.0),
-                Measurement::new("kappa_m", 0.0),
+                Measurement::new(MetricKey::KAPPA, 0.0),
+                Measurement::new(MetricKey::KAPPA_T, 0.0),
+                Measurement::new(MetricKey::KAPPA_M, 0.0),
             ]);
             return m;
         }
 
         let p_o = self.weight_correct.estimation();
-        let mut p_e = 0.0;
-        for c in 0..self.num_classes {
-            let pt = self.row_kappa[c].estimation();
-            let pp = self.col_kappa[c].estimation();
-            if pt.is_finite() && pp.is_finite() {
-                p_e += pt * pp;
-            }
-        }
+        let p_e = self.chance_agreement / (self.instances * self.instances);
         let denom = 1.0 - p_e;
         let kappa = if denom.abs() > f64::EPSILON {
             (p_o - p_e) / denom
@@ -245,38 +313,13 @@ impl<E: Estimator + Default> PerformanceEvaluator for BasicClassificationEvaluat
             }
         };
 
-        m.push(Measurement::new("kappa", kappa));
-        m.push(Measurement::new("kappa_t", kappa_t));
-        m.push(Measurement::new("kappa_m", kappa_m));
+        m.push(Measurement::new(MetricKey::KAPPA, kappa));
+        m.push(Measurement::new(MetricKey::KAPPA_T, kappa_t));
+        m.push(Measurement::new(MetricKey::KAPPA_M, kappa_m));
 
         if self.show_pr_summary {
-            let mut p_sum = 0.0;
-            let mut p_cnt = 0usize;
-            let mut r_sum = 0.0;
-            let mut r_cnt = 0usize;
-            for c in 0..self.num_classes {
-                let p = self.precision[c].estimation();
-                if p.is_finite() {
-                    p_sum += p;
-                    p_cnt += 1;
-                }
-                let r = self.recall[c].estimation();
-                if r.is_finite() {
-                    r_sum += r;
-                    r_cnt += 1;
-                }
-            }
-
-            let macro_precision = if p_cnt > 0 {
-                p_sum / (p_cnt as f64)
-            } else {
-                f64::NAN
-            };
-            let macro_recall = if r_cnt > 0 {
-                r_sum / (r_cnt as f64)
-            } else {
-                f64::NAN
-            };
+            let macro_precision = self.macro_precision.value();
+            let macro_recall = self.macro_recall.value();
 
             let macro_f1 = {
                 let s = macro_precision + macro_recall;
@@ -287,31 +330,31 @@ impl<E: Estimator + Default> PerformanceEvaluator for BasicClassificationEvaluat
                 }
             };
 
-            m.push(Measurement::new("precision", macro_precision));
-            m.push(Measurement::new("recall", macro_recall));
-            m.push(Measurement::new("f1", macro_f1));
+            m.push(Measurement::new(MetricKey::PRECISION, macro_precision));
+            m.push(Measurement::new(MetricKey::RECALL, macro_recall));
+            m.push(Measurement::new(MetricKey::F1, macro_f1));
         }
 
         if self.show_precision_per_class {
-            for c in 0..self.num_classes {
+            for (c, counts) in self.classes.iter().enumerate() {
                 m.push(Measurement::new(
                     &format!("precision_class_{c}"),
-                    self.precision[c].estimation(),
+                    counts.precision(),
                 ));
             }
         }
         if self.show_recall_per_class {
-            for c in 0..self.num_classes {
+            for (c, counts) in self.classes.iter().enumerate() {
                 m.push(Measurement::new(
                     &format!("recall_class_{c}"),
-                    self.recall[c].estimation(),
+                    counts.recall(),
                 ));
             }
         }
         if self.show_f1_per_class {
-            for c in 0..self.num_classes {
-                let p = self.precision[c].estimation();
-                let r = self.recall[c].estimation();
+            for (c, counts) in self.classes.iter().enumerate() {
+                let p = counts.precision();
+                let r = counts.recall();
                 let s = p + r;
                 let f1 = if p.is_finite() && r.is_finite() && s > f64::EPSILON {
                     2.0 * (p * r) / s
@@ -382,7 +425,7 @@ mod tests {
         let mut ev: Eval = Eval::new(2, true, false, false, false);
 
         let i = inst(&h, 1, 1.0);
-        ev.add_result(&i, votes(1));
+        ev.add_result(&i, &votes(1));
 
         let perf = ev.performance();
         let get = |name: &str| perf.iter().find(|m| m.name == name).unwrap().value;
@@ -403,7 +446,7 @@ mod tests {
 
         // OFF
         let mut ev = Eval::new(2, false, false, false, false);
-        ev.add_result(&inst(&h, 1, 1.0), votes(1));
+        ev.add_result(&inst(&h, 1, 1.0), &votes(1));
         let perf = ev.performance();
         assert!(perf.iter().find(|m| m.name == "precision").is_none());
         assert!(perf.iter().find(|m| m.name == "recall").is_none());
@@ -411,7 +454,7 @@ mod tests {
 
         // ON
         let mut ev = Eval::new(2, true, false, false, false);
-        ev.add_result(&inst(&h, 1, 1.0), votes(1));
+        ev.add_result(&inst(&h, 1, 1.0), &votes(1));
         let perf = ev.performance();
         let get = |name: &str| perf.iter().find(|m| m.name == name).unwrap().value;
         assert!((get("precision") - 1.0).abs() < 1e-12);
@@ -425,8 +468,8 @@ mod tests {
         type Eval = BasicClassificationEvaluator<BasicEstimator>;
         let mut ev = Eval::new(2, false, true, true, true);
 
-        ev.add_result(&inst(&h, 0, 1.0), votes(0));
-        ev.add_result(&inst(&h, 1, 1.0), votes(0));
+        ev.add_result(&inst(&h, 0, 1.0), &votes(0));
+        ev.add_result(&inst(&h, 1, 1.0), &votes(0));
 
         let perf = ev.performance();
         let has = |name: &str| perf.iter().any(|m| m.name == name);
@@ -442,6 +485,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn per_class_precision_and_recall_count_only_their_class() {
+        let h = header_binary();
+        let mut ev = Eval::new(2, true, true, true, false);
+        for (y, pred) in [(0, 0), (0, 1), (1, 1), (1, 1), (0, 0)] {
+            ev.add_result(&inst(&h, y, 1.0), &votes(pred));
+        }
+        // A third class that is never predicted nor seen stays out of the
+        // macro averages.
+        ev.add_result(&inst(&h, 0, 1.0), &[1.0, 0.0, 0.0]);
+
+        let perf = ev.performance();
+        let get = |name: &str| perf.iter().find(|m| m.name == name).unwrap().value;
+        assert!((get("precision_class_0") - 1.0).abs() < 1e-12);
+        assert!((get("precision_class_1") - 2.0 / 3.0).abs() < 1e-12);
+        assert!(get("precision_class_2").is_nan());
+        assert!((get("recall_class_0") - 0.75).abs() < 1e-12);
+        assert!((get("recall_class_1") - 1.0).abs() < 1e-12);
+        assert!(get("recall_class_2").is_nan());
+        assert!((get("precision") - 5.0 / 6.0).abs() < 1e-12);
+        assert!((get("recall") - 0.875).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cached_sums_match_a_recount_of_the_classes() {
+        let h = header_binary();
+        let mut ev = Eval::new(2, true, false, false, false);
+        for i in 0..200usize {
+            let votes = [(i % 3) as f64, (i % 5) as f64, (i % 7) as f64];
+            ev.add_result(&inst(&h, i % 2, 0.5 + (i % 4) as f64), &votes);
+        }
+
+        let mean = |f: fn(&ClassCounts) -> f64| {
+            let defined: Vec<f64> = ev.classes.iter().map(f).filter(|v| v.is_finite()).collect();
+            defined.iter().sum::<f64>() / defined.len() as f64
+        };
+        let chance: f64 = ev
+            .classes
+            .iter()
+            .map(|c| c.predicted_weight * c.actual_weight)
+            .sum();
+        assert!((ev.chance_agreement - chance).abs() < 1e-9 * chance);
+        assert!((ev.macro_precision.value() - mean(ClassCounts::precision)).abs() < 1e-12);
+        assert!((ev.macro_recall.value() - mean(ClassCounts::recall)).abs() < 1e-12);
+    }
+
     #[test]
     fn single_incorrect_updates_to_zero() {
         let h = header_binary();
@@ -449,7 +538,7 @@ mod tests {
         let mut ev: Eval = Eval::new_with_default_flags(2);
 
         let i = inst(&h, 0, 1.0);
-        ev.add_result(&i, votes(1));
+        ev.add_result(&i, &votes(1));
 
         let perf = ev.performance();
         let get = |name: &str| perf.iter().find(|m| m.name == name).unwrap().value;
@@ -466,9 +555,9 @@ mod tests {
         let mut ev: Eval = Eval::new_with_default_flags(2);
 
         let i0 = inst(&h, 0, 1.0);
-        ev.add_result(&i0, votes(0)); // acerta
+        ev.add_result(&i0, &votes(0)); // acerta
         let i1 = inst(&h, 1, 1.0);
-        ev.add_result(&i1, votes(1)); // acerta
+        ev.add_result(&i1, &votes(1)); // acerta
 
         let perf = ev.performance();
         let kappa = perf.iter().find(|m| m.name == "kappa").unwrap().value;
@@ -481,9 +570,9 @@ mod tests {
         let mut ev: Eval = Eval::new_with_default_flags(2);
 
         let i0 = inst(&h, 0, 1.0);
-        ev.add_result(&i0, votes(1));
+        ev.add_result(&i0, &votes(1));
         let i1 = inst(&h, 1, 1.0);
-        ev.add_result(&i1, votes(1));
+        ev.add_result(&i1, &votes(1));
 
         let perf = ev.performance();
         let kappa = perf.iter().find(|m| m.name == "kappa").unwrap().value;
@@ -496,16 +585,44 @@ mod tests {
         let mut ev: Eval = Eval::new_with_default_flags(2);
 
         let z = inst(&h, 1, 0.0);
-        ev.add_result(&z, votes(1));
+        ev.add_result(&z, &votes(1));
 
         let x = inst(&h, 1, 1.0);
-        ev.add_result(&x, votes(1));
+        ev.add_result(&x, &votes(1));
 
         let perf = ev.performance();
         let acc = perf.iter().find(|m| m.name == "accuracy").unwrap().value;
         assert!((acc - 1.0).abs() < 1e-12);
     }
 
+    #[test]
+    fn restored_state_resumes_cumulative_metrics() {
+        let h = header_binary();
+        let stream = [(0, 0), (1, 0), (1, 1), (0, 1), (1, 1), (0, 0)];
+        let mut full: Eval = Eval::new(2, true, true, true, true);
+        let mut first: Eval = Eval::new(2, true, true, true, true);
+        for &(y, pred) in &stream[..3] {
+            full.add_result(&inst(&h, y, 1.5), &votes(pred));
+            first.add_result(&inst(&h, y, 1.5), &votes(pred));
+        }
+
+        let json = serde_json::to_string(&first.snapshot_state().unwrap()).unwrap();
+        let mut resumed = serde_json::from_str::<EvaluatorState>(&json)
+            .unwrap()
+            .into_evaluator();
+        for &(y, pred) in &stream[3..] {
+            full.add_result(&inst(&h, y, 1.5), &votes(pred));
+            resumed.add_result(&inst(&h, y, 1.5), &votes(pred));
+        }
+
+        let (a, b) = (full.performance(), resumed.performance());
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.name, b.name);
+            assert!(a.value == b.value || (a.value.is_nan() && b.value.is_nan()));
+        }
+    }
+
     #[test]
     fn reset_clears_metrics() {
         let h = header_binary();
@@ -513,7 +630,7 @@ mod tests {
         let mut ev: Eval = Eval::new_with_default_flags(2);
 
         let i = inst(&h, 1, 1.0);
-        ev.add_result(&i, votes(1));
+        ev.add_result(&i, &votes(1));
         assert!(
             ev.performance()
                 .iter()
@@ -527,4 +644,23 @@ mod tests {
         assert!(get("accuracy").is_nan());
         assert_eq!(get("kappa"), 0.0);
     }
+
+    #[test]
+    fn confusion_matrix_counts_weighted_pairs_with_class_names() {
+        let h = header_binary();
+        let mut ev: Eval = Eval::new_with_default_flags(2);
+        ev.add_result(&inst(&h, 0, 1.0), &votes(0));
+        ev.add_result(&inst(&h, 0, 2.0), &votes(1));
+        ev.add_result(&inst(&h, 1, 1.0), &votes(1));
+
+        let m = ev.confusion_matrix().unwrap();
+        assert_eq!(m.labels, vec!["A", "B"]);
+        assert_eq!(m.get(0, 0), 1.0);
+        assert_eq!(m.get(0, 1), 2.0);
+        assert_eq!(m.get(1, 1), 1.0);
+        assert_eq!(m.get(1, 0), 0.0);
+
+        ev.reset();
+        assert_eq!(ev.confusion_matrix().unwrap().total(), 0.0);
+    }
 }
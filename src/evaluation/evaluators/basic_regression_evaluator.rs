@@ -0,0 +1,150 @@
+use crate::core::instances::Instance;
+use crate::evaluation::{EvaluatorState, Measurement, MetricKey, PerformanceEvaluator};
+use serde::{Deserialize, Serialize};
+use std::io::Error;
+
+/// Basic online regression evaluator.
+///
+/// The prediction is the first entry of the votes (learners for a numeric
+/// class return a single value). Tracks weighted sums of the absolute and
+/// squared errors and of the target, and reports:
+/// - `mae` and `rmse`;
+/// - `relative_mae` and `relative_rmse`, the errors relative to always
+///   predicting the mean target seen so far;
+/// - `r2`, the coefficient of determination (`1 - relative_rmse²`).
+///
+/// Examples with a missing target or prediction are skipped.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BasicRegressionEvaluator {
+    weight_seen: f64,
+    sum_abs_error: f64,
+    sum_sq_error: f64,
+    sum_target: f64,
+    sum_sq_target: f64,
+    sum_abs_error_mean: f64,
+}
+
+impl BasicRegressionEvaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    fn ratio(num: f64, den: f64) -> f64 {
+        if den > f64::EPSILON {
+            num / den
+        } else {
+            f64::NAN
+        }
+    }
+}
+
+impl PerformanceEvaluator for BasicRegressionEvaluator {
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn snapshot_state(&self) -> Result<EvaluatorState, Error> {
+        Ok(EvaluatorState::BasicRegression(self.clone()))
+    }
+
+    fn add_result(&mut self, example: &dyn Instance, class_votes: &[f64]) {
+        let Some(y) = example.class_value().filter(|y| y.is_finite()) else {
+            return;
+        };
+        let Some(&yhat) = class_votes.first().filter(|v| v.is_finite()) else {
+            return;
+        };
+        let w = example.weight();
+        if w <= 0.0 {
+            return;
+        }
+
+        if self.weight_seen > 0.0 {
+            let mean = self.sum_target / self.weight_seen;
+            self.sum_abs_error_mean += w * (y - mean).abs();
+        } else {
+            self.sum_abs_error_mean += w * y.abs();
+        }
+
+        let err = y - yhat;
+        self.weight_seen += w;
+        self.sum_abs_error += w * err.abs();
+        self.sum_sq_error += w * err * err;
+        self.sum_target += w * y;
+        self.sum_sq_target += w * y * y;
+    }
+
+    fn performance(&self) -> Vec<Measurement> {
+        let n = self.weight_seen;
+        let mae = Self::ratio(self.sum_abs_error, n);
+        let mse = Self::ratio(self.sum_sq_error, n);
+        let mean = Self::ratio(self.sum_target, n);
+        let variance = (Self::ratio(self.sum_sq_target, n) - mean * mean).max(0.0);
+
+        let relative_mse = Self::ratio(mse, variance);
+        vec![
+            Measurement::new(MetricKey::MAE, mae),
+            Measurement::new(MetricKey::RMSE, mse.sqrt()),
+            Measurement::new(
+                MetricKey::RELATIVE_MAE,
+                Self::ratio(self.sum_abs_error, self.sum_abs_error_mean),
+            ),
+            Measurement::new(MetricKey::RELATIVE_RMSE, relative_mse.sqrt()),
+            Measurement::new(MetricKey::R2, 1.0 - relative_mse),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instance_header::InstanceHeader;
+    use crate::core::instances::DenseInstance;
+    use crate::evaluation::PerformanceEvaluatorExt;
+    use std::sync::Arc;
+
+    fn header() -> Arc<InstanceHeader> {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NumericAttribute::new("y".into())),
+        ];
+        Arc::new(InstanceHeader::new("reg".into(), attrs, 1))
+    }
+
+    fn feed(ev: &mut BasicRegressionEvaluator, pairs: &[(f64, f64)]) {
+        let h = header();
+        for &(y, yhat) in pairs {
+            let inst = DenseInstance::new(Arc::clone(&h), vec![0.0, y], 1.0);
+            ev.add_result(&inst, &[yhat]);
+        }
+    }
+
+    #[test]
+    fn errors_and_r2_match_batch_formulas() {
+        let mut ev = BasicRegressionEvaluator::new();
+        feed(&mut ev, &[(1.0, 2.0), (3.0, 3.0), (5.0, 2.0)]);
+        let mae = ev.metric("mae").unwrap();
+        let rmse = ev.metric("rmse").unwrap();
+        let r2 = ev.metric("r2").unwrap();
+        assert!((mae - 4.0 / 3.0).abs() < 1e-12);
+        assert!((rmse - (10.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        // variance of {1, 3, 5} is 8/3
+        assert!((r2 - (1.0 - (10.0 / 3.0) / (8.0 / 3.0))).abs() < 1e-12);
+        assert_eq!(ev.metric("accuracy"), None);
+    }
+
+    #[test]
+    fn skips_missing_targets_and_predictions() {
+        let mut ev = BasicRegressionEvaluator::new();
+        assert!(ev.metric("mae").unwrap().is_nan());
+        feed(&mut ev, &[(f64::NAN, 1.0), (1.0, f64::NAN), (2.0, 2.0)]);
+        let h = header();
+        ev.add_result(&DenseInstance::new(h, vec![0.0, 4.0], 1.0), &[]);
+        assert_eq!(ev.metric("mae"), Some(0.0));
+
+        ev.reset();
+        assert!(ev.metric("rmse").unwrap().is_nan());
+    }
+}
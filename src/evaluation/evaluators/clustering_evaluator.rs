@@ -0,0 +1,298 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+use crate::core::instances::Instance;
+use crate::evaluation::{Measurement, MetricKey};
+
+/// Online evaluator of clustering quality.
+///
+/// Each example is assigned to its nearest cluster center and the evaluator
+/// tracks:
+/// - **SSQ**: weighted sum of squared distances to the assigned center;
+/// - **silhouette**: mean silhouette coefficient over a bounded reservoir
+///   sample of the points seen so far (computed on demand);
+/// - **purity**: fraction of labeled weight that falls in the majority
+///   ground-truth class of its cluster (only reported when labels exist).
+///
+/// Features are every attribute except the class attribute, in header order.
+/// The class attribute, when present and not missing, is treated as the
+/// ground-truth label produced by the generator.
+pub struct ClusteringEvaluator {
+    ssq: f64,
+    total_weight: f64,
+    labeled_weight: f64,
+    contingency: BTreeMap<usize, BTreeMap<usize, f64>>,
+    sample: Vec<(Vec<f64>, usize)>,
+    sample_size: usize,
+    points_seen: u64,
+    seed: u64,
+    rng: StdRng,
+}
+
+impl ClusteringEvaluator {
+    /// Creates an evaluator that keeps at most `sample_size` points for the
+    /// silhouette estimate. `seed` drives the reservoir sampling.
+    pub fn new(sample_size: usize, seed: u64) -> Self {
+        Self {
+            ssq: 0.0,
+            total_weight: 0.0,
+            labeled_weight: 0.0,
+            contingency: BTreeMap::new(),
+            sample: Vec::with_capacity(sample_size),
+            sample_size,
+            points_seen: 0,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Clears all accumulated statistics.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.sample_size, self.seed);
+    }
+
+    /// Extracts the feature vector of `example`, skipping the class attribute.
+    pub fn features_of(example: &dyn Instance) -> Vec<f64> {
+        let class_index = example.class_index();
+        example
+            .to_vec()
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| *i != class_index)
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    /// Returns the index of the center closest to `point` and the squared
+    /// distance to it, or `None` when there are no centers.
+    pub fn nearest_center(point: &[f64], centers: &[Vec<f64>]) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        for (i, c) in centers.iter().enumerate() {
+            let d = squared_distance(point, c);
+            if !d.is_finite() {
+                continue;
+            }
+            if best.is_none_or(|(_, bd)| d < bd) {
+                best = Some((i, d));
+            }
+        }
+        best
+    }
+
+    /// Feeds one example, assigning it to its nearest center in `centers`.
+    ///
+    /// Examples with non-positive weight, or updates without any center,
+    /// are ignored.
+    pub fn add_result(&mut self, example: &dyn Instance, centers: &[Vec<f64>]) {
+        let w = example.weight();
+        if w <= 0.0 {
+            return;
+        }
+        let point = Self::features_of(example);
+        let Some((cluster, d2)) = Self::nearest_center(&point, centers) else {
+            return;
+        };
+
+        self.total_weight += w;
+        self.ssq += w * d2;
+
+        if !example.is_class_missing()
+            && let Some(y) = example.class_value()
+            && y.is_finite()
+        {
+            self.labeled_weight += w;
+            *self
+                .contingency
+                .entry(cluster)
+                .or_default()
+                .entry(y as usize)
+                .or_insert(0.0) += w;
+        }
+
+        self.points_seen += 1;
+        if self.sample.len() < self.sample_size {
+            self.sample.push((point, cluster));
+        } else if self.sample_size > 0 {
+            let j = self.rng.random_range(0..self.points_seen);
+            if (j as usize) < self.sample_size {
+                self.sample[j as usize] = (point, cluster);
+            }
+        }
+    }
+
+    /// Mean silhouette coefficient over the current sample.
+    ///
+    /// Points in singleton clusters contribute `0`. Returns `NaN` when the
+    /// sample spans fewer than two clusters.
+    pub fn silhouette(&self) -> f64 {
+        let mut by_cluster: BTreeMap<usize, usize> = BTreeMap::new();
+        for (_, c) in &self.sample {
+            *by_cluster.entry(*c).or_insert(0) += 1;
+        }
+        if by_cluster.len() < 2 {
+            return f64::NAN;
+        }
+
+        let mut total = 0.0;
+        for (i, (p, c)) in self.sample.iter().enumerate() {
+            if by_cluster[c] < 2 {
+                continue;
+            }
+            let mut sums: BTreeMap<usize, f64> = BTreeMap::new();
+            for (j, (q, cq)) in self.sample.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                *sums.entry(*cq).or_insert(0.0) += squared_distance(p, q).sqrt();
+            }
+            let a = sums.get(c).copied().unwrap_or(0.0) / (by_cluster[c] - 1) as f64;
+            let b = sums
+                .iter()
+                .filter(|(k, _)| *k != c)
+                .map(|(k, s)| s / by_cluster[k] as f64)
+                .fold(f64::INFINITY, f64::min);
+            let m = a.max(b);
+            if m > 0.0 {
+                total += (b - a) / m;
+            }
+        }
+        total / self.sample.len() as f64
+    }
+
+    /// Fraction of labeled weight assigned to the majority label of its
+    /// cluster, or `NaN` when no labeled example was seen.
+    pub fn purity(&self) -> f64 {
+        if self.labeled_weight <= 0.0 {
+            return f64::NAN;
+        }
+        let majority: f64 = self
+            .contingency
+            .values()
+            .map(|labels| labels.values().copied().fold(0.0, f64::max))
+            .sum();
+        majority / self.labeled_weight
+    }
+
+    /// Returns a snapshot of current metrics.
+    ///
+    /// `purity` is only included once at least one labeled example was seen.
+    pub fn performance(&self) -> Vec<Measurement> {
+        let mut m = vec![
+            Measurement::new(MetricKey::SSQ, self.ssq),
+            Measurement::new(MetricKey::SILHOUETTE, self.silhouette()),
+        ];
+        if self.labeled_weight > 0.0 {
+            m.push(Measurement::new(MetricKey::PURITY, self.purity()));
+        }
+        m
+    }
+}
+
+#[inline]
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
+    use crate::core::instance_header::InstanceHeader;
+    use crate::core::instances::DenseInstance;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn header_2d() -> Arc<InstanceHeader> {
+        let mut class_map = HashMap::new();
+        class_map.insert("A".into(), 0);
+        class_map.insert("B".into(), 1);
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())) as AttributeRef,
+            Arc::new(NumericAttribute::new("y".into())) as AttributeRef,
+            Arc::new(NominalAttribute::with_values(
+                "class".into(),
+                vec!["A".into(), "B".into()],
+                class_map,
+            )) as AttributeRef,
+        ];
+        Arc::new(InstanceHeader::new("clusters".into(), attrs, 2))
+    }
+
+    fn inst(h: &Arc<InstanceHeader>, x: f64, y: f64, label: f64) -> DenseInstance {
+        DenseInstance::new(Arc::clone(h), vec![x, y, label], 1.0)
+    }
+
+    fn get(perf: &[Measurement], name: &str) -> Option<f64> {
+        perf.iter().find(|m| m.name == name).map(|m| m.value)
+    }
+
+    fn centers() -> Vec<Vec<f64>> {
+        vec![vec![0.0, 0.0], vec![10.0, 10.0]]
+    }
+
+    #[test]
+    fn empty_evaluator_reports_zero_ssq_and_no_purity() {
+        let ev = ClusteringEvaluator::new(10, 1);
+        let perf = ev.performance();
+        assert_eq!(get(&perf, "ssq"), Some(0.0));
+        assert!(get(&perf, "silhouette").unwrap().is_nan());
+        assert!(get(&perf, "purity").is_none());
+    }
+
+    #[test]
+    fn ssq_accumulates_squared_distance_to_nearest_center() {
+        let h = header_2d();
+        let mut ev = ClusteringEvaluator::new(10, 1);
+        ev.add_result(&inst(&h, 1.0, 0.0, 0.0), &centers());
+        ev.add_result(&inst(&h, 10.0, 12.0, 1.0), &centers());
+        assert!((get(&ev.performance(), "ssq").unwrap() - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn purity_reflects_majority_label_per_cluster() {
+        let h = header_2d();
+        let mut ev = ClusteringEvaluator::new(10, 1);
+        ev.add_result(&inst(&h, 0.0, 0.0, 0.0), &centers());
+        ev.add_result(&inst(&h, 0.5, 0.0, 0.0), &centers());
+        ev.add_result(&inst(&h, 0.0, 0.5, 1.0), &centers());
+        ev.add_result(&inst(&h, 10.0, 10.0, 1.0), &centers());
+        assert!((ev.purity() - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn missing_labels_do_not_count_towards_purity() {
+        let h = header_2d();
+        let mut ev = ClusteringEvaluator::new(10, 1);
+        ev.add_result(&inst(&h, 0.0, 0.0, f64::NAN), &centers());
+        assert!(get(&ev.performance(), "purity").is_none());
+    }
+
+    #[test]
+    fn well_separated_clusters_have_silhouette_near_one() {
+        let h = header_2d();
+        let mut ev = ClusteringEvaluator::new(10, 1);
+        for (x, y) in [(0.0, 0.0), (0.1, 0.0), (10.0, 10.0), (10.1, 10.0)] {
+            ev.add_result(&inst(&h, x, y, 0.0), &centers());
+        }
+        assert!(ev.silhouette() > 0.99);
+    }
+
+    #[test]
+    fn reservoir_never_exceeds_sample_size() {
+        let h = header_2d();
+        let mut ev = ClusteringEvaluator::new(3, 7);
+        for i in 0..100 {
+            ev.add_result(&inst(&h, i as f64, 0.0, 0.0), &centers());
+        }
+        assert_eq!(ev.sample.len(), 3);
+    }
+
+    #[test]
+    fn no_centers_ignores_update() {
+        let h = header_2d();
+        let mut ev = ClusteringEvaluator::new(3, 7);
+        ev.add_result(&inst(&h, 1.0, 1.0, 0.0), &[]);
+        assert_eq!(ev.total_weight, 0.0);
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// Weighted counts of (true class, predicted class) pairs.
+///
+/// Rows are true classes and columns predicted ones, both indexed like the
+/// class attribute; the matrix grows to fit the largest index seen.
+/// `labels` holds the class names when they are known.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfusionMatrix {
+    pub labels: Vec<String>,
+    counts: Vec<Vec<f64>>,
+}
+
+impl ConfusionMatrix {
+    pub fn new(labels: Vec<String>) -> Self {
+        let k = labels.len();
+        Self {
+            labels,
+            counts: vec![vec![0.0; k]; k],
+        }
+    }
+
+    /// Adds `weight` to the cell of a `truth` instance predicted as
+    /// `predicted`.
+    pub fn add(&mut self, truth: usize, predicted: usize, weight: f64) {
+        let k = self.counts.len().max(truth + 1).max(predicted + 1);
+        if k > self.counts.len() {
+            for row in &mut self.counts {
+                row.resize(k, 0.0);
+            }
+            self.counts.resize(k, vec![0.0; k]);
+        }
+        self.counts[truth][predicted] += weight;
+    }
+
+    /// Number of rows (and columns).
+    pub fn num_classes(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Weight of `truth` instances predicted as `predicted`.
+    pub fn get(&self, truth: usize, predicted: usize) -> f64 {
+        self.counts
+            .get(truth)
+            .and_then(|row| row.get(predicted))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Total weight of the `truth` instances.
+    pub fn row_total(&self, truth: usize) -> f64 {
+        self.counts.get(truth).map_or(0.0, |row| row.iter().sum())
+    }
+
+    /// Total weight counted.
+    pub fn total(&self) -> f64 {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Name of class `index`, or the index itself when it has none.
+    pub fn label(&self, index: usize) -> String {
+        self.labels
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| index.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_to_fit_and_sums_rows() {
+        let mut m = ConfusionMatrix::new(vec!["a".into(), "b".into()]);
+        m.add(0, 0, 1.0);
+        m.add(0, 1, 2.0);
+        m.add(2, 1, 0.5);
+        assert_eq!(m.num_classes(), 3);
+        assert_eq!(m.get(0, 1), 2.0);
+        assert_eq!(m.get(2, 1), 0.5);
+        assert_eq!(m.get(5, 5), 0.0);
+        assert_eq!(m.row_total(0), 3.0);
+        assert_eq!(m.total(), 3.5);
+        assert_eq!(m.label(1), "b");
+        assert_eq!(m.label(2), "2");
+    }
+}
@@ -0,0 +1,29 @@
+use crate::evaluation::{
+    BasicClassificationEvaluator, BasicEstimator, BasicRegressionEvaluator, PerformanceEvaluator,
+    WindowClassificationEvaluator,
+};
+use serde::{Deserialize, Serialize};
+
+/// Serializable form of the concrete performance evaluators.
+///
+/// Tasks hold their evaluator as `Box<dyn PerformanceEvaluator>`, so
+/// checkpoints go through this enum, returned by
+/// [`PerformanceEvaluator::snapshot_state`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "evaluator", content = "state", rename_all = "kebab-case")]
+pub enum EvaluatorState {
+    BasicClassification(BasicClassificationEvaluator<BasicEstimator>),
+    BasicRegression(BasicRegressionEvaluator),
+    WindowClassification(WindowClassificationEvaluator),
+}
+
+impl EvaluatorState {
+    /// Rebuilds the boxed evaluator with the saved statistics.
+    pub fn into_evaluator(self) -> Box<dyn PerformanceEvaluator> {
+        match self {
+            Self::BasicClassification(ev) => Box::new(ev),
+            Self::BasicRegression(ev) => Box::new(ev),
+            Self::WindowClassification(ev) => Box::new(ev),
+        }
+    }
+}
@@ -1,5 +1,17 @@
+mod anomaly_evaluator;
 mod basic_classification_evaluator;
+mod basic_regression_evaluator;
+mod clustering_evaluator;
+mod confusion_matrix;
+mod evaluator_state;
 mod performance_evaluator;
+mod window_classification_evaluator;
 
+pub use anomaly_evaluator::AnomalyEvaluator;
 pub use basic_classification_evaluator::BasicClassificationEvaluator;
+pub use basic_regression_evaluator::BasicRegressionEvaluator;
+pub use clustering_evaluator::ClusteringEvaluator;
+pub use confusion_matrix::ConfusionMatrix;
+pub use evaluator_state::EvaluatorState;
 pub use performance_evaluator::{PerformanceEvaluator, PerformanceEvaluatorExt};
+pub use window_classification_evaluator::WindowClassificationEvaluator;
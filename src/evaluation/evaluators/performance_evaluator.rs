@@ -1,6 +1,8 @@
 use crate::core::instances::Instance;
-use crate::evaluation::Measurement;
+use crate::error::RivuError;
+use crate::evaluation::{ConfusionMatrix, EvaluatorState, Measurement};
 use std::collections::HashMap;
+use std::io::Error;
 
 /// Online evaluator of predictive performance.
 ///
@@ -16,10 +18,29 @@ pub trait PerformanceEvaluator {
     /// The evaluator is free to interpret/normalize the scores as needed.
     /// If the example has a missing class or the votes are unusable,
     /// the implementation may choose to skip the update.
-    fn add_result(&mut self, example: &dyn Instance, class_votes: Vec<f64>);
+    fn add_result(&mut self, example: &dyn Instance, class_votes: &[f64]);
 
     /// Returns a snapshot of current metrics.
     fn performance(&self) -> Vec<Measurement>;
+
+    /// Snapshot of the accumulated statistics, from which
+    /// [`EvaluatorState::into_evaluator`] rebuilds an evaluator that carries
+    /// on where this one stopped.
+    ///
+    /// Evaluators without persistence support return `ErrorKind::Unsupported`.
+    fn snapshot_state(&self) -> Result<EvaluatorState, Error> {
+        Err(unsupported_state())
+    }
+
+    /// Counts of true against predicted classes, for classification
+    /// evaluators that keep them. Default: none.
+    fn confusion_matrix(&self) -> Option<&ConfusionMatrix> {
+        None
+    }
+}
+
+pub(crate) fn unsupported_state() -> Error {
+    RivuError::unsupported("this evaluator does not support saving its state").into()
 }
 
 pub trait PerformanceEvaluatorExt {
@@ -0,0 +1,311 @@
+use crate::core::instances::Instance;
+use crate::evaluation::{EvaluatorState, Measurement, MetricKey, PerformanceEvaluator, argmax};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Error;
+
+/// Online classifier evaluator over a sliding window, after MOA's
+/// `WindowClassificationPerformanceEvaluator`.
+///
+/// Reports accuracy, Cohen's κ, κ_t (against the no-change baseline, which
+/// predicts the last true class) and κ_m (against the majority baseline,
+/// which predicts the most frequent true class of the window) over the
+/// last `window_size` instances only, so the metrics follow the learner
+/// through concept drifts instead of averaging over the whole run.
+///
+/// The window keeps one entry per instance and running sums of its
+/// weights, so an update costs the same for any window size: the newest
+/// instance is added to the sums and, once the window is full, the oldest
+/// is subtracted. The baselines' predictions are taken when an instance
+/// arrives and leave the window with it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WindowClassificationEvaluator {
+    window_size: usize,
+    window: VecDeque<Entry>,
+    weight: f64,
+    correct_weight: f64,
+    predicted_weight: Vec<f64>,
+    actual_weight: Vec<f64>,
+    no_change: Baseline,
+    majority: Baseline,
+    last_true_class: Option<usize>,
+}
+
+/// One instance of the window.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct Entry {
+    weight: f64,
+    actual: usize,
+    predicted: usize,
+    /// Whether each baseline was right, `None` when it had no prediction.
+    no_change: Option<bool>,
+    majority: Option<bool>,
+}
+
+/// Weight a baseline predicted, and predicted right, in the window.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+struct Baseline {
+    weight: f64,
+    correct_weight: f64,
+}
+
+impl Baseline {
+    fn update(&mut self, correct: Option<bool>, w: f64) {
+        if let Some(correct) = correct {
+            self.weight += w;
+            if correct {
+                self.correct_weight += w;
+            }
+        }
+    }
+
+    fn accuracy(&self) -> f64 {
+        ratio(self.correct_weight, self.weight)
+    }
+}
+
+#[inline]
+fn ratio(num: f64, den: f64) -> f64 {
+    if den > 0.0 { num / den } else { f64::NAN }
+}
+
+/// κ-style statistic of accuracy `p_o` against a reference accuracy.
+#[inline]
+fn kappa_against(p_o: f64, reference: f64) -> f64 {
+    let d = 1.0 - reference;
+    if d.abs() > f64::EPSILON {
+        (p_o - reference) / d
+    } else {
+        f64::NAN
+    }
+}
+
+impl WindowClassificationEvaluator {
+    /// An evaluator over the last `window_size` instances, at least one.
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            window_size: window_size.max(1),
+            window: VecDeque::new(),
+            weight: 0.0,
+            correct_weight: 0.0,
+            predicted_weight: Vec::new(),
+            actual_weight: Vec::new(),
+            no_change: Baseline::default(),
+            majority: Baseline::default(),
+            last_true_class: None,
+        }
+    }
+
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Instances currently in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// Adds `entry`'s weight to the sums, or removes it with `sign` -1.
+    fn apply(&mut self, entry: &Entry, sign: f64) {
+        let w = sign * entry.weight;
+        self.weight += w;
+        if entry.predicted == entry.actual {
+            self.correct_weight += w;
+        }
+        self.predicted_weight[entry.predicted] += w;
+        self.actual_weight[entry.actual] += w;
+        self.no_change.update(entry.no_change, w);
+        self.majority.update(entry.majority, w);
+    }
+}
+
+impl PerformanceEvaluator for WindowClassificationEvaluator {
+    fn reset(&mut self) {
+        *self = Self::new(self.window_size);
+    }
+
+    fn add_result(&mut self, example: &dyn Instance, class_votes: &[f64]) {
+        let Some(yf) = example.class_value() else {
+            return;
+        };
+        if !yf.is_finite() {
+            return;
+        }
+        let y = yf as usize;
+        let Some(yhat) = argmax(class_votes) else {
+            return;
+        };
+        let w = example.weight();
+        if w <= 0.0 {
+            return;
+        }
+
+        let k = class_votes.len().max(y + 1);
+        if k > self.actual_weight.len() {
+            self.actual_weight.resize(k, 0.0);
+            self.predicted_weight.resize(k, 0.0);
+        }
+        // The majority baseline predicts from the window before this
+        // instance joins it.
+        let majority = (!self.window.is_empty())
+            .then(|| argmax(&self.actual_weight))
+            .flatten();
+        let entry = Entry {
+            weight: w,
+            actual: y,
+            predicted: yhat,
+            no_change: self.last_true_class.map(|c| c == y),
+            majority: majority.map(|c| c == y),
+        };
+        self.apply(&entry, 1.0);
+        self.window.push_back(entry);
+        if self.window.len() > self.window_size
+            && let Some(oldest) = self.window.pop_front()
+        {
+            self.apply(&oldest, -1.0);
+        }
+        self.last_true_class = Some(y);
+    }
+
+    fn snapshot_state(&self) -> Result<EvaluatorState, Error> {
+        Ok(EvaluatorState::WindowClassification(self.clone()))
+    }
+
+    fn performance(&self) -> Vec<Measurement> {
+        let p_o = ratio(self.correct_weight, self.weight);
+        let mut m = vec![Measurement::new(MetricKey::ACCURACY, p_o)];
+        if self.window.is_empty() {
+            m.extend([
+                Measurement::new(MetricKey::KAPPA, 0.0),
+                Measurement::new(MetricKey::KAPPA_T, 0.0),
+                Measurement::new(MetricKey::KAPPA_M, 0.0),
+            ]);
+            return m;
+        }
+
+        let p_e = self
+            .predicted_weight
+            .iter()
+            .zip(&self.actual_weight)
+            .map(|(p, a)| (p / self.weight) * (a / self.weight))
+            .sum::<f64>();
+        m.push(Measurement::new(MetricKey::KAPPA, kappa_against(p_o, p_e)));
+        m.push(Measurement::new(
+            MetricKey::KAPPA_T,
+            kappa_against(p_o, self.no_change.accuracy()),
+        ));
+        m.push(Measurement::new(
+            MetricKey::KAPPA_M,
+            kappa_against(p_o, self.majority.accuracy()),
+        ));
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
+    use crate::core::instance_header::InstanceHeader;
+    use crate::core::instances::DenseInstance;
+    use crate::evaluation::{
+        BasicClassificationEvaluator, BasicEstimator, PerformanceEvaluatorExt,
+    };
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn header() -> Arc<InstanceHeader> {
+        let values: Vec<String> = vec!["a".into(), "b".into(), "c".into()];
+        let map: HashMap<String, usize> = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.clone(), i))
+            .collect();
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NominalAttribute::with_values("class".into(), values, map)),
+        ];
+        Arc::new(InstanceHeader::new("w".into(), attrs, 1))
+    }
+
+    fn add(ev: &mut dyn PerformanceEvaluator, header: &Arc<InstanceHeader>, y: usize, yhat: usize) {
+        let instance = DenseInstance::new(header.clone(), vec![0.0, y as f64], 1.0);
+        let mut votes = vec![0.0; 3];
+        votes[yhat] = 1.0;
+        ev.add_result(&instance, &votes);
+    }
+
+    #[test]
+    fn a_window_larger_than_the_stream_matches_the_cumulative_evaluator() {
+        let header = header();
+        let mut window = WindowClassificationEvaluator::new(1_000);
+        let mut basic = BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(3);
+        let pairs = [
+            (0, 0),
+            (1, 1),
+            (1, 0),
+            (2, 2),
+            (0, 0),
+            (2, 1),
+            (1, 1),
+            (0, 2),
+        ];
+        for (y, yhat) in pairs {
+            add(&mut window, &header, y, yhat);
+            add(&mut basic, &header, y, yhat);
+        }
+        for key in ["accuracy", "kappa", "kappa_t", "kappa_m"] {
+            let (w, b) = (window.metric(key).unwrap(), basic.metric(key).unwrap());
+            assert!((w - b).abs() < 1e-12, "{key}: window {w}, cumulative {b}");
+        }
+    }
+
+    #[test]
+    fn old_instances_leave_the_window() {
+        let header = header();
+        let mut ev = WindowClassificationEvaluator::new(4);
+        assert_eq!(ev.metric("kappa"), Some(0.0));
+        assert!(ev.metric("accuracy").unwrap().is_nan());
+
+        for _ in 0..10 {
+            add(&mut ev, &header, 0, 1);
+        }
+        assert_eq!(ev.metric("accuracy"), Some(0.0));
+        // Four right predictions push every mistake out.
+        for y in [0, 1, 2, 0] {
+            add(&mut ev, &header, y, y);
+        }
+        assert_eq!(ev.len(), 4);
+        assert_eq!(ev.metric("accuracy"), Some(1.0));
+        assert_eq!(ev.metric("kappa"), Some(1.0));
+        // No-change was right on the first of them only, the majority
+        // (class 0 throughout) on the first and the last.
+        assert_eq!(ev.metric("kappa_t"), Some(1.0));
+        assert_eq!(ev.metric("kappa_m"), Some(1.0));
+        assert!((ev.majority.accuracy() - 0.5).abs() < 1e-12);
+
+        ev.reset();
+        assert!(ev.is_empty());
+        assert_eq!(ev.window_size(), 4);
+    }
+
+    #[test]
+    fn the_state_round_trips_and_keeps_sliding() {
+        let header = header();
+        let mut ev = WindowClassificationEvaluator::new(3);
+        for (y, yhat) in [(0, 0), (1, 0), (2, 2)] {
+            add(&mut ev, &header, y, yhat);
+        }
+        let json = serde_json::to_string(&ev.snapshot_state().unwrap()).unwrap();
+        let state: EvaluatorState = serde_json::from_str(&json).unwrap();
+        let mut restored = state.into_evaluator();
+        add(restored.as_mut(), &header, 1, 1);
+        add(&mut ev, &header, 1, 1);
+        assert_eq!(restored.performance(), ev.performance());
+        assert!((ev.metric("accuracy").unwrap() - 2.0 / 3.0).abs() < 1e-12);
+    }
+}
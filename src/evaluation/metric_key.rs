@@ -0,0 +1,223 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::ops::Deref;
+use std::sync::{Mutex, OnceLock};
+
+/// Name of a metric: a [`Measurement`](crate::evaluation::Measurement)
+/// name or a [`Snapshot`](crate::evaluation::Snapshot) extras key.
+///
+/// A key is a copyable `&'static str`. The metrics Rivu reports are
+/// constants; any other name (per-class metrics, `_std` columns, names read
+/// back from a file) is interned the first time [`new`](Self::new) sees it,
+/// so measuring, cloning snapshots, and looking keys up never allocate.
+/// Keys compare, order, hash, and serialize as their names, and a
+/// `BTreeMap<MetricKey, _>` can be queried with a plain `&str`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MetricKey(&'static str);
+
+impl MetricKey {
+    pub const ACCURACY: Self = Self("accuracy");
+    pub const KAPPA: Self = Self("kappa");
+    pub const KAPPA_T: Self = Self("kappa_t");
+    pub const KAPPA_M: Self = Self("kappa_m");
+    pub const PRECISION: Self = Self("precision");
+    pub const RECALL: Self = Self("recall");
+    pub const F1: Self = Self("f1");
+    pub const MAE: Self = Self("mae");
+    pub const RELATIVE_MAE: Self = Self("relative_mae");
+    pub const RMSE: Self = Self("rmse");
+    pub const RELATIVE_RMSE: Self = Self("relative_rmse");
+    pub const R2: Self = Self("r2");
+    pub const ROC_AUC: Self = Self("roc_auc");
+    pub const AVERAGE_PRECISION: Self = Self("average_precision");
+    pub const MEAN_SCORE: Self = Self("mean_score");
+    pub const SSQ: Self = Self("ssq");
+    pub const SILHOUETTE: Self = Self("silhouette");
+    pub const PURITY: Self = Self("purity");
+    pub const CLUSTERS: Self = Self("clusters");
+    pub const MICRO_CLUSTERS: Self = Self("micro_clusters");
+    pub const PREDICT_SECONDS: Self = Self("predict_seconds");
+    pub const TRAIN_SECONDS: Self = Self("train_seconds");
+    pub const INSTANCES_PER_SECOND: Self = Self("instances_per_second");
+    pub const WINDOW_INSTANCES_PER_SECOND: Self = Self("window_instances_per_second");
+    pub const MODEL_TRAINING_WEIGHT: Self = Self("model_training_weight");
+    pub const MODEL_BYTES: Self = Self("model_bytes");
+    pub const TREE_SIZE_NODES: Self = Self("tree_size_nodes");
+    pub const TREE_SIZE_LEAVES: Self = Self("tree_size_leaves");
+    pub const ACTIVE_LEAVES: Self = Self("active_leaves");
+
+    const KNOWN: [Self; 29] = [
+        Self::ACCURACY,
+        Self::KAPPA,
+        Self::KAPPA_T,
+        Self::KAPPA_M,
+        Self::PRECISION,
+        Self::RECALL,
+        Self::F1,
+        Self::MAE,
+        Self::RELATIVE_MAE,
+        Self::RMSE,
+        Self::RELATIVE_RMSE,
+        Self::R2,
+        Self::ROC_AUC,
+        Self::AVERAGE_PRECISION,
+        Self::MEAN_SCORE,
+        Self::SSQ,
+        Self::SILHOUETTE,
+        Self::PURITY,
+        Self::CLUSTERS,
+        Self::MICRO_CLUSTERS,
+        Self::PREDICT_SECONDS,
+        Self::TRAIN_SECONDS,
+        Self::INSTANCES_PER_SECOND,
+        Self::WINDOW_INSTANCES_PER_SECOND,
+        Self::MODEL_TRAINING_WEIGHT,
+        Self::MODEL_BYTES,
+        Self::TREE_SIZE_NODES,
+        Self::TREE_SIZE_LEAVES,
+        Self::ACTIVE_LEAVES,
+    ];
+
+    /// Key for a name known at compile time, without interning.
+    pub const fn from_static(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    /// Key for any name: a constant when it is one of Rivu's metrics,
+    /// otherwise the interned copy of `name`, made on first use.
+    pub fn new(name: &str) -> Self {
+        if let Some(known) = Self::KNOWN.iter().find(|k| k.0 == name) {
+            return *known;
+        }
+        static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+        let mut interned = INTERNED
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(s) = interned.get(name) {
+            return Self(s);
+        }
+        let s: &'static str = Box::leak(name.into());
+        interned.insert(s);
+        Self(s)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Deref for MetricKey {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl Borrow<str> for MetricKey {
+    fn borrow(&self) -> &str {
+        self.0
+    }
+}
+
+impl AsRef<str> for MetricKey {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl From<&str> for MetricKey {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for MetricKey {
+    fn from(name: String) -> Self {
+        Self::new(&name)
+    }
+}
+
+impl From<&String> for MetricKey {
+    fn from(name: &String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<MetricKey> for String {
+    fn from(key: MetricKey) -> Self {
+        key.0.to_string()
+    }
+}
+
+impl PartialEq<str> for MetricKey {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for MetricKey {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Display for MetricKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.0)
+    }
+}
+
+impl Debug for MetricKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(self.0, f)
+    }
+}
+
+impl Serialize for MetricKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for MetricKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(Self::new(&name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn known_names_are_the_constants_and_others_are_interned_once() {
+        assert_eq!(MetricKey::new("kappa_t"), MetricKey::KAPPA_T);
+        let a = MetricKey::new(&format!("f1_class_{}", 3));
+        let b = MetricKey::from(String::from("f1_class_3"));
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+        assert_eq!(a, "f1_class_3");
+        assert_eq!(a.to_string(), "f1_class_3");
+    }
+
+    #[test]
+    fn keys_order_and_look_up_like_strings() {
+        let map = BTreeMap::from([
+            (MetricKey::RECALL, 0.5),
+            (MetricKey::new("accuracy_std"), 0.1),
+        ]);
+        assert_eq!(map.get("recall"), Some(&0.5));
+        let keys: Vec<&str> = map.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, vec!["accuracy_std", "recall"]);
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"{"accuracy_std":0.1,"recall":0.5}"#);
+        let back: BTreeMap<MetricKey, f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, map);
+    }
+}
@@ -1,10 +1,27 @@
 mod estimators;
 mod evaluators;
 mod measurement;
+mod metric_key;
 mod preview;
 
 pub use estimators::{BasicEstimator, Estimator};
-pub use evaluators::{BasicClassificationEvaluator, PerformanceEvaluator, PerformanceEvaluatorExt};
+pub use evaluators::{
+    AnomalyEvaluator, BasicClassificationEvaluator, BasicRegressionEvaluator, ClusteringEvaluator,
+    ConfusionMatrix, EvaluatorState, PerformanceEvaluator, PerformanceEvaluatorExt,
+    WindowClassificationEvaluator,
+};
 pub use measurement::Measurement;
-pub use preview::learning_curve::LearningCurve;
+pub use metric_key::MetricKey;
+pub use preview::curve_changes::MetricChange;
+pub use preview::curve_checkpoint::CurveCheckpoint;
+pub use preview::curve_comparison::{AlignedPoint, CurveComparison, TestOutcome};
+pub use preview::curve_plot::{CurvePlot, PlotFormat};
+pub use preview::drift_alignment::DriftAlignment;
+pub use preview::eta::Eta;
+pub use preview::learning_curve::{CurveFormat, LearningCurve};
+pub(crate) use preview::prediction_log::argmax;
+pub use preview::prediction_log::{PredictionLog, PredictionLogFormat};
+pub use preview::river_results::{RiverResults, RiverTrack};
+pub use preview::run_report::RunReport;
+pub use preview::score_log::ScoreLog;
 pub use preview::snapshot::Snapshot;
@@ -0,0 +1,78 @@
+use crate::evaluation::LearningCurve;
+
+/// Change of one metric between two consecutive snapshots of a curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricChange {
+    /// Index of the later snapshot in the curve.
+    pub index: usize,
+    pub instances_seen: u64,
+    pub from: f64,
+    pub to: f64,
+}
+
+impl MetricChange {
+    pub fn delta(&self) -> f64 {
+        self.to - self.from
+    }
+
+    /// Up to `n` changes of `metric` with the largest magnitude, largest
+    /// first (ties keep curve order). Steps touching a non-finite value are
+    /// skipped.
+    pub fn largest(curve: &LearningCurve, metric: &str, n: usize) -> Vec<MetricChange> {
+        let snapshots = curve.as_slice();
+        let mut changes: Vec<MetricChange> = snapshots
+            .windows(2)
+            .enumerate()
+            .filter_map(|(i, w)| {
+                let from = w[0].metric(metric)?;
+                let to = w[1].metric(metric)?;
+                (from.is_finite() && to.is_finite()).then_some(MetricChange {
+                    index: i + 1,
+                    instances_seen: w[1].instances_seen,
+                    from,
+                    to,
+                })
+            })
+            .collect();
+        changes.sort_by(|a, b| b.delta().abs().total_cmp(&a.delta().abs()));
+        changes.truncate(n);
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Snapshot;
+    use std::collections::BTreeMap;
+
+    fn curve(acc: &[f64]) -> LearningCurve {
+        let mut lc = LearningCurve::default();
+        for (i, &a) in acc.iter().enumerate() {
+            lc.push(Snapshot {
+                instances_seen: (i as u64 + 1) * 100,
+                accuracy: a,
+                kappa: f64::NAN,
+                ram_hours: 0.0,
+                seconds: 0.0,
+                extras: BTreeMap::new(),
+                eta: None,
+            });
+        }
+        lc
+    }
+
+    #[test]
+    fn largest_changes_come_first_and_skip_gaps() {
+        let lc = curve(&[0.5, 0.52, 0.3, f64::NAN, 0.9, 0.91]);
+        let top = MetricChange::largest(&lc, "accuracy", 2);
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].index, top[0].instances_seen), (2, 300));
+        assert!((top[0].delta() + 0.22).abs() < 1e-12);
+        assert_eq!(top[1].index, 1);
+
+        assert_eq!(MetricChange::largest(&lc, "accuracy", 10).len(), 3);
+        assert!(MetricChange::largest(&lc, "kappa", 10).is_empty());
+        assert!(MetricChange::largest(&lc, "missing", 10).is_empty());
+    }
+}
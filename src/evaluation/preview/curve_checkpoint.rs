@@ -0,0 +1,172 @@
+use crate::error::RivuError;
+use crate::evaluation::{LearningCurve, Snapshot};
+use std::fs::{File, OpenOptions};
+use std::io::{Error, Read, Write};
+use std::path::Path;
+
+/// Append-only JSONL file of learning-curve snapshots.
+///
+/// Each snapshot is written as one line in its lossless serde form (see
+/// [`Snapshot`]) with a single write, so a run that crashes keeps every
+/// snapshot taken before the crash. [`resume`](Self::resume) reads such a
+/// file back and keeps appending to it; a last line cut off mid-write is
+/// dropped from the file.
+///
+/// Attach one to a curve with [`LearningCurve::checkpoint_to`].
+#[derive(Debug)]
+pub struct CurveCheckpoint {
+    file: File,
+    /// First write error, after which nothing more is written.
+    error: Option<Error>,
+}
+
+impl CurveCheckpoint {
+    /// Starts an empty checkpoint at `path`, replacing any file there.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(Self {
+            file: File::create(path)?,
+            error: None,
+        })
+    }
+
+    /// Reopens the checkpoint at `path` for appending, returning the curve it
+    /// holds so far.
+    pub fn resume<P: AsRef<Path>>(path: P) -> Result<(Self, LearningCurve), Error> {
+        let mut file = OpenOptions::new().read(true).append(true).open(path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let (curve, complete) = parse_complete_lines(&bytes)?;
+        if complete < bytes.len() {
+            file.set_len(complete as u64)?;
+        }
+        Ok((Self { file, error: None }, curve))
+    }
+
+    /// Reads the snapshots of the checkpoint at `path` without reopening it
+    /// for writing.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<LearningCurve, Error> {
+        parse_complete_lines(&std::fs::read(path)?).map(|(curve, _)| curve)
+    }
+
+    /// Appends `snapshot` as one line.
+    pub fn append(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(snapshot).map_err(Error::other)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file.flush()
+    }
+
+    /// Appends `snapshot` unless an earlier write failed, remembering the
+    /// first failure for [`finish`](Self::finish).
+    pub(crate) fn append_or_record(&mut self, snapshot: &Snapshot) {
+        if self.error.is_none()
+            && let Err(e) = self.append(snapshot)
+        {
+            self.error = Some(e);
+        }
+    }
+
+    /// Syncs the file to disk, or returns the first write error.
+    pub fn finish(self) -> Result<(), Error> {
+        match self.error {
+            Some(e) => Err(e),
+            None => self.file.sync_all(),
+        }
+    }
+}
+
+/// Parses every newline-terminated line of `bytes` as a snapshot, returning
+/// the curve and the number of bytes those lines span.
+fn parse_complete_lines(bytes: &[u8]) -> Result<(LearningCurve, usize), Error> {
+    let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let text = std::str::from_utf8(&bytes[..complete])
+        .map_err(|e| RivuError::parse(format!("curve checkpoint is not UTF-8: {e}")))?;
+    let mut curve = LearningCurve::default();
+    for (n, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let snapshot: Snapshot = serde_json::from_str(line)
+            .map_err(|e| RivuError::parse(format!("curve checkpoint line {}: {e}", n + 1)))?;
+        curve.push(snapshot);
+    }
+    Ok((curve, complete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    fn snap(seen: u64, acc: f64) -> Snapshot {
+        Snapshot {
+            instances_seen: seen,
+            accuracy: acc,
+            kappa: f64::NAN,
+            ram_hours: 0.0,
+            seconds: seen as f64,
+            extras: BTreeMap::from([("loss".into(), 0.5)]),
+            eta: None,
+        }
+    }
+
+    #[test]
+    fn snapshots_are_on_disk_as_soon_as_they_are_pushed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("curve.jsonl");
+        let mut curve = LearningCurve::default();
+        curve.push(snap(10, 0.5));
+        curve.checkpoint_to(CurveCheckpoint::create(&path).unwrap());
+        curve.push(snap(20, 0.75));
+
+        let read = CurveCheckpoint::read(&path).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read.as_slice()[1].accuracy, 0.75);
+        assert!(read.as_slice()[1].kappa.is_nan());
+        assert_eq!(read.as_slice()[1].extras["loss"], 0.5);
+
+        let mut copy = curve.clone();
+        copy.push(snap(30, 1.0));
+        assert_eq!(CurveCheckpoint::read(&path).unwrap().len(), 2);
+        curve.close_checkpoint().unwrap();
+    }
+
+    #[test]
+    fn resume_drops_a_torn_last_line_and_keeps_appending() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("curve.jsonl");
+        let mut checkpoint = CurveCheckpoint::create(&path).unwrap();
+        checkpoint.append(&snap(10, 0.5)).unwrap();
+        checkpoint.append(&snap(20, 0.6)).unwrap();
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(br#"{"instances_seen":30,"accu"#)
+            .unwrap();
+
+        let mut curve = LearningCurve::resume(&path).unwrap();
+        assert_eq!(curve.latest().unwrap().instances_seen, 20);
+        curve.push(snap(30, 0.7));
+        curve.close_checkpoint().unwrap();
+
+        let seen: Vec<u64> = CurveCheckpoint::read(&path)
+            .unwrap()
+            .iter()
+            .map(|s| s.instances_seen)
+            .collect();
+        assert_eq!(seen, vec![10, 20, 30]);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn corrupt_lines_are_parse_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("curve.jsonl");
+        fs::write(&path, "not json\n").unwrap();
+        let err = CurveCheckpoint::resume(&path).unwrap_err();
+        assert!(matches!(RivuError::of(&err), Some(RivuError::Parse(m)) if m.contains("line 1")));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::evaluation::{LearningCurve, Snapshot};
+use crate::utils::math::{mean_std, sign_test, wilcoxon_signed_rank};
+use std::collections::BTreeMap;
+
+/// One metric of two curves at the same `instances_seen`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignedPoint {
+    pub instances_seen: u64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl AlignedPoint {
+    /// `b - a`: positive when the second curve is ahead.
+    pub fn delta(&self) -> f64 {
+        self.b - self.a
+    }
+}
+
+/// Outcome of a paired significance test on the deltas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestOutcome {
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+/// A metric of two learning curves paired by instance count, e.g. two
+/// learners evaluated on the same stream.
+///
+/// Only instance counts present in both curves with a finite value on
+/// both sides are kept. A count recorded more than once, like the final
+/// snapshot a prequential run repeats after its last sample, is paired
+/// once, from its last snapshot. The significance tests treat the points as paired
+/// samples; points of a cumulative curve are not independent, so the
+/// p-values are a quick indication rather than a rigorous test.
+#[derive(Debug, Clone)]
+pub struct CurveComparison {
+    pub metric: String,
+    pub points: Vec<AlignedPoint>,
+}
+
+impl CurveComparison {
+    pub fn align(a: &LearningCurve, b: &LearningCurve, metric: &str) -> Self {
+        let (left, right) = (last_per_count(a), last_per_count(b));
+        let points = right
+            .iter()
+            .filter_map(|(&seen, s)| {
+                let bv = s.metric(metric)?;
+                let av = left.get(&seen)?.metric(metric)?;
+                (av.is_finite() && bv.is_finite()).then_some(AlignedPoint {
+                    instances_seen: seen,
+                    a: av,
+                    b: bv,
+                })
+            })
+            .collect();
+        Self {
+            metric: metric.to_string(),
+            points,
+        }
+    }
+
+    pub fn deltas(&self) -> Vec<f64> {
+        self.points.iter().map(AlignedPoint::delta).collect()
+    }
+
+    /// Mean of `b - a` over the aligned points (`NaN` when there are none).
+    pub fn mean_delta(&self) -> f64 {
+        mean_std(&self.deltas()).0
+    }
+
+    /// Points where `b` is ahead, behind, and tied.
+    pub fn wins_losses_ties(&self) -> (usize, usize, usize) {
+        self.points.iter().fold((0, 0, 0), |(w, l, t), p| {
+            let d = p.delta();
+            if d > 0.0 {
+                (w + 1, l, t)
+            } else if d < 0.0 {
+                (w, l + 1, t)
+            } else {
+                (w, l, t + 1)
+            }
+        })
+    }
+
+    /// Wilcoxon signed-rank test; the statistic is `W+`.
+    pub fn wilcoxon(&self) -> Option<TestOutcome> {
+        wilcoxon_signed_rank(&self.deltas())
+            .map(|(statistic, p_value)| TestOutcome { statistic, p_value })
+    }
+
+    /// Sign test; the statistic is the number of points where `b` is ahead.
+    pub fn sign_test(&self) -> Option<TestOutcome> {
+        sign_test(&self.deltas()).map(|(wins, p_value)| TestOutcome {
+            statistic: wins as f64,
+            p_value,
+        })
+    }
+
+    /// Up to `n` points evenly spread over the aligned range, always
+    /// including the last one.
+    pub fn sample(&self, n: usize) -> Vec<AlignedPoint> {
+        let len = self.points.len();
+        if n == 0 || len == 0 {
+            return Vec::new();
+        }
+        if len <= n {
+            return self.points.clone();
+        }
+        (1..=n).map(|i| self.points[i * len / n - 1]).collect()
+    }
+}
+
+/// The last snapshot of `curve` at each instance count.
+fn last_per_count(curve: &LearningCurve) -> BTreeMap<u64, &Snapshot> {
+    curve.iter().map(|s| (s.instances_seen, s)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(points: &[(u64, f64)]) -> LearningCurve {
+        let mut lc = LearningCurve::default();
+        for &(seen, acc) in points {
+            lc.push(Snapshot {
+                instances_seen: seen,
+                accuracy: acc,
+                kappa: f64::NAN,
+                ram_hours: 0.0,
+                seconds: 0.0,
+                extras: BTreeMap::new(),
+                eta: None,
+            });
+        }
+        lc
+    }
+
+    #[test]
+    fn aligns_on_shared_instance_counts_with_finite_values() {
+        let a = curve(&[(10, 0.5), (20, 0.6), (30, f64::NAN), (40, 0.7)]);
+        let b = curve(&[(10, 0.6), (20, 0.6), (30, 0.8), (35, 0.9), (40, 0.65)]);
+        let cmp = CurveComparison::align(&a, &b, "accuracy");
+
+        let seen: Vec<u64> = cmp.points.iter().map(|p| p.instances_seen).collect();
+        assert_eq!(seen, vec![10, 20, 40]);
+        assert_eq!(cmp.wins_losses_ties(), (1, 1, 1));
+        assert!((cmp.mean_delta() - 0.05 / 3.0).abs() < 1e-12);
+        assert!(CurveComparison::align(&a, &b, "kappa").points.is_empty());
+    }
+
+    #[test]
+    fn a_repeated_final_snapshot_is_paired_once() {
+        let a = curve(&[(10, 0.5), (20, 0.6), (20, 0.6)]);
+        let b = curve(&[(10, 0.7), (20, 0.5), (20, 0.8)]);
+        let cmp = CurveComparison::align(&a, &b, "accuracy");
+
+        let seen: Vec<u64> = cmp.points.iter().map(|p| p.instances_seen).collect();
+        assert_eq!(seen, vec![10, 20]);
+        assert!((cmp.points[1].b - 0.8).abs() < 1e-12);
+        assert_eq!(cmp.sign_test().unwrap().statistic, 2.0);
+    }
+
+    #[test]
+    fn consistent_improvement_is_significant() {
+        let a: Vec<(u64, f64)> = (1..=40).map(|i| (i * 100, 0.7)).collect();
+        let b: Vec<(u64, f64)> = (1..=40).map(|i| (i * 100, 0.7 + i as f64 * 1e-3)).collect();
+        let cmp = CurveComparison::align(&curve(&a), &curve(&b), "accuracy");
+
+        assert!(cmp.wilcoxon().unwrap().p_value < 1e-6);
+        let sign = cmp.sign_test().unwrap();
+        assert_eq!(sign.statistic, 40.0);
+        assert!(sign.p_value < 1e-6);
+    }
+
+    #[test]
+    fn sample_spreads_points_and_keeps_the_last() {
+        let a: Vec<(u64, f64)> = (1..=100).map(|i| (i, 0.5)).collect();
+        let cmp = CurveComparison::align(&curve(&a), &curve(&a), "accuracy");
+        let picked: Vec<u64> = cmp.sample(4).iter().map(|p| p.instances_seen).collect();
+        assert_eq!(picked, vec![25, 50, 75, 100]);
+        assert_eq!(cmp.sample(500).len(), 100);
+    }
+}
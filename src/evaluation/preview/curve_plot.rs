@@ -0,0 +1,371 @@
+use crate::evaluation::LearningCurve;
+use std::fmt::Write as _;
+use std::io::Error;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotFormat {
+    Svg,
+}
+
+impl PlotFormat {
+    /// Picks the format matching the extension of `path`, case-insensitively.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "svg" => Some(PlotFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+const PALETTE: [&str; 8] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_RIGHT: f64 = 24.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 48.0;
+const LEGEND_ROW: f64 = 18.0;
+
+/// Line chart of a [`LearningCurve`]: one line per selected metric against
+/// `instances_seen`.
+///
+/// Series are named like curve columns (`accuracy`, `kappa`, `ram_hours`,
+/// `seconds`, or any extras key). Non-finite values break the line. The
+/// y axis spans `[0, 1]` when every value fits, and the data range
+/// otherwise. Markers, such as known drift positions, are drawn as dashed
+/// vertical lines behind the series.
+#[derive(Debug, Clone)]
+pub struct CurvePlot {
+    pub title: Option<String>,
+    pub series: Vec<String>,
+    /// Instance counts marked on the x axis.
+    pub markers: Vec<u64>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for CurvePlot {
+    fn default() -> Self {
+        Self {
+            title: None,
+            series: vec!["accuracy".into(), "kappa".into()],
+            markers: Vec::new(),
+            width: 800,
+            height: 480,
+        }
+    }
+}
+
+impl CurvePlot {
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Replaces the plotted series; an empty list keeps the defaults.
+    pub fn with_series<I, S>(mut self, series: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let series: Vec<String> = series.into_iter().map(Into::into).collect();
+        if !series.is_empty() {
+            self.series = series;
+        }
+        self
+    }
+
+    pub fn with_markers(mut self, markers: impl IntoIterator<Item = u64>) -> Self {
+        self.markers = markers.into_iter().collect();
+        self
+    }
+
+    /// Renders `curve` to `path` in `fmt`.
+    pub fn render<P: AsRef<Path>>(
+        &self,
+        curve: &LearningCurve,
+        path: P,
+        fmt: PlotFormat,
+    ) -> Result<(), Error> {
+        match fmt {
+            PlotFormat::Svg => std::fs::write(path, self.to_svg(curve)),
+        }
+    }
+
+    /// The chart as a standalone SVG document.
+    pub fn to_svg(&self, curve: &LearningCurve) -> String {
+        let (w, h) = (self.width as f64, self.height as f64);
+        let plot_w = (w - MARGIN_LEFT - MARGIN_RIGHT).max(1.0);
+        let plot_h = (h - MARGIN_TOP - MARGIN_BOTTOM).max(1.0);
+
+        let points: Vec<Vec<(f64, f64)>> = self
+            .series
+            .iter()
+            .map(|name| {
+                curve
+                    .iter()
+                    .map(|s| (s.instances_seen as f64, s.metric(name).unwrap_or(f64::NAN)))
+                    .collect()
+            })
+            .collect();
+
+        let (x_lo, x_hi) = padded_range(
+            curve.iter().map(|s| s.instances_seen as f64),
+            (0.0, 1.0),
+            false,
+        );
+        let (y_lo, y_hi) = padded_range(points.iter().flatten().map(|&(_, y)| y), (0.0, 1.0), true);
+        let sx = |x: f64| MARGIN_LEFT + (x - x_lo) / (x_hi - x_lo) * plot_w;
+        let sy = |y: f64| MARGIN_TOP + plot_h - (y - y_lo) / (y_hi - y_lo) * plot_h;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#
+        );
+        let _ = writeln!(svg, r#"<rect width="{w}" height="{h}" fill="white"/>"#);
+        if let Some(title) = &self.title {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="24" text-anchor="middle" font-size="16">{}</text>"#,
+                w / 2.0,
+                escape(title)
+            );
+        }
+
+        for t in ticks(y_lo, y_hi) {
+            let y = sy(t);
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{MARGIN_LEFT}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#e0e0e0"/>"##,
+                MARGIN_LEFT + plot_w
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+                MARGIN_LEFT - 6.0,
+                y + 4.0,
+                tick_label(t)
+            );
+        }
+        for t in ticks(x_lo, x_hi) {
+            let x = sx(t);
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{x:.1}" y1="{MARGIN_TOP}" x2="{x:.1}" y2="{:.1}" stroke="#e0e0e0"/>"##,
+                MARGIN_TOP + plot_h
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{x:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                MARGIN_TOP + plot_h + 16.0,
+                tick_label(t)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{MARGIN_LEFT}" y="{MARGIN_TOP}" width="{plot_w:.1}" height="{plot_h:.1}" fill="none" stroke="black"/>"#
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">instances seen</text>"#,
+            MARGIN_LEFT + plot_w / 2.0,
+            h - 12.0
+        );
+        for &m in &self.markers {
+            let x = m as f64;
+            if x < x_lo || x > x_hi {
+                continue;
+            }
+            let _ = writeln!(
+                svg,
+                r##"<line class="marker" x1="{0:.1}" y1="{MARGIN_TOP}" x2="{0:.1}" y2="{1:.1}" stroke="#888" stroke-dasharray="4 3"/>"##,
+                sx(x),
+                MARGIN_TOP + plot_h
+            );
+        }
+
+        for (i, (name, pts)) in self.series.iter().zip(&points).enumerate() {
+            let color = PALETTE[i % PALETTE.len()];
+            for segment in pts.split(|(_, y)| !y.is_finite()) {
+                if let [(x, y)] = segment {
+                    let _ = writeln!(
+                        svg,
+                        r#"<circle cx="{:.1}" cy="{:.1}" r="2" fill="{color}"/>"#,
+                        sx(*x),
+                        sy(*y)
+                    );
+                } else if !segment.is_empty() {
+                    let coords: Vec<String> = segment
+                        .iter()
+                        .map(|&(x, y)| format!("{:.1},{:.1}", sx(x), sy(y)))
+                        .collect();
+                    let _ = writeln!(
+                        svg,
+                        r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="1.5"/>"#,
+                        coords.join(" ")
+                    );
+                }
+            }
+
+            let ly = MARGIN_TOP + 14.0 + i as f64 * LEGEND_ROW;
+            let lx = MARGIN_LEFT + plot_w - 140.0;
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{lx:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{color}" stroke-width="3"/>"#,
+                ly - 4.0,
+                lx + 20.0,
+                ly - 4.0
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{ly:.1}">{}</text>"#,
+                lx + 26.0,
+                escape(name)
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Range of the finite `values`, or `fallback` when there are none. With
+/// `prefer_unit`, values that all lie in `[0, 1]` get exactly that range;
+/// otherwise the range is padded by 5% on each side.
+fn padded_range(
+    values: impl Iterator<Item = f64>,
+    fallback: (f64, f64),
+    prefer_unit: bool,
+) -> (f64, f64) {
+    let (lo, hi) = values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    if lo > hi {
+        return fallback;
+    }
+    if prefer_unit && lo >= 0.0 && hi <= 1.0 {
+        return (0.0, 1.0);
+    }
+    if lo == hi {
+        return (lo - 0.5, hi + 0.5);
+    }
+    if prefer_unit {
+        let pad = (hi - lo) * 0.05;
+        (lo - pad, hi + pad)
+    } else {
+        (lo, hi)
+    }
+}
+
+/// Round tick positions inside `[lo, hi]`, about five of them.
+fn ticks(lo: f64, hi: f64) -> Vec<f64> {
+    let raw = (hi - lo) / 5.0;
+    if !raw.is_finite() || raw <= 0.0 {
+        return Vec::new();
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|s| *s >= raw)
+        .unwrap_or(10.0 * magnitude);
+
+    let mut out = Vec::new();
+    let mut t = (lo / step).ceil() * step;
+    while t <= hi + step * 1e-9 {
+        out.push(if t.abs() < step * 1e-9 { 0.0 } else { t });
+        t += step;
+    }
+    out
+}
+
+fn tick_label(t: f64) -> String {
+    let abs = t.abs();
+    if abs >= 1e6 {
+        format!("{}M", trim(t / 1e6))
+    } else if abs >= 1e4 {
+        format!("{}k", trim(t / 1e3))
+    } else {
+        trim(t)
+    }
+}
+
+fn trim(x: f64) -> String {
+    let s = format!("{x:.3}");
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Snapshot;
+    use std::collections::BTreeMap;
+
+    fn curve() -> LearningCurve {
+        let mut lc = LearningCurve::default();
+        for (i, acc) in [0.5, 0.6, f64::NAN, 0.8, 0.9].into_iter().enumerate() {
+            let mut extras = BTreeMap::new();
+            extras.insert("f1".into(), acc * 0.9);
+            lc.push(Snapshot {
+                instances_seen: (i as u64 + 1) * 100,
+                accuracy: acc,
+                kappa: acc - 0.5,
+                ram_hours: 0.0,
+                seconds: i as f64,
+                extras,
+                eta: None,
+            });
+        }
+        lc
+    }
+
+    #[test]
+    fn svg_draws_one_segment_per_finite_run_and_a_legend() {
+        let svg = CurvePlot::default()
+            .with_series(["accuracy", "f1"])
+            .with_title("SEA <NB>")
+            .with_markers([250, 10_000])
+            .to_svg(&curve());
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 4);
+        assert!(svg.contains(">accuracy</text>"));
+        assert!(svg.contains(">f1</text>"));
+        assert!(svg.contains("SEA &lt;NB&gt;"));
+        // The marker past the last snapshot is left out.
+        assert_eq!(svg.matches(r#"class="marker""#).count(), 1);
+    }
+
+    #[test]
+    fn only_svg_paths_have_a_plot_format() {
+        assert_eq!(PlotFormat::from_path("curve.png"), None);
+        assert_eq!(PlotFormat::from_path("c.SVG"), Some(PlotFormat::Svg));
+    }
+
+    #[test]
+    fn ticks_are_round_and_inside_the_range() {
+        let unit = ticks(0.0, 1.0);
+        assert_eq!(unit.len(), 6);
+        for (t, want) in unit.iter().zip([0.0, 0.2, 0.4, 0.6, 0.8, 1.0]) {
+            assert!((t - want).abs() < 1e-12);
+        }
+        let t = ticks(100.0, 5000.0);
+        assert_eq!(t.first(), Some(&1000.0));
+        assert!(t.iter().all(|x| (100.0..=5000.0).contains(x)));
+        assert_eq!(tick_label(25_000.0), "25k");
+    }
+}
@@ -0,0 +1,144 @@
+use crate::evaluation::LearningCurve;
+use crate::streams::DriftMarker;
+
+/// One ground-truth drift of a run lined up with what the run saw of it:
+/// the first detection that followed it and the dip it left in a metric of
+/// the learning curve.
+///
+/// A drift's span runs from its [`start`](DriftMarker::start) to the start
+/// of the next drift (or the end of the run). Detections and snapshots are
+/// attributed to the drift whose span contains them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriftAlignment {
+    pub marker: DriftMarker,
+    /// First detection in the drift's span, in instances seen.
+    pub detected_at: Option<u64>,
+    /// The metric at the last snapshot before the drift started.
+    pub before: Option<f64>,
+    /// Instances seen and value of the lowest snapshot in the drift's span.
+    pub lowest: Option<(u64, f64)>,
+}
+
+impl DriftAlignment {
+    /// Lines up each of `markers` with the `detections` (instance counts at
+    /// which a detector signalled, in order) and with `metric` on `curve`.
+    /// Non-finite metric values are skipped.
+    pub fn align(
+        markers: &[DriftMarker],
+        detections: &[u64],
+        curve: &LearningCurve,
+        metric: &str,
+    ) -> Vec<DriftAlignment> {
+        let snapshots = curve.as_slice();
+        let value = |i: usize| {
+            let s = &snapshots[i];
+            s.metric(metric)
+                .filter(|v| v.is_finite())
+                .map(|v| (s.instances_seen, v))
+        };
+        markers
+            .iter()
+            .enumerate()
+            .map(|(i, &marker)| {
+                let span = marker.start()..spans_until(markers, i);
+                let before = (0..snapshots.len())
+                    .rev()
+                    .filter(|&j| snapshots[j].instances_seen < marker.start())
+                    .find_map(value)
+                    .map(|(_, v)| v);
+                let lowest = (0..snapshots.len())
+                    .filter(|&j| span.contains(&snapshots[j].instances_seen))
+                    .filter_map(value)
+                    .min_by(|a, b| a.1.total_cmp(&b.1));
+                DriftAlignment {
+                    marker,
+                    detected_at: detections.iter().copied().find(|d| span.contains(d)),
+                    before,
+                    lowest,
+                }
+            })
+            .collect()
+    }
+
+    /// Detections that no drift accounts for: those before the first drift
+    /// starts, and every one after the first within a drift's span.
+    pub fn false_alarms(markers: &[DriftMarker], detections: &[u64]) -> usize {
+        let matched = (0..markers.len())
+            .filter(|&i| {
+                let span = markers[i].start()..spans_until(markers, i);
+                detections.iter().any(|d| span.contains(d))
+            })
+            .count();
+        detections.len() - matched
+    }
+
+    /// Instances between the drift's centre and its detection; zero for a
+    /// gradual drift caught before its centre.
+    pub fn delay(&self) -> Option<u64> {
+        self.detected_at
+            .map(|d| d.saturating_sub(self.marker.position))
+    }
+
+    /// How far the metric fell below its value before the drift.
+    pub fn dip(&self) -> Option<f64> {
+        Some(self.before? - self.lowest?.1)
+    }
+}
+
+/// End of the span of the `i`-th drift: the start of the next one.
+fn spans_until(markers: &[DriftMarker], i: usize) -> u64 {
+    markers.get(i + 1).map_or(u64::MAX, DriftMarker::start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Snapshot;
+    use std::collections::BTreeMap;
+
+    fn curve(acc: &[f64]) -> LearningCurve {
+        let mut lc = LearningCurve::default();
+        for (i, &a) in acc.iter().enumerate() {
+            lc.push(Snapshot {
+                instances_seen: (i as u64 + 1) * 100,
+                accuracy: a,
+                kappa: f64::NAN,
+                ram_hours: 0.0,
+                seconds: 0.0,
+                extras: BTreeMap::new(),
+                eta: None,
+            });
+        }
+        lc
+    }
+
+    #[test]
+    fn drifts_pick_up_the_detections_and_dips_of_their_spans() {
+        // Snapshots every 100 instances; drifts centred at 300 and 700.
+        let lc = curve(&[0.8, 0.9, 0.9, 0.6, 0.7, 0.85, 0.9, f64::NAN, 0.5, 0.8]);
+        let markers = [DriftMarker::new(300, 1), DriftMarker::new(700, 101)];
+        let detections = [150, 340, 360, 690];
+
+        let aligned = DriftAlignment::align(&markers, &detections, &lc, "accuracy");
+        assert_eq!(aligned.len(), 2);
+        assert_eq!(aligned[0].detected_at, Some(340));
+        assert_eq!(aligned[0].delay(), Some(40));
+        assert_eq!(aligned[0].before, Some(0.9));
+        assert_eq!(aligned[0].lowest, Some((400, 0.6)));
+        assert!((aligned[0].dip().unwrap() - 0.3).abs() < 1e-12);
+
+        // The gradual drift starts at 650, so the detection at 690 counts
+        // with no delay, and the snapshot at 700 is already in its span.
+        assert_eq!(markers[1].start(), 650);
+        assert_eq!(aligned[1].delay(), Some(0));
+        assert_eq!(aligned[1].before, Some(0.85));
+        assert_eq!(aligned[1].lowest, Some((900, 0.5)));
+
+        assert_eq!(DriftAlignment::false_alarms(&markers, &detections), 2);
+        assert_eq!(DriftAlignment::false_alarms(&[], &detections), 4);
+
+        let undetected = DriftAlignment::align(&markers, &[], &lc, "kappa");
+        assert_eq!(undetected[0].detected_at, None);
+        assert_eq!(undetected[0].dip(), None);
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// Estimated remaining work of a run, derived from its limits and the
+/// throughput observed so far.
+///
+/// A run stops at whichever limit it hits first, so every field reflects
+/// the binding limit: the larger completed fraction and the sooner end.
+/// Estimates assume the stream does not run out before the limits; a finite
+/// stream may finish earlier.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Eta {
+    /// Completed fraction of the run, in `[0, 1]`.
+    pub fraction: f64,
+    /// Seconds until a limit is reached, or `None` while the throughput is
+    /// still unknown and only an instance limit is set.
+    pub seconds_remaining: Option<f64>,
+    /// Instances left to process, or `None` while the throughput is still
+    /// unknown and only a time limit is set.
+    pub instances_remaining: Option<u64>,
+}
+
+impl Eta {
+    /// Estimates the remaining work after `instances_seen` instances in
+    /// `seconds`, or `None` when the run has neither limit.
+    pub fn estimate(
+        instances_seen: u64,
+        seconds: f64,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+    ) -> Option<Self> {
+        if max_instances.is_none() && max_seconds.is_none() {
+            return None;
+        }
+        let rate = (seconds > 0.0 && instances_seen > 0).then(|| instances_seen as f64 / seconds);
+
+        let mut fraction: f64 = 0.0;
+        let mut seconds_remaining: Option<f64> = None;
+        let mut instances_remaining: Option<u64> = None;
+
+        if let Some(max) = max_instances {
+            let left = max.saturating_sub(instances_seen);
+            fraction = fraction.max(ratio(instances_seen as f64, max as f64));
+            instances_remaining = Some(left);
+            seconds_remaining = rate.map(|r| left as f64 / r);
+        }
+        if let Some(max) = max_seconds {
+            let left = (max as f64 - seconds).max(0.0);
+            fraction = fraction.max(ratio(seconds, max as f64));
+            seconds_remaining = Some(seconds_remaining.map_or(left, |s| s.min(left)));
+            if let Some(r) = rate {
+                let by_time = (r * left).floor() as u64;
+                instances_remaining = Some(instances_remaining.map_or(by_time, |n| n.min(by_time)));
+            }
+        }
+
+        Some(Self {
+            fraction,
+            seconds_remaining,
+            instances_remaining,
+        })
+    }
+}
+
+fn ratio(current: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        (current / total).clamp(0.0, 1.0)
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_limits_means_no_estimate() {
+        assert_eq!(Eta::estimate(100, 1.0, None, None), None);
+    }
+
+    #[test]
+    fn instance_limit_uses_throughput() {
+        let eta = Eta::estimate(250, 5.0, Some(1000), None).unwrap();
+        assert!((eta.fraction - 0.25).abs() < 1e-12);
+        assert_eq!(eta.instances_remaining, Some(750));
+        assert!((eta.seconds_remaining.unwrap() - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn throughput_is_unknown_before_the_first_instance() {
+        let eta = Eta::estimate(0, 0.0, Some(1000), None).unwrap();
+        assert_eq!(eta.fraction, 0.0);
+        assert_eq!(eta.seconds_remaining, None);
+        assert_eq!(eta.instances_remaining, Some(1000));
+
+        let eta = Eta::estimate(0, 0.0, None, Some(60)).unwrap();
+        assert_eq!(eta.seconds_remaining, Some(60.0));
+        assert_eq!(eta.instances_remaining, None);
+    }
+
+    #[test]
+    fn the_sooner_limit_binds() {
+        // 100 inst/s: the instance limit would take 90 s more, the time limit 10 s.
+        let eta = Eta::estimate(1000, 10.0, Some(10_000), Some(20)).unwrap();
+        assert!((eta.fraction - 0.5).abs() < 1e-12);
+        assert_eq!(eta.seconds_remaining, Some(10.0));
+        assert_eq!(eta.instances_remaining, Some(1000));
+    }
+
+    #[test]
+    fn finished_runs_have_nothing_left() {
+        let eta = Eta::estimate(1200, 3.0, Some(1000), None).unwrap();
+        assert_eq!(eta.fraction, 1.0);
+        assert_eq!(eta.instances_remaining, Some(0));
+        assert_eq!(eta.seconds_remaining, Some(0.0));
+    }
+}
@@ -1,21 +1,87 @@
-use crate::evaluation::Snapshot;
+use crate::error::RivuError;
+use crate::evaluation::preview::curve_checkpoint::CurveCheckpoint;
+use crate::evaluation::{MetricKey, Snapshot};
+use crate::utils::math::mean_std;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::io::{Error, Write};
 use std::path::Path;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CurveFormat {
     Csv,
     Tsv,
     Json,
+    /// Columnar Parquet file, with one nullable column per extras key.
+    #[cfg(feature = "parquet")]
+    Parquet,
 }
+
+impl CurveFormat {
+    /// Picks the format matching the extension of `path` (`csv`, `tsv`,
+    /// `json`, and `parquet` when enabled), case-insensitively.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" => Some(CurveFormat::Csv),
+            "tsv" => Some(CurveFormat::Tsv),
+            "json" => Some(CurveFormat::Json),
+            #[cfg(feature = "parquet")]
+            "parquet" => Some(CurveFormat::Parquet),
+            _ => None,
+        }
+    }
+}
+
+/// Snapshots of a run in the order they were taken.
+///
+/// The curve serializes as the array of its snapshots, which is how a
+/// checkpoint keeps it; [`export`](Self::export) writes the tabular forms.
+/// With a [`CurveCheckpoint`] attached, every pushed snapshot is also
+/// appended to disk as it happens.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct LearningCurve {
     entries: Vec<Snapshot>,
+    #[serde(skip)]
+    checkpoint: Option<CurveCheckpoint>,
 }
 
 impl LearningCurve {
     pub fn push(&mut self, snapshot: Snapshot) {
+        if let Some(checkpoint) = self.checkpoint.as_mut() {
+            checkpoint.append_or_record(&snapshot);
+        }
         self.entries.push(snapshot)
     }
+
+    /// Appends every snapshot pushed from now on to `checkpoint`, after the
+    /// ones already in the curve. Write errors are reported by
+    /// [`close_checkpoint`](Self::close_checkpoint).
+    pub fn checkpoint_to(&mut self, mut checkpoint: CurveCheckpoint) {
+        for s in &self.entries {
+            checkpoint.append_or_record(s);
+        }
+        self.checkpoint = Some(checkpoint);
+    }
+
+    /// Picks up the checkpoint at `path` where it left off: the curve holds
+    /// the snapshots written so far and appends new ones to the same file.
+    pub fn resume<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let (checkpoint, mut curve) = CurveCheckpoint::resume(path)?;
+        curve.checkpoint = Some(checkpoint);
+        Ok(curve)
+    }
+
+    /// Detaches the checkpoint, if any, syncing it to disk; returns the first
+    /// error hit while appending to it.
+    pub fn close_checkpoint(&mut self) -> Result<(), Error> {
+        match self.checkpoint.take() {
+            Some(checkpoint) => checkpoint.finish(),
+            None => Ok(()),
+        }
+    }
     pub fn len(&self) -> usize {
         self.entries.len()
     }
@@ -39,18 +105,151 @@ impl LearningCurve {
             CurveFormat::Csv => self.export_with_delimiter(path, ','),
             CurveFormat::Tsv => self.export_with_delimiter(path, '\t'),
             CurveFormat::Json => self.export_json(path),
+            #[cfg(feature = "parquet")]
+            CurveFormat::Parquet => self.export_parquet(path),
+        }
+    }
+
+    /// Reads a curve written by [`export`](Self::export) in `fmt`.
+    ///
+    /// Columns besides the fixed snapshot fields become extras; empty cells
+    /// and JSON nulls are left out of the extras and read as `NaN` for the
+    /// fixed fields. Parquet curves cannot be read back.
+    pub fn import<P: AsRef<Path>>(path: P, fmt: CurveFormat) -> Result<Self, Error> {
+        match fmt {
+            CurveFormat::Csv => Self::parse_delimited(&std::fs::read_to_string(path)?, ','),
+            CurveFormat::Tsv => Self::parse_delimited(&std::fs::read_to_string(path)?, '\t'),
+            CurveFormat::Json => Self::parse_json(&std::fs::read_to_string(path)?),
+            #[cfg(feature = "parquet")]
+            CurveFormat::Parquet => {
+                Err(RivuError::unsupported("reading Parquet curves is not supported").into())
+            }
+        }
+    }
+
+    fn parse_delimited(text: &str, delimiter: char) -> Result<Self, Error> {
+        let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+        let Some(header) = lines.next() else {
+            return Ok(Self::default());
+        };
+        let columns: Vec<&str> = header.split(delimiter).map(str::trim).collect();
+
+        let mut out = Self::default();
+        for (row, line) in lines.enumerate() {
+            let cells: Vec<&str> = line.split(delimiter).map(str::trim).collect();
+            let fields = columns.iter().zip(cells).map(|(name, cell)| {
+                let value = match cell {
+                    "" => None,
+                    c => Some(c.parse::<f64>().map_err(|_| {
+                        RivuError::parse(format!(
+                            "row {}: `{c}` is not a number in column {name}",
+                            row + 1
+                        ))
+                    })?),
+                };
+                Ok((*name, value))
+            });
+            out.push(snapshot_from_fields(
+                fields.collect::<Result<Vec<_>, Error>>()?,
+            )?);
+        }
+        Ok(out)
+    }
+
+    fn parse_json(text: &str) -> Result<Self, Error> {
+        let rows: Vec<serde_json::Map<String, serde_json::Value>> =
+            serde_json::from_str(text).map_err(|e| RivuError::parse(e.to_string()))?;
+        let mut out = Self::default();
+        for row in &rows {
+            let fields = row.iter().map(|(k, v)| (k.as_str(), v.as_f64())).collect();
+            out.push(snapshot_from_fields(fields)?);
+        }
+        Ok(out)
+    }
+
+    /// Averages several curves point by point (e.g. repeated runs with
+    /// different seeds), truncated to the shortest curve.
+    ///
+    /// Each output snapshot holds the mean of every field and extra, and
+    /// `<name>_std` extras with the population standard deviation of
+    /// `accuracy`, `kappa`, and every extra. `instances_seen` is taken from
+    /// the first curve.
+    pub fn mean_std(curves: &[&LearningCurve]) -> LearningCurve {
+        let len = curves.iter().map(|c| c.len()).min().unwrap_or(0);
+        let mut out = LearningCurve::default();
+
+        for i in 0..len {
+            let points: Vec<&Snapshot> = curves.iter().map(|c| &c.entries[i]).collect();
+            let field = |f: fn(&Snapshot) -> f64| {
+                let values: Vec<f64> = points
+                    .iter()
+                    .map(|s| f(s))
+                    .filter(|v| v.is_finite())
+                    .collect();
+                mean_std(&values)
+            };
+
+            let (accuracy, accuracy_std) = field(|s| s.accuracy);
+            let (kappa, kappa_std) = field(|s| s.kappa);
+            let (ram_hours, _) = field(|s| s.ram_hours);
+            let (seconds, _) = field(|s| s.seconds);
+
+            let mut extras = BTreeMap::new();
+            extras.insert(MetricKey::new("accuracy_std"), accuracy_std);
+            extras.insert(MetricKey::new("kappa_std"), kappa_std);
+
+            let keys: BTreeSet<MetricKey> = points
+                .iter()
+                .flat_map(|s| s.extras.keys().copied())
+                .collect();
+            for key in keys {
+                let values: Vec<f64> = points
+                    .iter()
+                    .filter_map(|s| s.extras.get(&key).copied())
+                    .filter(|v| v.is_finite())
+                    .collect();
+                let (m, sd) = mean_std(&values);
+                extras.insert(key, m);
+                extras.insert(MetricKey::new(&format!("{key}_std")), sd);
+            }
+
+            out.push(Snapshot {
+                instances_seen: points[0].instances_seen,
+                accuracy,
+                kappa,
+                ram_hours,
+                seconds,
+                extras,
+                eta: None,
+            });
         }
+        out
+    }
+
+    /// Sorted union of every `extras` key present in any snapshot.
+    pub fn extras_keys(&self) -> Vec<MetricKey> {
+        let keys: BTreeSet<MetricKey> = self
+            .entries
+            .iter()
+            .flat_map(|s| s.extras.keys().copied())
+            .collect();
+        keys.into_iter().collect()
     }
 
     fn export_with_delimiter<P: AsRef<Path>>(&self, path: P, delimiter: char) -> Result<(), Error> {
+        let extras = self.extras_keys();
         let mut w = File::create(path)?;
-        writeln!(
+        write!(
             w,
             "instances_seen{d}accuracy{d}kappa{d}ram_hours{d}seconds",
             d = delimiter
         )?;
+        for key in &extras {
+            write!(w, "{delimiter}{key}")?;
+        }
+        writeln!(w)?;
         for s in &self.entries {
-            writeln!(
+            write!(
                 w,
                 "{}{d}{:.12}{d}{:.12}{d}{:.12}{d}{:.6}",
                 s.instances_seen,
@@ -60,33 +259,145 @@ impl LearningCurve {
                 s.seconds,
                 d = delimiter
             )?;
+            for key in &extras {
+                match s.extras.get(key) {
+                    Some(v) => write!(w, "{delimiter}{v:.12}")?,
+                    None => write!(w, "{delimiter}")?,
+                }
+            }
+            writeln!(w)?;
         }
         Ok(())
     }
 
     fn export_json<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let extras = self.extras_keys();
         let mut w = File::create(path)?;
         writeln!(w, "[")?;
         for (i, s) in self.entries.iter().enumerate() {
+            write!(
+                w,
+                "  {{\"instances_seen\":{},\"accuracy\":{},\"kappa\":{},\"ram_hours\":{},\"seconds\":{}",
+                s.instances_seen, s.accuracy, s.kappa, s.ram_hours, s.seconds,
+            )?;
+            for key in &extras {
+                let key_json = serde_json::to_string(key).map_err(Error::other)?;
+                match s.extras.get(key) {
+                    Some(v) if v.is_finite() => write!(w, ",{key_json}:{v}")?,
+                    _ => write!(w, ",{key_json}:null")?,
+                }
+            }
             writeln!(
                 w,
-                "  {{\"instances_seen\":{},\"accuracy\":{},\"kappa\":{},\"ram_hours\":{},\"seconds\":{}}}{}",
-                s.instances_seen,
-                s.accuracy,
-                s.kappa,
-                s.ram_hours,
-                s.seconds,
+                "}}{}",
                 if i + 1 == self.entries.len() { "" } else { "," }
             )?;
         }
         writeln!(w, "]")?;
         Ok(())
     }
+
+    #[cfg(feature = "parquet")]
+    fn export_parquet<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        use arrow_array::{ArrayRef, Float64Array, RecordBatch, UInt64Array};
+        use arrow_schema::{DataType, Field, Schema};
+        use parquet::arrow::ArrowWriter;
+        use std::sync::Arc;
+
+        let extras = self.extras_keys();
+
+        let mut fields = vec![
+            Field::new("instances_seen", DataType::UInt64, false),
+            Field::new("accuracy", DataType::Float64, false),
+            Field::new("kappa", DataType::Float64, false),
+            Field::new("ram_hours", DataType::Float64, false),
+            Field::new("seconds", DataType::Float64, false),
+        ];
+        fields.extend(
+            extras
+                .iter()
+                .map(|k| Field::new(k.as_str(), DataType::Float64, true)),
+        );
+        let schema = Arc::new(Schema::new(fields));
+
+        let column = |f: fn(&Snapshot) -> f64| -> ArrayRef {
+            Arc::new(Float64Array::from_iter_values(self.entries.iter().map(f)))
+        };
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from_iter_values(
+                self.entries.iter().map(|s| s.instances_seen),
+            )),
+            column(|s| s.accuracy),
+            column(|s| s.kappa),
+            column(|s| s.ram_hours),
+            column(|s| s.seconds),
+        ];
+        for key in &extras {
+            columns.push(Arc::new(Float64Array::from_iter(
+                self.entries.iter().map(|s| s.extras.get(key).copied()),
+            )));
+        }
+
+        let batch = RecordBatch::try_new(Arc::clone(&schema), columns).map_err(Error::other)?;
+        let file = File::create(path)?;
+        let mut writer = ArrowWriter::try_new(file, schema, None).map_err(Error::other)?;
+        writer.write(&batch).map_err(Error::other)?;
+        writer.close().map_err(Error::other)?;
+        Ok(())
+    }
+}
+
+/// Builds a snapshot from named values as found in an exported curve.
+fn snapshot_from_fields(fields: Vec<(&str, Option<f64>)>) -> Result<Snapshot, Error> {
+    let mut s = Snapshot {
+        instances_seen: 0,
+        accuracy: f64::NAN,
+        kappa: f64::NAN,
+        ram_hours: f64::NAN,
+        seconds: f64::NAN,
+        extras: BTreeMap::new(),
+        eta: None,
+    };
+    let mut seen = None;
+    for (name, value) in fields {
+        match name {
+            "instances_seen" => seen = value,
+            "accuracy" => s.accuracy = value.unwrap_or(f64::NAN),
+            "kappa" => s.kappa = value.unwrap_or(f64::NAN),
+            "ram_hours" => s.ram_hours = value.unwrap_or(f64::NAN),
+            "seconds" => s.seconds = value.unwrap_or(f64::NAN),
+            _ => {
+                if let Some(v) = value {
+                    s.extras.insert(MetricKey::new(name), v);
+                }
+            }
+        }
+    }
+    match seen {
+        Some(n) if n >= 0.0 => s.instances_seen = n as u64,
+        _ => {
+            return Err(RivuError::parse("curve row without instances_seen").into());
+        }
+    }
+    Ok(s)
 }
 
 impl Default for LearningCurve {
     fn default() -> Self {
-        Self { entries: vec![] }
+        Self {
+            entries: vec![],
+            checkpoint: None,
+        }
+    }
+}
+
+/// Clones the snapshots only; the copy does not write to the checkpoint.
+impl Clone for LearningCurve {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            checkpoint: None,
+        }
     }
 }
 
@@ -104,7 +415,8 @@ mod tests {
             kappa: kap,
             ram_hours: ram,
             seconds: secs,
-            extras: BTreeMap::<String, f64>::new(),
+            extras: BTreeMap::new(),
+            eta: None,
         }
     }
 
@@ -214,4 +526,206 @@ instances_seen\taccuracy\tkappa\tram_hours\tseconds
         let exp_json = "[\n]\n";
         assert_eq!(got_json, exp_json);
     }
+
+    fn curve_with_sparse_extras() -> LearningCurve {
+        let mut lc = LearningCurve::default();
+        let mut a = snap(10, 1.0, 0.5, 0.125, 2.5);
+        a.extras.insert("kappa_t".into(), 0.5);
+        let mut b = snap(20, 0.25, 0.0, 1.5, 3.0);
+        b.extras.insert("f1".into(), 0.25);
+        b.extras.insert("kappa_t".into(), f64::NAN);
+        lc.push(a);
+        lc.push(b);
+        lc
+    }
+
+    #[test]
+    fn export_csv_appends_union_of_extras_columns() {
+        let lc = curve_with_sparse_extras();
+        let tf = NamedTempFile::new().unwrap();
+        lc.export(tf.path(), CurveFormat::Csv).unwrap();
+
+        let got = fs::read_to_string(tf.path()).unwrap();
+        let exp = "\
+instances_seen,accuracy,kappa,ram_hours,seconds,f1,kappa_t
+10,1.000000000000,0.500000000000,0.125000000000,2.500000,,0.500000000000
+20,0.250000000000,0.000000000000,1.500000000000,3.000000,0.250000000000,NaN
+";
+        assert_eq!(got, exp);
+    }
+
+    #[test]
+    fn export_tsv_appends_union_of_extras_columns() {
+        let lc = curve_with_sparse_extras();
+        let tf = NamedTempFile::new().unwrap();
+        lc.export(tf.path(), CurveFormat::Tsv).unwrap();
+
+        let got = fs::read_to_string(tf.path()).unwrap();
+        let header = got.lines().next().unwrap();
+        assert_eq!(
+            header,
+            "instances_seen\taccuracy\tkappa\tram_hours\tseconds\tf1\tkappa_t"
+        );
+    }
+
+    #[test]
+    fn export_json_emits_extras_with_null_for_missing_or_nan() {
+        let lc = curve_with_sparse_extras();
+        let tf = NamedTempFile::new().unwrap();
+        lc.export(tf.path(), CurveFormat::Json).unwrap();
+
+        let got = fs::read_to_string(tf.path()).unwrap();
+        let exp = "\
+[
+  {\"instances_seen\":10,\"accuracy\":1,\"kappa\":0.5,\"ram_hours\":0.125,\"seconds\":2.5,\"f1\":null,\"kappa_t\":0.5},
+  {\"instances_seen\":20,\"accuracy\":0.25,\"kappa\":0,\"ram_hours\":1.5,\"seconds\":3,\"f1\":0.25,\"kappa_t\":null}
+]
+";
+        assert_eq!(got, exp);
+        let parsed: serde_json::Value = serde_json::from_str(&got).unwrap();
+        assert_eq!(parsed.as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn mean_std_aggregates_point_by_point() {
+        let mut a = LearningCurve::default();
+        a.push(snap(10, 0.5, 0.0, 0.0, 1.0));
+        a.push(snap(20, 1.0, 0.5, 0.0, 2.0));
+        let mut b = LearningCurve::default();
+        let mut s = snap(10, 1.0, 0.0, 0.0, 3.0);
+        s.extras.insert("f1".into(), 0.5);
+        b.push(s);
+
+        let agg = LearningCurve::mean_std(&[&a, &b]);
+        assert_eq!(agg.len(), 1);
+        let p = &agg.as_slice()[0];
+        assert_eq!(p.instances_seen, 10);
+        assert_eq!(p.accuracy, 0.75);
+        assert_eq!(p.extras["accuracy_std"], 0.25);
+        assert_eq!(p.seconds, 2.0);
+        assert_eq!(p.extras["f1"], 0.5);
+        assert_eq!(p.extras["f1_std"], 0.0);
+    }
+
+    #[test]
+    fn extras_keys_is_sorted_union() {
+        let mut lc = LearningCurve::default();
+        let mut a = snap(10, 1.0, 0.5, 0.0, 1.0);
+        a.extras.insert("recall".into(), 0.5);
+        let mut b = snap(20, 1.0, 0.5, 0.0, 2.0);
+        b.extras.insert("f1".into(), 0.25);
+        b.extras.insert("recall".into(), 0.75);
+        lc.push(a);
+        lc.push(b);
+        assert_eq!(lc.extras_keys(), vec!["f1", "recall"]);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn export_parquet_round_trips_columns_and_extras() {
+        use arrow_array::{Array, Float64Array, UInt64Array};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let mut lc = LearningCurve::default();
+        let mut a = snap(10, 1.0, 0.5, 0.125, 2.5);
+        a.extras.insert("f1".into(), 0.75);
+        lc.push(a);
+        lc.push(snap(20, 0.25, 0.0, 1.5, 3.0));
+
+        let tf = NamedTempFile::new().unwrap();
+        lc.export(tf.path(), CurveFormat::Parquet).unwrap();
+
+        let file = File::open(tf.path()).unwrap();
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 6);
+
+        let seen = batch
+            .column_by_name("instances_seen")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(seen.values(), &[10, 20]);
+
+        let f1 = batch
+            .column_by_name("f1")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(f1.value(0), 0.75);
+        assert!(f1.is_null(1));
+    }
+
+    #[test]
+    fn import_reads_back_every_text_format() {
+        let lc = curve_with_sparse_extras();
+        for (ext, fmt) in [
+            ("csv", CurveFormat::Csv),
+            ("tsv", CurveFormat::Tsv),
+            ("json", CurveFormat::Json),
+        ] {
+            let tmp = tempfile::Builder::new()
+                .suffix(&format!(".{ext}"))
+                .tempfile()
+                .unwrap();
+            lc.export(tmp.path(), fmt).unwrap();
+            let back = LearningCurve::import(tmp.path(), fmt).unwrap();
+
+            assert_eq!(back.len(), lc.len(), "{ext}");
+            for (a, b) in back.iter().zip(lc.iter()) {
+                assert_eq!(a.instances_seen, b.instances_seen);
+                assert!((a.accuracy - b.accuracy).abs() < 1e-9);
+                assert_eq!(
+                    a.extras.keys().collect::<Vec<_>>(),
+                    b.extras
+                        .iter()
+                        .filter(|(_, v)| v.is_finite() || ext != "json")
+                        .map(|(k, _)| k)
+                        .collect::<Vec<_>>(),
+                    "{ext}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn serialized_curve_keeps_every_value() {
+        let mut lc = LearningCurve::default();
+        lc.push(snap(10, f64::NAN, 0.0, 0.1, 1.0 / 3.0));
+        let mut s = snap(20, 0.5, f64::NEG_INFINITY, 0.2, 2.0);
+        s.extras.insert("f1".into(), f64::NAN);
+        s.eta = crate::evaluation::Eta::estimate(20, 2.0, Some(40), None);
+        lc.push(s);
+
+        let back: LearningCurve =
+            serde_json::from_str(&serde_json::to_string(&lc).unwrap()).unwrap();
+        assert_eq!(back.len(), 2);
+        let (a, b) = (&back.as_slice()[0], &back.as_slice()[1]);
+        assert!(a.accuracy.is_nan());
+        assert_eq!(a.seconds, 1.0 / 3.0);
+        assert_eq!(b.kappa, f64::NEG_INFINITY);
+        assert!(b.extras["f1"].is_nan());
+        assert_eq!(b.eta, lc.as_slice()[1].eta);
+    }
+
+    #[test]
+    fn format_from_path_uses_extension() {
+        assert_eq!(
+            CurveFormat::from_path("out/curve.CSV"),
+            Some(CurveFormat::Csv)
+        );
+        assert_eq!(CurveFormat::from_path("curve.tsv"), Some(CurveFormat::Tsv));
+        assert_eq!(
+            CurveFormat::from_path("curve.json"),
+            Some(CurveFormat::Json)
+        );
+        assert_eq!(CurveFormat::from_path("curve.txt"), None);
+        assert_eq!(CurveFormat::from_path("curve"), None);
+    }
 }
@@ -1,2 +1,12 @@
+pub mod curve_changes;
+pub mod curve_checkpoint;
+pub mod curve_comparison;
+pub mod curve_plot;
+pub mod drift_alignment;
+pub mod eta;
 pub mod learning_curve;
+pub mod prediction_log;
+pub mod river_results;
+pub mod run_report;
+pub mod score_log;
 pub mod snapshot;
@@ -0,0 +1,279 @@
+use crate::core::instance_header::TargetKind;
+use crate::core::instances::Instance;
+use serde_json::{Value, json};
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredictionLogFormat {
+    /// `instance,id,timestamp,true_label,predicted_label,correct,votes`,
+    /// with the votes joined by `;`. Unknown values are left empty.
+    Csv,
+    /// One JSON object per line with the same fields; unknown values are
+    /// `null` and `votes` is an array.
+    Jsonl,
+}
+
+impl PredictionLogFormat {
+    /// Picks the format matching the extension of `path` (`csv`, or
+    /// `jsonl`/`ndjson`), case-insensitively.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" => Some(PredictionLogFormat::Csv),
+            "jsonl" | "ndjson" => Some(PredictionLogFormat::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+/// Streams one record per prediction to a file, for post-hoc statistical
+/// tests and error analysis.
+///
+/// The predicted label is the index of the largest finite vote (the first
+/// one on ties), matching the evaluators. For a numeric class the log holds
+/// the true and predicted values instead, and `correct` is left unknown.
+/// `id` and `timestamp` are those of the instance (see [`Instance::id`]),
+/// for joining records back to the source data. Records are buffered; the
+/// log is flushed when dropped or through [`PredictionLog::flush`].
+pub struct PredictionLog {
+    writer: Box<dyn Write + Send>,
+    format: PredictionLogFormat,
+    header_written: bool,
+}
+
+impl PredictionLog {
+    pub fn create<P: AsRef<Path>>(path: P, format: PredictionLogFormat) -> Result<Self, Error> {
+        let file = File::create(path)?;
+        Ok(Self::from_writer(Box::new(BufWriter::new(file)), format))
+    }
+
+    pub fn from_writer(writer: Box<dyn Write + Send>, format: PredictionLogFormat) -> Self {
+        Self {
+            writer,
+            format,
+            header_written: false,
+        }
+    }
+
+    pub fn format(&self) -> PredictionLogFormat {
+        self.format
+    }
+
+    /// Appends the record of the `index`-th instance (zero-based) and the
+    /// `votes` the learner produced for it.
+    pub fn record(
+        &mut self,
+        index: u64,
+        instance: &dyn Instance,
+        votes: &[f64],
+    ) -> Result<(), Error> {
+        let truth = instance
+            .class_value()
+            .filter(|y| !instance.is_class_missing() && y.is_finite());
+        let (truth, predicted, correct) =
+            if instance.header().target_kind() == Some(TargetKind::Numeric) {
+                let predicted = votes.first().copied().filter(|v| v.is_finite());
+                (truth.map(Value::from), predicted.map(Value::from), None)
+            } else {
+                let truth = truth.map(|y| y as usize);
+                let predicted = argmax(votes);
+                let correct = match (truth, predicted) {
+                    (Some(y), Some(p)) => Some(y == p),
+                    _ => None,
+                };
+                (truth.map(Value::from), predicted.map(Value::from), correct)
+            };
+
+        match self.format {
+            PredictionLogFormat::Csv => {
+                if !self.header_written {
+                    writeln!(
+                        self.writer,
+                        "instance,id,timestamp,true_label,predicted_label,correct,votes"
+                    )?;
+                    self.header_written = true;
+                }
+                let votes = votes
+                    .iter()
+                    .map(|v| format!("{v}"))
+                    .collect::<Vec<_>>()
+                    .join(";");
+                writeln!(
+                    self.writer,
+                    "{},{},{},{},{},{},{}",
+                    index,
+                    opt(instance.id()),
+                    opt(instance.timestamp()),
+                    opt(truth),
+                    opt(predicted),
+                    opt(correct),
+                    votes
+                )
+            }
+            PredictionLogFormat::Jsonl => {
+                let votes: Vec<Option<f64>> =
+                    votes.iter().map(|v| v.is_finite().then_some(*v)).collect();
+                let line = json!({
+                    "instance": index,
+                    "id": instance.id(),
+                    "timestamp": instance.timestamp(),
+                    "true_label": truth,
+                    "predicted_label": predicted,
+                    "correct": correct,
+                    "votes": votes,
+                });
+                writeln!(self.writer, "{line}")
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()
+    }
+}
+
+impl Drop for PredictionLog {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+fn opt<T: ToString>(v: Option<T>) -> String {
+    v.map(|v| v.to_string()).unwrap_or_default()
+}
+
+pub(crate) fn argmax(v: &[f64]) -> Option<usize> {
+    let mut best = None;
+    let mut best_value = f64::NEG_INFINITY;
+    for (i, &x) in v.iter().enumerate() {
+        if !x.is_finite() {
+            continue;
+        }
+        if best.is_none() || x > best_value {
+            best = Some(i);
+            best_value = x;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
+    use crate::core::instance_header::InstanceHeader;
+    use crate::core::instances::DenseInstance;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuf {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn instance(label: f64) -> DenseInstance {
+        let mut class_map = HashMap::new();
+        class_map.insert("A".into(), 0);
+        class_map.insert("B".into(), 1);
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())) as AttributeRef,
+            Arc::new(NominalAttribute::with_values(
+                "class".into(),
+                vec!["A".into(), "B".into()],
+                class_map,
+            )) as AttributeRef,
+        ];
+        let header = Arc::new(InstanceHeader::new("r".into(), attrs, 1));
+        DenseInstance::new(header, vec![0.5, label], 1.0)
+    }
+
+    #[test]
+    fn csv_records_truth_prediction_and_votes() {
+        let buf = SharedBuf::default();
+        let mut log = PredictionLog::from_writer(Box::new(buf.clone()), PredictionLogFormat::Csv);
+        log.record(0, &instance(1.0), &[0.25, 0.75]).unwrap();
+        log.record(1, &instance(0.0).with_id(7), &[0.6, 0.4])
+            .unwrap();
+        log.record(2, &instance(f64::NAN), &[]).unwrap();
+
+        let text = buf.text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "instance,id,timestamp,true_label,predicted_label,correct,votes",
+                "0,,,1,1,true,0.25;0.75",
+                "1,7,,0,0,true,0.6;0.4",
+                "2,,,,,,",
+            ]
+        );
+    }
+
+    #[test]
+    fn jsonl_uses_nulls_for_unknowns() {
+        let buf = SharedBuf::default();
+        let mut log = PredictionLog::from_writer(Box::new(buf.clone()), PredictionLogFormat::Jsonl);
+        log.record(
+            4,
+            &instance(0.0).with_timestamp(1_700_000_000_000),
+            &[0.1, 0.9],
+        )
+        .unwrap();
+        log.record(5, &instance(1.0), &[]).unwrap();
+
+        let rows: Vec<serde_json::Value> = buf
+            .text()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(rows[0]["instance"], 4);
+        assert!(rows[0]["id"].is_null());
+        assert_eq!(rows[0]["timestamp"], 1_700_000_000_000i64);
+        assert_eq!(rows[0]["predicted_label"], 1);
+        assert_eq!(rows[0]["correct"], false);
+        assert!(rows[1]["predicted_label"].is_null());
+        assert!(rows[1]["correct"].is_null());
+    }
+
+    #[test]
+    fn numeric_classes_log_values() {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NumericAttribute::new("y".into())),
+        ];
+        let header = Arc::new(InstanceHeader::new("r".into(), attrs, 1));
+        let buf = SharedBuf::default();
+        let mut log = PredictionLog::from_writer(Box::new(buf.clone()), PredictionLogFormat::Csv);
+        log.record(0, &DenseInstance::new(header, vec![0.5, 2.5], 1.0), &[1.75])
+            .unwrap();
+        assert_eq!(buf.text().lines().nth(1), Some("0,,,2.5,1.75,,1.75"));
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            PredictionLogFormat::from_path("p.CSV"),
+            Some(PredictionLogFormat::Csv)
+        );
+        assert_eq!(
+            PredictionLogFormat::from_path("p.ndjson"),
+            Some(PredictionLogFormat::Jsonl)
+        );
+        assert_eq!(PredictionLogFormat::from_path("p.json"), None);
+    }
+}
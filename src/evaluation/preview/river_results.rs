@@ -0,0 +1,311 @@
+use crate::error::RivuError;
+use crate::evaluation::{LearningCurve, MetricKey, Snapshot};
+use serde_json::{Map, Value, json};
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+
+/// Benchmark track of River's suite, which decides the metric columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiverTrack {
+    /// `Accuracy` and `F1`, the F1 score of class 1.
+    BinaryClassification,
+    /// `Accuracy`, `MicroF1` and `MacroF1`, the mean of the per-class F1
+    /// scores.
+    MulticlassClassification,
+    /// `MAE`, `RMSE` and `R2`.
+    Regression,
+}
+
+impl RiverTrack {
+    /// Name of the track as River's results spell it.
+    pub fn name(self) -> &'static str {
+        match self {
+            RiverTrack::BinaryClassification => "Binary classification",
+            RiverTrack::MulticlassClassification => "Multiclass classification",
+            RiverTrack::Regression => "Regression",
+        }
+    }
+
+    pub fn metrics(self) -> &'static [&'static str] {
+        match self {
+            RiverTrack::BinaryClassification => &["Accuracy", "F1"],
+            RiverTrack::MulticlassClassification => &["Accuracy", "MicroF1", "MacroF1"],
+            RiverTrack::Regression => &["MAE", "RMSE", "R2"],
+        }
+    }
+
+    /// Track of a curve from its metrics: regression when it has regression
+    /// errors, binary when its per-class F1 scores stop at class 1, and
+    /// multiclass otherwise.
+    pub fn infer(curve: &LearningCurve) -> Self {
+        let keys = curve.extras_keys();
+        let has = |name: &str| keys.iter().any(|k| k == name);
+        if has("mae") || has("rmse") {
+            RiverTrack::Regression
+        } else if has("f1_class_1") && !has("f1_class_2") {
+            RiverTrack::BinaryClassification
+        } else {
+            RiverTrack::MulticlassClassification
+        }
+    }
+}
+
+impl std::str::FromStr for RiverTrack {
+    type Err = RivuError;
+
+    /// Parses `binary`, `multiclass` or `regression`.
+    fn from_str(s: &str) -> Result<Self, RivuError> {
+        match s.to_ascii_lowercase().as_str() {
+            "binary" => Ok(RiverTrack::BinaryClassification),
+            "multiclass" => Ok(RiverTrack::MulticlassClassification),
+            "regression" => Ok(RiverTrack::Regression),
+            _ => Err(RivuError::config(format!(
+                "unknown River track '{s}' (expected binary, multiclass or regression)"
+            ))),
+        }
+    }
+}
+
+/// A learning curve laid out as the results of River's benchmark suite,
+/// so Rivu runs can be tabulated next to River's without conversion.
+///
+/// Each snapshot becomes a row with `step`, `track`, `model`, `dataset`,
+/// the track's metrics under River's names, `Memory in Mb` (from the
+/// `model_bytes` model statistic, when the curve has it) and `Time in s`.
+/// Metrics are fractions, as River's `Metric.get()` returns them. Rivu
+/// computes the same quantities under other names:
+///
+/// | River | Rivu |
+/// | --- | --- |
+/// | `Accuracy` | `accuracy` |
+/// | `F1` | `f1_class_1` |
+/// | `MicroF1` | `accuracy` (equal for single-label predictions) |
+/// | `MacroF1` | mean of the `f1_class_*` columns, `f1` without them |
+/// | `MAE`, `RMSE`, `R2` | `mae`, `rmse`, `r2` |
+///
+/// A metric the curve lacks is left empty in CSV and `null` in JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiverResults {
+    pub track: RiverTrack,
+    pub dataset: String,
+    pub model: String,
+}
+
+impl RiverResults {
+    pub fn new(track: RiverTrack, dataset: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            track,
+            dataset: dataset.into(),
+            model: model.into(),
+        }
+    }
+
+    /// Column names, in order.
+    pub fn columns(&self) -> Vec<&'static str> {
+        let mut columns = vec!["step", "track", "model", "dataset"];
+        columns.extend(self.track.metrics());
+        columns.extend(["Memory in Mb", "Time in s"]);
+        columns
+    }
+
+    /// One row per snapshot of `curve`.
+    pub fn rows(&self, curve: &LearningCurve) -> Vec<Map<String, Value>> {
+        curve.iter().map(|s| self.row(s)).collect()
+    }
+
+    /// The row of the last snapshot, the final metrics of the run.
+    pub fn final_row(&self, curve: &LearningCurve) -> Option<Map<String, Value>> {
+        curve.as_slice().last().map(|s| self.row(s))
+    }
+
+    fn row(&self, s: &Snapshot) -> Map<String, Value> {
+        let extra = |key: &str| s.extras.get(key).copied();
+        let finite = |v: Option<f64>| json!(v.filter(|v| v.is_finite()));
+        let mut row = Map::new();
+        row.insert("step".into(), json!(s.instances_seen));
+        row.insert("track".into(), json!(self.track.name()));
+        row.insert("model".into(), json!(self.model));
+        row.insert("dataset".into(), json!(self.dataset));
+        for &metric in self.track.metrics() {
+            let value = match metric {
+                "Accuracy" | "MicroF1" => Some(s.accuracy),
+                "F1" => extra("f1_class_1"),
+                "MacroF1" => macro_f1(s),
+                "MAE" => extra(MetricKey::MAE.as_str()),
+                "RMSE" => extra(MetricKey::RMSE.as_str()),
+                "R2" => extra(MetricKey::R2.as_str()),
+                _ => None,
+            };
+            row.insert(metric.into(), finite(value));
+        }
+        let megabytes = extra(MetricKey::MODEL_BYTES.as_str()).map(|b| b / (1024.0 * 1024.0));
+        row.insert("Memory in Mb".into(), finite(megabytes));
+        row.insert("Time in s".into(), finite(Some(s.seconds)));
+        row
+    }
+
+    /// Writes `rows` (of this or other runs) to `path`: CSV with a header
+    /// line for a `.csv` path, otherwise a JSON array of row objects, the
+    /// layout `pandas.read_json(orient="records")` reads.
+    pub fn write(&self, path: &Path, rows: &[Map<String, Value>]) -> Result<(), Error> {
+        let mut w = BufWriter::new(File::create(path)?);
+        let csv = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+        if csv {
+            self.write_csv(&mut w, rows)?;
+        } else {
+            serde_json::to_writer_pretty(&mut w, rows).map_err(Error::other)?;
+            writeln!(w)?;
+        }
+        w.flush()
+    }
+
+    fn write_csv(&self, w: &mut dyn Write, rows: &[Map<String, Value>]) -> Result<(), Error> {
+        let columns = self.columns();
+        writeln!(w, "{}", columns.join(","))?;
+        for row in rows {
+            let cells: Vec<String> = columns
+                .iter()
+                .map(|&c| match row.get(c) {
+                    Some(Value::String(s)) => csv_quote(s),
+                    Some(Value::Null) | None => String::new(),
+                    Some(v) => v.to_string(),
+                })
+                .collect();
+            writeln!(w, "{}", cells.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// Mean of the per-class F1 scores of `s`, or its `f1` when the curve has
+/// no per-class scores. `None` when a class score is undefined.
+fn macro_f1(s: &Snapshot) -> Option<f64> {
+    let per_class: Vec<f64> = s
+        .extras
+        .iter()
+        .filter(|(k, _)| k.starts_with("f1_class_"))
+        .map(|(_, &v)| v)
+        .collect();
+    if per_class.is_empty() {
+        return s.extras.get(MetricKey::F1.as_str()).copied();
+    }
+    let mean = per_class.iter().sum::<f64>() / per_class.len() as f64;
+    mean.is_finite().then_some(mean)
+}
+
+fn csv_quote(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Instances seen, accuracy, and extras of a snapshot.
+    type Row<'a> = (u64, f64, &'a [(&'a str, f64)]);
+
+    fn curve(rows: &[Row]) -> LearningCurve {
+        let mut curve = LearningCurve::default();
+        for &(seen, accuracy, extras) in rows {
+            curve.push(Snapshot {
+                instances_seen: seen,
+                accuracy,
+                kappa: 0.0,
+                ram_hours: 0.0,
+                seconds: seen as f64 / 1000.0,
+                extras: extras
+                    .iter()
+                    .map(|&(k, v)| (MetricKey::new(k), v))
+                    .collect::<BTreeMap<_, _>>(),
+                eta: None,
+            });
+        }
+        curve
+    }
+
+    #[test]
+    fn binary_curves_map_onto_rivers_columns() {
+        let curve = curve(&[
+            (1000, 0.8, &[("f1_class_0", 0.7), ("f1_class_1", 0.75)]),
+            (2000, 0.85, &[("f1_class_0", 0.8), ("f1_class_1", f64::NAN)]),
+        ]);
+        assert_eq!(RiverTrack::infer(&curve), RiverTrack::BinaryClassification);
+
+        let results = RiverResults::new(RiverTrack::BinaryClassification, "Elec2", "Naive Bayes");
+        let rows = results.rows(&curve);
+        assert_eq!(
+            Value::Object(rows[0].clone()),
+            json!({
+                "step": 1000, "track": "Binary classification", "model": "Naive Bayes",
+                "dataset": "Elec2", "Accuracy": 0.8, "F1": 0.75, "Memory in Mb": null,
+                "Time in s": 1.0,
+            })
+        );
+        assert_eq!(results.final_row(&curve).unwrap()["F1"], Value::Null);
+
+        let mut csv = Vec::new();
+        results.write_csv(&mut csv, &rows).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "step,track,model,dataset,Accuracy,F1,Memory in Mb,Time in s"
+        );
+        assert_eq!(
+            lines[2],
+            "2000,Binary classification,Naive Bayes,Elec2,0.85,,,2.0"
+        );
+    }
+
+    #[test]
+    fn multiclass_and_regression_tracks_pick_their_metrics() {
+        let multiclass = curve(&[(
+            500,
+            0.6,
+            &[
+                ("f1_class_0", 0.5),
+                ("f1_class_1", 0.6),
+                ("f1_class_2", 0.7),
+            ],
+        )]);
+        assert_eq!(
+            RiverTrack::infer(&multiclass),
+            RiverTrack::MulticlassClassification
+        );
+        let row = RiverResults::new(RiverTrack::MulticlassClassification, "d", "m")
+            .final_row(&multiclass)
+            .unwrap();
+        assert_eq!(row["MicroF1"], json!(0.6));
+        assert!((row["MacroF1"].as_f64().unwrap() - 0.6).abs() < 1e-12);
+
+        let regression = curve(&[(
+            500,
+            f64::NAN,
+            &[
+                ("mae", 1.5),
+                ("rmse", 2.0),
+                ("r2", 0.4),
+                ("model_bytes", 2097152.0),
+            ],
+        )]);
+        assert_eq!(RiverTrack::infer(&regression), RiverTrack::Regression);
+        let row = RiverResults::new(RiverTrack::Regression, "d", "m")
+            .final_row(&regression)
+            .unwrap();
+        let columns: Vec<&str> = row.keys().map(String::as_str).collect();
+        assert_eq!(
+            columns,
+            RiverResults::new(RiverTrack::Regression, "d", "m").columns()
+        );
+        assert_eq!(row["RMSE"], json!(2.0));
+        assert_eq!(row["Memory in Mb"], json!(2.0));
+    }
+}
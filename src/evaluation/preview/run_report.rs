@@ -0,0 +1,358 @@
+use crate::evaluation::preview::curve_plot::escape;
+use crate::evaluation::{ConfusionMatrix, CurvePlot, DriftAlignment, LearningCurve, Measurement};
+use crate::streams::DriftMarker;
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::io::Error;
+use std::path::Path;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:960px;color:#222}\
+h1{font-size:1.6em}h2{font-size:1.2em;margin-top:2em;border-bottom:1px solid #ddd}\
+table{border-collapse:collapse;margin:.5em 0}\
+th,td{border:1px solid #ddd;padding:4px 10px;text-align:right}\
+th{background:#f5f5f5}td.name,th.name{text-align:left}\
+table.confusion td{min-width:4em}\
+pre{background:#f5f5f5;padding:1em;overflow:auto}\
+.note{color:#777}";
+
+/// Self-contained HTML page summarizing a run: the learning curve as
+/// inline SVG charts, the final metrics, the confusion matrix as a heatmap,
+/// the stream's known drifts, and the configuration it ran with.
+///
+/// Everything is embedded in the one file (no scripts, fonts, or external
+/// stylesheets), so the report can be shared and opened anywhere. Sections
+/// without data are left out, except the metrics, which note an empty curve.
+#[derive(Debug, Clone)]
+pub struct RunReport<'a> {
+    pub title: String,
+    curve: &'a LearningCurve,
+    model: &'a [Measurement],
+    confusion: Option<&'a ConfusionMatrix>,
+    drifts: &'a [DriftMarker],
+    detections: &'a [u64],
+    config: Option<&'a Value>,
+}
+
+impl<'a> RunReport<'a> {
+    pub fn new(curve: &'a LearningCurve) -> Self {
+        Self {
+            title: "Run report".into(),
+            curve,
+            model: &[],
+            confusion: None,
+            drifts: &[],
+            detections: &[],
+            config: None,
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Model statistics listed under the final metrics.
+    pub fn with_model(mut self, model: &'a [Measurement]) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn with_confusion(mut self, confusion: &'a ConfusionMatrix) -> Self {
+        self.confusion = Some(confusion);
+        self
+    }
+
+    /// Ground-truth drifts, marked on the charts and lined up with the
+    /// accuracy dips that followed them (see [`DriftAlignment`]).
+    pub fn with_drifts(mut self, drifts: &'a [DriftMarker]) -> Self {
+        self.drifts = drifts;
+        self
+    }
+
+    /// Instance counts at which a drift detector signalled, lined up with
+    /// the drifts they caught.
+    pub fn with_detections(mut self, detections: &'a [u64]) -> Self {
+        self.detections = detections;
+        self
+    }
+
+    /// Configuration shown as pretty-printed JSON.
+    pub fn with_config(mut self, config: &'a Value) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Writes the report to `path`.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        std::fs::write(path, self.to_html())
+    }
+
+    /// The report as a standalone HTML document.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let title = escape(&self.title);
+        let _ = writeln!(html, "<!DOCTYPE html>");
+        let _ = writeln!(html, r#"<html lang="en"><head><meta charset="utf-8">"#);
+        let _ = writeln!(html, "<title>{title}</title><style>{STYLE}</style>");
+        let _ = writeln!(html, "</head><body>\n<h1>{title}</h1>");
+
+        self.write_charts(&mut html);
+        self.write_metrics(&mut html);
+        if let Some(confusion) = self.confusion.filter(|m| m.total() > 0.0) {
+            write_confusion(&mut html, confusion);
+        }
+        if !self.drifts.is_empty() {
+            self.write_drifts(&mut html);
+        }
+        if let Some(config) = self.config {
+            let text = serde_json::to_string_pretty(config).unwrap_or_default();
+            let _ = writeln!(html, "<h2>Configuration</h2>\n<pre>{}</pre>", escape(&text));
+        }
+
+        html.push_str("</body></html>\n");
+        html
+    }
+
+    /// Accuracy and kappa, the extra metrics of the latest snapshot, and the
+    /// elapsed time, each against instances seen.
+    fn write_charts(&self, html: &mut String) {
+        let Some(latest) = self.curve.latest() else {
+            return;
+        };
+        let _ = writeln!(html, "<h2>Learning curve</h2>");
+        let drifts = || self.drifts.iter().map(|d| d.position);
+        let mut charts = vec![
+            CurvePlot::default()
+                .with_title("accuracy and kappa")
+                .with_markers(drifts()),
+        ];
+        if !latest.extras.is_empty() {
+            charts.push(
+                CurvePlot::default()
+                    .with_title("other metrics")
+                    .with_series(latest.extras.keys().cloned())
+                    .with_markers(drifts()),
+            );
+        }
+        charts.push(
+            CurvePlot::default()
+                .with_title("seconds")
+                .with_series(["seconds"]),
+        );
+        for chart in charts {
+            html.push_str(&chart.to_svg(self.curve));
+        }
+    }
+
+    fn write_metrics(&self, html: &mut String) {
+        let _ = writeln!(html, "<h2>Final metrics</h2>");
+        let Some(latest) = self.curve.latest() else {
+            let _ = writeln!(html, r#"<p class="note">No snapshots were taken.</p>"#);
+            return;
+        };
+        let mut rows: Vec<(String, String)> = vec![
+            ("instances seen".into(), latest.instances_seen.to_string()),
+            ("accuracy".into(), number(latest.accuracy)),
+            ("kappa".into(), number(latest.kappa)),
+        ];
+        rows.extend(
+            latest
+                .extras
+                .iter()
+                .map(|(k, v)| (k.to_string(), number(*v))),
+        );
+        rows.push(("seconds".into(), number(latest.seconds)));
+        rows.push(("RAM-hours".into(), number(latest.ram_hours)));
+        write_table(html, &rows);
+
+        if !self.model.is_empty() {
+            let _ = writeln!(html, "<h2>Model</h2>");
+            let rows: Vec<(String, String)> = self
+                .model
+                .iter()
+                .map(|m| (m.name.to_string(), number(m.value)))
+                .collect();
+            write_table(html, &rows);
+        }
+    }
+}
+
+impl RunReport<'_> {
+    /// One row per drift: where it is, the accuracy before it and at its
+    /// lowest after it, and when a detector caught it.
+    fn write_drifts(&self, html: &mut String) {
+        let aligned = DriftAlignment::align(self.drifts, self.detections, self.curve, "accuracy");
+        let _ = writeln!(html, "<h2>Drifts</h2>");
+        html.push_str(
+            "<table><tr><th>position</th><th>width</th><th>accuracy before</th>\
+             <th>lowest accuracy</th><th>lowest at</th><th>detected at</th><th>delay</th></tr>\n",
+        );
+        let count = |v: Option<u64>| v.map_or("–".into(), |v| v.to_string());
+        for a in &aligned {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                a.marker.position,
+                a.marker.width,
+                number(a.before.unwrap_or(f64::NAN)),
+                number(a.lowest.map_or(f64::NAN, |(_, v)| v)),
+                count(a.lowest.map(|(at, _)| at)),
+                count(a.detected_at),
+                count(a.delay()),
+            );
+        }
+        html.push_str("</table>\n");
+        if !self.detections.is_empty() {
+            let _ = writeln!(
+                html,
+                r#"<p class="note">{} of {} detections matched no drift.</p>"#,
+                DriftAlignment::false_alarms(self.drifts, self.detections),
+                self.detections.len()
+            );
+        }
+    }
+}
+
+fn write_table(html: &mut String, rows: &[(String, String)]) {
+    html.push_str("<table>\n");
+    for (name, value) in rows {
+        let _ = writeln!(
+            html,
+            r#"<tr><td class="name">{}</td><td>{value}</td></tr>"#,
+            escape(name)
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+/// Rows are true classes and columns predicted ones. Each cell is shaded by
+/// its share of the row, so a strong diagonal reads as a good classifier
+/// whatever the class balance.
+fn write_confusion(html: &mut String, m: &ConfusionMatrix) {
+    let k = m.num_classes();
+    let _ = writeln!(html, "<h2>Confusion matrix</h2>");
+    let _ = writeln!(
+        html,
+        r#"<p class="note">Rows are true classes, columns predicted classes; shading is the share of the row.</p>"#
+    );
+    html.push_str(r#"<table class="confusion"><tr><th class="name">true \ predicted</th>"#);
+    for p in 0..k {
+        let _ = write!(html, "<th>{}</th>", escape(&m.label(p)));
+    }
+    html.push_str("</tr>\n");
+    for t in 0..k {
+        let total = m.row_total(t);
+        let _ = write!(html, r#"<tr><th class="name">{}</th>"#, escape(&m.label(t)));
+        for p in 0..k {
+            let count = m.get(t, p);
+            let share = if total > 0.0 { count / total } else { 0.0 };
+            let text = if share > 0.6 { "white" } else { "black" };
+            let _ = write!(
+                html,
+                r#"<td style="background:rgba(31,119,180,{share:.3});color:{text}" title="{:.1}% of the row">{}</td>"#,
+                share * 100.0,
+                trim_count(count)
+            );
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+}
+
+fn number(v: f64) -> String {
+    if v.is_finite() {
+        format!("{v:.4}")
+    } else {
+        "–".into()
+    }
+}
+
+/// Whole weights without decimals, fractional ones with two.
+fn trim_count(w: f64) -> String {
+    if w.fract() == 0.0 {
+        format!("{w:.0}")
+    } else {
+        format!("{w:.2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Snapshot;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn curve() -> LearningCurve {
+        let mut lc = LearningCurve::default();
+        for i in 1..=3u64 {
+            lc.push(Snapshot {
+                instances_seen: i * 100,
+                accuracy: 0.5 + i as f64 * 0.1,
+                kappa: 0.2,
+                ram_hours: 0.0,
+                seconds: i as f64,
+                extras: BTreeMap::from([("f1".into(), 0.7)]),
+                eta: None,
+            });
+        }
+        lc
+    }
+
+    #[test]
+    fn report_embeds_charts_metrics_confusion_and_config() {
+        let curve = curve();
+        let mut confusion = ConfusionMatrix::new(vec!["yes".into(), "<no>".into()]);
+        confusion.add(0, 0, 3.0);
+        confusion.add(0, 1, 1.0);
+        confusion.add(1, 1, 2.0);
+        let model = [Measurement::new("tree_size_nodes", 5.0)];
+        let config = json!({ "type": "evaluate-prequential", "note": "a < b" });
+
+        let html = RunReport::new(&curve)
+            .with_title("SEA & NB")
+            .with_model(&model)
+            .with_confusion(&confusion)
+            .with_config(&config)
+            .to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>SEA &amp; NB</h1>"));
+        assert_eq!(html.matches("<svg").count(), 3);
+        assert!(html.contains("<td>0.8000</td>"));
+        assert!(html.contains(">tree_size_nodes</td><td>5.0000</td>"));
+        assert!(html.contains("<th>&lt;no&gt;</th>"));
+        assert!(html.contains("rgba(31,119,180,0.750)"));
+        assert!(html.contains("title=\"25.0% of the row\">1</td>"));
+        assert!(html.contains("&quot;a &lt; b&quot;"));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn empty_sections_are_left_out() {
+        let curve = LearningCurve::default();
+        let empty = ConfusionMatrix::default();
+        let html = RunReport::new(&curve).with_confusion(&empty).to_html();
+        assert!(!html.contains("<svg"));
+        assert!(html.contains("No snapshots were taken."));
+        assert!(!html.contains("Confusion matrix"));
+        assert!(!html.contains("Configuration"));
+        assert!(!html.contains("Drifts"));
+    }
+
+    #[test]
+    fn drifts_are_marked_and_tabulated() {
+        let curve = curve();
+        let drifts = [DriftMarker::new(150, 1)];
+        let html = RunReport::new(&curve)
+            .with_drifts(&drifts)
+            .with_detections(&[120, 180])
+            .to_html();
+        assert_eq!(html.matches(r#"class="marker""#).count(), 2);
+        assert!(html.contains("<h2>Drifts</h2>"));
+        assert!(html.contains(
+            "<tr><td>150</td><td>1</td><td>0.6000</td><td>0.7000</td><td>200</td><td>180</td><td>30</td></tr>"
+        ));
+        assert!(html.contains("1 of 2 detections matched no drift."));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::core::instances::Instance;
+use crate::evaluation::PredictionLogFormat;
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+
+/// Streams one anomaly score per instance to a file.
+///
+/// Uses the same formats as [`PredictionLog`](crate::evaluation::PredictionLog):
+/// CSV with the header `instance,score,label`, or JSONL objects with the
+/// same fields. The label is the class index, left empty (`null`) when the
+/// instance is unlabeled. The log is flushed when dropped or through
+/// [`ScoreLog::flush`].
+pub struct ScoreLog {
+    writer: Box<dyn Write + Send>,
+    format: PredictionLogFormat,
+    header_written: bool,
+}
+
+impl ScoreLog {
+    pub fn create<P: AsRef<Path>>(path: P, format: PredictionLogFormat) -> Result<Self, Error> {
+        let file = File::create(path)?;
+        Ok(Self::from_writer(Box::new(BufWriter::new(file)), format))
+    }
+
+    pub fn from_writer(writer: Box<dyn Write + Send>, format: PredictionLogFormat) -> Self {
+        Self {
+            writer,
+            format,
+            header_written: false,
+        }
+    }
+
+    /// Appends the `score` of the `index`-th instance (zero-based).
+    pub fn record(&mut self, index: u64, instance: &dyn Instance, score: f64) -> Result<(), Error> {
+        let label = instance
+            .class_value()
+            .filter(|y| !instance.is_class_missing() && y.is_finite())
+            .map(|y| y as usize);
+
+        match self.format {
+            PredictionLogFormat::Csv => {
+                if !self.header_written {
+                    writeln!(self.writer, "instance,score,label")?;
+                    self.header_written = true;
+                }
+                let label = label.map(|y| y.to_string()).unwrap_or_default();
+                writeln!(self.writer, "{index},{score},{label}")
+            }
+            PredictionLogFormat::Jsonl => {
+                let line = json!({
+                    "instance": index,
+                    "score": score.is_finite().then_some(score),
+                    "label": label,
+                });
+                writeln!(self.writer, "{line}")
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()
+    }
+}
+
+impl Drop for ScoreLog {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::instances::DenseInstance;
+    use crate::testing::header_binary;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn csv_and_jsonl_records() {
+        let tf = NamedTempFile::new().unwrap();
+        let mut log = ScoreLog::create(tf.path(), PredictionLogFormat::Csv).unwrap();
+        log.record(
+            0,
+            &DenseInstance::new(header_binary(), vec![1.0], 1.0),
+            0.25,
+        )
+        .unwrap();
+        log.record(
+            1,
+            &DenseInstance::new(header_binary(), vec![f64::NAN], 1.0),
+            0.5,
+        )
+        .unwrap();
+        drop(log);
+        assert_eq!(
+            std::fs::read_to_string(tf.path()).unwrap(),
+            "instance,score,label\n0,0.25,1\n1,0.5,\n"
+        );
+
+        let tf = NamedTempFile::new().unwrap();
+        let mut log = ScoreLog::create(tf.path(), PredictionLogFormat::Jsonl).unwrap();
+        log.record(
+            3,
+            &DenseInstance::new(header_binary(), vec![f64::NAN], 1.0),
+            0.75,
+        )
+        .unwrap();
+        drop(log);
+        let v: serde_json::Value =
+            serde_json::from_str(std::fs::read_to_string(tf.path()).unwrap().trim()).unwrap();
+        assert_eq!(v["instance"], 3);
+        assert_eq!(v["score"], 0.75);
+        assert!(v["label"].is_null());
+    }
+}
@@ -1,17 +1,71 @@
+use crate::evaluation::MetricKey;
+use crate::evaluation::preview::eta::Eta;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
-#[derive(Clone)]
+/// Metrics of a run at one point of its learning curve.
+///
+/// Serializes losslessly, `NaN` included, through
+/// [`float_serde`](crate::utils::float_serde); [`to_json`](Self::to_json)
+/// is the flat form written to logs and exports.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Snapshot {
     pub instances_seen: u64,
+    #[serde(with = "crate::utils::float_serde")]
     pub accuracy: f64,
+    #[serde(with = "crate::utils::float_serde")]
     pub kappa: f64,
+    #[serde(with = "crate::utils::float_serde")]
     pub ram_hours: f64,
+    #[serde(with = "crate::utils::float_serde")]
     pub seconds: f64,
-    pub extras: BTreeMap<String, f64>,
+    #[serde(with = "crate::utils::float_serde::map")]
+    pub extras: BTreeMap<MetricKey, f64>,
+    /// Remaining-work estimate against the run's limits; `None` for runs
+    /// without limits and for aggregated curves.
+    pub eta: Option<Eta>,
 }
 
 impl Snapshot {
+    /// Flat JSON object with the fixed fields, every extra under its own
+    /// key, and `eta` as a nested object (or `null`). Non-finite values are
+    /// written as `null`.
+    pub fn to_json(&self) -> Value {
+        let num = |v: f64| v.is_finite().then_some(v);
+        let mut obj = Map::new();
+        obj.insert("instances_seen".into(), json!(self.instances_seen));
+        obj.insert("accuracy".into(), json!(num(self.accuracy)));
+        obj.insert("kappa".into(), json!(num(self.kappa)));
+        obj.insert("ram_hours".into(), json!(num(self.ram_hours)));
+        obj.insert("seconds".into(), json!(num(self.seconds)));
+        for (k, v) in &self.extras {
+            obj.insert(k.to_string(), json!(num(*v)));
+        }
+        let eta = self.eta.map(|e| {
+            json!({
+                "fraction": num(e.fraction),
+                "seconds_remaining": e.seconds_remaining.and_then(num),
+                "instances_remaining": e.instances_remaining,
+            })
+        });
+        obj.insert("eta".into(), json!(eta));
+        Value::Object(obj)
+    }
+
+    /// Value of the curve column `name`: one of the fixed fields
+    /// (`accuracy`, `kappa`, `ram_hours`, `seconds`) or an extras key.
+    pub fn metric(&self, name: &str) -> Option<f64> {
+        match name {
+            "accuracy" => Some(self.accuracy),
+            "kappa" => Some(self.kappa),
+            "ram_hours" => Some(self.ram_hours),
+            "seconds" => Some(self.seconds),
+            other => self.extras.get(other).copied(),
+        }
+    }
+
     #[inline]
     fn fmtv(v: f64) -> String {
         if v.is_nan() {
@@ -52,3 +106,27 @@ impl Display for Snapshot {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_json_flattens_extras_and_nulls_non_finite_values() {
+        let s = Snapshot {
+            instances_seen: 10,
+            accuracy: 0.5,
+            kappa: f64::NAN,
+            ram_hours: 0.0,
+            seconds: 2.0,
+            extras: BTreeMap::from([("f1".into(), 0.25)]),
+            eta: Eta::estimate(10, 2.0, Some(20), None),
+        };
+        let v = s.to_json();
+        assert_eq!(v["instances_seen"], 10);
+        assert!(v["kappa"].is_null());
+        assert_eq!(v["f1"], 0.25);
+        assert_eq!(v["eta"]["fraction"], 0.5);
+        assert_eq!(v["eta"]["instances_remaining"], 10);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::error::RivuError;
+use crate::evaluation::{LearningCurve, Snapshot};
+use crate::experiment::{ExperimentManifest, ManifestError};
+use crate::tasks::{Overflow, ProgressOptions, ProgressSender, progress_channel};
+use std::io::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A snapshot emitted by one of the jobs of a [`ParallelExecutor`] run.
+#[derive(Clone)]
+pub struct JobProgress {
+    /// Index of the job in the list given to [`ParallelExecutor::run`].
+    pub job: usize,
+    pub snapshot: Snapshot,
+}
+
+/// Outcome of one job.
+pub struct JobResult {
+    pub job: usize,
+    pub name: Option<String>,
+    pub outcome: Result<LearningCurve, ManifestError>,
+}
+
+/// Runs independent experiments concurrently on at most `max_threads`
+/// worker threads.
+///
+/// Jobs are picked up in order as workers become free. Every task is built
+/// on the worker that runs it, so learners never cross threads. Snapshots
+/// of all running jobs are interleaved on the optional progress channel,
+/// tagged with their job index; its overflow policy applies to every job. A failing job does not stop the others.
+pub struct ParallelExecutor {
+    max_threads: usize,
+    progress_tx: Option<ProgressSender<JobProgress>>,
+}
+
+impl ParallelExecutor {
+    pub fn new(max_threads: usize) -> Result<Self, Error> {
+        if max_threads == 0 {
+            return Err(RivuError::config("max_threads must be > 0").into());
+        }
+        Ok(Self {
+            max_threads,
+            progress_tx: None,
+        })
+    }
+
+    /// Uses one worker per available CPU.
+    pub fn with_available_parallelism() -> Self {
+        let n = thread::available_parallelism().map_or(1, |n| n.get());
+        Self {
+            max_threads: n,
+            progress_tx: None,
+        }
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender<JobProgress>) -> Self {
+        self.progress_tx = Some(tx);
+        self
+    }
+
+    pub fn max_threads(&self) -> usize {
+        self.max_threads
+    }
+
+    /// Runs every manifest (including writing its outputs) and returns the
+    /// results in input order.
+    pub fn run(&self, jobs: Vec<ExperimentManifest>) -> Vec<JobResult> {
+        let total = jobs.len();
+        let jobs = Arc::new(jobs);
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<JobResult>>> = Mutex::new((0..total).map(|_| None).collect());
+
+        thread::scope(|scope| {
+            for _ in 0..self.max_threads.min(total) {
+                scope.spawn(|| {
+                    loop {
+                        let job = next.fetch_add(1, Ordering::Relaxed);
+                        if job >= total {
+                            break;
+                        }
+                        let manifest = &jobs[job];
+                        let outcome = self.run_one(job, manifest);
+                        results.lock().unwrap()[job] = Some(JobResult {
+                            job,
+                            name: manifest.name.clone(),
+                            outcome,
+                        });
+                    }
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.expect("every job produces a result"))
+            .collect()
+    }
+
+    fn run_one(
+        &self,
+        job: usize,
+        manifest: &ExperimentManifest,
+    ) -> Result<LearningCurve, ManifestError> {
+        let Some(out) = self.progress_tx.clone() else {
+            return manifest.run(None);
+        };
+
+        // The job blocks on its own channel only while the forwarder waits
+        // on `out`, which happens only when `out` itself blocks.
+        let (tx, rx) = progress_channel::<Snapshot>(ProgressOptions {
+            overflow: Overflow::Block,
+            ..ProgressOptions::default()
+        });
+        let forward = thread::spawn(move || {
+            for snapshot in rx {
+                if out.send(JobProgress { job, snapshot }).is_err() {
+                    break;
+                }
+            }
+        });
+        let outcome = manifest.run(Some(tx));
+        let _ = forward.join();
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeSet;
+    use std::io::ErrorKind;
+
+    fn manifest(name: &str, function_id: u8, max_instances: u64) -> ExperimentManifest {
+        let text = json!({
+            "name": name,
+            "task": {
+                "type": "evaluate-prequential",
+                "params": {
+                    "learner": { "type": "naive-bayes", "params": {} },
+                    "stream": {
+                        "type": "sea-generator",
+                        "params": { "function_id": function_id, "balance": false, "noise_pct": 0.0 }
+                    },
+                    "evaluator": { "type": "basic-classification", "params": {} },
+                    "max_instances": max_instances,
+                    "sample_frequency": 50,
+                    "mem_check_frequency": 50
+                }
+            }
+        })
+        .to_string();
+        ExperimentManifest::from_json_str(&text).unwrap()
+    }
+
+    #[test]
+    fn zero_threads_is_rejected() {
+        let err = ParallelExecutor::new(0).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn results_keep_input_order() {
+        let jobs = vec![
+            manifest("a", 1, 300),
+            manifest("b", 2, 100),
+            manifest("c", 3, 200),
+            manifest("d", 4, 50),
+        ];
+        let results = ParallelExecutor::new(2).unwrap().run(jobs);
+
+        let names: Vec<_> = results.iter().map(|r| r.name.as_deref().unwrap()).collect();
+        assert_eq!(names, vec!["a", "b", "c", "d"]);
+        let seen: Vec<u64> = results
+            .iter()
+            .map(|r| r.outcome.as_ref().unwrap().latest().unwrap().instances_seen)
+            .collect();
+        assert_eq!(seen, vec![300, 100, 200, 50]);
+    }
+
+    #[test]
+    fn progress_is_tagged_by_job() {
+        let (tx, rx) = progress_channel(ProgressOptions {
+            capacity: 1024,
+            overflow: Overflow::Block,
+        });
+        let exec = ParallelExecutor::new(3).unwrap().with_progress(tx);
+        let results = exec.run(vec![manifest("a", 1, 100), manifest("b", 2, 150)]);
+        drop(exec);
+
+        let progress: Vec<JobProgress> = rx.iter().collect();
+        let jobs: BTreeSet<usize> = progress.iter().map(|p| p.job).collect();
+        assert_eq!(jobs, BTreeSet::from([0, 1]));
+        for r in &results {
+            let curve = r.outcome.as_ref().unwrap();
+            let sent = progress.iter().filter(|p| p.job == r.job).count();
+            assert_eq!(sent, curve.len());
+        }
+    }
+
+    #[test]
+    fn a_failing_job_does_not_stop_the_others() {
+        let mut bad = manifest("bad", 1, 100);
+        bad.task = serde_json::from_value(json!({
+            "type": "evaluate-prequential",
+            "params": {
+                "learner": { "type": "naive-bayes", "params": {} },
+                "stream": { "type": "arff-file", "params": { "path": "does/not/exist.arff", "class_index": 0 } },
+                "evaluator": { "type": "basic-classification", "params": {} },
+                "sample_frequency": 50,
+                "mem_check_frequency": 50
+            }
+        }))
+        .unwrap();
+
+        let results = ParallelExecutor::new(2)
+            .unwrap()
+            .run(vec![bad, manifest("ok", 1, 100)]);
+        assert!(results[0].outcome.is_err());
+        assert!(results[1].outcome.is_ok());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::core::instance_header::TargetKind;
+use crate::experiment::{ConfigOverride, ExperimentManifest, ManifestError};
+use crate::ui::types::choices::{
+    EvaluatorChoice, EvaluatorKind, LearnerChoice, StreamChoice, TaskChoice, TaskKind, UIChoice,
+    ValidationError,
+};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use strum::IntoEnumIterator;
+
+/// An experiment described by component names instead of a config file,
+/// as given on the command line.
+///
+/// Names are the `type` tags of the config format (`rivu list` prints
+/// them), or a shorter form that is the start of exactly one of them, cut
+/// at a `-`: `sea` is `sea-generator`. Every component takes its default
+/// parameters, which overrides can then change.
+#[derive(Debug, Clone, Default)]
+pub struct RunFlags {
+    /// Task kind; `evaluate-prequential` when unset.
+    pub task: Option<String>,
+    pub stream: String,
+    pub learner: String,
+    /// Evaluator; when unset, or when the name fits several evaluators, the
+    /// first one for the learner's target kind.
+    pub evaluator: Option<String>,
+    /// Instance limit; the default sample frequency is lowered to it when
+    /// larger.
+    pub max_instances: Option<u64>,
+    pub seed: Option<u64>,
+    /// Learning curve file; the format follows the extension.
+    pub out: Option<PathBuf>,
+}
+
+impl RunFlags {
+    /// Builds the manifest these flags describe, with `overrides` applied to
+    /// the task before it is validated.
+    pub fn manifest(
+        &self,
+        overrides: &[ConfigOverride],
+    ) -> Result<ExperimentManifest, ManifestError> {
+        let task = match &self.task {
+            Some(name) => resolve::<TaskChoice>("task", name, |_| true)?,
+            None => TaskKind::EvaluatePrequential,
+        };
+        let stream = resolve::<StreamChoice>("stream", &self.stream, |_| true)?;
+        let learner = resolve::<LearnerChoice>("learner", &self.learner, |_| true)?;
+        let learner = component::<LearnerChoice>(learner);
+        let target = serde_json::from_value::<LearnerChoice>(learner.clone())?.target_kind();
+        let fits = |kind| evaluator_target(kind) == Some(target);
+        let evaluator = match &self.evaluator {
+            Some(name) => resolve::<EvaluatorChoice>("evaluator", name, fits)?,
+            None => EvaluatorKind::iter().find(|&k| fits(k)).ok_or_else(|| {
+                ValidationError::new("evaluator", "no evaluator fits the learner")
+            })?,
+        };
+
+        let mut params = TaskChoice::default_params(task);
+        params["stream"] = component::<StreamChoice>(stream);
+        params["learner"] = learner;
+        params["evaluator"] = component::<EvaluatorChoice>(evaluator);
+        if let Some(n) = self.max_instances {
+            params["max_instances"] = json!(n);
+            // The default cadence would record nothing in a shorter run.
+            if params["sample_frequency"].as_u64().is_some_and(|f| f > n) {
+                params["sample_frequency"] = json!(n.max(1));
+            }
+        }
+        let key: &'static str = task.into();
+        let manifest = json!({
+            "task": { "type": key, "params": params },
+            "seeds": self.seed.into_iter().collect::<Vec<_>>(),
+            "output": { "curve": self.out },
+        });
+        ExperimentManifest::from_config_value(manifest, overrides)
+    }
+}
+
+/// `kind` with its default parameters, as it appears in a config.
+fn component<C: UIChoice>(kind: C::Kind) -> Value {
+    let key: &'static str = kind.into();
+    json!({ "type": key, "params": C::default_params(kind) })
+}
+
+fn evaluator_target(kind: EvaluatorKind) -> Option<TargetKind> {
+    serde_json::from_value::<EvaluatorChoice>(component::<EvaluatorChoice>(kind))
+        .ok()
+        .map(|e| e.target_kind())
+}
+
+/// The kind of `C` called `name`, either its full `type` tag or the start
+/// of one cut at a `-`. When `name` starts several tags, those that `fits`
+/// are kept.
+fn resolve<C: UIChoice>(
+    field: &str,
+    name: &str,
+    fits: impl Fn(C::Kind) -> bool,
+) -> Result<C::Kind, ValidationError> {
+    let key = |k: C::Kind| -> &'static str { k.into() };
+    if let Some(kind) = C::Kind::iter().find(|&k| key(k) == name) {
+        return Ok(kind);
+    }
+    let prefix = format!("{name}-");
+    let mut matches: Vec<C::Kind> = C::Kind::iter()
+        .filter(|&k| key(k).starts_with(&prefix))
+        .collect();
+    if matches.len() > 1 {
+        matches.retain(|&k| fits(k));
+    }
+    match matches.as_slice() {
+        [kind] => Ok(*kind),
+        [] => {
+            let all: Vec<&str> = C::Kind::iter().map(key).collect();
+            Err(ValidationError::new(
+                field,
+                format!(
+                    "unknown type `{name}` (expected one of: {})",
+                    all.join(", ")
+                ),
+            ))
+        }
+        several => {
+            let names: Vec<&str> = several.iter().map(|&k| key(k)).collect();
+            Err(ValidationError::new(
+                field,
+                format!("`{name}` could be any of: {}", names.join(", ")),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn flags(stream: &str, learner: &str) -> RunFlags {
+        RunFlags {
+            stream: stream.into(),
+            learner: learner.into(),
+            max_instances: Some(200),
+            ..RunFlags::default()
+        }
+    }
+
+    fn params(m: &ExperimentManifest) -> Value {
+        serde_json::to_value(&m.task).unwrap()["params"].clone()
+    }
+
+    #[test]
+    fn short_names_resolve_and_the_evaluator_follows_the_learner() {
+        let m = flags("sea", "hoeffding-tree").manifest(&[]).unwrap();
+        assert!(matches!(m.task, TaskChoice::EvaluatePrequential(_)));
+        let p = params(&m);
+        assert_eq!(p["stream"]["type"], "sea-generator");
+        assert_eq!(p["evaluator"]["type"], "basic-classification");
+        assert_eq!(p["max_instances"], 200);
+
+        // The task is validated too, so a regression stream would be needed
+        // to build one for `target-mean`; its evaluator is picked the same way.
+        let numeric = |k| evaluator_target(k) == Some(TargetKind::Numeric);
+        assert_eq!(
+            resolve::<EvaluatorChoice>("evaluator", "basic", numeric).unwrap(),
+            EvaluatorKind::BasicRegression
+        );
+
+        let mut f = flags("sea", "naive-bayes");
+        f.evaluator = Some("window-classification".into());
+        f.task = Some("evaluate-interleaved-test-then-train".into());
+        let m = f.manifest(&[]).unwrap();
+        assert!(matches!(
+            m.task,
+            TaskChoice::EvaluateInterleavedTestThenTrain(_)
+        ));
+        assert_eq!(params(&m)["evaluator"]["type"], "window-classification");
+    }
+
+    #[test]
+    fn unknown_and_ambiguous_names_are_rejected() {
+        let err = flags("nope", "naive-bayes").manifest(&[]).unwrap_err();
+        assert!(matches!(&err, ManifestError::Invalid(e) if e.field == "stream"));
+        assert!(err.to_string().contains("sea-generator"));
+
+        let err = flags("sea", "hoeffding").manifest(&[]).unwrap_err();
+        assert!(err.to_string().contains("hoeffding-adaptive-tree"), "{err}");
+    }
+
+    #[test]
+    fn overrides_seed_and_output_make_a_runnable_manifest() {
+        let dir = tempdir().unwrap();
+        let out = dir.path().join("curve.csv");
+        let mut f = flags("sea", "naive-bayes");
+        f.seed = Some(3);
+        f.out = Some(out.clone());
+        let set: ConfigOverride = "stream.params.function_id=2".parse().unwrap();
+        let m = f.manifest(&[set]).unwrap();
+        assert_eq!(m.seeds, vec![3]);
+        assert_eq!(params(&m)["stream"]["params"]["function_id"], 2);
+
+        let curve = m.run(None).unwrap();
+        assert_eq!(curve.latest().unwrap().instances_seen, 200);
+        assert!(out.exists());
+
+        let bad: ConfigOverride = "stream.params.bogus=1".parse().unwrap();
+        assert!(f.manifest(&[bad]).is_err());
+    }
+}
@@ -0,0 +1,439 @@
+use crate::evaluation::{CurveFormat, LearningCurve};
+use crate::experiment::{ConfigOverride, OverrideError};
+use crate::tasks::{ProgressSender, Task};
+use crate::ui::types::build::{BuildError, build_seeded_task, build_task};
+use crate::ui::types::choices::{TaskChoice, UIChoice, ValidationError};
+use crate::utils::seed_sequence::SeedSequence;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid JSON manifest: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid YAML manifest: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error(transparent)]
+    Build(#[from] BuildError),
+
+    #[error(transparent)]
+    Override(#[from] OverrideError),
+
+    #[error("invalid parameters: {0}")]
+    Invalid(#[from] ValidationError),
+
+    #[error("unsupported curve format for {0} (expected .csv, .tsv, .json or .parquet)")]
+    UnsupportedCurveFormat(PathBuf),
+}
+
+/// Where a manifest run writes its results.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputPaths {
+    /// Learning curve file; the format follows the extension.
+    #[serde(default)]
+    pub curve: Option<PathBuf>,
+}
+
+/// A complete, reproducible experiment description.
+///
+/// The task is the same serde representation the wizard builds
+/// ([`TaskChoice`], with its nested stream, learner, and evaluator choices
+/// and limits). `seeds` reseeds the task: one seed runs it once with that
+/// seed, several run it once per seed and average the curves (see
+/// [`build_seeded_task`]); an empty list keeps the seeds given in `task`.
+/// `master_seed` instead derives a distinct seed for every seeded component
+/// of the task (see [`TaskChoice::with_seed_sequence`]), so the whole run
+/// is reproduced from that one number; it cannot be combined with `seeds`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentManifest {
+    #[serde(default)]
+    pub name: Option<String>,
+
+    pub task: TaskChoice,
+
+    #[serde(default)]
+    pub seeds: Vec<u64>,
+
+    #[serde(default)]
+    pub master_seed: Option<u64>,
+
+    #[serde(default)]
+    pub output: OutputPaths,
+}
+
+impl ExperimentManifest {
+    /// Loads a manifest, parsing `.yaml`/`.yml` files as YAML and anything
+    /// else as JSON.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        if is_yaml_path(path) {
+            Self::from_yaml_str(&text)
+        } else {
+            Self::from_json_str(&text)
+        }
+    }
+
+    pub fn from_json_str(text: &str) -> Result<Self, ManifestError> {
+        let manifest: Self = serde_json::from_str(text)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn from_yaml_str(text: &str) -> Result<Self, ManifestError> {
+        let manifest: Self = serde_yaml::from_str(text)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Loads a run config: either a full manifest or a bare [`TaskChoice`]
+    /// (`{"type": ..., "params": ...}`), as the wizard would build it.
+    ///
+    /// A document with a top-level `task` key is read as a manifest; anything
+    /// else is read as a task and wrapped in a manifest with no seeds and no
+    /// configured outputs. YAML and JSON are detected as in
+    /// [`ExperimentManifest::from_path`].
+    pub fn from_config_path<P: AsRef<Path>>(path: P) -> Result<Self, ManifestError> {
+        Self::from_config_path_with(path, &[])
+    }
+
+    /// Like [`from_config_path`](Self::from_config_path), with `overrides`
+    /// applied to the task before it is validated.
+    pub fn from_config_path_with<P: AsRef<Path>>(
+        path: P,
+        overrides: &[ConfigOverride],
+    ) -> Result<Self, ManifestError> {
+        Self::from_config_value(read_config_value(path.as_ref())?, overrides)
+    }
+
+    /// Loads a queue of run configs: a document holding a list has one run
+    /// per element (each a bare task or a manifest, as in
+    /// [`from_config_path`](Self::from_config_path)); any other document is
+    /// a queue of one. `overrides` apply to every run.
+    pub fn queue_from_config_path<P: AsRef<Path>>(
+        path: P,
+        overrides: &[ConfigOverride],
+    ) -> Result<Vec<Self>, ManifestError> {
+        match read_config_value(path.as_ref())? {
+            serde_json::Value::Array(items) => items
+                .into_iter()
+                .map(|v| Self::from_config_value(v, overrides))
+                .collect(),
+            value => Ok(vec![Self::from_config_value(value, overrides)?]),
+        }
+    }
+
+    pub(super) fn from_config_value(
+        value: serde_json::Value,
+        overrides: &[ConfigOverride],
+    ) -> Result<Self, ManifestError> {
+        let mut manifest = if value.get("task").is_some() {
+            serde_json::from_value(value)?
+        } else {
+            Self::from(serde_json::from_value::<TaskChoice>(value)?)
+        };
+        manifest.apply_overrides(overrides)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Sets the task parameters named by `overrides`, in order.
+    ///
+    /// The paths are resolved against the task with every default filled
+    /// in, so parameters missing from the config file can be set too.
+    pub fn apply_overrides(&mut self, overrides: &[ConfigOverride]) -> Result<(), ManifestError> {
+        if overrides.is_empty() {
+            return Ok(());
+        }
+        let mut task = serde_json::to_value(&self.task)?;
+        for o in overrides {
+            o.apply(&mut task["params"])?;
+        }
+        self.task = serde_json::from_value(task)?;
+        Ok(())
+    }
+
+    /// Format of the curve output, if one is configured.
+    pub fn curve_format(&self) -> Result<Option<(&Path, CurveFormat)>, ManifestError> {
+        let Some(path) = self.output.curve.as_deref() else {
+            return Ok(None);
+        };
+        let fmt = CurveFormat::from_path(path)
+            .ok_or_else(|| ManifestError::UnsupportedCurveFormat(path.to_path_buf()))?;
+        Ok(Some((path, fmt)))
+    }
+
+    /// Builds the runnable task, applying `master_seed` or `seeds`.
+    pub fn build(&self, progress: Option<ProgressSender>) -> Result<Box<dyn Task>, BuildError> {
+        let task = match self.master_seed {
+            Some(master) => self
+                .task
+                .clone()
+                .with_seed_sequence(&SeedSequence::new(master)),
+            None => self.task.clone(),
+        };
+        match self.seeds.as_slice() {
+            [] => build_task(task, progress),
+            [seed] => build_task(task.with_seed(*seed), progress),
+            seeds => build_seeded_task(task, seeds.to_vec(), progress),
+        }
+    }
+
+    /// Writes `curve` to the configured output, if any.
+    pub fn write_outputs(&self, curve: &LearningCurve) -> Result<(), ManifestError> {
+        if let Some((path, fmt)) = self.curve_format()? {
+            if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            curve.export(path, fmt)?;
+        }
+        Ok(())
+    }
+
+    /// Builds and runs the experiment to completion, then writes its outputs.
+    pub fn run(&self, progress: Option<ProgressSender>) -> Result<LearningCurve, ManifestError> {
+        let mut task = self.build(progress)?;
+        task.run()?;
+        let curve = task.curve().clone();
+        self.write_outputs(&curve)?;
+        Ok(curve)
+    }
+
+    /// Checks the curve output and the task's parameters (see
+    /// [`UIChoice::validate`]).
+    fn validate(&self) -> Result<(), ManifestError> {
+        if self.master_seed.is_some() && !self.seeds.is_empty() {
+            return Err(ValidationError::new(
+                "master_seed",
+                "set either master_seed or seeds, not both",
+            )
+            .into());
+        }
+        self.curve_format()?;
+        self.task.validate()?;
+        Ok(())
+    }
+}
+
+impl From<TaskChoice> for ExperimentManifest {
+    fn from(task: TaskChoice) -> Self {
+        Self {
+            name: None,
+            task,
+            seeds: Vec::new(),
+            master_seed: None,
+            output: OutputPaths::default(),
+        }
+    }
+}
+
+fn read_config_value(path: &Path) -> Result<serde_json::Value, ManifestError> {
+    let text = fs::read_to_string(path)?;
+    Ok(if is_yaml_path(path) {
+        serde_yaml::from_str(&text)?
+    } else {
+        serde_json::from_str(&text)?
+    })
+}
+
+fn is_yaml_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::types::choices::StreamChoice;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn manifest_json(seeds: &[u64], curve: Option<&Path>) -> String {
+        json!({
+            "name": "sea",
+            "task": {
+                "type": "evaluate-prequential",
+                "params": {
+                    "learner": { "type": "naive-bayes", "params": {} },
+                    "stream": {
+                        "type": "sea-generator",
+                        "params": { "function_id": 1, "balance": false, "noise_pct": 0.0 }
+                    },
+                    "evaluator": { "type": "basic-classification", "params": {} },
+                    "max_instances": 100,
+                    "sample_frequency": 25,
+                    "mem_check_frequency": 25
+                }
+            },
+            "seeds": seeds,
+            "output": { "curve": curve }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn bundled_yaml_example_parses() {
+        let m =
+            ExperimentManifest::from_yaml_str(include_str!("../../configs/sea-naive-bayes.yaml"))
+                .unwrap();
+        assert_eq!(m.name.as_deref(), Some("sea-naive-bayes"));
+        assert_eq!(m.seeds, vec![1, 2, 3]);
+        assert!(matches!(m.task, TaskChoice::EvaluatePrequential(_)));
+    }
+
+    #[test]
+    fn unknown_fields_and_curve_extensions_are_rejected() {
+        let err = ExperimentManifest::from_json_str(r#"{"task": {}, "bogus": 1}"#).unwrap_err();
+        assert!(matches!(err, ManifestError::Json(_)));
+
+        let err =
+            ExperimentManifest::from_json_str(&manifest_json(&[], Some(Path::new("out.xlsx"))))
+                .unwrap_err();
+        assert!(matches!(err, ManifestError::UnsupportedCurveFormat(_)));
+    }
+
+    #[test]
+    fn a_misspelt_nested_parameter_is_rejected() {
+        let mut value: serde_json::Value = serde_json::from_str(&manifest_json(&[], None)).unwrap();
+        value["task"]["params"]["learner"] = json!({
+            "type": "hoeffding-tree",
+            "params": { "grace_periode": 50 }
+        });
+        let err = ExperimentManifest::from_config_value(value, &[]).unwrap_err();
+        assert!(matches!(err, ManifestError::Json(_)));
+        assert!(err.to_string().contains("grace_periode"), "{err}");
+    }
+
+    #[test]
+    fn run_writes_curve_to_output_path() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("curve.csv");
+        let m = ExperimentManifest::from_json_str(&manifest_json(&[7], Some(&path))).unwrap();
+        let curve = m.run(None).unwrap();
+
+        assert_eq!(curve.latest().unwrap().instances_seen, 100);
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), curve.len() + 1);
+    }
+
+    #[test]
+    fn several_seeds_aggregate_into_mean_and_std() {
+        let m = ExperimentManifest::from_json_str(&manifest_json(&[1, 2], None)).unwrap();
+        let curve = m.run(None).unwrap();
+        assert!(curve.latest().unwrap().extras.contains_key("accuracy_std"));
+    }
+
+    #[test]
+    fn a_master_seed_reproduces_the_run_and_excludes_seeds() {
+        let mut value: serde_json::Value = serde_json::from_str(&manifest_json(&[], None)).unwrap();
+        value["master_seed"] = json!(5);
+        let m = ExperimentManifest::from_json_str(&value.to_string()).unwrap();
+        assert_eq!(m.master_seed, Some(5));
+        // Only the stream is seeded, so this is a run with its derived seed.
+        let stream_seed = SeedSequence::new(5).derive("stream");
+        let same = ExperimentManifest::from_json_str(&manifest_json(&[stream_seed], None)).unwrap();
+        let accuracy = |m: &ExperimentManifest| m.run(None).unwrap().latest().unwrap().accuracy;
+        assert_eq!(accuracy(&m), accuracy(&same));
+
+        value["seeds"] = json!([1]);
+        let err = ExperimentManifest::from_json_str(&value.to_string()).unwrap_err();
+        assert!(matches!(err, ManifestError::Invalid(e) if e.field == "master_seed"));
+    }
+
+    #[test]
+    fn json_and_yaml_files_are_detected_by_extension() {
+        let dir = tempdir().unwrap();
+        let json_path = dir.path().join("m.json");
+        fs::write(&json_path, manifest_json(&[], None)).unwrap();
+        assert!(ExperimentManifest::from_path(&json_path).is_ok());
+
+        let yaml_path = dir.path().join("m.yml");
+        let value: serde_json::Value = serde_json::from_str(&manifest_json(&[], None)).unwrap();
+        fs::write(&yaml_path, serde_yaml::to_string(&value).unwrap()).unwrap();
+        assert!(ExperimentManifest::from_path(&yaml_path).is_ok());
+    }
+
+    #[test]
+    fn config_accepts_a_bare_task_or_a_manifest() {
+        let dir = tempdir().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&manifest_json(&[3], None)).unwrap();
+
+        let task_path = dir.path().join("task.json");
+        fs::write(&task_path, value["task"].to_string()).unwrap();
+        let m = ExperimentManifest::from_config_path(&task_path).unwrap();
+        assert!(m.name.is_none() && m.seeds.is_empty());
+        assert_eq!(m.run(None).unwrap().latest().unwrap().instances_seen, 100);
+
+        let yaml_path = dir.path().join("task.yaml");
+        fs::write(&yaml_path, serde_yaml::to_string(&value["task"]).unwrap()).unwrap();
+        assert!(ExperimentManifest::from_config_path(&yaml_path).is_ok());
+
+        let manifest_path = dir.path().join("manifest.json");
+        fs::write(&manifest_path, value.to_string()).unwrap();
+        let m = ExperimentManifest::from_config_path(&manifest_path).unwrap();
+        assert_eq!(m.seeds, vec![3]);
+    }
+
+    #[test]
+    fn a_config_list_is_a_queue_of_runs() {
+        let dir = tempdir().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&manifest_json(&[], None)).unwrap();
+        let path = dir.path().join("queue.yaml");
+        let list = json!([value["task"], value]);
+        fs::write(&path, serde_yaml::to_string(&list).unwrap()).unwrap();
+
+        let set = "max_instances=50".parse::<ConfigOverride>().unwrap();
+        let queue = ExperimentManifest::queue_from_config_path(&path, &[set]).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert!(queue[0].name.is_none());
+        assert_eq!(queue[1].name.as_deref(), Some("sea"));
+        for m in &queue {
+            assert_eq!(m.run(None).unwrap().latest().unwrap().instances_seen, 50);
+        }
+
+        let single = dir.path().join("task.json");
+        fs::write(&single, value["task"].to_string()).unwrap();
+        let queue = ExperimentManifest::queue_from_config_path(&single, &[]).unwrap();
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn overrides_apply_before_validation() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("base.json");
+        fs::write(&path, manifest_json(&[], None)).unwrap();
+        let set = |s: &str| s.parse::<ConfigOverride>().unwrap();
+
+        // A cadence past max_instances is only invalid until it is fixed.
+        let overrides = [set("sample_frequency=500"), set("max_instances=1000")];
+        let m = ExperimentManifest::from_config_path_with(&path, &overrides).unwrap();
+        let TaskChoice::EvaluatePrequential(p) = &m.task else {
+            panic!("expected EvaluatePrequential");
+        };
+        assert_eq!((p.sample_frequency, p.max_instances), (500, Some(1000)));
+
+        let m = ExperimentManifest::from_config_path_with(&path, &[set("stream.params.seed=7")])
+            .unwrap();
+        let TaskChoice::EvaluatePrequential(p) = &m.task else {
+            panic!("expected EvaluatePrequential");
+        };
+        assert!(matches!(&p.stream, StreamChoice::SeaGenerator(s) if s.seed == 7));
+
+        let err = ExperimentManifest::from_config_path_with(&path, &[set("sample_frequency=500")])
+            .unwrap_err();
+        assert!(matches!(err, ManifestError::Invalid(_)));
+        let err = ExperimentManifest::from_config_path_with(&path, &[set("learner.params.k=1")])
+            .unwrap_err();
+        assert!(matches!(err, ManifestError::Override(_)));
+    }
+}
@@ -0,0 +1,21 @@
+//! Headless experiment execution from declarative manifests.
+
+mod executor;
+mod flags;
+mod manifest;
+mod overrides;
+mod queue;
+mod reproducibility;
+mod run_dir;
+mod summary;
+
+pub use executor::{JobProgress, JobResult, ParallelExecutor};
+pub use flags::RunFlags;
+pub use manifest::{ExperimentManifest, ManifestError, OutputPaths};
+pub use overrides::{ConfigOverride, OverrideError};
+pub use queue::{QueueEntry, QueueIndex};
+#[cfg(any(test, feature = "test-support"))]
+pub(crate) use reproducibility::TIMING_FIELDS;
+pub use reproducibility::{Discrepancy, Platform, Reproducibility};
+pub use run_dir::{RunDirectory, RunMetadata};
+pub use summary::{RunStatus, RunSummary};
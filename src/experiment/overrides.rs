@@ -0,0 +1,110 @@
+use serde_json::Value;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OverrideError {
+    #[error("`{0}` is not of the form path=value")]
+    Syntax(String),
+
+    #[error("`{path}` does not match any parameter of the config")]
+    UnknownPath { path: String },
+}
+
+/// One `--set path=value` override of a run config.
+///
+/// `path` is a dotted path below the task's `params`, e.g.
+/// `learner.params.grace_period` or `max_instances`; numeric segments index
+/// into lists. `value` is parsed as JSON when possible (`50`, `true`,
+/// `null`, `{"type": ...}`) and taken as a string otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigOverride {
+    pub path: Vec<String>,
+    pub value: Value,
+}
+
+impl ConfigOverride {
+    pub fn dotted_path(&self) -> String {
+        self.path.join(".")
+    }
+
+    /// Replaces the value at `path` in `params`.
+    ///
+    /// Every segment must already exist, so a misspelled parameter is an
+    /// error instead of being silently ignored.
+    pub fn apply(&self, params: &mut Value) -> Result<(), OverrideError> {
+        let mut target = params;
+        for segment in &self.path {
+            let next = match target {
+                Value::Object(map) => map.get_mut(segment),
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+                _ => None,
+            };
+            target = next.ok_or_else(|| OverrideError::UnknownPath {
+                path: self.dotted_path(),
+            })?;
+        }
+        *target = self.value.clone();
+        Ok(())
+    }
+}
+
+impl FromStr for ConfigOverride {
+    type Err = OverrideError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax = || OverrideError::Syntax(s.to_string());
+        let (path, raw) = s.split_once('=').ok_or_else(syntax)?;
+        let path: Vec<String> = path.trim().split('.').map(str::to_string).collect();
+        if path.iter().any(String::is_empty) {
+            return Err(syntax());
+        }
+        let value = serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+        Ok(Self { path, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn values_parse_as_json_or_fall_back_to_strings() {
+        let o: ConfigOverride = "learner.params.grace_period=50".parse().unwrap();
+        assert_eq!(o.path, vec!["learner", "params", "grace_period"]);
+        assert_eq!(o.value, json!(50));
+
+        let o: ConfigOverride = "stream.params.path=data/elec.arff".parse().unwrap();
+        assert_eq!(o.value, json!("data/elec.arff"));
+
+        assert!("grace_period".parse::<ConfigOverride>().is_err());
+        assert!("learner..x=1".parse::<ConfigOverride>().is_err());
+    }
+
+    #[test]
+    fn apply_replaces_existing_values_only() {
+        let mut params = json!({
+            "learner": { "type": "hoeffding-tree", "params": { "grace_period": 200 } },
+            "max_instances": null,
+        });
+        let set = |s: &str| s.parse::<ConfigOverride>().unwrap();
+
+        set("learner.params.grace_period=50")
+            .apply(&mut params)
+            .unwrap();
+        set("max_instances=1000").apply(&mut params).unwrap();
+        assert_eq!(params["learner"]["params"]["grace_period"], 50);
+        assert_eq!(params["max_instances"], 1000);
+
+        let err = set("learner.params.grace=50")
+            .apply(&mut params)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            OverrideError::UnknownPath {
+                path: "learner.params.grace".into()
+            }
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::experiment::{RunStatus, RunSummary};
+use chrono::{Local, SecondsFormat};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Error, Write};
+use std::path::{Path, PathBuf};
+
+/// One run of a queue, as listed in its [`QueueIndex`].
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueEntry {
+    /// Config file the run came from.
+    pub config: PathBuf,
+    /// Position of the run in its config file (always 0 unless the file
+    /// holds a list).
+    pub index_in_config: usize,
+    /// Results directory of the run, when `--results-dir` is set.
+    pub results_dir: Option<PathBuf>,
+    /// Curve file, when the run wrote one outside a results directory.
+    pub curve: Option<PathBuf>,
+    #[serde(flatten)]
+    pub summary: RunSummary,
+}
+
+/// Combined index of the runs of one queued invocation, so that every run
+/// and its outcome can be found from one file.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueIndex {
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// Status of the first run that did not complete, or `completed`.
+    pub status: RunStatus,
+    pub runs: Vec<QueueEntry>,
+}
+
+impl Default for QueueIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueueIndex {
+    pub fn new() -> Self {
+        Self {
+            started_at: Local::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            finished_at: None,
+            status: RunStatus::Completed,
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, entry: QueueEntry) {
+        if self.status == RunStatus::Completed {
+            self.status = entry.summary.status;
+        }
+        self.runs.push(entry);
+    }
+
+    /// Runs that did not complete.
+    pub fn failed(&self) -> usize {
+        self.runs
+            .iter()
+            .filter(|r| r.summary.status != RunStatus::Completed)
+            .count()
+    }
+
+    /// Stamps the end of the queue and writes the index to `path` as
+    /// pretty-printed JSON.
+    pub fn write(&mut self, path: &Path) -> Result<(), Error> {
+        self.finished_at = Some(Local::now().to_rfc3339_opts(SecondsFormat::Secs, true));
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut w = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut w, self)?;
+        writeln!(w)?;
+        w.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::experiment::RunMetadata;
+    use serde_json::Value;
+    use tempfile::tempdir;
+
+    fn entry(name: &str, status: RunStatus) -> QueueEntry {
+        let metadata = RunMetadata::start(Some(name.into()), Vec::new());
+        QueueEntry {
+            config: PathBuf::from("runs.yaml"),
+            index_in_config: 0,
+            results_dir: None,
+            curve: Some(PathBuf::from(format!("{name}.csv"))),
+            summary: RunSummary::new(&metadata, status),
+        }
+    }
+
+    #[test]
+    fn index_keeps_the_first_failure_and_lists_every_run() {
+        let mut index = QueueIndex::new();
+        index.push(entry("a", RunStatus::Completed));
+        index.push(entry("b", RunStatus::StreamError));
+        index.push(entry("c", RunStatus::InvalidConfig));
+        assert_eq!(index.status, RunStatus::StreamError);
+        assert_eq!(index.failed(), 2);
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("results").join("queue.json");
+        index.write(&path).unwrap();
+        let v: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(v["status"], "stream-error");
+        assert_eq!(v["runs"].as_array().unwrap().len(), 3);
+        assert_eq!(v["runs"][1]["name"], "b");
+        assert_eq!(v["runs"][1]["exit_code"], 3);
+        assert!(v["finished_at"].is_string());
+    }
+}
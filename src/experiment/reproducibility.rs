@@ -0,0 +1,254 @@
+use crate::evaluation::LearningCurve;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Error;
+use std::path::Path;
+
+/// Curve fields that depend on the machine and its load rather than on the
+/// data and the seeds, left out of [`Reproducibility::result`].
+pub(crate) const TIMING_FIELDS: [&str; 3] = ["seconds", "ram_hours", "eta"];
+
+/// Platform a run was executed on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Platform {
+    pub os: String,
+    pub family: String,
+    pub arch: String,
+    pub cpus: usize,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        Self {
+            os: std::env::consts::OS.to_string(),
+            family: std::env::consts::FAMILY.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+/// Everything needed to rerun a run and check that the rerun matches,
+/// written as `reproducibility.json` in each run directory.
+///
+/// The build (crate version, git commit with a `-dirty` suffix for
+/// uncommitted changes, compiler, and profile) and the platform are
+/// captured automatically. `config` is the configuration exactly as
+/// resolved for the run, `seeds` the seeds the task was reseeded with, and
+/// `config_seeds` every `seed` field inside the configuration by its JSON
+/// pointer. `result` is the final snapshot without its timings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reproducibility {
+    pub rivu_version: String,
+    pub git_commit: Option<String>,
+    pub rustc: Option<String>,
+    pub profile: String,
+    pub platform: Platform,
+    pub config: Value,
+    pub seeds: Vec<u64>,
+    pub config_seeds: BTreeMap<String, u64>,
+    pub result: Option<Value>,
+}
+
+/// A field whose value in a rerun differs from the recorded one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Discrepancy {
+    /// JSON pointer of the field in `reproducibility.json`, e.g.
+    /// `/config/stream/seed` or `/result/accuracy`.
+    pub field: String,
+    pub expected: Value,
+    pub actual: Value,
+}
+
+impl Reproducibility {
+    /// Captures the build and platform along with the run's `config`, the
+    /// `seeds` it was reseeded with, and the final snapshot of `curve`.
+    pub fn capture<C: Serialize>(
+        config: &C,
+        seeds: &[u64],
+        curve: &LearningCurve,
+    ) -> Result<Self, Error> {
+        let config = serde_json::to_value(config).map_err(Error::other)?;
+        let mut config_seeds = BTreeMap::new();
+        collect_seeds(&config, String::new(), &mut config_seeds);
+        let result = curve.latest().map(|s| {
+            let mut v = s.to_json();
+            if let Value::Object(obj) = &mut v {
+                for field in TIMING_FIELDS {
+                    obj.remove(field);
+                }
+            }
+            v
+        });
+        Ok(Self {
+            rivu_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: option_env!("RIVU_GIT_COMMIT").map(str::to_string),
+            rustc: option_env!("RIVU_RUSTC_VERSION").map(str::to_string),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+            .to_string(),
+            platform: Platform::current(),
+            config,
+            seeds: seeds.to_vec(),
+            config_seeds,
+            result,
+        })
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        serde_json::from_str(&fs::read_to_string(path)?).map_err(Error::other)
+    }
+
+    /// Lists where `rerun` departs from this record: the crate version, git
+    /// commit, configuration, seeds, and final metrics must be identical.
+    /// The compiler, profile, and platform are informational and not
+    /// compared, since they legitimately vary between machines. An empty
+    /// list means the rerun reproduced this run.
+    pub fn verify(&self, rerun: &Reproducibility) -> Vec<Discrepancy> {
+        let mut out = Vec::new();
+        diff(&self.compared(), &rerun.compared(), String::new(), &mut out);
+        out
+    }
+
+    /// The fields [`verify`](Self::verify) compares.
+    fn compared(&self) -> Value {
+        let mut obj = Map::new();
+        obj.insert("rivu_version".into(), self.rivu_version.clone().into());
+        obj.insert("git_commit".into(), self.git_commit.clone().into());
+        obj.insert("config".into(), self.config.clone());
+        obj.insert("seeds".into(), self.seeds.clone().into());
+        obj.insert("result".into(), self.result.clone().unwrap_or(Value::Null));
+        Value::Object(obj)
+    }
+}
+
+/// Records every `seed` field that holds an unsigned integer.
+fn collect_seeds(value: &Value, pointer: String, out: &mut BTreeMap<String, u64>) {
+    match value {
+        Value::Object(obj) => {
+            for (k, v) in obj {
+                let child = format!("{pointer}/{}", escape_pointer(k));
+                if k == "seed"
+                    && let Some(seed) = v.as_u64()
+                {
+                    out.insert(child.clone(), seed);
+                }
+                collect_seeds(v, child, out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                collect_seeds(v, format!("{pointer}/{i}"), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Appends the differing leaves of two JSON values, descending into objects
+/// and into arrays of the same length.
+fn diff(expected: &Value, actual: &Value, pointer: String, out: &mut Vec<Discrepancy>) {
+    match (expected, actual) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (k, va) in a {
+                let child = format!("{pointer}/{}", escape_pointer(k));
+                diff(va, b.get(k).unwrap_or(&Value::Null), child, out);
+            }
+            for (k, vb) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                diff(
+                    &Value::Null,
+                    vb,
+                    format!("{pointer}/{}", escape_pointer(k)),
+                    out,
+                );
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (i, (va, vb)) in a.iter().zip(b).enumerate() {
+                diff(va, vb, format!("{pointer}/{i}"), out);
+            }
+        }
+        _ if expected != actual => out.push(Discrepancy {
+            field: pointer,
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Snapshot;
+    use serde_json::json;
+
+    fn curve(accuracy: f64, seconds: f64) -> LearningCurve {
+        let mut c = LearningCurve::default();
+        c.push(Snapshot {
+            instances_seen: 100,
+            accuracy,
+            kappa: 0.1,
+            ram_hours: 0.0,
+            seconds,
+            extras: BTreeMap::new(),
+            eta: None,
+        });
+        c
+    }
+
+    fn config(seed: u64) -> Value {
+        json!({
+            "type": "evaluate-prequential",
+            "stream": { "type": "sea", "seed": seed },
+            "learners": [{ "seed": 3 }],
+        })
+    }
+
+    #[test]
+    fn captures_build_config_seeds_and_result_without_timings() {
+        let r = Reproducibility::capture(&config(7), &[1, 2], &curve(0.8, 5.0)).unwrap();
+        assert_eq!(r.rivu_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(r.seeds, vec![1, 2]);
+        assert_eq!(
+            r.config_seeds,
+            BTreeMap::from([("/learners/0/seed".into(), 3), ("/stream/seed".into(), 7)])
+        );
+        let result = r.result.as_ref().unwrap();
+        assert_eq!(result["accuracy"], 0.8);
+        assert!(result.get("seconds").is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reproducibility.json");
+        fs::write(&path, serde_json::to_string(&r).unwrap()).unwrap();
+        assert_eq!(Reproducibility::read(&path).unwrap(), r);
+    }
+
+    #[test]
+    fn verify_reports_what_changed_and_ignores_timing_and_platform() {
+        let original = Reproducibility::capture(&config(7), &[], &curve(0.8, 5.0)).unwrap();
+        let mut same = Reproducibility::capture(&config(7), &[], &curve(0.8, 9.0)).unwrap();
+        same.platform.cpus += 1;
+        assert!(original.verify(&same).is_empty());
+
+        let changed = Reproducibility::capture(&config(8), &[4], &curve(0.75, 5.0)).unwrap();
+        let fields: Vec<String> = original
+            .verify(&changed)
+            .into_iter()
+            .map(|d| d.field)
+            .collect();
+        assert_eq!(
+            fields,
+            vec!["/config/stream/seed", "/seeds", "/result/accuracy"]
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use crate::evaluation::{
+    ConfusionMatrix, CurveFormat, CurvePlot, LearningCurve, Measurement, PlotFormat, RunReport,
+};
+use crate::experiment::{Reproducibility, RunSummary};
+use crate::streams::DriftMarker;
+use crate::tasks::Task;
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::fs::{self, File};
+use std::io::{BufWriter, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Environment and bookkeeping of one run, written as `metadata.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunMetadata {
+    pub name: Option<String>,
+    pub rivu_version: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub wall_clock_seconds: Option<f64>,
+    pub os: String,
+    pub arch: String,
+    pub cpus: usize,
+    pub command_line: Vec<String>,
+    /// Seeds the task was reseeded with; empty when it ran with the seeds
+    /// stored in `config.json`.
+    pub seeds: Vec<u64>,
+    /// Files of the run directory, relative to it.
+    pub files: Vec<String>,
+    #[serde(skip)]
+    started: Instant,
+}
+
+impl RunMetadata {
+    /// Captures the environment at the start of a run.
+    pub fn start(name: Option<String>, seeds: Vec<u64>) -> Self {
+        Self {
+            name,
+            rivu_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: rfc3339(Local::now()),
+            finished_at: None,
+            wall_clock_seconds: None,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            command_line: std::env::args().collect(),
+            seeds,
+            files: Vec::new(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Time since [`RunMetadata::start`].
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Stamps the end of the run.
+    pub fn finish(&mut self, wall_clock: Duration) {
+        self.finished_at = Some(rfc3339(Local::now()));
+        self.wall_clock_seconds = Some(wall_clock.as_secs_f64());
+    }
+}
+
+/// A per-run results directory, `<root>/<timestamp>[-<name>]`.
+///
+/// Holds everything needed to interpret and compare a run later:
+/// `config.json` (the task as run), `curve.csv`, `curve.svg`, `metrics.json` (final
+/// snapshot and model statistics), `report.html` (all of it on one
+/// shareable page), `progress.log`, `model.json` when the
+/// learner can be saved, `summary.json` (status and timings, also written
+/// when the run fails), `reproducibility.json` (see [`Reproducibility`]),
+/// and `metadata.json` listing the other files.
+pub struct RunDirectory {
+    path: PathBuf,
+    files: Vec<String>,
+}
+
+impl RunDirectory {
+    /// Creates a fresh directory under `root`, adding a numeric suffix if a
+    /// run with the same timestamp and name already exists.
+    pub fn create<P: AsRef<Path>>(root: P, name: Option<&str>) -> Result<Self, Error> {
+        let root = root.as_ref();
+        fs::create_dir_all(root)?;
+
+        let mut base = Local::now().format("%Y%m%d-%H%M%S").to_string();
+        if let Some(name) = name.map(sanitize).filter(|n| !n.is_empty()) {
+            base.push('-');
+            base.push_str(&name);
+        }
+        for attempt in 1.. {
+            let dir = match attempt {
+                1 => root.join(&base),
+                n => root.join(format!("{base}-{n}")),
+            };
+            match fs::create_dir(&dir) {
+                Ok(()) => {
+                    return Ok(Self {
+                        path: dir,
+                        files: Vec::new(),
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of `file` inside the directory, recorded in the metadata.
+    pub fn file(&mut self, file: &str) -> PathBuf {
+        if !self.files.iter().any(|f| f == file) {
+            self.files.push(file.to_string());
+        }
+        self.path.join(file)
+    }
+
+    /// Writes the task configuration as pretty-printed JSON.
+    pub fn write_config<T: Serialize>(&mut self, config: &T) -> Result<PathBuf, Error> {
+        let path = self.file("config.json");
+        write_json(&path, &serde_json::to_value(config).map_err(Error::other)?)?;
+        Ok(path)
+    }
+
+    /// Writes the learning curve as CSV and its snapshots as a plain log.
+    pub fn write_curve(&mut self, curve: &LearningCurve) -> Result<(), Error> {
+        curve.export(self.file("curve.csv"), CurveFormat::Csv)?;
+        let mut log = BufWriter::new(File::create(self.file("progress.log"))?);
+        for s in curve.iter() {
+            writeln!(log, "{s}")?;
+        }
+        log.flush()
+    }
+
+    /// Draws accuracy and kappa over instances as `curve.svg`.
+    pub fn write_plot(&mut self, curve: &LearningCurve) -> Result<(), Error> {
+        CurvePlot::default().render(curve, self.file("curve.svg"), PlotFormat::Svg)
+    }
+
+    /// Writes the final snapshot and the model statistics.
+    pub fn write_metrics(
+        &mut self,
+        curve: &LearningCurve,
+        model: &[Measurement],
+    ) -> Result<(), Error> {
+        let mut model_obj = Map::new();
+        for m in model {
+            model_obj.insert(
+                m.name.to_string(),
+                json!(m.value.is_finite().then_some(m.value)),
+            );
+        }
+        let metrics = json!({
+            "final": curve.latest().map(|s| s.to_json()),
+            "model": model_obj,
+        });
+        write_json(&self.file("metrics.json"), &metrics)
+    }
+
+    /// Writes `report.html`, a self-contained page with the curve charts,
+    /// final metrics, confusion matrix, the stream's known `drifts` lined
+    /// up with the drift detector's `detections`, and `config`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_report<T: Serialize>(
+        &mut self,
+        title: &str,
+        config: &T,
+        curve: &LearningCurve,
+        model: &[Measurement],
+        confusion: Option<&ConfusionMatrix>,
+        drifts: &[DriftMarker],
+        detections: &[u64],
+    ) -> Result<(), Error> {
+        let config = serde_json::to_value(config).map_err(Error::other)?;
+        let mut report = RunReport::new(curve)
+            .with_title(title)
+            .with_model(model)
+            .with_drifts(drifts)
+            .with_detections(detections)
+            .with_config(&config);
+        if let Some(confusion) = confusion {
+            report = report.with_confusion(confusion);
+        }
+        report.write(self.file("report.html"))
+    }
+
+    /// Saves the task's model as `model.json`, if it has one that can be
+    /// saved. Returns whether a model was written.
+    pub fn write_model(&mut self, task: &dyn Task) -> Result<bool, Error> {
+        let path = self.path.join("model.json");
+        let mut w = BufWriter::new(File::create(&path)?);
+        match task.save_model(&mut w) {
+            Ok(()) => {
+                w.flush()?;
+                self.file("model.json");
+                Ok(true)
+            }
+            Err(e) if e.kind() == ErrorKind::Unsupported => {
+                drop(w);
+                fs::remove_file(&path)?;
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the run's status, timings, and final metrics.
+    pub fn write_summary(&mut self, summary: &RunSummary) -> Result<(), Error> {
+        let path = self.file("summary.json");
+        write_json(&path, &serde_json::to_value(summary).map_err(Error::other)?)
+    }
+
+    /// Writes the build, platform, resolved config, seeds, and final result
+    /// as `reproducibility.json`, against which a rerun can be verified.
+    pub fn write_reproducibility<T: Serialize>(
+        &mut self,
+        config: &T,
+        seeds: &[u64],
+        curve: &LearningCurve,
+    ) -> Result<Reproducibility, Error> {
+        let record = Reproducibility::capture(config, seeds, curve)?;
+        let path = self.file("reproducibility.json");
+        write_json(&path, &serde_json::to_value(&record).map_err(Error::other)?)?;
+        Ok(record)
+    }
+
+    /// Writes `metadata.json`, listing every file written so far.
+    pub fn write_metadata(&mut self, metadata: &RunMetadata) -> Result<(), Error> {
+        let path = self.file("metadata.json");
+        let mut metadata = metadata.clone();
+        metadata.files = self.files.clone();
+        write_json(
+            &path,
+            &serde_json::to_value(metadata).map_err(Error::other)?,
+        )
+    }
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<(), Error> {
+    let mut text = serde_json::to_string_pretty(value).map_err(Error::other)?;
+    text.push('\n');
+    fs::write(path, text)
+}
+
+fn rfc3339(t: DateTime<Local>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Keeps names safe to use as a path component.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Snapshot;
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
+
+    fn curve() -> LearningCurve {
+        let mut c = LearningCurve::default();
+        c.push(Snapshot {
+            instances_seen: 10,
+            accuracy: 0.5,
+            kappa: 0.0,
+            ram_hours: 0.0,
+            seconds: 1.0,
+            extras: BTreeMap::new(),
+            eta: None,
+        });
+        c
+    }
+
+    #[test]
+    fn directories_are_unique_and_named() {
+        let root = tempdir().unwrap();
+        let a = RunDirectory::create(root.path(), Some("sea nb")).unwrap();
+        let b = RunDirectory::create(root.path(), Some("sea nb")).unwrap();
+        assert_ne!(a.path(), b.path());
+        let name = a.path().file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.ends_with("-sea_nb"), "{name}");
+    }
+
+    #[test]
+    fn writes_self_describing_run() {
+        let root = tempdir().unwrap();
+        let mut dir = RunDirectory::create(root.path(), None).unwrap();
+        dir.write_config(&json!({ "type": "evaluate-prequential" }))
+            .unwrap();
+        dir.write_curve(&curve()).unwrap();
+        dir.write_metrics(&curve(), &[Measurement::new("tree_size_nodes", 3.0)])
+            .unwrap();
+        let mut confusion = ConfusionMatrix::new(vec!["a".into(), "b".into()]);
+        confusion.add(1, 0, 1.0);
+        dir.write_report(
+            "x",
+            &json!({ "type": "evaluate-prequential" }),
+            &curve(),
+            &[],
+            Some(&confusion),
+            &[DriftMarker::new(150, 1)],
+            &[160],
+        )
+        .unwrap();
+        dir.write_reproducibility(&json!({ "seed": 4 }), &[1, 2], &curve())
+            .unwrap();
+        let mut meta = RunMetadata::start(Some("x".into()), vec![1, 2]);
+        meta.finish(Duration::from_millis(1500));
+        dir.write_metadata(&meta).unwrap();
+
+        let read = |f: &str| -> Value {
+            serde_json::from_str(&fs::read_to_string(dir.path().join(f)).unwrap()).unwrap()
+        };
+        assert_eq!(read("metrics.json")["final"]["instances_seen"], 10);
+        assert_eq!(read("metrics.json")["model"]["tree_size_nodes"], 3.0);
+        let meta = read("metadata.json");
+        assert_eq!(meta["seeds"], json!([1, 2]));
+        assert_eq!(meta["wall_clock_seconds"], 1.5);
+        assert_eq!(
+            meta["files"],
+            json!([
+                "config.json",
+                "curve.csv",
+                "progress.log",
+                "metrics.json",
+                "report.html",
+                "reproducibility.json",
+                "metadata.json"
+            ])
+        );
+        let record = Reproducibility::read(dir.path().join("reproducibility.json")).unwrap();
+        assert_eq!(record.config_seeds["/seed"], 4);
+        let report = fs::read_to_string(dir.path().join("report.html")).unwrap();
+        assert!(report.contains("Confusion matrix"));
+        assert!(report.contains("<h2>Drifts</h2>"));
+        assert!(report.contains("evaluate-prequential"));
+        assert!(
+            fs::read_to_string(dir.path().join("progress.log"))
+                .unwrap()
+                .starts_with("seen=10")
+        );
+    }
+
+    struct NoModel(LearningCurve);
+
+    impl Task for NoModel {
+        fn run(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn curve(&self) -> &LearningCurve {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn unsupported_models_leave_no_file() {
+        let root = tempdir().unwrap();
+        let mut dir = RunDirectory::create(root.path(), None).unwrap();
+        assert!(!dir.write_model(&NoModel(curve())).unwrap());
+        assert!(!dir.path().join("model.json").exists());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::error::RivuError;
+use crate::evaluation::LearningCurve;
+use crate::experiment::{ManifestError, RunMetadata};
+use crate::ui::types::build::BuildError;
+use serde::Serialize;
+use serde_json::Value;
+use std::process::ExitCode;
+
+/// How a run ended.
+///
+/// Each status has its own process exit code so that scripts can branch on
+/// the outcome without parsing any output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RunStatus {
+    /// The run reached the end of its stream or one of its limits.
+    Completed,
+    /// The run hit an error not covered by the other statuses.
+    Failed,
+    /// The config could not be read, parsed, or turned into a task.
+    InvalidConfig,
+    /// Opening or reading the input stream failed.
+    StreamError,
+    /// The user stopped the run (or left the wizard) before it finished.
+    Cancelled,
+}
+
+impl RunStatus {
+    /// The status as written in `summary.json` (e.g. `invalid-config`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunStatus::Completed => "completed",
+            RunStatus::Failed => "failed",
+            RunStatus::InvalidConfig => "invalid-config",
+            RunStatus::StreamError => "stream-error",
+            RunStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// `0` completed, `1` failed, `2` invalid config, `3` stream error,
+    /// `4` cancelled.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            RunStatus::Completed => 0,
+            RunStatus::Failed => 1,
+            RunStatus::InvalidConfig => 2,
+            RunStatus::StreamError => 3,
+            RunStatus::Cancelled => 4,
+        }
+    }
+}
+
+impl From<RunStatus> for ExitCode {
+    fn from(status: RunStatus) -> Self {
+        ExitCode::from(status.exit_code())
+    }
+}
+
+/// I/O errors while building come from opening the input files, unless they
+/// carry a [`RivuError::Config`] from a constructor rejecting its parameters;
+/// the other build errors are bad parameters.
+impl From<&BuildError> for RunStatus {
+    fn from(e: &BuildError) -> Self {
+        match e {
+            BuildError::Io(e) => match RivuError::of(e) {
+                Some(RivuError::Config(_) | RivuError::IncompatibleHeader { .. }) => {
+                    RunStatus::InvalidConfig
+                }
+                _ => RunStatus::StreamError,
+            },
+            BuildError::NotImplemented(_) | BuildError::InvalidParameter(_) => {
+                RunStatus::InvalidConfig
+            }
+        }
+    }
+}
+
+/// Status of a run that failed with `e` in [`ExperimentManifest::run`]
+/// (an I/O error there means the stream failed).
+///
+/// [`ExperimentManifest::run`]: crate::experiment::ExperimentManifest::run
+impl From<&ManifestError> for RunStatus {
+    fn from(e: &ManifestError) -> Self {
+        match e {
+            ManifestError::Io(_) => RunStatus::StreamError,
+            ManifestError::Build(b) => b.into(),
+            ManifestError::Json(_)
+            | ManifestError::Yaml(_)
+            | ManifestError::Override(_)
+            | ManifestError::Invalid(_)
+            | ManifestError::UnsupportedCurveFormat(_) => RunStatus::InvalidConfig,
+        }
+    }
+}
+
+/// Machine-readable outcome of one run, written as `summary.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub name: Option<String>,
+    pub status: RunStatus,
+    pub exit_code: u8,
+    /// The error that ended the run, for statuses other than `completed`
+    /// and `cancelled`.
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub wall_clock_seconds: Option<f64>,
+    /// Evaluation time reported by the task, which excludes pauses and
+    /// building the task.
+    pub run_seconds: Option<f64>,
+    pub instances_seen: Option<u64>,
+    /// The last snapshot of the curve, as in `metrics.json`.
+    pub final_metrics: Option<Value>,
+}
+
+impl RunSummary {
+    /// Summary with the name and timings of `metadata` (call
+    /// [`RunMetadata::finish`] first).
+    pub fn new(metadata: &RunMetadata, status: RunStatus) -> Self {
+        Self {
+            name: metadata.name.clone(),
+            status,
+            exit_code: status.exit_code(),
+            error: None,
+            started_at: metadata.started_at.clone(),
+            finished_at: metadata.finished_at.clone(),
+            wall_clock_seconds: metadata.wall_clock_seconds,
+            run_seconds: None,
+            instances_seen: None,
+            final_metrics: None,
+        }
+    }
+
+    /// Adds the final snapshot of `curve`, if it has one.
+    pub fn with_curve(mut self, curve: &LearningCurve) -> Self {
+        if let Some(last) = curve.latest() {
+            self.run_seconds = Some(last.seconds);
+            self.instances_seen = Some(last.instances_seen);
+            self.final_metrics = Some(last.to_json());
+        }
+        self
+    }
+
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Snapshot;
+    use std::collections::BTreeMap;
+    use std::io::{Error, ErrorKind};
+    use std::time::Duration;
+
+    #[test]
+    fn statuses_have_distinct_exit_codes() {
+        let all = [
+            RunStatus::Completed,
+            RunStatus::Failed,
+            RunStatus::InvalidConfig,
+            RunStatus::StreamError,
+            RunStatus::Cancelled,
+        ];
+        let mut codes: Vec<u8> = all.iter().map(|s| s.exit_code()).collect();
+        codes.dedup();
+        assert_eq!(codes, vec![0, 1, 2, 3, 4]);
+        for status in all {
+            assert_eq!(serde_json::to_value(status).unwrap(), status.as_str());
+        }
+    }
+
+    #[test]
+    fn errors_map_to_config_or_stream_failures() {
+        let io = || Error::new(ErrorKind::NotFound, "no such file");
+        assert_eq!(
+            RunStatus::from(&ManifestError::Io(io())),
+            RunStatus::StreamError
+        );
+        assert_eq!(
+            RunStatus::from(&ManifestError::Build(BuildError::Io(io()))),
+            RunStatus::StreamError
+        );
+        assert_eq!(
+            RunStatus::from(&BuildError::InvalidParameter("k".into())),
+            RunStatus::InvalidConfig
+        );
+        assert_eq!(
+            RunStatus::from(&BuildError::Io(RivuError::config("k must be > 0").into())),
+            RunStatus::InvalidConfig
+        );
+        assert_eq!(
+            RunStatus::from(&BuildError::Io(RivuError::parse("bad row").into())),
+            RunStatus::StreamError
+        );
+        let json = serde_json::from_str::<Value>("{").unwrap_err();
+        assert_eq!(
+            RunStatus::from(&ManifestError::Json(json)),
+            RunStatus::InvalidConfig
+        );
+    }
+
+    #[test]
+    fn summary_carries_timings_and_final_snapshot() {
+        let mut metadata = RunMetadata::start(Some("sea".into()), Vec::new());
+        metadata.finish(Duration::from_secs(2));
+        let mut curve = LearningCurve::default();
+        curve.push(Snapshot {
+            instances_seen: 500,
+            accuracy: 0.9,
+            kappa: 0.8,
+            ram_hours: 0.0,
+            seconds: 1.5,
+            extras: BTreeMap::new(),
+            eta: None,
+        });
+
+        let summary = RunSummary::new(&metadata, RunStatus::Completed).with_curve(&curve);
+        let v = serde_json::to_value(&summary).unwrap();
+        assert_eq!(v["status"], "completed");
+        assert_eq!(v["exit_code"], 0);
+        assert_eq!(v["wall_clock_seconds"], 2.0);
+        assert_eq!(v["run_seconds"], 1.5);
+        assert_eq!(v["final_metrics"]["accuracy"], 0.9);
+        assert!(v["error"].is_null());
+    }
+}
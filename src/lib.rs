@@ -1,10 +1,17 @@
+pub mod anomaly;
 pub mod classifiers;
+pub mod clusterers;
 pub mod core;
+pub mod drift;
+pub mod error;
 pub mod evaluation;
+pub mod experiment;
 pub mod streams;
 pub mod tasks;
 pub mod ui;
 pub mod utils;
 
+pub use error::RivuError;
+
 #[cfg(any(test, feature = "test-support"))]
 pub mod testing;
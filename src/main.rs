@@ -1,15 +1,44 @@
-use std::io::{self, Write};
-use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{RecvTimeoutError, Sender};
 use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
 
-use rivu::evaluation::Snapshot;
-use rivu::tasks::PrequentialEvaluator;
-use rivu::ui::cli::{drivers::InquireDriver, wizard::prompt_choice};
-use rivu::ui::types::build::{build_evaluator, build_learner, build_stream};
-use rivu::ui::types::choices::TaskChoice;
+use rivu::classifiers::ClassifierState;
+use rivu::evaluation::{
+    ConfusionMatrix, CurveComparison, CurveFormat, CurvePlot, LearningCurve, Measurement,
+    PlotFormat, RiverResults, RiverTrack, Snapshot, TestOutcome,
+};
+use rivu::experiment::{
+    ConfigOverride, ExperimentManifest, JobProgress, ParallelExecutor, QueueEntry, QueueIndex,
+    RunDirectory, RunFlags, RunMetadata, RunStatus, RunSummary,
+};
+use rivu::streams::DriftMarker;
+use rivu::tasks::{
+    Overflow, ProgressOptions, ProgressReceiver, ProgressSender, SharedModel, Task, TaskControl,
+    progress_channel,
+};
+use rivu::ui::cli::catalog::{ComponentCategory, all_schemas, param_summary};
+use rivu::ui::cli::drivers::{GoBack, InquireDriver};
+use rivu::ui::cli::results_browser::{self, BrowserView, ResultsBrowser};
+use rivu::ui::cli::sparkline::Sparkline;
+use rivu::ui::cli::wizard::{
+    RecentConfigs, prompt_choice_with, prompt_preset, prompt_save_config, review_choice,
+    write_config,
+};
+use rivu::ui::dashboard::Dashboard;
+use rivu::ui::inference::InferenceServer;
+use rivu::ui::types::build::{BuildError, build_task};
+use rivu::ui::types::choices::{TaskChoice, UIChoice};
 
 const RESET: &str = "\x1b[0m";
 const BOLD: &str = "\x1b[1m";
@@ -19,78 +48,1566 @@ const FG_GREEN: &str = "\x1b[32m";
 const FG_MAGENTA: &str = "\x1b[35m";
 const FG_BLUE: &str = "\x1b[34m";
 const FG_GREY: &str = "\x1b[90m";
+const FG_RED: &str = "\x1b[31m";
 
-fn main() -> Result<()> {
-    let driver = InquireDriver;
+#[derive(Parser)]
+#[command(name = "rivu", version, about = "MOA-style stream learning evaluation")]
+struct Cli {
+    /// Without a subcommand, the interactive wizard is started.
+    #[command(subcommand)]
+    command: Option<Command>,
 
-    let task: TaskChoice =
-        prompt_choice::<TaskChoice, _>(&driver).context("failed while prompting for task")?;
+    /// How live progress is reported. `status` falls back to `plain` when
+    /// stdout is not a terminal.
+    #[arg(long, value_enum, global = true, default_value_t = ProgressFormat::Status)]
+    progress_format: ProgressFormat,
 
-    let render: JoinHandle<()>;
+    /// Report progress as periodic plain log lines (same as
+    /// `--progress-format plain`).
+    #[arg(short, long, global = true)]
+    quiet: bool,
 
-    let mut runner = match task {
-        TaskChoice::EvaluatePrequential(p) => {
-            let stream_choice = p.stream;
-            let evaluator_choice = p.evaluator;
-            let learner_choice = p.learner;
-            let max_instances = p.max_instances;
-            let max_seconds = p.max_seconds;
-            let sample_freq = p.sample_frequency;
-            let mem_check_freq = p.mem_check_frequency;
+    /// Write each run to its own timestamped directory under DIR (config,
+    /// curve, final metrics, model, log, and metadata).
+    #[arg(long, global = true, value_name = "DIR")]
+    results_dir: Option<PathBuf>,
 
-            let header: Vec<String> = vec![
-                format!("{BOLD}{FG_CYAN}▶ Prequential Evaluation{RESET}"),
-                format!(
-                    "{DIM}sample_freq={}{RESET}  {DIM}mem_check_freq={}{RESET}  {}",
-                    sample_freq,
-                    mem_check_freq,
-                    timestamp_now()
-                ),
-                format!(
-                    "{FG_GREY}────────────────────────────────────────────────────────────────────────{RESET}"
-                ),
-            ];
+    /// Follow ARFF/CSV input files: keep evaluating rows appended by another
+    /// process, and start over from the first row when the file is truncated
+    /// or replaced.
+    #[arg(long, global = true)]
+    watch: bool,
+
+    /// With `--watch`, end the stream once the file has not grown for SECS
+    /// seconds (default: wait until stopped).
+    #[arg(long, global = true, value_name = "SECS", requires = "watch")]
+    watch_idle: Option<u64>,
+
+    /// Serve a live dashboard of the running curves over HTTP on ADDR (e.g.
+    /// `0.0.0.0:8080`). It stops when the process exits.
+    #[arg(long, global = true, value_name = "ADDR")]
+    serve: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProgressFormat {
+    /// A status line with metrics and progress bars, repainted in place.
+    Status,
+    /// Plain log lines without colors or repainting, at most one every few
+    /// seconds.
+    Plain,
+    /// One JSON object per snapshot on stdout; other messages go to stderr.
+    Json,
+}
+
+impl ProgressFormat {
+    /// Prints an informational message, keeping stdout machine-readable in
+    /// JSON mode.
+    fn note(self, msg: &str) {
+        match self {
+            ProgressFormat::Status => println!("{msg}"),
+            ProgressFormat::Plain => println!("{}", strip_ansi(msg)),
+            ProgressFormat::Json => eprintln!("{}", strip_ansi(msg)),
+        }
+    }
+
+    /// Downgrades the repainted status line to plain lines when asked to be
+    /// quiet or when stdout is redirected.
+    fn resolve(self, quiet: bool, stdout_is_tty: bool) -> Self {
+        match self {
+            ProgressFormat::Status if quiet || !stdout_is_tty => ProgressFormat::Plain,
+            other => other,
+        }
+    }
+
+    /// Progress channel for this format. The status line and plain lines
+    /// only ever show the latest snapshot, so a lagging renderer coalesces;
+    /// JSON lines are consumed by other programs and must all arrive.
+    fn channel_options(self) -> ProgressOptions {
+        match self {
+            ProgressFormat::Json => ProgressOptions {
+                overflow: Overflow::Block,
+                ..ProgressOptions::default()
+            },
+            _ => ProgressOptions::default(),
+        }
+    }
+}
+
+/// `--watch` settings, applied to every task before it is built.
+#[derive(Clone, Copy)]
+struct Watch {
+    idle_secs: Option<u64>,
+}
+
+impl Watch {
+    fn apply(watch: Option<Watch>, task: &mut TaskChoice) {
+        if let Some(w) = watch {
+            *task = task.clone().with_watch(w.idle_secs);
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a saved task config headlessly, skipping every prompt.
+    ///
+    /// The config is either the task as the wizard serializes it or a full
+    /// experiment manifest, in JSON or YAML. Several `--config` flags, or a
+    /// config holding a list, queue runs one after the other.
+    ///
+    /// Without a config, `--stream` and `--learner` describe the run
+    /// instead, e.g. `rivu run --stream sea --learner hoeffding-tree
+    /// --max-instances 1e6 --out results.csv`. Components are named by their
+    /// `type` in `rivu list`, or by the start of one (`sea`), and take their
+    /// default parameters unless `--set` changes them.
+    Run {
+        /// Config file (`.json`, `.yaml` or `.yml`). Repeatable.
+        #[arg(short, long, required_unless_present = "stream")]
+        config: Vec<PathBuf>,
+
+        /// Stream to run instead of a config.
+        #[arg(
+            long,
+            value_name = "TYPE",
+            conflicts_with = "config",
+            requires = "learner"
+        )]
+        stream: Option<String>,
+
+        /// Learner to evaluate on `--stream`.
+        #[arg(long, value_name = "TYPE", requires = "stream")]
+        learner: Option<String>,
+
+        /// Evaluator (default: the first one for the learner's target).
+        #[arg(long, value_name = "TYPE", requires = "stream")]
+        evaluator: Option<String>,
+
+        /// Task (default: `evaluate-prequential`).
+        #[arg(long, value_name = "TYPE", requires = "stream")]
+        task: Option<String>,
+
+        /// Stop after this many instances; `1e6` is accepted.
+        #[arg(long, value_name = "N", value_parser = parse_count, requires = "stream")]
+        max_instances: Option<u64>,
+
+        /// Seed for the run's seeded components.
+        #[arg(long, requires = "stream")]
+        seed: Option<u64>,
+
+        /// Learning curve file (`.csv`, `.tsv`, `.json` or `.parquet`).
+        #[arg(short, long, requires = "stream")]
+        out: Option<PathBuf>,
+
+        /// Override a task parameter by its dotted path below the task's
+        /// `params`, e.g. `learner.params.grace_period=50`. Repeatable.
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<ConfigOverride>,
+    },
+    /// List the available components with their parameters.
+    List {
+        /// Only list this category (default: all).
+        #[arg(value_enum)]
+        category: Option<ComponentCategory>,
+    },
+    /// Print the JSON Schema of a component category's config (default: all,
+    /// keyed by category).
+    Schema {
+        #[arg(value_enum)]
+        category: Option<ComponentCategory>,
+    },
+    /// Write the answers of the wizard to a config file instead of running
+    /// them, for `run --config` to reproduce the run later.
+    ///
+    /// With `--recent`, a run already started from the wizard is exported
+    /// without any prompts.
+    ExportConfig {
+        /// Config file to write: YAML for `.yaml` or `.yml`, otherwise JSON.
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Export the N-th most recent wizard run (1 is the last one).
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        recent: Option<u64>,
+    },
+    /// Render a learning curve (`.csv`, `.tsv` or `.json`) as an SVG chart.
+    Plot {
+        /// Curve file written by a run.
+        curve: PathBuf,
+
+        /// SVG file to write (default: the curve path with an `.svg` extension).
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Metric to draw; repeat for several (default: accuracy and kappa).
+        #[arg(short, long = "metric")]
+        metrics: Vec<String>,
+    },
+    /// Compare two learning curves point by point with significance tests.
+    ///
+    /// Deltas are `b - a` at every instance count present in both curves.
+    Compare {
+        /// Baseline curve (`.csv`, `.tsv` or `.json`).
+        a: PathBuf,
+        /// Curve compared against the baseline.
+        b: PathBuf,
+
+        /// Metric to compare; repeat for several (default: accuracy and kappa).
+        #[arg(short, long = "metric")]
+        metrics: Vec<String>,
+
+        /// Number of aligned points printed per metric.
+        #[arg(long, default_value_t = 10)]
+        rows: usize,
+    },
+    /// Browse the snapshots of a finished run: scroll, filter metrics, and
+    /// rank the steps where a metric changed most.
+    ///
+    /// When stdout is not a terminal the table is printed instead.
+    Results {
+        /// Results directory of a run, or a curve (`.csv`, `.tsv` or `.json`).
+        path: PathBuf,
+
+        /// Only show metrics whose name contains this text.
+        #[arg(short, long, default_value = "")]
+        filter: String,
+
+        /// Start with the largest changes instead of every snapshot.
+        #[arg(long)]
+        changes: bool,
+    },
+    /// Write a run's learning curve in the results layout of River's
+    /// benchmark suite, for tables comparing Rivu with River.
+    ExportRiver {
+        /// Results directory of a run, or a curve (`.csv`, `.tsv` or `.json`).
+        path: PathBuf,
 
-            let stream = build_stream(stream_choice).context("failed to build stream")?;
-            let evaluator =
-                build_evaluator(evaluator_choice).context("failed to build evaluator")?;
-            let learner = build_learner(learner_choice).context("failed to build learner")?;
+        /// File to write: CSV for `.csv`, otherwise JSON.
+        #[arg(short, long)]
+        output: PathBuf,
 
-            let (tx, rx) = std::sync::mpsc::channel();
+        /// Name of the dataset in the `dataset` column.
+        #[arg(long)]
+        dataset: String,
 
-            render = std::thread::spawn(move || {
-                render_status_with_header(rx, header, 150, max_instances, max_seconds)
-            });
+        /// Name of the model in the `model` column.
+        #[arg(long)]
+        model: String,
 
-            PrequentialEvaluator::new(
-                learner,
+        /// Track deciding the metric columns: `binary`, `multiclass` or
+        /// `regression` (default: inferred from the curve's metrics).
+        #[arg(long)]
+        track: Option<RiverTrack>,
+
+        /// Write only the final metrics instead of every snapshot.
+        #[arg(long = "final")]
+        final_only: bool,
+    },
+    /// Run JSON or YAML experiment manifests without the wizard.
+    ///
+    /// Several manifests run concurrently, at most `--jobs` at a time.
+    RunConfig {
+        /// Manifest files (`.json`, `.yaml` or `.yml`).
+        #[arg(required = true)]
+        manifests: Vec<PathBuf>,
+
+        /// Maximum number of experiments running at once (default: CPU count).
+        #[arg(short, long)]
+        jobs: Option<usize>,
+    },
+    /// Answer predictions for a saved model over HTTP until stopped.
+    ///
+    /// `GET /model` lists the attributes the model expects; `POST /predict`
+    /// takes an instance (or an array of them) as a JSON object of attribute
+    /// values and returns the prediction and votes.
+    Serve {
+        /// Saved model: a model file, or the `model.json` of a run directory.
+        #[arg(short, long)]
+        model: PathBuf,
+
+        /// Address to listen on.
+        #[arg(long, default_value = "127.0.0.1:8000")]
+        addr: String,
+    },
+}
+
+/// Parses an instance count, also written in scientific notation (`1e6`).
+fn parse_count(s: &str) -> Result<u64, String> {
+    if let Ok(n) = s.parse::<u64>() {
+        return Ok(n);
+    }
+    match s.parse::<f64>() {
+        Ok(x) if x >= 0.0 && x.fract() == 0.0 && x <= u64::MAX as f64 => Ok(x as u64),
+        _ => Err(format!("`{s}` is not a whole number of instances")),
+    }
+}
+
+/// Context for errors reading or parsing a config; they end the process
+/// with [`RunStatus::InvalidConfig`] whatever their cause.
+#[derive(Debug)]
+struct ConfigError(String);
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Context for errors returned by a running task, which come from reading
+/// the stream.
+#[derive(Debug)]
+struct RunnerFailed;
+
+impl Display for RunnerFailed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("runner failed")
+    }
+}
+
+/// Status a run ends with when it fails with `e`.
+fn failure_status(e: &anyhow::Error) -> RunStatus {
+    if e.downcast_ref::<ConfigError>().is_some() {
+        RunStatus::InvalidConfig
+    } else if e.downcast_ref::<RunnerFailed>().is_some() {
+        RunStatus::StreamError
+    } else if let Some(b) = e.chain().find_map(|c| c.downcast_ref::<BuildError>()) {
+        b.into()
+    } else {
+        RunStatus::Failed
+    }
+}
+
+fn main() -> ExitCode {
+    match run_cli(Cli::parse()) {
+        Ok(status) => status.into(),
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            failure_status(&e).into()
+        }
+    }
+}
+
+fn run_cli(cli: Cli) -> Result<RunStatus> {
+    let progress = cli
+        .progress_format
+        .resolve(cli.quiet, io::stdout().is_terminal());
+    let results = cli.results_dir.as_deref();
+    let watch = cli.watch.then_some(Watch {
+        idle_secs: cli.watch_idle,
+    });
+    let dashboard = cli
+        .serve
+        .as_deref()
+        .map(|addr| {
+            Dashboard::bind(addr)
+                .with_context(|| format!("failed to serve the dashboard on {addr}"))
+        })
+        .transpose()?;
+    if let Some(d) = &dashboard {
+        progress.note(&format!(
+            "{DIM}dashboard at{RESET} http://{}/",
+            d.local_addr()
+        ));
+    }
+    let dashboard = dashboard.as_ref();
+    match cli.command {
+        None => run_wizard(progress, results, dashboard, watch),
+        Some(Command::List { category }) => {
+            list_components(category)?;
+            Ok(RunStatus::Completed)
+        }
+        Some(Command::Schema { category }) => {
+            let schema = match category {
+                Some(c) => c.schema().to_value(),
+                None => all_schemas(),
+            };
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            Ok(RunStatus::Completed)
+        }
+        Some(Command::ExportConfig { output, recent }) => export_config(&output, recent, progress),
+        Some(Command::Plot {
+            curve,
+            output,
+            metrics,
+        }) => {
+            plot_curve(&curve, output, metrics)?;
+            Ok(RunStatus::Completed)
+        }
+        Some(Command::Compare {
+            a,
+            b,
+            metrics,
+            rows,
+        }) => {
+            compare_curves(&a, &b, metrics, rows, progress)?;
+            Ok(RunStatus::Completed)
+        }
+        Some(Command::Results {
+            path,
+            filter,
+            changes,
+        }) => {
+            browse_results(&path, filter, changes)?;
+            Ok(RunStatus::Completed)
+        }
+        Some(Command::Run {
+            stream: Some(stream),
+            learner,
+            evaluator,
+            task,
+            max_instances,
+            seed,
+            out,
+            overrides,
+            ..
+        }) => {
+            let flags = RunFlags {
+                task,
                 stream,
+                learner: learner.unwrap_or_default(),
                 evaluator,
                 max_instances,
-                max_seconds,
-                sample_freq,
-                mem_check_freq,
-            )
-            .context("failed to construct PrequentialEvaluator")?
-            .with_progress(tx)
+                seed,
+                out,
+            };
+            let mut manifest = flags
+                .manifest(&overrides)
+                .with_context(|| ConfigError("invalid run flags".into()))?;
+            Watch::apply(watch, &mut manifest.task);
+            run_loaded_manifest(&manifest, progress, results, dashboard)
+        }
+        Some(Command::Run {
+            config, overrides, ..
+        }) => {
+            let mut queue = Vec::new();
+            for path in config {
+                let runs = ExperimentManifest::queue_from_config_path(&path, &overrides)
+                    .with_context(|| {
+                        ConfigError(format!("failed to load config {}", path.display()))
+                    })?;
+                for (i, mut manifest) in runs.into_iter().enumerate() {
+                    Watch::apply(watch, &mut manifest.task);
+                    queue.push(QueuedRun {
+                        config: path.clone(),
+                        index_in_config: i,
+                        manifest,
+                    });
+                }
+            }
+            match queue.as_slice() {
+                [one] => run_loaded_manifest(&one.manifest, progress, results, dashboard),
+                _ => run_queue(queue, progress, results, dashboard),
+            }
+        }
+        Some(Command::RunConfig { manifests, jobs }) => match manifests.as_slice() {
+            [one] => run_manifest(one, progress, results, dashboard, watch),
+            _ => run_manifests(&manifests, jobs, progress, results, dashboard, watch),
+        },
+        Some(Command::ExportRiver {
+            path,
+            output,
+            dataset,
+            model,
+            track,
+            final_only,
+        }) => {
+            let curve = read_curve(&curve_path(&path))?;
+            let track = track.unwrap_or_else(|| RiverTrack::infer(&curve));
+            let results = RiverResults::new(track, dataset, model);
+            let rows = match final_only {
+                true => results.final_row(&curve).into_iter().collect(),
+                false => results.rows(&curve),
+            };
+            results
+                .write(&output, &rows)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            Ok(RunStatus::Completed)
+        }
+        Some(Command::Serve { model, addr }) => {
+            let state = ClassifierState::load(&model)
+                .with_context(|| format!("failed to load model {}", model.display()))?;
+            let server = InferenceServer::bind(addr.as_str(), state)
+                .with_context(|| format!("failed to serve {} on {addr}", model.display()))?;
+            progress.note(&format!(
+                "{DIM}serving {} at{RESET} http://{}/predict",
+                model.display(),
+                server.local_addr()
+            ));
+            server.wait();
+            Ok(RunStatus::Completed)
+        }
+    }
+}
+
+fn read_curve(path: &Path) -> Result<LearningCurve> {
+    let fmt = CurveFormat::from_path(path)
+        .with_context(|| format!("unknown curve format for {}", path.display()))?;
+    LearningCurve::import(path, fmt).with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Prints, per metric, the two curves and their delta at a sample of shared
+/// instance counts, followed by the mean delta, win/loss counts, and the
+/// Wilcoxon signed-rank and sign tests. Colors are stripped unless the
+/// progress format is the status line (stdout is a terminal and `--quiet`
+/// is not set).
+fn compare_curves(
+    a: &Path,
+    b: &Path,
+    metrics: Vec<String>,
+    rows: usize,
+    progress: ProgressFormat,
+) -> Result<()> {
+    let say = |line: String| match progress {
+        ProgressFormat::Status => println!("{line}"),
+        _ => println!("{}", strip_ansi(&line)),
+    };
+    let (curve_a, curve_b) = (read_curve(a)?, read_curve(b)?);
+    let metrics = if metrics.is_empty() {
+        vec!["accuracy".to_string(), "kappa".to_string()]
+    } else {
+        metrics
+    };
+    say(format!("{DIM}a{RESET} {}", a.display()));
+    say(format!("{DIM}b{RESET} {}", b.display()));
+
+    let fmt_p = |t: Option<TestOutcome>, stat: &str| match t {
+        Some(t) => format!("{stat} {}  p {:.4}", t.statistic, t.p_value),
+        None => "—".to_string(),
+    };
+    for metric in metrics {
+        let cmp = CurveComparison::align(&curve_a, &curve_b, &metric);
+        say(String::new());
+        say(format!("{BOLD}{FG_CYAN}{metric}{RESET}"));
+        if cmp.points.is_empty() {
+            say(format!(
+                "  {DIM}no shared instance counts with values in both curves{RESET}"
+            ));
+            continue;
+        }
+        say(format!(
+            "  {DIM}{:>12}  {:>10}  {:>10}  {:>10}{RESET}",
+            "instances", "a", "b", "delta"
+        ));
+        for p in cmp.sample(rows) {
+            let d = p.delta();
+            let color = if d > 0.0 {
+                FG_GREEN
+            } else if d < 0.0 {
+                FG_RED
+            } else {
+                ""
+            };
+            say(format!(
+                "  {:>12}  {:>10.6}  {:>10.6}  {color}{:>+10.6}{RESET}",
+                p.instances_seen, p.a, p.b, d
+            ));
+        }
+        let (wins, losses, ties) = cmp.wins_losses_ties();
+        say(format!("{FG_GREY}  {}{RESET}", "─".repeat(48)));
+        say(format!(
+            "  {DIM}points{RESET} {}  {DIM}mean delta{RESET} {:+.6}  {DIM}b ahead/behind/tied{RESET} {wins}/{losses}/{ties}",
+            cmp.points.len(),
+            cmp.mean_delta()
+        ));
+        say(format!(
+            "  {DIM}wilcoxon{RESET}  {}",
+            fmt_p(cmp.wilcoxon(), "W+")
+        ));
+        say(format!(
+            "  {DIM}sign test{RESET} {}",
+            fmt_p(cmp.sign_test(), "k")
+        ));
+    }
+    Ok(())
+}
+
+fn plot_curve(curve: &Path, output: Option<PathBuf>, metrics: Vec<String>) -> Result<()> {
+    let data = read_curve(curve)?;
+
+    let output = output.unwrap_or_else(|| curve.with_extension("svg"));
+    let plot_fmt = PlotFormat::from_path(&output)
+        .with_context(|| format!("expected an .svg output, got {}", output.display()))?;
+    let title = curve
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    CurvePlot::default()
+        .with_title(title)
+        .with_series(metrics)
+        .render(&data, &output, plot_fmt)
+        .with_context(|| format!("failed to write {}", output.display()))?;
+    println!("{DIM}plot written to{RESET} {}", output.display());
+    Ok(())
+}
+
+/// The curve of a results directory, or `path` itself when it is a file.
+fn curve_path(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join("curve.csv")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Opens the curve of a run directory (its `curve.csv`) or a curve file in
+/// the results browser.
+fn browse_results(path: &Path, filter: String, changes: bool) -> Result<()> {
+    let curve = read_curve(&curve_path(path))?;
+    let title = path
+        .file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let view = if changes {
+        BrowserView::Changes
+    } else {
+        BrowserView::Snapshots
+    };
+    let browser = ResultsBrowser::new(title, curve)
+        .with_filter(filter)
+        .with_view(view);
+    if io::stdout().is_terminal() {
+        results_browser::browse(browser).context("results browser failed")
+    } else {
+        browser.table().iter().for_each(|line| println!("{line}"));
+        Ok(())
+    }
+}
+
+/// Prints every component of `category` (or of all categories) with its
+/// config key, description, and parameter summaries.
+fn list_components(category: Option<ComponentCategory>) -> Result<()> {
+    let categories = match category {
+        Some(c) => vec![c],
+        None => ComponentCategory::value_variants().to_vec(),
+    };
+    for (i, category) in categories.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{BOLD}{FG_CYAN}{}{RESET}", category.name());
+        for c in category.components()? {
+            println!("  {BOLD}{}{RESET}  {}", c.key, c.label);
+            if !c.description.is_empty() {
+                println!("    {DIM}{}{RESET}", c.description);
+            }
+            for p in &c.params {
+                println!("    {FG_GREY}•{RESET} {}", param_summary(p));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Recent wizard configs, or `None` when there is nowhere to keep them or
+/// the file cannot be read (the wizard then works without presets).
+fn load_recent(progress: ProgressFormat) -> Option<RecentConfigs> {
+    let path = RecentConfigs::default_path()?;
+    match RecentConfigs::load(path, RecentConfigs::DEFAULT_LIMIT) {
+        Ok(recent) => Some(recent),
+        Err(e) => {
+            progress.note(&format!("{DIM}ignoring recent runs: {e:#}{RESET}"));
+            None
+        }
+    }
+}
+
+fn run_wizard(
+    progress: ProgressFormat,
+    results: Option<&Path>,
+    dashboard: Option<&Dashboard>,
+    watch: Option<Watch>,
+) -> Result<RunStatus> {
+    let driver = InquireDriver;
+
+    let mut recent = load_recent(progress);
+    let picked = match recent.as_ref().map(prompt_preset).transpose() {
+        Ok(preset) => preset.flatten(),
+        Err(e) if GoBack::is(&e) => return Ok(RunStatus::Cancelled),
+        Err(e) => return Err(e.context("failed while choosing a preset")),
+    };
+
+    let task = match prompt_choice_with::<TaskChoice, _>(&driver, picked.as_ref()) {
+        Ok(task) => task,
+        Err(e) if GoBack::is(&e) => return Ok(RunStatus::Cancelled),
+        Err(e) => return Err(e.context("failed while prompting for task")),
+    };
+    let Some(mut task) = review_choice(&driver, task).context("failed while reviewing answers")?
+    else {
+        progress.note(&format!("{DIM}run cancelled{RESET}"));
+        return Ok(RunStatus::Cancelled);
+    };
+    if let Some(recent) = recent.as_mut() {
+        recent.push(serde_json::to_value(&task)?);
+        if let Err(e) = recent.save() {
+            progress.note(&format!("{DIM}could not remember this run: {e:#}{RESET}"));
+        }
+    }
+    if let Some(path) =
+        prompt_save_config(&driver, &task).context("failed to save the wizard config")?
+    {
+        progress.note(&format!(
+            "{DIM}config saved; repeat this run with{RESET} rivu run --config {}",
+            path.display()
+        ));
+    }
+
+    Watch::apply(watch, &mut task);
+    let mut dir = open_run_dir(results, None)?;
+    let metadata = RunMetadata::start(None, Vec::new());
+    let run = match execute(&task, None, progress, dashboard, dir.as_mut(), |tx| {
+        build_task(task.clone(), Some(tx))
+    }) {
+        Ok(run) => run,
+        Err(e) => {
+            if let Some(dir) = dir {
+                record_failure(dir, metadata, failure_status(&e), format!("{e:#}"));
+            }
+            return Err(e);
+        }
+    };
+    match dir {
+        Some(dir) => {
+            record_run(dir, &task, &run, metadata, progress)?;
+        }
+        None => {
+            write_default_curve(&run.curve, progress)?;
+        }
+    }
+    Ok(run.status())
+}
+
+/// Writes the wizard's answers, or those of the `recent`-th last wizard run,
+/// to `output`.
+fn export_config(
+    output: &Path,
+    recent: Option<u64>,
+    progress: ProgressFormat,
+) -> Result<RunStatus> {
+    let task = match recent {
+        Some(n) => {
+            let recent = load_recent(progress).context("no recent wizard runs are kept")?;
+            let entry = recent.entries().get(n as usize - 1).with_context(|| {
+                format!(
+                    "no recent wizard run {n} in {} ({} kept)",
+                    recent.path().display(),
+                    recent.entries().len()
+                )
+            })?;
+            let task: TaskChoice =
+                serde_json::from_value(entry.config.clone()).with_context(|| {
+                    ConfigError("the recent run no longer matches the config format".into())
+                })?;
+            task.validate().with_context(|| {
+                ConfigError("the recent run no longer has valid parameters".into())
+            })?;
+            task
+        }
+        None => {
+            let driver = InquireDriver;
+            let picked = match load_recent(progress)
+                .as_ref()
+                .map(prompt_preset)
+                .transpose()
+            {
+                Ok(preset) => preset.flatten(),
+                Err(e) if GoBack::is(&e) => return Ok(RunStatus::Cancelled),
+                Err(e) => return Err(e.context("failed while choosing a preset")),
+            };
+            match prompt_choice_with::<TaskChoice, _>(&driver, picked.as_ref()) {
+                Ok(task) => task,
+                Err(e) if GoBack::is(&e) => return Ok(RunStatus::Cancelled),
+                Err(e) => return Err(e.context("failed while prompting for task")),
+            }
+        }
+    };
+    write_config(&task, output)?;
+    progress.note(&format!(
+        "{DIM}config written; run it with{RESET} rivu run --config {}",
+        output.display()
+    ));
+    Ok(RunStatus::Completed)
+}
+
+fn run_manifest(
+    path: &Path,
+    progress: ProgressFormat,
+    results: Option<&Path>,
+    dashboard: Option<&Dashboard>,
+    watch: Option<Watch>,
+) -> Result<RunStatus> {
+    let mut manifest = ExperimentManifest::from_path(path)
+        .with_context(|| ConfigError(format!("failed to load manifest {}", path.display())))?;
+    Watch::apply(watch, &mut manifest.task);
+    run_loaded_manifest(&manifest, progress, results, dashboard)
+}
+
+fn run_loaded_manifest(
+    manifest: &ExperimentManifest,
+    progress: ProgressFormat,
+    results: Option<&Path>,
+    dashboard: Option<&Dashboard>,
+) -> Result<RunStatus> {
+    let run = run_recorded(
+        manifest,
+        manifest.name.as_deref(),
+        progress,
+        results,
+        dashboard,
+    );
+    match run.error {
+        Some(e) => Err(e),
+        None => Ok(run.summary.status),
+    }
+}
+
+/// Outcome of [`run_recorded`].
+struct RecordedRun {
+    summary: RunSummary,
+    dir: Option<PathBuf>,
+    /// Curve written outside the results directory, if any.
+    curve: Option<PathBuf>,
+    error: Option<anyhow::Error>,
+}
+
+/// Runs `manifest` with `title` in the header and writes its results
+/// directory (or its curve file when there is none). A failure is recorded
+/// in the returned summary and error instead of being returned early.
+fn run_recorded(
+    manifest: &ExperimentManifest,
+    title: Option<&str>,
+    progress: ProgressFormat,
+    results: Option<&Path>,
+    dashboard: Option<&Dashboard>,
+) -> RecordedRun {
+    let mut metadata = RunMetadata::start(manifest.name.clone(), manifest.seeds.clone());
+    let failed = |mut metadata: RunMetadata, dir: Option<RunDirectory>, e: anyhow::Error| {
+        let status = failure_status(&e);
+        let dir_path = dir.as_ref().map(|d| d.path().to_path_buf());
+        let summary = match dir {
+            Some(dir) => record_failure(dir, metadata, status, format!("{e:#}")),
+            None => failure_summary(&mut metadata, status, format!("{e:#}")),
+        };
+        RecordedRun {
+            summary,
+            dir: dir_path,
+            curve: None,
+            error: Some(e),
+        }
+    };
+
+    let mut dir = match open_run_dir(results, manifest.name.as_deref()) {
+        Ok(dir) => dir,
+        Err(e) => return failed(metadata, None, e),
+    };
+    let run = match execute(
+        &manifest.task,
+        title,
+        progress,
+        dashboard,
+        dir.as_mut(),
+        |tx| manifest.build(Some(tx)),
+    ) {
+        Ok(run) => run,
+        Err(e) => return failed(metadata, dir, e),
+    };
+    if let Some(out) = &manifest.output.curve
+        && let Err(e) = manifest
+            .write_outputs(&run.curve)
+            .with_context(|| format!("failed to write {}", out.display()))
+    {
+        return failed(metadata, dir, e);
+    }
+
+    if let Some(dir) = dir {
+        let dir_path = dir.path().to_path_buf();
+        return match record_run(dir, manifest, &run, metadata.clone(), progress) {
+            Ok(summary) => RecordedRun {
+                summary,
+                dir: Some(dir_path),
+                curve: manifest.output.curve.clone(),
+                error: None,
+            },
+            Err(e) => failed(metadata, None, e),
+        };
+    }
+    let curve = match &manifest.output.curve {
+        Some(out) => {
+            progress.note(&format!("{DIM}curve written to{RESET} {}", out.display()));
+            out.clone()
+        }
+        None => match write_default_curve(&run.curve, progress) {
+            Ok(path) => path,
+            Err(e) => return failed(metadata, None, e),
+        },
+    };
+    metadata.finish(run.wall_clock);
+    RecordedRun {
+        summary: RunSummary::new(&metadata, run.status()).with_curve(&run.curve),
+        dir: None,
+        curve: Some(curve),
+        error: None,
+    }
+}
+
+/// One run of `rivu run` and where its config came from.
+struct QueuedRun {
+    config: PathBuf,
+    index_in_config: usize,
+    manifest: ExperimentManifest,
+}
+
+/// Runs a queue one run after the other, each with the usual live display
+/// titled with its position in the queue, then prints a table of the
+/// outcomes and writes them to one [`QueueIndex`].
+///
+/// A failed run does not stop the queue; a run stopped with `q` does. The
+/// status of the first run that did not complete is returned.
+fn run_queue(
+    queue: Vec<QueuedRun>,
+    progress: ProgressFormat,
+    results: Option<&Path>,
+    dashboard: Option<&Dashboard>,
+) -> Result<RunStatus> {
+    let total = queue.len();
+    progress.note(&format!(
+        "{BOLD}{FG_CYAN}▶ {total} queued runs{RESET}  {}",
+        timestamp_now()
+    ));
+
+    let mut index = QueueIndex::new();
+    for (i, mut queued) in queue.into_iter().enumerate() {
+        let label = queued.manifest.name.clone().unwrap_or_else(|| "run".into());
+        if results.is_none() {
+            // Keep the curves of runs finishing in the same second apart.
+            queued
+                .manifest
+                .output
+                .curve
+                .get_or_insert_with(|| default_curve_path(Some(&format!("{}-{label}", i + 1))));
+        }
+        let title = format!("[{}/{total}] {label}", i + 1);
+        let run = run_recorded(&queued.manifest, Some(&title), progress, results, dashboard);
+        if let Some(e) = &run.error {
+            eprintln!("✘ {title}: {e:#}");
+        }
+        let cancelled = run.summary.status == RunStatus::Cancelled;
+        index.push(QueueEntry {
+            config: queued.config,
+            index_in_config: queued.index_in_config,
+            results_dir: run.dir,
+            curve: run.curve,
+            summary: run.summary,
+        });
+        if cancelled && i + 1 < total {
+            progress.note(&format!(
+                "{DIM}queue stopped; skipping the remaining {} runs{RESET}",
+                total - i - 1
+            ));
+            break;
+        }
+    }
+
+    if progress != ProgressFormat::Json {
+        print_queue_table(&index, progress);
+    }
+    let ts = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = match results {
+        Some(root) => root.join(format!("{ts}-queue.json")),
+        None => PathBuf::from(format!("rivu-queue-{ts}.json")),
+    };
+    index
+        .write(&path)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    progress.note(&format!(
+        "{DIM}queue index written to{RESET} {}",
+        path.display()
+    ));
+    let failed = index.failed();
+    if failed > 0 {
+        eprintln!("{failed} of {total} runs did not complete");
+    }
+    Ok(index.status)
+}
+
+/// One line per queued run: status, instances, final accuracy and kappa,
+/// wall-clock seconds, and where its results went.
+fn print_queue_table(index: &QueueIndex, progress: ProgressFormat) {
+    let width = index
+        .runs
+        .iter()
+        .map(|r| r.summary.name.as_deref().unwrap_or("run").len())
+        .fold("name".len(), usize::max);
+    let metric = |r: &QueueEntry, key: &str| {
+        r.summary
+            .final_metrics
+            .as_ref()
+            .and_then(|m| m.get(key))
+            .and_then(serde_json::Value::as_f64)
+            .filter(|v| v.is_finite())
+            .map_or("—".to_string(), |v| format!("{v:.4}"))
+    };
+    progress.note(&format!(
+        "{BOLD}{:>3}  {:<width$}  {:<14}  {:>10}  {:>8}  {:>8}  {:>8}  results{RESET}",
+        "#", "name", "status", "instances", "accuracy", "kappa", "seconds"
+    ));
+    for (i, r) in index.runs.iter().enumerate() {
+        let (color, mark) = match r.summary.status {
+            RunStatus::Completed => (FG_GREEN, "✔"),
+            _ => (FG_RED, "✘"),
+        };
+        let status = r.summary.status.as_str();
+        let place = r.results_dir.as_ref().or(r.curve.as_ref());
+        progress.note(&format!(
+            "{:>3}  {:<width$}  {color}{mark}{RESET} {:<12}  {:>10}  {:>8}  {:>8}  {:>8}  {DIM}{}{RESET}",
+            i + 1,
+            r.summary.name.as_deref().unwrap_or("run"),
+            status,
+            r.summary
+                .instances_seen
+                .map_or("—".to_string(), |n| n.to_string()),
+            metric(r, "accuracy"),
+            metric(r, "kappa"),
+            r.summary
+                .wall_clock_seconds
+                .map_or("—".to_string(), |s| format!("{s:.2}")),
+            place.map_or(String::new(), |p| p.display().to_string()),
+        ));
+    }
+}
+
+/// Outcome of [`execute`].
+struct RunOutput {
+    curve: LearningCurve,
+    model: Vec<Measurement>,
+    confusion: Option<ConfusionMatrix>,
+    drifts: Vec<DriftMarker>,
+    detections: Vec<u64>,
+    wall_clock: Duration,
+    cancelled: bool,
+}
+
+impl RunOutput {
+    fn status(&self) -> RunStatus {
+        if self.cancelled {
+            RunStatus::Cancelled
+        } else {
+            RunStatus::Completed
+        }
+    }
+}
+
+fn open_run_dir(results: Option<&Path>, name: Option<&str>) -> Result<Option<RunDirectory>> {
+    results
+        .map(|root| {
+            RunDirectory::create(root, name)
+                .with_context(|| format!("failed to create a run directory in {}", root.display()))
+        })
+        .transpose()
+}
+
+/// Fills a run directory with the config, curve, log, metrics, summary, and
+/// metadata of a finished run (the model is saved by [`execute`]).
+fn record_run<C: serde::Serialize>(
+    mut dir: RunDirectory,
+    config: &C,
+    run: &RunOutput,
+    mut metadata: RunMetadata,
+    progress: ProgressFormat,
+) -> Result<RunSummary> {
+    metadata.finish(run.wall_clock);
+    let summary = RunSummary::new(&metadata, run.status()).with_curve(&run.curve);
+    let written = (|| -> io::Result<()> {
+        dir.write_config(config)?;
+        dir.write_curve(&run.curve)?;
+        dir.write_plot(&run.curve)?;
+        dir.write_metrics(&run.curve, &run.model)?;
+        dir.write_report(
+            metadata.name.as_deref().unwrap_or("Run report"),
+            config,
+            &run.curve,
+            &run.model,
+            run.confusion.as_ref(),
+            &run.drifts,
+            &run.detections,
+        )?;
+        dir.write_reproducibility(config, &metadata.seeds, &run.curve)?;
+        dir.write_summary(&summary)?;
+        dir.write_metadata(&metadata)
+    })();
+    written.with_context(|| format!("failed to write results to {}", dir.path().display()))?;
+    progress.note(&format!(
+        "{DIM}results written to{RESET} {}",
+        dir.path().display()
+    ));
+    Ok(summary)
+}
+
+/// Writes `summary.json` and `metadata.json` for a run that failed after its
+/// directory was created.
+fn record_failure(
+    mut dir: RunDirectory,
+    mut metadata: RunMetadata,
+    status: RunStatus,
+    error: String,
+) -> RunSummary {
+    let summary = failure_summary(&mut metadata, status, error);
+    let written = dir
+        .write_summary(&summary)
+        .and_then(|()| dir.write_metadata(&metadata));
+    if let Err(e) = written {
+        eprintln!("failed to write results to {}: {e}", dir.path().display());
+    }
+    summary
+}
+
+/// Stamps the end of a failed run and summarizes it.
+fn failure_summary(metadata: &mut RunMetadata, status: RunStatus, error: String) -> RunSummary {
+    metadata.finish(metadata.elapsed());
+    RunSummary::new(metadata, status).with_error(error)
+}
+
+/// Runs several manifests on a [`ParallelExecutor`], printing one status
+/// line per snapshot prefixed with the job label (or, in JSON mode, one
+/// object per snapshot with a `job` field).
+///
+/// When some experiments fail, the status of the first failed one is
+/// returned.
+fn run_manifests(
+    paths: &[PathBuf],
+    jobs: Option<usize>,
+    progress: ProgressFormat,
+    results: Option<&Path>,
+    dashboard: Option<&Dashboard>,
+    watch: Option<Watch>,
+) -> Result<RunStatus> {
+    let mut manifests = Vec::with_capacity(paths.len());
+    let mut labels = Vec::with_capacity(paths.len());
+    let mut dirs = Vec::with_capacity(paths.len());
+    for (i, path) in paths.iter().enumerate() {
+        let mut m = ExperimentManifest::from_path(path)
+            .with_context(|| ConfigError(format!("failed to load manifest {}", path.display())))?;
+        Watch::apply(watch, &mut m.task);
+        let label = m.name.clone().unwrap_or_else(|| format!("job{i}"));
+        let mut dir = open_run_dir(results, Some(&label))?;
+        if let Some(dir) = dir.as_mut() {
+            m.output.curve = Some(dir.file("curve.csv"));
+        }
+        m.output
+            .curve
+            .get_or_insert_with(|| default_curve_path(Some(&label)));
+        dirs.push(dir.map(|d| (d, RunMetadata::start(m.name.clone(), m.seeds.clone()))));
+        labels.push(label);
+        manifests.push(m);
+    }
+    let configs = manifests.clone();
+    let outputs: Vec<PathBuf> = manifests
+        .iter()
+        .filter_map(|m| m.output.curve.clone())
+        .collect();
+
+    let executor = match jobs {
+        Some(n) => ParallelExecutor::new(n).context("invalid --jobs")?,
+        None => ParallelExecutor::with_available_parallelism(),
+    };
+    progress.note(&format!(
+        "{BOLD}{FG_CYAN}▶ {} experiments{RESET}  {DIM}jobs={}{RESET}  {}",
+        manifests.len(),
+        executor.max_threads(),
+        timestamp_now()
+    ));
+
+    let (tx, rx) = progress_channel::<JobProgress>(progress.channel_options());
+    let executor = executor.with_progress(tx);
+    let worker = std::thread::spawn(move || executor.run(manifests));
+
+    let width = labels.iter().map(String::len).max().unwrap_or(0);
+    let mut prev: Vec<Option<Snapshot>> = vec![None; labels.len()];
+    for p in rx {
+        match progress {
+            ProgressFormat::Status => {
+                let line = format_status(&p.snapshot, prev[p.job].as_ref(), None, None);
+                println!("{FG_GREY}[{:<width$}]{RESET} {line}", labels[p.job]);
+            }
+            ProgressFormat::Plain => {
+                println!("[{:<width$}] {}", labels[p.job], plain_status(&p.snapshot));
+            }
+            ProgressFormat::Json => {
+                let mut v = p.snapshot.to_json();
+                v["job"] = labels[p.job].clone().into();
+                println!("{v}");
+            }
+        }
+        if let Some(d) = dashboard {
+            d.publish(&labels[p.job], &p.snapshot);
+        }
+        prev[p.job] = Some(p.snapshot);
+    }
+    let results = worker.join().expect("executor thread panicked");
+
+    let mut failed = 0;
+    let mut status = RunStatus::Completed;
+    for ((r, out), dir) in results.iter().zip(&outputs).zip(dirs) {
+        if let Some(d) = dashboard {
+            let status = r
+                .outcome
+                .as_ref()
+                .map_or_else(RunStatus::from, |_| RunStatus::Completed);
+            d.end_run(&labels[r.job], status.as_str());
+        }
+        match &r.outcome {
+            Ok(curve) => match dir {
+                Some((dir, metadata)) => {
+                    let seconds = curve.latest().map_or(0.0, |s| s.seconds);
+                    let run = RunOutput {
+                        curve: curve.clone(),
+                        model: Vec::new(),
+                        confusion: None,
+                        drifts: Vec::new(),
+                        detections: Vec::new(),
+                        wall_clock: Duration::from_secs_f64(seconds),
+                        cancelled: false,
+                    };
+                    record_run(dir, &configs[r.job], &run, metadata, progress)?;
+                }
+                None => progress.note(&format!(
+                    "{FG_GREEN}✔{RESET} {}  {DIM}curve written to{RESET} {}",
+                    labels[r.job],
+                    out.display()
+                )),
+            },
+            Err(e) => {
+                failed += 1;
+                let job_status = RunStatus::from(e);
+                if status == RunStatus::Completed {
+                    status = job_status;
+                }
+                if let Some((dir, metadata)) = dir {
+                    record_failure(dir, metadata, job_status, e.to_string());
+                }
+                eprintln!("✘ {}: {e}", labels[r.job]);
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("{failed} of {} experiments failed", results.len());
+    }
+    Ok(status)
+}
+
+fn default_curve_path(label: Option<&str>) -> PathBuf {
+    let ts = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    match label {
+        Some(label) => PathBuf::from(format!("rivu-curve-{ts}-{label}.csv")),
+        None => PathBuf::from(format!("rivu-curve-{ts}.csv")),
+    }
+}
+
+fn write_default_curve(curve: &LearningCurve, progress: ProgressFormat) -> Result<PathBuf> {
+    let path = default_curve_path(None);
+    curve
+        .export(&path, CurveFormat::Csv)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    progress.note(&format!("{DIM}curve written to{RESET} {}", path.display()));
+    Ok(path)
+}
+
+/// Publishes every snapshot from `rx` to the dashboard under `run` and
+/// passes it on through the returned receiver.
+fn forward_to_dashboard(
+    rx: ProgressReceiver,
+    dashboard: Dashboard,
+    run: String,
+    options: ProgressOptions,
+) -> ProgressReceiver {
+    let (tx, forwarded) = progress_channel(options);
+    std::thread::spawn(move || {
+        for s in rx {
+            dashboard.publish(&run, &s);
+            if tx.send(s).is_err() {
+                break;
+            }
         }
+    });
+    forwarded
+}
+
+/// Builds the task through `build`, runs it with the live renderer (and
+/// keyboard control when attached to a terminal), and returns its curve.
+fn execute(
+    task: &TaskChoice,
+    name: Option<&str>,
+    progress: ProgressFormat,
+    dashboard: Option<&Dashboard>,
+    run_dir: Option<&mut RunDirectory>,
+    build: impl FnOnce(ProgressSender) -> Result<Box<dyn Task>, BuildError>,
+) -> Result<RunOutput> {
+    let (title, info_line, max_instances, max_seconds) = describe_task(task);
+    let title = match name {
+        Some(name) => format!("{title} · {name}"),
+        None => title.to_string(),
     };
 
-    runner.run().context("runner failed")?;
+    let (tx, rx) = progress_channel(progress.channel_options());
+    let mut runner = build(tx).context("failed to build task")?;
+    let label = name.unwrap_or("run").to_string();
+    let rx = match dashboard {
+        Some(d) => {
+            let model = SharedModel::new();
+            if runner.set_model_view(model.clone()).is_ok() {
+                d.watch_model(&label, model);
+            }
+            forward_to_dashboard(rx, d.clone(), label.clone(), progress.channel_options())
+        }
+        None => rx,
+    };
 
+    let mut keys: Option<KeyListener> = None;
+    if io::stdin().is_terminal() {
+        let (ctl_tx, ctl_rx) = std::sync::mpsc::channel();
+        if runner.set_control(ctl_rx).is_ok() {
+            keys = Some(KeyListener::spawn(ctl_tx));
+        }
+    }
+
+    let mut header: Vec<String> = vec![format!("{BOLD}{FG_CYAN}▶ {title}{RESET}"), info_line];
+    if keys.is_some() {
+        header.push(format!(
+            "{DIM}keys: [p] pause  [r] resume  [s] snapshot  [q] stop{RESET}"
+        ));
+    }
+    header.push(format!(
+        "{FG_GREY}────────────────────────────────────────────────────────────────────────{RESET}"
+    ));
+
+    let render: JoinHandle<()> = std::thread::spawn(move || match progress {
+        ProgressFormat::Status => {
+            render_status_with_header(rx, header, 150, max_instances, max_seconds)
+        }
+        ProgressFormat::Plain => render_plain_lines(rx, header, PLAIN_LOG_INTERVAL),
+        ProgressFormat::Json => render_json_lines(rx),
+    });
+
+    let started = Instant::now();
+    let result = runner.run();
+    let wall_clock = started.elapsed();
+    if let Some(k) = keys.take() {
+        k.stop();
+    }
+
+    let curve = runner.curve().clone();
+    let model = runner.model_measurements();
+    let confusion = runner.confusion_matrix();
+    let drifts = runner.drift_markers();
+    let detections = runner.drift_detections();
+    let cancelled = runner.cancelled();
+    if result.is_ok()
+        && let Some(dir) = run_dir
+    {
+        dir.write_model(runner.as_ref())
+            .context("failed to save the model")?;
+    }
     drop(runner);
     let _ = render.join();
+    if let Some(d) = dashboard {
+        let status = match &result {
+            Err(_) => RunStatus::StreamError,
+            Ok(()) if cancelled => RunStatus::Cancelled,
+            Ok(()) => RunStatus::Completed,
+        };
+        d.end_run(&label, status.as_str());
+    }
+    result.context(RunnerFailed)?;
 
-    // TODO: Implement file dumping
+    if progress != ProgressFormat::Json {
+        print_summary(&curve, &model, wall_clock, progress);
+    }
+    Ok(RunOutput {
+        curve,
+        model,
+        confusion,
+        drifts,
+        detections,
+        wall_clock,
+        cancelled,
+    })
+}
 
-    Ok(())
+/// Reads single key presses in raw mode and forwards them as [`TaskControl`]
+/// commands until stopped. Ctrl-C maps to cancel, since raw mode swallows
+/// the signal.
+struct KeyListener {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl KeyListener {
+    fn spawn(tx: Sender<TaskControl>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            if terminal::enable_raw_mode().is_err() {
+                return;
+            }
+            while !flag.load(Ordering::Relaxed) {
+                if !event::poll(Duration::from_millis(100)).unwrap_or(false) {
+                    continue;
+                }
+                let Ok(Event::Key(key)) = event::read() else {
+                    continue;
+                };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                let cmd = match key.code {
+                    KeyCode::Char('p') => TaskControl::Pause,
+                    KeyCode::Char('r') => TaskControl::Resume,
+                    KeyCode::Char('s') => TaskControl::SnapshotNow,
+                    KeyCode::Char('q') | KeyCode::Esc => TaskControl::Cancel,
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        TaskControl::Cancel
+                    }
+                    _ => continue,
+                };
+                if tx.send(cmd).is_err() {
+                    break;
+                }
+            }
+            let _ = terminal::disable_raw_mode();
+        });
+        Self { stop, handle }
+    }
+
+    /// Stops listening and restores the terminal.
+    fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+    }
+}
+
+/// Title, cadence line, and limits shown in the renderer header.
+fn describe_task(task: &TaskChoice) -> (&'static str, String, Option<u64>, Option<u64>) {
+    match task {
+        TaskChoice::EvaluatePrequential(p) => (
+            "Prequential Evaluation",
+            cadence_line(p.sample_frequency, p.mem_check_frequency),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::EvaluateInterleavedTestThenTrain(p) => (
+            "Interleaved Test-Then-Train",
+            cadence_line(p.sample_frequency, p.mem_check_frequency),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::EvaluatePrequentialDelayed(p) => (
+            "Prequential Evaluation (Delayed Labels)",
+            format!(
+                "{DIM}label_delay={}{RESET}  {}",
+                p.label_delay,
+                cadence_line(p.sample_frequency, p.mem_check_frequency)
+            ),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::EvaluatePeriodicHeldOutTest(p) => (
+            "Periodic Held-Out Test",
+            format!(
+                "{DIM}test_size={}{RESET}  {}",
+                p.test_size,
+                cadence_line(p.test_frequency, p.mem_check_frequency)
+            ),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::EvaluatePrequentialCV(p) => (
+            "Prequential Cross-Validation",
+            format!(
+                "{DIM}folds={}{RESET}  {}",
+                p.folds,
+                cadence_line(p.sample_frequency, p.mem_check_frequency)
+            ),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::RepeatWithSeeds(p) => {
+            let (_, info_line, max_instances, max_seconds) = describe_task(&p.task);
+            (
+                "Multi-Seed Repetition",
+                format!(
+                    "{DIM}seeds={}..{}{RESET}  {}",
+                    p.base_seed,
+                    p.base_seed.wrapping_add(p.repetitions),
+                    info_line
+                ),
+                max_instances,
+                max_seconds,
+            )
+        }
+        TaskChoice::ThroughputBenchmark(p) => (
+            "Throughput Benchmark",
+            cadence_line(p.sample_frequency, p.mem_check_frequency),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::ClusterStream(p) => (
+            "Stream Clustering",
+            format!(
+                "{DIM}window={}{RESET}  {}",
+                p.window_size,
+                cadence_line(p.sample_frequency, p.mem_check_frequency)
+            ),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::AnomalyScoring(p) => (
+            "Anomaly Scoring",
+            format!(
+                "{DIM}window={}  threshold={}{RESET}  {}",
+                p.eval_window,
+                p.threshold,
+                cadence_line(p.sample_frequency, p.mem_check_frequency)
+            ),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::ConvertStream(p) => (
+            "Convert Stream",
+            format!(
+                "{DIM}output={}{RESET}  {}",
+                p.output.display(),
+                cadence_line(p.sample_frequency, p.mem_check_frequency)
+            ),
+            p.max_instances,
+            p.max_seconds,
+        ),
+        TaskChoice::LearnModel(p) => (
+            "Learn Model",
+            format!(
+                "{DIM}model_path={}{RESET}  {}",
+                p.model_path.display(),
+                cadence_line(p.sample_frequency, p.mem_check_frequency)
+            ),
+            p.max_instances,
+            p.max_seconds,
+        ),
+    }
+}
+
+fn cadence_line(sample_frequency: u64, mem_check_frequency: u64) -> String {
+    format!(
+        "{DIM}sample_freq={}{RESET}  {DIM}mem_check_freq={}{RESET}  {}",
+        sample_frequency,
+        mem_check_frequency,
+        timestamp_now()
+    )
 }
 
 /// Print header once, then refresh a single line with status.
 /// Shows: seen, acc, κ, κₜ/κₘ (if present in `extras`), ips (throughput),
-/// RAM-hours, elapsed time, and small progress bars for instances/time if limits exist.
+/// RAM-hours, elapsed time, small progress bars for instances/time if limits exist,
+/// and the estimated time remaining.
 pub fn render_status_with_header(
-    rx: Receiver<Snapshot>,
+    rx: ProgressReceiver,
     header_lines: Vec<String>,
     repaint_every_ms: u64,
     max_instances: Option<u64>,
@@ -107,20 +1624,20 @@ pub fn render_status_with_header(
     let mut last_draw = Instant::now();
     let mut last_snap: Option<Snapshot> = None;
     let mut prev_for_ips: Option<Snapshot> = None;
+    let mut accuracy_trend = Sparkline::new(SPARKLINE_WIDTH);
 
     loop {
         match rx.recv_timeout(tick) {
             Ok(s) => {
                 prev_for_ips = last_snap.clone();
+                accuracy_trend.push(s.accuracy);
                 last_snap = Some(s);
             }
             Err(RecvTimeoutError::Timeout) => {}
             Err(RecvTimeoutError::Disconnected) => {
                 if let Some(s) = last_snap.take() {
-                    print!(
-                        "\r{}\x1B[K\n",
-                        format_status(&s, prev_for_ips.as_ref(), max_instances, max_seconds)
-                    );
+                    let line = format_status(&s, prev_for_ips.as_ref(), max_instances, max_seconds);
+                    print!("\r{}\x1B[K\n", with_trend(line, &accuracy_trend));
                     let _ = io::stdout().flush();
                 }
                 break;
@@ -130,7 +1647,7 @@ pub fn render_status_with_header(
         if last_draw.elapsed() >= tick {
             if let Some(s) = last_snap.as_ref() {
                 let line = format_status(s, prev_for_ips.as_ref(), max_instances, max_seconds);
-                print!("\r{}\x1B[K", line);
+                print!("\r{}\x1B[K", with_trend(line, &accuracy_trend));
                 let _ = io::stdout().flush();
             }
             last_draw = Instant::now();
@@ -138,6 +1655,180 @@ pub fn render_status_with_header(
     }
 }
 
+/// Appends the recent-accuracy sparkline to a status line.
+fn with_trend(line: String, trend: &Sparkline) -> String {
+    if trend.is_empty() {
+        return line;
+    }
+    format!(
+        "{line}  {DIM}trend{RESET} {FG_CYAN}{}{RESET}",
+        trend.render()
+    )
+}
+
+/// Prints the final snapshot's metrics, the model statistics, and run
+/// totals as a two-column table.
+fn print_summary(
+    curve: &LearningCurve,
+    model: &[Measurement],
+    wall_clock: Duration,
+    progress: ProgressFormat,
+) {
+    let Some(last) = curve.latest() else {
+        return;
+    };
+    let fmt_value = |v: f64| {
+        if !v.is_finite() {
+            "NaN".to_string()
+        } else if v.fract() == 0.0 && v.abs() < 1e15 {
+            format!("{v:.0}")
+        } else {
+            format!("{v:.6}")
+        }
+    };
+
+    let mut sections: Vec<(&str, Vec<(String, String)>)> = Vec::new();
+    let mut metrics = vec![
+        ("accuracy".to_string(), fmt_value(last.accuracy)),
+        ("kappa".to_string(), fmt_value(last.kappa)),
+    ];
+    metrics.extend(
+        last.extras
+            .iter()
+            .map(|(k, v)| (k.to_string(), fmt_value(*v))),
+    );
+    metrics.retain(|(_, v)| v != "NaN");
+    sections.push(("metrics", metrics));
+    if !model.is_empty() {
+        sections.push((
+            "model",
+            model
+                .iter()
+                .map(|m| (m.name.to_string(), fmt_value(m.value)))
+                .collect(),
+        ));
+    }
+    let ips = if last.seconds > 0.0 {
+        fmt_int(last.instances_seen as f64 / last.seconds)
+    } else {
+        "—".into()
+    };
+    sections.push((
+        "run",
+        vec![
+            ("instances".into(), last.instances_seen.to_string()),
+            (
+                "wall_clock".into(),
+                format!("{:.2}s", wall_clock.as_secs_f64()),
+            ),
+            ("instances_per_second".into(), ips),
+            ("ram_hours".into(), format!("{:.6}", last.ram_hours)),
+        ],
+    ));
+
+    let key_w = sections
+        .iter()
+        .flat_map(|(_, rows)| rows.iter().map(|(k, _)| k.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let val_w = sections
+        .iter()
+        .flat_map(|(_, rows)| rows.iter().map(|(_, v)| v.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let rule = "─".repeat(key_w + val_w + 4);
+
+    let mut lines = vec![String::new(), format!("{BOLD}{FG_CYAN}Summary{RESET}")];
+    for (title, rows) in sections {
+        if rows.is_empty() {
+            continue;
+        }
+        lines.push(format!("{FG_GREY}{rule}{RESET}"));
+        lines.push(format!("{DIM}{title}{RESET}"));
+        for (k, v) in rows {
+            lines.push(format!("  {k:<key_w$}  {v:>val_w$}"));
+        }
+    }
+    lines.push(format!("{FG_GREY}{rule}{RESET}"));
+    for line in lines {
+        progress.note(&line);
+    }
+}
+
+/// Number of recent accuracy values drawn in the live status line.
+const SPARKLINE_WIDTH: usize = 24;
+
+/// Minimum time between two lines in plain progress mode.
+const PLAIN_LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Prints the header without colors, then one timestamped line per
+/// snapshot, skipping snapshots that arrive within `every` of the last
+/// printed one. The final snapshot is always printed.
+fn render_plain_lines(rx: ProgressReceiver, header_lines: Vec<String>, every: Duration) {
+    // Locked per line, not for the whole run: the task prints from its own
+    // thread too, and would block on a lock held here until it finished.
+    let mut out = io::stdout();
+    for line in &header_lines {
+        let _ = writeln!(out, "{}", strip_ansi(line));
+    }
+    let _ = out.flush();
+
+    let mut last_print: Option<Instant> = None;
+    let mut pending: Option<Snapshot> = None;
+    for s in rx {
+        if last_print.is_some_and(|t| t.elapsed() < every) {
+            pending = Some(s);
+            continue;
+        }
+        let _ = writeln!(out, "{}", plain_status(&s));
+        let _ = out.flush();
+        last_print = Some(Instant::now());
+        pending = None;
+    }
+    if let Some(s) = pending {
+        let _ = writeln!(out, "{}", plain_status(&s));
+        let _ = out.flush();
+    }
+}
+
+fn plain_status(s: &Snapshot) -> String {
+    let now = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let eta = s.eta.and_then(|e| e.seconds_remaining);
+    match eta {
+        Some(_) => format!("{now} {s}, eta={}", fmt_eta(eta)),
+        None => format!("{now} {s}"),
+    }
+}
+
+/// Removes ANSI escape sequences (CSI `ESC [ ... <letter>`) from `s`.
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Prints every snapshot as one JSON object per line, as soon as it arrives.
+fn render_json_lines(rx: ProgressReceiver) {
+    let mut out = io::stdout();
+    for s in rx {
+        let _ = writeln!(out, "{}", s.to_json());
+        let _ = out.flush();
+    }
+}
+
 fn format_status(
     s: &Snapshot,
     prev: Option<&Snapshot>,
@@ -189,6 +1880,7 @@ fn format_status(
     let bar_w = 20usize;
     let inst_bar = progress_bar(seen as f64, max_instances.map(|m| m as f64), bar_w);
     let time_bar = progress_bar(s.seconds, max_seconds.map(|m| m as f64), bar_w);
+    let eta = fmt_eta(s.eta.and_then(|e| e.seconds_remaining));
 
     format!(
         "{FG_GREEN}{BOLD}seen{RESET} {:>9}  \
@@ -199,7 +1891,8 @@ fn format_status(
          {DIM}ram_h{RESET} {:>8.3}  \
          {DIM}t{RESET} {:>7.2}s  \
          {DIM}[inst]{RESET} {}  \
-         {DIM}[time]{RESET} {}",
+         {DIM}[time]{RESET} {}  \
+         {DIM}eta{RESET} {:>7}",
         seen,
         acc,
         kappa,
@@ -212,11 +1905,14 @@ fn format_status(
         s.ram_hours,
         s.seconds,
         inst_bar,
-        time_bar
+        time_bar,
+        eta
     )
 }
 
-fn snapshot_extras(s: &Snapshot) -> Option<&std::collections::BTreeMap<String, f64>> {
+fn snapshot_extras(
+    s: &Snapshot,
+) -> Option<&std::collections::BTreeMap<rivu::evaluation::MetricKey, f64>> {
     Some(&s.extras)
 }
 
@@ -237,6 +1933,21 @@ fn progress_bar(current: f64, total: Option<f64>, width: usize) -> String {
     }
 }
 
+fn fmt_eta(seconds: Option<f64>) -> String {
+    let Some(secs) = seconds.filter(|s| s.is_finite()) else {
+        return "—".into();
+    };
+    let secs = secs.ceil() as u64;
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{h}h{m:02}m")
+    } else if m > 0 {
+        format!("{m}m{s:02}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
 fn fmtf(x: f64, prec: usize) -> String {
     if x.is_nan() {
         format!("{DIM}NaN{RESET}")
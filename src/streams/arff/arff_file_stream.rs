@@ -1,21 +1,73 @@
 use crate::core::instance_header::InstanceHeader;
 use crate::core::instances::{DenseInstance, Instance};
-use crate::streams::stream::Stream;
+use crate::error::RivuError;
+use crate::streams::stream::{Stream, StreamPosition};
+use crate::streams::watch::{Follow, Follower, WatchOptions, arrival_millis, next_line};
 
-use crate::streams::arff::parser::{is_comment_or_empty, parse_header, parse_instance_values};
+use crate::streams::arff::parse_mode::{ParseMode, ParseReport, RowAction, RowIssue};
+use crate::streams::arff::parser::{RowDecoder, RowError, is_comment_or_empty, parse_header};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Error, Seek, SeekFrom};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Error, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::thread;
 
+/// Capacity of the file buffer, large enough that big files are read in a
+/// few hundred system calls per million rows.
+const READ_BUFFER: usize = 256 * 1024;
+
+/// Stream over the `@data` rows of an ARFF file.
+///
+/// With [`with_watch`](Self::with_watch) the stream follows the file like
+/// `tail -f`: rows appended by another process are read as they arrive,
+/// and when the file shrinks or is replaced reading starts over from its
+/// first data row. The header must stay the same.
+///
+/// Each instance has the zero-based number of its data row as its id
+/// (skipped malformed rows are counted, comments and blank lines are not),
+/// and when watching, the time it was read as its timestamp.
+///
+/// Malformed rows are handled by the [`ParseMode`] set with
+/// [`with_parse_mode`](Self::with_parse_mode): by default they are skipped
+/// and listed in [`report`](Self::report), with their line and column, and
+/// a summary is printed to stderr once the end of the file is reached.
+///
+/// Rows are read into reused line buffers and split without copying. With
+/// [`with_decode_threads`](Self::with_decode_threads), the rows of each
+/// [`next_batch`](Stream::next_batch) (the batches a run's producer thread
+/// reads ahead) are decoded on several threads.
 #[derive(Debug)]
 pub struct ArffFileStream {
     path: PathBuf,
     reader: BufReader<File>,
     header: Arc<InstanceHeader>,
+    decoder: RowDecoder,
+    mode: ParseMode,
+    report: ParseReport,
+    /// The malformed row that ended a strict stream, until taken.
+    error: Option<RowIssue>,
+    /// Whether the report of this pass over the file was printed.
+    report_printed: bool,
+    /// Threads decoding the rows of a batch.
+    decode_threads: usize,
+    /// Line buffers of rows already decoded, reused for the next rows.
+    spare_lines: Vec<String>,
     data_start_pos: u64,
+    /// Lines of the file before the first data row.
+    data_start_line: u64,
+    /// Lines read from the file so far, the number of the last one.
+    lines_read: u64,
     next_line: Option<String>,
+    /// Line number of the row in `next_line`.
+    next_line_number: u64,
     finished: bool,
+    watch: Option<Follower>,
+    /// Data rows consumed since the file was (re)opened, the id of the next.
+    records: u64,
+    /// Byte offset just past the last line read from the file.
+    offset: u64,
+    /// Byte offset of the row in `next_line`.
+    next_offset: u64,
 }
 
 impl Stream for ArffFileStream {
@@ -32,74 +84,356 @@ impl Stream for ArffFileStream {
             return None;
         }
 
-        let line = self.next_line.take()?;
-        if let Err(_) = self.fill_next_line() {
+        // A watched file is read lazily so that the last row is not held
+        // back until the next one arrives.
+        if self.next_line.is_none() && self.fill_next_line().is_err() {
+            self.finished = true;
+        }
+        let Some(line) = self.next_line.take() else {
+            self.print_report();
+            return None;
+        };
+        let number = self.next_line_number;
+        if self.watch.is_none() && self.fill_next_line().is_err() {
             self.finished = true;
         }
+        let id = self.records;
+        self.records += 1;
+
+        let decoded = self.decode(&line, id, number);
+        self.spare_lines.push(line);
+        let accepted = self.accept(decoded);
+        if self.finished {
+            self.print_report();
+        }
+        match accepted {
+            Some(instance) => Some(instance),
+            None => self.next_instance(),
+        }
+    }
 
-        match parse_instance_values(&self.header, &line) {
-            Ok(values) => {
-                let inst = DenseInstance::new(Arc::clone(&self.header), values, 1.0);
-                Some(Box::new(inst) as Box<dyn Instance>)
+    fn next_batch(&mut self, n: usize) -> Vec<Box<dyn Instance>> {
+        if self.decode_threads <= 1 || self.watch.is_some() {
+            let mut batch = Vec::with_capacity(n);
+            while batch.len() < n {
+                match self.next_instance() {
+                    Some(instance) => batch.push(instance),
+                    None => break,
+                }
             }
-            Err(e) => {
-                eprintln!("Invalid data found in line '{line}': {e}");
-                self.next_instance()
+            return batch;
+        }
+
+        let mut lines = Vec::with_capacity(n);
+        while lines.len() < n {
+            let Some(line) = self.next_line.take() else {
+                break;
+            };
+            let number = self.next_line_number;
+            if self.fill_next_line().is_err() {
+                self.finished = true;
             }
+            lines.push((self.records, number, line));
+            self.records += 1;
         }
+
+        let chunk = lines.len().div_ceil(self.decode_threads).max(1);
+        let this = &*self;
+        let decoded: Vec<Vec<Decoded>> = thread::scope(|scope| {
+            let workers: Vec<_> = lines
+                .chunks(chunk)
+                .map(|rows| {
+                    scope.spawn(move || {
+                        rows.iter()
+                            .map(|(id, number, line)| this.decode(line, *id, *number))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|w| w.join().expect("ARFF decoding thread panicked"))
+                .collect()
+        });
+        self.spare_lines
+            .extend(lines.into_iter().map(|(_, _, line)| line));
+        let mut batch = Vec::with_capacity(n);
+        for row in decoded.into_iter().flatten() {
+            if let Some(instance) = self.accept(row) {
+                batch.push(instance);
+            }
+            if self.error.is_some() {
+                break;
+            }
+        }
+        if self.finished {
+            self.print_report();
+        }
+        batch
+    }
+
+    /// Watched files are read in step with the learner: a producer blocked
+    /// waiting for new rows could not be told that the run was cancelled.
+    fn prefers_prefetch(&self) -> bool {
+        self.watch.is_none()
     }
 
     fn restart(&mut self) -> Result<(), Error> {
-        self.reader = BufReader::new(File::open(&self.path)?);
-        self.reader.seek(SeekFrom::Start(self.data_start_pos))?;
+        self.reopen()?;
+        self.report = ParseReport::default();
+        self.error = None;
+        self.report_printed = false;
         self.finished = false;
         self.next_line = None;
-        self.fill_next_line()?;
+        if self.watch.is_none() {
+            self.fill_next_line()?;
+        }
         Ok(())
     }
+
+    fn position(&self) -> Result<StreamPosition, Error> {
+        let offset = if self.next_line.is_some() {
+            self.next_offset
+        } else {
+            self.offset
+        };
+        Ok(StreamPosition::File {
+            record: self.records,
+            offset,
+        })
+    }
+
+    fn seek(&mut self, position: &StreamPosition) -> Result<(), Error> {
+        let &StreamPosition::File { record, offset } = position else {
+            return Err(RivuError::config("a file stream can only seek to a file position").into());
+        };
+        if offset < self.data_start_pos {
+            return Err(RivuError::config(format!(
+                "offset {offset} is before the first data row at {}",
+                self.data_start_pos
+            ))
+            .into());
+        }
+        self.lines_read = lines_before(&self.path, offset)?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.offset = offset;
+        self.records = record;
+        self.finished = false;
+        self.next_line = None;
+        if self.watch.is_none() {
+            self.fill_next_line()?;
+        }
+        Ok(())
+    }
+
+    fn take_error(&mut self) -> Option<Error> {
+        let issue = self.error.take()?;
+        Some(RivuError::parse(format!("{}: {issue}", self.path.display())).into())
+    }
+}
+
+/// A decoded data row: its instance, unless it had to be skipped, and what
+/// was wrong with it.
+struct Decoded {
+    instance: Option<Box<dyn Instance>>,
+    issues: Vec<RowIssue>,
 }
 
 impl ArffFileStream {
     pub fn new(path: PathBuf, class_index: usize) -> Result<Self, Error> {
         let file = File::open(&path)?;
-        let mut reader = BufReader::new(file);
+        let mut reader = BufReader::with_capacity(READ_BUFFER, file);
 
         let (header, data_start_pos) = parse_header(&mut reader, class_index)?;
+        let data_start_line = lines_before(&path, data_start_pos)?;
 
         let mut stream = ArffFileStream {
             path,
             reader,
+            decoder: RowDecoder::new(&header),
+            mode: ParseMode::default(),
+            report: ParseReport::default(),
+            error: None,
+            report_printed: false,
             header: Arc::new(header),
+            decode_threads: 1,
+            spare_lines: Vec::new(),
             data_start_pos,
+            data_start_line,
+            lines_read: data_start_line,
             next_line: None,
+            next_line_number: data_start_line,
             finished: false,
+            watch: None,
+            records: 0,
+            offset: data_start_pos,
+            next_offset: data_start_pos,
         };
 
         stream.fill_next_line()?;
         Ok(stream)
     }
 
+    /// Keeps following the file once its end is reached instead of
+    /// finishing.
+    pub fn with_watch(mut self, options: WatchOptions) -> Self {
+        self.watch = Some(Follower::new(options));
+        self.finished = false;
+        self
+    }
+
+    /// Decodes the rows of each batch on `threads` threads. Rows are still
+    /// read in order on the calling thread, and instances keep their order;
+    /// one thread (the default) decodes them where they are read.
+    pub fn with_decode_threads(mut self, threads: usize) -> Self {
+        self.decode_threads = threads.max(1);
+        self
+    }
+
+    pub fn with_parse_mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn parse_mode(&self) -> ParseMode {
+        self.mode
+    }
+
+    /// Malformed rows skipped or imputed since the stream was opened or
+    /// last restarted.
+    pub fn report(&self) -> &ParseReport {
+        &self.report
+    }
+
+    /// Decodes data row `id`, line `number` of the file.
+    fn decode(&self, line: &str, id: u64, number: u64) -> Decoded {
+        let issue = |e: RowError, action| RowIssue {
+            line: number,
+            column: line[..e.at].chars().count() + 1,
+            record: id,
+            reason: e.message,
+            action,
+        };
+        let mut values = Vec::with_capacity(self.header.number_of_attributes());
+        let impute = self.mode == ParseMode::Impute;
+        let imputed = match self.decoder.decode(line, &mut values, impute) {
+            Ok(imputed) => imputed,
+            Err(e) => {
+                return Decoded {
+                    instance: None,
+                    issues: vec![issue(e, RowAction::Skipped)],
+                };
+            }
+        };
+        let mut inst = DenseInstance::new(Arc::clone(&self.header), values, 1.0).with_id(id);
+        if self.watch.is_some() {
+            inst = inst.with_timestamp(arrival_millis());
+        }
+        Decoded {
+            instance: Some(Box::new(inst)),
+            issues: imputed
+                .into_iter()
+                .map(|e| issue(e, RowAction::Imputed))
+                .collect(),
+        }
+    }
+
+    /// Records the issues of a decoded row and returns its instance, or
+    /// ends a strict stream at its first issue.
+    fn accept(&mut self, decoded: Decoded) -> Option<Box<dyn Instance>> {
+        let mut issues = decoded.issues.into_iter();
+        if self.mode == ParseMode::Strict {
+            if let Some(issue) = issues.next() {
+                self.error = Some(issue);
+                self.finished = true;
+                self.next_line = None;
+                return None;
+            }
+        } else {
+            for issue in issues {
+                self.report.record(issue);
+            }
+        }
+        decoded.instance
+    }
+
+    /// Prints how many rows were skipped or imputed, and the first of them,
+    /// once per pass over the file.
+    fn print_report(&mut self) {
+        if std::mem::replace(&mut self.report_printed, true) {
+            return;
+        }
+        if let Some(first) = self.report.issues.first() {
+            eprintln!(
+                "{}: {} malformed rows skipped, {} values imputed as missing; first at {first}",
+                self.path.display(),
+                self.report.skipped_rows,
+                self.report.imputed_values,
+            );
+        }
+    }
+
+    fn reopen(&mut self) -> Result<(), Error> {
+        self.reader = BufReader::with_capacity(READ_BUFFER, File::open(&self.path)?);
+        self.reader.seek(SeekFrom::Start(self.data_start_pos))?;
+        self.records = 0;
+        self.offset = self.data_start_pos;
+        self.lines_read = self.data_start_line;
+        if let Some(f) = self.watch.as_mut() {
+            f.reset();
+        }
+        Ok(())
+    }
+
     fn fill_next_line(&mut self) -> Result<(), Error> {
         if self.finished {
             self.next_line = None;
             return Ok(());
         }
-        let mut line = String::new();
+        let mut line = self.spare_lines.pop().unwrap_or_default();
         loop {
-            line.clear();
-            let n = self.reader.read_line(&mut line)?;
-            if n == 0 {
-                self.finished = true;
-                self.next_line = None;
-                return Ok(());
+            let start = self.offset;
+            match next_line(&mut self.reader, &self.path, self.watch.as_mut(), &mut line)? {
+                Follow::Line => {
+                    self.offset += line.len() as u64;
+                    self.lines_read += 1;
+                }
+                Follow::Reset => {
+                    self.reopen()?;
+                    continue;
+                }
+                Follow::End => {
+                    self.finished = true;
+                    self.next_line = None;
+                    self.spare_lines.push(line);
+                    return Ok(());
+                }
             }
             if !is_comment_or_empty(&line) {
-                self.next_line = Some(line.trim().to_string());
+                line.truncate(line.trim_end().len());
+                self.next_line = Some(line);
+                self.next_offset = start;
+                self.next_line_number = self.lines_read;
                 return Ok(());
             }
         }
     }
 }
+/// Number of lines that end before byte `offset` of the file at `path`.
+fn lines_before(path: &Path, offset: u64) -> Result<u64, Error> {
+    let mut reader = BufReader::new(File::open(path)?).take(offset);
+    let mut lines = 0;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(lines);
+        }
+        lines += buf.iter().filter(|&&b| b == b'\n').count() as u64;
+        let n = buf.len();
+        reader.consume(n);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +571,158 @@ rainy,70,96,FALSE,yes
         let err = stream.restart().unwrap_err();
         assert_eq!(err.kind(), ErrorKind::NotFound);
     }
+
+    #[test]
+    fn watch_reads_rows_appended_after_the_end() {
+        let tf = write_arff("@relation r\n@attribute a numeric\n@data\n1\n");
+        let options = crate::streams::WatchOptions {
+            poll_interval: std::time::Duration::from_millis(5),
+            idle_timeout: Some(std::time::Duration::from_millis(100)),
+        };
+        let mut s = ArffFileStream::new(tf.path().to_path_buf(), 0)
+            .unwrap()
+            .with_watch(options);
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![1.0]);
+
+        let mut f = fs::OpenOptions::new().append(true).open(tf.path()).unwrap();
+        f.write_all(b"% appended\n2\n").unwrap();
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![2.0]);
+
+        assert!(s.next_instance().is_none());
+        assert!(!s.has_more_instances());
+    }
+
+    #[test]
+    fn parallel_decoding_keeps_the_order_ids_and_skipped_rows() {
+        let mut arff =
+            String::from("@relation r\n@attribute a numeric\n@attribute b {x,y}\n@data\n");
+        for i in 0..50 {
+            if i % 7 == 3 {
+                arff.push_str("oops,x\n% comment\n");
+            } else {
+                arff.push_str(&format!("{i},'{}'\n", if i % 2 == 0 { "x" } else { "y" }));
+            }
+        }
+        let tf = write_arff(&arff);
+        let rows = |s: &mut ArffFileStream| -> Vec<(Option<u64>, Vec<f64>)> {
+            std::iter::from_fn(|| Some(s.next_batch(16)))
+                .take_while(|b| !b.is_empty())
+                .flatten()
+                .map(|i| (i.id(), i.to_vec()))
+                .collect()
+        };
+
+        let mut sequential = ArffFileStream::new(tf.path().to_path_buf(), 1).unwrap();
+        let expected = rows(&mut sequential);
+        assert_eq!(expected.len(), 43);
+        assert_eq!(expected[3], (Some(4), vec![4.0, 0.0]));
+
+        let mut parallel = ArffFileStream::new(tf.path().to_path_buf(), 1)
+            .unwrap()
+            .with_decode_threads(3);
+        assert_eq!(rows(&mut parallel), expected);
+        assert!(!parallel.has_more_instances());
+
+        parallel.restart().unwrap();
+        assert_eq!(rows(&mut parallel), expected);
+    }
+
+    #[test]
+    fn seek_returns_to_a_saved_position() {
+        let tf = write_arff("@relation r\n@attribute a numeric\n@data\n1\n% note\n2\n\n3\n4\n");
+        let mut s = ArffFileStream::new(tf.path().to_path_buf(), 0).unwrap();
+        s.next_instance().unwrap();
+        let position = s.position().unwrap();
+        let rest: Vec<_> = std::iter::from_fn(|| s.next_instance()).collect();
+        assert_eq!(rest.len(), 3);
+        assert!(!s.has_more_instances());
+
+        s.seek(&position).unwrap();
+        let again = s.next_instance().unwrap();
+        assert_eq!(again.to_vec(), vec![2.0]);
+        assert_eq!(again.id(), Some(1));
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![3.0]);
+
+        let before_data = StreamPosition::File {
+            record: 0,
+            offset: 0,
+        };
+        let err = s.seek(&before_data).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    const MALFORMED: &str = "@relation r
+@attribute a numeric
+@attribute b {x,y}
+@data
+1,x
+% note
+2,  oops
+3
+4,y
+";
+
+    #[test]
+    fn strict_mode_ends_the_stream_with_the_line_and_column() {
+        let tf = write_arff(MALFORMED);
+        let mut s = ArffFileStream::new(tf.path().to_path_buf(), 1)
+            .unwrap()
+            .with_parse_mode(ParseMode::Strict);
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![1.0, 0.0]);
+        assert!(s.next_instance().is_none());
+        assert!(!s.has_more_instances());
+
+        let err = s.take_error().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line 7, column 5:"), "{err}");
+        assert!(s.take_error().is_none());
+
+        s.restart().unwrap();
+        assert_eq!(std::iter::from_fn(|| s.next_instance()).count(), 1);
+    }
+
+    #[test]
+    fn skip_mode_reports_each_skipped_row() {
+        let tf = write_arff(MALFORMED);
+        let mut s = ArffFileStream::new(tf.path().to_path_buf(), 1).unwrap();
+        let rows: Vec<_> = std::iter::from_fn(|| s.next_instance())
+            .map(|i| (i.id(), i.to_vec()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![(Some(0), vec![1.0, 0.0]), (Some(3), vec![4.0, 1.0])]
+        );
+        assert!(s.take_error().is_none());
+
+        let report = s.report();
+        assert_eq!((report.skipped_rows, report.imputed_values), (2, 0));
+        let at: Vec<_> = report.issues.iter().map(|i| (i.line, i.column)).collect();
+        assert_eq!(at, vec![(7, 5), (8, 2)]);
+        assert!(report.issues.iter().all(|i| i.action == RowAction::Skipped));
+
+        s.restart().unwrap();
+        assert!(s.report().is_empty());
+    }
+
+    #[test]
+    fn impute_mode_reads_bad_values_as_missing() {
+        let tf = write_arff(MALFORMED);
+        let mut s = ArffFileStream::new(tf.path().to_path_buf(), 1)
+            .unwrap()
+            .with_parse_mode(ParseMode::Impute)
+            .with_decode_threads(2);
+        let rows: Vec<_> = std::iter::from_fn(|| Some(s.next_batch(8)))
+            .take_while(|b| !b.is_empty())
+            .flatten()
+            .map(|i| i.to_vec())
+            .collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1][0], 2.0);
+        assert!(rows[1][1].is_nan());
+
+        let report = s.report();
+        assert_eq!((report.skipped_rows, report.imputed_values), (1, 1));
+        assert_eq!(report.issues[0].action, RowAction::Imputed);
+        assert_eq!(report.issues[1].line, 8);
+    }
 }
@@ -1,4 +1,6 @@
 pub mod arff_file_stream;
+pub mod parse_mode;
 pub(crate) mod parser;
 
 pub use arff_file_stream::ArffFileStream;
+pub use parse_mode::{ParseMode, ParseReport, RowAction, RowIssue};
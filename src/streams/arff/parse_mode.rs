@@ -0,0 +1,109 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Most issues a [`ParseReport`] lists; later ones are only counted.
+pub const MAX_REPORTED_ISSUES: usize = 1_000;
+
+/// How an [`ArffFileStream`](super::ArffFileStream) treats malformed data
+/// rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ParseMode {
+    /// End the stream at the first malformed row. The run fails with the
+    /// row's line and column (see
+    /// [`Stream::take_error`](crate::streams::Stream::take_error)).
+    Strict,
+    /// Skip malformed rows, listing each in the stream's [`ParseReport`].
+    #[default]
+    Skip,
+    /// Read values that do not fit their attribute (an unparsable number,
+    /// an unknown label) as missing, and skip only rows that cannot be
+    /// split into one value per attribute. Both are listed in the report.
+    Impute,
+}
+
+/// What a lenient stream did about a malformed row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RowAction {
+    Skipped,
+    /// The offending value was read as missing.
+    Imputed,
+}
+
+/// A malformed value or row of an ARFF file, and where it is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RowIssue {
+    /// One-based line of the row in the file.
+    pub line: u64,
+    /// One-based character column where the offending value starts.
+    pub column: usize,
+    /// Zero-based number of the data row, the id its instance has.
+    pub record: u64,
+    pub reason: String,
+    pub action: RowAction,
+}
+
+impl fmt::Display for RowIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.reason
+        )
+    }
+}
+
+/// Malformed rows a lenient stream met since it was opened or last
+/// restarted.
+///
+/// The first [`MAX_REPORTED_ISSUES`] issues are kept; the counts cover all
+/// of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ParseReport {
+    /// Rows left out of the stream.
+    pub skipped_rows: u64,
+    /// Values read as missing, in rows that were kept.
+    pub imputed_values: u64,
+    pub issues: Vec<RowIssue>,
+}
+
+impl ParseReport {
+    pub fn is_empty(&self) -> bool {
+        self.skipped_rows == 0 && self.imputed_values == 0
+    }
+
+    pub(super) fn record(&mut self, issue: RowIssue) {
+        match issue.action {
+            RowAction::Skipped => self.skipped_rows += 1,
+            RowAction::Imputed => self.imputed_values += 1,
+        }
+        if self.issues.len() < MAX_REPORTED_ISSUES {
+            self.issues.push(issue);
+        }
+    }
+}
+
+impl fmt::Display for ParseReport {
+    /// One line with the counts, then one line per listed issue.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} malformed rows skipped, {} values imputed as missing",
+            self.skipped_rows, self.imputed_values
+        )?;
+        for issue in &self.issues {
+            let action = match issue.action {
+                RowAction::Skipped => "skipped",
+                RowAction::Imputed => "imputed",
+            };
+            write!(f, "\n  {issue} ({action})")?;
+        }
+        let unlisted = (self.skipped_rows + self.imputed_values) as usize - self.issues.len();
+        if unlisted > 0 {
+            write!(f, "\n  ... and {unlisted} more")?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
 use crate::core::instance_header::InstanceHeader;
-use crate::utils::file_parsing::{split_csv_preserving_quotes, strip_surrounding_quotes};
+use crate::error::RivuError;
+use crate::utils::file_parsing::strip_surrounding_quotes;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Error, ErrorKind, Seek};
@@ -10,6 +12,9 @@ use std::sync::Arc;
 pub(super) enum AttributeKind {
     Numeric,
     Nominal(Vec<String>),
+    /// A nominal set followed by `ordinal`, e.g. `{low, medium, high} ordinal`:
+    /// the values rank in the order listed.
+    Ordinal(Vec<String>),
 }
 
 pub(super) fn is_comment_or_empty(s: &str) -> bool {
@@ -86,36 +91,83 @@ pub(super) fn parse_header(
                     let attribute = NominalAttribute::with_values(name, values, map);
                     attributes.push(Arc::new(attribute) as AttributeRef);
                 }
+                AttributeKind::Ordinal(values) => {
+                    let attribute = NominalAttribute::ordinal(name, values);
+                    attributes.push(Arc::new(attribute) as AttributeRef);
+                }
             }
         } else if low.starts_with("@data") {
             data_start_pos = reader.stream_position()?;
             break;
         } else {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Unsupported header directive: {}", line.trim()),
-            ));
+            return Err(
+                RivuError::parse(format!("Unsupported header directive: {}", line.trim())).into(),
+            );
         }
     }
 
-    let header = InstanceHeader::new(
-        relation.unwrap_or_else(|| "unnamed_relation".to_string()),
-        attributes,
-        class_index,
-    );
+    let relation = relation.unwrap_or_else(|| "unnamed_relation".to_string());
+    let header = match meka_label_count(&relation) {
+        Some(count) => {
+            let labels = label_indices(count, &attributes)?;
+            InstanceHeader::multi_label(relation, attributes, labels)
+        }
+        None => InstanceHeader::new(relation, attributes, class_index),
+    };
+    header.validate_class()?;
 
     Ok((header, data_start_pos))
 }
 
+/// Number of labels declared by a MEKA-style `-C <n>` option in the relation
+/// name (e.g. `@relation 'Music: -C 6'`): the first `n` attributes are labels,
+/// or the last `-n` when negative.
+pub(super) fn meka_label_count(relation: &str) -> Option<i64> {
+    let mut tokens = relation.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token == "-C" {
+            return tokens.next()?.parse().ok();
+        }
+    }
+    None
+}
+
+fn label_indices(count: i64, attributes: &[AttributeRef]) -> Result<Vec<usize>, Error> {
+    let n = attributes.len();
+    let k = count.unsigned_abs() as usize;
+    if count == 0 || k > n {
+        return Err(RivuError::parse(format!(
+            "Relation declares -C {count} labels but there are {n} attributes"
+        ))
+        .into());
+    }
+    let labels: Vec<usize> = if count > 0 {
+        (0..k).collect()
+    } else {
+        (n - k..n).collect()
+    };
+    for &i in &labels {
+        let binary = attributes[i]
+            .as_any()
+            .downcast_ref::<NominalAttribute>()
+            .is_some_and(|a| a.values.len() == 2);
+        if !binary {
+            return Err(RivuError::parse(format!(
+                "Label attribute '{}' must be nominal with two values",
+                attributes[i].name()
+            ))
+            .into());
+        }
+    }
+    Ok(labels)
+}
+
 pub(super) fn parse_attribute_line(line: &str) -> Result<(String, AttributeKind), Error> {
     let rest = {
         let mut l = line.trim();
         let low = l.to_ascii_lowercase();
         if !low.starts_with("@attribute") {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Line is not '@attribute'",
-            ));
+            return Err(RivuError::parse("Line is not '@attribute'").into());
         }
         if let Some(idx) = low.find("@attribute") {
             l = &l[idx + "@attribute".len()..];
@@ -132,12 +184,8 @@ pub(super) fn parse_attribute_line(line: &str) -> Result<(String, AttributeKind)
                 break;
             }
         }
-        let end = end.ok_or_else(|| {
-            Error::new(
-                ErrorKind::InvalidData,
-                "Attribute name without closing quote marks",
-            )
-        })?;
+        let end =
+            end.ok_or_else(|| RivuError::parse("Attribute name without closing quote marks"))?;
         let name = rest[1..end].to_string();
         (name, rest[end + 1..].trim())
     } else {
@@ -145,7 +193,7 @@ pub(super) fn parse_attribute_line(line: &str) -> Result<(String, AttributeKind)
         let name = it.next().unwrap().to_string();
         let after = it
             .next()
-            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Attribute type is missing"))?;
+            .ok_or_else(|| RivuError::parse("Attribute type is missing"))?;
         (name, after.trim())
     };
 
@@ -158,7 +206,7 @@ pub(super) fn parse_attribute_line(line: &str) -> Result<(String, AttributeKind)
     if after_name.starts_with('{') {
         let close = after_name
             .rfind('}')
-            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Nominal set without closing '}'"))?;
+            .ok_or_else(|| RivuError::parse("Nominal set without closing '}'"))?;
 
         let inside = &after_name[1..close];
         let values = inside
@@ -169,74 +217,293 @@ pub(super) fn parse_attribute_line(line: &str) -> Result<(String, AttributeKind)
             .collect::<Vec<_>>();
 
         if values.is_empty() {
-            return Err(Error::new(ErrorKind::InvalidData, "Empty nominal domain"));
+            return Err(RivuError::parse("Empty nominal domain").into());
         }
 
+        let suffix = after_name[close + 1..].trim();
+        if suffix.eq_ignore_ascii_case("ordinal") {
+            return Ok((name, AttributeKind::Ordinal(values)));
+        }
         return Ok((name, AttributeKind::Nominal(values)));
     }
 
-    Err(Error::new(
-        ErrorKind::InvalidData,
-        format!("Attribute kind not supported: {after_name}"),
-    ))
+    Err(RivuError::parse(format!("Attribute kind not supported: {after_name}")).into())
+}
+
+/// How the values of a data column are decoded, resolved once per header.
+#[derive(Debug)]
+enum Column {
+    Numeric,
+    Nominal(HashMap<String, usize>),
+    Unsupported,
+}
+
+/// Why a data row could not be decoded, and where in the row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct RowError {
+    /// Byte offset in the row where the offending value starts.
+    pub(super) at: usize,
+    pub(super) message: String,
+}
+
+impl RowError {
+    fn new(at: usize, message: impl Into<String>) -> Self {
+        Self {
+            at,
+            message: message.into(),
+        }
+    }
 }
 
-pub(super) fn parse_instance_values(
-    header: &InstanceHeader,
-    line: &str,
-) -> Result<Vec<f64>, Error> {
-    let tokens = split_csv_preserving_quotes(line);
-    if tokens.len() != header.attributes.len() {
-        return Err(Error::new(
-            ErrorKind::InvalidData,
+impl From<RowError> for Error {
+    fn from(e: RowError) -> Self {
+        RivuError::parse(e.message).into()
+    }
+}
+
+/// Decodes the `@data` rows of one header.
+///
+/// The attribute types are looked up when the decoder is built, not for
+/// every value, and rows are split by [`Tokens`] without copying them.
+#[derive(Debug)]
+pub(super) struct RowDecoder {
+    columns: Vec<Column>,
+}
+
+impl RowDecoder {
+    pub(super) fn new(header: &InstanceHeader) -> Self {
+        let columns = header
+            .attributes
+            .iter()
+            .map(|attr| {
+                let any = attr.as_any();
+                if any.is::<NumericAttribute>() {
+                    Column::Numeric
+                } else if let Some(nominal) = any.downcast_ref::<NominalAttribute>() {
+                    Column::Nominal(nominal.label_to_index.clone())
+                } else {
+                    Column::Unsupported
+                }
+            })
+            .collect();
+        Self { columns }
+    }
+
+    /// Replaces the contents of `values` with the values of the data row
+    /// `line`, with `NaN` for each missing (`?`) value.
+    ///
+    /// With `impute`, a value that does not fit its attribute is decoded as
+    /// missing instead of failing the row, and the returned errors say why
+    /// each such value was replaced. Rows that cannot be split into one
+    /// value per attribute still fail.
+    pub(super) fn decode(
+        &self,
+        line: &str,
+        values: &mut Vec<f64>,
+        impute: bool,
+    ) -> Result<Vec<RowError>, RowError> {
+        values.clear();
+        let mut imputed = Vec::new();
+        let mut tokens = Tokens::new(line);
+        for (idx, column) in self.columns.iter().enumerate() {
+            let Some(token) = tokens.next() else {
+                return Err(self.arity_error(line.len(), idx));
+            };
+            let Token {
+                text,
+                quoted,
+                start,
+            } = token?;
+            if !quoted && text == "?" {
+                values.push(f64::NAN);
+                continue;
+            }
+            let value = match column {
+                Column::Numeric => text.parse().map_err(|_| {
+                    RowError::new(
+                        start,
+                        format!("Invalid numeric value '{text}' for attribute #{idx}"),
+                    )
+                }),
+                Column::Nominal(label_to_index) => match label_to_index.get(text.as_ref()) {
+                    Some(&pos) => Ok(pos as f64),
+                    None => Err(RowError::new(
+                        start,
+                        format!("Nominal value '{text}' not found in domain of attribute #{idx}"),
+                    )),
+                },
+                Column::Unsupported => Err(RowError::new(
+                    start,
+                    format!("Unsupported attribute type at column #{idx}"),
+                )),
+            };
+            match value {
+                Ok(v) => values.push(v),
+                Err(e) if impute => {
+                    values.push(f64::NAN);
+                    imputed.push(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if let Some(extra) = tokens.next() {
+            let at = extra.map_or_else(|e| e.at, |t| t.start);
+            return Err(self.arity_error(at, self.columns.len() + 1 + tokens.count()));
+        }
+        Ok(imputed)
+    }
+
+    fn arity_error(&self, at: usize, found: usize) -> RowError {
+        RowError::new(
+            at,
             format!(
-                "Number of columns ({}) differs from number of attributes ({})",
-                tokens.len(),
-                header.attributes.len()
+                "Number of columns ({found}) differs from number of attributes ({})",
+                self.columns.len()
             ),
-        ));
+        )
     }
+}
 
-    let mut values = Vec::with_capacity(tokens.len());
-    for (idx, raw) in tokens.into_iter().enumerate() {
-        let raw = raw.trim();
-        if raw == "?" {
-            values.push(f64::NAN);
-            continue;
+/// One value of a data row.
+#[derive(Debug, PartialEq)]
+pub(super) struct Token<'a> {
+    /// The value without its quotes, borrowed from the row unless it had
+    /// escapes to resolve.
+    pub(super) text: Cow<'a, str>,
+    /// Whether the value was quoted, so a quoted `'?'` is a label rather
+    /// than a missing value.
+    pub(super) quoted: bool,
+    /// Byte offset of the value (its opening quote, if quoted) in the row.
+    pub(super) start: usize,
+}
+
+/// Comma-separated values of a data row.
+///
+/// A value may be quoted with `'` or `"`. Inside quotes commas are literal
+/// and a backslash escapes the next character (`\'`, `\"`, `\\`, and `\n`,
+/// `\t`, `\r` for control characters). Whitespace around values is dropped,
+/// and so is an empty value after a trailing comma.
+pub(super) struct Tokens<'a> {
+    line_len: usize,
+    rest: &'a str,
+    done: bool,
+}
+
+impl<'a> Tokens<'a> {
+    pub(super) fn new(line: &'a str) -> Self {
+        Self {
+            line_len: line.len(),
+            rest: line,
+            done: false,
         }
+    }
 
-        let attr = &header.attributes[idx];
+    /// Byte offset of `s`, a suffix of the row, in the row.
+    fn offset(&self, s: &str) -> usize {
+        self.line_len - s.len()
+    }
 
-        if attr.as_any().is::<NumericAttribute>() {
-            let v: f64 = raw.parse().map_err(|_| {
-                Error::new(
-                    ErrorKind::InvalidData,
-                    format!("Invalid numeric value '{raw}' for attribute #{idx}"),
-                )
-            })?;
-            values.push(v);
-            continue;
+    fn quoted(&mut self, s: &'a str) -> Result<Token<'a>, RowError> {
+        let start = self.offset(s);
+        let quote = s.as_bytes()[0];
+        let mut escaped = false;
+        let mut end = None;
+        let mut i = 1;
+        while i < s.len() {
+            match s.as_bytes()[i] {
+                b'\\' => {
+                    escaped = true;
+                    i += 1;
+                }
+                b if b == quote => {
+                    end = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        let end = end.ok_or_else(|| RowError::new(start, "Quoted value without closing quote"))?;
+        let inner = &s[1..end];
+        let after = s[end + 1..].trim_start();
+        let after_at = self.offset(after);
+        if !self.advance(after) {
+            return Err(RowError::new(
+                after_at,
+                format!("Unexpected text after quoted value: {after}"),
+            ));
         }
+        Ok(Token {
+            text: if escaped {
+                Cow::Owned(unescape(inner))
+            } else {
+                Cow::Borrowed(inner)
+            },
+            quoted: true,
+            start,
+        })
+    }
 
-        if let Some(nominal) = attr.as_any().downcast_ref::<NominalAttribute>() {
-            let key = strip_surrounding_quotes(raw);
-            let Some(&pos) = nominal.label_to_index.get(key) else {
-                return Err(Error::new(
-                    ErrorKind::InvalidData,
-                    format!("Nominal value '{key}' not found in domain of attribute #{idx}"),
-                ));
-            };
-            values.push(pos as f64);
-            continue;
+    /// Moves past the comma at the start of `after`, or finishes the row
+    /// when `after` is empty. Returns false when `after` is anything else.
+    fn advance(&mut self, after: &'a str) -> bool {
+        if let Some(rest) = after.strip_prefix(',') {
+            self.rest = rest;
+            self.done = rest.is_empty();
+            true
+        } else {
+            self.done = true;
+            after.is_empty()
         }
+    }
+}
 
-        return Err(Error::new(
-            ErrorKind::InvalidData,
-            format!("Unsupported attribute type at column #{idx}"),
-        ));
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<Token<'a>, RowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let s = self.rest.trim_start();
+        if s.starts_with(['\'', '"']) {
+            let token = self.quoted(s);
+            if token.is_err() {
+                self.done = true;
+            }
+            return Some(token);
+        }
+        let start = self.offset(s);
+        let (text, after) = match s.find(',') {
+            Some(i) => (&s[..i], &s[i..]),
+            None => (s, ""),
+        };
+        self.advance(after);
+        Some(Ok(Token {
+            text: Cow::Borrowed(text.trim_end()),
+            quoted: false,
+            start,
+        }))
     }
+}
 
-    Ok(values)
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
 }
 
 #[cfg(test)]
@@ -244,6 +511,7 @@ mod tests {
     use super::*;
     use crate::core::attributes::{Attribute, AttributeRef, NominalAttribute, NumericAttribute};
     use crate::core::instance_header::InstanceHeader;
+    use crate::core::instances::{DenseInstance, Instance};
     use std::any::Any;
     use std::collections::HashMap;
     use std::fs::File;
@@ -255,6 +523,12 @@ mod tests {
         InstanceHeader::new("r".into(), attrs, class_index)
     }
 
+    fn parse_instance_values(h: &InstanceHeader, line: &str) -> Result<Vec<f64>, Error> {
+        let mut values = Vec::new();
+        RowDecoder::new(h).decode(line, &mut values, false)?;
+        Ok(values)
+    }
+
     fn write_temp(contents: &str) -> NamedTempFile {
         let mut f = NamedTempFile::new().expect("tempfile");
         f.write_all(contents.as_bytes()).unwrap();
@@ -302,6 +576,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_attribute_line_ordinal_suffix_keeps_value_order() {
+        let (_, kind) = parse_attribute_line("@attribute size {low, mid, high} ORDINAL").unwrap();
+        match kind {
+            AttributeKind::Ordinal(v) => assert_eq!(v, vec!["low", "mid", "high"]),
+            _ => panic!("expected ordinal"),
+        }
+    }
+
     #[test]
     fn parse_attribute_line_nominal_missing_closing_brace() {
         let err = parse_attribute_line("@attribute a {x, y").unwrap_err();
@@ -349,6 +632,84 @@ mod tests {
         assert_eq!(err.kind(), ErrorKind::InvalidData);
     }
 
+    fn texts(line: &str) -> Vec<(String, bool)> {
+        Tokens::new(line)
+            .map(|t| t.map(|t| (t.text.into_owned(), t.quoted)))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn tokens_unquote_and_unescape_values() {
+        let t = |s: &str, quoted| (s.to_string(), quoted);
+        assert_eq!(
+            texts(r#" sunny , 'over, cast' ,"85",'it\'s',"a\\b\tc",'?', ?,"#),
+            vec![
+                t("sunny", false),
+                t("over, cast", true),
+                t("85", true),
+                t("it's", true),
+                t("a\\b\tc", true),
+                t("?", true),
+                t("?", false),
+            ]
+        );
+        assert_eq!(
+            texts("1,,2"),
+            vec![t("1", false), t("", false), t("2", false)]
+        );
+
+        let borrowed = Tokens::new("'a,b',c").next().unwrap().unwrap();
+        assert!(matches!(borrowed.text, Cow::Borrowed("a,b")));
+
+        assert!(Tokens::new("'open,1").any(|t| t.is_err()));
+        assert!(Tokens::new("'a'b,1").any(|t| t.is_err()));
+    }
+
+    #[test]
+    fn decoder_keeps_quoted_question_marks_as_labels() {
+        let values = vec!["?".into(), "x y".into()];
+        let map = HashMap::from([("?".into(), 0), ("x y".into(), 1)]);
+        let h = hdr(
+            vec![
+                Arc::new(NominalAttribute::with_values("a".into(), values, map)) as AttributeRef,
+                Arc::new(NumericAttribute::new("n".into())) as AttributeRef,
+            ],
+            0,
+        );
+        let decoder = RowDecoder::new(&h);
+        let mut v = Vec::new();
+        decoder.decode("'?', 2.5", &mut v, false).unwrap();
+        assert_eq!(v, vec![0.0, 2.5]);
+        decoder.decode("\"x y\",?", &mut v, false).unwrap();
+        assert_eq!(v[0], 1.0);
+        assert!(v[1].is_nan());
+        assert!(decoder.decode("?,1,2", &mut v, false).is_err());
+    }
+
+    #[test]
+    fn decoder_imputes_values_that_do_not_fit() {
+        let h = hdr(
+            vec![
+                Arc::new(NominalAttribute::with_values(
+                    "a".into(),
+                    vec!["x".into()],
+                    HashMap::from([("x".into(), 0)]),
+                )) as AttributeRef,
+                Arc::new(NumericAttribute::new("n".into())) as AttributeRef,
+            ],
+            0,
+        );
+        let decoder = RowDecoder::new(&h);
+        let mut v = Vec::new();
+        assert!(decoder.decode("z,1", &mut v, false).is_err());
+        let imputed = decoder.decode("z, abc", &mut v, true).unwrap();
+        assert!(v.iter().all(|x| x.is_nan()));
+        let at: Vec<usize> = imputed.iter().map(|e| e.at).collect();
+        assert_eq!(at, vec![0, 3]);
+        assert!(decoder.decode("?,1,2", &mut v, true).is_err());
+    }
+
     #[derive(Debug)]
     struct DummyAttr;
     impl Attribute for DummyAttr {
@@ -386,6 +747,40 @@ mod tests {
         assert_eq!(err.kind(), ErrorKind::InvalidData);
     }
 
+    #[test]
+    fn parse_header_reads_meka_label_sets() {
+        let tf = write_temp(
+            "@relation 'scene: -C -2'\n@attribute x numeric\n\
+             @attribute beach {0,1}\n@attribute urban {0,1}\n@data\n0.5,1,0\n",
+        );
+        let mut br = BufReader::new(File::open(tf.path()).unwrap());
+        let (h, _) = parse_header(&mut br, 0).unwrap();
+        assert!(h.is_multi_label());
+        assert_eq!(h.label_indices(), &[1, 2]);
+        assert_eq!(h.class_index(), 1);
+
+        let h = Arc::new(h);
+        let values = parse_instance_values(&h, "0.5,1,0").unwrap();
+        let inst = DenseInstance::new(h, values, 1.0);
+        assert_eq!(inst.label_values(), vec![1.0, 0.0]);
+        assert_eq!(inst.relevant_labels(), vec![0]);
+
+        assert_eq!(meka_label_count("Music: -C 6"), Some(6));
+        assert_eq!(meka_label_count("weather"), None);
+    }
+
+    #[test]
+    fn parse_header_rejects_non_binary_labels() {
+        let tf = write_temp("@relation 'r -C 1'\n@attribute x numeric\n@data\n1\n");
+        let mut br = BufReader::new(File::open(tf.path()).unwrap());
+        let err = parse_header(&mut br, 0).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let tf = write_temp("@relation 'r -C 3'\n@attribute x {0,1}\n@data\n1\n");
+        let mut br = BufReader::new(File::open(tf.path()).unwrap());
+        assert!(parse_header(&mut br, 0).is_err());
+    }
+
     #[test]
     fn parse_header_attribute_before_relation_is_reprocessed() {
         let tf = write_temp("@attribute a numeric\n@data\n1\n");
@@ -0,0 +1,265 @@
+use crate::core::header_compatibility::HeaderCompatibility;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::evaluation::Measurement;
+use crate::streams::stream::{DriftMarker, Stream};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::io::Error;
+
+/// A stream that drifts from one concept to another, after MOA's
+/// `ConceptDriftStream`.
+///
+/// Each instance is read from the new concept's stream with probability
+/// `1 / (1 + e^(-4 (t - position) / width))`, where `t` counts the
+/// instances produced so far, and from the base stream otherwise: the
+/// drift is centred at `position` and the concepts blend over about
+/// `width` instances. A width of 1 makes the drift abrupt, switching
+/// streams exactly at `position` without drawing from the RNG.
+///
+/// Either stream may itself be a `ConceptDriftStream`, which is how a run
+/// with several drifts is put together. A stream only advances when it is
+/// read from, so the positions of the drifts inside the new concept's
+/// stream count from this drift: nesting a drift at 1000 as the new concept
+/// of a drift at 5000 puts the second drift near 6000. Both streams must
+/// share one header layout.
+pub struct ConceptDriftStream {
+    base: Box<dyn Stream>,
+    drift: Box<dyn Stream>,
+    marker: DriftMarker,
+    seed: u64,
+    rng: ChaCha12Rng,
+    produced: u64,
+}
+
+impl ConceptDriftStream {
+    /// Returns a header error when `drift` produces instances that do not
+    /// match the header of `base`.
+    pub fn new(
+        base: Box<dyn Stream>,
+        drift: Box<dyn Stream>,
+        position: u64,
+        width: u64,
+        seed: u64,
+    ) -> Result<Self, Error> {
+        HeaderCompatibility::check(base.header(), drift.header()).into_result("drift stream")?;
+        Ok(Self {
+            base,
+            drift,
+            marker: DriftMarker::new(position, width),
+            seed,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            produced: 0,
+        })
+    }
+
+    /// Probability that the next instance comes from the new concept.
+    pub fn drift_probability(&self) -> f64 {
+        let DriftMarker { position, width } = self.marker;
+        if width == 1 {
+            return if self.produced >= position { 1.0 } else { 0.0 };
+        }
+        let x = -4.0 * (self.produced as f64 - position as f64) / width as f64;
+        1.0 / (1.0 + x.exp())
+    }
+
+    /// Whether the next instance is read from the new concept, falling
+    /// back to the other stream once the chosen one is exhausted.
+    fn next_from_drift(&mut self) -> bool {
+        let p = self.drift_probability();
+        let drift = p >= 1.0 || (p > 0.0 && self.rng.random::<f64>() < p);
+        if drift {
+            self.drift.has_more_instances() || !self.base.has_more_instances()
+        } else {
+            !self.base.has_more_instances() && self.drift.has_more_instances()
+        }
+    }
+}
+
+impl Stream for ConceptDriftStream {
+    fn header(&self) -> &InstanceHeader {
+        self.base.header()
+    }
+
+    fn has_more_instances(&self) -> bool {
+        self.base.has_more_instances() || self.drift.has_more_instances()
+    }
+
+    fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
+        let instance = if self.next_from_drift() {
+            self.drift.next_instance()
+        } else {
+            self.base.next_instance()
+        }?;
+        self.produced += 1;
+        Some(instance)
+    }
+
+    fn prefers_prefetch(&self) -> bool {
+        self.base.prefers_prefetch() || self.drift.prefers_prefetch()
+    }
+
+    fn restart(&mut self) -> Result<(), Error> {
+        self.base.restart()?;
+        self.drift.restart()?;
+        self.rng = ChaCha12Rng::seed_from_u64(self.seed);
+        self.produced = 0;
+        Ok(())
+    }
+
+    fn take_error(&mut self) -> Option<Error> {
+        self.base.take_error().or_else(|| self.drift.take_error())
+    }
+
+    fn measurements(&self) -> Vec<Measurement> {
+        let mut out = self.base.measurements();
+        out.extend(self.drift.measurements());
+        out
+    }
+
+    /// The base stream's drifts before this one, this drift, and the new
+    /// concept's drifts shifted to start counting at this drift's position.
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        let position = self.marker.position;
+        let mut markers: Vec<DriftMarker> = self
+            .base
+            .drift_markers()
+            .into_iter()
+            .filter(|m| m.position < position)
+            .collect();
+        markers.push(self.marker);
+        markers.extend(
+            self.drift
+                .drift_markers()
+                .into_iter()
+                .map(|m| DriftMarker::new(m.position + position, m.width)),
+        );
+        markers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use std::sync::Arc;
+
+    /// `len` instances (endless when `None`) whose only attribute is
+    /// `value`.
+    struct Constant {
+        header: Arc<InstanceHeader>,
+        value: f64,
+        len: Option<u64>,
+        read: u64,
+    }
+
+    fn constant(value: f64, len: Option<u64>) -> Box<dyn Stream> {
+        let attrs: Vec<AttributeRef> = vec![Arc::new(NumericAttribute::new("x".into()))];
+        Box::new(Constant {
+            header: Arc::new(InstanceHeader::new("c".into(), attrs, 0)),
+            value,
+            len,
+            read: 0,
+        })
+    }
+
+    impl Stream for Constant {
+        fn header(&self) -> &InstanceHeader {
+            &self.header
+        }
+
+        fn has_more_instances(&self) -> bool {
+            self.len.is_none_or(|n| self.read < n)
+        }
+
+        fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
+            if !self.has_more_instances() {
+                return None;
+            }
+            self.read += 1;
+            Some(Box::new(DenseInstance::new(
+                self.header.clone(),
+                vec![self.value],
+                1.0,
+            )))
+        }
+
+        fn restart(&mut self) -> Result<(), Error> {
+            self.read = 0;
+            Ok(())
+        }
+    }
+
+    fn values(stream: &mut dyn Stream, n: usize) -> Vec<f64> {
+        (0..n)
+            .map_while(|_| stream.next_instance())
+            .map(|i| i.value_at_index(0).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn an_abrupt_drift_switches_streams_at_its_position() {
+        let mut s =
+            ConceptDriftStream::new(constant(0.0, None), constant(1.0, None), 100, 1, 1).unwrap();
+        let v = values(&mut s, 200);
+        assert!(v[..100].iter().all(|&x| x == 0.0));
+        assert!(v[100..].iter().all(|&x| x == 1.0));
+        assert_eq!(s.drift_markers(), vec![DriftMarker::new(100, 1)]);
+    }
+
+    #[test]
+    fn a_gradual_drift_blends_the_concepts_around_its_centre() {
+        let mut s =
+            ConceptDriftStream::new(constant(0.0, None), constant(1.0, None), 2000, 1000, 3)
+                .unwrap();
+        let v = values(&mut s, 4000);
+        let share = |range: std::ops::Range<usize>| {
+            let n = range.len() as f64;
+            v[range].iter().sum::<f64>() / n
+        };
+        assert!(share(0..500) < 0.01);
+        assert!((share(1900..2100) - 0.5).abs() < 0.15);
+        assert!(share(3500..4000) > 0.99);
+
+        // Restarting replays the same choices.
+        s.restart().unwrap();
+        assert_eq!(values(&mut s, 4000), v);
+    }
+
+    #[test]
+    fn nested_drifts_report_their_markers_in_order() {
+        let inner =
+            ConceptDriftStream::new(constant(1.0, None), constant(2.0, None), 300, 1, 1).unwrap();
+        let mut s =
+            ConceptDriftStream::new(constant(0.0, None), Box::new(inner), 500, 1, 1).unwrap();
+        assert_eq!(
+            s.drift_markers(),
+            vec![DriftMarker::new(500, 1), DriftMarker::new(800, 1)]
+        );
+        let v = values(&mut s, 1000);
+        assert_eq!((v[499], v[500], v[799], v[800]), (0.0, 1.0, 1.0, 2.0));
+    }
+
+    #[test]
+    fn exhausted_streams_and_mismatched_headers() {
+        let mut s =
+            ConceptDriftStream::new(constant(0.0, Some(3)), constant(1.0, Some(2)), 10, 1, 1)
+                .unwrap();
+        // The base runs out before the drift, so the new concept takes over.
+        assert_eq!(values(&mut s, 10), vec![0.0, 0.0, 0.0, 1.0, 1.0]);
+        assert!(!s.has_more_instances());
+
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())),
+            Arc::new(NumericAttribute::new("y".into())),
+        ];
+        let wide = Box::new(Constant {
+            header: Arc::new(InstanceHeader::new("w".into(), attrs, 1)),
+            value: 0.0,
+            len: None,
+            read: 0,
+        });
+        assert!(ConceptDriftStream::new(constant(0.0, None), wide, 10, 1, 1).is_err());
+    }
+}
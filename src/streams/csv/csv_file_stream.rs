@@ -0,0 +1,573 @@
+use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::{DenseInstance, Instance};
+use crate::error::RivuError;
+use crate::streams::stream::{Stream, StreamPosition};
+use crate::streams::watch::{Follow, Follower, WatchOptions, arrival_millis, next_line};
+use crate::utils::file_parsing::{split_csv_preserving_quotes, strip_surrounding_quotes};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Error, ErrorKind, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Stream over a comma-separated file whose first row holds the column names.
+///
+/// The schema is inferred with one pass over the file when the stream is
+/// opened: a column whose present values all parse as numbers is numeric,
+/// any other column is nominal with its labels in order of first
+/// appearance. Empty cells and `?` are missing values. The relation name is
+/// the file stem, and the class defaults to the last column.
+///
+/// With [`with_watch`](Self::with_watch) the stream follows the file like
+/// `tail -f`: rows appended by another process are read as they arrive,
+/// and when the file shrinks or is replaced reading starts over from its
+/// first data row. The header row must stay the same.
+///
+/// Each instance has the zero-based number of its data row as its id
+/// (skipped malformed rows are counted, blank lines are not), and when
+/// watching, the time it was read as its timestamp.
+#[derive(Debug)]
+pub struct CsvFileStream {
+    path: PathBuf,
+    reader: BufReader<File>,
+    header: Arc<InstanceHeader>,
+    nominal_maps: Vec<Option<HashMap<String, usize>>>,
+    data_start_pos: u64,
+    next_line: Option<String>,
+    finished: bool,
+    watch: Option<Follower>,
+    /// Data rows consumed since the file was (re)opened, the id of the next.
+    records: u64,
+    /// Byte offset just past the last line read from the file.
+    offset: u64,
+    /// Byte offset of the row in `next_line`.
+    next_offset: u64,
+}
+
+enum ColumnKind {
+    Numeric,
+    Nominal(Vec<String>),
+}
+
+impl CsvFileStream {
+    pub fn new(path: PathBuf, class_index: Option<usize>) -> Result<Self, Error> {
+        let mut reader = BufReader::new(File::open(&path)?);
+
+        let mut first = String::new();
+        if reader.read_line(&mut first)? == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "CSV file has no header row",
+            ));
+        }
+        let names: Vec<String> = split_csv_preserving_quotes(first.trim())
+            .iter()
+            .map(|c| strip_surrounding_quotes(c).to_string())
+            .collect();
+        if names.is_empty() {
+            return Err(RivuError::parse("CSV header is empty").into());
+        }
+        let data_start_pos = reader.stream_position()?;
+
+        let class_index = class_index.unwrap_or(names.len() - 1);
+        if class_index >= names.len() {
+            return Err(RivuError::config(format!(
+                "class index {class_index} out of range for {} columns",
+                names.len()
+            ))
+            .into());
+        }
+
+        let kinds = infer_columns(&mut reader, names.len())?;
+
+        let mut attributes: Vec<AttributeRef> = Vec::with_capacity(names.len());
+        let mut nominal_maps = Vec::with_capacity(names.len());
+        for (name, kind) in names.into_iter().zip(kinds) {
+            match kind {
+                ColumnKind::Numeric => {
+                    attributes.push(Arc::new(NumericAttribute::new(name)));
+                    nominal_maps.push(None);
+                }
+                ColumnKind::Nominal(values) => {
+                    let map: HashMap<String, usize> = values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| (v.clone(), i))
+                        .collect();
+                    attributes.push(Arc::new(NominalAttribute::with_values(
+                        name,
+                        values,
+                        map.clone(),
+                    )));
+                    nominal_maps.push(Some(map));
+                }
+            }
+        }
+
+        let relation = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unnamed_relation")
+            .to_string();
+        let header = InstanceHeader::new(relation, attributes, class_index);
+
+        reader.seek(SeekFrom::Start(data_start_pos))?;
+        let mut stream = CsvFileStream {
+            path,
+            reader,
+            header: Arc::new(header),
+            nominal_maps,
+            data_start_pos,
+            next_line: None,
+            finished: false,
+            watch: None,
+            records: 0,
+            offset: data_start_pos,
+            next_offset: data_start_pos,
+        };
+        stream.fill_next_line()?;
+        Ok(stream)
+    }
+
+    /// Keeps following the file once its end is reached instead of
+    /// finishing.
+    pub fn with_watch(mut self, options: WatchOptions) -> Self {
+        self.watch = Some(Follower::new(options));
+        self.finished = false;
+        self
+    }
+
+    fn reopen(&mut self) -> Result<(), Error> {
+        self.reader = BufReader::new(File::open(&self.path)?);
+        self.reader.seek(SeekFrom::Start(self.data_start_pos))?;
+        self.records = 0;
+        self.offset = self.data_start_pos;
+        if let Some(f) = self.watch.as_mut() {
+            f.reset();
+        }
+        Ok(())
+    }
+
+    /// Lets every nominal attribute except the class grow: a label first seen
+    /// in a later row (typically one appended while watching) is added to
+    /// the attribute and the stream switches to a header that includes it,
+    /// instead of skipping the row. Instances read earlier keep the header
+    /// they were created with.
+    pub fn with_dynamic_nominals(mut self) -> Self {
+        let mut header = (*self.header).clone();
+        let class_index = header.class_index();
+        for (i, attr) in header.attributes.iter_mut().enumerate() {
+            if i == class_index {
+                continue;
+            }
+            if let Some(nominal) = attr.as_any().downcast_ref::<NominalAttribute>() {
+                let mut nominal = nominal.clone();
+                nominal.dynamic = true;
+                *attr = Arc::new(nominal);
+            }
+        }
+        self.header = Arc::new(header);
+        self
+    }
+
+    /// Index of the new `label` of nominal column `col`, when that
+    /// attribute is dynamic; the header is replaced by one listing it.
+    fn grow_nominal(&mut self, col: usize, label: &str) -> Option<usize> {
+        let mut nominal = self.header.attributes[col]
+            .as_any()
+            .downcast_ref::<NominalAttribute>()
+            .filter(|n| n.dynamic)?
+            .clone();
+        let index = nominal.add_value(label);
+        let mut header = (*self.header).clone();
+        header.attributes[col] = Arc::new(nominal);
+        self.header = Arc::new(header);
+        if let Some(map) = self.nominal_maps[col].as_mut() {
+            map.insert(label.to_string(), index);
+        }
+        Some(index)
+    }
+
+    fn parse_values(&mut self, line: &str) -> Result<Vec<f64>, Error> {
+        let cells = split_csv_preserving_quotes(line);
+        if cells.len() != self.nominal_maps.len() {
+            return Err(RivuError::parse(format!(
+                "expected {} columns, found {}",
+                self.nominal_maps.len(),
+                cells.len()
+            ))
+            .into());
+        }
+        let mut values = Vec::with_capacity(cells.len());
+        for (col, cell) in cells.iter().enumerate() {
+            let cell = strip_surrounding_quotes(cell);
+            if is_missing(cell) {
+                values.push(f64::NAN);
+                continue;
+            }
+            let value = match &self.nominal_maps[col] {
+                None => cell
+                    .parse::<f64>()
+                    .map_err(|e| RivuError::parse(format!("'{cell}': {e}")))?,
+                Some(map) => match map.get(cell) {
+                    Some(&i) => i as f64,
+                    None => self
+                        .grow_nominal(col, cell)
+                        .ok_or_else(|| RivuError::parse(format!("unknown label '{cell}'")))?
+                        as f64,
+                },
+            };
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    fn fill_next_line(&mut self) -> Result<(), Error> {
+        if self.finished {
+            self.next_line = None;
+            return Ok(());
+        }
+        let mut line = String::new();
+        loop {
+            let start = self.offset;
+            match next_line(&mut self.reader, &self.path, self.watch.as_mut(), &mut line)? {
+                Follow::Line => self.offset += line.len() as u64,
+                Follow::Reset => {
+                    self.reopen()?;
+                    continue;
+                }
+                Follow::End => {
+                    self.finished = true;
+                    self.next_line = None;
+                    return Ok(());
+                }
+            }
+            if !line.trim().is_empty() {
+                self.next_line = Some(line.trim().to_string());
+                self.next_offset = start;
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Stream for CsvFileStream {
+    fn header(&self) -> &InstanceHeader {
+        &self.header
+    }
+
+    fn has_more_instances(&self) -> bool {
+        !self.finished
+    }
+
+    fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
+        if self.finished {
+            return None;
+        }
+
+        // A watched file is read lazily so that the last row is not held
+        // back until the next one arrives.
+        if self.next_line.is_none() && self.fill_next_line().is_err() {
+            self.finished = true;
+        }
+        let line = self.next_line.take()?;
+        if self.watch.is_none() && self.fill_next_line().is_err() {
+            self.finished = true;
+        }
+        let id = self.records;
+        self.records += 1;
+
+        match self.parse_values(&line) {
+            Ok(values) => {
+                let mut inst =
+                    DenseInstance::new(Arc::clone(&self.header), values, 1.0).with_id(id);
+                if self.watch.is_some() {
+                    inst = inst.with_timestamp(arrival_millis());
+                }
+                Some(Box::new(inst) as Box<dyn Instance>)
+            }
+            Err(e) => {
+                eprintln!("Invalid data found in line '{line}': {e}");
+                self.next_instance()
+            }
+        }
+    }
+
+    /// Watched files are read in step with the learner: a producer blocked
+    /// waiting for new rows could not be told that the run was cancelled.
+    fn prefers_prefetch(&self) -> bool {
+        self.watch.is_none()
+    }
+
+    fn restart(&mut self) -> Result<(), Error> {
+        self.reopen()?;
+        self.finished = false;
+        self.next_line = None;
+        if self.watch.is_none() {
+            self.fill_next_line()?;
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> Result<StreamPosition, Error> {
+        let offset = if self.next_line.is_some() {
+            self.next_offset
+        } else {
+            self.offset
+        };
+        Ok(StreamPosition::File {
+            record: self.records,
+            offset,
+        })
+    }
+
+    fn seek(&mut self, position: &StreamPosition) -> Result<(), Error> {
+        let &StreamPosition::File { record, offset } = position else {
+            return Err(RivuError::config("a file stream can only seek to a file position").into());
+        };
+        if offset < self.data_start_pos {
+            return Err(RivuError::config(format!(
+                "offset {offset} is before the first data row at {}",
+                self.data_start_pos
+            ))
+            .into());
+        }
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.offset = offset;
+        self.records = record;
+        self.finished = false;
+        self.next_line = None;
+        if self.watch.is_none() {
+            self.fill_next_line()?;
+        }
+        Ok(())
+    }
+}
+
+fn is_missing(cell: &str) -> bool {
+    cell.is_empty() || cell == "?"
+}
+
+/// Scans the data rows once and decides the kind of every column.
+fn infer_columns(reader: &mut BufReader<File>, width: usize) -> Result<Vec<ColumnKind>, Error> {
+    let mut numeric = vec![true; width];
+    let mut labels: Vec<Vec<String>> = vec![Vec::new(); width];
+    let mut seen: Vec<HashMap<String, ()>> = vec![HashMap::new(); width];
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let cells = split_csv_preserving_quotes(trimmed);
+        if cells.len() != width {
+            continue;
+        }
+        for (i, cell) in cells.iter().enumerate() {
+            let cell = strip_surrounding_quotes(cell);
+            if is_missing(cell) {
+                continue;
+            }
+            if numeric[i] && cell.parse::<f64>().is_err() {
+                numeric[i] = false;
+            }
+            if seen[i].insert(cell.to_string(), ()).is_none() {
+                labels[i].push(cell.to_string());
+            }
+        }
+    }
+
+    Ok(numeric
+        .into_iter()
+        .zip(labels)
+        .map(|(is_num, labels)| {
+            if is_num {
+                ColumnKind::Numeric
+            } else {
+                ColumnKind::Nominal(labels)
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::Path;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::NamedTempFile;
+
+    fn write_csv(contents: &str) -> NamedTempFile {
+        let mut f = NamedTempFile::with_suffix(".csv").expect("tempfile");
+        f.write_all(contents.as_bytes()).unwrap();
+        f.flush().unwrap();
+        f
+    }
+
+    #[test]
+    fn infers_numeric_and_nominal_columns() {
+        let tf = write_csv("outlook,temp,play\nsunny,85,no\n\"over cast\",83.5,yes\nrainy,?,yes\n");
+        let mut s = CsvFileStream::new(tf.path().to_path_buf(), None).unwrap();
+
+        let h = s.header();
+        assert_eq!(h.number_of_attributes(), 3);
+        assert_eq!(h.class_index(), 2);
+        assert_eq!(h.number_of_classes(), 2);
+        let outlook = h
+            .attribute_at_index(0)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<NominalAttribute>()
+            .unwrap();
+        assert_eq!(outlook.values, vec!["sunny", "over cast", "rainy"]);
+
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![0.0, 85.0, 0.0]);
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![1.0, 83.5, 1.0]);
+        let third = s.next_instance().unwrap();
+        assert!(third.is_missing_at_index(1).unwrap());
+        assert!(!s.has_more_instances());
+
+        s.restart().unwrap();
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![0.0, 85.0, 0.0]);
+    }
+
+    #[test]
+    fn explicit_class_index_is_checked() {
+        let tf = write_csv("a,b\n1,2\n");
+        let s = CsvFileStream::new(tf.path().to_path_buf(), Some(0)).unwrap();
+        assert_eq!(s.header().class_index(), 0);
+
+        let err = CsvFileStream::new(tf.path().to_path_buf(), Some(2)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn rows_with_wrong_arity_are_skipped() {
+        let tf = write_csv("a,b\n1,2\n3\n4,5\n");
+        let mut s = CsvFileStream::new(tf.path().to_path_buf(), None).unwrap();
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![1.0, 2.0]);
+        let last = s.next_instance().unwrap();
+        assert_eq!(last.to_vec(), vec![4.0, 5.0]);
+        assert_eq!((last.id(), last.timestamp()), (Some(2), None));
+        assert!(s.next_instance().is_none());
+
+        s.restart().unwrap();
+        assert_eq!(s.next_instance().unwrap().id(), Some(0));
+    }
+
+    #[test]
+    fn empty_file_errors() {
+        let tf = write_csv("");
+        let err = CsvFileStream::new(tf.path().to_path_buf(), None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut f = OpenOptions::new().append(true).open(path).unwrap();
+        f.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn dynamic_nominals_grow_on_unseen_labels() {
+        let tf = write_csv("color,class\nred,yes\n");
+        let path = tf.path().to_path_buf();
+        let mut fixed = CsvFileStream::new(path.clone(), None).unwrap();
+        let mut dynamic = CsvFileStream::new(path.clone(), None)
+            .unwrap()
+            .with_dynamic_nominals();
+        append(&path, "blue,yes\nred,no\ngreen,yes\n");
+
+        let first = dynamic.next_instance().unwrap();
+        let second = dynamic.next_instance().unwrap();
+        assert_eq!(second.to_vec(), vec![1.0, 0.0]);
+        let values = |h: &InstanceHeader, i: usize| {
+            h.attributes[i]
+                .as_any()
+                .downcast_ref::<NominalAttribute>()
+                .unwrap()
+                .values
+                .clone()
+        };
+        assert_eq!(values(first.header(), 0), vec!["red"]);
+        assert_eq!(values(second.header(), 0), vec!["red", "blue"]);
+        // the class does not grow, so the row with a new class is skipped
+        assert_eq!(dynamic.next_instance().unwrap().to_vec(), vec![2.0, 0.0]);
+        assert_eq!(values(dynamic.header(), 0), vec!["red", "blue", "green"]);
+        assert_eq!(values(dynamic.header(), 1), vec!["yes"]);
+
+        assert_eq!(fixed.next_instance().unwrap().to_vec(), vec![0.0, 0.0]);
+        assert!(fixed.next_instance().is_none());
+    }
+
+    #[test]
+    fn watch_follows_appends_and_restarts_after_truncation() {
+        let tf = write_csv("a,b\n1,2\n");
+        let path = tf.path().to_path_buf();
+        let options = WatchOptions {
+            poll_interval: Duration::from_millis(5),
+            idle_timeout: Some(Duration::from_millis(200)),
+        };
+        let mut s = CsvFileStream::new(path.clone(), None)
+            .unwrap()
+            .with_watch(options);
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![1.0, 2.0]);
+        assert!(s.has_more_instances());
+
+        append(&path, "3,4\n");
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![3.0, 4.0]);
+
+        fs::write(&path, "a,b\n5,6\n").unwrap();
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![5.0, 6.0]);
+
+        append(&path, "7,");
+        let writer = {
+            let path = path.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                append(&path, "8\n");
+            })
+        };
+        assert_eq!(s.next_instance().unwrap().to_vec(), vec![7.0, 8.0]);
+        writer.join().unwrap();
+
+        assert!(s.next_instance().is_none());
+        assert!(!s.has_more_instances());
+    }
+
+    #[test]
+    fn seek_returns_to_a_saved_position_with_and_without_watching() {
+        let tf = write_csv("a,b\n1,x\n2,y\n\n3,x\n");
+        let options = WatchOptions {
+            poll_interval: Duration::from_millis(5),
+            idle_timeout: Some(Duration::from_millis(20)),
+        };
+        for watch in [false, true] {
+            let mut s = CsvFileStream::new(tf.path().to_path_buf(), None).unwrap();
+            if watch {
+                s = s.with_watch(options);
+            }
+            s.next_instance().unwrap();
+            let position = s.position().unwrap();
+            assert_eq!(s.next_instance().unwrap().to_vec(), vec![2.0, 1.0]);
+            assert_eq!(s.next_instance().unwrap().to_vec(), vec![3.0, 0.0]);
+            assert!(s.next_instance().is_none());
+
+            s.seek(&position).unwrap();
+            assert!(s.has_more_instances());
+            let again = s.next_instance().unwrap();
+            assert_eq!(again.to_vec(), vec![2.0, 1.0]);
+            assert_eq!(again.id(), Some(1));
+            assert_eq!(s.next_instance().unwrap().to_vec(), vec![3.0, 0.0]);
+        }
+    }
+}
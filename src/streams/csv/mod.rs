@@ -0,0 +1,3 @@
+pub mod csv_file_stream;
+
+pub use csv_file_stream::CsvFileStream;
@@ -0,0 +1,296 @@
+use crate::error::RivuError;
+use crate::streams::Stream;
+use crate::streams::arff::ArffFileStream;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Program that downloads the datasets.
+const CURL: &str = "curl";
+
+/// Where OpenML describes a dataset, including the URL of its ARFF file.
+const OPENML_API: &str = "https://www.openml.org/api/v1/json/data";
+
+/// Public benchmark streams of the stream-learning literature, the versions
+/// MOA, River and scikit-multiflow evaluate on.
+///
+/// Each dataset is downloaded once as ARFF from OpenML into a cache
+/// directory ([`default_dir`](Self::default_dir) unless another is given)
+/// and read from there afterwards. A file placed in the directory under
+/// [`file_name`](Self::file_name) is used as is, for machines without
+/// network access. Before a file is used its header is checked against the
+/// dataset's, and the class attribute is taken by name, so a renamed or
+/// truncated download fails instead of training on the wrong column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dataset {
+    /// Electricity prices of the Australian NSW market (Elec2), 45,312
+    /// half-hour readings labelled by whether the price went up or down,
+    /// normalized as in MOA.
+    #[default]
+    Electricity,
+    /// Forest cover type of 30x30 m cells from cartographic variables,
+    /// 581,012 instances of 54 attributes and 7 classes, normalized as in
+    /// MOA.
+    Covertype,
+    /// US flight records labelled by whether the flight was delayed,
+    /// 539,383 instances of 7 attributes.
+    Airlines,
+}
+
+impl Dataset {
+    pub const ALL: [Dataset; 3] = [Dataset::Electricity, Dataset::Covertype, Dataset::Airlines];
+
+    /// Name of the cached file.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Dataset::Electricity => "electricity.arff",
+            Dataset::Covertype => "covertype.arff",
+            Dataset::Airlines => "airlines.arff",
+        }
+    }
+
+    fn openml_id(self) -> u32 {
+        match self {
+            Dataset::Electricity => 151,
+            Dataset::Covertype => 150,
+            Dataset::Airlines => 1169,
+        }
+    }
+
+    /// Number of attributes, the class included.
+    pub fn number_of_attributes(self) -> usize {
+        match self {
+            Dataset::Electricity => 9,
+            Dataset::Covertype => 55,
+            Dataset::Airlines => 8,
+        }
+    }
+
+    /// Name of the class attribute.
+    pub fn class_name(self) -> &'static str {
+        match self {
+            Dataset::Electricity | Dataset::Covertype => "class",
+            Dataset::Airlines => "Delay",
+        }
+    }
+
+    /// `$RIVU_DATA_DIR` when set, otherwise `rivu/datasets` under the
+    /// user's cache directory (`$XDG_CACHE_HOME`, `%LOCALAPPDATA%` or
+    /// `~/.cache`). `None` when no such directory is known.
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Some(p) = std::env::var_os("RIVU_DATA_DIR") {
+            return Some(PathBuf::from(p));
+        }
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .or_else(|| std::env::var_os("LOCALAPPDATA"))
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+        Some(base.join("rivu").join("datasets"))
+    }
+
+    /// Path of the dataset in `dir`, downloading it first when it is not
+    /// there yet.
+    ///
+    /// Downloading runs `curl`, which must be on the `PATH`. The file is
+    /// written next to its final name and only moved there once its header
+    /// checks out, so an interrupted download is never mistaken for the
+    /// dataset.
+    pub fn fetch(self, dir: &Path) -> Result<PathBuf, Error> {
+        self.fetch_with_class(dir).map(|(path, _)| path)
+    }
+
+    /// Stream over the dataset in `dir`, fetched as by
+    /// [`fetch`](Self::fetch).
+    pub fn open(self, dir: &Path) -> Result<ArffFileStream, Error> {
+        let (path, class_index) = self.fetch_with_class(dir)?;
+        ArffFileStream::new(path, class_index)
+    }
+
+    /// Whether [`fetch`](Self::fetch) can get the dataset into `dir`: it is
+    /// there already, or `curl` is on the `PATH` to download it. Checks
+    /// nothing about the network, so a download can still fail.
+    pub fn check_fetchable(self, dir: &Path) -> Result<(), Error> {
+        self.check_fetchable_with(dir, CURL)
+    }
+
+    fn check_fetchable_with(self, dir: &Path, downloader: &str) -> Result<(), Error> {
+        let path = dir.join(self.file_name());
+        if path.is_file() || runs(downloader) {
+            return Ok(());
+        }
+        Err(Error::new(
+            ErrorKind::NotFound,
+            format!(
+                "{} is not cached and downloading it needs `{downloader}` on the PATH; \
+                 install it or place the ARFF file there",
+                path.display()
+            ),
+        ))
+    }
+
+    fn fetch_with_class(self, dir: &Path) -> Result<(PathBuf, usize), Error> {
+        let path = dir.join(self.file_name());
+        if path.is_file() {
+            let class_index = self.class_index(&path)?;
+            return Ok((path, class_index));
+        }
+        fs::create_dir_all(dir)?;
+        let partial = dir.join(format!("{}.part", self.file_name()));
+        let downloaded = self
+            .arff_url()
+            .and_then(|url| curl(&url, Some(&partial)))
+            .and_then(|_| self.class_index(&partial));
+        match downloaded {
+            Ok(class_index) => {
+                fs::rename(&partial, &path)?;
+                Ok((path, class_index))
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&partial);
+                Err(e)
+            }
+        }
+    }
+
+    /// Index of the class attribute in the ARFF file at `path`, after
+    /// checking that the file has the dataset's attributes.
+    fn class_index(self, path: &Path) -> Result<usize, Error> {
+        let stream = ArffFileStream::new(path.to_path_buf(), 0)?;
+        let header = stream.header();
+        let class_index = header.index_of_attribute(self.class_name());
+        match class_index {
+            Some(i) if header.number_of_attributes() == self.number_of_attributes() => Ok(i),
+            _ => Err(RivuError::parse(format!(
+                "{} is not the {self:?} dataset: expected {} attributes with class '{}', \
+                 found {} attributes; delete it to download the dataset again",
+                path.display(),
+                self.number_of_attributes(),
+                self.class_name(),
+                header.number_of_attributes(),
+            ))
+            .into()),
+        }
+    }
+
+    /// URL of the dataset's ARFF file, from its OpenML description.
+    fn arff_url(self) -> Result<String, Error> {
+        let api = format!("{OPENML_API}/{}", self.openml_id());
+        let output = curl(&api, None)?;
+        let description: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| RivuError::parse(format!("invalid OpenML response from {api}: {e}")))?;
+        description["data_set_description"]["url"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| {
+                RivuError::parse(format!("OpenML response from {api} has no file URL")).into()
+            })
+    }
+}
+
+/// Downloads `url` into the file `to`, or into the returned output when
+/// `to` is `None`.
+fn curl(url: &str, to: Option<&Path>) -> Result<Output, Error> {
+    let mut command = Command::new(CURL);
+    command.args(["--fail", "--silent", "--show-error", "--location"]);
+    if let Some(to) = to {
+        command.arg("--output").arg(to);
+    }
+    let output = command.arg(url).output().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => Error::new(
+            ErrorKind::NotFound,
+            "downloading a dataset needs `curl` on the PATH; \
+             alternatively, place the ARFF file in the data directory",
+        ),
+        _ => e,
+    })?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "failed to download {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output)
+}
+
+/// Whether `program` is installed, i.e. `program --version` runs and
+/// succeeds.
+fn runs(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const ELECTRICITY_HEAD: &str = "@relation elecNormNew
+@attribute date numeric
+@attribute day {1,2,3,4,5,6,7}
+@attribute period numeric
+@attribute nswprice numeric
+@attribute nswdemand numeric
+@attribute vicprice numeric
+@attribute vicdemand numeric
+@attribute transfer numeric
+@attribute class {UP,DOWN}
+@data
+0,2,0,0.056443,0.439155,0.003467,0.422915,0.414912,UP
+0,2,0.021277,0.051699,0.415055,0.003467,0.422915,0.414912,UP
+";
+
+    #[test]
+    fn a_cached_file_is_opened_with_its_class_attribute() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("electricity.arff"), ELECTRICITY_HEAD).unwrap();
+
+        let mut stream = Dataset::Electricity.open(dir.path()).unwrap();
+        assert_eq!(stream.header().class_index(), 8);
+        assert_eq!(stream.header().number_of_classes(), 2);
+        assert!(stream.next_instance().is_some());
+    }
+
+    #[test]
+    fn a_missing_downloader_is_reported_unless_the_file_is_cached() {
+        let dir = tempdir().unwrap();
+        let missing = "rivu-no-such-downloader";
+        let err = Dataset::Electricity
+            .check_fetchable_with(dir.path(), missing)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(
+            err.to_string().contains("electricity.arff is not cached"),
+            "{err}"
+        );
+        assert!(err.to_string().contains(missing), "{err}");
+
+        fs::write(dir.path().join("electricity.arff"), ELECTRICITY_HEAD).unwrap();
+        assert!(
+            Dataset::Electricity
+                .check_fetchable_with(dir.path(), missing)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn a_file_with_another_header_is_rejected() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("airlines.arff"), ELECTRICITY_HEAD).unwrap();
+
+        let err = Dataset::Airlines.fetch(dir.path()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("class 'Delay'"), "{err}");
+    }
+
+    #[test]
+    fn datasets_are_named_in_kebab_case() {
+        let json = serde_json::to_string(&Dataset::ALL).unwrap();
+        assert_eq!(json, r#"["electricity","covertype","airlines"]"#);
+    }
+}
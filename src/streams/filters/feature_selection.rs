@@ -0,0 +1,492 @@
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::error::RivuError;
+use crate::evaluation::Measurement;
+use crate::streams::{DriftMarker, Stream};
+use std::collections::VecDeque;
+use std::io::Error;
+
+/// Bins numeric attributes are cut into when their information is
+/// measured.
+const DEFAULT_BINS: usize = 10;
+
+/// Least symmetric uncertainty with the class a selected attribute has.
+const DEFAULT_THRESHOLD: f64 = 0.01;
+
+/// Online feature selection over a sliding window, in the style of FCBF
+/// (Yu and Liu, 2003).
+///
+/// The last `window` labelled instances are kept. Every `update_interval`
+/// instances the selection is redone over them: each attribute is scored
+/// by its symmetric uncertainty with the class, `SU(X, C) = 2 I(X; C) /
+/// (H(X) + H(C))`, a mutual information normalized to `[0, 1]`, and those
+/// scoring below the threshold are dropped. The rest are visited from the
+/// most relevant down, and each drops every less relevant one it predicts
+/// at least as well as the class does (`SU(X, Y) >= SU(Y, C)`), so
+/// redundant copies of a kept attribute go too. At most `max_features` of
+/// what remains are kept, and never fewer than one.
+///
+/// Numeric attributes (and a numeric class) are cut into `bins` bins of
+/// equal width over the range they have in the window; nominal attributes
+/// use their values. Missing values are left out of the scores they would
+/// enter. Until the first update every attribute is selected.
+#[derive(Debug, Clone)]
+pub struct FeatureSelector {
+    window: usize,
+    update_interval: u64,
+    bins: usize,
+    threshold: f64,
+    max_features: Option<usize>,
+
+    /// Value count of each nominal attribute, `None` for numeric ones.
+    levels: Vec<Option<usize>>,
+    class_index: usize,
+    rows: VecDeque<Vec<f64>>,
+    seen: u64,
+    updates: u64,
+    /// One flag per attribute; the class is always selected.
+    mask: Vec<bool>,
+    /// Symmetric uncertainty of each attribute with the class at the last
+    /// update (1 for the class itself).
+    relevance: Vec<f64>,
+}
+
+impl FeatureSelector {
+    /// Selector for instances of `header`, redone every `update_interval`
+    /// instances over the last `window`.
+    pub fn new(
+        header: &InstanceHeader,
+        window: usize,
+        update_interval: u64,
+    ) -> Result<Self, Error> {
+        if window == 0 || update_interval == 0 {
+            return Err(RivuError::config("window and update_interval must be positive").into());
+        }
+        let n = header.number_of_attributes();
+        let levels = (0..n)
+            .map(|i| {
+                header
+                    .attribute_at_index(i)
+                    .and_then(|a| a.as_any().downcast_ref::<NominalAttribute>())
+                    .map(|a| a.values.len())
+            })
+            .collect();
+        let class_index = header.class_index();
+        let mut relevance = vec![0.0; n];
+        relevance[class_index] = 1.0;
+        Ok(Self {
+            window,
+            update_interval,
+            bins: DEFAULT_BINS,
+            threshold: DEFAULT_THRESHOLD,
+            max_features: None,
+            levels,
+            class_index,
+            rows: VecDeque::with_capacity(window),
+            seen: 0,
+            updates: 0,
+            mask: vec![true; n],
+            relevance,
+        })
+    }
+
+    /// Bins numeric attributes are cut into (10 by default).
+    pub fn with_bins(mut self, bins: usize) -> Self {
+        self.bins = bins.max(2);
+        self
+    }
+
+    /// Least relevance a selected attribute has (0.01 by default).
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Largest number of attributes selected, the class aside.
+    pub fn with_max_features(mut self, max_features: usize) -> Self {
+        self.max_features = Some(max_features.max(1));
+        self
+    }
+
+    /// Which attributes are selected, by index.
+    pub fn mask(&self) -> &[bool] {
+        &self.mask
+    }
+
+    /// Indices of the selected attributes, the class aside.
+    pub fn selected(&self) -> Vec<usize> {
+        (0..self.mask.len())
+            .filter(|&i| i != self.class_index && self.mask[i])
+            .collect()
+    }
+
+    /// Symmetric uncertainty of each attribute with the class, as of the
+    /// last update.
+    pub fn relevance(&self) -> &[f64] {
+        &self.relevance
+    }
+
+    /// Times the selection has been redone.
+    pub fn updates(&self) -> u64 {
+        self.updates
+    }
+
+    /// Adds a labelled instance to the window, redoing the selection when
+    /// an update is due. Returns whether it was redone.
+    pub fn observe(&mut self, instance: &dyn Instance) -> bool {
+        if instance.is_class_missing() || instance.weight() <= 0.0 {
+            return false;
+        }
+        if self.rows.len() == self.window {
+            self.rows.pop_front();
+        }
+        self.rows.push_back(instance.to_vec());
+        self.seen += 1;
+        if self.seen.is_multiple_of(self.update_interval) {
+            self.update();
+            return true;
+        }
+        false
+    }
+
+    /// Forgets the window and selects every attribute again.
+    pub fn reset(&mut self) {
+        self.rows.clear();
+        self.seen = 0;
+        self.updates = 0;
+        self.mask.fill(true);
+        self.relevance.fill(0.0);
+        self.relevance[self.class_index] = 1.0;
+    }
+
+    /// `selected_features`, the number of attributes selected, then
+    /// `feature_<i>_selected` (1 or 0) and `feature_<i>_relevance` for each
+    /// attribute `i` but the class.
+    pub fn measurements(&self) -> Vec<Measurement> {
+        let mut out = vec![Measurement::new(
+            "selected_features",
+            self.selected().len() as f64,
+        )];
+        for i in (0..self.mask.len()).filter(|&i| i != self.class_index) {
+            let selected = if self.mask[i] { 1.0 } else { 0.0 };
+            out.push(Measurement::new(format!("feature_{i}_selected"), selected));
+            out.push(Measurement::new(
+                format!("feature_{i}_relevance"),
+                self.relevance[i],
+            ));
+        }
+        out
+    }
+
+    fn update(&mut self) {
+        let columns: Vec<Column> = (0..self.mask.len()).map(|i| self.column(i)).collect();
+        let class = &columns[self.class_index];
+
+        let mut candidates: Vec<usize> = Vec::new();
+        for (i, column) in columns.iter().enumerate() {
+            if i == self.class_index {
+                continue;
+            }
+            self.relevance[i] = symmetric_uncertainty(column, class);
+            if self.relevance[i] >= self.threshold {
+                candidates.push(i);
+            }
+        }
+        candidates.sort_by(|&a, &b| self.relevance[b].total_cmp(&self.relevance[a]));
+
+        let mut kept: Vec<usize> = Vec::new();
+        for &q in &candidates {
+            let redundant = kept
+                .iter()
+                .any(|&p| symmetric_uncertainty(&columns[p], &columns[q]) >= self.relevance[q]);
+            if !redundant {
+                kept.push(q);
+            }
+        }
+        if let Some(max) = self.max_features {
+            kept.truncate(max);
+        }
+        if kept.is_empty() {
+            let best = (0..self.mask.len())
+                .filter(|&i| i != self.class_index)
+                .max_by(|&a, &b| self.relevance[a].total_cmp(&self.relevance[b]));
+            kept.extend(best);
+        }
+
+        self.mask.fill(false);
+        self.mask[self.class_index] = true;
+        for i in kept {
+            self.mask[i] = true;
+        }
+        self.updates += 1;
+    }
+
+    /// Attribute `i` over the window, discretized.
+    fn column(&self, i: usize) -> Column {
+        let values = self.rows.iter().map(|row| row[i]);
+        if let Some(levels) = self.levels[i] {
+            let bins = values
+                .map(|v| {
+                    (v.is_finite() && v >= 0.0)
+                        .then_some(v as usize)
+                        .filter(|&b| b < levels)
+                })
+                .collect();
+            return Column { bins, levels };
+        }
+        let (min, max) = values
+            .clone()
+            .filter(|v| v.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            });
+        let width = (max - min) / self.bins as f64;
+        let bins = values
+            .map(|v| {
+                v.is_finite().then(|| {
+                    if width > 0.0 {
+                        (((v - min) / width) as usize).min(self.bins - 1)
+                    } else {
+                        0
+                    }
+                })
+            })
+            .collect();
+        Column {
+            bins,
+            levels: self.bins,
+        }
+    }
+}
+
+/// An attribute over the window: the bin of each row (`None` where the
+/// value is missing) and the number of bins.
+struct Column {
+    bins: Vec<Option<usize>>,
+    levels: usize,
+}
+
+/// `2 I(X; Y) / (H(X) + H(Y))` over the rows where both are present; 0
+/// when neither varies.
+fn symmetric_uncertainty(x: &Column, y: &Column) -> f64 {
+    let mut joint = vec![0.0; x.levels * y.levels];
+    let mut px = vec![0.0; x.levels];
+    let mut py = vec![0.0; y.levels];
+    for (a, b) in x.bins.iter().zip(&y.bins) {
+        if let (Some(a), Some(b)) = (a, b) {
+            joint[a * y.levels + b] += 1.0;
+            px[*a] += 1.0;
+            py[*b] += 1.0;
+        }
+    }
+    let (hx, hy, hxy) = (entropy(&px), entropy(&py), entropy(&joint));
+    if hx + hy <= 0.0 {
+        return 0.0;
+    }
+    (2.0 * (hx + hy - hxy) / (hx + hy)).clamp(0.0, 1.0)
+}
+
+/// Entropy in bits of the distribution with these counts.
+fn entropy(counts: &[f64]) -> f64 {
+    let total: f64 = counts.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    counts
+        .iter()
+        .filter(|&&c| c > 0.0)
+        .map(|&c| {
+            let p = c / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// A stream with only the attributes a [`FeatureSelector`] selects.
+///
+/// Each instance of the inner stream goes to the selector first, and then
+/// has the attributes left out replaced by missing values. The header stays
+/// the inner stream's, as a [`Stream`] header cannot change while the
+/// selection does; learners skip missing values, so they train and predict
+/// on the selected attributes only. Restarting the stream resets the
+/// selector.
+pub struct FeatureSelectionStream {
+    inner: Box<dyn Stream>,
+    selector: FeatureSelector,
+}
+
+impl FeatureSelectionStream {
+    pub fn new(inner: Box<dyn Stream>, selector: FeatureSelector) -> Self {
+        Self { inner, selector }
+    }
+
+    pub fn selector(&self) -> &FeatureSelector {
+        &self.selector
+    }
+
+    fn project(&self, instance: &mut dyn Instance) {
+        for (i, &selected) in self.selector.mask().iter().enumerate() {
+            if !selected {
+                let _ = instance.set_value_at_index(i, f64::NAN);
+            }
+        }
+    }
+}
+
+impl Stream for FeatureSelectionStream {
+    fn header(&self) -> &InstanceHeader {
+        self.inner.header()
+    }
+
+    fn has_more_instances(&self) -> bool {
+        self.inner.has_more_instances()
+    }
+
+    fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
+        let mut instance = self.inner.next_instance()?;
+        self.selector.observe(instance.as_ref());
+        self.project(instance.as_mut());
+        Some(instance)
+    }
+
+    fn recycle(&mut self, instance: Box<dyn Instance>) {
+        self.inner.recycle(instance);
+    }
+
+    fn prefers_prefetch(&self) -> bool {
+        self.inner.prefers_prefetch()
+    }
+
+    fn restart(&mut self) -> Result<(), Error> {
+        self.selector.reset();
+        self.inner.restart()
+    }
+
+    fn take_error(&mut self) -> Option<Error> {
+        self.inner.take_error()
+    }
+
+    fn measurements(&self) -> Vec<Measurement> {
+        let mut out = self.inner.measurements();
+        out.extend(self.selector.measurements());
+        out
+    }
+
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        self.inner.drift_markers()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::core::instances::DenseInstance;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::sync::Arc;
+
+    /// `signal` decides the class, `copy` repeats it, `noise` is unrelated.
+    fn rows(n: usize) -> (Arc<InstanceHeader>, Vec<Vec<f64>>) {
+        let attrs: Vec<AttributeRef> = ["signal", "copy", "noise", "class"]
+            .into_iter()
+            .map(|name| Arc::new(NumericAttribute::new(name.into())) as AttributeRef)
+            .collect();
+        let header = Arc::new(InstanceHeader::new("r".into(), attrs, 3));
+        let mut rng = StdRng::seed_from_u64(1);
+        let rows = (0..n)
+            .map(|_| {
+                let signal: f64 = rng.random();
+                let class = if signal > 0.5 { 1.0 } else { 0.0 };
+                vec![signal, signal * 2.0, rng.random(), class]
+            })
+            .collect();
+        (header, rows)
+    }
+
+    struct Rows {
+        header: Arc<InstanceHeader>,
+        rows: Vec<Vec<f64>>,
+        next: usize,
+    }
+
+    impl Stream for Rows {
+        fn header(&self) -> &InstanceHeader {
+            &self.header
+        }
+
+        fn has_more_instances(&self) -> bool {
+            self.next < self.rows.len()
+        }
+
+        fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
+            let row = self.rows.get(self.next)?.clone();
+            self.next += 1;
+            Some(Box::new(DenseInstance::new(self.header.clone(), row, 1.0)))
+        }
+
+        fn restart(&mut self) -> Result<(), Error> {
+            self.next = 0;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn ctor_guards() {
+        let (header, _) = rows(0);
+        assert!(FeatureSelector::new(&header, 0, 10).is_err());
+        assert!(FeatureSelector::new(&header, 10, 0).is_err());
+    }
+
+    #[test]
+    fn relevant_attributes_are_kept_and_redundant_or_noisy_ones_dropped() {
+        let (header, rows) = rows(500);
+        let mut selector = FeatureSelector::new(&header, 400, 250).unwrap();
+        assert_eq!(selector.selected(), vec![0, 1, 2]);
+        for row in &rows {
+            selector.observe(&DenseInstance::new(header.clone(), row.clone(), 1.0));
+        }
+        assert_eq!(selector.updates(), 2);
+        assert_eq!(selector.selected(), vec![0]);
+        assert!(selector.relevance()[0] > 0.3);
+        assert!(selector.relevance()[2] < 0.05);
+
+        let m = selector.measurements();
+        assert_eq!(m[0].value, 1.0);
+        assert_eq!(m.len(), 1 + 2 * 3);
+
+        selector.reset();
+        assert_eq!(selector.selected(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn the_stream_masks_what_the_selector_leaves_out() {
+        let (header, rows) = rows(300);
+        let inner = Rows {
+            header: header.clone(),
+            rows,
+            next: 0,
+        };
+        let selector = FeatureSelector::new(&header, 100, 100)
+            .unwrap()
+            .with_max_features(1);
+        let mut stream = FeatureSelectionStream::new(Box::new(inner), selector);
+
+        let first = stream.next_instance().unwrap();
+        assert!(first.to_vec().iter().all(|v| !v.is_nan()));
+        let later: Vec<_> = std::iter::from_fn(|| stream.next_instance()).collect();
+        let last = later.last().unwrap().to_vec();
+        assert!(!last[0].is_nan() && last[1].is_nan() && last[2].is_nan());
+        assert!(!last[3].is_nan());
+        assert!(
+            stream
+                .measurements()
+                .iter()
+                .any(|m| m.name.as_str() == "selected_features")
+        );
+
+        stream.restart().unwrap();
+        assert_eq!(stream.selector().updates(), 0);
+    }
+}
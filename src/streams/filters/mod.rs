@@ -0,0 +1,3 @@
+pub mod feature_selection;
+
+pub use feature_selection::{FeatureSelectionStream, FeatureSelector};
@@ -1,24 +1,29 @@
 use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
 use crate::core::instance_header::InstanceHeader;
-use crate::core::instances::{DenseInstance, Instance};
+use crate::core::instances::{Instance, InstancePool};
+use crate::error::RivuError;
 use crate::streams::generators::agrawal::function::AgrawalFunction;
 use crate::streams::generators::agrawal::rules::{RawAttrs, determine};
-use crate::streams::stream::Stream;
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use crate::streams::generators::{
+    GeneratorRng, RowGenerator, batch_from_rows, generator_position, seek_generator,
+};
+use crate::streams::stream::{Stream, StreamPosition};
+use rand::Rng;
 use std::collections::HashMap;
-use std::io::{Error, ErrorKind};
+use std::io::Error;
 use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct AgrawalGenerator {
     seed: u64,
-    rng: StdRng,
+    rng: GeneratorRng,
     function: AgrawalFunction,
     balance_classes: bool,
     next_class_should_be_zero: bool,
     perturb_fraction: f64,
     header: Arc<InstanceHeader>,
+    pool: InstancePool,
+    rows: Vec<f64>,
     max_instances: Option<usize>,
     produced: usize,
 }
@@ -32,19 +37,19 @@ impl AgrawalGenerator {
         seed: u64,
     ) -> Result<Self, Error> {
         if !(0.0..=1.0).contains(&perturb_fraction) {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "perturb_fraction must be in 0.0..=1.0",
-            ));
+            return Err(RivuError::config("perturb_fraction must be in 0.0..=1.0").into());
         }
+        let header = Arc::new(build_agrawal_header());
         Ok(Self {
             seed,
-            rng: StdRng::seed_from_u64(seed),
+            rng: GeneratorRng::new(seed, false),
             function,
             balance_classes,
             next_class_should_be_zero: false,
             perturb_fraction,
-            header: Arc::new(build_agrawal_header()),
+            pool: InstancePool::new(Arc::clone(&header)),
+            rows: Vec::new(),
+            header,
             max_instances,
             produced: 0,
         })
@@ -67,6 +72,12 @@ impl AgrawalGenerator {
         )
     }
 
+    /// Draws from the fast RNG; see `GeneratorRng` in `streams::generators`.
+    pub fn with_fast_rng(mut self) -> Self {
+        self.rng = GeneratorRng::new(self.seed, true);
+        self
+    }
+
     fn sample_raw_attributes<R: Rng + ?Sized>(rng: &mut R) -> RawAttrs {
         let salary = rng.random_range(20_000.0..150_000.0);
         let commission = if salary < 75_000.0 {
@@ -112,7 +123,7 @@ impl AgrawalGenerator {
             return;
         }
 
-        let mult = |rng: &mut StdRng, x: &mut f64| {
+        let mult = |rng: &mut GeneratorRng, x: &mut f64| {
             let sign = if rng.random::<bool>() { 1.0 } else { -1.0 };
             let factor = 1.0 + sign * self.perturb_fraction;
             *x *= factor;
@@ -123,7 +134,7 @@ impl AgrawalGenerator {
         mult(rng, &mut a.hvalue);
         mult(rng, &mut a.loan);
 
-        let perturb_i = |rng: &mut StdRng, v: &mut i32| {
+        let perturb_i = |rng: &mut GeneratorRng, v: &mut i32| {
             let fv = *v as f64;
             let sign = if rng.random::<bool>() { 1.0 } else { -1.0 };
             let factor = 1.0 + sign * self.perturb_fraction;
@@ -137,25 +148,8 @@ impl AgrawalGenerator {
         a.hyears = a.hyears.clamp(0, 60);
     }
 
-    fn determine_class(&self, a: &RawAttrs) -> i32 {
-        determine(self.function.as_u8(), a) as i32
-    }
-}
-
-impl Stream for AgrawalGenerator {
-    fn header(&self) -> &InstanceHeader {
-        &self.header
-    }
-
-    fn has_more_instances(&self) -> bool {
-        self.max_instances.map_or(true, |m| self.produced < m)
-    }
-
-    fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
-        if !self.has_more_instances() {
-            return None;
-        }
-
+    /// Generates one instance and appends its values to `out`.
+    fn push_row(&mut self, out: &mut Vec<f64>) {
         let mut group;
         let (mut attributes, mut ok);
 
@@ -180,30 +174,91 @@ impl Stream for AgrawalGenerator {
 
         self.maybe_perturb(&mut attributes);
 
-        let mut values = Vec::with_capacity(10);
-        values.push(attributes.salary);
-        values.push(attributes.commission);
-        values.push(attributes.age as f64);
-        values.push(attributes.elevel as f64);
-        values.push(attributes.car as f64);
-        values.push(attributes.zipcode as f64);
-        values.push(attributes.hvalue);
-        values.push(attributes.hyears as f64);
-        values.push(attributes.loan);
-        values.push(group as f64);
+        out.extend_from_slice(&[
+            attributes.salary,
+            attributes.commission,
+            attributes.age as f64,
+            attributes.elevel as f64,
+            // Cars are numbered from 1, their labels indexed from 0.
+            (attributes.car - 1) as f64,
+            attributes.zipcode as f64,
+            attributes.hvalue,
+            attributes.hyears as f64,
+            attributes.loan,
+            group as f64,
+        ]);
+        self.produced += 1;
+    }
+
+    fn determine_class(&self, a: &RawAttrs) -> i32 {
+        determine(self.function.as_u8(), a) as i32
+    }
+}
+
+impl RowGenerator for AgrawalGenerator {
+    fn fill_rows(&mut self, n: usize, values: &mut Vec<f64>) -> usize {
+        let n = self
+            .max_instances
+            .map_or(n, |max| n.min(max.saturating_sub(self.produced)));
+        values.reserve(n * 10);
+        for _ in 0..n {
+            self.push_row(values);
+        }
+        n
+    }
+}
 
-        let instance = DenseInstance::new(Arc::clone(&self.header), values, 1.0);
+impl Stream for AgrawalGenerator {
+    fn header(&self) -> &InstanceHeader {
+        &self.header
+    }
 
-        self.produced += 1;
-        Some(Box::new(instance))
+    fn has_more_instances(&self) -> bool {
+        self.max_instances.map_or(true, |m| self.produced < m)
+    }
+
+    fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
+        if !self.has_more_instances() {
+            return None;
+        }
+        let mut instance = self.pool.acquire();
+        self.push_row(&mut instance.values);
+        Some(instance)
+    }
+
+    fn next_batch(&mut self, n: usize) -> Vec<Box<dyn Instance>> {
+        let mut rows = std::mem::take(&mut self.rows);
+        rows.clear();
+        self.fill_rows(n, &mut rows);
+        let batch = batch_from_rows(&mut self.pool, &rows);
+        self.rows = rows;
+        batch
+    }
+
+    fn recycle(&mut self, instance: Box<dyn Instance>) {
+        self.pool.release(instance);
     }
 
     fn restart(&mut self) -> Result<(), Error> {
-        self.rng = StdRng::seed_from_u64(self.seed);
+        self.rng = GeneratorRng::new(self.seed, self.rng.is_fast());
         self.next_class_should_be_zero = false;
         self.produced = 0;
         Ok(())
     }
+
+    fn position(&self) -> Result<StreamPosition, Error> {
+        Ok(generator_position(
+            &self.rng,
+            self.produced,
+            self.next_class_should_be_zero,
+        ))
+    }
+
+    fn seek(&mut self, position: &StreamPosition) -> Result<(), Error> {
+        (self.rng, self.produced, self.next_class_should_be_zero) =
+            seek_generator(self.seed, &self.rng, position)?;
+        Ok(())
+    }
 }
 
 fn build_agrawal_header() -> InstanceHeader {
@@ -268,6 +323,7 @@ mod tests {
     use super::*;
     use crate::core::attributes::NominalAttribute;
     use crate::streams::stream::Stream;
+    use std::io::ErrorKind;
 
     #[test]
     fn new_rejects_invalid_function_id() {
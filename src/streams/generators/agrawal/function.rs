@@ -1,3 +1,4 @@
+use crate::error::RivuError;
 use std::io::Error;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -41,10 +42,7 @@ impl TryFrom<u8> for AgrawalFunction {
             9 => F9,
             10 => F10,
             _ => {
-                return Err(Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    "function must be 1..=10",
-                ));
+                return Err(RivuError::config("function must be 1..=10").into());
             }
         })
     }
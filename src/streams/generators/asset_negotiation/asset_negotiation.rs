@@ -1,11 +1,14 @@
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
-use std::io::{Error, ErrorKind};
+use crate::error::RivuError;
+use rand::Rng;
+use std::io::Error;
 use std::sync::Arc;
 
 use crate::core::instance_header::InstanceHeader;
-use crate::core::instances::{DenseInstance, Instance};
-use crate::streams::stream::Stream;
+use crate::core::instances::{Instance, InstancePool};
+use crate::streams::generators::{
+    GeneratorRng, RowGenerator, batch_from_rows, generator_position, seek_generator,
+};
+use crate::streams::stream::{Stream, StreamPosition};
 
 use super::AssetRule;
 use super::domain::{AMOUNT, COLOR, DELAY, PAYMENT, PRICE, build_header};
@@ -33,7 +36,7 @@ pub struct AssetNegotiationGenerator {
     /// RNG seed used to (re)initialize the pseudo-random sequence.
     seed: u64,
     /// Pseudo-random generator; reseeded by [Stream::restart].
-    rng: StdRng,
+    rng: GeneratorRng,
     /// Classification rule to use (R1...R5).
     rule: AssetRule,
     /// Probability ∈ [0, 1] of flipping the class label.
@@ -44,6 +47,10 @@ pub struct AssetNegotiationGenerator {
     next_class_should_be_zero: bool,
     /// Stream schema: nominal domains for features and class (class index = 5).
     header: Arc<InstanceHeader>,
+    /// Recycled instances handed out again by `next_instance`.
+    pool: InstancePool,
+    /// Row buffer `next_batch` generates into, kept between batches.
+    rows: Vec<f64>,
     /// Number of examples produced since last restart.
     produced: usize,
 }
@@ -63,20 +70,20 @@ impl AssetNegotiationGenerator {
         seed: u64,
     ) -> Result<Self, Error> {
         if !(0.0..=1.0).contains(&noise_percentage) {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "noise_percentage must be in 0.0..=1.0",
-            ));
+            return Err(RivuError::config("noise_percentage must be in 0.0..=1.0").into());
         }
 
+        let header = Arc::new(build_header());
         Ok(Self {
             seed,
-            rng: StdRng::seed_from_u64(seed),
+            rng: GeneratorRng::new(seed, false),
             rule,
             noise_percentage,
             balance_classes: balance,
             next_class_should_be_zero: false,
-            header: Arc::new(build_header()),
+            pool: InstancePool::new(Arc::clone(&header)),
+            rows: Vec::new(),
+            header,
             produced: 0,
         })
     }
@@ -93,6 +100,12 @@ impl AssetNegotiationGenerator {
         Self::new(rule, balance, noise_percentage, seed)
     }
 
+    /// Draws from the fast RNG; see `GeneratorRng` in `streams::generators`.
+    pub fn with_fast_rng(mut self) -> Self {
+        self.rng = GeneratorRng::new(self.seed, true);
+        self
+    }
+
     /// Bernoulli label noise: flips cls with probability noise_percentage.
     /// Returns the (possibly flipped) class index {0,1}.
     #[inline]
@@ -117,18 +130,46 @@ impl AssetNegotiationGenerator {
         ]
     }
 
-    /// Packs domain indices and the class into a Vec<f64> in header order.
-    /// This is the storage layout used by DenseInstance.
-    #[inline]
-    fn build_instance_vec(vals: &[usize; 5], cls: usize) -> Vec<f64> {
-        vec![
-            vals[0] as f64,
-            vals[1] as f64,
-            vals[2] as f64,
-            vals[3] as f64,
-            vals[4] as f64,
-            cls as f64,
-        ]
+    /// Generates one instance and appends its domain indices and class to
+    /// `out` in header order, the storage layout used by `DenseInstance`.
+    fn push_row(&mut self, out: &mut Vec<f64>) {
+        loop {
+            let mut vals = self.sample_indices();
+            let class0_or1 = evaluate_rule_idx(self.rule, &vals);
+
+            let want_one = !self.next_class_should_be_zero;
+
+            let out_cls = if !self.balance_classes {
+                self.add_noise(class0_or1)
+            } else if class0_or1 == 0 && want_one {
+                make_true_sample_idx(self.rule, &mut self.rng, &mut vals);
+                self.next_class_should_be_zero = !self.next_class_should_be_zero;
+                self.add_noise(1)
+            } else if class0_or1 == 0 && !want_one {
+                self.next_class_should_be_zero = !self.next_class_should_be_zero;
+                self.add_noise(0)
+            } else if class0_or1 == 1 && want_one {
+                self.next_class_should_be_zero = !self.next_class_should_be_zero;
+                self.add_noise(1)
+            } else {
+                continue;
+            };
+
+            out.extend(vals.iter().map(|&v| v as f64));
+            out.push(out_cls as f64);
+            self.produced += 1;
+            return;
+        }
+    }
+}
+
+impl RowGenerator for AssetNegotiationGenerator {
+    fn fill_rows(&mut self, n: usize, values: &mut Vec<f64>) -> usize {
+        values.reserve(n * 6);
+        for _ in 0..n {
+            self.push_row(values);
+        }
+        n
     }
 }
 
@@ -154,36 +195,22 @@ impl Stream for AssetNegotiationGenerator {
     /// 4) flip the label with probability `noise_percentage`;
     /// 5) return an `Instance` (weight = 1.0).
     fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
-        loop {
-            let mut vals = self.sample_indices();
-            let class0_or1 = evaluate_rule_idx(self.rule, &vals);
-
-            let want_one = !self.next_class_should_be_zero;
+        let mut inst = self.pool.acquire();
+        self.push_row(&mut inst.values);
+        Some(inst)
+    }
 
-            let out_cls = if !self.balance_classes {
-                self.add_noise(class0_or1)
-            } else if class0_or1 == 0 && want_one {
-                make_true_sample_idx(self.rule, &mut self.rng, &mut vals);
-                self.next_class_should_be_zero = !self.next_class_should_be_zero;
-                self.add_noise(1)
-            } else if class0_or1 == 0 && !want_one {
-                self.next_class_should_be_zero = !self.next_class_should_be_zero;
-                self.add_noise(0)
-            } else if class0_or1 == 1 && want_one {
-                self.next_class_should_be_zero = !self.next_class_should_be_zero;
-                self.add_noise(1)
-            } else {
-                continue;
-            };
+    fn next_batch(&mut self, n: usize) -> Vec<Box<dyn Instance>> {
+        let mut rows = std::mem::take(&mut self.rows);
+        rows.clear();
+        self.fill_rows(n, &mut rows);
+        let batch = batch_from_rows(&mut self.pool, &rows);
+        self.rows = rows;
+        batch
+    }
 
-            let inst = DenseInstance::new(
-                Arc::clone(&self.header),
-                Self::build_instance_vec(&vals, out_cls),
-                1.0,
-            );
-            self.produced += 1;
-            return Some(Box::new(inst));
-        }
+    fn recycle(&mut self, instance: Box<dyn Instance>) {
+        self.pool.release(instance);
     }
 
     /// Resets generator state: `RNG` is reseeded with seed, class-alternation
@@ -191,11 +218,25 @@ impl Stream for AssetNegotiationGenerator {
     /// sequence of outputs matches a fresh generator constructed with the same
     /// parameters.
     fn restart(&mut self) -> Result<(), Error> {
-        self.rng = StdRng::seed_from_u64(self.seed);
+        self.rng = GeneratorRng::new(self.seed, self.rng.is_fast());
         self.next_class_should_be_zero = false;
         self.produced = 0;
         Ok(())
     }
+
+    fn position(&self) -> Result<StreamPosition, Error> {
+        Ok(generator_position(
+            &self.rng,
+            self.produced,
+            self.next_class_should_be_zero,
+        ))
+    }
+
+    fn seek(&mut self, position: &StreamPosition) -> Result<(), Error> {
+        (self.rng, self.produced, self.next_class_should_be_zero) =
+            seek_generator(self.seed, &self.rng, position)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -30,12 +30,32 @@ pub const AMOUNT: [&str; 8] = [
 pub const DELAY: [&str; 5] = ["veryLow", "low", "normal", "high", "veryHigh"];
 pub const CLASS: [&str; 2] = ["interested", "notInterested"];
 
-#[inline]
-pub fn idx(domain: &[&str], label: &str) -> usize {
-    domain
-        .iter()
-        .position(|&s| s == label)
-        .expect("label not in domain")
+/// Index of `label` in `domain`. A `const fn`, so the rules resolve the
+/// labels they test once, at compile time, rather than per instance.
+pub const fn idx(domain: &[&str], label: &str) -> usize {
+    let mut i = 0;
+    while i < domain.len() {
+        if same_label(domain[i], label) {
+            return i;
+        }
+        i += 1;
+    }
+    panic!("label not in domain")
+}
+
+const fn same_label(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
 }
 
 /// Builds the fixed `InstanceHeader` for the Asset Negotiation stream:
@@ -1,4 +1,5 @@
-use std::io::{Error, ErrorKind};
+use crate::error::RivuError;
+use std::io::Error;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
@@ -33,10 +34,7 @@ impl TryFrom<u8> for AssetRule {
             3 => Ok(R3),
             4 => Ok(R4),
             5 => Ok(R5),
-            _ => Err(Error::new(
-                ErrorKind::InvalidInput,
-                "function must be 1..=5",
-            )),
+            _ => Err(RivuError::config("function must be 1..=5").into()),
         }
     }
 }
@@ -2,6 +2,27 @@ use super::AssetRule;
 use super::domain::{AMOUNT, COLOR, DELAY, PAYMENT, PRICE, idx};
 use rand::Rng;
 
+const C_BLACK: usize = idx(&COLOR, "black");
+const C_BLUE: usize = idx(&COLOR, "blue");
+const C_BROWN: usize = idx(&COLOR, "brown");
+const C_RED: usize = idx(&COLOR, "red");
+const C_CYAN: usize = idx(&COLOR, "cyan");
+const C_MAGENTA: usize = idx(&COLOR, "magenta");
+const P_NORM: usize = idx(&PRICE, "normal");
+const P_VLOW: usize = idx(&PRICE, "veryLow");
+const P_LOW: usize = idx(&PRICE, "low");
+const P_HIGH: usize = idx(&PRICE, "high");
+const A_LOW: usize = idx(&AMOUNT, "low");
+const A_HIGH: usize = idx(&AMOUNT, "high");
+const A_VHIGH: usize = idx(&AMOUNT, "veryHigh");
+const PAY_0: usize = idx(&PAYMENT, "0");
+const PAY_30: usize = idx(&PAYMENT, "30");
+const PAY_60: usize = idx(&PAYMENT, "60");
+const PAY_90: usize = idx(&PAYMENT, "90");
+const D_NORM: usize = idx(&DELAY, "normal");
+const D_HIGH: usize = idx(&DELAY, "high");
+const D_VLOW: usize = idx(&DELAY, "veryLow");
+
 /// Evaluates the target concept for a given rule and a 5-tuple of
 /// domain indices (color, price, payment, amount, delay).
 ///
@@ -18,64 +39,22 @@ use rand::Rng;
 pub fn evaluate_rule_idx(rule: AssetRule, vals: &[usize; 5]) -> usize {
     let (c, p, pay, a, d) = (vals[0], vals[1], vals[2], vals[3], vals[4]);
 
-    let (
-        c_black,
-        c_brown,
-        c_red,
-        c_cyan,
-        c_magenta,
-        p_norm,
-        p_vlow,
-        p_low,
-        p_high,
-        a_low,
-        a_high,
-        a_vhigh,
-        pay_0,
-        pay_30,
-        pay_60,
-        pay_90,
-        d_norm,
-        d_high,
-        d_vlow,
-    ) = (
-        idx(&COLOR, "black"),
-        idx(&COLOR, "brown"),
-        idx(&COLOR, "red"),
-        idx(&COLOR, "cyan"),
-        idx(&COLOR, "magenta"),
-        idx(&PRICE, "normal"),
-        idx(&PRICE, "veryLow"),
-        idx(&PRICE, "low"),
-        idx(&PRICE, "high"),
-        idx(&AMOUNT, "low"),
-        idx(&AMOUNT, "high"),
-        idx(&AMOUNT, "veryHigh"),
-        idx(&PAYMENT, "0"),
-        idx(&PAYMENT, "30"),
-        idx(&PAYMENT, "60"),
-        idx(&PAYMENT, "90"),
-        idx(&DELAY, "normal"),
-        idx(&DELAY, "high"),
-        idx(&DELAY, "veryLow"),
-    );
-
     let interested = match rule {
         AssetRule::R1 => {
-            (p == p_norm && a == a_high) || (c == c_brown && p == p_vlow && d == d_high)
+            (p == P_NORM && a == A_HIGH) || (c == C_BROWN && p == P_VLOW && d == D_HIGH)
         }
-        AssetRule::R2 => p == p_high && a == a_vhigh && d == d_high,
+        AssetRule::R2 => p == P_HIGH && a == A_VHIGH && d == D_HIGH,
         AssetRule::R3 => {
-            (p == p_vlow && pay == pay_0 && a == a_high)
-                || (c == c_red && p == p_low && pay == pay_30)
+            (p == P_VLOW && pay == PAY_0 && a == A_HIGH)
+                || (c == C_RED && p == P_LOW && pay == PAY_30)
         }
         AssetRule::R4 => {
-            (c == c_black && pay == pay_90 && d == d_vlow)
-                || (c == c_magenta && p == p_high && d == d_vlow)
+            (c == C_BLACK && pay == PAY_90 && d == D_VLOW)
+                || (c == C_MAGENTA && p == P_HIGH && d == D_VLOW)
         }
         AssetRule::R5 => {
-            (c == c_cyan && a == a_low && d == d_norm)
-                || (/* variante blue */a == a_low && d == d_norm && pay == pay_60 && c == idx(&COLOR, "blue"))
+            (c == C_CYAN && a == A_LOW && d == D_NORM)
+                || (/* variante blue */a == A_LOW && d == D_NORM && pay == PAY_60 && c == C_BLUE)
         }
     };
 
@@ -89,98 +68,54 @@ pub fn evaluate_rule_idx(rule: AssetRule, vals: &[usize; 5]) -> usize {
 ///
 /// The exact patterns match the ones documented in [evaluate_rule_idx].
 pub fn make_true_sample_idx<R: Rng + ?Sized>(rule: AssetRule, rng: &mut R, vals: &mut [usize; 5]) {
-    let (
-        c_black,
-        c_brown,
-        c_red,
-        c_cyan,
-        c_blue,
-        c_magenta,
-        p_norm,
-        p_vlow,
-        p_low,
-        p_high,
-        a_low,
-        a_high,
-        a_vhigh,
-        pay_0,
-        pay_30,
-        pay_60,
-        pay_90,
-        d_norm,
-        d_vlow,
-        d_high,
-    ) = (
-        idx(&COLOR, "black"),
-        idx(&COLOR, "brown"),
-        idx(&COLOR, "red"),
-        idx(&COLOR, "cyan"),
-        idx(&COLOR, "blue"),
-        idx(&COLOR, "magenta"),
-        idx(&PRICE, "normal"),
-        idx(&PRICE, "veryLow"),
-        idx(&PRICE, "low"),
-        idx(&PRICE, "high"),
-        idx(&AMOUNT, "low"),
-        idx(&AMOUNT, "high"),
-        idx(&AMOUNT, "veryHigh"),
-        idx(&PAYMENT, "0"),
-        idx(&PAYMENT, "30"),
-        idx(&PAYMENT, "60"),
-        idx(&PAYMENT, "90"),
-        idx(&DELAY, "normal"),
-        idx(&DELAY, "veryLow"),
-        idx(&DELAY, "high"),
-    );
-
     match rule {
         AssetRule::R1 => {
             if rng.random::<bool>() {
-                vals[1] = p_norm;
-                vals[3] = a_high;
+                vals[1] = P_NORM;
+                vals[3] = A_HIGH;
             } else {
-                vals[0] = c_brown;
-                vals[1] = p_vlow;
-                vals[4] = d_high;
+                vals[0] = C_BROWN;
+                vals[1] = P_VLOW;
+                vals[4] = D_HIGH;
             }
         }
         AssetRule::R2 => {
-            vals[1] = p_high;
-            vals[3] = a_vhigh;
-            vals[4] = d_high;
+            vals[1] = P_HIGH;
+            vals[3] = A_VHIGH;
+            vals[4] = D_HIGH;
         }
         AssetRule::R3 => {
             if rng.random::<bool>() {
-                vals[1] = p_vlow;
-                vals[2] = pay_0;
-                vals[3] = a_high;
+                vals[1] = P_VLOW;
+                vals[2] = PAY_0;
+                vals[3] = A_HIGH;
             } else {
-                vals[0] = c_red;
-                vals[1] = p_low;
-                vals[2] = pay_30;
+                vals[0] = C_RED;
+                vals[1] = P_LOW;
+                vals[2] = PAY_30;
             }
         }
         AssetRule::R4 => {
             if rng.random::<bool>() {
-                vals[0] = c_black;
-                vals[2] = pay_90;
-                vals[4] = d_vlow;
+                vals[0] = C_BLACK;
+                vals[2] = PAY_90;
+                vals[4] = D_VLOW;
             } else {
-                vals[0] = c_magenta;
-                vals[1] = p_high;
-                vals[4] = d_vlow;
+                vals[0] = C_MAGENTA;
+                vals[1] = P_HIGH;
+                vals[4] = D_VLOW;
             }
         }
         AssetRule::R5 => {
             if rng.random::<bool>() {
-                vals[0] = c_blue;
-                vals[2] = pay_60;
-                vals[3] = a_low;
-                vals[4] = d_norm;
+                vals[0] = C_BLUE;
+                vals[2] = PAY_60;
+                vals[3] = A_LOW;
+                vals[4] = D_NORM;
             } else {
-                vals[0] = c_cyan;
-                vals[3] = a_low;
-                vals[4] = d_norm;
+                vals[0] = C_CYAN;
+                vals[3] = A_LOW;
+                vals[4] = D_NORM;
             }
         }
     }
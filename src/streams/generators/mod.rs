@@ -5,3 +5,263 @@ mod sea;
 pub use agrawal::{agrawal_generator::AgrawalGenerator, function::AgrawalFunction};
 pub use asset_negotiation::{AssetNegotiationGenerator, AssetRule};
 pub use sea::{SeaFunction, SeaGenerator};
+
+use crate::core::instances::{Instance, InstancePool};
+use crate::error::RivuError;
+use crate::streams::{Stream, StreamPosition};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::{ChaCha8Rng, ChaCha12Rng};
+use std::io::Error;
+
+/// RNG of the generators, a ChaCha generator because it can report and
+/// restore how far it has advanced.
+///
+/// By default it is ChaCha12, the generator behind `StdRng`. Generators
+/// built `with_fast_rng` use ChaCha8 instead, which is noticeably faster,
+/// for benchmark runs where the stream itself should not be the
+/// bottleneck. A fast stream is as reproducible from its seed as the
+/// default one, but a different sequence.
+#[derive(Debug, Clone)]
+pub(crate) enum GeneratorRng {
+    Standard(ChaCha12Rng),
+    Fast(ChaCha8Rng),
+}
+
+impl GeneratorRng {
+    pub(crate) fn new(seed: u64, fast: bool) -> Self {
+        if fast {
+            Self::Fast(ChaCha8Rng::seed_from_u64(seed))
+        } else {
+            Self::Standard(ChaCha12Rng::seed_from_u64(seed))
+        }
+    }
+
+    pub(crate) fn is_fast(&self) -> bool {
+        matches!(self, Self::Fast(_))
+    }
+
+    fn get_word_pos(&self) -> u128 {
+        match self {
+            Self::Standard(rng) => rng.get_word_pos(),
+            Self::Fast(rng) => rng.get_word_pos(),
+        }
+    }
+
+    fn set_word_pos(&mut self, word_pos: u128) {
+        match self {
+            Self::Standard(rng) => rng.set_word_pos(word_pos),
+            Self::Fast(rng) => rng.set_word_pos(word_pos),
+        }
+    }
+}
+
+impl RngCore for GeneratorRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Standard(rng) => rng.next_u32(),
+            Self::Fast(rng) => rng.next_u32(),
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Standard(rng) => rng.next_u64(),
+            Self::Fast(rng) => rng.next_u64(),
+        }
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        match self {
+            Self::Standard(rng) => rng.fill_bytes(dst),
+            Self::Fast(rng) => rng.fill_bytes(dst),
+        }
+    }
+}
+
+/// Generators that can write their rows straight into a caller's buffer.
+pub trait RowGenerator: Stream {
+    /// Appends up to `n` rows to `values`, each holding every attribute of
+    /// the header including the class, and returns how many were written.
+    ///
+    /// Nothing is allocated once `values` has room for the rows, so a buffer
+    /// reused across calls generates data without touching the allocator.
+    fn fill_rows(&mut self, n: usize, values: &mut Vec<f64>) -> usize;
+}
+
+/// Turns rows written by [`RowGenerator::fill_rows`] into a batch of unit
+/// weight instances taken from `pool`.
+pub(crate) fn batch_from_rows(pool: &mut InstancePool, rows: &[f64]) -> Vec<Box<dyn Instance>> {
+    let width = pool.header().number_of_attributes();
+    rows.chunks_exact(width)
+        .map(|row| {
+            let mut instance = pool.acquire();
+            instance.values.extend_from_slice(row);
+            instance as Box<dyn Instance>
+        })
+        .collect()
+}
+
+/// Position of a generator that has produced `produced` instances.
+pub(crate) fn generator_position(
+    rng: &GeneratorRng,
+    produced: usize,
+    next_class_zero: bool,
+) -> StreamPosition {
+    StreamPosition::Generator {
+        produced: produced as u64,
+        word_pos: rng.get_word_pos(),
+        next_class_zero,
+    }
+}
+
+/// RNG, instance count, and balancing toggle of a generator seeded with
+/// `seed` once moved to `position`, keeping the RNG kind of `rng`.
+pub(crate) fn seek_generator(
+    seed: u64,
+    rng: &GeneratorRng,
+    position: &StreamPosition,
+) -> Result<(GeneratorRng, usize, bool), Error> {
+    let &StreamPosition::Generator {
+        produced,
+        word_pos,
+        next_class_zero,
+    } = position
+    else {
+        return Err(RivuError::config("a generator can only seek to a generator position").into());
+    };
+    let mut rng = GeneratorRng::new(seed, rng.is_fast());
+    rng.set_word_pos(word_pos);
+    let produced = usize::try_from(produced).unwrap_or(usize::MAX);
+    Ok((rng, produced, next_class_zero))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::stubs::VecStream;
+
+    fn rows<S: Stream + ?Sized>(stream: &mut S, batches: &[usize]) -> Vec<Vec<f64>> {
+        batches
+            .iter()
+            .flat_map(|&n| stream.next_batch(n))
+            .map(|i| i.to_vec())
+            .collect()
+    }
+
+    fn one_by_one<S: Stream + ?Sized>(stream: &mut S, n: usize) -> Vec<Vec<f64>> {
+        (0..n)
+            .map_while(|_| stream.next_instance())
+            .map(|i| i.to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn batches_follow_the_instance_sequence_and_limits() {
+        let sea = || SeaGenerator::new(SeaFunction::F2, true, 10, Some(25), 7).unwrap();
+        let batched = rows(&mut sea(), &[10, 10, 10, 10]);
+        assert_eq!(batched.len(), 25);
+        assert_eq!(batched, one_by_one(&mut sea(), 30));
+
+        let agrawal = || AgrawalGenerator::new(AgrawalFunction::F3, true, 0.05, None, 3).unwrap();
+        assert_eq!(
+            rows(&mut agrawal(), &[4, 0, 9]),
+            one_by_one(&mut agrawal(), 13)
+        );
+
+        let asset = || AssetNegotiationGenerator::new(AssetRule::R2, true, 0.1, 5).unwrap();
+        let mut buffer = Vec::new();
+        assert_eq!(asset().fill_rows(8, &mut buffer), 8);
+        let expected: Vec<f64> = one_by_one(&mut asset(), 8).concat();
+        assert_eq!(buffer, expected);
+
+        let mut finite = VecStream::new(vec![0, 1, 1]);
+        assert_eq!(rows(&mut finite, &[2, 2]).len(), 3);
+        assert!(finite.next_batch(5).is_empty());
+    }
+
+    #[test]
+    fn recycled_instances_are_reused_without_changing_the_sequence() {
+        let sea = || SeaGenerator::new(SeaFunction::F1, false, 0, None, 11).unwrap();
+        let expected = one_by_one(&mut sea(), 6);
+
+        let mut g = sea();
+        let first = g.next_instance().unwrap();
+        let mut seen = vec![first.to_vec()];
+        let buffer = first.into_dense().unwrap();
+        let ptr = buffer.values.as_ptr();
+        g.recycle(buffer);
+
+        let second = g.next_instance().unwrap().into_dense().unwrap();
+        assert_eq!(second.values.as_ptr(), ptr);
+        seen.push(second.values.clone());
+        g.recycle(second);
+        for instance in g.next_batch(4) {
+            seen.push(instance.to_vec());
+            g.recycle(instance);
+        }
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn the_fast_rng_gives_its_own_reproducible_sequence() {
+        let sea = || SeaGenerator::new(SeaFunction::F3, true, 10, None, 9).unwrap();
+        let mut fast = sea().with_fast_rng();
+        let first = one_by_one(&mut fast, 20);
+        assert_ne!(first, one_by_one(&mut sea(), 20));
+        assert_eq!(first, one_by_one(&mut sea().with_fast_rng(), 20));
+
+        fast.restart().unwrap();
+        assert_eq!(rows(&mut fast, &[7, 13]), first);
+
+        let asset = || AssetNegotiationGenerator::new(AssetRule::R5, true, 0.1, 4).unwrap();
+        assert_eq!(
+            one_by_one(&mut asset().with_fast_rng(), 10),
+            one_by_one(&mut asset().with_fast_rng(), 10)
+        );
+    }
+
+    fn resumes_where_it_left_off<S: Stream>(mut g: S) {
+        one_by_one(&mut g, 7);
+        let position = g.position().unwrap();
+        let ahead = one_by_one(&mut g, 9);
+
+        g.restart().unwrap();
+        g.seek(&position).unwrap();
+        assert_eq!(one_by_one(&mut g, 9), ahead);
+
+        let json = serde_json::to_string(&position).unwrap();
+        let back: StreamPosition = serde_json::from_str(&json).unwrap();
+        g.seek(&back).unwrap();
+        assert_eq!(g.position().unwrap(), position);
+        assert_eq!(one_by_one(&mut g, 9), ahead);
+    }
+
+    #[test]
+    fn seeking_to_a_saved_position_replays_the_same_instances() {
+        resumes_where_it_left_off(
+            SeaGenerator::new(SeaFunction::F2, true, 10, Some(12), 7).unwrap(),
+        );
+        resumes_where_it_left_off(
+            AgrawalGenerator::new(AgrawalFunction::F4, true, 0.05, None, 3).unwrap(),
+        );
+        resumes_where_it_left_off(
+            AssetNegotiationGenerator::new(AssetRule::R3, true, 0.1, 5).unwrap(),
+        );
+        resumes_where_it_left_off(
+            AgrawalGenerator::new(AgrawalFunction::F2, false, 0.05, None, 3)
+                .unwrap()
+                .with_fast_rng(),
+        );
+
+        let mut sea = SeaGenerator::new(SeaFunction::F1, false, 0, None, 1).unwrap();
+        let file = StreamPosition::File {
+            record: 0,
+            offset: 0,
+        };
+        let err = sea.seek(&file).unwrap_err();
+        assert!(matches!(RivuError::of(&err), Some(RivuError::Config(_))));
+    }
+}
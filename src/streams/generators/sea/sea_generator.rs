@@ -1,25 +1,30 @@
+use crate::error::RivuError;
 use std::collections::HashMap;
-use std::io::{Error, ErrorKind};
+use std::io::Error;
 use std::sync::Arc;
 
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use rand::Rng;
 
 use crate::core::attributes::{AttributeRef, NominalAttribute, NumericAttribute};
 use crate::core::instance_header::InstanceHeader;
-use crate::core::instances::{DenseInstance, Instance};
+use crate::core::instances::{Instance, InstancePool};
 use crate::streams::generators::sea::SeaFunction;
-use crate::streams::stream::Stream;
+use crate::streams::generators::{
+    GeneratorRng, RowGenerator, batch_from_rows, generator_position, seek_generator,
+};
+use crate::streams::stream::{Stream, StreamPosition};
 
 #[derive(Debug)]
 pub struct SeaGenerator {
     seed: u64,
-    rng: StdRng,
+    rng: GeneratorRng,
     threshold: f64,
     balance_classes: bool,
     next_class_should_be_zero: bool,
     noise_percentage: u32,
     header: Arc<InstanceHeader>,
+    pool: InstancePool,
+    rows: Vec<f64>,
     concept_instances_number: Option<usize>,
     produced: usize,
 }
@@ -33,10 +38,7 @@ impl SeaGenerator {
         seed: u64,
     ) -> Result<Self, Error> {
         if noise_percentage > 100 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Noise percentage must be in [0, 100]",
-            ));
+            return Err(RivuError::config("Noise percentage must be in [0, 100]").into());
         }
 
         let mut map = HashMap::new();
@@ -56,17 +58,25 @@ impl SeaGenerator {
 
         Ok(Self {
             seed,
-            rng: StdRng::seed_from_u64(seed),
+            rng: GeneratorRng::new(seed, false),
             threshold: function.threshold(),
             balance_classes: balance,
             next_class_should_be_zero: false,
             noise_percentage,
+            pool: InstancePool::new(Arc::clone(&header)),
+            rows: Vec::new(),
             header,
             concept_instances_number,
             produced: 0,
         })
     }
 
+    /// Draws from the fast RNG; see `GeneratorRng` in `streams::generators`.
+    pub fn with_fast_rng(mut self) -> Self {
+        self.rng = GeneratorRng::new(self.seed, true);
+        self
+    }
+
     #[inline]
     fn gen_attr(&mut self) -> f64 {
         self.rng.random_range(0.0..10.0)
@@ -77,6 +87,31 @@ impl SeaGenerator {
         if a1 + a2 <= self.threshold { 0 } else { 1 }
     }
 
+    /// Generates one instance and appends its values to `out`.
+    fn push_row(&mut self, out: &mut Vec<f64>) {
+        let (a1, a2, a3, mut cls) = loop {
+            let a1 = self.gen_attr();
+            let a2 = self.gen_attr();
+            let a3 = self.gen_attr();
+
+            let g = self.determine_class(a1, a2, a3);
+
+            if !self.balance_classes {
+                break (a1, a2, a3, g);
+            } else {
+                let want_zero = self.next_class_should_be_zero;
+                if (want_zero && g == 0) || (!want_zero && g == 1) {
+                    self.next_class_should_be_zero = !self.next_class_should_be_zero;
+                    break (a1, a2, a3, g);
+                }
+            }
+        };
+
+        cls = self.maybe_flip_with_noise(cls);
+        out.extend_from_slice(&[a1, a2, a3, cls as f64]);
+        self.produced += 1;
+    }
+
     #[inline]
     fn maybe_flip_with_noise(&mut self, cls: u8) -> u8 {
         let roll: u32 = self.rng.random_range(1..=100);
@@ -88,6 +123,19 @@ impl SeaGenerator {
     }
 }
 
+impl RowGenerator for SeaGenerator {
+    fn fill_rows(&mut self, n: usize, values: &mut Vec<f64>) -> usize {
+        let n = self
+            .concept_instances_number
+            .map_or(n, |max| n.min(max.saturating_sub(self.produced)));
+        values.reserve(n * 4);
+        for _ in 0..n {
+            self.push_row(values);
+        }
+        n
+    }
+}
+
 impl Stream for SeaGenerator {
     fn header(&self) -> &InstanceHeader {
         &self.header
@@ -102,38 +150,44 @@ impl Stream for SeaGenerator {
         if !self.has_more_instances() {
             return None;
         }
+        let mut inst = self.pool.acquire();
+        self.push_row(&mut inst.values);
+        Some(inst)
+    }
 
-        let (a1, a2, a3, mut cls) = loop {
-            let a1 = self.gen_attr();
-            let a2 = self.gen_attr();
-            let a3 = self.gen_attr();
-
-            let g = self.determine_class(a1, a2, a3);
-
-            if !self.balance_classes {
-                break (a1, a2, a3, g);
-            } else {
-                let want_zero = self.next_class_should_be_zero;
-                if (want_zero && g == 0) || (!want_zero && g == 1) {
-                    self.next_class_should_be_zero = !self.next_class_should_be_zero;
-                    break (a1, a2, a3, g);
-                }
-            }
-        };
-
-        cls = self.maybe_flip_with_noise(cls);
+    fn next_batch(&mut self, n: usize) -> Vec<Box<dyn Instance>> {
+        let mut rows = std::mem::take(&mut self.rows);
+        rows.clear();
+        self.fill_rows(n, &mut rows);
+        let batch = batch_from_rows(&mut self.pool, &rows);
+        self.rows = rows;
+        batch
+    }
 
-        let inst = DenseInstance::new(Arc::clone(&self.header), vec![a1, a2, a3, cls as f64], 1.0);
-        self.produced += 1;
-        Some(Box::new(inst))
+    fn recycle(&mut self, instance: Box<dyn Instance>) {
+        self.pool.release(instance);
     }
 
     fn restart(&mut self) -> Result<(), Error> {
-        self.rng = StdRng::seed_from_u64(self.seed);
+        self.rng = GeneratorRng::new(self.seed, self.rng.is_fast());
         self.produced = 0;
         self.next_class_should_be_zero = false;
         Ok(())
     }
+
+    fn position(&self) -> Result<StreamPosition, Error> {
+        Ok(generator_position(
+            &self.rng,
+            self.produced,
+            self.next_class_should_be_zero,
+        ))
+    }
+
+    fn seek(&mut self, position: &StreamPosition) -> Result<(), Error> {
+        (self.rng, self.produced, self.next_class_should_be_zero) =
+            seek_generator(self.seed, &self.rng, position)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
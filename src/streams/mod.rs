@@ -1,5 +1,17 @@
 pub mod arff;
+pub mod concept_drift;
+pub mod csv;
+pub mod datasets;
+pub mod filters;
 pub mod generators;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod sinks;
 pub mod stream;
+pub mod watch;
 
-pub use stream::Stream;
+pub use concept_drift::ConceptDriftStream;
+pub use datasets::Dataset;
+pub use sinks::{StreamSink, create_sink};
+pub use stream::{DriftMarker, Stream, StreamPosition};
+pub use watch::WatchOptions;
@@ -0,0 +1,3 @@
+pub mod parquet_file_stream;
+
+pub use parquet_file_stream::ParquetFileStream;
@@ -0,0 +1,119 @@
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::streams::sinks::{StreamSink, format_cell};
+use crate::utils::file_parsing::quote_if_needed;
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+
+/// Writes instances as an ARFF file that
+/// [`ArffFileStream`](crate::streams::arff::ArffFileStream) can read back.
+///
+/// Names and labels are quoted when needed and missing values are written
+/// as `?`.
+pub struct ArffSink {
+    writer: BufWriter<Box<dyn Write + Send>>,
+    header: InstanceHeader,
+}
+
+impl ArffSink {
+    pub fn create<P: AsRef<Path>>(path: P, header: &InstanceHeader) -> Result<Self, Error> {
+        Self::from_writer(Box::new(File::create(path)?), header)
+    }
+
+    /// Writes to an arbitrary writer; the header section is emitted right
+    /// away.
+    pub fn from_writer(
+        writer: Box<dyn Write + Send>,
+        header: &InstanceHeader,
+    ) -> Result<Self, Error> {
+        let mut writer = BufWriter::new(writer);
+        writeln!(
+            writer,
+            "@relation {}",
+            quote_if_needed(header.relation_name())
+        )?;
+        writeln!(writer)?;
+        for attribute in &header.attributes {
+            let name = quote_if_needed(&attribute.name());
+            match attribute.as_any().downcast_ref::<NominalAttribute>() {
+                Some(nominal) => {
+                    let labels: Vec<String> =
+                        nominal.values.iter().map(|v| quote_if_needed(v)).collect();
+                    let ordinal = if nominal.is_ordinal() { " ordinal" } else { "" };
+                    writeln!(
+                        writer,
+                        "@attribute {name} {{{}}}{ordinal}",
+                        labels.join(",")
+                    )?;
+                }
+                None => writeln!(writer, "@attribute {name} numeric")?,
+            }
+        }
+        writeln!(writer)?;
+        writeln!(writer, "@data")?;
+
+        Ok(Self {
+            writer,
+            header: header.clone(),
+        })
+    }
+}
+
+impl StreamSink for ArffSink {
+    fn write_instance(&mut self, instance: &dyn Instance) -> Result<(), Error> {
+        let cells: Vec<String> = instance
+            .to_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| format_cell(&self.header, i, v))
+            .collect();
+        writeln!(self.writer, "{}", cells.join(","))
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streams::Stream;
+    use crate::streams::arff::ArffFileStream;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn round_trips_through_arff_file_stream() {
+        let mut src = NamedTempFile::with_suffix(".arff").unwrap();
+        src.write_all(
+            b"@relation 'weather data'\n\
+              @attribute outlook {sunny,'over cast',rainy}\n\
+              @attribute temp numeric\n\
+              @attribute play {yes,no}\n\
+              @data\n\
+              sunny,85,no\n\
+              'over cast',?,yes\n",
+        )
+        .unwrap();
+        src.flush().unwrap();
+        let mut input = ArffFileStream::new(src.path().to_path_buf(), 2).unwrap();
+
+        let out = NamedTempFile::with_suffix(".arff").unwrap();
+        let mut sink = ArffSink::create(out.path(), input.header()).unwrap();
+        while let Some(inst) = input.next_instance() {
+            sink.write_instance(inst.as_ref()).unwrap();
+        }
+        sink.finish().unwrap();
+
+        let mut back = ArffFileStream::new(out.path().to_path_buf(), 2).unwrap();
+        assert_eq!(back.header().relation_name(), "weather data");
+        assert_eq!(back.header().number_of_classes(), 2);
+        assert_eq!(back.next_instance().unwrap().to_vec(), vec![0.0, 85.0, 1.0]);
+        let second = back.next_instance().unwrap();
+        assert_eq!(second.value_at_index(0), Some(1.0));
+        assert!(second.is_missing_at_index(1).unwrap());
+        assert!(back.next_instance().is_none());
+    }
+}
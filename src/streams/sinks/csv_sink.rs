@@ -0,0 +1,85 @@
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::streams::sinks::{StreamSink, format_cell};
+use crate::utils::file_parsing::quote_if_needed;
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+
+/// Writes instances as comma-separated rows under a header row of
+/// attribute names, in the layout
+/// [`CsvFileStream`](crate::streams::csv::CsvFileStream) reads.
+///
+/// Nominal values are written as their labels and missing values as `?`.
+pub struct CsvSink {
+    writer: BufWriter<Box<dyn Write + Send>>,
+    header: InstanceHeader,
+}
+
+impl CsvSink {
+    pub fn create<P: AsRef<Path>>(path: P, header: &InstanceHeader) -> Result<Self, Error> {
+        Self::from_writer(Box::new(File::create(path)?), header)
+    }
+
+    /// Writes to an arbitrary writer; the header row is emitted right away.
+    pub fn from_writer(
+        writer: Box<dyn Write + Send>,
+        header: &InstanceHeader,
+    ) -> Result<Self, Error> {
+        let mut writer = BufWriter::new(writer);
+        let names: Vec<String> = header
+            .attributes
+            .iter()
+            .map(|a| quote_if_needed(&a.name()))
+            .collect();
+        writeln!(writer, "{}", names.join(","))?;
+
+        Ok(Self {
+            writer,
+            header: header.clone(),
+        })
+    }
+}
+
+impl StreamSink for CsvSink {
+    fn write_instance(&mut self, instance: &dyn Instance) -> Result<(), Error> {
+        let cells: Vec<String> = instance
+            .to_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| format_cell(&self.header, i, v))
+            .collect();
+        writeln!(self.writer, "{}", cells.join(","))
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streams::Stream;
+    use crate::streams::csv::CsvFileStream;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn round_trips_through_csv_file_stream() {
+        let mut src = NamedTempFile::with_suffix(".csv").unwrap();
+        src.write_all(b"outlook,temp,play\nsunny,85,no\n'over cast',?,yes\n")
+            .unwrap();
+        src.flush().unwrap();
+        let mut input = CsvFileStream::new(src.path().to_path_buf(), None).unwrap();
+
+        let out = NamedTempFile::with_suffix(".csv").unwrap();
+        let mut sink = CsvSink::create(out.path(), input.header()).unwrap();
+        while let Some(inst) = input.next_instance() {
+            sink.write_instance(inst.as_ref()).unwrap();
+        }
+        sink.finish().unwrap();
+
+        let text = std::fs::read_to_string(out.path()).unwrap();
+        assert_eq!(text, "outlook,temp,play\nsunny,85,no\n'over cast',?,yes\n");
+    }
+}
@@ -0,0 +1,118 @@
+pub mod arff_sink;
+pub mod csv_sink;
+#[cfg(feature = "parquet")]
+pub mod parquet_sink;
+
+pub use arff_sink::ArffSink;
+pub use csv_sink::CsvSink;
+#[cfg(feature = "parquet")]
+pub use parquet_sink::ParquetSink;
+
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::error::RivuError;
+use std::io::Error;
+use std::path::Path;
+
+/// Push-based counterpart of [`Stream`](crate::streams::Stream): receives
+/// instances one at a time and persists them in some format.
+///
+/// Every instance passed to a sink must match the header it was created
+/// with. Output may be buffered until [`finish`](StreamSink::finish) is
+/// called, so callers must call it once after the last instance.
+pub trait StreamSink {
+    /// Appends one instance.
+    fn write_instance(&mut self, instance: &dyn Instance) -> Result<(), Error>;
+
+    /// Flushes buffered output and finalizes the file.
+    fn finish(&mut self) -> Result<(), Error>;
+}
+
+/// File formats a [`StreamSink`] can produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkFormat {
+    Arff,
+    Csv,
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl SinkFormat {
+    /// Picks the format from the file extension (`arff`, `csv`, and
+    /// `parquet` when enabled), case-insensitively.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "arff" => Some(SinkFormat::Arff),
+            "csv" => Some(SinkFormat::Csv),
+            #[cfg(feature = "parquet")]
+            "parquet" => Some(SinkFormat::Parquet),
+            _ => None,
+        }
+    }
+}
+
+/// Creates the sink matching the extension of `path`.
+pub fn create_sink<P: AsRef<Path>>(
+    path: P,
+    header: &InstanceHeader,
+) -> Result<Box<dyn StreamSink>, Error> {
+    let path = path.as_ref();
+    let format = SinkFormat::from_path(path).ok_or_else(|| {
+        RivuError::config(format!("unsupported output format for {}", path.display()))
+    })?;
+    Ok(match format {
+        SinkFormat::Arff => Box::new(ArffSink::create(path, header)?),
+        SinkFormat::Csv => Box::new(CsvSink::create(path, header)?),
+        #[cfg(feature = "parquet")]
+        SinkFormat::Parquet => Box::new(ParquetSink::create(path, header)?),
+    })
+}
+
+/// Label of the nominal value stored as `value` in attribute `index`, or
+/// `None` when the attribute is numeric, the value is missing, or it lies
+/// outside the domain.
+pub(crate) fn nominal_label(header: &InstanceHeader, index: usize, value: f64) -> Option<&str> {
+    let nominal = header
+        .attribute_at_index(index)?
+        .as_any()
+        .downcast_ref::<NominalAttribute>()?;
+    if !value.is_finite() || value < 0.0 {
+        return None;
+    }
+    nominal.values.get(value as usize).map(String::as_str)
+}
+
+pub(crate) fn is_nominal(header: &InstanceHeader, index: usize) -> bool {
+    header
+        .attribute_at_index(index)
+        .is_some_and(|a| a.as_any().is::<NominalAttribute>())
+}
+
+/// Formats one cell for the text sinks; missing values become `?`.
+pub(crate) fn format_cell(header: &InstanceHeader, index: usize, value: f64) -> String {
+    if value.is_nan() {
+        return "?".to_string();
+    }
+    if is_nominal(header, index) {
+        return match nominal_label(header, index, value) {
+            Some(label) => crate::utils::file_parsing::quote_if_needed(label),
+            None => "?".to_string(),
+        };
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_from_path_uses_extension() {
+        assert_eq!(SinkFormat::from_path("a/b.ARFF"), Some(SinkFormat::Arff));
+        assert_eq!(SinkFormat::from_path("b.csv"), Some(SinkFormat::Csv));
+        assert_eq!(SinkFormat::from_path("b.json"), None);
+        assert_eq!(SinkFormat::from_path("b"), None);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::core::arrow::{schema_for, to_record_batch};
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::{Instance, InstanceView};
+use crate::streams::sinks::StreamSink;
+use parquet::arrow::ArrowWriter;
+use std::fs::File;
+use std::io::Error;
+use std::path::Path;
+
+/// Rows buffered before a record batch is handed to the writer.
+const BATCH_ROWS: usize = 4096;
+
+/// Writes instances as a Parquet file with one column per attribute.
+///
+/// Numeric attributes become nullable `Float64` columns and nominal ones
+/// nullable `Utf8` columns holding the labels; missing values are nulls.
+pub struct ParquetSink {
+    writer: Option<ArrowWriter<File>>,
+    header: InstanceHeader,
+    /// Buffered rows, concatenated.
+    values: Vec<f64>,
+}
+
+impl ParquetSink {
+    pub fn create<P: AsRef<Path>>(path: P, header: &InstanceHeader) -> Result<Self, Error> {
+        let file = File::create(path)?;
+        let writer = ArrowWriter::try_new(file, schema_for(header), None).map_err(Error::other)?;
+
+        Ok(Self {
+            writer: Some(writer),
+            header: header.clone(),
+            values: Vec::with_capacity(BATCH_ROWS * header.number_of_attributes()),
+        })
+    }
+
+    fn flush_rows(&mut self) -> Result<(), Error> {
+        if self.values.is_empty() {
+            return Ok(());
+        }
+        let Some(writer) = self.writer.as_mut() else {
+            return Err(Error::other("parquet sink already finished"));
+        };
+
+        let views: Vec<InstanceView> = InstanceView::rows(&self.header, &self.values).collect();
+        let rows: Vec<&dyn Instance> = views.iter().map(|v| v as &dyn Instance).collect();
+        let batch = to_record_batch(&self.header, &rows)?;
+        writer.write(&batch).map_err(Error::other)?;
+        self.values.clear();
+        Ok(())
+    }
+}
+
+impl StreamSink for ParquetSink {
+    fn write_instance(&mut self, instance: &dyn Instance) -> Result<(), Error> {
+        let width = self.header.number_of_attributes();
+        self.values
+            .extend((0..width).map(|i| instance.value_at_index(i).unwrap_or(f64::NAN)));
+        if self.values.len() >= BATCH_ROWS * width.max(1) {
+            self.flush_rows()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.flush_rows()?;
+        if let Some(writer) = self.writer.take() {
+            writer.close().map_err(Error::other)?;
+        }
+        Ok(())
+    }
+}
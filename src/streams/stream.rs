@@ -1,13 +1,72 @@
 use crate::core::instance_header::InstanceHeader;
 use crate::core::instances::instance::Instance;
+use crate::error::RivuError;
+use crate::evaluation::Measurement;
+use serde::{Deserialize, Serialize};
 use std::io::Error;
 
+/// Where a stream is, as returned by [`Stream::position`] and restored with
+/// [`Stream::seek`].
+///
+/// A position only makes sense for the stream it was taken from (or one
+/// opened on the same file or with the same generator settings). It is
+/// serializable so that checkpoints can store it alongside the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StreamPosition {
+    /// A file stream about to read data row `record`, which starts `offset`
+    /// bytes into the file. Parquet streams decode the whole file when they
+    /// are opened and only use `record`.
+    File { record: u64, offset: u64 },
+    /// A generator that has produced `produced` instances, whose RNG has
+    /// handed out `word_pos` 32-bit words since it was seeded, and whose
+    /// class balancing wants a class 0 next when `next_class_zero` is set.
+    Generator {
+        produced: u64,
+        word_pos: u128,
+        next_class_zero: bool,
+    },
+}
+
+/// A concept drift the stream is known to contain, as ground truth for
+/// drift detectors and learning curves (see [`Stream::drift_markers`]).
+///
+/// `position` counts the instances produced before the drift's centre: the
+/// new concept is the likelier one from that instance on. `width` is the
+/// number of instances over which the concepts blend, 1 for an abrupt
+/// drift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriftMarker {
+    pub position: u64,
+    pub width: u64,
+}
+
+impl DriftMarker {
+    pub fn new(position: u64, width: u64) -> Self {
+        Self {
+            position,
+            width: width.max(1),
+        }
+    }
+
+    /// First instance touched by the drift.
+    pub fn start(&self) -> u64 {
+        self.position.saturating_sub(self.width / 2)
+    }
+
+    /// First instance past the drift, drawn from the new concept only.
+    pub fn end(&self) -> u64 {
+        self.start() + self.width
+    }
+}
+
 /// Pull-based interface for data streams that produce `Instance`s.
 ///
 /// Implementations may represent finite datasets (e.g., files) or unbounded
 /// generators. All returned instances must conform to the same, immutable
-/// [`InstanceHeader`] for the lifetime of the stream.
-pub trait Stream {
+/// [`InstanceHeader`] for the lifetime of the stream. Streams are `Send` so
+/// that a run can read them on a producer thread (see
+/// [`prefers_prefetch`](Self::prefers_prefetch)).
+pub trait Stream: Send {
     /// Returns the stream header (relation name, attributes, class index).
     ///
     /// The header must remain valid and immutable for the entire lifetime of
@@ -34,6 +93,41 @@ pub trait Stream {
     /// Returned instances must be compatible with [`header`].
     fn next_instance(&mut self) -> Option<Box<dyn Instance>>;
 
+    /// Produces up to `n` instances, fewer only once the stream is exhausted.
+    ///
+    /// The default pulls [`next_instance`] into a vector allocated once for
+    /// the whole batch; generators override it to write their rows directly
+    /// (see [`RowGenerator`](crate::streams::generators::RowGenerator)).
+    fn next_batch(&mut self, n: usize) -> Vec<Box<dyn Instance>> {
+        let mut batch = Vec::with_capacity(n);
+        while batch.len() < n && self.has_more_instances() {
+            match self.next_instance() {
+                Some(instance) => batch.push(instance),
+                None => break,
+            }
+        }
+        batch
+    }
+
+    /// Hands back an instance the caller is done with, so the stream can
+    /// reuse its allocations for the instances that follow. The default just
+    /// drops it; the generators keep it in an
+    /// [`InstancePool`](crate::core::instances::InstancePool).
+    fn recycle(&mut self, instance: Box<dyn Instance>) {
+        drop(instance);
+    }
+
+    /// Whether a run should read this stream on a producer thread, ahead of
+    /// the learner, so that decoding overlaps with prediction and training.
+    ///
+    /// Streams that parse files say yes. The default is no, which keeps
+    /// generators stepping in lock step with the learner: they are never
+    /// advanced past the last instance a run uses and keep recycling their
+    /// instances.
+    fn prefers_prefetch(&self) -> bool {
+        false
+    }
+
     /// Resets the stream to its initial state.
     ///
     /// For file-backed streams, this typically seeks back to the start of the
@@ -42,4 +136,44 @@ pub trait Stream {
     ///
     /// Returns an error if the underlying source cannot be reopened or sought.
     fn restart(&mut self) -> Result<(), Error>;
+
+    /// Returns where the stream is, so that [`seek`](Self::seek) can later
+    /// return it to this point: the next instance read after seeking is the
+    /// one that would have been read next now.
+    ///
+    /// The default reports the operation as unsupported.
+    fn position(&self) -> Result<StreamPosition, Error> {
+        Err(RivuError::unsupported("this stream cannot report its position").into())
+    }
+
+    /// Moves the stream to a `position` taken with
+    /// [`position`](Self::position), forwards or backwards.
+    ///
+    /// Returns a config error for a position of the wrong kind. The default
+    /// reports the operation as unsupported.
+    fn seek(&mut self, _position: &StreamPosition) -> Result<(), Error> {
+        Err(RivuError::unsupported("this stream cannot seek").into())
+    }
+
+    /// Takes the error that ended the stream early, if any, so that a run
+    /// fails with it instead of finishing as if the stream had been read to
+    /// its end. Returns `None` once taken, and always by default.
+    fn take_error(&mut self) -> Option<Error> {
+        None
+    }
+
+    /// Statistics of the stream itself, such as the state of a filter,
+    /// reported by a task next to its learner's model measurements. Empty
+    /// by default.
+    fn measurements(&self) -> Vec<Measurement> {
+        Vec::new()
+    }
+
+    /// Concept drifts of the stream's schedule, in order of position,
+    /// whether or not the stream has reached them yet. Wrappers report the
+    /// drifts of the streams they read from along with their own. Empty by
+    /// default: a file or a stationary generator has no known drifts.
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        Vec::new()
+    }
 }
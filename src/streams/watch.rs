@@ -0,0 +1,125 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Error, Seek, SeekFrom};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+/// How a file stream keeps following its file once it reaches the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// Delay between checks for new data at the end of the file.
+    pub poll_interval: Duration,
+    /// Stop once the file has not grown for this long; `None` waits forever.
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(250),
+            idle_timeout: None,
+        }
+    }
+}
+
+/// Time a watched stream read a record, in milliseconds since the Unix
+/// epoch, used as the instance timestamp.
+pub(crate) fn arrival_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Result of asking a [`Follower`] for the next line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Follow {
+    /// A complete line was read into the buffer.
+    Line,
+    /// The file shrank or was replaced; the caller should reopen it and
+    /// continue from its first data row.
+    Reset,
+    /// The end of the file was reached (and, when watching, the idle
+    /// timeout expired).
+    End,
+}
+
+/// Tail-style line reader shared by the file streams.
+///
+/// At the end of the file, or on a line that is still being written, it
+/// rewinds to the start of the line and polls until another process
+/// appends the rest.
+#[derive(Debug, Clone)]
+pub(crate) struct Follower {
+    options: WatchOptions,
+    seen_len: u64,
+}
+
+impl Follower {
+    pub(crate) fn new(options: WatchOptions) -> Self {
+        Self {
+            options,
+            seen_len: 0,
+        }
+    }
+
+    /// Forgets the file length observed so far, e.g. after reopening.
+    pub(crate) fn reset(&mut self) {
+        self.seen_len = 0;
+    }
+
+    pub(crate) fn read_line(
+        &mut self,
+        reader: &mut BufReader<File>,
+        path: &Path,
+        buf: &mut String,
+    ) -> Result<Follow, Error> {
+        let start = reader.stream_position()?;
+        let mut idle = Duration::ZERO;
+        loop {
+            buf.clear();
+            let n = reader.read_line(buf)?;
+            if buf.ends_with('\n') {
+                self.seen_len = self.seen_len.max(start + n as u64);
+                return Ok(Follow::Line);
+            }
+            if self.options.idle_timeout.is_some_and(|t| idle >= t) {
+                // Nothing more is coming: a final line without a newline
+                // is taken as it is.
+                return Ok(if n == 0 { Follow::End } else { Follow::Line });
+            }
+            reader.seek(SeekFrom::Start(start))?;
+
+            let open_len = reader.get_ref().metadata()?.len();
+            // The path can briefly vanish while another process replaces
+            // the file; keep polling until it is back.
+            if let Ok(meta) = fs::metadata(path)
+                && (meta.len() < self.seen_len || meta.len() != open_len)
+            {
+                self.reset();
+                return Ok(Follow::Reset);
+            }
+            self.seen_len = self.seen_len.max(open_len);
+
+            thread::sleep(self.options.poll_interval);
+            idle += self.options.poll_interval;
+        }
+    }
+}
+
+/// Reads the next line, following the file when `follower` is set.
+pub(crate) fn next_line(
+    reader: &mut BufReader<File>,
+    path: &Path,
+    follower: Option<&mut Follower>,
+    buf: &mut String,
+) -> Result<Follow, Error> {
+    match follower {
+        Some(f) => f.read_line(reader, path, buf),
+        None => {
+            buf.clear();
+            Ok(if reader.read_line(buf)? == 0 {
+                Follow::End
+            } else {
+                Follow::Line
+            })
+        }
+    }
+}
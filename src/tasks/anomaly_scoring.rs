@@ -0,0 +1,197 @@
+use crate::anomaly::AnomalyDetector;
+use crate::core::instance_header::InstanceHeader;
+use crate::evaluation::{AnomalyEvaluator, LearningCurve, ScoreLog};
+use crate::streams::Stream;
+use crate::tasks::runner::{RunState, check_stream};
+use crate::tasks::{ProgressSender, Task, TaskControl};
+use std::io::Error;
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+
+/// Test-then-train anomaly scoring.
+///
+/// Every instance is first scored by the detector and then used to update
+/// it. Scores go to the optional [`ScoreLog`] and, together with the labels
+/// when the stream has them, to an [`AnomalyEvaluator`]. Snapshots leave
+/// accuracy and kappa as `NaN` and report the evaluator metrics in
+/// `extras`.
+pub struct AnomalyScoring {
+    detector: Box<dyn AnomalyDetector>,
+    stream: Box<dyn Stream>,
+    evaluator: AnomalyEvaluator,
+    score_log: Option<ScoreLog>,
+
+    state: RunState,
+}
+
+impl AnomalyScoring {
+    pub fn new(
+        mut detector: Box<dyn AnomalyDetector>,
+        stream: Box<dyn Stream>,
+        evaluator: AnomalyEvaluator,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
+
+        let header = stream.header();
+        detector.set_model_context(Arc::new(InstanceHeader::new(
+            header.relation_name().to_string(),
+            header.attributes.clone(),
+            header.class_index(),
+        )));
+
+        Ok(Self {
+            detector,
+            stream,
+            evaluator,
+            score_log: None,
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    /// Writes every instance's score to `log`.
+    pub fn with_score_log(mut self, log: ScoreLog) -> Self {
+        self.score_log = Some(log);
+        self
+    }
+
+    fn push_snapshot(&mut self) {
+        let perf = self.evaluator.performance();
+        self.state.push_snapshot(perf);
+    }
+}
+
+impl Task for AnomalyScoring {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.start();
+
+        while self.stream.has_more_instances() {
+            while self.state.poll_control() {
+                self.push_snapshot();
+            }
+            if self.state.limits_reached() {
+                break;
+            }
+            let Some(instance) = self.stream.next_instance() else {
+                break;
+            };
+
+            let score = self.detector.score_instance(instance.as_ref());
+            if let Some(log) = self.score_log.as_mut() {
+                log.record(self.state.processed, instance.as_ref(), score)?;
+            }
+            self.evaluator.add_result(instance.as_ref(), score);
+            self.detector.train_on_instance(instance.as_ref());
+
+            self.state.count_instance();
+            if self.state.at_sample_point() {
+                self.push_snapshot();
+            }
+        }
+
+        check_stream(self.stream.as_mut())?;
+        if !self.state.at_sample_point() || self.state.processed == 0 {
+            self.push_snapshot();
+        }
+        match self.score_log.as_mut() {
+            Some(log) => log.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn set_control(&mut self, rx: Receiver<TaskControl>) -> Result<(), Error> {
+        self.state.control_rx = Some(rx);
+        Ok(())
+    }
+
+    fn cancelled(&self) -> bool {
+        self.state.cancelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::instances::Instance;
+    use crate::evaluation::PredictionLogFormat;
+    use crate::testing::VecStream;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Scores every instance with its class value and counts updates.
+    struct LabelEcho(Rc<Cell<u64>>);
+
+    impl AnomalyDetector for LabelEcho {
+        fn set_model_context(&mut self, _header: Arc<InstanceHeader>) {}
+        fn score_instance(&self, instance: &dyn Instance) -> f64 {
+            instance.class_value().unwrap_or(0.0)
+        }
+        fn train_on_instance(&mut self, _instance: &dyn Instance) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn scores_then_trains_and_reports_metrics() {
+        let trained = Rc::new(Cell::new(0));
+        let stream: Box<dyn Stream> =
+            Box::new(VecStream::new((0..30).map(|i| i % 3 / 2).collect()));
+        let mut task = AnomalyScoring::new(
+            Box::new(LabelEcho(Rc::clone(&trained))),
+            stream,
+            AnomalyEvaluator::new(100, 1, 0.5),
+            None,
+            None,
+            10,
+            10,
+        )
+        .unwrap();
+        task.run().unwrap();
+
+        assert_eq!(trained.get(), 30);
+        assert_eq!(task.curve().len(), 3);
+        let last = task.curve().latest().unwrap();
+        assert!(last.accuracy.is_nan());
+        assert_eq!(last.extras.get("roc_auc"), Some(&1.0));
+        assert_eq!(last.extras.get("f1"), Some(&1.0));
+    }
+
+    #[test]
+    fn score_log_has_one_line_per_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scores.csv");
+        let stream: Box<dyn Stream> = Box::new(VecStream::new(vec![0, 1, 0, 0]));
+        let mut task = AnomalyScoring::new(
+            Box::new(LabelEcho(Rc::new(Cell::new(0)))),
+            stream,
+            AnomalyEvaluator::new(100, 1, 0.5),
+            Some(3),
+            None,
+            10,
+            10,
+        )
+        .unwrap()
+        .with_score_log(ScoreLog::create(&path, PredictionLogFormat::Csv).unwrap());
+        task.run().unwrap();
+
+        let text = std::fs::read_to_string(path).unwrap();
+        assert_eq!(text, "instance,score,label\n0,0,0\n1,1,1\n2,0,0\n");
+    }
+}
@@ -0,0 +1,224 @@
+use crate::clusterers::Clusterer;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::error::RivuError;
+use crate::evaluation::{ClusteringEvaluator, LearningCurve, Measurement, MetricKey};
+use crate::streams::Stream;
+use crate::tasks::runner::{RunState, check_stream};
+use crate::tasks::{ProgressSender, Task, TaskControl};
+use std::collections::VecDeque;
+use std::io::Error;
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+
+/// Stream clustering, the unsupervised counterpart of
+/// [`PrequentialEvaluator`](crate::tasks::PrequentialEvaluator).
+///
+/// Every instance trains the clusterer and joins a sliding window of the
+/// last `window` instances. On the sampling cadence the macro-clusters are
+/// extracted and the window is scored against them with a fresh
+/// [`ClusteringEvaluator`], so each point of the curve describes the
+/// current clustering of recent data. Snapshots leave accuracy and kappa as
+/// `NaN` and report the evaluator metrics plus `clusters` and
+/// `micro_clusters` in `extras`.
+pub struct ClusterStream {
+    clusterer: Box<dyn Clusterer>,
+    stream: Box<dyn Stream>,
+    evaluator: ClusteringEvaluator,
+    window_size: usize,
+
+    window: VecDeque<Box<dyn Instance>>,
+    state: RunState,
+}
+
+impl ClusterStream {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mut clusterer: Box<dyn Clusterer>,
+        stream: Box<dyn Stream>,
+        evaluator: ClusteringEvaluator,
+        window_size: usize,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        if window_size == 0 {
+            return Err(RivuError::config("window_size must be > 0").into());
+        }
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
+
+        let header = stream.header();
+        clusterer.set_model_context(Arc::new(InstanceHeader::new(
+            header.relation_name().to_string(),
+            header.attributes.clone(),
+            header.class_index(),
+        )));
+
+        Ok(Self {
+            clusterer,
+            stream,
+            evaluator,
+            window_size,
+            window: VecDeque::with_capacity(window_size),
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    fn evaluate_window(&mut self) {
+        let centers = self.clusterer.macro_cluster_centers();
+        self.evaluator.reset();
+        for instance in &self.window {
+            self.evaluator.add_result(instance.as_ref(), &centers);
+        }
+
+        let mut perf = self.evaluator.performance();
+        perf.push(Measurement::new(MetricKey::CLUSTERS, centers.len() as f64));
+        perf.push(Measurement::new(
+            MetricKey::MICRO_CLUSTERS,
+            self.clusterer.micro_cluster_centers().len() as f64,
+        ));
+        self.state.push_snapshot(perf);
+    }
+}
+
+impl Task for ClusterStream {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.start();
+
+        while self.stream.has_more_instances() {
+            while self.state.poll_control() {
+                self.evaluate_window();
+            }
+            if self.state.limits_reached() {
+                break;
+            }
+            let Some(instance) = self.stream.next_instance() else {
+                break;
+            };
+
+            self.clusterer.train_on_instance(instance.as_ref());
+            if self.window.len() == self.window_size {
+                self.window.pop_front();
+            }
+            self.window.push_back(instance);
+
+            self.state.count_instance();
+            if self.state.at_sample_point() {
+                self.evaluate_window();
+            }
+        }
+
+        check_stream(self.stream.as_mut())?;
+        if !self.state.at_sample_point() || self.state.processed == 0 {
+            self.evaluate_window();
+        }
+        Ok(())
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn set_control(&mut self, rx: Receiver<TaskControl>) -> Result<(), Error> {
+        self.state.control_rx = Some(rx);
+        Ok(())
+    }
+
+    fn cancelled(&self) -> bool {
+        self.state.cancelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clusterers::CluStream;
+    use crate::testing::VecStream;
+    use std::io::ErrorKind;
+
+    fn clusterer() -> Box<dyn Clusterer> {
+        Box::new(CluStream::new(10, 2, 2.0, 1000, 1).unwrap())
+    }
+
+    #[test]
+    fn ctor_guards() {
+        let s: Box<dyn Stream> = Box::new(VecStream::new(vec![0, 1]));
+        let err = ClusterStream::new(
+            clusterer(),
+            s,
+            ClusteringEvaluator::new(10, 1),
+            0,
+            None,
+            None,
+            10,
+            10,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn snapshots_report_clustering_metrics_on_cadence() {
+        // Class-only vectors: a zero-dimensional feature space.
+        let s: Box<dyn Stream> = Box::new(VecStream::new((0..25).map(|i| i % 2).collect()));
+        let mut task = ClusterStream::new(
+            clusterer(),
+            s,
+            ClusteringEvaluator::new(10, 1),
+            8,
+            None,
+            None,
+            10,
+            10,
+        )
+        .unwrap();
+        task.run().unwrap();
+
+        let seen: Vec<u64> = task.curve().iter().map(|s| s.instances_seen).collect();
+        assert_eq!(seen, vec![10, 20, 25]);
+        let last = task.curve().latest().unwrap();
+        assert!(last.accuracy.is_nan());
+        assert_eq!(last.extras.get("ssq"), Some(&0.0));
+        assert_eq!(last.extras.get("clusters"), Some(&1.0));
+        assert!(last.extras.contains_key("purity"));
+        assert_eq!(task.window.len(), 8);
+    }
+
+    #[test]
+    fn clusters_generator_features_within_limits() {
+        use crate::streams::generators::{SeaFunction, SeaGenerator};
+        let s: Box<dyn Stream> =
+            Box::new(SeaGenerator::new(SeaFunction::F1, false, 0, None, 7).unwrap());
+        let mut task = ClusterStream::new(
+            clusterer(),
+            s,
+            ClusteringEvaluator::new(50, 1),
+            200,
+            Some(500),
+            None,
+            100,
+            100,
+        )
+        .unwrap();
+        task.run().unwrap();
+
+        assert_eq!(task.curve().len(), 5);
+        let last = task.curve().latest().unwrap();
+        assert_eq!(last.extras.get("clusters"), Some(&2.0));
+        assert!(last.extras["micro_clusters"] <= 10.0);
+        assert!(last.extras["ssq"] > 0.0);
+        assert!(last.extras["silhouette"] > 0.0);
+    }
+}
@@ -0,0 +1,16 @@
+/// Commands a running task accepts on its control channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskControl {
+    /// Stop processing instances until [`TaskControl::Resume`] arrives.
+    /// Paused time does not count towards `max_seconds` or the reported
+    /// elapsed seconds.
+    Pause,
+    /// Continue after a [`TaskControl::Pause`].
+    Resume,
+    /// Stop the run as if a limit was reached; the task still records its
+    /// final snapshot and returns `Ok`.
+    Cancel,
+    /// Record a snapshot right away, off the sampling cadence (also while
+    /// paused).
+    SnapshotNow,
+}
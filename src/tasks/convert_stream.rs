@@ -0,0 +1,163 @@
+use crate::evaluation::{LearningCurve, Measurement, MetricKey};
+use crate::streams::{Stream, StreamSink};
+use crate::tasks::runner::{RunState, check_stream};
+use crate::tasks::{ProgressSender, Task, TaskControl};
+use std::io::Error;
+use std::sync::mpsc::Receiver;
+
+/// Copies a stream into a [`StreamSink`], e.g. to turn an ARFF file into
+/// CSV or Parquet, or to dump a generator to disk.
+///
+/// No learner is involved. Snapshots leave accuracy and kappa as `NaN` and
+/// report `instances_per_second` in `extras`, so the usual progress display
+/// doubles as a conversion progress bar. The sink is finalized when the
+/// stream ends or a limit is reached.
+pub struct ConvertStream {
+    stream: Box<dyn Stream>,
+    sink: Box<dyn StreamSink>,
+
+    state: RunState,
+}
+
+impl ConvertStream {
+    pub fn new(
+        stream: Box<dyn Stream>,
+        sink: Box<dyn StreamSink>,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
+
+        Ok(Self {
+            stream,
+            sink,
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    fn push_snapshot(&mut self) {
+        let secs = self.state.start_time.elapsed().as_secs_f64();
+        let rate = if secs > 0.0 {
+            self.state.processed as f64 / secs
+        } else {
+            f64::NAN
+        };
+        let perf = vec![Measurement::new(MetricKey::INSTANCES_PER_SECOND, rate)];
+        self.state.push_snapshot(perf);
+    }
+}
+
+impl Task for ConvertStream {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.start();
+
+        while self.stream.has_more_instances() {
+            while self.state.poll_control() {
+                self.push_snapshot();
+            }
+            if self.state.limits_reached() {
+                break;
+            }
+            let Some(instance) = self.stream.next_instance() else {
+                break;
+            };
+            self.sink.write_instance(instance.as_ref())?;
+
+            self.state.count_instance();
+            if self.state.at_sample_point() {
+                self.push_snapshot();
+            }
+        }
+
+        check_stream(self.stream.as_mut())?;
+        self.sink.finish()?;
+        if !self.state.at_sample_point() || self.state.processed == 0 {
+            self.push_snapshot();
+        }
+        Ok(())
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn set_control(&mut self, rx: Receiver<TaskControl>) -> Result<(), Error> {
+        self.state.control_rx = Some(rx);
+        Ok(())
+    }
+
+    fn cancelled(&self) -> bool {
+        self.state.cancelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streams::create_sink;
+    use crate::streams::csv::CsvFileStream;
+    use crate::testing::VecStream;
+    use std::io::ErrorKind;
+    use tempfile::TempDir;
+
+    fn stream(n: usize) -> Box<dyn Stream> {
+        Box::new(VecStream::new((0..n).map(|i| i % 2).collect()))
+    }
+
+    #[test]
+    fn ctor_guards() {
+        let dir = TempDir::new().unwrap();
+        let s = stream(3);
+        let sink = create_sink(dir.path().join("out.csv"), s.header()).unwrap();
+        let err = ConvertStream::new(s, sink, None, None, 0, 1).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn copies_every_instance_and_reports_progress() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("out.csv");
+        let s = stream(25);
+        let sink = create_sink(&out, s.header()).unwrap();
+        let mut task = ConvertStream::new(s, sink, None, None, 10, 10).unwrap();
+        task.run().unwrap();
+
+        let seen: Vec<u64> = task.curve().iter().map(|s| s.instances_seen).collect();
+        assert_eq!(seen, vec![10, 20, 25]);
+        assert!(task.curve().latest().unwrap().accuracy.is_nan());
+
+        let mut back = CsvFileStream::new(out, None).unwrap();
+        let mut labels = Vec::new();
+        while let Some(inst) = back.next_instance() {
+            labels.push(inst.class_value().unwrap() as usize);
+        }
+        assert_eq!(labels, (0..25).map(|i| i % 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn instance_limit_truncates_output() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("out.arff");
+        let s = stream(100);
+        let sink = create_sink(&out, s.header()).unwrap();
+        let mut task = ConvertStream::new(s, sink, Some(7), None, 5, 5).unwrap();
+        task.run().unwrap();
+
+        let text = std::fs::read_to_string(out).unwrap();
+        let data = text.split("@data\n").nth(1).unwrap();
+        assert_eq!(data.lines().count(), 7);
+        assert_eq!(task.curve().latest().unwrap().instances_seen, 7);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::classifiers::Classifier;
+use crate::drift::ChangeDetector;
+use crate::evaluation::{
+    ConfusionMatrix, CurveCheckpoint, LearningCurve, Measurement, PerformanceEvaluator,
+    PredictionLog,
+};
+use crate::streams::{DriftMarker, Stream};
+use crate::tasks::runner::{RunState, bind_model_context, check_stream, model_measurements};
+use crate::tasks::{ProgressSender, SharedModel, SnapshotSchedule, Task};
+use std::io::{Error, Write};
+
+/// Interleaved test-then-train evaluation, following MOA's
+/// `EvaluateInterleavedTestThenTrain`.
+///
+/// Every instance is first used for testing and then for training. Unlike
+/// [`PrequentialEvaluator`](crate::tasks::PrequentialEvaluator), a snapshot
+/// is recorded only when the instance count hits the sample frequency or
+/// when the stream runs out; stopping on an instance or time limit does not
+/// add a trailing entry, so each point of the curve lies on the cadence
+/// (or at the end of the data).
+pub struct InterleavedTestThenTrain {
+    learner: Box<dyn Classifier>,
+    stream: Box<dyn Stream>,
+    evaluator: Box<dyn PerformanceEvaluator>,
+
+    state: RunState,
+}
+
+impl InterleavedTestThenTrain {
+    pub fn new(
+        mut learner: Box<dyn Classifier>,
+        stream: Box<dyn Stream>,
+        evaluator: Box<dyn PerformanceEvaluator>,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
+
+        bind_model_context(learner.as_mut(), stream.as_ref());
+
+        Ok(Self {
+            learner,
+            stream,
+            evaluator,
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    /// Decides when snapshots are taken during the run (see
+    /// [`SnapshotSchedule`]); every `sample_frequency` instances by default.
+    pub fn with_snapshot_schedule(mut self, schedule: SnapshotSchedule) -> Self {
+        self.state.schedule = schedule;
+        self
+    }
+
+    /// Publishes a copy of the learner to `model` at each sample point and
+    /// when the run ends (see [`SharedModel`]).
+    pub fn with_model_view(mut self, model: SharedModel) -> Self {
+        self.state.model_view = Some(model);
+        self
+    }
+
+    /// Trains on the first `n` instances without passing them to the
+    /// evaluator, so the cold-start phase does not weigh on the cumulative
+    /// metrics. Those instances still count towards `instances_seen`.
+    pub fn with_warmup(mut self, n: u64) -> Self {
+        self.state.warmup_instances = n;
+        self
+    }
+
+    /// Feeds the 0/1 error of every evaluated prediction to `detector` and
+    /// records where it signals a change (see [`Task::drift_detections`]).
+    pub fn with_drift_detector(mut self, detector: Box<dyn ChangeDetector>) -> Self {
+        self.state.drift_detector = Some(detector);
+        self
+    }
+
+    /// Writes one record per prediction (true label, predicted label, votes,
+    /// correctness) to `log`.
+    pub fn with_prediction_log(mut self, log: PredictionLog) -> Self {
+        self.state.prediction_log = Some(log);
+        self
+    }
+
+    /// Appends every snapshot to `checkpoint` as it is taken, so the curve
+    /// survives a crash mid-run.
+    pub fn with_curve_checkpoint(mut self, checkpoint: CurveCheckpoint) -> Self {
+        self.state.curve.checkpoint_to(checkpoint);
+        self
+    }
+}
+
+impl Task for InterleavedTestThenTrain {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.schedule.validate()?;
+        self.state.start();
+
+        let mut votes = Vec::new();
+        while self.stream.has_more_instances() && !self.state.limits_reached() {
+            let Some(instance) = self.stream.next_instance() else {
+                break;
+            };
+
+            if !self.state.in_warmup() {
+                self.learner.get_votes_into(&*instance, &mut votes);
+                self.state.log_prediction(&*instance, &votes)?;
+                self.state.watch_drift(&*instance, &votes);
+                self.evaluator.add_result(&*instance, &votes);
+            }
+            self.learner.train_on_instance(instance.as_ref());
+            self.stream.recycle(instance);
+
+            self.state.count_instance();
+            if !self.stream.has_more_instances() {
+                let perf = self.evaluator.performance();
+                self.state.push_snapshot(perf);
+                self.state.publish_model(self.learner.as_ref());
+            } else if self.state.at_sample_point() {
+                let perf = self.evaluator.performance();
+                self.state.push_sampled_snapshot(perf);
+                self.state.publish_model(self.learner.as_ref());
+            }
+        }
+
+        check_stream(self.stream.as_mut())?;
+        self.state.finish()
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        model_measurements(self.learner.as_ref(), self.stream.as_ref())
+    }
+
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        self.stream.drift_markers()
+    }
+
+    fn drift_detections(&self) -> Vec<u64> {
+        self.state.detections.clone()
+    }
+
+    fn confusion_matrix(&self) -> Option<ConfusionMatrix> {
+        self.evaluator.confusion_matrix().cloned()
+    }
+
+    fn save_model(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.learner.save_model(writer)
+    }
+
+    fn set_model_view(&mut self, model: SharedModel) -> Result<(), Error> {
+        self.state.model_view = Some(model);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{BasicClassificationEvaluator, BasicEstimator};
+    use crate::testing::{OracleClassifier, TrainSpyClassifier, VecStream};
+    use std::io::ErrorKind;
+
+    fn evaluator() -> Box<dyn PerformanceEvaluator> {
+        Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2))
+    }
+
+    fn stream(n: usize) -> Box<dyn Stream> {
+        Box::new(VecStream::new((0..n).map(|i| i % 2).collect()))
+    }
+
+    #[test]
+    fn ctor_guards() {
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let err = InterleavedTestThenTrain::new(l, stream(10), evaluator(), None, None, 0, 5)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn no_duplicate_final_snapshot_on_cadence() {
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let mut task =
+            InterleavedTestThenTrain::new(l, stream(100), evaluator(), None, None, 10, 7).unwrap();
+        task.run().unwrap();
+
+        assert_eq!(task.curve().len(), 10);
+        let last = task.curve().latest().unwrap();
+        assert_eq!(last.instances_seen, 100);
+        assert!(last.accuracy > 0.9999);
+    }
+
+    #[test]
+    fn stream_end_off_cadence_is_reported() {
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let mut task =
+            InterleavedTestThenTrain::new(l, stream(12), evaluator(), None, None, 5, 1).unwrap();
+        task.run().unwrap();
+
+        let seen: Vec<u64> = task.curve().iter().map(|s| s.instances_seen).collect();
+        assert_eq!(seen, vec![5, 10, 12]);
+    }
+
+    #[test]
+    fn instance_limit_off_cadence_adds_no_trailing_snapshot() {
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let mut task =
+            InterleavedTestThenTrain::new(l, stream(1000), evaluator(), Some(23), None, 5, 3)
+                .unwrap();
+        task.run().unwrap();
+
+        assert_eq!(task.curve().len(), 4);
+        assert_eq!(task.curve().latest().unwrap().instances_seen, 20);
+    }
+
+    #[test]
+    fn trains_once_per_instance() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let mut task =
+            InterleavedTestThenTrain::new(l, stream(37), evaluator(), None, None, 10, 4).unwrap();
+        task.run().unwrap();
+
+        assert_eq!(handle.count(), 37);
+    }
+
+    #[test]
+    fn warmup_skips_evaluation_only() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let mut task = InterleavedTestThenTrain::new(
+            Box::new(spy),
+            stream(20),
+            evaluator(),
+            None,
+            None,
+            10,
+            10,
+        )
+        .unwrap()
+        .with_warmup(10);
+        task.run().unwrap();
+
+        assert_eq!(handle.count(), 20);
+        let first = &task.curve().as_slice()[0];
+        assert!(first.accuracy.is_nan());
+        assert!(!task.curve().latest().unwrap().accuracy.is_nan());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::classifiers::Classifier;
+use crate::evaluation::{LearningCurve, Measurement};
+use crate::streams::Stream;
+use crate::tasks::runner::{RunState, bind_model_context, check_stream, model_measurements};
+use crate::tasks::{ProgressSender, Task};
+use std::io::{Error, Write};
+use std::path::PathBuf;
+
+/// Trains a learner on a stream without evaluating it, then saves the model.
+///
+/// Follows MOA's `LearnModel`: no predictions are made, so the curve only
+/// carries progress and resource usage (accuracy and kappa are `NaN`).
+/// After the loop ends the model is written to `model_path` through
+/// [`Classifier::save`], to be read back with
+/// [`load_classifier`](crate::classifiers::load_classifier).
+pub struct LearnModel {
+    learner: Box<dyn Classifier>,
+    stream: Box<dyn Stream>,
+    model_path: PathBuf,
+
+    state: RunState,
+}
+
+impl LearnModel {
+    pub fn new(
+        mut learner: Box<dyn Classifier>,
+        stream: Box<dyn Stream>,
+        model_path: PathBuf,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
+
+        bind_model_context(learner.as_mut(), stream.as_ref());
+        // Fail before training if the learner cannot be persisted at all.
+        learner.save_model(&mut std::io::sink())?;
+
+        Ok(Self {
+            learner,
+            stream,
+            model_path,
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    /// The trained learner.
+    pub fn learner(&self) -> &dyn Classifier {
+        self.learner.as_ref()
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        self.learner.save(&self.model_path)
+    }
+}
+
+impl Task for LearnModel {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.start();
+
+        while self.stream.has_more_instances() && !self.state.limits_reached() {
+            let Some(instance) = self.stream.next_instance() else {
+                break;
+            };
+            self.learner.train_on_instance(instance.as_ref());
+            self.stream.recycle(instance);
+
+            self.state.count_instance();
+            if self.state.at_sample_point() {
+                self.state.push_snapshot(Vec::new());
+            }
+        }
+
+        check_stream(self.stream.as_mut())?;
+        if !self.state.at_sample_point() || self.state.processed == 0 {
+            self.state.push_snapshot(Vec::new());
+        }
+        self.save()
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        model_measurements(self.learner.as_ref(), self.stream.as_ref())
+    }
+
+    fn save_model(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.learner.save_model(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::NaiveBayes;
+    use crate::testing::{TrainSpyClassifier, VecStream};
+    use std::io::ErrorKind;
+    use tempfile::tempdir;
+
+    fn stream(n: usize) -> Box<dyn Stream> {
+        Box::new(VecStream::new((0..n).map(|i| i % 2).collect()))
+    }
+
+    #[test]
+    fn trains_up_to_limit_and_saves_model() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nb.json");
+        let l: Box<dyn Classifier> = Box::new(NaiveBayes::new());
+        let mut task =
+            LearnModel::new(l, stream(100), path.clone(), Some(40), None, 10, 10).unwrap();
+        task.run().unwrap();
+
+        assert_eq!(task.curve().latest().unwrap().instances_seen, 40);
+        assert!(task.curve().latest().unwrap().accuracy.is_nan());
+
+        let state = NaiveBayes::load(&path).unwrap().state().unwrap();
+        assert_eq!(state.observed_class_distribution, vec![20.0, 20.0]);
+    }
+
+    #[test]
+    fn unsupported_learner_is_rejected_before_training() {
+        let dir = tempdir().unwrap();
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let err = LearnModel::new(l, stream(15), dir.path().join("m"), None, None, 10, 10)
+            .err()
+            .unwrap();
+
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert_eq!(handle.count(), 0);
+    }
+}
@@ -1,3 +1,37 @@
+mod anomaly_scoring;
+mod cluster_stream;
+mod control;
+mod convert_stream;
+mod interleaved_test_then_train;
+mod learn_model;
+mod model_view;
+mod multi_seed;
+mod periodic_held_out_test;
+mod prequential_cv;
+mod prequential_delayed;
 mod prequential_evaluator;
+mod progress;
+mod runner;
+mod schedule;
+mod task;
+mod throughput_benchmark;
 
+pub use anomaly_scoring::AnomalyScoring;
+pub use cluster_stream::ClusterStream;
+pub use control::TaskControl;
+pub use convert_stream::ConvertStream;
+pub use interleaved_test_then_train::InterleavedTestThenTrain;
+pub use learn_model::LearnModel;
+pub use model_view::{ModelView, SharedModel};
+pub use multi_seed::{MultiSeed, SeededTaskFactory};
+pub use periodic_held_out_test::PeriodicHeldOutTest;
+pub use prequential_cv::{PrequentialCV, ValidationMethodology};
+pub use prequential_delayed::PrequentialDelayed;
 pub use prequential_evaluator::PrequentialEvaluator;
+pub use progress::{
+    IntoIter as ProgressIter, Overflow, ProgressOptions, ProgressReceiver, ProgressSender,
+    progress_channel,
+};
+pub use schedule::SnapshotSchedule;
+pub use task::Task;
+pub use throughput_benchmark::ThroughputBenchmark;
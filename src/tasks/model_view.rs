@@ -0,0 +1,116 @@
+use crate::classifiers::{Classifier, ClassifierState};
+use crate::error::RivuError;
+use crate::evaluation::Measurement;
+use std::io::Error;
+use std::sync::{Arc, Mutex};
+
+/// Frozen copy of a running task's model, taken at a sample point.
+#[derive(Debug, Clone)]
+pub struct ModelView {
+    /// Instances the task had processed when the copy was taken.
+    pub instances_seen: u64,
+    /// The learner's [`model_measurements`](Classifier::model_measurements).
+    pub measurements: Vec<Measurement>,
+    /// The learner's state, or `None` for learners that cannot be saved.
+    pub state: Option<ClassifierState>,
+}
+
+impl ModelView {
+    /// Copies what `learner` has learned so far.
+    pub fn capture(learner: &dyn Classifier, instances_seen: u64) -> Self {
+        Self {
+            instances_seen,
+            measurements: learner.model_measurements(),
+            state: learner.snapshot_state().ok(),
+        }
+    }
+
+    /// Rebuilds the model as a classifier owned by the caller, to predict
+    /// with on any thread without touching the one being trained.
+    pub fn predictor(&self) -> Result<Box<dyn Classifier>, Error> {
+        match &self.state {
+            Some(state) => state.clone().into_classifier(),
+            None => Err(
+                RivuError::unsupported("this learner does not support copying its model").into(),
+            ),
+        }
+    }
+}
+
+/// Latest [`ModelView`] of a running task, shared between the task and any
+/// number of readers; clones share the same slot.
+///
+/// The task swaps in a new view at each sample point and readers take the
+/// current one, each holding the lock only long enough to copy an `Arc`,
+/// so querying the model never pauses the run loop and a reader keeps a
+/// consistent view for as long as it needs it.
+#[derive(Debug, Clone, Default)]
+pub struct SharedModel {
+    slot: Arc<Mutex<Option<Arc<ModelView>>>>,
+}
+
+impl SharedModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The most recent view, or `None` before the first sample point.
+    pub fn latest(&self) -> Option<Arc<ModelView>> {
+        self.slot.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replaces the current view with `view`.
+    pub fn publish(&self, view: ModelView) {
+        let view = Arc::new(view);
+        let old = self
+            .slot
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(view);
+        // Readers may still hold the previous view; if not, it is freed
+        // here, outside the lock.
+        drop(old);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::NaiveBayes;
+    use crate::streams::Stream;
+    use crate::streams::generators::{SeaFunction, SeaGenerator};
+    use std::thread;
+
+    #[test]
+    fn readers_predict_from_the_published_copy_on_their_own_thread() {
+        let mut sea = SeaGenerator::new(SeaFunction::F1, false, 0, None, 1).unwrap();
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(Arc::new(sea.header().clone()));
+        let probe = sea.next_instance().unwrap();
+        for _ in 0..500 {
+            nb.train_on_instance(sea.next_instance().unwrap().as_ref());
+        }
+
+        let shared = SharedModel::new();
+        assert!(shared.latest().is_none());
+        shared.publish(ModelView::capture(&nb, 500));
+        let expected = nb.get_votes_for_instance(probe.as_ref());
+
+        // Training goes on after the copy was taken.
+        for _ in 0..500 {
+            nb.train_on_instance(sea.next_instance().unwrap().as_ref());
+        }
+
+        let reader = shared.clone();
+        let votes = thread::spawn(move || {
+            let view = reader.latest().unwrap();
+            assert_eq!(view.instances_seen, 500);
+            view.predictor()
+                .unwrap()
+                .get_votes_for_instance(probe.as_ref())
+        })
+        .join()
+        .unwrap();
+        assert_eq!(votes, expected);
+    }
+}
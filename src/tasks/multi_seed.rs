@@ -0,0 +1,115 @@
+use crate::error::RivuError;
+use crate::evaluation::LearningCurve;
+use crate::tasks::Task;
+use std::io::Error;
+
+/// Builds the task for one repetition from its seed.
+pub type SeededTaskFactory = Box<dyn FnMut(u64) -> Result<Box<dyn Task>, Error>>;
+
+/// Repeats an evaluation task once per seed and aggregates the curves.
+///
+/// Repetitions run one after another; each is built lazily by the factory
+/// so only one task is alive at a time. [`Task::curve`] returns the
+/// point-by-point mean with `<name>_std` bands (see
+/// [`LearningCurve::mean_std`]); the individual curves are kept in
+/// [`runs`](Self::runs).
+pub struct MultiSeed {
+    seeds: Vec<u64>,
+    factory: SeededTaskFactory,
+    runs: Vec<LearningCurve>,
+    curve: LearningCurve,
+}
+
+impl MultiSeed {
+    pub fn new(seeds: Vec<u64>, factory: SeededTaskFactory) -> Result<Self, Error> {
+        if seeds.is_empty() {
+            return Err(RivuError::config("at least one seed is required").into());
+        }
+        Ok(Self {
+            seeds,
+            factory,
+            runs: Vec::new(),
+            curve: LearningCurve::default(),
+        })
+    }
+
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    /// Curves of the finished repetitions, in seed order.
+    pub fn runs(&self) -> &[LearningCurve] {
+        &self.runs
+    }
+}
+
+impl Task for MultiSeed {
+    fn run(&mut self) -> Result<(), Error> {
+        self.runs.clear();
+        for &seed in &self.seeds {
+            let mut task = (self.factory)(seed)?;
+            task.run()?;
+            self.runs.push(task.curve().clone());
+        }
+        let refs: Vec<&LearningCurve> = self.runs.iter().collect();
+        self.curve = LearningCurve::mean_std(&refs);
+        Ok(())
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.curve
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::Classifier;
+    use crate::evaluation::{BasicClassificationEvaluator, BasicEstimator, PerformanceEvaluator};
+    use crate::streams::Stream;
+    use crate::tasks::PrequentialEvaluator;
+    use crate::testing::{OracleClassifier, VecStream};
+    use std::io::ErrorKind;
+
+    fn factory() -> SeededTaskFactory {
+        Box::new(|seed| {
+            let n = 20 + seed as usize;
+            let s: Box<dyn Stream> = Box::new(VecStream::new((0..n).map(|i| i % 2).collect()));
+            let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+            let e: Box<dyn PerformanceEvaluator> =
+                Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2));
+            Ok(Box::new(PrequentialEvaluator::new(l, s, e, None, None, 10, 10)?) as Box<dyn Task>)
+        })
+    }
+
+    #[test]
+    fn requires_a_seed() {
+        let err = MultiSeed::new(vec![], factory()).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn runs_every_seed_and_aggregates() {
+        let mut task = MultiSeed::new(vec![0, 5, 10], factory()).unwrap();
+        task.run().unwrap();
+
+        assert_eq!(task.runs().len(), 3);
+        assert_eq!(task.runs()[2].latest().unwrap().instances_seen, 30);
+
+        // Shortest run (seed 0) has snapshots at 10, 20, 20.
+        assert_eq!(task.curve().len(), 3);
+        let first = &task.curve().as_slice()[0];
+        assert_eq!(first.accuracy, 1.0);
+        assert_eq!(first.extras["accuracy_std"], 0.0);
+    }
+
+    #[test]
+    fn factory_errors_propagate() {
+        let mut task = MultiSeed::new(
+            vec![1],
+            Box::new(|_| Err(Error::new(ErrorKind::NotFound, "missing"))),
+        )
+        .unwrap();
+        assert_eq!(task.run().unwrap_err().kind(), ErrorKind::NotFound);
+    }
+}
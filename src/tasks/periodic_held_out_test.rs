@@ -0,0 +1,231 @@
+use crate::classifiers::Classifier;
+use crate::core::header_compatibility::HeaderCompatibility;
+use crate::core::instances::Instance;
+use crate::evaluation::{ConfusionMatrix, LearningCurve, Measurement, PerformanceEvaluator};
+use crate::streams::{DriftMarker, Stream};
+use crate::tasks::runner::{RunState, bind_model_context, check_stream, model_measurements};
+use crate::tasks::{ProgressSender, Task};
+use std::io::{Error, ErrorKind, Write};
+
+/// Periodic held-out evaluation, following MOA's `EvaluatePeriodicHeldOutTest`.
+///
+/// Before training starts, `test_size` instances are cached as a fixed test
+/// set, taken either from a dedicated test stream or from the head of the
+/// training stream (those instances are then never trained on). The learner
+/// is trained on the stream and, every `test_frequency` training instances,
+/// the evaluator is reset and the cached test set is scored without
+/// training. Each point of the curve is therefore a holdout estimate, with
+/// `instances_seen` counting training instances.
+pub struct PeriodicHeldOutTest {
+    learner: Box<dyn Classifier>,
+    stream: Box<dyn Stream>,
+    test_stream: Option<Box<dyn Stream>>,
+    evaluator: Box<dyn PerformanceEvaluator>,
+    test_size: u64,
+
+    test_set: Vec<Box<dyn Instance>>,
+    state: RunState,
+}
+
+impl PeriodicHeldOutTest {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mut learner: Box<dyn Classifier>,
+        stream: Box<dyn Stream>,
+        test_stream: Option<Box<dyn Stream>>,
+        evaluator: Box<dyn PerformanceEvaluator>,
+        test_size: u64,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        test_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        if test_size == 0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "test_size must be > 0"));
+        }
+        if let Some(ts) = &test_stream {
+            HeaderCompatibility::check(stream.header(), ts.header()).into_result("test stream")?;
+        }
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            test_frequency,
+            mem_check_frequency,
+        )?;
+
+        bind_model_context(learner.as_mut(), stream.as_ref());
+
+        Ok(Self {
+            learner,
+            stream,
+            test_stream,
+            evaluator,
+            test_size,
+            test_set: Vec::new(),
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    /// Number of cached held-out instances.
+    pub fn test_set_len(&self) -> usize {
+        self.test_set.len()
+    }
+
+    fn cache_test_set(&mut self) {
+        let source: &mut dyn Stream = match self.test_stream.as_mut() {
+            Some(s) => s.as_mut(),
+            None => self.stream.as_mut(),
+        };
+        while (self.test_set.len() as u64) < self.test_size && source.has_more_instances() {
+            let Some(instance) = source.next_instance() else {
+                break;
+            };
+            self.test_set.push(instance);
+        }
+    }
+
+    fn evaluate_held_out(&mut self) {
+        self.evaluator.reset();
+        let mut votes = Vec::new();
+        for instance in &self.test_set {
+            self.learner.get_votes_into(instance.as_ref(), &mut votes);
+            self.evaluator.add_result(instance.as_ref(), &votes);
+        }
+        let perf = self.evaluator.performance();
+        self.state.push_snapshot(perf);
+    }
+}
+
+impl Task for PeriodicHeldOutTest {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.start();
+        self.cache_test_set();
+
+        let mut evaluated = false;
+        while self.stream.has_more_instances() && !self.state.limits_reached() {
+            let Some(instance) = self.stream.next_instance() else {
+                break;
+            };
+            self.learner.train_on_instance(instance.as_ref());
+            self.stream.recycle(instance);
+
+            self.state.count_instance();
+            evaluated = self.state.at_sample_point();
+            if evaluated {
+                self.evaluate_held_out();
+            }
+        }
+
+        check_stream(self.stream.as_mut())?;
+        if !evaluated {
+            self.evaluate_held_out();
+        }
+        Ok(())
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        model_measurements(self.learner.as_ref(), self.stream.as_ref())
+    }
+
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        self.stream.drift_markers()
+    }
+
+    fn confusion_matrix(&self) -> Option<ConfusionMatrix> {
+        self.evaluator.confusion_matrix().cloned()
+    }
+
+    fn save_model(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.learner.save_model(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{BasicClassificationEvaluator, BasicEstimator};
+    use crate::testing::{OracleClassifier, TrainSpyClassifier, VecStream};
+
+    fn evaluator() -> Box<dyn PerformanceEvaluator> {
+        Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2))
+    }
+
+    fn stream(n: usize) -> Box<dyn Stream> {
+        Box::new(VecStream::new((0..n).map(|i| i % 2).collect()))
+    }
+
+    #[test]
+    fn ctor_rejects_zero_test_size_and_frequency() {
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let err = PeriodicHeldOutTest::new(l, stream(10), None, evaluator(), 0, None, None, 5, 5)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let err = PeriodicHeldOutTest::new(l, stream(10), None, evaluator(), 5, None, None, 0, 5)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn head_of_stream_is_held_out_and_not_trained_on() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let mut task =
+            PeriodicHeldOutTest::new(l, stream(100), None, evaluator(), 20, None, None, 10, 10)
+                .unwrap();
+        task.run().unwrap();
+
+        assert_eq!(task.test_set_len(), 20);
+        assert_eq!(handle.count(), 80);
+        let seen: Vec<u64> = task.curve().iter().map(|s| s.instances_seen).collect();
+        assert_eq!(seen, (1..=8).map(|k| k * 10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn separate_test_stream_leaves_training_stream_intact() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let mut task = PeriodicHeldOutTest::new(
+            l,
+            stream(30),
+            Some(stream(50)),
+            evaluator(),
+            15,
+            None,
+            None,
+            7,
+            7,
+        )
+        .unwrap();
+        task.run().unwrap();
+
+        assert_eq!(task.test_set_len(), 15);
+        assert_eq!(handle.count(), 30);
+        assert_eq!(task.curve().latest().unwrap().instances_seen, 30);
+    }
+
+    #[test]
+    fn each_evaluation_scores_the_whole_test_set() {
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let mut task =
+            PeriodicHeldOutTest::new(l, stream(40), None, evaluator(), 10, None, None, 10, 10)
+                .unwrap();
+        task.run().unwrap();
+
+        for s in task.curve().iter() {
+            assert!((s.accuracy - 1.0).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use crate::classifiers::Classifier;
+use crate::error::RivuError;
+use crate::evaluation::{LearningCurve, Measurement, MetricKey, PerformanceEvaluator};
+use crate::streams::{DriftMarker, Stream};
+use crate::tasks::runner::{RunState, bind_model_context, check_stream};
+use crate::tasks::{ProgressSender, Task};
+use crate::utils::math::mean_std;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::io::Error;
+
+/// How each instance is split between the training folds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMethodology {
+    /// k-fold: instance `n` is left out of fold `n % k` and trains all others.
+    CrossValidation,
+    /// Instance `n` trains only fold `n % k`.
+    SplitValidation,
+    /// Each fold trains on the instance with a Poisson(1) weight.
+    Bootstrap,
+}
+
+/// Prequential cross-validation, following MOA's `EvaluatePrequentialCV`.
+///
+/// Keeps `k` independent learner/evaluator pairs. Every instance is first
+/// tested on all folds and then used to train each fold according to the
+/// [`ValidationMethodology`]. Snapshots report the mean of every metric
+/// across folds under its own name, plus `<name>_std` with the (population)
+/// standard deviation.
+pub struct PrequentialCV {
+    learners: Vec<Box<dyn Classifier>>,
+    evaluators: Vec<Box<dyn PerformanceEvaluator>>,
+    stream: Box<dyn Stream>,
+    methodology: ValidationMethodology,
+    rng: StdRng,
+
+    state: RunState,
+}
+
+impl PrequentialCV {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mut learners: Vec<Box<dyn Classifier>>,
+        stream: Box<dyn Stream>,
+        evaluators: Vec<Box<dyn PerformanceEvaluator>>,
+        methodology: ValidationMethodology,
+        seed: u64,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        if learners.len() < 2 {
+            return Err(RivuError::config("prequential CV needs at least 2 folds").into());
+        }
+        if learners.len() != evaluators.len() {
+            return Err(RivuError::config("number of learners and evaluators must match").into());
+        }
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
+
+        for learner in learners.iter_mut() {
+            bind_model_context(learner.as_mut(), stream.as_ref());
+        }
+
+        Ok(Self {
+            learners,
+            evaluators,
+            stream,
+            methodology,
+            rng: StdRng::seed_from_u64(seed),
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    pub fn folds(&self) -> usize {
+        self.learners.len()
+    }
+
+    /// Training weight multiplier of fold `fold` for instance number `n`.
+    fn fold_weight(&mut self, fold: usize, n: u64) -> f64 {
+        let k = self.learners.len() as u64;
+        match self.methodology {
+            ValidationMethodology::CrossValidation => {
+                if n % k == fold as u64 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            ValidationMethodology::SplitValidation => {
+                if n % k == fold as u64 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            ValidationMethodology::Bootstrap => poisson_one(&mut self.rng) as f64,
+        }
+    }
+
+    fn aggregate_performance(&self) -> Vec<Measurement> {
+        let mut by_name: BTreeMap<MetricKey, Vec<f64>> = BTreeMap::new();
+        let mut order: Vec<MetricKey> = Vec::new();
+        for evaluator in &self.evaluators {
+            for m in evaluator.performance() {
+                let values = by_name.entry(m.name).or_insert_with(|| {
+                    order.push(m.name);
+                    Vec::new()
+                });
+                if m.value.is_finite() {
+                    values.push(m.value);
+                }
+            }
+        }
+
+        let mut out = Vec::with_capacity(order.len() * 2);
+        for name in order {
+            let values = &by_name[&name];
+            let (mean, std) = mean_std(values);
+            out.push(Measurement::new(format!("{name}_std"), std));
+            out.push(Measurement::new(name, mean));
+        }
+        out
+    }
+
+    fn push_snapshot(&mut self) {
+        let perf = self.aggregate_performance();
+        self.state.push_snapshot(perf);
+    }
+}
+
+impl Task for PrequentialCV {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.start();
+
+        let mut votes = Vec::new();
+        while self.stream.has_more_instances() && !self.state.limits_reached() {
+            let Some(mut instance) = self.stream.next_instance() else {
+                break;
+            };
+
+            for (learner, evaluator) in self.learners.iter().zip(self.evaluators.iter_mut()) {
+                learner.get_votes_into(&*instance, &mut votes);
+                evaluator.add_result(&*instance, &votes);
+            }
+
+            let n = self.state.processed;
+            let base_weight = instance.weight();
+            for fold in 0..self.learners.len() {
+                let k = self.fold_weight(fold, n);
+                if k > 0.0 {
+                    instance.set_weight(base_weight * k)?;
+                    self.learners[fold].train_on_instance(instance.as_ref());
+                }
+            }
+
+            self.state.count_instance();
+            if self.state.at_sample_point() {
+                self.push_snapshot();
+            }
+        }
+
+        check_stream(self.stream.as_mut())?;
+        if !self.state.at_sample_point() || self.state.processed == 0 {
+            self.push_snapshot();
+        }
+        Ok(())
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        self.stream.drift_markers()
+    }
+}
+
+/// Draws from Poisson(1) using Knuth's multiplication method.
+fn poisson_one(rng: &mut StdRng) -> u32 {
+    let limit = (-1.0f64).exp();
+    let mut k = 0;
+    let mut p = rng.random::<f64>();
+    while p > limit {
+        k += 1;
+        p *= rng.random::<f64>();
+    }
+    k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{BasicClassificationEvaluator, BasicEstimator};
+    use crate::testing::{OracleClassifier, TrainSpyClassifier, TrainSpyHandle, VecStream};
+    use std::io::ErrorKind;
+
+    fn evaluators(k: usize) -> Vec<Box<dyn PerformanceEvaluator>> {
+        (0..k)
+            .map(|_| {
+                Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2))
+                    as Box<dyn PerformanceEvaluator>
+            })
+            .collect()
+    }
+
+    fn stream(n: usize) -> Box<dyn Stream> {
+        Box::new(VecStream::new((0..n).map(|i| i % 2).collect()))
+    }
+
+    fn spies(k: usize) -> (Vec<Box<dyn Classifier>>, Vec<TrainSpyHandle>) {
+        (0..k)
+            .map(|_| {
+                let (spy, handle) = TrainSpyClassifier::new();
+                (Box::new(spy) as Box<dyn Classifier>, handle)
+            })
+            .unzip()
+    }
+
+    fn run_with(
+        methodology: ValidationMethodology,
+        k: usize,
+        n: usize,
+    ) -> (PrequentialCV, Vec<TrainSpyHandle>) {
+        let (learners, handles) = spies(k);
+        let mut task = PrequentialCV::new(
+            learners,
+            stream(n),
+            evaluators(k),
+            methodology,
+            1,
+            None,
+            None,
+            10,
+            10,
+        )
+        .unwrap();
+        task.run().unwrap();
+        (task, handles)
+    }
+
+    #[test]
+    fn ctor_guards() {
+        let (learners, _) = spies(1);
+        let err = PrequentialCV::new(
+            learners,
+            stream(10),
+            evaluators(1),
+            ValidationMethodology::CrossValidation,
+            1,
+            None,
+            None,
+            10,
+            10,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let (learners, _) = spies(3);
+        let err = PrequentialCV::new(
+            learners,
+            stream(10),
+            evaluators(2),
+            ValidationMethodology::CrossValidation,
+            1,
+            None,
+            None,
+            10,
+            10,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn cross_validation_leaves_each_instance_out_of_one_fold() {
+        let (_, handles) = run_with(ValidationMethodology::CrossValidation, 4, 40);
+        for h in handles {
+            assert_eq!(h.count(), 30);
+        }
+    }
+
+    #[test]
+    fn split_validation_trains_each_instance_once() {
+        let (_, handles) = run_with(ValidationMethodology::SplitValidation, 4, 40);
+        let total: u64 = handles.iter().map(|h| h.count()).sum();
+        assert_eq!(total, 40);
+        for h in handles {
+            assert_eq!(h.count(), 10);
+        }
+    }
+
+    #[test]
+    fn bootstrap_is_deterministic_for_a_seed() {
+        let (_, a) = run_with(ValidationMethodology::Bootstrap, 3, 200);
+        let (_, b) = run_with(ValidationMethodology::Bootstrap, 3, 200);
+        let ca: Vec<u64> = a.iter().map(|h| h.count()).collect();
+        let cb: Vec<u64> = b.iter().map(|h| h.count()).collect();
+        assert_eq!(ca, cb);
+        assert!(ca.iter().all(|&c| c > 0 && c < 200));
+    }
+
+    #[test]
+    fn snapshots_report_mean_and_std_across_folds() {
+        let learners: Vec<Box<dyn Classifier>> = (0..3)
+            .map(|_| Box::new(OracleClassifier::default()) as Box<dyn Classifier>)
+            .collect();
+        let mut task = PrequentialCV::new(
+            learners,
+            stream(25),
+            evaluators(3),
+            ValidationMethodology::CrossValidation,
+            1,
+            None,
+            None,
+            10,
+            10,
+        )
+        .unwrap();
+        task.run().unwrap();
+
+        let seen: Vec<u64> = task.curve().iter().map(|s| s.instances_seen).collect();
+        assert_eq!(seen, vec![10, 20, 25]);
+        let last = task.curve().latest().unwrap();
+        assert!((last.accuracy - 1.0).abs() < 1e-12);
+        assert_eq!(last.extras.get("accuracy_std"), Some(&0.0));
+        assert!(last.extras.contains_key("kappa_std"));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::classifiers::Classifier;
+use crate::core::instances::Instance;
+use crate::evaluation::{
+    ConfusionMatrix, CurveCheckpoint, LearningCurve, Measurement, PerformanceEvaluator,
+    PredictionLog,
+};
+use crate::streams::{DriftMarker, Stream};
+use crate::tasks::runner::{RunState, bind_model_context, check_stream, model_measurements};
+use crate::tasks::{ProgressSender, Task};
+use std::collections::VecDeque;
+use std::io::{Error, Write};
+
+/// Prequential evaluation with verification latency, following MOA's
+/// `EvaluatePrequentialDelayed`.
+///
+/// Each instance is predicted as soon as it arrives, but its label is only
+/// revealed `label_delay` instances later: at that point the stored
+/// prediction is scored by the evaluator and the learner is trained on the
+/// instance. Pending instances are kept in a FIFO buffer of at most
+/// `label_delay` entries. `instances_seen` counts arrivals, so the evaluator
+/// lags the curve by the delay. When the stream ends the remaining labels
+/// are released before the final snapshot; stopping on a limit discards
+/// them. A delay of `0` is plain prequential evaluation.
+pub struct PrequentialDelayed {
+    learner: Box<dyn Classifier>,
+    stream: Box<dyn Stream>,
+    evaluator: Box<dyn PerformanceEvaluator>,
+    label_delay: u64,
+
+    pending: VecDeque<(Box<dyn Instance>, Vec<f64>)>,
+    state: RunState,
+}
+
+impl PrequentialDelayed {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mut learner: Box<dyn Classifier>,
+        stream: Box<dyn Stream>,
+        evaluator: Box<dyn PerformanceEvaluator>,
+        label_delay: u64,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
+
+        bind_model_context(learner.as_mut(), stream.as_ref());
+
+        Ok(Self {
+            learner,
+            stream,
+            evaluator,
+            label_delay,
+            pending: VecDeque::new(),
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    /// Writes one record per prediction (true label, predicted label, votes,
+    /// correctness) to `log`.
+    ///
+    /// Records are written when the prediction is made, not when the label
+    /// is revealed.
+    pub fn with_prediction_log(mut self, log: PredictionLog) -> Self {
+        self.state.prediction_log = Some(log);
+        self
+    }
+
+    /// Appends every snapshot to `checkpoint` as it is taken, so the curve
+    /// survives a crash mid-run.
+    pub fn with_curve_checkpoint(mut self, checkpoint: CurveCheckpoint) -> Self {
+        self.state.curve.checkpoint_to(checkpoint);
+        self
+    }
+
+    /// Number of predictions still waiting for their label.
+    pub fn pending_labels(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn release_oldest(&mut self) {
+        if let Some((instance, votes)) = self.pending.pop_front() {
+            self.evaluator.add_result(instance.as_ref(), &votes);
+            self.learner.train_on_instance(instance.as_ref());
+        }
+    }
+
+    fn push_snapshot(&mut self) {
+        let perf = self.evaluator.performance();
+        self.state.push_snapshot(perf);
+    }
+}
+
+impl Task for PrequentialDelayed {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.start();
+
+        while self.stream.has_more_instances() && !self.state.limits_reached() {
+            let Some(instance) = self.stream.next_instance() else {
+                break;
+            };
+
+            let votes = self.learner.get_votes_for_instance(instance.as_ref());
+            self.state.log_prediction(instance.as_ref(), &votes)?;
+            self.pending.push_back((instance, votes));
+            if self.pending.len() as u64 > self.label_delay {
+                self.release_oldest();
+            }
+
+            self.state.count_instance();
+            if self.state.at_sample_point() {
+                self.push_snapshot();
+            }
+        }
+
+        check_stream(self.stream.as_mut())?;
+        if !self.stream.has_more_instances() {
+            while !self.pending.is_empty() {
+                self.release_oldest();
+            }
+        }
+        self.push_snapshot();
+        self.state.finish()
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        model_measurements(self.learner.as_ref(), self.stream.as_ref())
+    }
+
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        self.stream.drift_markers()
+    }
+
+    fn confusion_matrix(&self) -> Option<ConfusionMatrix> {
+        self.evaluator.confusion_matrix().cloned()
+    }
+
+    fn save_model(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.learner.save_model(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{BasicClassificationEvaluator, BasicEstimator};
+    use crate::testing::{OracleClassifier, TrainSpyClassifier, VecStream};
+
+    fn evaluator() -> Box<dyn PerformanceEvaluator> {
+        Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2))
+    }
+
+    fn stream(n: usize) -> Box<dyn Stream> {
+        Box::new(VecStream::new((0..n).map(|i| i % 2).collect()))
+    }
+
+    #[test]
+    fn training_lags_arrivals_by_the_delay() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let mut task =
+            PrequentialDelayed::new(l, stream(100), evaluator(), 15, Some(40), None, 10, 10)
+                .unwrap();
+        task.run().unwrap();
+
+        assert_eq!(handle.count(), 25);
+        assert_eq!(task.pending_labels(), 15);
+        assert_eq!(task.curve().latest().unwrap().instances_seen, 40);
+    }
+
+    #[test]
+    fn stream_end_releases_pending_labels() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let mut task =
+            PrequentialDelayed::new(l, stream(30), evaluator(), 8, None, None, 10, 10).unwrap();
+        task.run().unwrap();
+
+        assert_eq!(handle.count(), 30);
+        assert_eq!(task.pending_labels(), 0);
+    }
+
+    #[test]
+    fn zero_delay_matches_prequential_scoring() {
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let mut task =
+            PrequentialDelayed::new(l, stream(50), evaluator(), 0, None, None, 10, 10).unwrap();
+        task.run().unwrap();
+
+        assert_eq!(task.pending_labels(), 0);
+        assert!((task.curve().latest().unwrap().accuracy - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn evaluator_has_no_results_until_first_label_arrives() {
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let mut task =
+            PrequentialDelayed::new(l, stream(1000), evaluator(), 50, Some(20), None, 10, 10)
+                .unwrap();
+        task.run().unwrap();
+
+        for s in task.curve().iter() {
+            assert!(s.accuracy.is_nan());
+        }
+    }
+}
@@ -1,32 +1,23 @@
 use crate::classifiers::Classifier;
-use crate::core::instance_header::InstanceHeader;
-use crate::evaluation::{LearningCurve, PerformanceEvaluator, Snapshot};
-use crate::streams::Stream;
-use crate::utils::system::current_rss_gb;
-use std::io::{Error, ErrorKind};
-use std::sync::Arc;
-use std::sync::mpsc::Sender;
-use std::time::Instant;
+use crate::drift::ChangeDetector;
+use crate::evaluation::{
+    ConfusionMatrix, CurveCheckpoint, LearningCurve, Measurement, PerformanceEvaluator,
+    PredictionLog,
+};
+use crate::streams::{DriftMarker, Stream};
+use crate::tasks::runner::{
+    RunState, bind_model_context, check_stream, model_measurements, with_source,
+};
+use crate::tasks::{ProgressSender, SharedModel, SnapshotSchedule, Task, TaskControl};
+use std::io::{Error, Write};
+use std::sync::mpsc::Receiver;
 
 pub struct PrequentialEvaluator {
     learner: Box<dyn Classifier>,
     stream: Box<dyn Stream>,
     evaluator: Box<dyn PerformanceEvaluator>,
 
-    curve: LearningCurve,
-
-    max_instances: Option<u64>,
-    max_seconds: Option<u64>,
-    sample_frequency: u64,
-    mem_check_frequency: u64,
-
-    processed: u64,
-    start_time: Instant,
-    last_sample_time: Instant,
-    last_mem_sample: Instant,
-    ram_hours: f64,
-
-    progress_tx: Option<Sender<Snapshot>>,
+    state: RunState,
 }
 
 impl PrequentialEvaluator {
@@ -39,153 +30,195 @@ impl PrequentialEvaluator {
         sample_frequency: u64,
         mem_check_frequency: u64,
     ) -> Result<Self, Error> {
-        if sample_frequency == 0 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "sample_frequency must be > 0",
-            ));
-        }
-        if mem_check_frequency == 0 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "mem_check_frequency must be > 0",
-            ));
-        }
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
 
-        let header = stream.header();
-        let header_arc = Arc::new(InstanceHeader::new(
-            header.relation_name().to_string(),
-            header.attributes.clone(),
-            header.class_index(),
-        ));
-        learner.set_model_context(Arc::clone(&header_arc));
+        bind_model_context(learner.as_mut(), stream.as_ref());
 
         Ok(Self {
             learner,
             stream,
             evaluator,
-            curve: LearningCurve::default(),
-            max_instances,
-            max_seconds,
-            sample_frequency,
-            mem_check_frequency,
-            processed: 0,
-            start_time: Instant::now(),
-            last_sample_time: Instant::now(),
-            last_mem_sample: Instant::now(),
-            ram_hours: 0.0,
-            progress_tx: None,
+            state,
         })
     }
 }
 
 impl PrequentialEvaluator {
-    pub fn with_progress(mut self, tx: Sender<Snapshot>) -> Self {
-        self.progress_tx = Some(tx);
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    /// Decides when snapshots are taken during the run (see
+    /// [`SnapshotSchedule`]); every `sample_frequency` instances by default.
+    pub fn with_snapshot_schedule(mut self, schedule: SnapshotSchedule) -> Self {
+        self.state.schedule = schedule;
         self
     }
 
+    /// Trains on the first `n` instances without passing them to the
+    /// evaluator, so the cold-start phase does not weigh on the cumulative
+    /// metrics. Those instances still count towards `instances_seen`.
+    pub fn with_warmup(mut self, n: u64) -> Self {
+        self.state.warmup_instances = n;
+        self
+    }
+
+    /// Feeds the 0/1 error of every evaluated prediction to `detector` and
+    /// records where it signals a change (see [`Task::drift_detections`]).
+    pub fn with_drift_detector(mut self, detector: Box<dyn ChangeDetector>) -> Self {
+        self.state.drift_detector = Some(detector);
+        self
+    }
+
+    /// Writes one record per prediction (true label, predicted label, votes,
+    /// correctness) to `log`.
+    pub fn with_prediction_log(mut self, log: PredictionLog) -> Self {
+        self.state.prediction_log = Some(log);
+        self
+    }
+
+    /// Appends every snapshot to `checkpoint` as it is taken, so the curve
+    /// survives a crash mid-run.
+    pub fn with_curve_checkpoint(mut self, checkpoint: CurveCheckpoint) -> Self {
+        self.state.curve.checkpoint_to(checkpoint);
+        self
+    }
+
+    /// Publishes a copy of the learner to `model` at each sample point and
+    /// when the run ends (see [`SharedModel`]).
+    pub fn with_model_view(mut self, model: SharedModel) -> Self {
+        self.state.model_view = Some(model);
+        self
+    }
+
+    /// Accepts [`TaskControl`] commands while running.
+    pub fn with_control(mut self, rx: Receiver<TaskControl>) -> Self {
+        self.state.control_rx = Some(rx);
+        self
+    }
+
+    /// Runs the prequential loop. Streams that prefer it (see
+    /// [`Stream::prefers_prefetch`]) are read on a producer thread, so that
+    /// decoding a file overlaps with prediction and training; generators
+    /// are stepped in lock step with the learner.
     pub fn run(&mut self) -> Result<(), Error> {
-        self.start_time = Instant::now();
-        self.last_sample_time = self.start_time;
-        self.last_mem_sample = self.start_time;
+        self.state.schedule.validate()?;
+        self.state.start();
 
-        while self.stream.has_more_instances() {
-            if let Some(n) = self.max_instances {
-                if self.processed >= n {
-                    break;
+        let limit = self.state.remaining_instances();
+        let Self {
+            learner,
+            stream,
+            evaluator,
+            state,
+        } = self;
+        let mut votes = Vec::new();
+        with_source(stream.as_mut(), limit, |source| -> Result<(), Error> {
+            loop {
+                while state.poll_control() {
+                    state.push_snapshot(evaluator.performance());
                 }
-            }
-            if let Some(s) = self.max_seconds {
-                if self.start_time.elapsed().as_secs() >= s {
+                if state.limits_reached() {
                     break;
                 }
-            }
-            let Some(instance) = self.stream.next_instance() else {
-                break;
-            };
-            self.processed += 1;
-
-            // TODO: Remove this
-            if self.processed == 581012 {
-                println!("last element");
-            }
-
-            let votes = self.learner.get_votes_for_instance(&*instance);
+                let Some(instance) = source.next() else {
+                    break;
+                };
 
-            self.evaluator.add_result(&*instance, votes);
+                if !state.in_warmup() {
+                    learner.get_votes_into(&*instance, &mut votes);
+                    state.log_prediction(&*instance, &votes)?;
+                    state.watch_drift(&*instance, &votes);
+                    evaluator.add_result(&*instance, &votes);
+                }
 
-            self.learner.train_on_instance(instance.as_ref());
+                learner.train_on_instance(instance.as_ref());
+                source.recycle(instance);
 
-            if self.processed % self.mem_check_frequency == 0 {
-                self.bump_ram_hours();
-            }
-            if self.processed % self.sample_frequency == 0 {
-                self.push_snapshot();
+                state.count_instance();
+                if state.at_sample_point() {
+                    state.push_sampled_snapshot(evaluator.performance());
+                    state.publish_model(learner.as_ref());
+                }
             }
-        }
+            Ok(())
+        })?;
+        check_stream(self.stream.as_mut())?;
 
         self.push_snapshot();
-        Ok(())
+        self.state.publish_model(self.learner.as_ref());
+        self.state.finish()
     }
 
     pub fn curve(&self) -> &LearningCurve {
-        &self.curve
+        &self.state.curve
     }
 
     fn push_snapshot(&mut self) {
-        use std::collections::BTreeMap;
-
-        let secs = self.start_time.elapsed().as_secs_f64();
         let perf = self.evaluator.performance();
+        self.state.push_snapshot(perf);
+    }
+}
 
-        let mut acc = f64::NAN;
-        let mut kap = f64::NAN;
-        let mut extras = BTreeMap::new();
-
-        for m in perf {
-            let key: &str = m.name.as_ref();
-            match key {
-                "accuracy" => acc = m.value,
-                "kappa" => kap = m.value,
-                _ => {
-                    extras.insert(key.to_string(), m.value);
-                }
-            }
-        }
+impl Task for PrequentialEvaluator {
+    fn run(&mut self) -> Result<(), Error> {
+        PrequentialEvaluator::run(self)
+    }
 
-        let snapshot = Snapshot {
-            instances_seen: self.processed,
-            accuracy: acc,
-            kappa: kap,
-            ram_hours: self.ram_hours,
-            seconds: secs,
-            extras,
-        };
+    fn curve(&self) -> &LearningCurve {
+        PrequentialEvaluator::curve(self)
+    }
 
-        if let Some(tx) = &self.progress_tx {
-            let _ = tx.send(snapshot.clone());
-        }
+    fn model_measurements(&self) -> Vec<Measurement> {
+        model_measurements(self.learner.as_ref(), self.stream.as_ref())
+    }
 
-        self.curve.push(snapshot);
-        self.last_sample_time = Instant::now();
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        self.stream.drift_markers()
     }
 
-    fn bump_ram_hours(&mut self) {
-        let now = Instant::now();
-        let duration = now - self.last_mem_sample;
-        let dt_h = duration.as_secs_f64() / 3600.0;
-        self.last_mem_sample = now;
+    fn drift_detections(&self) -> Vec<u64> {
+        self.state.detections.clone()
+    }
 
-        let rss_gb = current_rss_gb().unwrap_or(0.0);
-        self.ram_hours += rss_gb * dt_h;
+    fn confusion_matrix(&self) -> Option<ConfusionMatrix> {
+        self.evaluator.confusion_matrix().cloned()
+    }
+
+    fn save_model(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.learner.save_model(writer)
+    }
+
+    fn set_control(&mut self, rx: Receiver<TaskControl>) -> Result<(), Error> {
+        self.state.control_rx = Some(rx);
+        Ok(())
+    }
+
+    fn cancelled(&self) -> bool {
+        self.state.cancelled
+    }
+
+    fn set_model_view(&mut self, model: SharedModel) -> Result<(), Error> {
+        self.state.model_view = Some(model);
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::classifiers::NaiveBayes;
+    use crate::core::instance_header::InstanceHeader;
+    use crate::core::instances::Instance;
     use crate::evaluation::{BasicClassificationEvaluator, BasicEstimator, PerformanceEvaluator};
+    use crate::streams::arff::{ArffFileStream, ParseMode};
+    use crate::streams::csv::CsvFileStream;
     use crate::testing::{ClassifierNoneVotes, OracleClassifier, TrainSpyClassifier, VecStream};
     use std::io::ErrorKind;
 
@@ -212,6 +245,33 @@ mod tests {
         assert_eq!(err.kind(), ErrorKind::InvalidInput);
     }
 
+    #[test]
+    fn snapshots_carry_eta_against_limits() {
+        let s: Box<dyn Stream> =
+            Box::new(VecStream::new((0..100).map(|i| (i % 2) as usize).collect()));
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let e: Box<dyn PerformanceEvaluator> =
+            Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2));
+
+        let mut pq = PrequentialEvaluator::new(l, s, e, Some(40), None, 10, 10).unwrap();
+        pq.run().unwrap();
+
+        let first = pq.curve().as_slice()[0].eta.unwrap();
+        assert!((first.fraction - 0.25).abs() < 1e-12);
+        assert_eq!(first.instances_remaining, Some(30));
+        let last = pq.curve().latest().unwrap().eta.unwrap();
+        assert_eq!(last.fraction, 1.0);
+        assert_eq!(last.instances_remaining, Some(0));
+
+        let s: Box<dyn Stream> = Box::new(VecStream::new(vec![0, 1]));
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let e: Box<dyn PerformanceEvaluator> =
+            Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2));
+        let mut pq = PrequentialEvaluator::new(l, s, e, None, None, 10, 10).unwrap();
+        pq.run().unwrap();
+        assert!(pq.curve().latest().unwrap().eta.is_none());
+    }
+
     #[test]
     fn periodic_and_final_snapshots() {
         let s: Box<dyn Stream> =
@@ -313,4 +373,232 @@ mod tests {
 
         assert_eq!(handle.count(), 37);
     }
+
+    fn prequential(n: usize) -> PrequentialEvaluator {
+        let s: Box<dyn Stream> = Box::new(VecStream::new((0..n).map(|i| i % 2).collect()));
+        let l: Box<dyn Classifier> = Box::new(OracleClassifier::default());
+        let e: Box<dyn PerformanceEvaluator> =
+            Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2));
+        PrequentialEvaluator::new(l, s, e, None, None, 10, 10).unwrap()
+    }
+
+    #[test]
+    fn cancel_stops_with_a_final_snapshot() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut pq = prequential(100).with_control(rx);
+        tx.send(TaskControl::Cancel).unwrap();
+        pq.run().unwrap();
+
+        assert!(pq.cancelled());
+        assert_eq!(pq.curve().len(), 1);
+        assert_eq!(pq.curve().latest().unwrap().instances_seen, 0);
+    }
+
+    #[test]
+    fn snapshot_now_while_paused_then_resume() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut pq = prequential(20).with_control(rx);
+        for cmd in [
+            TaskControl::Pause,
+            TaskControl::SnapshotNow,
+            TaskControl::SnapshotNow,
+            TaskControl::Resume,
+        ] {
+            tx.send(cmd).unwrap();
+        }
+        pq.run().unwrap();
+
+        assert!(!pq.cancelled());
+        let seen: Vec<u64> = pq.curve().iter().map(|s| s.instances_seen).collect();
+        assert_eq!(seen, vec![0, 0, 10, 20, 20]);
+    }
+
+    #[test]
+    fn pause_blocks_until_resumed_from_another_thread() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut pq = prequential(50).with_control(rx);
+        tx.send(TaskControl::Pause).unwrap();
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            tx.send(TaskControl::Resume).unwrap();
+        });
+        pq.run().unwrap();
+        sender.join().unwrap();
+
+        let last = pq.curve().latest().unwrap();
+        assert_eq!(last.instances_seen, 50);
+        assert!(last.seconds < 0.1);
+    }
+
+    #[test]
+    fn dropped_control_channel_is_ignored() {
+        let (tx, rx) = std::sync::mpsc::channel::<TaskControl>();
+        drop(tx);
+        let mut pq = prequential(30);
+        assert!(Task::set_control(&mut pq, rx).is_ok());
+        pq.run().unwrap();
+        assert_eq!(pq.curve().latest().unwrap().instances_seen, 30);
+    }
+
+    #[test]
+    fn snapshot_schedules_pick_the_sample_points() {
+        let seen = |schedule| {
+            let mut pq = prequential(150).with_snapshot_schedule(schedule);
+            pq.run().unwrap();
+            pq.curve()
+                .iter()
+                .map(|s| s.instances_seen)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            seen(SnapshotSchedule::LogSpaced { per_decade: 10 }),
+            vec![
+                1, 2, 3, 4, 5, 6, 8, 10, 13, 16, 20, 25, 32, 40, 50, 63, 79, 100, 126, 150
+            ]
+        );
+        assert_eq!(
+            seen(SnapshotSchedule::Interval { seconds: 3600.0 }),
+            vec![150]
+        );
+        // The oracle's accuracy and kappa never move after the first sample.
+        assert_eq!(
+            seen(SnapshotSchedule::OnChange { min_delta: 0.01 }),
+            vec![10, 150]
+        );
+    }
+
+    #[test]
+    fn an_invalid_snapshot_schedule_fails_the_run() {
+        let mut pq =
+            prequential(10).with_snapshot_schedule(SnapshotSchedule::LogSpaced { per_decade: 0 });
+        assert_eq!(pq.run().unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn model_view_follows_the_sample_points() {
+        let model = SharedModel::new();
+        let mut pq = prequential(35).with_model_view(model.clone());
+        pq.run().unwrap();
+
+        let view = model.latest().unwrap();
+        assert_eq!(view.instances_seen, 35);
+        // The oracle cannot be saved, so only its statistics are shared.
+        assert!(view.state.is_none());
+        assert!(view.predictor().is_err());
+    }
+
+    #[test]
+    fn warmup_instances_train_but_are_not_evaluated() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let s: Box<dyn Stream> = Box::new(VecStream::new((0..30).map(|i| i % 2).collect()));
+        let e: Box<dyn PerformanceEvaluator> =
+            Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2));
+        let mut pq = PrequentialEvaluator::new(Box::new(spy), s, e, None, None, 10, 10)
+            .unwrap()
+            .with_warmup(20);
+        pq.run().unwrap();
+
+        assert_eq!(handle.count(), 30);
+        let acc: Vec<bool> = pq.curve().iter().map(|s| s.accuracy.is_nan()).collect();
+        assert_eq!(acc, vec![true, true, false, false]);
+    }
+
+    #[test]
+    fn prediction_log_gets_one_record_per_evaluated_instance() {
+        use crate::evaluation::PredictionLogFormat;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("preds.csv");
+        let log = PredictionLog::create(&path, PredictionLogFormat::Csv).unwrap();
+        let mut pq = prequential(12).with_warmup(2).with_prediction_log(log);
+        pq.run().unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1 + 10);
+        assert!(lines[1].starts_with("2,,,0,0,true,"));
+    }
+
+    #[test]
+    fn curve_checkpoint_holds_every_snapshot_when_the_run_ends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("curve.jsonl");
+        let checkpoint = CurveCheckpoint::create(&path).unwrap();
+        let mut pq = prequential(30).with_curve_checkpoint(checkpoint);
+        pq.run().unwrap();
+
+        let saved = CurveCheckpoint::read(&path).unwrap();
+        assert_eq!(saved.len(), pq.curve().len());
+        assert_eq!(saved.latest().unwrap().instances_seen, 30);
+    }
+
+    /// Same stream, read in step with the learner.
+    struct Inline(CsvFileStream);
+
+    impl Stream for Inline {
+        fn header(&self) -> &InstanceHeader {
+            self.0.header()
+        }
+        fn has_more_instances(&self) -> bool {
+            self.0.has_more_instances()
+        }
+        fn next_instance(&mut self) -> Option<Box<dyn Instance>> {
+            self.0.next_instance()
+        }
+        fn restart(&mut self) -> Result<(), Error> {
+            self.0.restart()
+        }
+    }
+
+    #[test]
+    fn prefetched_file_streams_give_the_same_curve() {
+        let mut csv = String::from("x,noise,class\n");
+        for i in 0..1500 {
+            let class = if i % 7 < 3 { "a" } else { "b" };
+            csv.push_str(&format!("{},{},{class}\n", i % 7, (i * 31) % 11));
+        }
+        let file = tempfile::NamedTempFile::with_suffix(".csv").unwrap();
+        std::fs::write(file.path(), csv).unwrap();
+        let open = || CsvFileStream::new(file.path().to_path_buf(), None).unwrap();
+        assert!(open().prefers_prefetch());
+
+        let curve = |stream: Box<dyn Stream>, max: Option<u64>| {
+            let l: Box<dyn Classifier> = Box::new(NaiveBayes::new());
+            let e: Box<dyn PerformanceEvaluator> =
+                Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2));
+            let mut pq = PrequentialEvaluator::new(l, stream, e, max, None, 100, 100).unwrap();
+            pq.run().unwrap();
+            pq.curve()
+                .iter()
+                .map(|s| (s.instances_seen, s.accuracy, s.kappa))
+                .collect::<Vec<_>>()
+        };
+        for max in [None, Some(700)] {
+            let prefetched = curve(Box::new(open()), max);
+            assert_eq!(prefetched, curve(Box::new(Inline(open())), max));
+            assert_eq!(prefetched.last().unwrap().0, max.unwrap_or(1500));
+        }
+    }
+
+    #[test]
+    fn a_strict_stream_fails_the_run_at_its_first_malformed_row() {
+        let mut arff =
+            String::from("@relation r\n@attribute x numeric\n@attribute class {a,b}\n@data\n");
+        for i in 0..300 {
+            arff.push_str(if i == 250 { "1,c\n" } else { "1,a\n" });
+        }
+        let file = tempfile::NamedTempFile::with_suffix(".arff").unwrap();
+        std::fs::write(file.path(), arff).unwrap();
+        let stream = ArffFileStream::new(file.path().to_path_buf(), 1)
+            .unwrap()
+            .with_parse_mode(ParseMode::Strict);
+
+        let l: Box<dyn Classifier> = Box::new(NaiveBayes::new());
+        let e: Box<dyn PerformanceEvaluator> =
+            Box::new(BasicClassificationEvaluator::<BasicEstimator>::new_with_default_flags(2));
+        let mut pq =
+            PrequentialEvaluator::new(l, Box::new(stream), e, None, None, 100, 100).unwrap();
+        let err = pq.run().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line 255, column 3:"), "{err}");
+    }
 }
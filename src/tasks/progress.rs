@@ -0,0 +1,347 @@
+use crate::evaluation::Snapshot;
+use std::collections::VecDeque;
+use std::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// What a [`ProgressSender`] does with a message when its channel is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Wait until the receiver makes room. Nothing is lost, but a receiver
+    /// that falls behind slows the run down to its pace.
+    Block,
+    /// Discard the message being sent, keeping the ones already queued.
+    Drop,
+    /// Replace the newest queued message with the one being sent, so the
+    /// receiver still ends up with the latest message (the final snapshot
+    /// of a run) while the queue stays within its capacity.
+    #[default]
+    Coalesce,
+}
+
+/// Capacity and overflow policy of a progress channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgressOptions {
+    /// Messages queued before the `overflow` policy applies, at least one.
+    pub capacity: usize,
+    pub overflow: Overflow,
+}
+
+impl Default for ProgressOptions {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            overflow: Overflow::Coalesce,
+        }
+    }
+}
+
+/// Creates a bounded channel for progress messages, snapshots by default.
+///
+/// Unlike an unbounded `mpsc` channel, a run sampling faster than its
+/// renderer can draw cannot grow the queue without limit: once `capacity`
+/// messages are waiting, `options.overflow` decides what happens to the
+/// next one. Senders can be cloned; the receiver reports disconnection
+/// once every sender is gone and the queue is drained.
+pub fn progress_channel<T>(options: ProgressOptions) -> (ProgressSender<T>, ProgressReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            senders: 1,
+            receiver: true,
+            overflowed: 0,
+        }),
+        capacity: options.capacity.max(1),
+        overflow: options.overflow,
+        sent: Condvar::new(),
+        received: Condvar::new(),
+    });
+    (
+        ProgressSender {
+            shared: Arc::clone(&shared),
+        },
+        ProgressReceiver { shared },
+    )
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    capacity: usize,
+    overflow: Overflow,
+    /// Signalled when a message is queued or the last sender leaves.
+    sent: Condvar,
+    /// Signalled when a message is taken or the receiver leaves.
+    received: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug)]
+struct State<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    receiver: bool,
+    overflowed: u64,
+}
+
+/// Sending half of a [`progress_channel`].
+#[derive(Debug)]
+pub struct ProgressSender<T = Snapshot> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> ProgressSender<T> {
+    /// Queues `msg`, applying the overflow policy when the channel is full.
+    ///
+    /// Fails only when the receiver is gone, handing `msg` back. A message
+    /// dropped or coalesced away because of the policy is not an error.
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        let shared = &*self.shared;
+        let mut state = shared.lock();
+        loop {
+            if !state.receiver {
+                return Err(SendError(msg));
+            }
+            if state.queue.len() < shared.capacity {
+                state.queue.push_back(msg);
+                break;
+            }
+            match shared.overflow {
+                Overflow::Block => {
+                    state = shared
+                        .received
+                        .wait(state)
+                        .unwrap_or_else(|e| e.into_inner());
+                }
+                Overflow::Drop => {
+                    state.overflowed += 1;
+                    return Ok(());
+                }
+                Overflow::Coalesce => {
+                    state.overflowed += 1;
+                    if let Some(newest) = state.queue.back_mut() {
+                        *newest = msg;
+                    }
+                    break;
+                }
+            }
+        }
+        drop(state);
+        shared.sent.notify_one();
+        Ok(())
+    }
+}
+
+impl<T> Clone for ProgressSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for ProgressSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            self.shared.sent.notify_all();
+        }
+    }
+}
+
+/// Receiving half of a [`progress_channel`], with the methods of an `mpsc`
+/// receiver.
+#[derive(Debug)]
+pub struct ProgressReceiver<T = Snapshot> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> ProgressReceiver<T> {
+    /// Waits for the next message; fails once every sender is gone and the
+    /// queue is empty.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(msg) = self.take(&mut state) {
+                return Ok(msg);
+            }
+            if state.senders == 0 {
+                return Err(RecvError);
+            }
+            state = self
+                .shared
+                .sent
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.lock();
+        match self.take(&mut state) {
+            Some(msg) => Ok(msg),
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(msg) = self.take(&mut state) {
+                return Ok(msg);
+            }
+            if state.senders == 0 {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            state = self
+                .shared
+                .sent
+                .wait_timeout(state, left)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Blocking iterator over the messages, ending once every sender is gone.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.recv().ok())
+    }
+
+    /// Messages dropped or coalesced away so far because the channel was
+    /// full.
+    pub fn overflowed(&self) -> u64 {
+        self.shared.lock().overflowed
+    }
+
+    fn take(&self, state: &mut State<T>) -> Option<T> {
+        let msg = state.queue.pop_front()?;
+        self.shared.received.notify_one();
+        Some(msg)
+    }
+}
+
+impl<T> Drop for ProgressReceiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receiver = false;
+        state.queue.clear();
+        drop(state);
+        self.shared.received.notify_all();
+    }
+}
+
+impl<T> IntoIterator for ProgressReceiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+/// Owning blocking iterator over a [`ProgressReceiver`].
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: ProgressReceiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn options(capacity: usize, overflow: Overflow) -> ProgressOptions {
+        ProgressOptions { capacity, overflow }
+    }
+
+    #[test]
+    fn a_full_channel_drops_or_coalesces_without_growing() {
+        let (tx, rx) = progress_channel(options(3, Overflow::Drop));
+        for i in 0..10 {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+        assert_eq!(rx.overflowed(), 7);
+        assert_eq!(rx.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let (tx, rx) = progress_channel(options(3, Overflow::Coalesce));
+        for i in 0..10 {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+        assert_eq!(rx.overflowed(), 7);
+        assert_eq!(rx.into_iter().collect::<Vec<_>>(), vec![0, 1, 9]);
+    }
+
+    #[test]
+    fn blocking_senders_wait_for_the_receiver_and_lose_nothing() {
+        let (tx, rx) = progress_channel(options(2, Overflow::Block));
+        let producers: Vec<_> = (0..3)
+            .map(|p| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for i in 0..100 {
+                        tx.send(p * 1000 + i).unwrap();
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+        let mut got: Vec<i32> = rx.iter().collect();
+        for p in producers {
+            p.join().unwrap();
+        }
+        assert_eq!(rx.overflowed(), 0);
+        got.sort_unstable();
+        let expected: Vec<i32> = (0..3)
+            .flat_map(|p| (0..100).map(move |i| p * 1000 + i))
+            .collect();
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn disconnection_is_reported_on_both_ends() {
+        let (tx, rx) = progress_channel::<u8>(ProgressOptions::default());
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(5)),
+            Err(RecvTimeoutError::Timeout)
+        );
+        tx.send(1).unwrap();
+        drop(tx);
+        assert_eq!(rx.recv_timeout(Duration::from_millis(5)), Ok(1));
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(5)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+
+        let (tx, rx) = progress_channel(options(1, Overflow::Block));
+        tx.send(1).unwrap();
+        let blocked = thread::spawn(move || tx.send(2));
+        thread::sleep(Duration::from_millis(20));
+        drop(rx);
+        assert_eq!(blocked.join().unwrap(), Err(SendError(2)));
+    }
+}
@@ -0,0 +1,430 @@
+use crate::classifiers::Classifier;
+use crate::core::instances::Instance;
+use crate::drift::ChangeDetector;
+use crate::error::RivuError;
+use crate::evaluation::{
+    Eta, LearningCurve, Measurement, MetricKey, PredictionLog, Snapshot, argmax,
+};
+use crate::streams::Stream;
+use crate::tasks::schedule::{changed, next_log_point};
+use crate::tasks::{ModelView, ProgressSender, SharedModel, SnapshotSchedule, TaskControl};
+use crate::utils::system::current_rss_gb;
+use std::collections::BTreeMap;
+use std::io::Error;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Instant;
+
+/// Instances a prefetching producer hands over per message.
+const PREFETCH_BATCH: usize = 256;
+/// Batches a prefetching producer may read ahead of the learner.
+const PREFETCH_DEPTH: usize = 4;
+
+/// Bookkeeping shared by the evaluation tasks.
+///
+/// Owns the stop conditions (instance and time limits), the sampling
+/// schedule and memory-check cadence, RAM-hours accounting, the learning curve, and the
+/// optional progress and control channels. Tasks drive their own loop and call into this
+/// to decide when to stop and when to record a [`Snapshot`].
+pub(crate) struct RunState {
+    pub(crate) curve: LearningCurve,
+
+    pub(crate) max_instances: Option<u64>,
+    pub(crate) max_seconds: Option<u64>,
+    pub(crate) sample_frequency: u64,
+    pub(crate) mem_check_frequency: u64,
+    pub(crate) warmup_instances: u64,
+    pub(crate) schedule: SnapshotSchedule,
+    log_step: u32,
+    next_log_point: u64,
+
+    pub(crate) processed: u64,
+    pub(crate) start_time: Instant,
+    pub(crate) last_sample_time: Instant,
+    pub(crate) last_mem_sample: Instant,
+    pub(crate) ram_hours: f64,
+
+    pub(crate) progress_tx: Option<ProgressSender>,
+    pub(crate) control_rx: Option<Receiver<TaskControl>>,
+    pub(crate) paused_at: Option<Instant>,
+    pub(crate) cancelled: bool,
+    pub(crate) prediction_log: Option<PredictionLog>,
+    pub(crate) model_view: Option<SharedModel>,
+    pub(crate) drift_detector: Option<Box<dyn ChangeDetector>>,
+    /// Instance counts at which `drift_detector` signalled a change.
+    pub(crate) detections: Vec<u64>,
+}
+
+impl RunState {
+    pub(crate) fn new(
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        if sample_frequency == 0 {
+            return Err(RivuError::config("sample_frequency must be > 0").into());
+        }
+        if mem_check_frequency == 0 {
+            return Err(RivuError::config("mem_check_frequency must be > 0").into());
+        }
+
+        Ok(Self {
+            curve: LearningCurve::default(),
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+            warmup_instances: 0,
+            schedule: SnapshotSchedule::Fixed,
+            log_step: 0,
+            next_log_point: 1,
+            processed: 0,
+            start_time: Instant::now(),
+            last_sample_time: Instant::now(),
+            last_mem_sample: Instant::now(),
+            ram_hours: 0.0,
+            progress_tx: None,
+            control_rx: None,
+            paused_at: None,
+            cancelled: false,
+            prediction_log: None,
+            model_view: None,
+            drift_detector: None,
+            detections: Vec::new(),
+        })
+    }
+
+    /// Resets the clocks; call right before entering the main loop.
+    pub(crate) fn start(&mut self) {
+        self.start_time = Instant::now();
+        self.last_sample_time = self.start_time;
+        self.last_mem_sample = self.start_time;
+    }
+
+    /// Instances left before `max_instances` is reached.
+    pub(crate) fn remaining_instances(&self) -> Option<u64> {
+        self.max_instances.map(|n| n.saturating_sub(self.processed))
+    }
+
+    /// Whether the instance or time limit has been reached, or the run was
+    /// cancelled.
+    pub(crate) fn limits_reached(&self) -> bool {
+        if self.cancelled {
+            return true;
+        }
+        if let Some(n) = self.max_instances
+            && self.processed >= n
+        {
+            return true;
+        }
+        if let Some(s) = self.max_seconds
+            && self.start_time.elapsed().as_secs() >= s
+        {
+            return true;
+        }
+        false
+    }
+
+    /// Counts one processed instance and updates RAM-hours on the
+    /// memory-check cadence.
+    pub(crate) fn count_instance(&mut self) {
+        self.processed += 1;
+        if self.processed.is_multiple_of(self.mem_check_frequency) {
+            self.bump_ram_hours();
+        }
+    }
+
+    /// Whether the next instance is still in the warm-up period, i.e. should
+    /// be trained on without being evaluated.
+    pub(crate) fn in_warmup(&self) -> bool {
+        self.processed < self.warmup_instances
+    }
+
+    /// Records the prediction for the instance about to be counted, if a
+    /// prediction log is attached.
+    pub(crate) fn log_prediction(
+        &mut self,
+        instance: &dyn Instance,
+        votes: &[f64],
+    ) -> Result<(), Error> {
+        match self.prediction_log.as_mut() {
+            Some(log) => log.record(self.processed, instance, votes),
+            None => Ok(()),
+        }
+    }
+
+    /// Feeds whether `votes` missed the class of the instance about to be
+    /// counted to the drift detector, if one is attached, and records the
+    /// instance count when it signals a change. Unlabelled instances are
+    /// skipped.
+    pub(crate) fn watch_drift(&mut self, instance: &dyn Instance, votes: &[f64]) {
+        let Some(detector) = self.drift_detector.as_mut() else {
+            return;
+        };
+        let Some(truth) = instance.class_value() else {
+            return;
+        };
+        let correct = argmax(votes).is_some_and(|p| p as f64 == truth);
+        detector.add_element(if correct { 0.0 } else { 1.0 });
+        if detector.detected_change() {
+            self.detections.push(self.processed + 1);
+        }
+    }
+
+    /// Flushes the prediction log and closes the curve checkpoint, if any;
+    /// call once the loop is done.
+    pub(crate) fn finish(&mut self) -> Result<(), Error> {
+        if let Some(log) = self.prediction_log.as_mut() {
+            log.flush()?;
+        }
+        self.curve.close_checkpoint()
+    }
+
+    /// Whether the instance just counted falls on the sampling schedule.
+    pub(crate) fn at_sample_point(&self) -> bool {
+        match self.schedule {
+            SnapshotSchedule::Fixed | SnapshotSchedule::OnChange { .. } => {
+                self.processed.is_multiple_of(self.sample_frequency)
+            }
+            SnapshotSchedule::LogSpaced { .. } => self.processed >= self.next_log_point,
+            SnapshotSchedule::Interval { seconds } => {
+                self.last_sample_time.elapsed().as_secs_f64() >= seconds
+            }
+        }
+    }
+
+    /// Records the snapshot due at a sample point: like
+    /// [`push_snapshot`](Self::push_snapshot), except that an
+    /// [`OnChange`](SnapshotSchedule::OnChange) schedule skips it when the
+    /// metrics have not moved.
+    pub(crate) fn push_sampled_snapshot(&mut self, perf: Vec<Measurement>) {
+        match self.schedule {
+            SnapshotSchedule::LogSpaced { per_decade } => {
+                self.next_log_point =
+                    next_log_point(&mut self.log_step, per_decade, self.processed);
+            }
+            SnapshotSchedule::OnChange { min_delta } => {
+                let snapshot = self.snapshot_from(perf);
+                if self
+                    .curve
+                    .as_slice()
+                    .last()
+                    .is_none_or(|last| changed(last, &snapshot, min_delta))
+                {
+                    self.record(snapshot);
+                }
+                return;
+            }
+            SnapshotSchedule::Fixed | SnapshotSchedule::Interval { .. } => {}
+        }
+        self.push_snapshot(perf);
+    }
+
+    /// Builds a snapshot from `perf`, sends it to the progress channel (if
+    /// any), and appends it to the curve.
+    pub(crate) fn push_snapshot(&mut self, perf: Vec<Measurement>) {
+        let snapshot = self.snapshot_from(perf);
+        self.record(snapshot);
+    }
+
+    fn record(&mut self, snapshot: Snapshot) {
+        if let Some(tx) = &self.progress_tx {
+            let _ = tx.send(snapshot.clone());
+        }
+
+        self.curve.push(snapshot);
+        self.last_sample_time = Instant::now();
+    }
+
+    /// Publishes a copy of `learner` to the shared model view, if any.
+    pub(crate) fn publish_model(&self, learner: &dyn Classifier) {
+        if let Some(view) = &self.model_view {
+            view.publish(ModelView::capture(learner, self.processed));
+        }
+    }
+
+    /// Turns evaluator measurements into a [`Snapshot`] at the current position.
+    ///
+    /// `accuracy` and `kappa` map to the fixed fields; everything else goes
+    /// to `extras`.
+    pub(crate) fn snapshot_from(&self, perf: Vec<Measurement>) -> Snapshot {
+        let secs = self.start_time.elapsed().as_secs_f64();
+
+        let mut acc = f64::NAN;
+        let mut kap = f64::NAN;
+        let mut extras = BTreeMap::new();
+
+        for m in perf {
+            match m.name {
+                MetricKey::ACCURACY => acc = m.value,
+                MetricKey::KAPPA => kap = m.value,
+                key => {
+                    extras.insert(key, m.value);
+                }
+            }
+        }
+
+        Snapshot {
+            instances_seen: self.processed,
+            accuracy: acc,
+            kappa: kap,
+            ram_hours: self.ram_hours,
+            seconds: secs,
+            extras,
+            eta: Eta::estimate(self.processed, secs, self.max_instances, self.max_seconds),
+        }
+    }
+
+    /// Handles pending control commands; call before each instance.
+    ///
+    /// Returns `true` when a snapshot was requested, in which case the task
+    /// should record one and call this again before processing the next
+    /// instance. While paused this blocks until a command arrives. Losing the
+    /// control channel resumes the run.
+    pub(crate) fn poll_control(&mut self) -> bool {
+        loop {
+            let Some(rx) = &self.control_rx else {
+                return false;
+            };
+            let msg = match self.paused_at {
+                Some(_) => rx.recv().map_err(|_| TryRecvError::Disconnected),
+                None => rx.try_recv(),
+            };
+            match msg {
+                Ok(TaskControl::Pause) => {
+                    self.paused_at.get_or_insert_with(Instant::now);
+                }
+                Ok(TaskControl::Resume) => self.resume(),
+                Ok(TaskControl::Cancel) => {
+                    self.resume();
+                    self.cancelled = true;
+                    return false;
+                }
+                Ok(TaskControl::SnapshotNow) => return true,
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => {
+                    self.control_rx = None;
+                    self.resume();
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Shifts the clocks past the pause so it is not counted as run time.
+    fn resume(&mut self) {
+        if let Some(at) = self.paused_at.take() {
+            let paused = at.elapsed();
+            self.start_time += paused;
+            self.last_sample_time += paused;
+            self.last_mem_sample += paused;
+        }
+    }
+
+    fn bump_ram_hours(&mut self) {
+        let now = Instant::now();
+        let duration = now - self.last_mem_sample;
+        let dt_h = duration.as_secs_f64() / 3600.0;
+        self.last_mem_sample = now;
+
+        let rss_gb = current_rss_gb().unwrap_or(0.0);
+        self.ram_hours += rss_gb * dt_h;
+    }
+}
+
+/// Where a run loop takes its instances from: the stream itself, or a
+/// producer thread reading it ahead (see [`Stream::prefers_prefetch`]).
+pub(crate) enum Source<'a> {
+    Direct(&'a mut dyn Stream),
+    Prefetched {
+        rx: Receiver<Vec<Box<dyn Instance>>>,
+        batch: std::vec::IntoIter<Box<dyn Instance>>,
+    },
+}
+
+impl Source<'_> {
+    pub(crate) fn next(&mut self) -> Option<Box<dyn Instance>> {
+        match self {
+            Source::Direct(stream) => stream
+                .has_more_instances()
+                .then(|| stream.next_instance())
+                .flatten(),
+            Source::Prefetched { rx, batch } => loop {
+                if let Some(instance) = batch.next() {
+                    return Some(instance);
+                }
+                *batch = rx.recv().ok()?.into_iter();
+            },
+        }
+    }
+
+    /// Hands a used instance back to a direct stream; prefetched ones are
+    /// dropped.
+    pub(crate) fn recycle(&mut self, instance: Box<dyn Instance>) {
+        if let Source::Direct(stream) = self {
+            stream.recycle(instance);
+        }
+    }
+}
+
+/// Runs `consume` over the instances of `stream`, reading them on a
+/// producer thread when the stream prefers it. The producer stops after
+/// `limit` instances, at the end of the stream, or as soon as `consume`
+/// returns, so it never outlives the call.
+pub(crate) fn with_source<R>(
+    stream: &mut dyn Stream,
+    limit: Option<u64>,
+    consume: impl FnOnce(&mut Source<'_>) -> R,
+) -> R {
+    if !stream.prefers_prefetch() {
+        return consume(&mut Source::Direct(stream));
+    }
+    thread::scope(|scope| {
+        let (tx, rx) = mpsc::sync_channel(PREFETCH_DEPTH);
+        scope.spawn(move || {
+            let mut remaining = limit.unwrap_or(u64::MAX);
+            while remaining > 0 && stream.has_more_instances() {
+                let batch = stream.next_batch(remaining.min(PREFETCH_BATCH as u64) as usize);
+                if batch.is_empty() {
+                    break;
+                }
+                remaining -= batch.len() as u64;
+                if tx.send(batch).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut source = Source::Prefetched {
+            rx,
+            batch: Vec::new().into_iter(),
+        };
+        let out = consume(&mut source);
+        // Dropping the receiver unblocks a producer waiting to send.
+        drop(source);
+        out
+    })
+}
+
+/// Copies the stream header and hands it to the learner as model context.
+pub(crate) fn bind_model_context(learner: &mut dyn Classifier, stream: &dyn Stream) {
+    learner.set_model_context(Arc::new(stream.header().clone()));
+}
+
+/// The learner's model measurements, then the stream's own (see
+/// [`Stream::measurements`]).
+pub(crate) fn model_measurements(
+    learner: &dyn Classifier,
+    stream: &dyn Stream,
+) -> Vec<Measurement> {
+    let mut out = learner.model_measurements();
+    out.extend(stream.measurements());
+    out
+}
+
+/// Fails with the error that ended `stream` early, if any, so a run over a
+/// stream that gave up on its input does not pass for a complete one.
+pub(crate) fn check_stream(stream: &mut dyn Stream) -> Result<(), Error> {
+    stream.take_error().map_or(Ok(()), Err)
+}
@@ -0,0 +1,133 @@
+use crate::error::RivuError;
+use crate::evaluation::Snapshot;
+use serde::{Deserialize, Serialize};
+
+/// When a run records a snapshot on its own, besides the final one and
+/// those asked for through [`TaskControl::SnapshotNow`](crate::tasks::TaskControl).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SnapshotSchedule {
+    /// Every `sample_frequency` instances.
+    #[default]
+    Fixed,
+    /// At instance counts evenly spaced on a log scale, `per_decade` of
+    /// them between each power of ten (1, 2, 3, 4, 5, 6, 8, 10, 13, 16, ...
+    /// for ten per decade), so the early learning phase is sampled densely and
+    /// a long run adds only a few snapshots per order of magnitude.
+    LogSpaced { per_decade: u32 },
+    /// Whenever at least `seconds` of run time have passed since the last
+    /// snapshot.
+    Interval { seconds: f64 },
+    /// Every `sample_frequency` instances, but only when accuracy or kappa
+    /// moved by at least `min_delta` since the last recorded snapshot, so
+    /// a plateau produces no snapshots until it ends.
+    OnChange { min_delta: f64 },
+}
+
+impl SnapshotSchedule {
+    pub fn validate(&self) -> Result<(), RivuError> {
+        match *self {
+            SnapshotSchedule::Fixed => Ok(()),
+            SnapshotSchedule::LogSpaced { per_decade: 0 } => Err(RivuError::config(
+                "log-spaced snapshots need per_decade > 0",
+            )),
+            SnapshotSchedule::Interval { seconds } if !(seconds.is_finite() && seconds > 0.0) => {
+                Err(RivuError::config(format!(
+                    "snapshot interval must be a positive number of seconds, got {seconds}"
+                )))
+            }
+            SnapshotSchedule::OnChange { min_delta }
+                if !(min_delta.is_finite() && min_delta >= 0.0) =>
+            {
+                Err(RivuError::config(format!(
+                    "min_delta must be a non-negative number, got {min_delta}"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// First log-spaced instance count above `after`, for `per_decade` points
+/// per decade. `step` is the index of the point on the log scale, advanced
+/// past every point that rounds to `after` or below.
+pub(crate) fn next_log_point(step: &mut u32, per_decade: u32, after: u64) -> u64 {
+    loop {
+        let point = 10f64.powf(f64::from(*step) / f64::from(per_decade)).round() as u64;
+        if point > after {
+            return point;
+        }
+        *step += 1;
+    }
+}
+
+/// Whether `snapshot` differs from `last` enough for
+/// [`SnapshotSchedule::OnChange`]: accuracy or kappa moved by at least
+/// `min_delta`, or one of them became defined or undefined.
+pub(crate) fn changed(last: &Snapshot, snapshot: &Snapshot, min_delta: f64) -> bool {
+    let moved = |a: f64, b: f64| match (a.is_nan(), b.is_nan()) {
+        (true, true) => false,
+        (false, false) => (a - b).abs() >= min_delta,
+        _ => true,
+    };
+    moved(last.accuracy, snapshot.accuracy) || moved(last.kappa, snapshot.kappa)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_points_are_spaced_per_decade_and_strictly_increasing() {
+        let (mut step, mut last) = (0, 0);
+        let points: Vec<u64> = (0..18)
+            .map(|_| {
+                last = next_log_point(&mut step, 10, last);
+                last
+            })
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                1, 2, 3, 4, 5, 6, 8, 10, 13, 16, 20, 25, 32, 40, 50, 63, 79, 100
+            ]
+        );
+        assert_eq!(next_log_point(&mut step, 10, 999_999), 1_000_000);
+        assert_eq!(next_log_point(&mut 0, 1, 0), 1);
+        assert_eq!(next_log_point(&mut 0, 1, 1), 10);
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        assert!(SnapshotSchedule::Fixed.validate().is_ok());
+        assert!(
+            SnapshotSchedule::LogSpaced { per_decade: 0 }
+                .validate()
+                .is_err()
+        );
+        assert!(
+            SnapshotSchedule::Interval { seconds: 0.0 }
+                .validate()
+                .is_err()
+        );
+        assert!(
+            SnapshotSchedule::Interval { seconds: f64::NAN }
+                .validate()
+                .is_err()
+        );
+        assert!(
+            SnapshotSchedule::OnChange { min_delta: -0.1 }
+                .validate()
+                .is_err()
+        );
+        assert!(
+            SnapshotSchedule::OnChange { min_delta: 0.0 }
+                .validate()
+                .is_ok()
+        );
+
+        let json = r#"{"type":"log-spaced","per_decade":5}"#;
+        let parsed: SnapshotSchedule = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed, SnapshotSchedule::LogSpaced { per_decade: 5 });
+    }
+}
@@ -0,0 +1,72 @@
+use crate::error::RivuError;
+use crate::evaluation::{ConfusionMatrix, LearningCurve, Measurement};
+use crate::streams::DriftMarker;
+use crate::tasks::{SharedModel, TaskControl};
+use std::io::{Error, Write};
+use std::sync::mpsc::Receiver;
+
+/// A runnable evaluation task that produces a learning curve.
+pub trait Task {
+    /// Runs the task to completion (or until its limits are reached).
+    fn run(&mut self) -> Result<(), Error>;
+
+    /// Snapshots recorded so far.
+    fn curve(&self) -> &LearningCurve;
+
+    /// Statistics of the trained model (size, training weight, ...) and of
+    /// its stream, for tasks that train a single learner. Default: none.
+    fn model_measurements(&self) -> Vec<Measurement> {
+        Vec::new()
+    }
+
+    /// Ground-truth drifts of the stream the task evaluates on (see
+    /// [`Stream::drift_markers`](crate::streams::Stream::drift_markers)),
+    /// for reports to line up with the curve. Default: none.
+    fn drift_markers(&self) -> Vec<DriftMarker> {
+        Vec::new()
+    }
+
+    /// Instance counts at which the task's drift detector signalled a
+    /// change, in order, for reports to line up with the drift markers.
+    /// Default: none.
+    fn drift_detections(&self) -> Vec<u64> {
+        Vec::new()
+    }
+
+    /// Counts of true against predicted classes, for classification tasks
+    /// whose evaluator keeps them. Default: none.
+    fn confusion_matrix(&self) -> Option<ConfusionMatrix> {
+        None
+    }
+
+    /// Writes the trained model to `writer`, for tasks that train a single
+    /// learner.
+    ///
+    /// Returns `ErrorKind::Unsupported` when the task has no such learner or
+    /// the learner cannot be saved.
+    fn save_model(&self, _writer: &mut dyn Write) -> Result<(), Error> {
+        Err(RivuError::unsupported("task has no model to save").into())
+    }
+
+    /// Whether the last run was stopped by [`TaskControl::Cancel`]. Tasks
+    /// without a control channel are never cancelled.
+    fn cancelled(&self) -> bool {
+        false
+    }
+
+    /// Installs a channel of [`TaskControl`] commands polled during `run`.
+    ///
+    /// Tasks that cannot be controlled return `ErrorKind::Unsupported`.
+    fn set_control(&mut self, _rx: Receiver<TaskControl>) -> Result<(), Error> {
+        Err(RivuError::unsupported("task does not accept control commands").into())
+    }
+
+    /// Publishes a [`ModelView`](crate::tasks::ModelView) of the model to
+    /// `model` at each sample point and when the run ends, for readers on
+    /// other threads.
+    ///
+    /// Tasks that do not train a classifier return `ErrorKind::Unsupported`.
+    fn set_model_view(&mut self, _model: SharedModel) -> Result<(), Error> {
+        Err(RivuError::unsupported("task has no model to share").into())
+    }
+}
@@ -0,0 +1,257 @@
+use crate::classifiers::Classifier;
+use crate::evaluation::{LearningCurve, Measurement, MetricKey};
+use crate::streams::Stream;
+use crate::tasks::runner::{RunState, bind_model_context, check_stream, model_measurements};
+use crate::tasks::{ProgressSender, Task};
+use std::hint::black_box;
+use std::io::{Error, Write};
+use std::time::{Duration, Instant};
+
+/// Measures raw train/predict throughput of a learner on a stream.
+///
+/// Every instance is predicted and then trained on, exactly like the
+/// prequential loop, but no evaluator is involved: votes are discarded, so
+/// the timings only cover the learner (and the stream). Snapshots leave
+/// accuracy and kappa as `NaN` and report in `extras`:
+/// - `predict_seconds` / `train_seconds`: cumulative time spent in each call;
+/// - `instances_per_second`: instances per second of learner time;
+/// - `window_instances_per_second`: the same, over the last sample window.
+///
+/// With a [batch size](Self::with_batch_size) above one, instances are read
+/// with [`Stream::next_batch`] and go through
+/// [`Classifier::get_votes_for_batch`] and [`Classifier::train_on_batch`];
+/// each batch is predicted before any of it is trained on.
+pub struct ThroughputBenchmark {
+    learner: Box<dyn Classifier>,
+    stream: Box<dyn Stream>,
+    batch_size: usize,
+
+    predict_time: Duration,
+    train_time: Duration,
+    window_start: (u64, Duration),
+
+    state: RunState,
+}
+
+impl ThroughputBenchmark {
+    pub fn new(
+        mut learner: Box<dyn Classifier>,
+        stream: Box<dyn Stream>,
+        max_instances: Option<u64>,
+        max_seconds: Option<u64>,
+        sample_frequency: u64,
+        mem_check_frequency: u64,
+    ) -> Result<Self, Error> {
+        let state = RunState::new(
+            max_instances,
+            max_seconds,
+            sample_frequency,
+            mem_check_frequency,
+        )?;
+
+        bind_model_context(learner.as_mut(), stream.as_ref());
+
+        Ok(Self {
+            learner,
+            stream,
+            batch_size: 1,
+            predict_time: Duration::ZERO,
+            train_time: Duration::ZERO,
+            window_start: (0, Duration::ZERO),
+            state,
+        })
+    }
+
+    pub fn with_progress(mut self, tx: ProgressSender) -> Self {
+        self.state.progress_tx = Some(tx);
+        self
+    }
+
+    /// Predicts and trains on `batch_size` instances per call (at least one).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Cumulative time spent in `get_votes_for_instance`.
+    pub fn predict_time(&self) -> Duration {
+        self.predict_time
+    }
+
+    /// Cumulative time spent in `train_on_instance`.
+    pub fn train_time(&self) -> Duration {
+        self.train_time
+    }
+
+    fn push_snapshot(&mut self) {
+        let busy = self.predict_time + self.train_time;
+        let (window_n, window_busy) = self.window_start;
+        let perf = vec![
+            Measurement::new(MetricKey::PREDICT_SECONDS, self.predict_time.as_secs_f64()),
+            Measurement::new(MetricKey::TRAIN_SECONDS, self.train_time.as_secs_f64()),
+            Measurement::new(
+                MetricKey::INSTANCES_PER_SECOND,
+                rate(self.state.processed, busy),
+            ),
+            Measurement::new(
+                MetricKey::WINDOW_INSTANCES_PER_SECOND,
+                rate(self.state.processed - window_n, busy - window_busy),
+            ),
+        ];
+        self.window_start = (self.state.processed, busy);
+        self.state.push_snapshot(perf);
+    }
+
+    fn run_instances(&mut self) {
+        let mut votes = Vec::new();
+        while self.stream.has_more_instances() && !self.state.limits_reached() {
+            let Some(instance) = self.stream.next_instance() else {
+                break;
+            };
+
+            let t0 = Instant::now();
+            self.learner.get_votes_into(instance.as_ref(), &mut votes);
+            black_box(&votes);
+            let t1 = Instant::now();
+            self.learner.train_on_instance(instance.as_ref());
+            let t2 = Instant::now();
+            self.stream.recycle(instance);
+            self.predict_time += t1 - t0;
+            self.train_time += t2 - t1;
+
+            self.state.count_instance();
+            if self.state.at_sample_point() {
+                self.push_snapshot();
+            }
+        }
+    }
+
+    fn run_batches(&mut self) {
+        while self.stream.has_more_instances() && !self.state.limits_reached() {
+            let n = self
+                .state
+                .remaining_instances()
+                .map_or(self.batch_size, |r| r.min(self.batch_size as u64) as usize);
+            let batch = self.stream.next_batch(n);
+            if batch.is_empty() {
+                break;
+            }
+
+            let t0 = Instant::now();
+            black_box(self.learner.get_votes_for_batch(&batch));
+            let t1 = Instant::now();
+            self.learner.train_on_batch(&batch);
+            let t2 = Instant::now();
+            self.predict_time += t1 - t0;
+            self.train_time += t2 - t1;
+
+            for instance in batch {
+                self.stream.recycle(instance);
+                self.state.count_instance();
+                if self.state.at_sample_point() {
+                    self.push_snapshot();
+                }
+            }
+        }
+    }
+}
+
+impl Task for ThroughputBenchmark {
+    fn run(&mut self) -> Result<(), Error> {
+        self.state.start();
+
+        if self.batch_size > 1 {
+            self.run_batches();
+        } else {
+            self.run_instances();
+        }
+
+        check_stream(self.stream.as_mut())?;
+        if !self.state.at_sample_point() || self.state.processed == 0 {
+            self.push_snapshot();
+        }
+        Ok(())
+    }
+
+    fn curve(&self) -> &LearningCurve {
+        &self.state.curve
+    }
+
+    fn model_measurements(&self) -> Vec<Measurement> {
+        model_measurements(self.learner.as_ref(), self.stream.as_ref())
+    }
+
+    fn save_model(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        self.learner.save_model(writer)
+    }
+}
+
+fn rate(instances: u64, busy: Duration) -> f64 {
+    let secs = busy.as_secs_f64();
+    if secs > 0.0 {
+        instances as f64 / secs
+    } else {
+        f64::NAN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TrainSpyClassifier, VecStream};
+
+    fn stream(n: usize) -> Box<dyn Stream> {
+        Box::new(VecStream::new((0..n).map(|i| i % 2).collect()))
+    }
+
+    #[test]
+    fn trains_every_instance_and_reports_only_timings() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let mut task = ThroughputBenchmark::new(l, stream(25), None, None, 10, 10).unwrap();
+        task.run().unwrap();
+
+        assert_eq!(handle.count(), 25);
+        let seen: Vec<u64> = task.curve().iter().map(|s| s.instances_seen).collect();
+        assert_eq!(seen, vec![10, 20, 25]);
+
+        let last = task.curve().latest().unwrap();
+        assert!(last.accuracy.is_nan());
+        let keys: Vec<&str> = last.extras.keys().map(|k| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "instances_per_second",
+                "predict_seconds",
+                "train_seconds",
+                "window_instances_per_second"
+            ]
+        );
+        assert!(last.extras["train_seconds"] >= 0.0);
+    }
+
+    #[test]
+    fn respects_instance_limit() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let mut task = ThroughputBenchmark::new(l, stream(100), Some(30), None, 10, 10).unwrap();
+        task.run().unwrap();
+
+        assert_eq!(handle.count(), 30);
+        assert_eq!(task.curve().len(), 3);
+    }
+
+    #[test]
+    fn batches_cover_the_same_instances_and_sample_points() {
+        let (spy, handle) = TrainSpyClassifier::new();
+        let l: Box<dyn Classifier> = Box::new(spy);
+        let mut task = ThroughputBenchmark::new(l, stream(100), Some(45), None, 10, 10)
+            .unwrap()
+            .with_batch_size(8);
+        task.run().unwrap();
+
+        assert_eq!(handle.count(), 45);
+        let seen: Vec<u64> = task.curve().iter().map(|s| s.instances_seen).collect();
+        assert_eq!(seen, vec![10, 20, 30, 40, 45]);
+    }
+}
@@ -0,0 +1,304 @@
+use crate::experiment::{Discrepancy, ExperimentManifest, OutputPaths, TIMING_FIELDS};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+/// Set (to anything) to rewrite the golden files from the current code
+/// instead of checking against them.
+pub const UPDATE_GOLDEN_ENV: &str = "RIVU_UPDATE_GOLDEN";
+
+/// Directory of the checked-in golden files.
+pub const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/testing/golden");
+
+/// How far a rerun's metric may drift from its golden value: it matches
+/// when within `absolute` or within `relative` times the golden value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Default for Tolerance {
+    /// Close enough to absorb floating-point differences between platforms,
+    /// far too close for a behavior change to slip through.
+    fn default() -> Self {
+        Self {
+            absolute: 1e-9,
+            relative: 1e-9,
+        }
+    }
+}
+
+impl Tolerance {
+    fn allows(&self, expected: f64, actual: f64) -> bool {
+        let diff = (expected - actual).abs();
+        diff <= self.absolute || diff <= self.relative * expected.abs()
+    }
+}
+
+/// Contents of a golden file: the configuration of the run it records and
+/// the snapshots it produced, without their timings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct GoldenFile {
+    task: Value,
+    seeds: Vec<u64>,
+    snapshots: Vec<Value>,
+}
+
+/// A fixed seed/learner/stream/evaluator run whose learning curve must keep
+/// matching the golden values checked in under [`GOLDEN_DIR`], so that a
+/// change to a learner or evaluator that alters its results fails a test
+/// instead of passing silently.
+///
+/// Every snapshot field is compared except the timings (`seconds`,
+/// `ram_hours`, `eta`) and the columns passed to
+/// [`ignoring`](Self::ignoring). The golden file also records the run's
+/// configuration, for the reader; it is not compared, so that adding a
+/// parameter with a default does not invalidate every golden file.
+///
+/// To regenerate after an intended behavior change, rerun the tests with
+/// [`UPDATE_GOLDEN_ENV`] set and review the diff of the golden files:
+///
+/// ```text
+/// RIVU_UPDATE_GOLDEN=1 cargo test golden
+/// ```
+pub struct GoldenRun {
+    name: String,
+    manifest: ExperimentManifest,
+    tolerance: Tolerance,
+    ignored: Vec<String>,
+    dir: PathBuf,
+}
+
+impl GoldenRun {
+    /// The run of `manifest` recorded as `<name>.json`. Outputs configured
+    /// in the manifest are not written.
+    pub fn new(name: impl Into<String>, mut manifest: ExperimentManifest) -> Self {
+        manifest.output = OutputPaths::default();
+        Self {
+            name: name.into(),
+            manifest,
+            tolerance: Tolerance::default(),
+            ignored: Vec::new(),
+            dir: PathBuf::from(GOLDEN_DIR),
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Leaves the curve column `column` out of the comparison, for values
+    /// that legitimately vary between machines (e.g. memory estimates).
+    pub fn ignoring(mut self, column: impl Into<String>) -> Self {
+        self.ignored.push(column.into());
+        self
+    }
+
+    /// Reads and writes the golden file in `dir` instead of [`GOLDEN_DIR`].
+    pub fn in_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(format!("{}.json", self.name))
+    }
+
+    /// Runs the experiment and returns what its golden file holds.
+    fn record(&self) -> Result<GoldenFile, Error> {
+        let curve = self.manifest.run(None).map_err(Error::other)?;
+        let snapshots = curve
+            .iter()
+            .map(|s| {
+                let mut v = s.to_json();
+                if let Value::Object(obj) = &mut v {
+                    for field in TIMING_FIELDS {
+                        obj.remove(field);
+                    }
+                    for column in &self.ignored {
+                        obj.remove(column);
+                    }
+                }
+                v
+            })
+            .collect();
+        Ok(GoldenFile {
+            task: serde_json::to_value(&self.manifest.task).map_err(Error::other)?,
+            seeds: self.manifest.seeds.clone(),
+            snapshots,
+        })
+    }
+
+    /// Writes the golden file from a fresh run.
+    pub fn update(&self) -> Result<(), Error> {
+        let golden = self.record()?;
+        fs::create_dir_all(&self.dir)?;
+        let mut text = serde_json::to_string_pretty(&golden).map_err(Error::other)?;
+        text.push('\n');
+        fs::write(self.path(), text)
+    }
+
+    /// Runs the experiment and lists where it departs from the golden
+    /// file, by JSON pointer (`/snapshots/3/kappa`). Empty when it matches.
+    pub fn compare(&self) -> Result<Vec<Discrepancy>, Error> {
+        let golden = read(&self.path())?;
+        let rerun = self.record()?;
+        let mut out = Vec::new();
+        if golden.snapshots.len() != rerun.snapshots.len() {
+            out.push(Discrepancy {
+                field: "/snapshots".into(),
+                expected: golden.snapshots.len().into(),
+                actual: rerun.snapshots.len().into(),
+            });
+        }
+        for (i, (expected, actual)) in golden.snapshots.iter().zip(&rerun.snapshots).enumerate() {
+            self.diff_snapshot(expected, actual, &format!("/snapshots/{i}"), &mut out);
+        }
+        Ok(out)
+    }
+
+    /// Panics unless the run matches its golden file, listing every
+    /// discrepancy. With [`UPDATE_GOLDEN_ENV`] set, rewrites the file
+    /// instead.
+    pub fn check(&self) {
+        let path = self.path();
+        if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+            self.update()
+                .unwrap_or_else(|e| panic!("cannot write {}: {e}", path.display()));
+            return;
+        }
+        if !path.is_file() {
+            panic!(
+                "no golden file {}; run with {UPDATE_GOLDEN_ENV}=1 to record it",
+                path.display()
+            );
+        }
+        let discrepancies = self
+            .compare()
+            .unwrap_or_else(|e| panic!("golden run '{}' failed: {e}", self.name));
+        if !discrepancies.is_empty() {
+            let lines: Vec<String> = discrepancies
+                .iter()
+                .map(|d| format!("  {}: expected {}, got {}", d.field, d.expected, d.actual))
+                .collect();
+            panic!(
+                "golden run '{}' departs from {}:\n{}\nif the change is intended, rerun with \
+                 {UPDATE_GOLDEN_ENV}=1 and commit the updated file",
+                self.name,
+                path.display(),
+                lines.join("\n")
+            );
+        }
+    }
+
+    fn diff_snapshot(
+        &self,
+        expected: &Value,
+        actual: &Value,
+        at: &str,
+        out: &mut Vec<Discrepancy>,
+    ) {
+        let empty = serde_json::Map::new();
+        let expected = expected.as_object().unwrap_or(&empty);
+        let actual = actual.as_object().unwrap_or(&empty);
+        let keys = expected
+            .keys()
+            .chain(actual.keys().filter(|k| !expected.contains_key(*k)));
+        for key in keys {
+            let e = expected.get(key).unwrap_or(&Value::Null);
+            let a = actual.get(key).unwrap_or(&Value::Null);
+            let same = match (e.as_f64(), a.as_f64()) {
+                (Some(e), Some(a)) => self.tolerance.allows(e, a),
+                _ => e == a,
+            };
+            if !same {
+                out.push(Discrepancy {
+                    field: format!("{at}/{key}"),
+                    expected: e.clone(),
+                    actual: a.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<GoldenFile, Error> {
+    serde_json::from_str(&fs::read_to_string(path)?).map_err(Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn manifest(yaml: &str) -> ExperimentManifest {
+        ExperimentManifest::from_yaml_str(yaml).unwrap()
+    }
+
+    fn sea_naive_bayes() -> ExperimentManifest {
+        manifest(
+            "
+task:
+  type: evaluate-prequential
+  params:
+    learner: {type: naive-bayes, params: {}}
+    stream: {type: sea-generator, params: {function_id: 2, balance: false, noise_pct: 0.1}}
+    evaluator: {type: basic-classification, params: {}}
+    max_instances: 5000
+    sample_frequency: 1000
+    mem_check_frequency: 1000
+seeds: [7]
+",
+        )
+    }
+
+    #[test]
+    fn sea_naive_bayes_matches_its_golden_curve() {
+        GoldenRun::new("sea-naive-bayes", sea_naive_bayes()).check();
+    }
+
+    #[test]
+    fn agrawal_hoeffding_tree_matches_its_golden_curve() {
+        let manifest = manifest(
+            "
+task:
+  type: evaluate-prequential
+  params:
+    learner: {type: hoeffding-tree, params: {grace_period: 100}}
+    stream: {type: agrawal-generator, params: {function_id: 3, balance: false, perturb_fraction: 0.05}}
+    evaluator: {type: basic-classification, params: {}}
+    max_instances: 5000
+    sample_frequency: 1000
+    mem_check_frequency: 1000
+seeds: [7]
+",
+        );
+        GoldenRun::new("agrawal-hoeffding-tree", manifest).check();
+    }
+
+    #[test]
+    fn drift_beyond_the_tolerance_is_reported_by_field() {
+        let dir = tempdir().unwrap();
+        let run = GoldenRun::new("drift", sea_naive_bayes()).in_dir(dir.path());
+        run.update().unwrap();
+        assert!(run.compare().unwrap().is_empty());
+
+        let mut golden = read(&run.path()).unwrap();
+        let accuracy = golden.snapshots[2]["accuracy"].as_f64().unwrap();
+        golden.snapshots[2]["accuracy"] = (accuracy + 1e-6).into();
+        fs::write(run.path(), serde_json::to_string(&golden).unwrap()).unwrap();
+
+        let discrepancies = run.compare().unwrap();
+        assert_eq!(discrepancies.len(), 1);
+        assert_eq!(discrepancies[0].field, "/snapshots/2/accuracy");
+        let loose = Tolerance {
+            absolute: 1e-5,
+            relative: 0.0,
+        };
+        assert!(run.with_tolerance(loose).compare().unwrap().is_empty());
+    }
+}
@@ -1,4 +1,6 @@
 pub mod dummies;
+pub mod golden_runs;
+pub mod properties;
 pub mod spies;
 pub mod stubs;
 
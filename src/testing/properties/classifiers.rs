@@ -0,0 +1,231 @@
+use crate::classifiers::Classifier;
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::{DenseInstance, Instance};
+use crate::testing::properties::PropertyConfig;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+
+/// Chance of each value, the class included, being missing in the
+/// instances the classifier checks draw.
+const MISSING_RATE: f64 = 0.1;
+/// Instances the trained models are compared on at the end of a case.
+const PROBES: usize = 20;
+
+/// Instance of `header` with every value drawn at random and weight 1:
+/// nominal values uniformly among the attribute's labels, numeric values
+/// uniformly in `-10..10`, and each value missing (`NaN`) with probability
+/// `missing_rate`.
+pub fn random_instance(
+    header: &Arc<InstanceHeader>,
+    rng: &mut StdRng,
+    missing_rate: f64,
+) -> DenseInstance {
+    let values = (0..header.number_of_attributes())
+        .map(|i| {
+            if rng.random_bool(missing_rate) {
+                return f64::NAN;
+            }
+            let nominal = header
+                .attribute_at_index(i)
+                .and_then(|a| a.as_any().downcast_ref::<NominalAttribute>());
+            match nominal {
+                Some(n) if !n.values.is_empty() => rng.random_range(0..n.values.len()) as f64,
+                Some(_) => f64::NAN,
+                None => rng.random_range(-10.0..10.0),
+            }
+        })
+        .collect();
+    DenseInstance::new(Arc::clone(header), values, 1.0)
+}
+
+/// Checks the classifier invariants on classifiers built by `make`, trained
+/// test-then-train on [`random_instance`]s of `header` with assorted
+/// weights and missing values:
+///
+/// - no call panics, whatever values are missing;
+/// - votes for a nominal class have at most one entry per class, and for a
+///   numeric class at most one (the predicted value), all finite and, for
+///   a nominal class, non-negative;
+/// - training on instances of weight 0 changes no prediction: a copy that
+///   also saw such instances votes like one that did not.
+///
+/// Panics, naming the case seed, on the first violation.
+pub fn check_classifier<C: Classifier>(
+    make: impl Fn() -> C,
+    header: Arc<InstanceHeader>,
+    config: PropertyConfig,
+) {
+    for seed in config.case_seeds() {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut model = make();
+        let mut with_zero_weights = make();
+        model.set_model_context(Arc::clone(&header));
+        with_zero_weights.set_model_context(Arc::clone(&header));
+
+        for i in 0..config.instances {
+            let mut instance = random_instance(&header, &mut rng, MISSING_RATE);
+            let weight = match rng.random_range(0..4) {
+                0 => 0.5,
+                1 => 2.0,
+                2 => rng.random_range(0.01..5.0),
+                _ => 1.0,
+            };
+            instance.set_weight(weight).unwrap();
+            let votes = model.get_votes_for_instance(&instance);
+            if let Err(e) = check_votes(&header, &votes) {
+                panic!("case seed {seed}: instance {i}: {e}");
+            }
+            model.train_on_instance(&instance);
+            with_zero_weights.train_on_instance(&instance);
+
+            let mut ignored = random_instance(&header, &mut rng, MISSING_RATE);
+            ignored.set_weight(0.0).unwrap();
+            with_zero_weights.train_on_instance(&ignored);
+        }
+
+        for i in 0..PROBES {
+            let probe = random_instance(&header, &mut rng, MISSING_RATE);
+            let expected = model.get_votes_for_instance(&probe);
+            let votes = with_zero_weights.get_votes_for_instance(&probe);
+            if let Err(e) = check_votes(&header, &votes) {
+                panic!("case seed {seed}: probe {i}: {e}");
+            }
+            let close = expected.len() == votes.len()
+                && expected
+                    .iter()
+                    .zip(&votes)
+                    .all(|(a, b)| (a - b).abs() <= 1e-9 * a.abs().max(1.0));
+            assert!(
+                close,
+                "case seed {seed}: probe {i}: training on weight-0 instances changed the votes \
+                 from {expected:?} to {votes:?}"
+            );
+        }
+    }
+}
+
+fn check_votes(header: &InstanceHeader, votes: &[f64]) -> Result<(), String> {
+    let classes = header.number_of_classes();
+    let nominal = classes > 0;
+    let max_len = if nominal { classes } else { 1 };
+    if votes.len() > max_len {
+        return Err(format!(
+            "{} votes for {max_len} possible entries: {votes:?}",
+            votes.len()
+        ));
+    }
+    if votes
+        .iter()
+        .any(|v| !v.is_finite() || (nominal && *v < 0.0))
+    {
+        return Err(format!("invalid votes {votes:?}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::{HoeffdingAdaptiveTree, HoeffdingTree, NaiveBayes, TargetMean};
+    use crate::core::attributes::{AttributeRef, NumericAttribute};
+    use crate::streams::Stream;
+    use crate::streams::generators::{AgrawalFunction, AgrawalGenerator};
+    use crate::ui::types::choices::{HoeffdingAdaptiveTreeParams, HoeffdingTreeParams};
+
+    fn agrawal_header() -> Arc<InstanceHeader> {
+        let agrawal = AgrawalGenerator::new(AgrawalFunction::F1, false, 0.0, None, 1).unwrap();
+        Arc::new(agrawal.header().clone())
+    }
+
+    fn numeric_class_header() -> Arc<InstanceHeader> {
+        let attrs: Vec<AttributeRef> = vec![
+            Arc::new(NumericAttribute::new("x".into())) as AttributeRef,
+            Arc::new(NumericAttribute::new("y".into())) as AttributeRef,
+        ];
+        Arc::new(InstanceHeader::new("regression".into(), attrs, 1))
+    }
+
+    #[test]
+    fn built_in_learners_hold_the_classifier_invariants() {
+        let config = PropertyConfig {
+            cases: 4,
+            instances: 400,
+            ..PropertyConfig::default()
+        };
+        check_classifier(NaiveBayes::new, agrawal_header(), config);
+        check_classifier(
+            || {
+                HoeffdingTree::from(HoeffdingTreeParams {
+                    grace_period: 50,
+                    ..HoeffdingTreeParams::default()
+                })
+            },
+            agrawal_header(),
+            config,
+        );
+        check_classifier(
+            || {
+                HoeffdingAdaptiveTree::from(HoeffdingAdaptiveTreeParams {
+                    grace_period: 50,
+                    ..HoeffdingAdaptiveTreeParams::default()
+                })
+            },
+            agrawal_header(),
+            config,
+        );
+        check_classifier(TargetMean::new, numeric_class_header(), config);
+    }
+
+    #[test]
+    fn random_instances_stay_in_the_header_domain() {
+        let header = agrawal_header();
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut missing = 0;
+        for _ in 0..200 {
+            let instance = random_instance(&header, &mut rng, 0.5);
+            for (i, v) in instance.to_vec().into_iter().enumerate() {
+                let Some(n) = header
+                    .attribute_at_index(i)
+                    .and_then(|a| a.as_any().downcast_ref::<NominalAttribute>())
+                else {
+                    continue;
+                };
+                if v.is_nan() {
+                    missing += 1;
+                } else {
+                    assert!(v >= 0.0 && (v as usize) < n.values.len());
+                }
+            }
+        }
+        assert!(missing > 0);
+    }
+
+    /// Votes for the first class by the number of instances trained on,
+    /// whatever their weight.
+    #[derive(Default)]
+    struct Counter(f64);
+
+    impl Classifier for Counter {
+        fn get_votes_for_instance(&self, _instance: &dyn Instance) -> Vec<f64> {
+            vec![self.0, 0.0]
+        }
+
+        fn set_model_context(&mut self, _header: Arc<InstanceHeader>) {}
+
+        fn train_on_instance(&mut self, _instance: &dyn Instance) {
+            self.0 += 1.0;
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "weight-0 instances changed the votes")]
+    fn learners_that_count_ignored_instances_are_caught() {
+        check_classifier(
+            Counter::default,
+            agrawal_header(),
+            PropertyConfig::default(),
+        );
+    }
+}
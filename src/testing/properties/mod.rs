@@ -0,0 +1,40 @@
+//! Invariants every [`Stream`](crate::streams::Stream) and
+//! [`Classifier`](crate::classifiers::Classifier) must satisfy, checked
+//! over many randomly drawn cases so a new component gets broad coverage
+//! from a single call in its tests.
+//!
+//! Cases are drawn from a seeded RNG and a failure panics with the seed of
+//! its case, which reproduces it with `cases: 1` and that `seed`.
+
+pub mod classifiers;
+pub mod streams;
+
+pub use classifiers::{check_classifier, random_instance};
+pub use streams::check_stream;
+
+/// How many cases a property check runs, and how long each one is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyConfig {
+    /// Independent cases, each with its own seed.
+    pub cases: usize,
+    /// Instances read or trained on per case.
+    pub instances: usize,
+    /// Seed of the first case; later cases use the following seeds.
+    pub seed: u64,
+}
+
+impl Default for PropertyConfig {
+    fn default() -> Self {
+        Self {
+            cases: 16,
+            instances: 200,
+            seed: 0,
+        }
+    }
+}
+
+impl PropertyConfig {
+    fn case_seeds(&self) -> impl Iterator<Item = u64> {
+        (0..self.cases as u64).map(|i| self.seed.wrapping_add(i))
+    }
+}
@@ -0,0 +1,156 @@
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::InstanceHeader;
+use crate::core::instances::Instance;
+use crate::streams::Stream;
+use crate::testing::properties::PropertyConfig;
+
+/// Checks the stream invariants on streams built by `make` from each case
+/// seed:
+///
+/// - every instance has the header's attributes and class index, a finite
+///   non-negative weight, nominal values that are indices of one of the
+///   attribute's labels, and numeric values that are finite (`NaN` stands
+///   for a missing value of either kind);
+/// - after [`restart`](Stream::restart) the stream yields the same
+///   instances again, bit for bit.
+///
+/// Panics, naming the case seed, on the first violation.
+pub fn check_stream<S: Stream>(make: impl Fn(u64) -> S, config: PropertyConfig) {
+    for seed in config.case_seeds() {
+        let mut stream = make(seed);
+        let first = read(&mut stream, config.instances, seed);
+        stream
+            .restart()
+            .unwrap_or_else(|e| panic!("case seed {seed}: restart failed: {e}"));
+        let again = read(&mut stream, config.instances, seed);
+        assert_eq!(
+            first.len(),
+            again.len(),
+            "case seed {seed}: the stream yielded a different number of instances after restart"
+        );
+        for (i, (a, b)) in first.iter().zip(&again).enumerate() {
+            assert!(
+                same_bits(a, b),
+                "case seed {seed}: instance {i} differs after restart: {a:?} vs {b:?}"
+            );
+        }
+    }
+}
+
+/// Up to `n` instances of `stream` as their values followed by their
+/// weight, each checked against the header.
+fn read<S: Stream>(stream: &mut S, n: usize, seed: u64) -> Vec<Vec<f64>> {
+    let mut rows = Vec::with_capacity(n);
+    for i in 0..n {
+        let Some(instance) = stream.next_instance() else {
+            break;
+        };
+        if let Err(e) = check_domain(stream.header(), instance.as_ref()) {
+            panic!("case seed {seed}: instance {i}: {e}");
+        }
+        let mut row = instance.to_vec();
+        row.push(instance.weight());
+        rows.push(row);
+    }
+    rows
+}
+
+fn check_domain(header: &InstanceHeader, instance: &dyn Instance) -> Result<(), String> {
+    if instance.number_of_attributes() != header.number_of_attributes() {
+        return Err(format!(
+            "{} attributes, the header has {}",
+            instance.number_of_attributes(),
+            header.number_of_attributes()
+        ));
+    }
+    if instance.class_index() != header.class_index() {
+        return Err(format!(
+            "class index {}, the header has {}",
+            instance.class_index(),
+            header.class_index()
+        ));
+    }
+    let weight = instance.weight();
+    if !(weight.is_finite() && weight >= 0.0) {
+        return Err(format!("weight {weight}"));
+    }
+    for i in 0..header.number_of_attributes() {
+        let Some(value) = instance.value_at_index(i) else {
+            return Err(format!("no value for attribute {i}"));
+        };
+        if value.is_nan() {
+            continue;
+        }
+        let attribute = header.attribute_at_index(i).map(|a| a.as_any());
+        let valid = match attribute.and_then(|a| a.downcast_ref::<NominalAttribute>()) {
+            Some(nominal) => {
+                value.fract() == 0.0 && value >= 0.0 && value < nominal.values.len() as f64
+            }
+            None => value.is_finite(),
+        };
+        if !valid {
+            return Err(format!(
+                "value {value} is outside the domain of attribute {i}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn same_bits(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.to_bits() == y.to_bits())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streams::arff::ArffFileStream;
+    use crate::streams::generators::{
+        AgrawalFunction, AgrawalGenerator, AssetNegotiationGenerator, AssetRule, SeaFunction,
+        SeaGenerator,
+    };
+    use crate::testing::VecStream;
+    use std::io::Write;
+
+    #[test]
+    fn generators_hold_the_stream_invariants() {
+        let config = PropertyConfig::default();
+        check_stream(
+            |seed| SeaGenerator::new(SeaFunction::F2, false, 10, None, seed).unwrap(),
+            config,
+        );
+        check_stream(
+            |seed| AgrawalGenerator::new(AgrawalFunction::F3, true, 0.05, None, seed).unwrap(),
+            config,
+        );
+        check_stream(
+            |seed| AssetNegotiationGenerator::new(AssetRule::R2, false, 0.1, seed).unwrap(),
+            config,
+        );
+    }
+
+    #[test]
+    fn file_and_memory_streams_hold_the_stream_invariants() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "@relation r\n@attribute x numeric\n@attribute c {{a,b}}\n@data\n1.5,a\n?,b\n-2,?\n"
+        )
+        .unwrap();
+        let path = file.path().to_path_buf();
+        check_stream(
+            |_| ArffFileStream::new(path.clone(), 1).unwrap(),
+            PropertyConfig::default(),
+        );
+        check_stream(
+            |seed| VecStream::new((0..50).map(|i| (i + seed as usize) % 2).collect()),
+            PropertyConfig::default(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "case seed 0: instance 0: value 2 is outside the domain")]
+    fn out_of_domain_values_are_reported_with_their_case() {
+        check_stream(|_| VecStream::new(vec![2]), PropertyConfig::default());
+    }
+}
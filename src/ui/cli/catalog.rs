@@ -0,0 +1,181 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use schemars::Schema;
+use serde_json::{Map, Value};
+use strum::{EnumMessage, IntoEnumIterator};
+
+use crate::ui::types::choices::{
+    ClustererChoice, DetectorChoice, EvaluatorChoice, FieldKind, FieldSpec, LearnerChoice,
+    StreamChoice, TaskChoice, UIChoice, specs_for_kind,
+};
+
+/// The families of choice enums a user can pick from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ComponentCategory {
+    Learners,
+    Streams,
+    Evaluators,
+    Tasks,
+    Clusterers,
+    Detectors,
+}
+
+/// One selectable component and the parameters it accepts.
+#[derive(Debug, Clone)]
+pub struct ComponentInfo {
+    /// The `type` tag used in configs.
+    pub key: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+    /// Scalar parameters, with defaults filled in from `default_params`.
+    /// Nested choices (learner, stream, ...) are not listed.
+    pub params: Vec<FieldSpec>,
+}
+
+impl ComponentCategory {
+    /// Every component of this category, in declaration order.
+    pub fn components(self) -> Result<Vec<ComponentInfo>> {
+        match self {
+            ComponentCategory::Learners => components_of::<LearnerChoice>(),
+            ComponentCategory::Streams => components_of::<StreamChoice>(),
+            ComponentCategory::Evaluators => components_of::<EvaluatorChoice>(),
+            ComponentCategory::Tasks => components_of::<TaskChoice>(),
+            ComponentCategory::Clusterers => components_of::<ClustererChoice>(),
+            ComponentCategory::Detectors => components_of::<DetectorChoice>(),
+        }
+    }
+
+    /// The JSON Schema of the whole tagged choice enum, as used to validate
+    /// configs.
+    pub fn schema(self) -> Schema {
+        match self {
+            ComponentCategory::Learners => LearnerChoice::schema(),
+            ComponentCategory::Streams => StreamChoice::schema(),
+            ComponentCategory::Evaluators => EvaluatorChoice::schema(),
+            ComponentCategory::Tasks => TaskChoice::schema(),
+            ComponentCategory::Clusterers => ClustererChoice::schema(),
+            ComponentCategory::Detectors => DetectorChoice::schema(),
+        }
+    }
+
+    /// Name used on the command line and as the key in [`all_schemas`].
+    pub fn name(self) -> &'static str {
+        match self {
+            ComponentCategory::Learners => "learners",
+            ComponentCategory::Streams => "streams",
+            ComponentCategory::Evaluators => "evaluators",
+            ComponentCategory::Tasks => "tasks",
+            ComponentCategory::Clusterers => "clusterers",
+            ComponentCategory::Detectors => "detectors",
+        }
+    }
+}
+
+/// Every category's schema in one object keyed by [`ComponentCategory::name`].
+pub fn all_schemas() -> Value {
+    let mut out = Map::new();
+    for c in ComponentCategory::value_variants() {
+        out.insert(c.name().into(), c.schema().to_value());
+    }
+    Value::Object(out)
+}
+
+/// Describes every variant of `C` from its strum messages and JSON Schema.
+pub fn components_of<C: UIChoice>() -> Result<Vec<ComponentInfo>> {
+    let schema = C::schema();
+    C::Kind::iter()
+        .map(|kind| {
+            let key: &'static str = kind.into();
+            let defaults = C::default_params(kind);
+            let mut params = specs_for_kind(&schema, key)?;
+            for p in &mut params {
+                if p.default.is_none() {
+                    p.default = defaults.get(&p.name).cloned();
+                }
+            }
+            Ok(ComponentInfo {
+                key,
+                label: kind.get_message().unwrap_or(key),
+                description: kind.get_detailed_message().unwrap_or(""),
+                params,
+            })
+        })
+        .collect()
+}
+
+/// One-line summary of a parameter, e.g. `seed: integer = 1 (≥ 0)`.
+pub fn param_summary(p: &FieldSpec) -> String {
+    let kind = match p.kind {
+        FieldKind::String => "string",
+        FieldKind::Integer => "integer",
+        FieldKind::Number => "number",
+        FieldKind::Boolean => "boolean",
+    };
+    let mut out = format!("{}: {kind}", p.name);
+    match &p.default {
+        Some(Value::Null) | None if !p.required => out.push_str(" = none"),
+        Some(v) if !v.is_null() => out.push_str(&format!(" = {v}")),
+        _ => {}
+    }
+    match (p.min, p.max) {
+        (Some(lo), Some(hi)) => out.push_str(&format!(" ({lo}..={hi})")),
+        (Some(lo), None) => out.push_str(&format!(" (≥ {lo})")),
+        (None, Some(hi)) => out.push_str(&format!(" (≤ {hi})")),
+        (None, None) => {}
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_category_lists_its_variants() {
+        for category in ComponentCategory::value_variants() {
+            let components = category.components().unwrap();
+            assert!(!components.is_empty(), "{category:?} is empty");
+            for c in &components {
+                assert!(!c.label.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn schemas_are_tagged_unions_keyed_by_category() {
+        let all = all_schemas();
+        for c in ComponentCategory::value_variants() {
+            let s = &all[c.name()];
+            assert!(
+                s.get("oneOf").or_else(|| s.get("anyOf")).is_some(),
+                "{} schema is not a union",
+                c.name()
+            );
+            assert_eq!(c.to_possible_value().unwrap().get_name(), c.name());
+        }
+    }
+
+    #[test]
+    fn params_carry_defaults_and_ranges() {
+        let detectors = ComponentCategory::Detectors.components().unwrap();
+        let hst = detectors
+            .iter()
+            .find(|c| c.key == "half-space-trees")
+            .unwrap();
+        let height = hst.params.iter().find(|p| p.name == "height").unwrap();
+        assert_eq!(param_summary(height), "height: integer = 15 (1..=30)");
+
+        let tasks = ComponentCategory::Tasks.components().unwrap();
+        let preq = tasks
+            .iter()
+            .find(|c| c.key == "evaluate-prequential")
+            .unwrap();
+        assert!(preq.params.iter().all(|p| p.name != "learner"));
+        let max = preq
+            .params
+            .iter()
+            .find(|p| p.name == "max_instances")
+            .unwrap();
+        assert_eq!(param_summary(max), "max_instances: integer = none (≥ 0)");
+    }
+}
@@ -1,22 +1,32 @@
-use crate::ui::cli::drivers::PromptDriver;
+use crate::ui::cli::drivers::{GoBack, PromptDriver};
 use anyhow::Result;
-use inquire::{Confirm, CustomType, Text, validator::Validation};
+use inquire::{Confirm, CustomType, InquireError, Text, validator::Validation};
+
+/// Maps Esc to [`GoBack`]; every other prompt error is passed through.
+pub(crate) fn map_prompt_error(err: InquireError) -> anyhow::Error {
+    match err {
+        InquireError::OperationCanceled => GoBack.into(),
+        other => other.into(),
+    }
+}
 
 pub struct InquireDriver;
 
 impl PromptDriver for InquireDriver {
     fn ask_bool(&self, title: &str, help: &str, default: bool) -> Result<bool> {
-        Ok(Confirm::new(title)
+        Confirm::new(title)
             .with_default(default)
             .with_help_message(help)
-            .prompt()?)
+            .prompt()
+            .map_err(map_prompt_error)
     }
 
     fn ask_string(&self, title: &str, help: &str, default: &str) -> Result<String> {
-        Ok(Text::new(title)
+        Text::new(title)
             .with_initial_value(default)
             .with_help_message(help)
-            .prompt()?)
+            .prompt()
+            .map_err(map_prompt_error)
     }
 
     fn ask_u64(
@@ -59,7 +69,7 @@ impl PromptDriver for InquireDriver {
             });
         }
 
-        Ok(q.prompt()?)
+        q.prompt().map_err(map_prompt_error)
     }
 
     fn ask_f64(
@@ -102,6 +112,6 @@ impl PromptDriver for InquireDriver {
             });
         }
 
-        Ok(q.prompt()?)
+        q.prompt().map_err(map_prompt_error)
     }
 }
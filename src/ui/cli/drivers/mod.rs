@@ -2,4 +2,4 @@ pub mod inquire_driver;
 mod prompt_driver;
 
 pub use inquire_driver::InquireDriver;
-pub use prompt_driver::PromptDriver;
+pub use prompt_driver::{GoBack, PromptDriver};
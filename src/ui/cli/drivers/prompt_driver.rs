@@ -1,5 +1,18 @@
 use anyhow::Result;
 
+/// Returned (wrapped in `anyhow::Error`) when the user asks to return to the
+/// previous question instead of answering.
+#[derive(Debug, thiserror::Error)]
+#[error("went back to the previous question")]
+pub struct GoBack;
+
+impl GoBack {
+    /// Whether `err` is a back request rather than a real failure.
+    pub fn is(err: &anyhow::Error) -> bool {
+        err.is::<GoBack>()
+    }
+}
+
 pub trait PromptDriver {
     fn ask_bool(&self, title: &str, help: &str, default: bool) -> Result<bool>;
     fn ask_string(&self, title: &str, help: &str, default: &str) -> Result<String>;
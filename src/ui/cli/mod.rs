@@ -1,2 +1,5 @@
+pub mod catalog;
 pub mod drivers;
+pub mod results_browser;
+pub mod sparkline;
 pub mod wizard;
@@ -0,0 +1,393 @@
+use crate::evaluation::{LearningCurve, MetricChange};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, terminal};
+use std::io::{self, Write};
+
+/// What the rows of a [`ResultsBrowser`] are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowserView {
+    /// Every snapshot of the curve, in order.
+    Snapshots,
+    /// Steps between consecutive snapshots, largest change of the focused
+    /// metric first.
+    Changes,
+}
+
+/// Scrollable table over a learning curve, for curves too long to read
+/// from a CSV.
+///
+/// The visible metric columns are those whose name contains the filter
+/// text; one of them is focused and decides the order of the
+/// [`BrowserView::Changes`] view. The browser only holds state and renders
+/// lines, so [`browse`] can drive it from a terminal and tests can drive it
+/// with key events.
+pub struct ResultsBrowser {
+    title: String,
+    curve: LearningCurve,
+    columns: Vec<String>,
+    filter: String,
+    editing_filter: bool,
+    focus: usize,
+    view: BrowserView,
+    offset: usize,
+    changes: Vec<MetricChange>,
+}
+
+const INSTANCES_WIDTH: usize = 14;
+const MIN_COLUMN_WIDTH: usize = 12;
+
+impl ResultsBrowser {
+    pub fn new(title: impl Into<String>, curve: LearningCurve) -> Self {
+        let mut columns: Vec<String> = ["accuracy", "kappa", "ram_hours", "seconds"]
+            .map(String::from)
+            .into();
+        columns.extend(curve.extras_keys().into_iter().map(String::from));
+        let mut browser = Self {
+            title: title.into(),
+            curve,
+            columns,
+            filter: String::new(),
+            editing_filter: false,
+            focus: 0,
+            view: BrowserView::Snapshots,
+            offset: 0,
+            changes: Vec::new(),
+        };
+        browser.refresh_changes();
+        browser
+    }
+
+    /// Only show metrics whose name contains `filter` (case-insensitive).
+    pub fn with_filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = filter.into();
+        self.focus = 0;
+        self.refresh_changes();
+        self
+    }
+
+    pub fn with_view(mut self, view: BrowserView) -> Self {
+        self.view = view;
+        self
+    }
+
+    pub fn view(&self) -> BrowserView {
+        self.view
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn visible_columns(&self) -> Vec<&str> {
+        let needle = self.filter.to_lowercase();
+        self.columns
+            .iter()
+            .filter(|c| c.to_lowercase().contains(&needle))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn focused_metric(&self) -> Option<&str> {
+        self.visible_columns().get(self.focus).copied()
+    }
+
+    pub fn row_count(&self) -> usize {
+        match self.view {
+            BrowserView::Snapshots => self.curve.len(),
+            BrowserView::Changes => self.changes.len(),
+        }
+    }
+
+    /// Applies one key press, with `page` rows on screen. Returns `false`
+    /// when the browser should close.
+    pub fn handle_key(&mut self, key: KeyEvent, page: usize) -> bool {
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Char(c) => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Enter | KeyCode::Esc => self.editing_filter = false,
+                _ => return true,
+            }
+            self.focus = 0;
+            self.offset = 0;
+            self.refresh_changes();
+            return true;
+        }
+
+        let page = page.max(1);
+        let last = self.row_count().saturating_sub(page);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.offset = self.offset.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.offset += 1,
+            KeyCode::PageUp => self.offset = self.offset.saturating_sub(page),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.offset += page,
+            KeyCode::Home | KeyCode::Char('g') => self.offset = 0,
+            KeyCode::End | KeyCode::Char('G') => self.offset = last,
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.focus = self.focus.saturating_sub(1);
+                self.refresh_changes();
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                let visible = self.visible_columns().len();
+                self.focus = (self.focus + 1).min(visible.saturating_sub(1));
+                self.refresh_changes();
+            }
+            KeyCode::Char('c') | KeyCode::Tab => {
+                self.view = match self.view {
+                    BrowserView::Snapshots => BrowserView::Changes,
+                    BrowserView::Changes => BrowserView::Snapshots,
+                };
+                self.offset = 0;
+            }
+            KeyCode::Char('/') => self.editing_filter = true,
+            _ => {}
+        }
+        self.offset = self.offset.min(last);
+        true
+    }
+
+    /// The header line followed by every row of the current view.
+    pub fn table(&self) -> Vec<String> {
+        let visible = self.visible_columns();
+        let focused = self.focused_metric();
+        let width = |name: &str| (name.len() + 2).max(MIN_COLUMN_WIDTH);
+        let head = |name: &str| {
+            let label = if Some(name) == focused {
+                format!("[{name}]")
+            } else {
+                name.to_string()
+            };
+            format!(" {label:>w$}", w = width(name))
+        };
+        let cell = |name: &str, v: Option<f64>| format!(" {:>w$}", fmt_value(v), w = width(name));
+
+        let mut lines = Vec::with_capacity(self.row_count() + 1);
+        match self.view {
+            BrowserView::Snapshots => {
+                let mut header = format!("{:>INSTANCES_WIDTH$}", "instances_seen");
+                visible.iter().for_each(|c| header.push_str(&head(c)));
+                lines.push(header);
+                for s in self.curve.iter() {
+                    let mut line = format!("{:>INSTANCES_WIDTH$}", s.instances_seen);
+                    visible
+                        .iter()
+                        .for_each(|c| line.push_str(&cell(c, s.metric(c))));
+                    lines.push(line);
+                }
+            }
+            BrowserView::Changes => {
+                let others: Vec<&str> = visible
+                    .iter()
+                    .copied()
+                    .filter(|c| Some(*c) != focused)
+                    .collect();
+                let mut header = format!(
+                    "{:>5} {:>INSTANCES_WIDTH$} {:>12} {:>12} {:>12}",
+                    "#", "instances_seen", "from", "to", "delta"
+                );
+                others.iter().for_each(|c| header.push_str(&head(c)));
+                lines.push(header);
+                let snapshots = self.curve.as_slice();
+                for (rank, ch) in self.changes.iter().enumerate() {
+                    let mut line = format!(
+                        "{:>5} {:>INSTANCES_WIDTH$} {:>12} {:>12} {:>+12.6}",
+                        rank + 1,
+                        ch.instances_seen,
+                        fmt_value(Some(ch.from)),
+                        fmt_value(Some(ch.to)),
+                        ch.delta()
+                    );
+                    let s = &snapshots[ch.index];
+                    others
+                        .iter()
+                        .for_each(|c| line.push_str(&cell(c, s.metric(c))));
+                    lines.push(line);
+                }
+            }
+        }
+        lines
+    }
+
+    /// A screen of at most `height` lines, each cut to `width` characters:
+    /// a title, the table header, the rows from the current offset, and a
+    /// line of key hints.
+    pub fn render(&self, width: usize, height: usize) -> Vec<String> {
+        let page = Self::page_size(height);
+        let table = self.table();
+        let view = match (self.view, self.focused_metric()) {
+            (BrowserView::Snapshots, _) => "snapshots".to_string(),
+            (BrowserView::Changes, Some(m)) => format!("largest changes of {m}"),
+            (BrowserView::Changes, None) => "largest changes (no metric matches)".to_string(),
+        };
+        let rows = self.row_count();
+        let shown_to = (self.offset + page).min(rows);
+        let mut lines = vec![format!(
+            "{} · {view} · rows {}–{shown_to} of {rows}",
+            self.title,
+            (self.offset + 1).min(shown_to),
+        )];
+        lines.extend(table.iter().take(1).cloned());
+        lines.extend(table.iter().skip(1 + self.offset).take(page).cloned());
+        lines.push(if self.editing_filter {
+            format!("filter: {}_   (Enter to apply)", self.filter)
+        } else {
+            format!(
+                "↑↓ PgUp PgDn scroll  ←→ metric  c changes  / filter [{}]  q quit",
+                self.filter
+            )
+        });
+        lines
+            .into_iter()
+            .take(height.max(1))
+            .map(|l| l.chars().take(width).collect())
+            .collect()
+    }
+
+    /// Rows that fit under the title, header, and hint lines.
+    pub fn page_size(height: usize) -> usize {
+        height.saturating_sub(3).max(1)
+    }
+
+    fn refresh_changes(&mut self) {
+        self.changes = match self.focused_metric() {
+            Some(m) => MetricChange::largest(&self.curve, m, usize::MAX),
+            None => Vec::new(),
+        };
+    }
+}
+
+fn fmt_value(v: Option<f64>) -> String {
+    match v {
+        Some(v) if v.is_finite() => format!("{v:.6}"),
+        _ => "—".to_string(),
+    }
+}
+
+/// Restores the terminal when the browser closes, also on errors.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Shows `browser` full-screen until the user quits.
+pub fn browse(mut browser: ResultsBrowser) -> io::Result<()> {
+    let _screen = Screen::enter()?;
+    let mut out = io::stdout();
+    loop {
+        let (w, h) = terminal::size()?;
+        let (w, h) = (w as usize, h as usize);
+        execute!(
+            out,
+            cursor::MoveTo(0, 0),
+            terminal::Clear(terminal::ClearType::All)
+        )?;
+        for line in browser.render(w, h) {
+            write!(out, "{line}\r\n")?;
+        }
+        out.flush()?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !browser.handle_key(key, ResultsBrowser::page_size(h))
+        {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Snapshot;
+    use std::collections::BTreeMap;
+
+    fn curve(n: usize) -> LearningCurve {
+        let mut lc = LearningCurve::default();
+        for i in 0..n {
+            lc.push(Snapshot {
+                instances_seen: (i as u64 + 1) * 100,
+                accuracy: if i == 5 { 0.2 } else { 0.8 },
+                kappa: 0.5,
+                ram_hours: 0.0,
+                seconds: i as f64,
+                extras: BTreeMap::from([("kappa_t".into(), 0.4)]),
+                eta: None,
+            });
+        }
+        lc
+    }
+
+    fn press(b: &mut ResultsBrowser, code: KeyCode) -> bool {
+        b.handle_key(KeyEvent::new(code, KeyModifiers::NONE), 10)
+    }
+
+    #[test]
+    fn scrolling_stays_within_the_rows() {
+        let mut b = ResultsBrowser::new("run", curve(25));
+        press(&mut b, KeyCode::Up);
+        assert_eq!(b.offset(), 0);
+        press(&mut b, KeyCode::PageDown);
+        press(&mut b, KeyCode::PageDown);
+        assert_eq!(b.offset(), 15);
+        press(&mut b, KeyCode::Home);
+        press(&mut b, KeyCode::End);
+        assert_eq!(b.offset(), 15);
+
+        let screen = b.render(200, 13);
+        assert_eq!(screen.len(), 13);
+        assert!(screen[0].contains("rows 16–25 of 25"), "{}", screen[0]);
+        assert!(screen[2].trim_start().starts_with("1600"));
+        assert!(!press(&mut b, KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn filter_selects_metric_columns() {
+        let mut b = ResultsBrowser::new("run", curve(3));
+        assert_eq!(
+            b.visible_columns(),
+            vec!["accuracy", "kappa", "ram_hours", "seconds", "kappa_t"]
+        );
+        press(&mut b, KeyCode::Char('/'));
+        for c in "KAP".chars() {
+            press(&mut b, KeyCode::Char(c));
+        }
+        press(&mut b, KeyCode::Enter);
+        assert_eq!(b.visible_columns(), vec!["kappa", "kappa_t"]);
+        assert!(b.table()[0].contains("[kappa]"));
+        assert!(!b.table()[0].contains("accuracy"));
+    }
+
+    #[test]
+    fn changes_view_ranks_the_largest_steps_of_the_focused_metric() {
+        let mut b = ResultsBrowser::new("run", curve(10));
+        press(&mut b, KeyCode::Char('c'));
+        assert_eq!(b.view(), BrowserView::Changes);
+        assert_eq!(b.focused_metric(), Some("accuracy"));
+
+        let table = b.table();
+        assert!(table[1].contains("600") && table[1].contains("-0.600000"));
+        assert!(table[2].contains("700") && table[2].contains("+0.600000"));
+
+        press(&mut b, KeyCode::Right);
+        press(&mut b, KeyCode::Right);
+        press(&mut b, KeyCode::Right);
+        assert_eq!(b.focused_metric(), Some("seconds"));
+        assert_eq!(b.row_count(), 9);
+    }
+}
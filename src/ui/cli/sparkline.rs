@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Ring buffer of the most recent values of a metric, drawn as a unicode
+/// sparkline.
+///
+/// Bars are scaled between the smallest and largest value in the buffer,
+/// so small drifts stay visible; non-finite values are drawn as gaps.
+#[derive(Debug, Clone)]
+pub struct Sparkline {
+    values: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl Sparkline {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Appends `value`, evicting the oldest one once the buffer is full.
+    pub fn push(&mut self, value: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// One character per buffered value, oldest first.
+    pub fn render(&self) -> String {
+        let (lo, hi) = self
+            .values
+            .iter()
+            .filter(|v| v.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        let span = hi - lo;
+        self.values
+            .iter()
+            .map(|&v| {
+                if !v.is_finite() {
+                    ' '
+                } else if span <= f64::EPSILON {
+                    BARS[BARS.len() / 2]
+                } else {
+                    let level = ((v - lo) / span * (BARS.len() - 1) as f64).round() as usize;
+                    BARS[level.min(BARS.len() - 1)]
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_most_recent_values() {
+        let mut s = Sparkline::new(3);
+        for v in [1.0, 2.0, 3.0, 4.0] {
+            s.push(v);
+        }
+        assert_eq!(s.len(), 3);
+        assert_eq!(s.render(), "▁▅█");
+    }
+
+    #[test]
+    fn scales_between_min_and_max_and_leaves_gaps() {
+        let mut s = Sparkline::new(8);
+        for v in [0.5, 1.0, f64::NAN, 0.0] {
+            s.push(v);
+        }
+        assert_eq!(s.render(), "▅█ ▁");
+    }
+
+    #[test]
+    fn flat_series_draws_a_level_line() {
+        let mut s = Sparkline::new(4);
+        s.push(0.5);
+        s.push(0.5);
+        assert_eq!(s.render(), "▅▅");
+        assert!(Sparkline::new(4).render().is_empty());
+    }
+}
@@ -1,3 +1,8 @@
+mod presets;
 mod wizard;
 
-pub use wizard::prompt_choice;
+pub use presets::{RecentConfigs, RecentEntry, prompt_preset};
+pub use wizard::{
+    ReviewAction, prompt_choice, prompt_choice_with, prompt_save_config, review_choice,
+    review_lines, write_config,
+};
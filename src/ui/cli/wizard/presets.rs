@@ -0,0 +1,234 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use crate::ui::cli::drivers::inquire_driver::map_prompt_error;
+
+/// One remembered wizard configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentEntry {
+    /// RFC 3339 timestamp of when the run was started.
+    pub saved_at: String,
+    /// The serialized choice, as passed to `prompt_choice_with`.
+    pub config: Value,
+}
+
+impl RecentEntry {
+    /// Short description: the top-level kind followed by the kinds of its
+    /// nested choices, e.g. `evaluate-prequential (naive-bayes, sea-generator)`.
+    pub fn summary(&self) -> String {
+        let kind = self
+            .config
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        let nested: Vec<&str> = self
+            .config
+            .get("params")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|p| p.values())
+            .filter_map(|v| v.get("type").and_then(Value::as_str))
+            .collect();
+        if nested.is_empty() {
+            kind.to_string()
+        } else {
+            format!("{kind} ({})", nested.join(", "))
+        }
+    }
+}
+
+impl Display for RecentEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match DateTime::parse_from_rfc3339(&self.saved_at) {
+            Ok(at) => {
+                let when = at.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+                write!(f, "{}  {when}", self.summary())
+            }
+            Err(_) => f.write_str(&self.summary()),
+        }
+    }
+}
+
+/// The last few configurations started from the wizard, most recent first,
+/// kept in a small JSON file.
+#[derive(Debug, Clone)]
+pub struct RecentConfigs {
+    path: PathBuf,
+    limit: usize,
+    entries: Vec<RecentEntry>,
+}
+
+impl RecentConfigs {
+    /// Number of configurations kept by default.
+    pub const DEFAULT_LIMIT: usize = 10;
+
+    /// `$RIVU_RECENT_FILE` when set, otherwise `rivu/recent.json` under the
+    /// user's config directory (`$XDG_CONFIG_HOME`, `%APPDATA%` or
+    /// `~/.config`). `None` when no such directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(p) = std::env::var_os("RIVU_RECENT_FILE") {
+            return Some(PathBuf::from(p));
+        }
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .or_else(|| std::env::var_os("APPDATA"))
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+        Some(base.join("rivu").join("recent.json"))
+    }
+
+    /// Reads the file at `path`; a missing file yields an empty list.
+    pub fn load(path: impl Into<PathBuf>, limit: usize) -> Result<Self> {
+        let path = path.into();
+        let mut entries: Vec<RecentEntry> = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text)
+                .with_context(|| format!("failed to parse {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        entries.truncate(limit);
+        Ok(Self {
+            path,
+            limit,
+            entries,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> &[RecentEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Puts `config` first, dropping an identical older entry and anything
+    /// beyond the limit.
+    pub fn push(&mut self, config: Value) {
+        self.entries.retain(|e| e.config != config);
+        self.entries.insert(
+            0,
+            RecentEntry {
+                saved_at: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+                config,
+            },
+        );
+        self.entries.truncate(self.limit);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut text = serde_json::to_string_pretty(&self.entries)?;
+        text.push('\n');
+        std::fs::write(&self.path, text)
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+enum PresetItem<'a> {
+    Fresh,
+    Recent(&'a RecentEntry),
+}
+
+impl Display for PresetItem<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PresetItem::Fresh => f.write_str("Start from scratch"),
+            PresetItem::Recent(entry) => entry.fmt(f),
+        }
+    }
+}
+
+/// Lets the user pick one of the `recent` configurations as a preset.
+///
+/// Returns the chosen configuration, or `None` for a fresh start (also when
+/// there is nothing to choose from). Esc surfaces as a `GoBack` error.
+pub fn prompt_preset(recent: &RecentConfigs) -> Result<Option<Value>> {
+    if recent.is_empty() {
+        return Ok(None);
+    }
+    let items: Vec<PresetItem> = std::iter::once(PresetItem::Fresh)
+        .chain(recent.entries().iter().map(PresetItem::Recent))
+        .collect();
+    let picked = inquire::Select::new("Start from a recent run?", items)
+        .with_help_message("Presets pre-fill every answer; ↑/↓ to navigate, ↵ to select")
+        .prompt()
+        .map_err(map_prompt_error)?;
+    Ok(match picked {
+        PresetItem::Fresh => None,
+        PresetItem::Recent(entry) => Some(entry.config.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(seed: u64) -> Value {
+        json!({
+            "type": "evaluate-prequential",
+            "params": {
+                "learner": { "type": "naive-bayes", "params": {} },
+                "stream": { "type": "sea-generator", "params": { "seed": seed } },
+                "sample_frequency": 100,
+            }
+        })
+    }
+
+    #[test]
+    fn push_keeps_the_newest_first_without_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recent = RecentConfigs::load(dir.path().join("recent.json"), 3).unwrap();
+        assert!(recent.is_empty());
+
+        for seed in [1, 2, 3, 1, 4] {
+            recent.push(config(seed));
+        }
+        let seeds: Vec<u64> = recent
+            .entries()
+            .iter()
+            .map(|e| {
+                e.config["params"]["stream"]["params"]["seed"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(seeds, vec![4, 1, 3]);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("recent.json");
+        let mut recent = RecentConfigs::load(&path, 5).unwrap();
+        recent.push(config(7));
+        recent.save().unwrap();
+
+        let back = RecentConfigs::load(&path, 5).unwrap();
+        assert_eq!(back.entries(), recent.entries());
+    }
+
+    #[test]
+    fn summary_lists_nested_kinds() {
+        let entry = RecentEntry {
+            saved_at: "2026-01-02T03:04:05+00:00".into(),
+            config: config(1),
+        };
+        assert_eq!(
+            entry.summary(),
+            "evaluate-prequential (naive-bayes, sea-generator)"
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use anyhow::{Context, Result};
+use serde::Serialize;
 use serde_json::{Map, Value};
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 use strum::{EnumMessage, IntoEnumIterator};
 
-use crate::ui::cli::drivers::PromptDriver;
-use crate::ui::types::choices::{FieldKind, UIChoice, schema_for, specs_for_kind};
+use crate::ui::cli::drivers::inquire_driver::map_prompt_error;
+use crate::ui::cli::drivers::{GoBack, PromptDriver};
+use crate::ui::types::choices::{FieldKind, FieldSpec, UIChoice, schema_for, specs_for_kind};
 
 const DIM_ITALIC: &str = "\x1b[2m\x1b[3m";
 const RESET: &str = "\x1b[0m";
@@ -40,132 +42,395 @@ where
 }
 
 pub fn prompt_choice<C: UIChoice, D: PromptDriver>(driver: &D) -> Result<C> {
-    let items = kind_items::<C::Kind>();
-
-    let mut select = inquire::Select::new(C::prompt_label(), items);
+    prompt_choice_with::<C, D>(driver, None)
+}
 
-    if let Some(help) = C::prompt_help() {
-        select = select.with_help_message(help);
-    }
+/// Like [`prompt_choice`], but pre-selects the kind and pre-fills every
+/// answer from `preset`, a serialized choice (`{"type": .., "params": ..}`).
+///
+/// Pressing Esc returns to the previous question; on the kind selection it
+/// surfaces as a [`GoBack`] error so the caller can step back further.
+///
+/// The finished choice is checked with [`UIChoice::validate`]; when that
+/// fails the problem is printed and the offending field asked again, with
+/// every other answer kept.
+pub fn prompt_choice_with<C: UIChoice, D: PromptDriver>(
+    driver: &D,
+    preset: Option<&Value>,
+) -> Result<C> {
+    let mut remembered_kind = preset
+        .and_then(|v| v.get("type"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let mut remembered = preset
+        .and_then(|v| v.get("params"))
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
 
-    let selected = select.prompt()?;
-    let choice_kind: C::Kind = selected.kind;
+    let mut retry: Option<(C::Kind, String)> = None;
 
-    let key: &'static str = choice_kind.into();
-    let schema = schema_for::<C>();
-    let specs = specs_for_kind(&schema, key)?;
+    loop {
+        let (choice_kind, start_at) = match retry.take() {
+            Some(retry) => retry,
+            None => {
+                let items = kind_items::<C::Kind>();
+                let cursor = remembered_kind
+                    .as_deref()
+                    .and_then(|key| items.iter().position(|i| key == i.kind.into()))
+                    .unwrap_or(0);
 
-    let defaults = C::default_params(choice_kind);
-
-    let mut params = Map::new();
-    for s in specs {
-        let init = s.default.clone().or_else(|| defaults.get(&s.name).cloned());
-        let help = s.description.as_deref().unwrap_or("");
-
-        let is_optional_numeric = !s.required
-            && matches!(s.kind, FieldKind::Integer | FieldKind::Number)
-            && matches!(init, None | Some(Value::Null));
-
-        let val_opt: Option<Value> = if is_optional_numeric {
-            let def_txt = match s.kind {
-                FieldKind::Integer => init
-                    .as_ref()
-                    .and_then(|v| v.as_u64())
-                    .map(|n| n.to_string()),
-                FieldKind::Number => init
-                    .as_ref()
-                    .and_then(|v| v.as_f64())
-                    .map(|x| x.to_string()),
-                _ => None,
+                let mut select =
+                    inquire::Select::new(C::prompt_label(), items).with_starting_cursor(cursor);
+                if let Some(help) = C::prompt_help() {
+                    select = select.with_help_message(help);
+                }
+                (
+                    select.prompt().map_err(map_prompt_error)?.kind,
+                    String::new(),
+                )
             }
-            .unwrap_or_default();
+        };
+        let key: &'static str = choice_kind.into();
 
-            let answer = driver.ask_string(
-                &s.title,
-                &format!("{help}\n(leave blank for none)"),
-                &def_txt,
-            )?;
+        let prefill = if remembered_kind.as_deref() == Some(key) {
+            std::mem::take(&mut remembered)
+        } else {
+            Map::new()
+        };
 
-            let answer = answer.trim();
-            if answer.is_empty() {
-                None
-            } else {
-                Some(match s.kind {
-                    FieldKind::Integer => {
-                        let n: u64 = answer
-                            .parse()
-                            .with_context(|| format!("invalid integer for {}", s.title))?;
-                        Value::from(n)
+        let mut params = Map::new();
+        match prompt_params::<C, D>(driver, choice_kind, &prefill, &mut params, &start_at) {
+            Ok(()) => {
+                let choice = C::from_parts(choice_kind, Value::Object(params.clone()))?;
+                match choice.validate() {
+                    Ok(()) => return Ok(choice),
+                    Err(invalid) => {
+                        eprintln!("✗ {}", invalid.message);
+                        remembered = params;
+                        remembered_kind = Some(key.to_string());
+                        retry = Some((choice_kind, invalid.top_field().to_string()));
                     }
-                    FieldKind::Number => {
-                        let x: f64 = answer
-                            .parse()
-                            .with_context(|| format!("invalid number for {}", s.title))?;
-                        Value::from(x)
-                    }
-                    _ => unreachable!(),
-                })
-            }
-        } else {
-            Some(match s.kind {
-                FieldKind::Boolean => {
-                    let def = init.and_then(|v| v.as_bool()).unwrap_or(false);
-                    Value::Bool(driver.ask_bool(&s.title, help, def)?)
-                }
-                FieldKind::String => {
-                    let def = init
-                        .and_then(|v| v.as_str().map(|s| s.to_string()))
-                        .unwrap_or_default();
-                    let is_arff_path = s.name == "path";
-
-                    let answered = if is_arff_path {
-                        let more_help = if help.is_empty() {
-                            "Please type a valid .arff file path"
-                        } else {
-                            help
-                        };
-                        let pb = prompt_path_until_ok(
-                            driver,
-                            &s.title,
-                            more_help,
-                            &def,
-                            true,
-                            true,
-                            &["arff"],
-                        )?;
-                        pb.to_string_lossy().into_owned()
-                    } else {
-                        driver.ask_string(&s.title, help, &def)?
-                    };
-
-                    Value::String(answered)
                 }
-                FieldKind::Integer => {
-                    let def = init.and_then(|v| v.as_u64()).unwrap_or(0);
-                    Value::from(driver.ask_u64(
-                        &s.title,
-                        help,
-                        def,
-                        s.min.map(|x| x as u64),
-                        s.max.map(|x| x as u64),
-                    )?)
+            }
+            Err(e) if GoBack::is(&e) => {
+                remembered = prefill;
+                remembered.extend(params);
+                remembered_kind = Some(key.to_string());
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Asks every field of `kind` in order, then its subprompts, writing the
+/// answers to `params`. A [`GoBack`] on a field re-asks the one before it;
+/// on the first field it is returned with the answers given so far.
+///
+/// A non-empty `start_at` skips ahead to that field, keeping the prefilled
+/// answers of the ones before it; a name that is not a field (a nested
+/// choice) goes straight to the subprompts.
+fn prompt_params<C: UIChoice, D: PromptDriver>(
+    driver: &D,
+    kind: C::Kind,
+    prefill: &Map<String, Value>,
+    params: &mut Map<String, Value>,
+    start_at: &str,
+) -> Result<()> {
+    let key: &'static str = kind.into();
+    let schema = schema_for::<C>();
+    let specs = specs_for_kind(&schema, key)?;
+    let defaults = C::default_params(kind);
+
+    let mut i = 0;
+    if !start_at.is_empty() {
+        i = specs
+            .iter()
+            .position(|s| s.name == start_at)
+            .unwrap_or(specs.len());
+        for s in &specs[..i] {
+            if let Some(v) = prefill.get(&s.name) {
+                params.insert(s.name.clone(), v.clone());
+            }
+        }
+    }
+    loop {
+        let Some(s) = specs.get(i) else {
+            let mut preset = prefill.clone();
+            preset.extend(params.clone());
+            match C::subprompts(driver, kind, &preset) {
+                Ok(extra) => {
+                    params.extend(extra.unwrap_or_default());
+                    return Ok(());
                 }
-                FieldKind::Number => {
-                    let def = init.and_then(|v| v.as_f64()).unwrap_or(0.0);
-                    Value::from(driver.ask_f64(&s.title, help, def, s.min, s.max)?)
+                Err(e) if GoBack::is(&e) && i > 0 => {
+                    i -= 1;
+                    continue;
                 }
-            })
+                Err(e) => return Err(e),
+            }
         };
 
-        if let Some(val) = val_opt {
-            params.insert(s.name.clone(), val);
+        let base = s.default.clone().or_else(|| defaults.get(&s.name).cloned());
+        let init = params
+            .get(&s.name)
+            .or_else(|| prefill.get(&s.name))
+            .cloned()
+            .or_else(|| base.clone());
+
+        match ask_field(driver, s, base.as_ref(), init) {
+            Ok(Some(val)) => {
+                params.insert(s.name.clone(), val);
+                i += 1;
+            }
+            Ok(None) => {
+                params.remove(&s.name);
+                i += 1;
+            }
+            Err(e) if GoBack::is(&e) && i > 0 => i -= 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Asks one field, offering `init` as the default. `base` is the schema or
+/// kind default, which decides whether a numeric field may be left blank.
+fn ask_field<D: PromptDriver>(
+    driver: &D,
+    s: &FieldSpec,
+    base: Option<&Value>,
+    init: Option<Value>,
+) -> Result<Option<Value>> {
+    let help = s.description.as_deref().unwrap_or("");
+
+    let is_optional_numeric = !s.required
+        && matches!(s.kind, FieldKind::Integer | FieldKind::Number)
+        && matches!(base, None | Some(Value::Null));
+
+    if is_optional_numeric {
+        let def_txt = match s.kind {
+            FieldKind::Integer => init
+                .as_ref()
+                .and_then(|v| v.as_u64())
+                .map(|n| n.to_string()),
+            FieldKind::Number => init
+                .as_ref()
+                .and_then(|v| v.as_f64())
+                .map(|x| x.to_string()),
+            _ => None,
+        }
+        .unwrap_or_default();
+
+        let answer = driver.ask_string(
+            &s.title,
+            &format!("{help}\n(leave blank for none)"),
+            &def_txt,
+        )?;
+
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok(None);
+        }
+        return Ok(Some(match s.kind {
+            FieldKind::Integer => {
+                let n: u64 = answer
+                    .parse()
+                    .with_context(|| format!("invalid integer for {}", s.title))?;
+                Value::from(n)
+            }
+            FieldKind::Number => {
+                let x: f64 = answer
+                    .parse()
+                    .with_context(|| format!("invalid number for {}", s.title))?;
+                Value::from(x)
+            }
+            _ => unreachable!(),
+        }));
+    }
+
+    Ok(Some(match s.kind {
+        FieldKind::Boolean => {
+            let def = init.and_then(|v| v.as_bool()).unwrap_or(false);
+            Value::Bool(driver.ask_bool(&s.title, help, def)?)
+        }
+        FieldKind::String => {
+            let def = init
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or_default();
+            let is_arff_path = s.name == "path";
+
+            let answered = if is_arff_path {
+                let more_help = if help.is_empty() {
+                    "Please type a valid .arff file path"
+                } else {
+                    help
+                };
+                let pb =
+                    prompt_path_until_ok(driver, &s.title, more_help, &def, true, true, &["arff"])?;
+                pb.to_string_lossy().into_owned()
+            } else {
+                driver.ask_string(&s.title, help, &def)?
+            };
+
+            Value::String(answered)
+        }
+        FieldKind::Integer => {
+            let def = init.and_then(|v| v.as_u64()).unwrap_or(0);
+            Value::from(driver.ask_u64(
+                &s.title,
+                help,
+                def,
+                s.min.map(|x| x as u64),
+                s.max.map(|x| x as u64),
+            )?)
+        }
+        FieldKind::Number => {
+            let def = init.and_then(|v| v.as_f64()).unwrap_or(0.0);
+            Value::from(driver.ask_f64(&s.title, help, def, s.min, s.max)?)
+        }
+    }))
+}
+
+/// What to do with the answers shown on the review screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewAction {
+    Start,
+    Edit,
+    Cancel,
+}
+
+impl Display for ReviewAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReviewAction::Start => "Start run",
+            ReviewAction::Edit => "Edit answers",
+            ReviewAction::Cancel => "Cancel",
+        })
+    }
+}
+
+/// Shows every answer in `choice` and lets the user start, go back through
+/// the questions with the current answers pre-filled, or cancel.
+///
+/// Returns the (possibly edited) choice, or `None` when cancelled.
+pub fn review_choice<C: UIChoice, D: PromptDriver>(driver: &D, mut choice: C) -> Result<Option<C>> {
+    loop {
+        let value = serde_json::to_value(&choice)?;
+        println!("\nReview");
+        for line in review_lines(&value) {
+            println!("  {line}");
+        }
+        println!();
+
+        let action = inquire::Select::new(
+            "Start the run?",
+            vec![
+                ReviewAction::Start,
+                ReviewAction::Edit,
+                ReviewAction::Cancel,
+            ],
+        )
+        .with_help_message("↑/↓ to navigate, ↵ to select")
+        .prompt()
+        .map_err(map_prompt_error);
+
+        match action {
+            Ok(ReviewAction::Start) => return Ok(Some(choice)),
+            Ok(ReviewAction::Edit) => match prompt_choice_with::<C, D>(driver, Some(&value)) {
+                Ok(edited) => choice = edited,
+                Err(e) if GoBack::is(&e) => {}
+                Err(e) => return Err(e),
+            },
+            Ok(ReviewAction::Cancel) => return Ok(None),
+            Err(e) if GoBack::is(&e) => return Ok(None),
+            Err(e) => return Err(e),
         }
     }
+}
 
-    if let Some(extra) = C::subprompts(driver, choice_kind)? {
-        params.extend(extra);
+/// Flattens a serialized choice into indented `name: value` lines, nesting
+/// sub-choices under the parameter that holds them.
+pub fn review_lines(choice: &Value) -> Vec<String> {
+    let mut out = Vec::new();
+    match choice.get("type").and_then(Value::as_str) {
+        Some(kind) => {
+            out.push(kind.to_string());
+            push_params(choice.get("params"), 1, &mut out);
+        }
+        None => out.push(choice.to_string()),
     }
-    C::from_parts(choice_kind, Value::Object(params))
+    out
+}
+
+fn push_params(params: Option<&Value>, depth: usize, out: &mut Vec<String>) {
+    let Some(params) = params.and_then(Value::as_object) else {
+        return;
+    };
+    let indent = "  ".repeat(depth);
+    for (name, value) in params {
+        match value.get("type").and_then(Value::as_str) {
+            Some(kind) => {
+                out.push(format!("{indent}{name}: {kind}"));
+                push_params(value.get("params"), depth + 1, out);
+            }
+            None => {
+                let text = match value {
+                    Value::Null => "none".to_string(),
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                out.push(format!("{indent}{name}: {text}"));
+            }
+        }
+    }
+}
+
+/// Offers to save the composed `choice` as a config file (see
+/// [`write_config`]).
+///
+/// Returns the path written to, or `None` when the user declined. The file
+/// can be passed back through `rivu run --config` to repeat the run
+/// without prompts.
+pub fn prompt_save_config<C: Serialize, D: PromptDriver>(
+    driver: &D,
+    choice: &C,
+) -> Result<Option<PathBuf>> {
+    let save = driver.ask_bool(
+        "Save Config",
+        "Write these answers to a JSON file for later batch runs",
+        false,
+    )?;
+    if !save {
+        return Ok(None);
+    }
+    let answer = driver.ask_string(
+        "Config Path",
+        "JSON file to write (YAML for .yaml or .yml)",
+        "experiment.json",
+    )?;
+    let path = PathBuf::from(answer.trim());
+    write_config(choice, &path)?;
+    Ok(Some(path))
+}
+
+/// Writes `choice` to `path` as YAML for a `.yaml` or `.yml` file and as
+/// pretty-printed JSON otherwise, the two formats `rivu run --config` reads.
+pub fn write_config<C: Serialize>(choice: &C, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let yaml = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"));
+    let text = if yaml {
+        serde_yaml::to_string(choice)?
+    } else {
+        let mut text = serde_json::to_string_pretty(choice)?;
+        text.push('\n');
+        text
+    };
+    std::fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))
 }
 
 fn validate_path_str(
@@ -214,3 +479,231 @@ fn prompt_path_until_ok<D: PromptDriver>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::types::choices::{StreamChoice, StreamKind};
+    use serde_json::json;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    /// Answers with the offered default unless the next scripted step says
+    /// to go back; records every question with the default it offered.
+    #[derive(Default)]
+    struct Script {
+        back_at: RefCell<VecDeque<bool>>,
+        asked: RefCell<Vec<(String, String)>>,
+    }
+
+    impl Script {
+        fn step<T>(&self, title: &str, default: T) -> Result<T>
+        where
+            T: ToString,
+        {
+            self.asked
+                .borrow_mut()
+                .push((title.to_string(), default.to_string()));
+            if self.back_at.borrow_mut().pop_front().unwrap_or(false) {
+                return Err(GoBack.into());
+            }
+            Ok(default)
+        }
+    }
+
+    impl PromptDriver for Script {
+        fn ask_bool(&self, title: &str, _help: &str, default: bool) -> Result<bool> {
+            self.step(title, default)
+        }
+        fn ask_string(&self, title: &str, _help: &str, default: &str) -> Result<String> {
+            self.step(title, default.to_string())
+        }
+        fn ask_u64(
+            &self,
+            title: &str,
+            _help: &str,
+            default: u64,
+            _min: Option<u64>,
+            _max: Option<u64>,
+        ) -> Result<u64> {
+            self.step(title, default)
+        }
+        fn ask_f64(
+            &self,
+            title: &str,
+            _help: &str,
+            default: f64,
+            _min: Option<f64>,
+            _max: Option<f64>,
+        ) -> Result<f64> {
+            self.step(title, default)
+        }
+    }
+
+    #[test]
+    fn go_back_reasks_the_previous_field_with_its_answer() {
+        let driver = Script::default();
+        driver
+            .back_at
+            .borrow_mut()
+            .extend([false, false, true, false, false]);
+        let prefill = json!({ "function_id": 3, "seed": 9 })
+            .as_object()
+            .cloned()
+            .unwrap();
+
+        let mut params = Map::new();
+        prompt_params::<StreamChoice, _>(
+            &driver,
+            StreamKind::SeaGenerator,
+            &prefill,
+            &mut params,
+            "",
+        )
+        .unwrap();
+
+        let asked = driver.asked.borrow();
+        assert_eq!(asked[0].1, "3");
+        assert_ne!(asked[2].0, asked[1].0);
+        assert_eq!(asked[3].0, asked[1].0);
+        assert_eq!(params["function_id"], json!(3));
+        assert_eq!(params["seed"], json!(9));
+    }
+
+    #[test]
+    fn go_back_on_the_first_field_keeps_answers_and_propagates() {
+        let driver = Script::default();
+        driver.back_at.borrow_mut().extend([false, true, true]);
+
+        let mut params = Map::new();
+        let err = prompt_params::<StreamChoice, _>(
+            &driver,
+            StreamKind::SeaGenerator,
+            &Map::new(),
+            &mut params,
+            "",
+        )
+        .unwrap_err();
+
+        assert!(GoBack::is(&err));
+        assert!(params.contains_key("function_id"));
+        assert_eq!(driver.asked.borrow().len(), 3);
+    }
+
+    #[test]
+    fn start_at_reasks_only_the_invalid_field_onwards() {
+        let driver = Script::default();
+        let prefill = json!({ "function_id": 2, "balance": true, "noise_pct": 0.1, "seed": 5 })
+            .as_object()
+            .cloned()
+            .unwrap();
+
+        let mut params = Map::new();
+        prompt_params::<StreamChoice, _>(
+            &driver,
+            StreamKind::SeaGenerator,
+            &prefill,
+            &mut params,
+            "noise_pct",
+        )
+        .unwrap();
+
+        let asked = driver.asked.borrow();
+        assert_eq!(asked[0], ("Noise".to_string(), "0.1".to_string()));
+        assert_eq!(asked.len(), 4);
+        assert_eq!(params["function_id"], json!(2));
+        assert_eq!(params["balance"], json!(true));
+        assert_eq!(params["seed"], json!(5));
+    }
+
+    #[test]
+    fn review_lines_nest_sub_choices_under_their_parameter() {
+        let choice = json!({
+            "type": "evaluate-prequential",
+            "params": {
+                "max_instances": null,
+                "learner": { "type": "hoeffding-tree", "params": { "grace_period": 200 } },
+            }
+        });
+        assert_eq!(
+            review_lines(&choice),
+            vec![
+                "evaluate-prequential",
+                "  max_instances: none",
+                "  learner: hoeffding-tree",
+                "    grace_period: 200",
+            ]
+        );
+    }
+
+    struct Answers {
+        save: bool,
+        path: String,
+    }
+
+    impl PromptDriver for Answers {
+        fn ask_bool(&self, _title: &str, _help: &str, _default: bool) -> Result<bool> {
+            Ok(self.save)
+        }
+        fn ask_string(&self, _title: &str, _help: &str, _default: &str) -> Result<String> {
+            Ok(self.path.clone())
+        }
+        fn ask_u64(
+            &self,
+            _title: &str,
+            _help: &str,
+            default: u64,
+            _min: Option<u64>,
+            _max: Option<u64>,
+        ) -> Result<u64> {
+            Ok(default)
+        }
+        fn ask_f64(
+            &self,
+            _title: &str,
+            _help: &str,
+            default: f64,
+            _min: Option<f64>,
+            _max: Option<f64>,
+        ) -> Result<f64> {
+            Ok(default)
+        }
+    }
+
+    #[test]
+    fn save_config_writes_json_only_when_accepted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runs").join("exp.json");
+        let choice = json!({ "type": "evaluate-prequential", "params": {} });
+
+        let declined = Answers {
+            save: false,
+            path: path.to_string_lossy().into_owned(),
+        };
+        assert!(prompt_save_config(&declined, &choice).unwrap().is_none());
+        assert!(!path.exists());
+
+        let accepted = Answers {
+            save: true,
+            ..declined
+        };
+        assert_eq!(
+            prompt_save_config(&accepted, &choice).unwrap(),
+            Some(path.clone())
+        );
+        let back: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(back, choice);
+    }
+
+    #[test]
+    fn configs_with_a_yaml_extension_are_written_as_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("exp.yml");
+        let choice = json!({ "type": "evaluate-prequential", "params": { "max_instances": 5 } });
+        write_config(&choice, &path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("type: evaluate-prequential"), "{text}");
+        let back: Value = serde_yaml::from_str(&text).unwrap();
+        assert_eq!(back, choice);
+    }
+}
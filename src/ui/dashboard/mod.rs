@@ -0,0 +1,336 @@
+//! Live web dashboard for `--serve`.
+//!
+//! A small HTTP/1.1 server on the standard library: `/` serves an embedded
+//! page plotting the curves, `/api/events` returns every event so far as a
+//! JSON array, and `/api/stream` replays them and then pushes new ones as
+//! server-sent events. Events are `{"event": "snapshot", "run", "snapshot"}`
+//! for each snapshot (in the [`Snapshot::to_json`] layout) and
+//! `{"event": "end", "run", "status"}` when a run finishes. `/api/models`
+//! returns the latest [`ModelView`](crate::tasks::ModelView) of every run
+//! registered with [`Dashboard::watch_model`], read while training goes on.
+
+use crate::classifiers::introspection::describe;
+use crate::evaluation::Snapshot;
+use crate::tasks::SharedModel;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+const PAGE: &str = include_str!("page.html");
+
+/// How often an idle event stream sends a comment, so that closed
+/// connections are noticed.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Largest request head read before the connection is dropped.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+#[derive(Default)]
+struct Events {
+    log: Mutex<Vec<Value>>,
+    added: Condvar,
+}
+
+impl Events {
+    fn lock(&self) -> MutexGuard<'_, Vec<Value>> {
+        self.log.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn push(&self, event: Value) {
+        self.lock().push(event);
+        self.added.notify_all();
+    }
+}
+
+/// What the server threads read: the event log and the models of the runs.
+#[derive(Default)]
+struct Shared {
+    events: Events,
+    models: Mutex<BTreeMap<String, SharedModel>>,
+}
+
+impl Shared {
+    /// `{run: {"instances_seen", "measurements", "model"}}` for every
+    /// watched run that has published a view; `model` is the
+    /// [`describe`] dump, or `null` for learners that cannot be saved.
+    fn models(&self) -> Value {
+        let models = self
+            .models
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let mut out = Map::new();
+        for (run, model) in models {
+            let Some(view) = model.latest() else {
+                continue;
+            };
+            let measurements: Map<String, Value> = view
+                .measurements
+                .iter()
+                .map(|m| (m.name.to_string(), json!(m.value)))
+                .collect();
+            out.insert(
+                run,
+                json!({
+                    "instances_seen": view.instances_seen,
+                    "measurements": measurements,
+                    "model": view.state.as_ref().map(describe),
+                }),
+            );
+        }
+        Value::Object(out)
+    }
+}
+
+/// Handle to a running dashboard server; clones share the same server.
+///
+/// The server runs on background threads for as long as the process does.
+#[derive(Clone)]
+pub struct Dashboard {
+    shared: Arc<Shared>,
+    addr: SocketAddr,
+}
+
+impl Dashboard {
+    /// Starts serving on `addr` (e.g. `0.0.0.0:8080`, or port `0` for any
+    /// free port).
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let dashboard = Self {
+            shared: Arc::default(),
+            addr: listener.local_addr()?,
+        };
+        let shared = Arc::clone(&dashboard.shared);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let _ = serve(stream, &shared);
+                });
+            }
+        });
+        Ok(dashboard)
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn publish(&self, run: &str, snapshot: &Snapshot) {
+        self.shared.events.push(json!({
+            "event": "snapshot",
+            "run": run,
+            "snapshot": snapshot.to_json(),
+        }));
+    }
+
+    /// Marks `run` as finished with `status` (e.g. `completed`).
+    pub fn end_run(&self, run: &str, status: &str) {
+        self.shared
+            .events
+            .push(json!({ "event": "end", "run": run, "status": status }));
+    }
+
+    /// Every event published so far.
+    pub fn events(&self) -> Vec<Value> {
+        self.shared.events.lock().clone()
+    }
+
+    /// Lists `model` under `run` in `/api/models`, replacing any model
+    /// watched under that name before.
+    pub fn watch_model(&self, run: &str, model: SharedModel) {
+        self.shared
+            .models
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(run.to_string(), model);
+    }
+}
+
+fn serve(stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    let events = &shared.events;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut head = String::new();
+    loop {
+        let n = reader.read_line(&mut head)?;
+        if n == 0 || head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            break;
+        }
+        if head.len() > MAX_HEAD_BYTES {
+            return respond(
+                stream,
+                "431 Request Header Fields Too Large",
+                "text/plain",
+                "",
+            );
+        }
+    }
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, target) = (request_line.next(), request_line.next().unwrap_or("/"));
+    let path = target.split('?').next().unwrap_or(target);
+    match (method, path) {
+        (Some("GET"), "/") => respond(stream, "200 OK", "text/html; charset=utf-8", PAGE),
+        (Some("GET"), "/api/events") => {
+            let body = Value::Array(events.lock().clone()).to_string();
+            respond(stream, "200 OK", "application/json", &body)
+        }
+        (Some("GET"), "/api/stream") => stream_events(stream, events),
+        (Some("GET"), "/api/models") => {
+            let body = shared.models().to_string();
+            respond(stream, "200 OK", "application/json", &body)
+        }
+        (Some("GET"), _) => respond(stream, "404 Not Found", "text/plain", "not found\n"),
+        _ => respond(
+            stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "only GET is supported\n",
+        ),
+    }
+}
+
+fn respond(mut stream: TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Sends every event as a server-sent event, replaying the log first, until
+/// the client disconnects.
+fn stream_events(mut stream: TcpStream, events: &Events) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\n\
+         Connection: keep-alive\r\n\r\n"
+    )?;
+    stream.flush()?;
+    let mut sent = 0;
+    loop {
+        let batch: Vec<Value> = {
+            let log = events.lock();
+            let (log, _) = events
+                .added
+                .wait_timeout_while(log, KEEP_ALIVE, |log| log.len() == sent)
+                .unwrap_or_else(|e| e.into_inner());
+            log[sent..].to_vec()
+        };
+        if batch.is_empty() {
+            stream.write_all(b": keep-alive\n\n")?;
+        }
+        for event in &batch {
+            write!(stream, "data: {event}\n\n")?;
+        }
+        sent += batch.len();
+        stream.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::io::Read;
+
+    fn snapshot(seen: u64) -> Snapshot {
+        Snapshot {
+            instances_seen: seen,
+            accuracy: 0.9,
+            kappa: 0.8,
+            ram_hours: 0.0,
+            seconds: 1.0,
+            extras: BTreeMap::new(),
+            eta: None,
+        }
+    }
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: test\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_the_page_and_the_event_log() {
+        let dashboard = Dashboard::bind("127.0.0.1:0").unwrap();
+        dashboard.publish("sea", &snapshot(100));
+        dashboard.end_run("sea", "completed");
+
+        let page = get(dashboard.local_addr(), "/");
+        assert!(page.starts_with("HTTP/1.1 200 OK"));
+        assert!(page.contains("/api/stream"));
+
+        let events = get(dashboard.local_addr(), "/api/events?x=1");
+        let body = events.split("\r\n\r\n").nth(1).unwrap();
+        let v: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(v[0]["run"], "sea");
+        assert_eq!(v[0]["snapshot"]["instances_seen"], 100);
+        assert_eq!(v[1]["status"], "completed");
+
+        assert!(get(dashboard.local_addr(), "/nope").starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn models_endpoint_reports_the_latest_view_of_each_run() {
+        use crate::classifiers::{Classifier, NaiveBayes};
+        use crate::streams::Stream;
+        use crate::streams::generators::{SeaFunction, SeaGenerator};
+        use crate::tasks::ModelView;
+
+        let dashboard = Dashboard::bind("127.0.0.1:0").unwrap();
+        let model = SharedModel::new();
+        dashboard.watch_model("sea", model.clone());
+        let body = |response: String| -> Value {
+            serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+        };
+        assert_eq!(body(get(dashboard.local_addr(), "/api/models")), json!({}));
+
+        let mut sea = SeaGenerator::new(SeaFunction::F1, false, 0, None, 1).unwrap();
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(Arc::new(sea.header().clone()));
+        for _ in 0..50 {
+            nb.train_on_instance(sea.next_instance().unwrap().as_ref());
+        }
+        model.publish(ModelView::capture(&nb, 50));
+
+        let v = body(get(dashboard.local_addr(), "/api/models"));
+        assert_eq!(v["sea"]["instances_seen"], 50);
+        assert!(v["sea"]["model"].is_object());
+    }
+
+    #[test]
+    fn stream_replays_then_pushes_new_events() {
+        let dashboard = Dashboard::bind("127.0.0.1:0").unwrap();
+        dashboard.publish("a", &snapshot(1));
+
+        let mut stream = TcpStream::connect(dashboard.local_addr()).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        write!(stream, "GET /api/stream HTTP/1.1\r\n\r\n").unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut data = Vec::new();
+        let mut line = String::new();
+        while data.len() < 2 {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            if let Some(event) = line.strip_prefix("data: ") {
+                data.push(serde_json::from_str::<Value>(event).unwrap());
+                if data.len() == 1 {
+                    dashboard.publish("a", &snapshot(2));
+                }
+            }
+        }
+        assert_eq!(data[0]["snapshot"]["instances_seen"], 1);
+        assert_eq!(data[1]["snapshot"]["instances_seen"], 2);
+    }
+}
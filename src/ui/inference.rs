@@ -0,0 +1,518 @@
+//! JSON inference server for `rivu serve`.
+//!
+//! A small HTTP/1.1 server on the standard library around one saved model.
+//! `GET /model` describes the model: its learner, the attributes a request
+//! must give with their kinds and nominal values, and the class.
+//! `POST /predict` takes one instance as a JSON object mapping attribute
+//! names to values (a number for a numeric attribute, one of its labels for
+//! a nominal one, `null` when missing), or an array of such objects, and
+//! answers with `{"prediction", "votes"}` for each. The class attribute may
+//! be included and is ignored; any other unknown name, missing attribute, or
+//! value of the wrong kind is rejected with `400` and `{"error"}` before
+//! the model sees the request.
+//!
+//! Connections are handled on their own threads, at most 64 at once, past
+//! which a connection is answered with `503`; the model lives on one thread
+//! that scores the validated instances in the order they arrive.
+
+use crate::classifiers::ClassifierState;
+use crate::core::attributes::NominalAttribute;
+use crate::core::instance_header::{InstanceHeader, TargetKind};
+use crate::core::instances::DenseInstance;
+use crate::error::RivuError;
+use crate::evaluation::{Measurement, argmax};
+use serde_json::{Map, Value, json};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Largest request head read; a longer one is answered with `431`.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+/// Connections served at once.
+const MAX_CONNECTIONS: usize = 64;
+
+/// Largest request body accepted.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// How long a connection may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Instances to score and where to send their votes.
+type Job = (Vec<Vec<f64>>, Sender<Vec<Vec<f64>>>);
+
+/// What the connection threads share: the model's header, its `/model`
+/// description, the queue into the model thread, and how many connections
+/// are being served.
+struct Shared {
+    header: Arc<InstanceHeader>,
+    info: String,
+    jobs: Sender<Job>,
+    active: AtomicUsize,
+}
+
+/// Handle to a running inference server.
+pub struct InferenceServer {
+    addr: SocketAddr,
+    accept: JoinHandle<()>,
+}
+
+impl InferenceServer {
+    /// Starts serving `state` on `addr` (e.g. `127.0.0.1:8000`, or port `0`
+    /// for any free port).
+    ///
+    /// Fails when the model was saved without its header, since requests
+    /// could then not be checked against the attributes it was trained on.
+    pub fn bind<A: ToSocketAddrs>(addr: A, state: ClassifierState) -> io::Result<Self> {
+        let header = state.header.clone().map(Arc::new).ok_or_else(|| {
+            RivuError::model("the model was saved without its header and cannot be served")
+        })?;
+        header.validate_class()?;
+        let learner = state.learner.name();
+
+        // Classifiers are not `Send`, so the model is rebuilt on the thread
+        // that owns it, which reports back its measurements or the error.
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let (ready, built) = mpsc::channel();
+        let model_header = Arc::clone(&header);
+        thread::spawn(move || {
+            let model = match state.into_classifier() {
+                Ok(model) => model,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(model.model_measurements()));
+            for (rows, reply) in queue {
+                let votes = rows
+                    .into_iter()
+                    .map(|values| {
+                        let instance = DenseInstance::new(Arc::clone(&model_header), values, 1.0);
+                        model.get_votes_for_instance(&instance)
+                    })
+                    .collect();
+                let _ = reply.send(votes);
+            }
+        });
+        let measurements = built.recv().map_err(io::Error::other)??;
+        let info = model_info(learner, &header, &measurements).to_string();
+
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            header,
+            info,
+            jobs,
+            active: AtomicUsize::new(0),
+        });
+        let accept = thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if shared.active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    shared.active.fetch_sub(1, Ordering::SeqCst);
+                    let _ = turn_away(stream);
+                    continue;
+                }
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let _ = serve(stream, &shared);
+                    shared.active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        Ok(Self { addr, accept })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Blocks for as long as the server runs, which is until the process
+    /// exits.
+    pub fn wait(self) {
+        let _ = self.accept.join();
+    }
+}
+
+/// The `GET /model` description.
+fn model_info(learner: &str, header: &InstanceHeader, measurements: &[Measurement]) -> Value {
+    let attributes: Vec<Value> = header
+        .attributes
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != header.class_index())
+        .map(|(_, attr)| attribute_json(attr.as_any().downcast_ref(), attr.name()))
+        .collect();
+    let class = &header.attributes[header.class_index()];
+    let measurements: Map<String, Value> = measurements
+        .iter()
+        .map(|m| (m.name.to_string(), json!(m.value)))
+        .collect();
+    json!({
+        "learner": learner,
+        "relation": header.relation_name(),
+        "attributes": attributes,
+        "class": attribute_json(class.as_any().downcast_ref(), class.name()),
+        "measurements": measurements,
+    })
+}
+
+fn attribute_json(nominal: Option<&NominalAttribute>, name: String) -> Value {
+    match nominal {
+        Some(n) => json!({ "name": name, "type": "nominal", "values": n.values }),
+        None => json!({ "name": name, "type": "numeric" }),
+    }
+}
+
+/// Attribute values of one request instance, in header order with the class
+/// missing.
+fn instance_values(header: &InstanceHeader, instance: &Value) -> Result<Vec<f64>, String> {
+    let Some(fields) = instance.as_object() else {
+        return Err(format!(
+            "an instance must be an object of attribute values, got {instance}"
+        ));
+    };
+    let class_index = header.class_index();
+    if let Some(name) = fields
+        .keys()
+        .find(|name| header.index_of_attribute(name).is_none())
+    {
+        return Err(format!("unknown attribute '{name}'"));
+    }
+    let mut values = Vec::with_capacity(header.number_of_attributes());
+    for (i, attr) in header.attributes.iter().enumerate() {
+        let name = attr.name();
+        if i == class_index {
+            values.push(f64::NAN);
+            continue;
+        }
+        let value = fields
+            .get(&name)
+            .ok_or_else(|| format!("missing attribute '{name}'"))?;
+        let nominal = attr.as_any().downcast_ref::<NominalAttribute>();
+        values.push(match (value, nominal) {
+            (Value::Null, _) => f64::NAN,
+            (Value::Number(n), None) => n.as_f64().unwrap_or(f64::NAN),
+            (Value::String(label), Some(n)) => match n.values.iter().position(|v| v == label) {
+                Some(index) => index as f64,
+                None => {
+                    return Err(format!(
+                        "'{label}' is not a value of nominal attribute '{name}' (expected one of {})",
+                        n.values.join(", ")
+                    ));
+                }
+            },
+            (_, Some(_)) => {
+                return Err(format!(
+                    "nominal attribute '{name}' needs a label, got {value}"
+                ));
+            }
+            (_, None) => {
+                return Err(format!(
+                    "numeric attribute '{name}' needs a number, got {value}"
+                ));
+            }
+        });
+    }
+    Ok(values)
+}
+
+/// `{"prediction", "votes"}` for one instance: the label with the largest
+/// finite vote and the votes by label for a nominal class, the predicted
+/// value for a numeric one.
+fn prediction_json(header: &InstanceHeader, votes: &[f64]) -> Value {
+    let finite = |v: f64| v.is_finite().then_some(v);
+    match header.target_kind() {
+        Some(TargetKind::Numeric) => {
+            json!({ "prediction": votes.first().copied().and_then(finite) })
+        }
+        _ => {
+            let class = header.attributes[header.class_index()]
+                .as_any()
+                .downcast_ref::<NominalAttribute>();
+            let label = |i: usize| class.and_then(|c| c.values.get(i)).cloned();
+            let by_label: Map<String, Value> = votes
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| (label(i).unwrap_or_else(|| i.to_string()), json!(finite(v))))
+                .collect();
+            json!({
+                "prediction": argmax(votes).and_then(label),
+                "votes": by_label,
+            })
+        }
+    }
+}
+
+/// Validates a `/predict` body and scores it on the model thread.
+fn predict(shared: &Shared, body: &[u8]) -> Result<Value, String> {
+    let body: Value =
+        serde_json::from_slice(body).map_err(|e| format!("request body is not JSON: {e}"))?;
+    let (instances, batch) = match &body {
+        Value::Array(items) => (items.as_slice(), true),
+        one => (std::slice::from_ref(one), false),
+    };
+    let rows = instances
+        .iter()
+        .enumerate()
+        .map(|(i, instance)| {
+            instance_values(&shared.header, instance).map_err(|e| match batch {
+                true => format!("instance {i}: {e}"),
+                false => e,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (reply, votes) = mpsc::channel();
+    shared
+        .jobs
+        .send((rows, reply))
+        .map_err(|_| "the model is no longer running".to_string())?;
+    let votes = votes
+        .recv()
+        .map_err(|_| "the model is no longer running".to_string())?;
+    let mut predictions = votes.iter().map(|v| prediction_json(&shared.header, v));
+    Ok(match batch {
+        true => Value::Array(predictions.collect()),
+        false => predictions.next().unwrap_or(Value::Null),
+    })
+}
+
+fn serve(stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut head = String::new();
+    let mut limited = reader.by_ref().take(MAX_HEAD_BYTES as u64);
+    loop {
+        let n = limited.read_line(&mut head)?;
+        if head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            break;
+        }
+        if n == 0 {
+            if limited.limit() == 0 {
+                return respond(
+                    stream,
+                    "431 Request Header Fields Too Large",
+                    &json!({ "error": format!("request head over {MAX_HEAD_BYTES} bytes") }),
+                );
+            }
+            break;
+        }
+    }
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, target) = (request_line.next(), request_line.next().unwrap_or("/"));
+    let path = target.split('?').next().unwrap_or(target);
+    match (method, path) {
+        (Some("GET"), "/model") => {
+            let mut stream = stream;
+            write_response(&mut stream, "200 OK", &shared.info)
+        }
+        (Some("POST"), "/predict") => {
+            let length = content_length(&head).unwrap_or(0);
+            if length > MAX_BODY_BYTES {
+                return respond(
+                    stream,
+                    "413 Content Too Large",
+                    &json!({ "error": format!("request body over {MAX_BODY_BYTES} bytes") }),
+                );
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body)?;
+            match predict(shared, &body) {
+                Ok(predictions) => respond(stream, "200 OK", &predictions),
+                Err(e) => respond(stream, "400 Bad Request", &json!({ "error": e })),
+            }
+        }
+        (Some("GET" | "POST"), _) => respond(
+            stream,
+            "404 Not Found",
+            &json!({ "error": "not found; use GET /model or POST /predict" }),
+        ),
+        _ => respond(
+            stream,
+            "405 Method Not Allowed",
+            &json!({ "error": "only GET and POST are supported" }),
+        ),
+    }
+}
+
+/// Answers a connection past [`MAX_CONNECTIONS`] with `503`. What has
+/// already arrived of its request is read off first, as closing a socket
+/// with unread data resets it and can lose the answer.
+fn turn_away(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_nonblocking(true)?;
+    let _ = io::copy(&mut stream, &mut io::sink());
+    stream.set_nonblocking(false)?;
+    respond(
+        stream,
+        "503 Service Unavailable",
+        &json!({ "error": "too many connections; try again later" }),
+    )
+}
+
+/// Value of the `Content-Length` header of a request head.
+fn content_length(head: &str) -> Option<usize> {
+    head.lines().skip(1).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("content-length")
+            .then(|| value.trim().parse().ok())?
+    })
+}
+
+fn respond(mut stream: TcpStream, status: &str, body: &Value) -> io::Result<()> {
+    write_response(&mut stream, status, &body.to_string())
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifiers::{Classifier, NaiveBayes};
+    use crate::streams::Stream;
+    use crate::streams::generators::{AgrawalFunction, AgrawalGenerator};
+
+    fn trained_server() -> InferenceServer {
+        let mut stream = AgrawalGenerator::new(AgrawalFunction::F1, false, 0.0, None, 3).unwrap();
+        let mut nb = NaiveBayes::new();
+        nb.set_model_context(Arc::new(stream.header().clone()));
+        for _ in 0..2000 {
+            nb.train_on_instance(stream.next_instance().unwrap().as_ref());
+        }
+        InferenceServer::bind("127.0.0.1:0", nb.snapshot_state().unwrap()).unwrap()
+    }
+
+    fn request(server: &InferenceServer, method: &str, path: &str, body: &str) -> (String, Value) {
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: x\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.lines().next().unwrap().to_string();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    /// An instance for the Agrawal header with every attribute given.
+    fn applicant(age: f64) -> Value {
+        json!({
+            "salary": 50000.0, "commission": 0.0, "age": age, "elevel": "L2",
+            "car": "C5", "zipcode": "Z3", "hvalue": 150000.0, "hyears": 10.0,
+            "loan": 100000.0,
+        })
+    }
+
+    #[test]
+    fn the_model_description_lists_the_expected_attributes() {
+        let server = trained_server();
+        let (status, info) = request(&server, "GET", "/model", "");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(info["learner"], "naive-bayes");
+        assert_eq!(info["attributes"].as_array().unwrap().len(), 9);
+        assert_eq!(info["class"]["type"], "nominal");
+    }
+
+    #[test]
+    fn valid_instances_are_scored_one_or_many_at_a_time() {
+        let server = trained_server();
+        let (status, one) = request(&server, "POST", "/predict", &applicant(30.0).to_string());
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        // Function 1 labels applicants under 40 as groupA.
+        assert_eq!(one["prediction"], "groupA");
+        assert_eq!(one["votes"].as_object().unwrap().len(), 2);
+
+        let mut unknown_age = applicant(0.0);
+        unknown_age["age"] = Value::Null;
+        let batch = json!([applicant(30.0), applicant(50.0), unknown_age]);
+        let (_, many) = request(&server, "POST", "/predict", &batch.to_string());
+        let many = many.as_array().unwrap();
+        assert_eq!(many.len(), 3);
+        assert_eq!(many[0], one);
+        assert_eq!(many[1]["prediction"], "groupB");
+    }
+
+    #[test]
+    fn payloads_that_do_not_match_the_header_are_rejected() {
+        let server = trained_server();
+        let reject = |body: Value| {
+            let (status, answer) = request(&server, "POST", "/predict", &body.to_string());
+            assert_eq!(status, "HTTP/1.1 400 Bad Request");
+            answer["error"].as_str().unwrap().to_string()
+        };
+
+        let mut wrong_label = applicant(30.0);
+        wrong_label["car"] = json!("C99");
+        assert!(reject(wrong_label).contains("'C99' is not a value"));
+
+        let mut missing = applicant(30.0);
+        missing.as_object_mut().unwrap().remove("loan");
+        assert_eq!(reject(missing), "missing attribute 'loan'");
+
+        let mut extra = applicant(30.0);
+        extra["height"] = json!(1.8);
+        assert_eq!(
+            reject(json!([applicant(30.0), extra])),
+            "instance 1: unknown attribute 'height'"
+        );
+
+        let mut text = applicant(30.0);
+        text["salary"] = json!("high");
+        assert!(reject(text).contains("numeric attribute 'salary' needs a number"));
+    }
+
+    #[test]
+    fn an_oversized_head_is_answered_with_431() {
+        let server = trained_server();
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        let start = "GET /model HTTP/1.1\r\nX-Pad: ";
+        let pad = "a".repeat(MAX_HEAD_BYTES - start.len());
+        write!(stream, "{start}{pad}").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(
+            response.starts_with("HTTP/1.1 431 Request Header Fields Too Large"),
+            "{response}"
+        );
+    }
+
+    #[test]
+    fn connections_past_the_limit_are_answered_with_503() {
+        let server = trained_server();
+        let idle: Vec<TcpStream> = (0..MAX_CONNECTIONS)
+            .map(|_| TcpStream::connect(server.local_addr()).unwrap())
+            .collect();
+        let mut response = String::new();
+        TcpStream::connect(server.local_addr())
+            .unwrap()
+            .read_to_string(&mut response)
+            .unwrap();
+        assert!(
+            response.starts_with("HTTP/1.1 503 Service Unavailable"),
+            "{response}"
+        );
+
+        drop(idle);
+        let served = (0..100).any(|_| {
+            thread::sleep(Duration::from_millis(20));
+            request(&server, "GET", "/model", "").0 == "HTTP/1.1 200 OK"
+        });
+        assert!(served, "the freed connections were not given back");
+    }
+}
@@ -1,2 +1,4 @@
 pub mod cli;
+pub mod dashboard;
+pub mod inference;
 pub mod types;
@@ -0,0 +1,18 @@
+use crate::clusterers::CluStream;
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::CluStreamParams;
+
+impl TryFrom<CluStreamParams> for CluStream {
+    type Error = BuildError;
+
+    fn try_from(p: CluStreamParams) -> Result<Self, Self::Error> {
+        CluStream::new(
+            p.max_micro_clusters,
+            p.k,
+            p.kernel_radius_factor,
+            p.horizon,
+            p.seed,
+        )
+        .map_err(BuildError::from)
+    }
+}
@@ -0,0 +1,13 @@
+use crate::clusterers::{CluStream, Clusterer, StreamKm};
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::ClustererChoice;
+
+mod clustream;
+mod streamkm;
+
+pub fn build_clusterer(choice: ClustererChoice) -> Result<Box<dyn Clusterer>, BuildError> {
+    match choice {
+        ClustererChoice::CluStream(p) => Ok(Box::new(CluStream::try_from(p)?)),
+        ClustererChoice::StreamKm(p) => Ok(Box::new(StreamKm::try_from(p)?)),
+    }
+}
@@ -0,0 +1,11 @@
+use crate::clusterers::StreamKm;
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::StreamKmParams;
+
+impl TryFrom<StreamKmParams> for StreamKm {
+    type Error = BuildError;
+
+    fn try_from(p: StreamKmParams) -> Result<Self, Self::Error> {
+        StreamKm::new(p.coreset_size, p.k, p.seed).map_err(BuildError::from)
+    }
+}
@@ -0,0 +1,11 @@
+use crate::anomaly::HalfSpaceTrees;
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::HalfSpaceTreesParams;
+
+impl TryFrom<HalfSpaceTreesParams> for HalfSpaceTrees {
+    type Error = BuildError;
+
+    fn try_from(p: HalfSpaceTreesParams) -> Result<Self, Self::Error> {
+        HalfSpaceTrees::new(p.n_trees, p.height, p.window_size, p.seed).map_err(BuildError::from)
+    }
+}
@@ -0,0 +1,11 @@
+use crate::anomaly::{AnomalyDetector, HalfSpaceTrees};
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::DetectorChoice;
+
+mod half_space_trees;
+
+pub fn build_detector(choice: DetectorChoice) -> Result<Box<dyn AnomalyDetector>, BuildError> {
+    match choice {
+        DetectorChoice::HalfSpaceTrees(p) => Ok(Box::new(HalfSpaceTrees::try_from(p)?)),
+    }
+}
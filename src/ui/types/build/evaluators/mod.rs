@@ -1,4 +1,7 @@
-use crate::evaluation::{BasicClassificationEvaluator, BasicEstimator, PerformanceEvaluator};
+use crate::evaluation::{
+    BasicClassificationEvaluator, BasicEstimator, BasicRegressionEvaluator, PerformanceEvaluator,
+    WindowClassificationEvaluator,
+};
 use crate::ui::types::build::BuildError;
 use crate::ui::types::choices::EvaluatorChoice;
 
@@ -12,5 +15,9 @@ pub fn build_evaluator(
             let ev = BasicClassificationEvaluator::<BasicEstimator>::try_from(p)?;
             Ok(Box::new(ev))
         }
+        EvaluatorChoice::BasicRegression(_) => Ok(Box::new(BasicRegressionEvaluator::new())),
+        EvaluatorChoice::WindowClassification(p) => {
+            Ok(Box::new(WindowClassificationEvaluator::new(p.window_size)))
+        }
     }
 }
@@ -0,0 +1,30 @@
+use crate::classifiers::HoeffdingAdaptiveTree;
+use crate::classifiers::hoeffding_tree::LeafPredictionOption;
+use crate::classifiers::hoeffding_tree::split_criteria::GiniSplitCriterion;
+use crate::ui::types::choices::{
+    HoeffdingAdaptiveTreeParams, LeafPredictionChoice, SplitCriterionChoice,
+};
+
+impl From<HoeffdingAdaptiveTreeParams> for HoeffdingAdaptiveTree {
+    fn from(params: HoeffdingAdaptiveTreeParams) -> Self {
+        let split_criterion = Box::new(match params.split_criterion {
+            SplitCriterionChoice::GiniSplit(_) => GiniSplitCriterion::new(),
+        });
+
+        let leaf_prediction = match params.leaf_prediction {
+            LeafPredictionChoice::NBAdaptive(_) => LeafPredictionOption::AdaptiveNaiveBayes,
+            LeafPredictionChoice::MajorityClass(_) => LeafPredictionOption::MajorityClass,
+            LeafPredictionChoice::NaiveBayes(_) => LeafPredictionOption::NaiveBayes,
+        };
+
+        HoeffdingAdaptiveTree::new()
+            .with_grace_period(params.grace_period)
+            .with_split_criterion(split_criterion)
+            .with_split_confidence(params.split_confidence)
+            .with_tie_threshold(params.tie_threshold)
+            .with_binary_splits(params.binary_splits)
+            .with_no_pre_prune(params.no_pre_prune)
+            .with_leaf_prediction(leaf_prediction)
+            .with_nb_threshold(params.nb_threshold)
+    }
+}
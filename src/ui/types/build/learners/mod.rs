@@ -1,14 +1,18 @@
 use crate::classifiers::Classifier;
-use crate::classifiers::{HoeffdingTree, NaiveBayes};
+use crate::classifiers::{HoeffdingAdaptiveTree, HoeffdingTree, NaiveBayes, TargetMean};
 use crate::ui::types::build::BuildError;
 use crate::ui::types::choices::LearnerChoice;
 
+mod hoeffding_adaptive_tree;
 mod hoeffding_tree;
 mod naive_bayes;
+mod target_mean;
 
 pub fn build_learner(choice: LearnerChoice) -> Result<Box<dyn Classifier>, BuildError> {
     match choice {
         LearnerChoice::NaiveBayes(p) => Ok(Box::new(NaiveBayes::from(p))),
         LearnerChoice::HoeffdingTree(p) => Ok(Box::new(HoeffdingTree::from(p))),
+        LearnerChoice::HoeffdingAdaptiveTree(p) => Ok(Box::new(HoeffdingAdaptiveTree::from(p))),
+        LearnerChoice::TargetMean(p) => Ok(Box::new(TargetMean::from(p))),
     }
 }
@@ -0,0 +1,8 @@
+use crate::classifiers::TargetMean;
+use crate::ui::types::choices::NoParams;
+
+impl From<NoParams> for TargetMean {
+    fn from(_: NoParams) -> Self {
+        TargetMean::new()
+    }
+}
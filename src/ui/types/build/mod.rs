@@ -1,10 +1,16 @@
+mod clusterers;
+mod detectors;
 mod error;
 mod evaluators;
 mod learners;
 mod streams;
+mod tasks;
 
 pub use error::BuildError;
 
+pub use clusterers::build_clusterer;
+pub use detectors::build_detector;
 pub use evaluators::build_evaluator;
 pub use learners::build_learner;
 pub use streams::build_stream;
+pub use tasks::{build_seeded_task, build_task};
@@ -33,8 +33,13 @@ impl TryFrom<AgrawalParameters> for AgrawalGenerator {
             })
             .transpose()?;
 
-        AgrawalGenerator::new(func, p.balance, p.perturb_fraction, max_instances, p.seed)
-            .map_err(BuildError::from)
+        let generator =
+            AgrawalGenerator::new(func, p.balance, p.perturb_fraction, max_instances, p.seed)?;
+        Ok(if p.fast_rng {
+            generator.with_fast_rng()
+        } else {
+            generator
+        })
     }
 }
 
@@ -50,6 +55,7 @@ mod tests {
             perturb_fraction: 0.5,
             max_instances: None,
             seed: 42,
+            fast_rng: false,
         }
     }
 
@@ -1,11 +1,23 @@
+use crate::streams::WatchOptions;
 use crate::streams::arff::ArffFileStream;
 use crate::ui::types::build::BuildError;
 use crate::ui::types::choices::ArffParameters;
+use std::time::Duration;
 
 impl TryFrom<ArffParameters> for ArffFileStream {
     type Error = BuildError;
 
     fn try_from(p: ArffParameters) -> Result<Self, Self::Error> {
-        ArffFileStream::new(p.path, p.class_index).map_err(BuildError::from)
+        let mut stream = ArffFileStream::new(p.path, p.class_index)?.with_parse_mode(p.parse_mode);
+        if p.watch {
+            stream = stream.with_watch(WatchOptions {
+                idle_timeout: p.watch_idle_secs.map(Duration::from_secs),
+                ..WatchOptions::default()
+            });
+        }
+        if let Some(threads) = p.decode_threads {
+            stream = stream.with_decode_threads(threads);
+        }
+        Ok(stream)
     }
 }
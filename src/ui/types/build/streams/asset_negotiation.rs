@@ -10,7 +10,11 @@ impl TryFrom<AssetNegotiationParameters> for AssetNegotiationGenerator {
         let rule = AssetRule::try_from(p.rule_id)
             .map_err(|_| BuildError::InvalidParameter("rule_id must be in 1..=5".into()))?;
 
-        AssetNegotiationGenerator::new(rule, p.balance, p.noise_pct, p.seed)
-            .map_err(BuildError::from)
+        let generator = AssetNegotiationGenerator::new(rule, p.balance, p.noise_pct, p.seed)?;
+        Ok(if p.fast_rng {
+            generator.with_fast_rng()
+        } else {
+            generator
+        })
     }
 }
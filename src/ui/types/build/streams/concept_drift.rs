@@ -0,0 +1,10 @@
+use crate::streams::{ConceptDriftStream, Stream};
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::ConceptDriftParameters;
+
+pub(super) fn build(p: ConceptDriftParameters) -> Result<Box<dyn Stream>, BuildError> {
+    let base = super::build_stream(*p.stream)?;
+    let drift = super::build_stream(*p.drift_stream)?;
+    let stream = ConceptDriftStream::new(base, drift, p.position, p.width, p.seed)?;
+    Ok(Box::new(stream))
+}
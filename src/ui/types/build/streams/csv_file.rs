@@ -0,0 +1,23 @@
+use crate::streams::WatchOptions;
+use crate::streams::csv::CsvFileStream;
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::CsvParameters;
+use std::time::Duration;
+
+impl TryFrom<CsvParameters> for CsvFileStream {
+    type Error = BuildError;
+
+    fn try_from(p: CsvParameters) -> Result<Self, Self::Error> {
+        let mut stream = CsvFileStream::new(p.path, p.class_index)?;
+        if p.watch {
+            stream = stream.with_watch(WatchOptions {
+                idle_timeout: p.watch_idle_secs.map(Duration::from_secs),
+                ..WatchOptions::default()
+            });
+        }
+        if p.dynamic_nominals {
+            stream = stream.with_dynamic_nominals();
+        }
+        Ok(stream)
+    }
+}
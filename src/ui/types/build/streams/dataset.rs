@@ -0,0 +1,13 @@
+use crate::streams::Dataset;
+use crate::streams::arff::ArffFileStream;
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::DatasetParameters;
+
+pub(super) fn build(p: DatasetParameters) -> Result<ArffFileStream, BuildError> {
+    let dir = p.data_dir.or_else(Dataset::default_dir).ok_or_else(|| {
+        BuildError::InvalidParameter(
+            "no data directory: set data_dir or $RIVU_DATA_DIR".to_string(),
+        )
+    })?;
+    Ok(p.dataset.open(&dir)?)
+}
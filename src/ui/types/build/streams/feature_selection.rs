@@ -0,0 +1,15 @@
+use crate::streams::Stream;
+use crate::streams::filters::{FeatureSelectionStream, FeatureSelector};
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::FeatureSelectionParameters;
+
+pub(super) fn build(p: FeatureSelectionParameters) -> Result<Box<dyn Stream>, BuildError> {
+    let inner = super::build_stream(*p.stream)?;
+    let mut selector = FeatureSelector::new(inner.header(), p.window, p.update_interval)?
+        .with_bins(p.bins)
+        .with_threshold(p.threshold);
+    if let Some(max) = p.max_features {
+        selector = selector.with_max_features(max);
+    }
+    Ok(Box::new(FeatureSelectionStream::new(inner, selector)))
+}
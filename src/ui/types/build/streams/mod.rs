@@ -1,5 +1,6 @@
 use crate::streams::Stream;
 use crate::streams::arff::ArffFileStream;
+use crate::streams::csv::CsvFileStream;
 use crate::streams::generators::{AgrawalGenerator, AssetNegotiationGenerator, SeaGenerator};
 use crate::ui::types::build::BuildError;
 use crate::ui::types::choices::StreamChoice;
@@ -7,6 +8,11 @@ use crate::ui::types::choices::StreamChoice;
 mod agrawal;
 mod arff_file;
 mod asset_negotiation;
+mod concept_drift;
+mod csv_file;
+mod dataset;
+mod feature_selection;
+mod parquet_file;
 mod sea_generator;
 
 pub fn build_stream(choice: StreamChoice) -> Result<Box<dyn Stream>, BuildError> {
@@ -15,6 +21,15 @@ pub fn build_stream(choice: StreamChoice) -> Result<Box<dyn Stream>, BuildError>
             let s = ArffFileStream::try_from(p)?;
             Ok(Box::new(s))
         }
+        StreamChoice::CsvFile(p) => {
+            let s = CsvFileStream::try_from(p)?;
+            Ok(Box::new(s))
+        }
+        StreamChoice::ParquetFile(p) => parquet_file::build(p),
+        StreamChoice::Dataset(p) => {
+            let s = dataset::build(p)?;
+            Ok(Box::new(s))
+        }
         StreamChoice::SeaGenerator(p) => {
             let s = SeaGenerator::try_from(p)?;
             Ok(Box::new(s))
@@ -27,5 +42,7 @@ pub fn build_stream(choice: StreamChoice) -> Result<Box<dyn Stream>, BuildError>
             let s = AssetNegotiationGenerator::try_from(p)?;
             Ok(Box::new(s))
         }
+        StreamChoice::FeatureSelection(p) => feature_selection::build(p),
+        StreamChoice::ConceptDrift(p) => concept_drift::build(p),
     }
 }
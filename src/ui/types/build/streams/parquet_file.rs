@@ -0,0 +1,16 @@
+use crate::streams::Stream;
+use crate::ui::types::build::BuildError;
+use crate::ui::types::choices::ParquetParameters;
+
+#[cfg(feature = "parquet")]
+pub(super) fn build(p: ParquetParameters) -> Result<Box<dyn Stream>, BuildError> {
+    let s = crate::streams::parquet::ParquetFileStream::new(p.path, p.class_index)?;
+    Ok(Box::new(s))
+}
+
+#[cfg(not(feature = "parquet"))]
+pub(super) fn build(_: ParquetParameters) -> Result<Box<dyn Stream>, BuildError> {
+    Err(BuildError::NotImplemented(
+        "Parquet streams require the `parquet` feature",
+    ))
+}
@@ -29,13 +29,17 @@ impl TryFrom<SeaParameters> for SeaGenerator {
             })
             .transpose()?;
 
-        SeaGenerator::new(
+        let generator = SeaGenerator::new(
             func,
             parameters.balance,
             noice_percentage,
             max_instances,
             parameters.seed,
-        )
-        .map_err(BuildError::from)
+        )?;
+        Ok(if parameters.fast_rng {
+            generator.with_fast_rng()
+        } else {
+            generator
+        })
     }
 }
@@ -0,0 +1,434 @@
+use crate::error::RivuError;
+use crate::evaluation::{AnomalyEvaluator, ClusteringEvaluator};
+use crate::evaluation::{PredictionLog, PredictionLogFormat, ScoreLog};
+use crate::streams::create_sink;
+use crate::tasks::{
+    AnomalyScoring, ClusterStream, ConvertStream, InterleavedTestThenTrain, LearnModel, MultiSeed,
+    PeriodicHeldOutTest, PrequentialCV, PrequentialDelayed, PrequentialEvaluator, ProgressSender,
+    Task, ThroughputBenchmark,
+};
+use crate::ui::types::build::{
+    BuildError, build_clusterer, build_detector, build_evaluator, build_learner, build_stream,
+};
+use crate::ui::types::choices::TaskChoice;
+use std::io::Error;
+use std::path::PathBuf;
+
+mod prequential_cv;
+
+/// Builds a runnable task, wiring `progress` (if any) to receive snapshots.
+pub fn build_task(
+    choice: TaskChoice,
+    progress: Option<ProgressSender>,
+) -> Result<Box<dyn Task>, BuildError> {
+    match choice {
+        TaskChoice::EvaluatePrequential(p) => {
+            let mut t = PrequentialEvaluator::new(
+                build_learner(p.learner)?,
+                build_stream(p.stream)?,
+                build_evaluator(p.evaluator)?,
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?
+            .with_warmup(p.warmup_instances)
+            .with_snapshot_schedule(p.snapshot_schedule);
+            if let Some(path) = p.prediction_log {
+                t = t.with_prediction_log(open_prediction_log(path)?);
+            }
+            if let Some(detector) = p.drift_detector {
+                t = t.with_drift_detector(detector.build());
+            }
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::EvaluateInterleavedTestThenTrain(p) => {
+            let mut t = InterleavedTestThenTrain::new(
+                build_learner(p.learner)?,
+                build_stream(p.stream)?,
+                build_evaluator(p.evaluator)?,
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?
+            .with_warmup(p.warmup_instances)
+            .with_snapshot_schedule(p.snapshot_schedule);
+            if let Some(path) = p.prediction_log {
+                t = t.with_prediction_log(open_prediction_log(path)?);
+            }
+            if let Some(detector) = p.drift_detector {
+                t = t.with_drift_detector(detector.build());
+            }
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::EvaluatePrequentialDelayed(p) => {
+            let mut t = PrequentialDelayed::new(
+                build_learner(p.learner)?,
+                build_stream(p.stream)?,
+                build_evaluator(p.evaluator)?,
+                p.label_delay,
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?;
+            if let Some(path) = p.prediction_log {
+                t = t.with_prediction_log(open_prediction_log(path)?);
+            }
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::EvaluatePeriodicHeldOutTest(p) => {
+            let test_stream = p.test_stream.map(build_stream).transpose()?;
+            let mut t = PeriodicHeldOutTest::new(
+                build_learner(p.learner)?,
+                build_stream(p.stream)?,
+                test_stream,
+                build_evaluator(p.evaluator)?,
+                p.test_size,
+                p.max_instances,
+                p.max_seconds,
+                p.test_frequency,
+                p.mem_check_frequency,
+            )?;
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::EvaluatePrequentialCV(p) => {
+            let learners = (0..p.folds)
+                .map(|_| build_learner(p.learner.clone()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let evaluators = (0..p.folds)
+                .map(|_| build_evaluator(p.evaluator.clone()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut t = PrequentialCV::new(
+                learners,
+                build_stream(p.stream)?,
+                evaluators,
+                p.validation.into(),
+                p.seed,
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?;
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::LearnModel(p) => {
+            let mut t = LearnModel::new(
+                build_learner(p.learner)?,
+                build_stream(p.stream)?,
+                p.model_path,
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?;
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::ThroughputBenchmark(p) => {
+            let mut t = ThroughputBenchmark::new(
+                build_learner(p.learner)?,
+                build_stream(p.stream)?,
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?
+            .with_batch_size(p.batch_size);
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::ClusterStream(p) => {
+            let seed = p.clusterer.seed();
+            let mut t = ClusterStream::new(
+                build_clusterer(p.clusterer)?,
+                build_stream(p.stream)?,
+                ClusteringEvaluator::new(p.silhouette_sample, seed),
+                p.window_size,
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?;
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::AnomalyScoring(p) => {
+            let mut t = AnomalyScoring::new(
+                build_detector(p.detector)?,
+                build_stream(p.stream)?,
+                AnomalyEvaluator::new(p.eval_window, p.positive_class, p.threshold),
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?;
+            if let Some(path) = p.score_log {
+                t = t.with_score_log(open_score_log(path)?);
+            }
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::ConvertStream(p) => {
+            let stream = build_stream(p.stream)?;
+            let sink =
+                create_sink(&p.output, stream.header()).map_err(|e| match RivuError::from(e) {
+                    RivuError::Config(msg) => BuildError::InvalidParameter(msg),
+                    other => BuildError::Io(other.into()),
+                })?;
+            let mut t = ConvertStream::new(
+                stream,
+                sink,
+                p.max_instances,
+                p.max_seconds,
+                p.sample_frequency,
+                p.mem_check_frequency,
+            )?;
+            if let Some(tx) = progress {
+                t = t.with_progress(tx);
+            }
+            Ok(Box::new(t))
+        }
+        TaskChoice::RepeatWithSeeds(p) => {
+            let seeds = p.seeds();
+            build_seeded_task(*p.task, seeds, progress)
+        }
+    }
+}
+
+fn open_prediction_log(path: PathBuf) -> Result<PredictionLog, BuildError> {
+    let format = PredictionLogFormat::from_path(&path).ok_or_else(|| {
+        BuildError::InvalidParameter(format!(
+            "prediction log {} must end in .csv or .jsonl",
+            path.display()
+        ))
+    })?;
+    Ok(PredictionLog::create(path, format)?)
+}
+
+fn open_score_log(path: PathBuf) -> Result<ScoreLog, BuildError> {
+    let format = PredictionLogFormat::from_path(&path).ok_or_else(|| {
+        BuildError::InvalidParameter(format!(
+            "score log {} must end in .csv or .jsonl",
+            path.display()
+        ))
+    })?;
+    Ok(ScoreLog::create(path, format)?)
+}
+
+/// Builds a [`MultiSeed`] task that runs `task` once per seed, reseeded
+/// through [`TaskChoice::with_seed`], and averages the curves.
+pub fn build_seeded_task(
+    task: TaskChoice,
+    seeds: Vec<u64>,
+    progress: Option<ProgressSender>,
+) -> Result<Box<dyn Task>, BuildError> {
+    let factory = Box::new(move |seed| {
+        build_task(task.clone().with_seed(seed), progress.clone()).map_err(|e| match e {
+            BuildError::Io(e) => e,
+            other => Error::other(other),
+        })
+    });
+    Ok(Box::new(MultiSeed::new(seeds, factory)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::types::choices::{
+        ClustererChoice, ClustererKind, DetectorChoice, DetectorKind, EvaluatorChoice,
+        EvaluatorKind, LearnerChoice, LearnerKind, StreamChoice, StreamKind, TaskKind, UIChoice,
+    };
+    use serde_json::{Value, json};
+
+    fn choice_json<C: UIChoice>(kind: C::Kind) -> Value {
+        let choice = C::from_parts(kind, C::default_params(kind)).unwrap();
+        serde_json::to_value(choice).unwrap()
+    }
+
+    fn task(kind: TaskKind) -> TaskChoice {
+        let mut params = TaskChoice::default_params(kind);
+        let obj = params.as_object_mut().unwrap();
+        let components: &[&str] = match kind {
+            TaskKind::RepeatWithSeeds => &[],
+            TaskKind::ConvertStream => &["stream"],
+            TaskKind::ClusterStream => &["clusterer", "stream"],
+            TaskKind::AnomalyScoring => &["detector", "stream"],
+            TaskKind::LearnModel | TaskKind::ThroughputBenchmark => &["learner", "stream"],
+            _ => &["learner", "stream", "evaluator"],
+        };
+        for &key in components {
+            let value = match key {
+                "learner" => choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+                "stream" => choice_json::<StreamChoice>(StreamKind::SeaGenerator),
+                "evaluator" => choice_json::<EvaluatorChoice>(EvaluatorKind::BasicClassification),
+                "clusterer" => choice_json::<ClustererChoice>(ClustererKind::CluStream),
+                _ => choice_json::<DetectorChoice>(DetectorKind::HalfSpaceTrees),
+            };
+            obj.insert(key.into(), value);
+        }
+        if obj.contains_key("max_instances") {
+            obj.insert("max_instances".into(), json!(200));
+        }
+        if obj.contains_key("model_path") {
+            let path =
+                std::env::temp_dir().join(format!("rivu-build-task-{}.json", std::process::id()));
+            obj.insert("model_path".into(), json!(path));
+        }
+        if obj.contains_key("output") {
+            let path =
+                std::env::temp_dir().join(format!("rivu-build-task-{}.csv", std::process::id()));
+            obj.insert("output".into(), json!(path));
+        }
+        if kind == TaskKind::RepeatWithSeeds {
+            obj.insert(
+                "task".into(),
+                serde_json::to_value(task(TaskKind::EvaluatePrequential)).unwrap(),
+            );
+            obj.insert("repetitions".into(), json!(2));
+        }
+        for key in [
+            "sample_frequency",
+            "test_frequency",
+            "test_size",
+            "folds",
+            "label_delay",
+        ] {
+            if obj.contains_key(key) {
+                obj.insert(key.into(), json!(50));
+            }
+        }
+        TaskChoice::from_parts(kind, params).unwrap()
+    }
+
+    #[test]
+    fn every_task_kind_builds_and_runs() {
+        use strum::IntoEnumIterator;
+        for kind in TaskKind::iter() {
+            let mut t = build_task(task(kind), None).unwrap();
+            t.run().unwrap();
+            assert!(t.curve().len() > 0, "{kind} produced no snapshots");
+        }
+    }
+
+    #[test]
+    fn repeat_with_seeds_aggregates_runs() {
+        let mut t = build_task(task(TaskKind::RepeatWithSeeds), None).unwrap();
+        t.run().unwrap();
+
+        let last = t.curve().latest().unwrap();
+        assert_eq!(last.instances_seen, 200);
+        assert!(last.extras.contains_key("accuracy_std"));
+    }
+
+    #[test]
+    fn prediction_log_is_written_and_extension_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("preds.jsonl");
+        let mut choice = task(TaskKind::EvaluatePrequential);
+        if let TaskChoice::EvaluatePrequential(p) = &mut choice {
+            p.prediction_log = Some(path.clone());
+        }
+        let mut t = build_task(choice.clone(), None).unwrap();
+        t.run().unwrap();
+        drop(t);
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), 200);
+
+        if let TaskChoice::EvaluatePrequential(p) = &mut choice {
+            p.prediction_log = Some(dir.path().join("preds.txt"));
+        }
+        let err = build_task(choice, None).err().unwrap();
+        assert!(matches!(err, BuildError::InvalidParameter(_)));
+    }
+
+    #[test]
+    fn drift_detections_line_up_with_the_drift_in_the_report() {
+        use crate::evaluation::DriftAlignment;
+        use crate::experiment::RunDirectory;
+
+        let sea = |function_id| {
+            let mut sea = choice_json::<StreamChoice>(StreamKind::SeaGenerator);
+            sea["params"]["function_id"] = json!(function_id);
+            sea
+        };
+        let stream = json!({
+            "type": "concept-drift",
+            "params": { "stream": sea(1), "drift_stream": sea(4), "position": 3000, "width": 1 },
+        });
+        let config = json!({
+            "type": "evaluate-prequential",
+            "params": {
+                "learner": choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+                "stream": stream,
+                "evaluator": choice_json::<EvaluatorChoice>(EvaluatorKind::BasicClassification),
+                "drift_detector": { "type": "adwin" },
+                "max_instances": 6000,
+                "sample_frequency": 500,
+                "mem_check_frequency": 500,
+            },
+        });
+        let choice: TaskChoice = serde_json::from_value(config.clone()).unwrap();
+        assert_eq!(choice.validate(), Ok(()));
+        let mut t = build_task(choice, None).unwrap();
+        t.run().unwrap();
+
+        let (drifts, detections) = (t.drift_markers(), t.drift_detections());
+        let aligned = DriftAlignment::align(&drifts, &detections, t.curve(), "accuracy");
+        let caught = aligned[0].detected_at.expect("the drift went undetected");
+        assert!((3000..4000).contains(&caught), "{detections:?}");
+
+        let root = tempfile::tempdir().unwrap();
+        let mut dir = RunDirectory::create(root.path(), None).unwrap();
+        dir.write_report("drift", &config, t.curve(), &[], None, &drifts, &detections)
+            .unwrap();
+        let html = std::fs::read_to_string(dir.path().join("report.html")).unwrap();
+        let false_alarms = DriftAlignment::false_alarms(&drifts, &detections);
+        assert!(html.contains(&format!(
+            "{false_alarms} of {} detections matched no drift.",
+            detections.len()
+        )));
+
+        let mut bad = config;
+        bad["params"]["drift_detector"] = json!({ "type": "adwin", "delta": 2.0 });
+        let choice: TaskChoice = serde_json::from_value(bad.clone()).unwrap();
+        assert_eq!(choice.validate().unwrap_err().field, "drift_detector");
+        bad["params"]["drift_detector"] = json!({ "type": "adwin", "delt": 0.1 });
+        assert!(serde_json::from_value::<TaskChoice>(bad).is_err());
+    }
+
+    #[test]
+    fn convert_stream_rejects_unknown_output_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut choice = task(TaskKind::ConvertStream);
+        if let TaskChoice::ConvertStream(p) = &mut choice {
+            p.output = dir.path().join("out.xlsx");
+        }
+        let err = build_task(choice, None).err().unwrap();
+        assert!(matches!(err, BuildError::InvalidParameter(_)));
+    }
+}
@@ -0,0 +1,12 @@
+use crate::tasks::ValidationMethodology;
+use crate::ui::types::choices::ValidationMethodChoice;
+
+impl From<ValidationMethodChoice> for ValidationMethodology {
+    fn from(choice: ValidationMethodChoice) -> Self {
+        match choice {
+            ValidationMethodChoice::CrossValidation(_) => ValidationMethodology::CrossValidation,
+            ValidationMethodChoice::SplitValidation(_) => ValidationMethodology::SplitValidation,
+            ValidationMethodChoice::Bootstrap(_) => ValidationMethodology::Bootstrap,
+        }
+    }
+}
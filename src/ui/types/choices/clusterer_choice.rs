@@ -0,0 +1,210 @@
+use crate::ui::types::choices::UIChoice;
+use schemars::{JsonSchema, Schema, schema_for};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumMessage, EnumString, IntoStaticStr};
+
+fn default_max_micro_clusters() -> usize {
+    100
+}
+fn default_macro_clusters() -> usize {
+    5
+}
+fn default_kernel_radius_factor() -> f64 {
+    2.0
+}
+fn default_horizon() -> u64 {
+    1_000
+}
+fn default_coreset_size() -> usize {
+    200
+}
+fn default_seed() -> u64 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, EnumDiscriminants)]
+#[serde(tag = "type", content = "params", rename_all = "kebab-case")]
+#[strum_discriminants(name(ClustererKind))]
+#[strum_discriminants(derive(EnumIter, EnumString, Display, IntoStaticStr, EnumMessage))]
+#[strum_discriminants(strum(serialize_all = "kebab-case"))]
+pub enum ClustererChoice {
+    #[strum_discriminants(strum(
+        message = "CluStream",
+        detailed_message = "Online micro-clusters summarized into k macro-clusters with k-means."
+    ))]
+    CluStream(CluStreamParams),
+    #[strum_discriminants(strum(
+        message = "StreamKM++",
+        detailed_message = "Merge-and-reduce coresets in logarithmic memory, clustered with k-means++."
+    ))]
+    StreamKm(StreamKmParams),
+}
+
+impl ClustererChoice {
+    /// Returns the same clusterer with its PRNG seed replaced.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        match &mut self {
+            ClustererChoice::CluStream(p) => p.seed = seed,
+            ClustererChoice::StreamKm(p) => p.seed = seed,
+        }
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        match self {
+            ClustererChoice::CluStream(p) => p.seed,
+            ClustererChoice::StreamKm(p) => p.seed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CluStreamParams {
+    #[serde(default = "default_max_micro_clusters")]
+    #[schemars(
+        title = "Max Micro-Clusters",
+        description = "Number of micro-clusters kept online",
+        range(min = 1),
+        default = "default_max_micro_clusters"
+    )]
+    pub max_micro_clusters: usize,
+
+    #[serde(default = "default_macro_clusters")]
+    #[schemars(
+        title = "Macro-Clusters (k)",
+        description = "Number of clusters produced by the offline k-means step",
+        range(min = 1),
+        default = "default_macro_clusters"
+    )]
+    pub k: usize,
+
+    #[serde(default = "default_kernel_radius_factor")]
+    #[schemars(
+        title = "Kernel Radius Factor",
+        description = "A point joins a micro-cluster within this many radii of its center",
+        default = "default_kernel_radius_factor"
+    )]
+    pub kernel_radius_factor: f64,
+
+    #[serde(default = "default_horizon")]
+    #[schemars(
+        title = "Horizon",
+        description = "Micro-clusters not refreshed within this many instances may be deleted",
+        range(min = 1),
+        default = "default_horizon"
+    )]
+    pub horizon: u64,
+
+    #[serde(default = "default_seed")]
+    #[schemars(
+        title = "Seed",
+        description = "k-means++ seed",
+        default = "default_seed"
+    )]
+    pub seed: u64,
+}
+
+impl Default for CluStreamParams {
+    fn default() -> Self {
+        Self {
+            max_micro_clusters: default_max_micro_clusters(),
+            k: default_macro_clusters(),
+            kernel_radius_factor: default_kernel_radius_factor(),
+            horizon: default_horizon(),
+            seed: default_seed(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct StreamKmParams {
+    #[serde(default = "default_coreset_size")]
+    #[schemars(
+        title = "Coreset Size",
+        description = "Weighted points per coreset; memory grows with this times the log of the stream length",
+        range(min = 1),
+        default = "default_coreset_size"
+    )]
+    pub coreset_size: usize,
+
+    #[serde(default = "default_macro_clusters")]
+    #[schemars(
+        title = "Clusters (k)",
+        description = "Number of clusters produced by the offline k-means++ step",
+        range(min = 1),
+        default = "default_macro_clusters"
+    )]
+    pub k: usize,
+
+    #[serde(default = "default_seed")]
+    #[schemars(
+        title = "Seed",
+        description = "Coreset sampling and k-means++ seed",
+        default = "default_seed"
+    )]
+    pub seed: u64,
+}
+
+impl Default for StreamKmParams {
+    fn default() -> Self {
+        Self {
+            coreset_size: default_coreset_size(),
+            k: default_macro_clusters(),
+            seed: default_seed(),
+        }
+    }
+}
+
+impl UIChoice for ClustererChoice {
+    type Kind = ClustererKind;
+
+    fn schema() -> Schema {
+        schema_for!(ClustererChoice)
+    }
+
+    fn prompt_label() -> &'static str {
+        "Choose a clusterer:"
+    }
+
+    fn default_params(kind: Self::Kind) -> Value {
+        match kind {
+            ClustererKind::CluStream => serde_json::to_value(CluStreamParams::default()).unwrap(),
+            ClustererKind::StreamKm => serde_json::to_value(StreamKmParams::default()).unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clustream_defaults_round_trip() {
+        let params = <ClustererChoice as UIChoice>::default_params(ClustererKind::CluStream);
+        let choice =
+            <ClustererChoice as UIChoice>::from_parts(ClustererKind::CluStream, params).unwrap();
+        let v = serde_json::to_value(&choice).unwrap();
+        assert_eq!(v["type"], "clu-stream");
+        assert_eq!(v["params"]["k"], 5);
+
+        let ClustererChoice::CluStream(p) = choice.with_seed(9) else {
+            panic!("expected CluStream");
+        };
+        assert_eq!(p.seed, 9);
+        assert_eq!(p.max_micro_clusters, 100);
+    }
+
+    #[test]
+    fn streamkm_defaults_round_trip() {
+        let params = <ClustererChoice as UIChoice>::default_params(ClustererKind::StreamKm);
+        let choice =
+            <ClustererChoice as UIChoice>::from_parts(ClustererKind::StreamKm, params).unwrap();
+        let v = serde_json::to_value(&choice).unwrap();
+        assert_eq!(v["type"], "stream-km");
+        assert_eq!(v["params"]["coreset_size"], 200);
+        assert_eq!(choice.with_seed(4).seed(), 4);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::ui::types::choices::UIChoice;
+use schemars::{JsonSchema, Schema, schema_for};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumMessage, EnumString, IntoStaticStr};
+
+fn default_n_trees() -> usize {
+    25
+}
+fn default_height() -> usize {
+    15
+}
+fn default_window_size() -> u64 {
+    250
+}
+fn default_seed() -> u64 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, EnumDiscriminants)]
+#[serde(tag = "type", content = "params", rename_all = "kebab-case")]
+#[strum_discriminants(name(DetectorKind))]
+#[strum_discriminants(derive(EnumIter, EnumString, Display, IntoStaticStr, EnumMessage))]
+#[strum_discriminants(strum(serialize_all = "kebab-case"))]
+pub enum DetectorChoice {
+    #[strum_discriminants(strum(
+        message = "Half-Space Trees",
+        detailed_message = "Ensemble of random half-space trees scoring mass in sliding windows."
+    ))]
+    HalfSpaceTrees(HalfSpaceTreesParams),
+}
+
+impl DetectorChoice {
+    /// Returns the same detector with its PRNG seed replaced.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        match &mut self {
+            DetectorChoice::HalfSpaceTrees(p) => p.seed = seed,
+        }
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        match self {
+            DetectorChoice::HalfSpaceTrees(p) => p.seed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HalfSpaceTreesParams {
+    #[serde(default = "default_n_trees")]
+    #[schemars(
+        title = "Trees",
+        description = "Number of half-space trees in the ensemble",
+        range(min = 1),
+        default = "default_n_trees"
+    )]
+    pub n_trees: usize,
+
+    #[serde(default = "default_height")]
+    #[schemars(
+        title = "Height",
+        description = "Depth of every tree",
+        range(min = 1, max = 30),
+        default = "default_height"
+    )]
+    pub height: usize,
+
+    #[serde(default = "default_window_size")]
+    #[schemars(
+        title = "Window Size",
+        description = "Instances per mass window; scores are 0 until the first window completes",
+        range(min = 1),
+        default = "default_window_size"
+    )]
+    pub window_size: u64,
+
+    #[serde(default = "default_seed")]
+    #[schemars(
+        title = "Seed",
+        description = "Seed for the random split dimensions",
+        default = "default_seed"
+    )]
+    pub seed: u64,
+}
+
+impl Default for HalfSpaceTreesParams {
+    fn default() -> Self {
+        Self {
+            n_trees: default_n_trees(),
+            height: default_height(),
+            window_size: default_window_size(),
+            seed: default_seed(),
+        }
+    }
+}
+
+impl UIChoice for DetectorChoice {
+    type Kind = DetectorKind;
+
+    fn schema() -> Schema {
+        schema_for!(DetectorChoice)
+    }
+
+    fn prompt_label() -> &'static str {
+        "Choose an anomaly detector:"
+    }
+
+    fn default_params(kind: Self::Kind) -> Value {
+        match kind {
+            DetectorKind::HalfSpaceTrees => {
+                serde_json::to_value(HalfSpaceTreesParams::default()).unwrap()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_space_trees_defaults_round_trip() {
+        let params = <DetectorChoice as UIChoice>::default_params(DetectorKind::HalfSpaceTrees);
+        let choice =
+            <DetectorChoice as UIChoice>::from_parts(DetectorKind::HalfSpaceTrees, params).unwrap();
+        let v = serde_json::to_value(&choice).unwrap();
+        assert_eq!(v["type"], "half-space-trees");
+        assert_eq!(v["params"]["height"], 15);
+
+        let DetectorChoice::HalfSpaceTrees(p) = choice.with_seed(4);
+        assert_eq!(p.seed, 4);
+        assert_eq!(p.n_trees, 25);
+    }
+}
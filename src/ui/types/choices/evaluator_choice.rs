@@ -1,3 +1,4 @@
+use crate::core::instance_header::TargetKind;
 use crate::ui::types::choices::UIChoice;
 use schemars::{JsonSchema, Schema, schema_for};
 use serde::{Deserialize, Serialize};
@@ -8,6 +9,10 @@ fn default_false() -> bool {
     false
 }
 
+fn default_window_size() -> usize {
+    1_000
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, EnumDiscriminants)]
 #[serde(tag = "type", content = "params", rename_all = "kebab-case")]
 #[strum_discriminants(name(EvaluatorKind))]
@@ -19,9 +24,20 @@ pub enum EvaluatorChoice {
         detailed_message = "Online classification metrics (accuracy, precision/recall, kappa, etc.)."
     ))]
     BasicClassification(BasicClassificationParameters),
+    #[strum_discriminants(strum(
+        message = "Basic Regression",
+        detailed_message = "Online regression metrics for numeric classes (MAE, RMSE, R², etc.)."
+    ))]
+    BasicRegression(BasicRegressionParameters),
+    #[strum_discriminants(strum(
+        message = "Window Classification",
+        detailed_message = "Classification metrics (accuracy, kappa, kappa_t, kappa_m) over the last instances only."
+    ))]
+    WindowClassification(WindowClassificationParameters),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct BasicClassificationParameters {
     #[serde(default = "default_false")]
     #[schemars(
@@ -56,6 +72,45 @@ pub struct BasicClassificationParameters {
     pub f1_per_class: bool,
 }
 
+/// The regression evaluator has no options yet; the empty object keeps the
+/// `params` layout of the other evaluators.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BasicRegressionParameters {}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WindowClassificationParameters {
+    #[serde(default = "default_window_size")]
+    #[schemars(
+        title = "Window size",
+        description = "Latest instances the metrics are computed over",
+        range(min = 1),
+        default = "default_window_size"
+    )]
+    pub window_size: usize,
+}
+
+impl Default for WindowClassificationParameters {
+    fn default() -> Self {
+        Self {
+            window_size: default_window_size(),
+        }
+    }
+}
+
+impl EvaluatorChoice {
+    /// Kind of class the evaluator scores predictions for.
+    pub fn target_kind(&self) -> TargetKind {
+        match self {
+            EvaluatorChoice::BasicClassification(_) | EvaluatorChoice::WindowClassification(_) => {
+                TargetKind::Nominal
+            }
+            EvaluatorChoice::BasicRegression(_) => TargetKind::Numeric,
+        }
+    }
+}
+
 impl UIChoice for EvaluatorChoice {
     type Kind = EvaluatorKind;
 
@@ -71,6 +126,12 @@ impl UIChoice for EvaluatorChoice {
             EvaluatorKind::BasicClassification => {
                 serde_json::to_value(BasicClassificationParameters::default()).unwrap()
             }
+            EvaluatorKind::BasicRegression => {
+                serde_json::to_value(BasicRegressionParameters::default()).unwrap()
+            }
+            EvaluatorKind::WindowClassification => {
+                serde_json::to_value(WindowClassificationParameters::default()).unwrap()
+            }
         }
     }
 }
@@ -160,6 +221,7 @@ mod tests {
             EvaluatorChoice::BasicClassification(p) => {
                 assert_eq!(p, BasicClassificationParameters::default());
             }
+            other => panic!("unexpected evaluator: {other:?}"),
         }
     }
 
@@ -180,6 +242,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn window_classification_defaults_to_a_thousand_instances() {
+        let v = <EvaluatorChoice as UIChoice>::default_params(EvaluatorKind::WindowClassification);
+        assert_eq!(v, json!({ "window_size": 1000 }));
+        let choice: EvaluatorChoice =
+            serde_json::from_value(json!({ "type": "window-classification", "params": {} }))
+                .unwrap();
+        assert!(matches!(
+            choice,
+            EvaluatorChoice::WindowClassification(WindowClassificationParameters {
+                window_size: 1000
+            })
+        ));
+        assert_eq!(choice.target_kind(), TargetKind::Nominal);
+    }
+
     #[test]
     fn discriminant_messages_available() {
         assert_eq!(
@@ -0,0 +1,108 @@
+use super::hoeffding_tree_choice::{
+    default_grace_period, default_nb_threshold, default_split_confidence, default_tie_threshold,
+};
+use crate::ui::types::choices::{LeafPredictionChoice, SplitCriterionChoice};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Growth options of a Hoeffding Adaptive Tree. Those it shares with the
+/// Hoeffding Tree take the same defaults; the memory options are absent,
+/// since the tree bounds itself by regrowing stale branches.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HoeffdingAdaptiveTreeParams {
+    #[serde(default = "default_grace_period")]
+    #[schemars(
+        title = "Grace period",
+        description = "Instances a leaf should observe between split attempts.",
+        default = "default_grace_period"
+    )]
+    pub grace_period: usize,
+
+    #[serde(default)]
+    #[schemars(skip)]
+    pub split_criterion: SplitCriterionChoice,
+
+    #[serde(default = "default_split_confidence")]
+    #[schemars(
+        title = "Split confidence",
+        description = "Allowed error in split decision (0–1).",
+        range(min = 0.0, max = 1.0),
+        default = "default_split_confidence"
+    )]
+    pub split_confidence: f64,
+
+    #[serde(default = "default_tie_threshold")]
+    #[schemars(
+        title = "Tie threshold",
+        description = "Force split when merit difference < threshold (0–1).",
+        range(min = 0.0, max = 1.0),
+        default = "default_tie_threshold"
+    )]
+    pub tie_threshold: f64,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Enforce binary splits?",
+        description = "Only allow binary splits."
+    )]
+    pub binary_splits: bool,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Disable pre-pruning?",
+        description = "Skip pre-pruning checks."
+    )]
+    pub no_pre_prune: bool,
+
+    #[serde(default)]
+    #[schemars(skip)]
+    pub leaf_prediction: LeafPredictionChoice,
+
+    #[serde(default = "default_nb_threshold")]
+    #[schemars(
+        title = "Naive Bayes threshold",
+        description = "Instances before allowing NB at leaves.",
+        default = "default_nb_threshold"
+    )]
+    pub nb_threshold: Option<usize>,
+}
+impl Default for HoeffdingAdaptiveTreeParams {
+    fn default() -> Self {
+        Self {
+            grace_period: default_grace_period(),
+            split_criterion: SplitCriterionChoice::default(),
+            split_confidence: default_split_confidence(),
+            tie_threshold: default_tie_threshold(),
+            binary_splits: false,
+            no_pre_prune: false,
+            leaf_prediction: LeafPredictionChoice::default(),
+            nb_threshold: default_nb_threshold(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::schema_for;
+    use serde_json::json;
+
+    #[test]
+    fn missing_fields_take_the_hoeffding_tree_defaults() {
+        let p: HoeffdingAdaptiveTreeParams = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(p, HoeffdingAdaptiveTreeParams::default());
+        assert_eq!(p.grace_period, 200);
+        assert_eq!(p.nb_threshold, Some(0));
+        assert!(matches!(
+            p.leaf_prediction,
+            LeafPredictionChoice::NBAdaptive(_)
+        ));
+
+        let v = serde_json::to_value(schema_for!(HoeffdingAdaptiveTreeParams)).unwrap();
+        let props = v["properties"].as_object().unwrap();
+        assert!(!props.contains_key("split_criterion"));
+        assert!(!props.contains_key("leaf_prediction"));
+        assert!(props.contains_key("grace_period"));
+    }
+}
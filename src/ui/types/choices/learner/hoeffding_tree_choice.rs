@@ -9,16 +9,16 @@ fn default_max_byte_size() -> usize {
 fn default_memory_estimate_period() -> usize {
     1_000_000
 }
-fn default_grace_period() -> usize {
+pub(super) fn default_grace_period() -> usize {
     200
 }
-fn default_split_confidence() -> f64 {
+pub(super) fn default_split_confidence() -> f64 {
     0.0
 }
-fn default_tie_threshold() -> f64 {
+pub(super) fn default_tie_threshold() -> f64 {
     0.05
 }
-fn default_nb_threshold() -> Option<usize> {
+pub(super) fn default_nb_threshold() -> Option<usize> {
     Some(0)
 }
 fn default_num_bins() -> usize {
@@ -26,6 +26,7 @@ fn default_num_bins() -> usize {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct GaussianNumericClassObserverParams {
     #[serde(default = "default_num_bins")]
     #[schemars(
@@ -110,6 +111,7 @@ impl Default for LeafPredictionChoice {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct HoeffdingTreeParams {
     #[serde(default = "default_max_byte_size")]
     #[schemars(
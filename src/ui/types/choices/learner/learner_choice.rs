@@ -1,3 +1,4 @@
+use crate::core::instance_header::TargetKind;
 use crate::ui::types::choices::UIChoice;
 use crate::ui::types::choices::learner::*;
 use schemars::{JsonSchema, Schema, schema_for};
@@ -7,6 +8,7 @@ use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumMessage, EnumString
 
 /// Empty parameter object so the wizard can still look under "params"
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct NoParams {}
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, EnumDiscriminants)]
@@ -25,6 +27,28 @@ pub enum LearnerChoice {
         detailed_message = "Hoeffding Tree / VFDT."
     ))]
     HoeffdingTree(HoeffdingTreeParams),
+    #[strum_discriminants(strum(
+        message = "Hoeffding Adaptive Tree Classifier",
+        detailed_message = "Hoeffding Tree that regrows branches whose error rises (ADWIN)."
+    ))]
+    HoeffdingAdaptiveTree(HoeffdingAdaptiveTreeParams),
+    #[strum_discriminants(strum(
+        message = "Target Mean Regressor",
+        detailed_message = "Baseline for numeric classes: predicts the mean target seen so far."
+    ))]
+    TargetMean(NoParams),
+}
+
+impl LearnerChoice {
+    /// Kind of class the learner predicts.
+    pub fn target_kind(&self) -> TargetKind {
+        match self {
+            LearnerChoice::NaiveBayes(_)
+            | LearnerChoice::HoeffdingTree(_)
+            | LearnerChoice::HoeffdingAdaptiveTree(_) => TargetKind::Nominal,
+            LearnerChoice::TargetMean(_) => TargetKind::Numeric,
+        }
+    }
 }
 
 impl UIChoice for LearnerChoice {
@@ -40,23 +64,38 @@ impl UIChoice for LearnerChoice {
 
     fn default_params(kind: Self::Kind) -> Value {
         match kind {
-            LearnerKind::NaiveBayes => serde_json::to_value(NoParams::default()).unwrap(),
+            LearnerKind::NaiveBayes | LearnerKind::TargetMean => {
+                serde_json::to_value(NoParams::default()).unwrap()
+            }
             LearnerKind::HoeffdingTree => {
                 serde_json::to_value(HoeffdingTreeParams::default()).unwrap()
             }
+            LearnerKind::HoeffdingAdaptiveTree => {
+                serde_json::to_value(HoeffdingAdaptiveTreeParams::default()).unwrap()
+            }
         }
     }
 
     fn subprompts<D: crate::ui::cli::drivers::PromptDriver>(
         driver: &D,
         kind: Self::Kind,
+        preset: &serde_json::Map<String, Value>,
     ) -> anyhow::Result<Option<serde_json::Map<String, Value>>> {
-        use crate::ui::cli::wizard::prompt_choice;
+        use crate::ui::cli::wizard::prompt_choice_with;
 
         if let LearnerKind::HoeffdingTree = kind {
-            let ne: NumericEstimatorChoice = prompt_choice::<NumericEstimatorChoice, _>(driver)?;
-            let sc: SplitCriterionChoice = prompt_choice::<SplitCriterionChoice, _>(driver)?;
-            let lp: LeafPredictionChoice = prompt_choice::<LeafPredictionChoice, _>(driver)?;
+            let ne: NumericEstimatorChoice = prompt_choice_with::<NumericEstimatorChoice, _>(
+                driver,
+                preset.get("numeric_estimator"),
+            )?;
+            let sc: SplitCriterionChoice = prompt_choice_with::<SplitCriterionChoice, _>(
+                driver,
+                preset.get("split_criterion"),
+            )?;
+            let lp: LeafPredictionChoice = prompt_choice_with::<LeafPredictionChoice, _>(
+                driver,
+                preset.get("leaf_prediction"),
+            )?;
 
             let mut extra = serde_json::Map::new();
             extra.insert("numeric_estimator".into(), serde_json::to_value(ne)?);
@@ -64,6 +103,21 @@ impl UIChoice for LearnerChoice {
             extra.insert("leaf_prediction".into(), serde_json::to_value(lp)?);
             return Ok(Some(extra));
         }
+        if let LearnerKind::HoeffdingAdaptiveTree = kind {
+            let sc: SplitCriterionChoice = prompt_choice_with::<SplitCriterionChoice, _>(
+                driver,
+                preset.get("split_criterion"),
+            )?;
+            let lp: LeafPredictionChoice = prompt_choice_with::<LeafPredictionChoice, _>(
+                driver,
+                preset.get("leaf_prediction"),
+            )?;
+
+            let mut extra = serde_json::Map::new();
+            extra.insert("split_criterion".into(), serde_json::to_value(sc)?);
+            extra.insert("leaf_prediction".into(), serde_json::to_value(lp)?);
+            return Ok(Some(extra));
+        }
         Ok(None)
     }
 }
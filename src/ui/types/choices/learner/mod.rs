@@ -1,4 +1,6 @@
+mod hoeffding_adaptive_tree_choice;
 mod hoeffding_tree_choice;
 pub mod learner_choice;
 
+pub use hoeffding_adaptive_tree_choice::*;
 pub use hoeffding_tree_choice::*;
@@ -1,15 +1,21 @@
+mod clusterer_choice;
+mod detector_choice;
 mod evaluator_choice;
 mod learner;
 mod schema;
 mod stream_choice;
 mod task_choice;
 mod ui_choice;
+mod validation;
 
+pub use clusterer_choice::*;
+pub use detector_choice::*;
 pub use evaluator_choice::*;
 pub use learner::learner_choice::*;
 pub use schema::*;
 pub use stream_choice::*;
 pub use task_choice::*;
 pub use ui_choice::UIChoice;
+pub use validation::{ValidationError, validate_bounds};
 
 pub use learner::*;
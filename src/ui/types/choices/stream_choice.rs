@@ -1,8 +1,18 @@
-use crate::ui::types::choices::UIChoice;
+use crate::core::instance_header::TargetKind;
+use crate::streams::Dataset;
+use crate::streams::Stream;
+use crate::streams::arff::{ArffFileStream, ParseMode};
+use crate::ui::cli::wizard::prompt_choice_with;
+use crate::ui::types::build::build_stream;
+use crate::ui::types::choices::{UIChoice, ValidationError, validate_bounds};
+use crate::utils::file_parsing::split_csv_preserving_quotes;
+use crate::utils::seed_sequence::SeedSequence;
 use schemars::{JsonSchema, Schema, schema_for};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::path::PathBuf;
+use serde_json::{Map, Value, json};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 const DEFAULT_SEED: u64 = 42;
@@ -19,6 +29,7 @@ fn default_agrawal_function() -> u8 {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
 pub struct ArffParameters {
     #[schemars(
         with = "String",
@@ -39,9 +50,128 @@ pub struct ArffParameters {
         range(min = 0)
     )]
     pub class_index: usize,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Watch File",
+        description = "Keep reading rows appended to the file; restart from the first row when it is truncated or replaced"
+    )]
+    pub watch: bool,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Watch Idle Timeout",
+        description = "With Watch File, seconds without new rows before the stream ends; empty = wait forever",
+        range(min = 1)
+    )]
+    pub watch_idle_secs: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Decode Threads",
+        description = "Threads decoding the rows read ahead of the learner, for large files; empty = 1",
+        range(min = 1)
+    )]
+    pub decode_threads: Option<usize>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Parse Mode",
+        description = "What to do with malformed rows: fail the run (strict), skip them (skip) or read bad values as missing (impute)"
+    )]
+    pub parse_mode: ParseMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CsvParameters {
+    #[schemars(
+        with = "String",
+        title = "CSV Path",
+        description = "Path to .csv file with a header row",
+        extend(
+            "format" = "path",
+            "x-file" = true,
+            "x-must-exist" = true,
+            "x-extensions" = ["csv"]
+        )
+    )]
+    pub path: PathBuf,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Class Index",
+        description = "Zero-based index of the class column; empty = last column",
+        range(min = 0)
+    )]
+    pub class_index: Option<usize>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Watch File",
+        description = "Keep reading rows appended to the file; restart from the first row when it is truncated or replaced"
+    )]
+    pub watch: bool,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Watch Idle Timeout",
+        description = "With Watch File, seconds without new rows before the stream ends; empty = wait forever",
+        range(min = 1)
+    )]
+    pub watch_idle_secs: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Grow Nominal Values",
+        description = "Add labels first seen after the file was opened to their nominal attribute (except the class) instead of skipping the row"
+    )]
+    pub dynamic_nominals: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetParameters {
+    #[schemars(
+        with = "String",
+        title = "Parquet Path",
+        description = "Path to .parquet file",
+        extend(
+            "format" = "path",
+            "x-file" = true,
+            "x-must-exist" = true,
+            "x-extensions" = ["parquet"]
+        )
+    )]
+    pub path: PathBuf,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Class Index",
+        description = "Zero-based index of the class column; empty = last column",
+        range(min = 0)
+    )]
+    pub class_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DatasetParameters {
+    #[schemars(title = "Dataset", description = "electricity, covertype or airlines")]
+    pub dataset: Dataset,
+
+    #[serde(default)]
+    #[schemars(
+        with = "Option<String>",
+        title = "Data Directory",
+        description = "Where the dataset is cached; empty = $RIVU_DATA_DIR or the user cache directory",
+        extend("format" = "path")
+    )]
+    pub data_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct SeaParameters {
     #[serde(default = "default_sea_function")]
     #[schemars(
@@ -72,6 +202,13 @@ pub struct SeaParameters {
     #[serde(default = "default_seed")]
     #[schemars(title = "Seed", description = "PRNG seed", default = "default_seed")]
     pub seed: u64,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Fast RNG",
+        description = "Draw from the faster ChaCha8 RNG, for benchmark runs; the same seed then gives a different stream"
+    )]
+    pub fast_rng: bool,
 }
 
 impl Default for SeaParameters {
@@ -82,11 +219,13 @@ impl Default for SeaParameters {
             noise_pct: 0.0,
             max_instances: None,
             seed: DEFAULT_SEED,
+            fast_rng: false,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct AgrawalParameters {
     #[schemars(
         title = "Function",
@@ -116,9 +255,17 @@ pub struct AgrawalParameters {
     #[serde(default = "default_seed")]
     #[schemars(title = "Seed", description = "PRNG seed", default = "default_seed")]
     pub seed: u64,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Fast RNG",
+        description = "Draw from the faster ChaCha8 RNG, for benchmark runs; the same seed then gives a different stream"
+    )]
+    pub fast_rng: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct AssetNegotiationParameters {
     #[schemars(
         title = "Rule",
@@ -140,6 +287,123 @@ pub struct AssetNegotiationParameters {
     #[serde(default = "default_seed")]
     #[schemars(title = "Seed", description = "PRNG seed", default = "default_seed")]
     pub seed: u64,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Fast RNG",
+        description = "Draw from the faster ChaCha8 RNG, for benchmark runs; the same seed then gives a different stream"
+    )]
+    pub fast_rng: bool,
+}
+
+fn default_selection_window() -> usize {
+    1_000
+}
+
+fn default_selection_interval() -> u64 {
+    1_000
+}
+
+fn default_selection_bins() -> usize {
+    10
+}
+
+fn default_selection_threshold() -> f64 {
+    0.01
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FeatureSelectionParameters {
+    #[schemars(skip)]
+    pub stream: Box<StreamChoice>,
+
+    #[serde(default = "default_selection_window")]
+    #[schemars(
+        title = "Window",
+        description = "Latest labelled instances the selection is made over",
+        range(min = 1),
+        default = "default_selection_window"
+    )]
+    pub window: usize,
+
+    #[serde(default = "default_selection_interval")]
+    #[schemars(
+        title = "Update Interval",
+        description = "Instances between two selections",
+        range(min = 1),
+        default = "default_selection_interval"
+    )]
+    pub update_interval: u64,
+
+    #[serde(default = "default_selection_bins")]
+    #[schemars(
+        title = "Bins",
+        description = "Equal-width bins numeric attributes are cut into",
+        range(min = 2),
+        default = "default_selection_bins"
+    )]
+    pub bins: usize,
+
+    #[serde(default = "default_selection_threshold")]
+    #[schemars(
+        title = "Threshold",
+        description = "Least symmetric uncertainty with the class a selected attribute has",
+        range(min = 0.0, max = 1.0),
+        default = "default_selection_threshold"
+    )]
+    pub threshold: f64,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Features",
+        description = "Most attributes selected; empty = no limit",
+        range(min = 1)
+    )]
+    pub max_features: Option<usize>,
+}
+
+fn default_drift_position() -> u64 {
+    1_000
+}
+
+fn default_drift_width() -> u64 {
+    1_000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ConceptDriftParameters {
+    #[schemars(skip)]
+    pub stream: Box<StreamChoice>,
+
+    #[schemars(skip)]
+    pub drift_stream: Box<StreamChoice>,
+
+    #[serde(default = "default_drift_position")]
+    #[schemars(
+        title = "Position",
+        description = "Instances before the centre of the drift",
+        default = "default_drift_position"
+    )]
+    pub position: u64,
+
+    #[serde(default = "default_drift_width")]
+    #[schemars(
+        title = "Width",
+        description = "Instances over which the concepts blend; 1 = abrupt",
+        range(min = 1),
+        default = "default_drift_width"
+    )]
+    pub width: u64,
+
+    #[serde(default = "default_seed")]
+    #[schemars(
+        title = "Seed",
+        description = "PRNG seed of the choice between the streams",
+        default = "default_seed"
+    )]
+    pub seed: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, EnumDiscriminants)]
@@ -154,6 +418,24 @@ pub enum StreamChoice {
     ))]
     ArffFile(ArffParameters),
 
+    #[strum_discriminants(strum(
+        message = "CSV File Stream",
+        detailed_message = "A stream read from a CSV file with a header row."
+    ))]
+    CsvFile(CsvParameters),
+
+    #[strum_discriminants(strum(
+        message = "Parquet File Stream",
+        detailed_message = "A stream read from a Parquet file (requires the `parquet` feature)."
+    ))]
+    ParquetFile(ParquetParameters),
+
+    #[strum_discriminants(strum(
+        message = "Benchmark Dataset",
+        detailed_message = "Electricity, Covertype or Airlines, downloaded on first use and cached."
+    ))]
+    Dataset(DatasetParameters),
+
     #[strum_discriminants(strum(
         message = "SEA Generator",
         detailed_message = "Generates SEA concept functions."
@@ -171,6 +453,95 @@ pub enum StreamChoice {
         detailed_message = "Generates instances using 5 concept functions to model agent interest."
     ))]
     AssetNegotiationGenerator(AssetNegotiationParameters),
+
+    #[strum_discriminants(strum(
+        message = "Feature Selection",
+        detailed_message = "Another stream keeping only the attributes most informative of the class."
+    ))]
+    FeatureSelection(FeatureSelectionParameters),
+
+    #[strum_discriminants(strum(
+        message = "Concept Drift Stream",
+        detailed_message = "Drifts from one stream to another, abruptly or gradually; either may drift again."
+    ))]
+    ConceptDrift(ConceptDriftParameters),
+}
+
+impl StreamChoice {
+    /// Returns the same stream with its PRNG seed replaced.
+    ///
+    /// Streams without a seed (files) are returned unchanged. A concept
+    /// drift derives distinct seeds for itself and its two streams.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        match &mut self {
+            StreamChoice::ArffFile(_)
+            | StreamChoice::CsvFile(_)
+            | StreamChoice::ParquetFile(_)
+            | StreamChoice::Dataset(_) => {}
+            StreamChoice::SeaGenerator(p) => p.seed = seed,
+            StreamChoice::AgrawalGenerator(p) => p.seed = seed,
+            StreamChoice::AssetNegotiationGenerator(p) => p.seed = seed,
+            StreamChoice::FeatureSelection(p) => *p.stream = p.stream.clone().with_seed(seed),
+            StreamChoice::ConceptDrift(p) => {
+                let seeds = SeedSequence::new(seed);
+                p.seed = seeds.derive("drift");
+                *p.stream = p.stream.clone().with_seed(seeds.derive("stream"));
+                *p.drift_stream = p
+                    .drift_stream
+                    .clone()
+                    .with_seed(seeds.derive("drift-stream"));
+            }
+        }
+        self
+    }
+
+    /// Returns the same stream set to follow its file, ending after
+    /// `idle_secs` without new rows when given.
+    ///
+    /// Only ARFF and CSV files can be watched; other streams are returned
+    /// unchanged.
+    pub fn with_watch(mut self, idle_secs: Option<u64>) -> Self {
+        match &mut self {
+            StreamChoice::ArffFile(p) => {
+                p.watch = true;
+                p.watch_idle_secs = idle_secs;
+            }
+            StreamChoice::CsvFile(p) => {
+                p.watch = true;
+                p.watch_idle_secs = idle_secs;
+            }
+            StreamChoice::FeatureSelection(p) => {
+                *p.stream = p.stream.clone().with_watch(idle_secs);
+            }
+            StreamChoice::ConceptDrift(p) => {
+                *p.stream = p.stream.clone().with_watch(idle_secs);
+                *p.drift_stream = p.drift_stream.clone().with_watch(idle_secs);
+            }
+            StreamChoice::ParquetFile(_)
+            | StreamChoice::Dataset(_)
+            | StreamChoice::SeaGenerator(_)
+            | StreamChoice::AgrawalGenerator(_)
+            | StreamChoice::AssetNegotiationGenerator(_) => {}
+        }
+        self
+    }
+
+    /// Kind of the class attribute. The generators and benchmark datasets
+    /// have nominal classes; files are opened to read their header, and
+    /// give `None` when they cannot be read.
+    pub fn target_kind(&self) -> Option<TargetKind> {
+        match self {
+            StreamChoice::ArffFile(_) | StreamChoice::CsvFile(_) | StreamChoice::ParquetFile(_) => {
+                build_stream(self.clone()).ok()?.header().target_kind()
+            }
+            StreamChoice::Dataset(_)
+            | StreamChoice::SeaGenerator(_)
+            | StreamChoice::AgrawalGenerator(_)
+            | StreamChoice::AssetNegotiationGenerator(_) => Some(TargetKind::Nominal),
+            StreamChoice::FeatureSelection(p) => p.stream.target_kind(),
+            StreamChoice::ConceptDrift(p) => p.stream.target_kind(),
+        }
+    }
 }
 
 impl UIChoice for StreamChoice {
@@ -187,6 +558,9 @@ impl UIChoice for StreamChoice {
     fn default_params(kind: Self::Kind) -> Value {
         match kind {
             StreamKind::ArffFile => serde_json::to_value(ArffParameters::default()).unwrap(),
+            StreamKind::CsvFile => serde_json::to_value(CsvParameters::default()).unwrap(),
+            StreamKind::ParquetFile => serde_json::to_value(ParquetParameters::default()).unwrap(),
+            StreamKind::Dataset => serde_json::to_value(DatasetParameters::default()).unwrap(),
             StreamKind::SeaGenerator => serde_json::to_value(SeaParameters::default()).unwrap(),
             StreamKind::AgrawalGenerator => {
                 serde_json::to_value(AgrawalParameters::default()).unwrap()
@@ -194,13 +568,123 @@ impl UIChoice for StreamChoice {
             StreamKind::AssetNegotiationGenerator => {
                 serde_json::to_value(AssetNegotiationParameters::default()).unwrap()
             }
+            StreamKind::FeatureSelection => json!({
+                "window": default_selection_window(),
+                "update_interval": default_selection_interval(),
+                "bins": default_selection_bins(),
+                "threshold": default_selection_threshold(),
+            }),
+            StreamKind::ConceptDrift => json!({
+                "position": default_drift_position(),
+                "width": default_drift_width(),
+                "seed": DEFAULT_SEED,
+            }),
         }
     }
+
+    fn subprompts<D: crate::ui::cli::drivers::PromptDriver>(
+        driver: &D,
+        kind: Self::Kind,
+        preset: &Map<String, Value>,
+    ) -> anyhow::Result<Option<Map<String, Value>>> {
+        match kind {
+            StreamKind::FeatureSelection => {
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+                let mut m = Map::new();
+                m.insert("stream".into(), serde_json::to_value(stream)?);
+                Ok(Some(m))
+            }
+            StreamKind::ConceptDrift => {
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+                let drift_stream =
+                    prompt_choice_with::<StreamChoice, _>(driver, preset.get("drift_stream"))?;
+                let mut m = Map::new();
+                m.insert("stream".into(), serde_json::to_value(stream)?);
+                m.insert("drift_stream".into(), serde_json::to_value(drift_stream)?);
+                Ok(Some(m))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Besides the schema bounds, checks that the class index of an ARFF or
+    /// CSV file names one of its columns. Files that cannot be opened are
+    /// left for the stream to report when it is built.
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_bounds(self)?;
+        match self {
+            StreamChoice::ArffFile(p) => check_class_index(&p.path, p.class_index, arff_width),
+            StreamChoice::CsvFile(p) => match p.class_index {
+                Some(i) => check_class_index(&p.path, i, csv_width),
+                None => Ok(()),
+            },
+            StreamChoice::FeatureSelection(p) => {
+                p.stream.validate().map_err(|e| e.nested("stream"))
+            }
+            StreamChoice::ConceptDrift(p) => {
+                p.stream.validate().map_err(|e| e.nested("stream"))?;
+                p.drift_stream
+                    .validate()
+                    .map_err(|e| e.nested("drift_stream"))
+            }
+            StreamChoice::Dataset(p) => {
+                let dir = p
+                    .data_dir
+                    .clone()
+                    .or_else(Dataset::default_dir)
+                    .ok_or_else(|| {
+                        ValidationError::new(
+                            "data_dir",
+                            "no data directory: set data_dir or $RIVU_DATA_DIR",
+                        )
+                    })?;
+                p.dataset
+                    .check_fetchable(&dir)
+                    .map_err(|e| ValidationError::new("dataset", e.to_string()))
+            }
+            StreamChoice::ParquetFile(_)
+            | StreamChoice::SeaGenerator(_)
+            | StreamChoice::AgrawalGenerator(_)
+            | StreamChoice::AssetNegotiationGenerator(_) => Ok(()),
+        }
+    }
+}
+
+fn check_class_index(
+    path: &Path,
+    class_index: usize,
+    width: fn(&Path) -> Option<usize>,
+) -> Result<(), ValidationError> {
+    match width(path) {
+        Some(n) if class_index >= n => Err(ValidationError::new(
+            "class_index",
+            format!(
+                "class index {class_index} is out of range: {} has {n} columns (0–{})",
+                path.display(),
+                n.saturating_sub(1)
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn arff_width(path: &Path) -> Option<usize> {
+    let stream = ArffFileStream::new(path.to_path_buf(), 0).ok()?;
+    Some(stream.header().number_of_attributes())
+}
+
+fn csv_width(path: &Path) -> Option<usize> {
+    let mut first = String::new();
+    BufReader::new(File::open(path).ok()?)
+        .read_line(&mut first)
+        .ok()?;
+    Some(split_csv_preserving_quotes(first.trim()).len())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::streams::DriftMarker;
     use crate::ui::types::choices::UIChoice;
     use schemars::schema_for;
     use serde_json::{Value, json};
@@ -224,6 +708,7 @@ mod tests {
         let p0 = ArffParameters {
             path: PathBuf::from("data/a.arff"),
             class_index: 1,
+            ..Default::default()
         };
         let j = serde_json::to_string(&p0).unwrap();
         let p1: ArffParameters = serde_json::from_str(&j).unwrap();
@@ -239,6 +724,7 @@ mod tests {
             noise_pct: 0.25,
             max_instances: Some(123),
             seed: 42,
+            fast_rng: true,
         };
         let j = serde_json::to_string(&p0).unwrap();
         let p1: SeaParameters = serde_json::from_str(&j).unwrap();
@@ -253,6 +739,7 @@ mod tests {
             perturb_fraction: 0.1,
             max_instances: None,
             seed: 99,
+            fast_rng: false,
         };
         let j = serde_json::to_string(&p0).unwrap();
         let p1: AgrawalParameters = serde_json::from_str(&j).unwrap();
@@ -266,6 +753,7 @@ mod tests {
             balance: true,
             noise_pct: 0.75,
             seed: 7,
+            fast_rng: true,
         };
         let j = serde_json::to_string(&p0).unwrap();
         let p1: AssetNegotiationParameters = serde_json::from_str(&j).unwrap();
@@ -306,6 +794,37 @@ mod tests {
         let arff_params = serde_json::to_value(ArffParameters::default()).unwrap();
         let e = <StreamChoice as UIChoice>::from_parts(StreamKind::ArffFile, arff_params).unwrap();
         matches!(e, StreamChoice::ArffFile(_));
+
+        let csv_params = serde_json::to_value(CsvParameters::default()).unwrap();
+        let e = <StreamChoice as UIChoice>::from_parts(StreamKind::CsvFile, csv_params).unwrap();
+        assert!(matches!(
+            e,
+            StreamChoice::CsvFile(CsvParameters {
+                class_index: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn dataset_choice_names_the_dataset_and_defaults_its_directory() {
+        let json = json!({ "type": "dataset", "params": { "dataset": "airlines" } });
+        let choice: StreamChoice = serde_json::from_value(json).unwrap();
+        let StreamChoice::Dataset(p) = &choice else {
+            panic!("expected a dataset, got {choice:?}");
+        };
+        assert_eq!(p.dataset, Dataset::Airlines);
+        assert_eq!(p.data_dir, None);
+        assert_eq!(choice.target_kind(), Some(TargetKind::Nominal));
+
+        // A cached file needs no downloader.
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("airlines.arff"), "").unwrap();
+        let cached = StreamChoice::Dataset(DatasetParameters {
+            dataset: Dataset::Airlines,
+            data_dir: Some(dir.path().to_path_buf()),
+        });
+        assert_eq!(cached.validate(), Ok(()));
     }
 
     #[test]
@@ -409,4 +928,132 @@ mod tests {
             "Choose a stream:"
         );
     }
+
+    #[test]
+    fn with_seed_replaces_generator_seed_only() {
+        let sea = StreamChoice::SeaGenerator(SeaParameters::default()).with_seed(7);
+        let StreamChoice::SeaGenerator(p) = sea else {
+            panic!("expected SEA");
+        };
+        assert_eq!(p.seed, 7);
+
+        let arff = StreamChoice::ArffFile(ArffParameters::default()).with_seed(7);
+        assert!(matches!(arff, StreamChoice::ArffFile(_)));
+    }
+
+    #[test]
+    fn feature_selection_wraps_a_stream_and_drops_seas_irrelevant_attribute() {
+        let mut params = StreamChoice::default_params(StreamKind::FeatureSelection);
+        params["stream"] = json!({ "type": "sea-generator", "params": SeaParameters::default() });
+        let choice = StreamChoice::from_parts(StreamKind::FeatureSelection, params)
+            .unwrap()
+            .with_seed(7);
+        assert_eq!(choice.validate(), Ok(()));
+        assert_eq!(choice.target_kind(), Some(TargetKind::Nominal));
+        let StreamChoice::FeatureSelection(p) = &choice else {
+            panic!("expected FeatureSelection");
+        };
+        assert!(matches!(&*p.stream, StreamChoice::SeaGenerator(s) if s.seed == 7));
+
+        let mut stream = build_stream(choice).unwrap();
+        for _ in 0..2_000 {
+            stream.next_instance().unwrap();
+        }
+        let selected = |i: usize| {
+            let name = format!("feature_{i}_selected");
+            stream
+                .measurements()
+                .into_iter()
+                .find(|m| m.name.as_str() == name)
+                .unwrap()
+                .value
+        };
+        assert_eq!((selected(0), selected(1), selected(2)), (1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn concept_drift_nests_and_reports_every_drift() {
+        let sea = |function_id| {
+            let params = SeaParameters {
+                function_id,
+                ..SeaParameters::default()
+            };
+            json!({ "type": "sea-generator", "params": params })
+        };
+        let inner = json!({
+            "type": "concept-drift",
+            "params": { "stream": sea(2), "drift_stream": sea(3), "position": 500, "width": 1 },
+        });
+        let mut params = StreamChoice::default_params(StreamKind::ConceptDrift);
+        params["stream"] = sea(1);
+        params["drift_stream"] = inner;
+        let choice = StreamChoice::from_parts(StreamKind::ConceptDrift, params)
+            .unwrap()
+            .with_seed(7);
+        assert_eq!(choice.validate(), Ok(()));
+        assert_eq!(choice.target_kind(), Some(TargetKind::Nominal));
+        let StreamChoice::ConceptDrift(p) = &choice else {
+            panic!("expected ConceptDrift");
+        };
+        let StreamChoice::SeaGenerator(base) = &*p.stream else {
+            panic!("expected SEA");
+        };
+        assert_ne!(base.seed, p.seed);
+
+        let stream = build_stream(choice).unwrap();
+        assert_eq!(
+            stream.drift_markers(),
+            vec![DriftMarker::new(1_000, 1_000), DriftMarker::new(1_500, 1)]
+        );
+    }
+
+    #[test]
+    fn with_watch_only_affects_file_streams_and_defaults_off() {
+        let csv: CsvParameters = serde_json::from_value(json!({ "path": "a.csv" })).unwrap();
+        assert!(!csv.watch);
+
+        let StreamChoice::CsvFile(p) = StreamChoice::CsvFile(csv).with_watch(Some(30)) else {
+            panic!("expected CSV");
+        };
+        assert!(p.watch);
+        assert_eq!(p.watch_idle_secs, Some(30));
+
+        let sea = StreamChoice::SeaGenerator(SeaParameters::default()).with_watch(None);
+        assert!(matches!(sea, StreamChoice::SeaGenerator(_)));
+    }
+
+    #[test]
+    fn validate_rejects_a_class_index_past_the_last_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let arff = dir.path().join("d.arff");
+        std::fs::write(
+            &arff,
+            "@relation r\n@attribute a numeric\n@attribute c {x,y}\n@data\n1,x\n",
+        )
+        .unwrap();
+        let csv = dir.path().join("d.csv");
+        std::fs::write(&csv, "a,b,c\n1,2,x\n").unwrap();
+
+        let ok = StreamChoice::ArffFile(ArffParameters {
+            path: arff.clone(),
+            class_index: 1,
+            ..Default::default()
+        });
+        assert_eq!(ok.validate(), Ok(()));
+
+        let err = StreamChoice::ArffFile(ArffParameters {
+            path: arff,
+            class_index: 2,
+            ..Default::default()
+        })
+        .validate()
+        .unwrap_err();
+        assert_eq!(err.field, "class_index");
+        assert!(err.message.contains("has 2 columns"), "{}", err.message);
+
+        let csv: CsvParameters =
+            serde_json::from_value(json!({ "path": csv, "class_index": 3 })).unwrap();
+        let err = StreamChoice::CsvFile(csv).validate().unwrap_err();
+        assert!(err.message.contains("has 3 columns"), "{}", err.message);
+    }
 }
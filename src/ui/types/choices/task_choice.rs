@@ -1,11 +1,21 @@
-use crate::ui::cli::wizard::prompt_choice;
-use crate::ui::types::choices::{EvaluatorChoice, LearnerChoice, StreamChoice, UIChoice};
+use crate::core::instance_header::TargetKind;
+use crate::drift::ChangeDetectorConfig;
+use crate::tasks::SnapshotSchedule;
+use crate::ui::cli::wizard::prompt_choice_with;
+use crate::ui::types::choices::{
+    ClustererChoice, DetectorChoice, EvaluatorChoice, EvaluatorKind, LearnerChoice, LearnerKind,
+    NoParams, StreamChoice, UIChoice, ValidationError, validate_bounds,
+};
+use crate::utils::seed_sequence::SeedSequence;
 use schemars::{JsonSchema, Schema, schema_for};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value, json};
+use std::path::PathBuf;
+use strum::EnumMessage;
 use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct PrequentialParams {
     #[schemars(skip)]
     pub learner: LearnerChoice,
@@ -13,6 +23,536 @@ pub struct PrequentialParams {
     pub stream: StreamChoice,
     #[schemars(skip)]
     pub evaluator: EvaluatorChoice,
+    /// Optional per-instance prediction log (`.csv` or `.jsonl`).
+    #[serde(default)]
+    #[schemars(skip)]
+    pub prediction_log: Option<PathBuf>,
+    /// When snapshots are taken; every `sample_frequency` instances unless
+    /// set in a config file.
+    #[serde(default)]
+    #[schemars(skip)]
+    pub snapshot_schedule: SnapshotSchedule,
+    /// Optional change detector watching the learner's errors; set in a
+    /// config file, its detections go into the run report.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub drift_detector: Option<ChangeDetectorConfig>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Warm-up Instances",
+        description = "Train on the first N instances without evaluating them"
+    )]
+    pub warmup_instances: u64,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Instances",
+        description = "Stop after this many instances (None = unlimited)"
+    )]
+    pub max_instances: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Seconds",
+        description = "Stop after this many seconds (None = unlimited)"
+    )]
+    pub max_seconds: Option<u64>,
+
+    #[schemars(
+        title = "Sample Frequency",
+        description = "Emit metrics every N instances",
+        range(min = 1)
+    )]
+    pub sample_frequency: u64,
+
+    #[schemars(
+        title = "Memory Check Frequency",
+        description = "Check memory every N instances",
+        range(min = 1)
+    )]
+    pub mem_check_frequency: u64,
+}
+
+fn default_label_delay() -> u64 {
+    1_000
+}
+
+fn default_batch_size() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DelayedPrequentialParams {
+    #[schemars(skip)]
+    pub learner: LearnerChoice,
+    #[schemars(skip)]
+    pub stream: StreamChoice,
+    #[schemars(skip)]
+    pub evaluator: EvaluatorChoice,
+    /// Optional per-instance prediction log (`.csv` or `.jsonl`).
+    #[serde(default)]
+    #[schemars(skip)]
+    pub prediction_log: Option<PathBuf>,
+
+    #[serde(default = "default_label_delay")]
+    #[schemars(
+        title = "Label Delay",
+        description = "Instances that arrive before a label is revealed",
+        default = "default_label_delay"
+    )]
+    pub label_delay: u64,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Instances",
+        description = "Stop after this many instances (None = unlimited)"
+    )]
+    pub max_instances: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Seconds",
+        description = "Stop after this many seconds (None = unlimited)"
+    )]
+    pub max_seconds: Option<u64>,
+
+    #[schemars(
+        title = "Sample Frequency",
+        description = "Emit metrics every N instances",
+        range(min = 1)
+    )]
+    pub sample_frequency: u64,
+
+    #[schemars(
+        title = "Memory Check Frequency",
+        description = "Check memory every N instances",
+        range(min = 1)
+    )]
+    pub mem_check_frequency: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PeriodicHeldOutParams {
+    #[schemars(skip)]
+    pub learner: LearnerChoice,
+    #[schemars(skip)]
+    pub stream: StreamChoice,
+    /// Optional stream to draw the held-out set from; when absent the
+    /// first `test_size` instances of `stream` are held out.
+    #[serde(default)]
+    #[schemars(skip)]
+    pub test_stream: Option<StreamChoice>,
+    #[schemars(skip)]
+    pub evaluator: EvaluatorChoice,
+
+    #[schemars(
+        title = "Test Size",
+        description = "Number of instances held out for testing",
+        range(min = 1)
+    )]
+    pub test_size: u64,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Instances",
+        description = "Stop after training on this many instances (None = unlimited)"
+    )]
+    pub max_instances: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Seconds",
+        description = "Stop after this many seconds (None = unlimited)"
+    )]
+    pub max_seconds: Option<u64>,
+
+    #[schemars(
+        title = "Test Frequency",
+        description = "Evaluate on the held-out set every N training instances",
+        range(min = 1)
+    )]
+    pub test_frequency: u64,
+
+    #[schemars(
+        title = "Memory Check Frequency",
+        description = "Check memory every N instances",
+        range(min = 1)
+    )]
+    pub mem_check_frequency: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LearnModelParams {
+    #[schemars(skip)]
+    pub learner: LearnerChoice,
+    #[schemars(skip)]
+    pub stream: StreamChoice,
+
+    #[schemars(
+        with = "String",
+        title = "Model Path",
+        description = "File the trained model is written to"
+    )]
+    pub model_path: PathBuf,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Instances",
+        description = "Train on this many instances (None = whole stream)"
+    )]
+    pub max_instances: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Seconds",
+        description = "Stop after this many seconds (None = unlimited)"
+    )]
+    pub max_seconds: Option<u64>,
+
+    #[schemars(
+        title = "Sample Frequency",
+        description = "Report progress every N instances",
+        range(min = 1)
+    )]
+    pub sample_frequency: u64,
+
+    #[schemars(
+        title = "Memory Check Frequency",
+        description = "Check memory every N instances",
+        range(min = 1)
+    )]
+    pub mem_check_frequency: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ThroughputParams {
+    #[schemars(skip)]
+    pub learner: LearnerChoice,
+    #[schemars(skip)]
+    pub stream: StreamChoice,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Instances",
+        description = "Stop after this many instances (None = unlimited)"
+    )]
+    pub max_instances: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Seconds",
+        description = "Stop after this many seconds (None = unlimited)"
+    )]
+    pub max_seconds: Option<u64>,
+
+    #[schemars(
+        title = "Sample Frequency",
+        description = "Report throughput every N instances",
+        range(min = 1)
+    )]
+    pub sample_frequency: u64,
+
+    #[schemars(
+        title = "Memory Check Frequency",
+        description = "Check memory every N instances",
+        range(min = 1)
+    )]
+    pub mem_check_frequency: u64,
+
+    #[serde(default = "default_batch_size")]
+    #[schemars(
+        title = "Batch Size",
+        description = "Instances predicted and trained on per call (1 = one at a time)",
+        range(min = 1),
+        default = "default_batch_size"
+    )]
+    pub batch_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ConvertStreamParams {
+    #[schemars(skip)]
+    pub stream: StreamChoice,
+
+    #[schemars(
+        with = "String",
+        title = "Output Path",
+        description = "File the stream is written to; the extension selects the format (.arff, .csv or .parquet)"
+    )]
+    pub output: PathBuf,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Instances",
+        description = "Copy at most this many instances (None = whole stream)"
+    )]
+    pub max_instances: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Seconds",
+        description = "Stop after this many seconds (None = unlimited)"
+    )]
+    pub max_seconds: Option<u64>,
+
+    #[schemars(
+        title = "Sample Frequency",
+        description = "Report progress every N instances",
+        range(min = 1)
+    )]
+    pub sample_frequency: u64,
+
+    #[schemars(
+        title = "Memory Check Frequency",
+        description = "Check memory every N instances",
+        range(min = 1)
+    )]
+    pub mem_check_frequency: u64,
+}
+
+fn default_cluster_window() -> usize {
+    1_000
+}
+fn default_silhouette_sample() -> usize {
+    500
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ClusterStreamParams {
+    #[schemars(skip)]
+    pub clusterer: ClustererChoice,
+    #[schemars(skip)]
+    pub stream: StreamChoice,
+
+    #[serde(default = "default_cluster_window")]
+    #[schemars(
+        title = "Evaluation Window",
+        description = "Score the clustering on the last N instances",
+        range(min = 1),
+        default = "default_cluster_window"
+    )]
+    pub window_size: usize,
+
+    #[serde(default = "default_silhouette_sample")]
+    #[schemars(
+        title = "Silhouette Sample",
+        description = "Points sampled from the window for the silhouette estimate",
+        default = "default_silhouette_sample"
+    )]
+    pub silhouette_sample: usize,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Instances",
+        description = "Stop after this many instances (None = unlimited)"
+    )]
+    pub max_instances: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Seconds",
+        description = "Stop after this many seconds (None = unlimited)"
+    )]
+    pub max_seconds: Option<u64>,
+
+    #[schemars(
+        title = "Sample Frequency",
+        description = "Extract and evaluate macro-clusters every N instances",
+        range(min = 1)
+    )]
+    pub sample_frequency: u64,
+
+    #[schemars(
+        title = "Memory Check Frequency",
+        description = "Check memory every N instances",
+        range(min = 1)
+    )]
+    pub mem_check_frequency: u64,
+}
+
+fn default_positive_class() -> usize {
+    1
+}
+fn default_anomaly_threshold() -> f64 {
+    0.5
+}
+fn default_anomaly_window() -> usize {
+    1_000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AnomalyScoringParams {
+    #[schemars(skip)]
+    pub detector: DetectorChoice,
+    #[schemars(skip)]
+    pub stream: StreamChoice,
+    /// Optional per-instance score log (`.csv` or `.jsonl`).
+    #[serde(default)]
+    #[schemars(skip)]
+    pub score_log: Option<PathBuf>,
+
+    #[serde(default = "default_positive_class")]
+    #[schemars(
+        title = "Anomaly Class",
+        description = "Class index that marks an anomaly when the stream is labeled",
+        default = "default_positive_class"
+    )]
+    pub positive_class: usize,
+
+    #[serde(default = "default_anomaly_threshold")]
+    #[schemars(
+        title = "Threshold",
+        description = "Scores at or above this value count as flagged for precision/recall",
+        range(min = 0.0, max = 1.0),
+        default = "default_anomaly_threshold"
+    )]
+    pub threshold: f64,
+
+    #[serde(default = "default_anomaly_window")]
+    #[schemars(
+        title = "Evaluation Window",
+        description = "Compute the metrics over the last N scored instances",
+        range(min = 1),
+        default = "default_anomaly_window"
+    )]
+    pub eval_window: usize,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Instances",
+        description = "Stop after this many instances (None = unlimited)"
+    )]
+    pub max_instances: Option<u64>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Max Seconds",
+        description = "Stop after this many seconds (None = unlimited)"
+    )]
+    pub max_seconds: Option<u64>,
+
+    #[schemars(
+        title = "Sample Frequency",
+        description = "Report metrics every N instances",
+        range(min = 1)
+    )]
+    pub sample_frequency: u64,
+
+    #[schemars(
+        title = "Memory Check Frequency",
+        description = "Check memory every N instances",
+        range(min = 1)
+    )]
+    pub mem_check_frequency: u64,
+}
+
+fn default_repetitions() -> u64 {
+    5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MultiSeedParams {
+    #[schemars(skip)]
+    pub task: Box<TaskChoice>,
+
+    #[serde(default)]
+    #[schemars(
+        title = "Base Seed",
+        description = "Seed of the first repetition; later ones use base + i"
+    )]
+    pub base_seed: u64,
+
+    #[serde(default = "default_repetitions")]
+    #[schemars(
+        title = "Repetitions",
+        description = "Number of seeds to run",
+        range(min = 1),
+        default = "default_repetitions"
+    )]
+    pub repetitions: u64,
+}
+
+impl MultiSeedParams {
+    pub fn seeds(&self) -> Vec<u64> {
+        (0..self.repetitions)
+            .map(|i| self.base_seed.wrapping_add(i))
+            .collect()
+    }
+}
+
+fn default_folds() -> usize {
+    10
+}
+fn default_cv_seed() -> u64 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, EnumDiscriminants, PartialEq)]
+#[serde(tag = "type", content = "params", rename_all = "kebab-case")]
+#[strum_discriminants(name(ValidationMethodKind))]
+#[strum_discriminants(derive(EnumIter, EnumString, Display, IntoStaticStr, EnumMessage))]
+#[strum_discriminants(strum(serialize_all = "kebab-case"))]
+pub enum ValidationMethodChoice {
+    #[strum_discriminants(strum(
+        message = "Cross-Validation",
+        detailed_message = "k-fold: each instance is left out of one fold's training."
+    ))]
+    CrossValidation(NoParams),
+    #[strum_discriminants(strum(
+        message = "Split-Validation",
+        detailed_message = "Each instance trains exactly one fold."
+    ))]
+    SplitValidation(NoParams),
+    #[strum_discriminants(strum(
+        message = "Bootstrap-Validation",
+        detailed_message = "Each fold trains with a Poisson(1) instance weight."
+    ))]
+    Bootstrap(NoParams),
+}
+impl Default for ValidationMethodChoice {
+    fn default() -> Self {
+        Self::CrossValidation(NoParams::default())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PrequentialCVParams {
+    #[schemars(skip)]
+    pub learner: LearnerChoice,
+    #[schemars(skip)]
+    pub stream: StreamChoice,
+    #[schemars(skip)]
+    pub evaluator: EvaluatorChoice,
+    #[serde(default)]
+    #[schemars(skip)]
+    pub validation: ValidationMethodChoice,
+
+    #[serde(default = "default_folds")]
+    #[schemars(
+        title = "Folds",
+        description = "Number of learner copies (folds)",
+        range(min = 2),
+        default = "default_folds"
+    )]
+    pub folds: usize,
+
+    #[serde(default = "default_cv_seed")]
+    #[schemars(
+        title = "Random Seed",
+        description = "Seed for bootstrap weights",
+        default = "default_cv_seed"
+    )]
+    pub seed: u64,
 
     #[serde(default)]
     #[schemars(
@@ -54,6 +594,235 @@ pub enum TaskChoice {
         detailed_message = "Interleave test-then-train with periodic reporting."
     ))]
     EvaluatePrequential(PrequentialParams),
+    #[strum_discriminants(strum(
+        message = "Evaluate Interleaved Test-Then-Train",
+        detailed_message = "Test then train on every instance, reporting only on the sample cadence."
+    ))]
+    EvaluateInterleavedTestThenTrain(PrequentialParams),
+    #[strum_discriminants(strum(
+        message = "Evaluate Prequential Delayed",
+        detailed_message = "Predict on arrival; score and train once the label arrives N instances later."
+    ))]
+    EvaluatePrequentialDelayed(DelayedPrequentialParams),
+    #[strum_discriminants(strum(
+        message = "Evaluate Periodic Held-Out Test",
+        detailed_message = "Train on the stream and periodically score a fixed held-out set."
+    ))]
+    EvaluatePeriodicHeldOutTest(PeriodicHeldOutParams),
+    #[serde(rename = "evaluate-prequential-cv")]
+    #[strum_discriminants(strum(
+        serialize = "evaluate-prequential-cv",
+        message = "Evaluate Prequential CV",
+        detailed_message = "Prequential evaluation over k learner copies, reporting mean ± std."
+    ))]
+    EvaluatePrequentialCV(PrequentialCVParams),
+    #[strum_discriminants(strum(
+        message = "Learn Model",
+        detailed_message = "Train a learner without evaluating and save the model to disk."
+    ))]
+    LearnModel(LearnModelParams),
+    #[strum_discriminants(strum(
+        message = "Repeat With Seeds",
+        detailed_message = "Repeat a task over several seeds and report mean ± std curves."
+    ))]
+    RepeatWithSeeds(MultiSeedParams),
+    #[strum_discriminants(strum(
+        message = "Throughput Benchmark",
+        detailed_message = "Time train/predict calls of a learner with all metrics disabled."
+    ))]
+    ThroughputBenchmark(ThroughputParams),
+    #[strum_discriminants(strum(
+        message = "Convert Stream",
+        detailed_message = "Write a stream to another file format (ARFF, CSV or Parquet)."
+    ))]
+    ConvertStream(ConvertStreamParams),
+    #[strum_discriminants(strum(
+        message = "Cluster Stream",
+        detailed_message = "Feed a stream to an online clusterer and evaluate its macro-clusters."
+    ))]
+    ClusterStream(ClusterStreamParams),
+    #[strum_discriminants(strum(
+        message = "Anomaly Scoring",
+        detailed_message = "Score every instance with an anomaly detector, then train on it."
+    ))]
+    AnomalyScoring(AnomalyScoringParams),
+}
+
+impl TaskChoice {
+    /// Returns the same task with every seeded component reseeded.
+    ///
+    /// Stream generators (including a held-out test stream) and the
+    /// prequential CV bootstrap take `seed`; ARFF streams are unaffected.
+    pub fn with_seed(self, seed: u64) -> Self {
+        match self {
+            TaskChoice::EvaluatePrequential(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                TaskChoice::EvaluatePrequential(p)
+            }
+            TaskChoice::EvaluateInterleavedTestThenTrain(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                TaskChoice::EvaluateInterleavedTestThenTrain(p)
+            }
+            TaskChoice::EvaluatePrequentialDelayed(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                TaskChoice::EvaluatePrequentialDelayed(p)
+            }
+            TaskChoice::EvaluatePeriodicHeldOutTest(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                p.test_stream = p.test_stream.map(|s| s.with_seed(seed.wrapping_add(1)));
+                TaskChoice::EvaluatePeriodicHeldOutTest(p)
+            }
+            TaskChoice::EvaluatePrequentialCV(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                p.seed = seed;
+                TaskChoice::EvaluatePrequentialCV(p)
+            }
+            TaskChoice::LearnModel(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                TaskChoice::LearnModel(p)
+            }
+            TaskChoice::RepeatWithSeeds(mut p) => {
+                p.base_seed = seed;
+                TaskChoice::RepeatWithSeeds(p)
+            }
+            TaskChoice::ThroughputBenchmark(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                TaskChoice::ThroughputBenchmark(p)
+            }
+            TaskChoice::ConvertStream(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                TaskChoice::ConvertStream(p)
+            }
+            TaskChoice::ClusterStream(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                p.clusterer = p.clusterer.with_seed(seed);
+                TaskChoice::ClusterStream(p)
+            }
+            TaskChoice::AnomalyScoring(mut p) => {
+                p.stream = p.stream.with_seed(seed);
+                p.detector = p.detector.with_seed(seed);
+                TaskChoice::AnomalyScoring(p)
+            }
+        }
+    }
+
+    /// Returns the same task with every seeded component reseeded from
+    /// `seeds`, each under its own name: `stream`, `test-stream`,
+    /// `validation` (the prequential CV bootstrap), `clusterer`, `detector`,
+    /// and `repetitions` (the base seed of a repeated task, whose inner task
+    /// takes the `task` child sequence).
+    ///
+    /// Unlike [`with_seed`](Self::with_seed), no two components share a
+    /// seed, so one master seed fixes the whole run.
+    pub fn with_seed_sequence(self, seeds: &SeedSequence) -> Self {
+        let stream = seeds.derive("stream");
+        match self {
+            TaskChoice::EvaluatePrequential(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                TaskChoice::EvaluatePrequential(p)
+            }
+            TaskChoice::EvaluateInterleavedTestThenTrain(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                TaskChoice::EvaluateInterleavedTestThenTrain(p)
+            }
+            TaskChoice::EvaluatePrequentialDelayed(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                TaskChoice::EvaluatePrequentialDelayed(p)
+            }
+            TaskChoice::EvaluatePeriodicHeldOutTest(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                p.test_stream = p
+                    .test_stream
+                    .map(|s| s.with_seed(seeds.derive("test-stream")));
+                TaskChoice::EvaluatePeriodicHeldOutTest(p)
+            }
+            TaskChoice::EvaluatePrequentialCV(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                p.seed = seeds.derive("validation");
+                TaskChoice::EvaluatePrequentialCV(p)
+            }
+            TaskChoice::LearnModel(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                TaskChoice::LearnModel(p)
+            }
+            TaskChoice::RepeatWithSeeds(mut p) => {
+                p.task = Box::new(p.task.with_seed_sequence(&seeds.child("task")));
+                p.base_seed = seeds.derive("repetitions");
+                TaskChoice::RepeatWithSeeds(p)
+            }
+            TaskChoice::ThroughputBenchmark(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                TaskChoice::ThroughputBenchmark(p)
+            }
+            TaskChoice::ConvertStream(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                TaskChoice::ConvertStream(p)
+            }
+            TaskChoice::ClusterStream(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                p.clusterer = p.clusterer.with_seed(seeds.derive("clusterer"));
+                TaskChoice::ClusterStream(p)
+            }
+            TaskChoice::AnomalyScoring(mut p) => {
+                p.stream = p.stream.with_seed(stream);
+                p.detector = p.detector.with_seed(seeds.derive("detector"));
+                TaskChoice::AnomalyScoring(p)
+            }
+        }
+    }
+
+    /// Returns the same task with its input stream following its file (see
+    /// [`StreamChoice::with_watch`]).
+    ///
+    /// A held-out test stream is read up front and is never watched.
+    pub fn with_watch(self, idle_secs: Option<u64>) -> Self {
+        match self {
+            TaskChoice::EvaluatePrequential(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::EvaluatePrequential(p)
+            }
+            TaskChoice::EvaluateInterleavedTestThenTrain(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::EvaluateInterleavedTestThenTrain(p)
+            }
+            TaskChoice::EvaluatePrequentialDelayed(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::EvaluatePrequentialDelayed(p)
+            }
+            TaskChoice::EvaluatePeriodicHeldOutTest(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::EvaluatePeriodicHeldOutTest(p)
+            }
+            TaskChoice::EvaluatePrequentialCV(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::EvaluatePrequentialCV(p)
+            }
+            TaskChoice::LearnModel(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::LearnModel(p)
+            }
+            TaskChoice::RepeatWithSeeds(mut p) => {
+                p.task = Box::new(p.task.with_watch(idle_secs));
+                TaskChoice::RepeatWithSeeds(p)
+            }
+            TaskChoice::ThroughputBenchmark(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::ThroughputBenchmark(p)
+            }
+            TaskChoice::ConvertStream(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::ConvertStream(p)
+            }
+            TaskChoice::ClusterStream(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::ClusterStream(p)
+            }
+            TaskChoice::AnomalyScoring(mut p) => {
+                p.stream = p.stream.with_watch(idle_secs);
+                TaskChoice::AnomalyScoring(p)
+            }
+        }
+    }
 }
 
 impl UIChoice for TaskChoice {
@@ -68,29 +837,214 @@ impl UIChoice for TaskChoice {
     }
     fn default_params(kind: Self::Kind) -> Value {
         match kind {
-            TaskKind::EvaluatePrequential => json!({
+            TaskKind::EvaluatePrequential | TaskKind::EvaluateInterleavedTestThenTrain => json!({
+                "warmup_instances": 0,
+                "max_instances": null,
+                "max_seconds": null,
+                "sample_frequency": 100_000,
+                "mem_check_frequency": 100_000,
+            }),
+            TaskKind::EvaluatePrequentialDelayed => json!({
+                "label_delay": default_label_delay(),
+                "max_instances": null,
+                "max_seconds": null,
+                "sample_frequency": 100_000,
+                "mem_check_frequency": 100_000,
+            }),
+            TaskKind::EvaluatePeriodicHeldOutTest => json!({
+                "test_size": 100_000,
+                "max_instances": null,
+                "max_seconds": null,
+                "test_frequency": 100_000,
+                "mem_check_frequency": 100_000,
+            }),
+            TaskKind::EvaluatePrequentialCV => json!({
+                "folds": default_folds(),
+                "seed": default_cv_seed(),
+                "max_instances": null,
+                "max_seconds": null,
+                "sample_frequency": 100_000,
+                "mem_check_frequency": 100_000,
+            }),
+            TaskKind::ThroughputBenchmark => json!({
+                "max_instances": null,
+                "max_seconds": null,
+                "sample_frequency": 100_000,
+                "mem_check_frequency": 100_000,
+                "batch_size": default_batch_size(),
+            }),
+            TaskKind::LearnModel => json!({
+                "model_path": "model.json",
+                "max_instances": null,
+                "max_seconds": null,
+                "sample_frequency": 100_000,
+                "mem_check_frequency": 100_000,
+            }),
+            TaskKind::ClusterStream => json!({
+                "window_size": default_cluster_window(),
+                "silhouette_sample": default_silhouette_sample(),
+                "max_instances": null,
+                "max_seconds": null,
+                "sample_frequency": 100_000,
+                "mem_check_frequency": 100_000,
+            }),
+            TaskKind::AnomalyScoring => json!({
+                "positive_class": default_positive_class(),
+                "threshold": default_anomaly_threshold(),
+                "eval_window": default_anomaly_window(),
+                "max_instances": null,
+                "max_seconds": null,
+                "sample_frequency": 100_000,
+                "mem_check_frequency": 100_000,
+            }),
+            TaskKind::ConvertStream => json!({
+                "output": "converted.csv",
                 "max_instances": null,
                 "max_seconds": null,
                 "sample_frequency": 100_000,
                 "mem_check_frequency": 100_000,
             }),
+            TaskKind::RepeatWithSeeds => json!({
+                "base_seed": 0,
+                "repetitions": default_repetitions(),
+            }),
         }
     }
 
     fn subprompts<D: crate::ui::cli::drivers::PromptDriver>(
         driver: &D,
         kind: Self::Kind,
+        preset: &Map<String, Value>,
     ) -> anyhow::Result<Option<Map<String, Value>>> {
         match kind {
-            TaskKind::EvaluatePrequential => {
-                let learner = prompt_choice::<LearnerChoice, _>(driver)?;
-                let stream = prompt_choice::<StreamChoice, _>(driver)?;
-                let eval = prompt_choice::<EvaluatorChoice, _>(driver)?;
+            TaskKind::EvaluatePrequential
+            | TaskKind::EvaluateInterleavedTestThenTrain
+            | TaskKind::EvaluatePrequentialDelayed => {
+                let learner =
+                    prompt_choice_with::<LearnerChoice, _>(driver, preset.get("learner"))?;
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+                let eval =
+                    prompt_choice_with::<EvaluatorChoice, _>(driver, preset.get("evaluator"))?;
+                let log = driver.ask_bool(
+                    "Prediction Log",
+                    "Write one record per prediction to a .csv or .jsonl file",
+                    preset_is_set(preset, "prediction_log"),
+                )?;
+
+                let mut m = Map::new();
+                m.insert("learner".into(), serde_json::to_value(learner)?);
+                m.insert("stream".into(), serde_json::to_value(stream)?);
+                m.insert("evaluator".into(), serde_json::to_value(eval)?);
+                if log {
+                    let path = driver.ask_string(
+                        "Prediction Log Path",
+                        "File extension selects the format (.csv or .jsonl)",
+                        &preset_str(preset, "prediction_log", "predictions.csv"),
+                    )?;
+                    m.insert("prediction_log".into(), Value::String(path));
+                }
+                Ok(Some(m))
+            }
+            TaskKind::EvaluatePeriodicHeldOutTest => {
+                let learner =
+                    prompt_choice_with::<LearnerChoice, _>(driver, preset.get("learner"))?;
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+                let separate = driver.ask_bool(
+                    "Separate Test Stream",
+                    "Draw the held-out set from a second stream instead of the head of the training stream",
+                    preset_is_set(preset, "test_stream"),
+                )?;
+                let test_stream = if separate {
+                    Some(prompt_choice_with::<StreamChoice, _>(
+                        driver,
+                        preset.get("test_stream"),
+                    )?)
+                } else {
+                    None
+                };
+                let eval =
+                    prompt_choice_with::<EvaluatorChoice, _>(driver, preset.get("evaluator"))?;
+
+                let mut m = Map::new();
+                m.insert("learner".into(), serde_json::to_value(learner)?);
+                m.insert("stream".into(), serde_json::to_value(stream)?);
+                m.insert("test_stream".into(), serde_json::to_value(test_stream)?);
+                m.insert("evaluator".into(), serde_json::to_value(eval)?);
+                Ok(Some(m))
+            }
+            TaskKind::EvaluatePrequentialCV => {
+                let learner =
+                    prompt_choice_with::<LearnerChoice, _>(driver, preset.get("learner"))?;
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+                let eval =
+                    prompt_choice_with::<EvaluatorChoice, _>(driver, preset.get("evaluator"))?;
+                let validation = prompt_choice_with::<ValidationMethodChoice, _>(
+                    driver,
+                    preset.get("validation"),
+                )?;
 
                 let mut m = Map::new();
                 m.insert("learner".into(), serde_json::to_value(learner)?);
                 m.insert("stream".into(), serde_json::to_value(stream)?);
                 m.insert("evaluator".into(), serde_json::to_value(eval)?);
+                m.insert("validation".into(), serde_json::to_value(validation)?);
+                Ok(Some(m))
+            }
+            TaskKind::LearnModel | TaskKind::ThroughputBenchmark => {
+                let learner =
+                    prompt_choice_with::<LearnerChoice, _>(driver, preset.get("learner"))?;
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+
+                let mut m = Map::new();
+                m.insert("learner".into(), serde_json::to_value(learner)?);
+                m.insert("stream".into(), serde_json::to_value(stream)?);
+                Ok(Some(m))
+            }
+            TaskKind::ClusterStream => {
+                let clusterer =
+                    prompt_choice_with::<ClustererChoice, _>(driver, preset.get("clusterer"))?;
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+
+                let mut m = Map::new();
+                m.insert("clusterer".into(), serde_json::to_value(clusterer)?);
+                m.insert("stream".into(), serde_json::to_value(stream)?);
+                Ok(Some(m))
+            }
+            TaskKind::AnomalyScoring => {
+                let detector =
+                    prompt_choice_with::<DetectorChoice, _>(driver, preset.get("detector"))?;
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+                let log = driver.ask_bool(
+                    "Score Log",
+                    "Write every instance's anomaly score to a .csv or .jsonl file",
+                    preset_is_set(preset, "score_log"),
+                )?;
+
+                let mut m = Map::new();
+                m.insert("detector".into(), serde_json::to_value(detector)?);
+                m.insert("stream".into(), serde_json::to_value(stream)?);
+                if log {
+                    let path = driver.ask_string(
+                        "Score Log Path",
+                        "File extension selects the format (.csv or .jsonl)",
+                        &preset_str(preset, "score_log", "scores.csv"),
+                    )?;
+                    m.insert("score_log".into(), Value::String(path));
+                }
+                Ok(Some(m))
+            }
+            TaskKind::ConvertStream => {
+                let stream = prompt_choice_with::<StreamChoice, _>(driver, preset.get("stream"))?;
+
+                let mut m = Map::new();
+                m.insert("stream".into(), serde_json::to_value(stream)?);
+                Ok(Some(m))
+            }
+            TaskKind::RepeatWithSeeds => {
+                let task = prompt_choice_with::<TaskChoice, _>(driver, preset.get("task"))?;
+
+                let mut m = Map::new();
+                m.insert("task".into(), serde_json::to_value(task)?);
                 Ok(Some(m))
             }
         }
@@ -102,16 +1056,241 @@ impl UIChoice for TaskChoice {
                 let p: PrequentialParams = serde_json::from_value(params)?;
                 Ok(TaskChoice::EvaluatePrequential(p))
             }
+            TaskKind::EvaluateInterleavedTestThenTrain => {
+                let p: PrequentialParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::EvaluateInterleavedTestThenTrain(p))
+            }
+            TaskKind::EvaluatePrequentialDelayed => {
+                let p: DelayedPrequentialParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::EvaluatePrequentialDelayed(p))
+            }
+            TaskKind::EvaluatePeriodicHeldOutTest => {
+                let p: PeriodicHeldOutParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::EvaluatePeriodicHeldOutTest(p))
+            }
+            TaskKind::EvaluatePrequentialCV => {
+                let p: PrequentialCVParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::EvaluatePrequentialCV(p))
+            }
+            TaskKind::LearnModel => {
+                let p: LearnModelParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::LearnModel(p))
+            }
+            TaskKind::ThroughputBenchmark => {
+                let p: ThroughputParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::ThroughputBenchmark(p))
+            }
+            TaskKind::ClusterStream => {
+                let p: ClusterStreamParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::ClusterStream(p))
+            }
+            TaskKind::AnomalyScoring => {
+                let p: AnomalyScoringParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::AnomalyScoring(p))
+            }
+            TaskKind::ConvertStream => {
+                let p: ConvertStreamParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::ConvertStream(p))
+            }
+            TaskKind::RepeatWithSeeds => {
+                let p: MultiSeedParams = serde_json::from_value(params)?;
+                Ok(TaskChoice::RepeatWithSeeds(p))
+            }
+        }
+    }
+
+    /// Besides the schema bounds, checks the cadences, warm-up, and
+    /// held-out size against `max_instances`, and validates every nested
+    /// choice.
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_bounds(self)?;
+        let nested = |r: Result<(), ValidationError>, field: &str| r.map_err(|e| e.nested(field));
+        match self {
+            TaskChoice::EvaluatePrequential(p)
+            | TaskChoice::EvaluateInterleavedTestThenTrain(p) => {
+                check_cadence("sample_frequency", p.sample_frequency, p.max_instances)?;
+                check_warmup(p.warmup_instances, p.max_instances)?;
+                p.snapshot_schedule
+                    .validate()
+                    .map_err(|e| ValidationError::new("snapshot_schedule", e.to_string()))?;
+                if let Some(detector) = &p.drift_detector {
+                    detector
+                        .validate()
+                        .map_err(|e| ValidationError::new("drift_detector", e.to_string()))?;
+                }
+                nested(p.learner.validate(), "learner")?;
+                nested(p.stream.validate(), "stream")?;
+                nested(p.evaluator.validate(), "evaluator")?;
+                check_targets(&p.stream, &p.learner, Some(&p.evaluator))
+            }
+            TaskChoice::EvaluatePrequentialDelayed(p) => {
+                check_cadence("sample_frequency", p.sample_frequency, p.max_instances)?;
+                nested(p.learner.validate(), "learner")?;
+                nested(p.stream.validate(), "stream")?;
+                nested(p.evaluator.validate(), "evaluator")?;
+                check_targets(&p.stream, &p.learner, Some(&p.evaluator))
+            }
+            TaskChoice::EvaluatePeriodicHeldOutTest(p) => {
+                check_cadence("test_frequency", p.test_frequency, p.max_instances)?;
+                if p.test_stream.is_none()
+                    && let Some(max) = p.max_instances
+                    && p.test_size >= max
+                {
+                    return Err(ValidationError::new(
+                        "test_size",
+                        format!(
+                            "test size {} leaves nothing to train on: the held-out set is taken \
+                             from the first instances of the stream, so it must stay below \
+                             max_instances ({max}); lower it or use a separate test stream",
+                            p.test_size
+                        ),
+                    ));
+                }
+                nested(p.learner.validate(), "learner")?;
+                nested(p.stream.validate(), "stream")?;
+                if let Some(test) = &p.test_stream {
+                    nested(test.validate(), "test_stream")?;
+                }
+                nested(p.evaluator.validate(), "evaluator")?;
+                check_targets(&p.stream, &p.learner, Some(&p.evaluator))
+            }
+            TaskChoice::EvaluatePrequentialCV(p) => {
+                check_cadence("sample_frequency", p.sample_frequency, p.max_instances)?;
+                nested(p.learner.validate(), "learner")?;
+                nested(p.stream.validate(), "stream")?;
+                nested(p.evaluator.validate(), "evaluator")?;
+                nested(p.validation.validate(), "validation")?;
+                check_targets(&p.stream, &p.learner, Some(&p.evaluator))
+            }
+            TaskChoice::LearnModel(p) => {
+                check_cadence("sample_frequency", p.sample_frequency, p.max_instances)?;
+                nested(p.learner.validate(), "learner")?;
+                nested(p.stream.validate(), "stream")?;
+                check_targets(&p.stream, &p.learner, None)
+            }
+            TaskChoice::RepeatWithSeeds(p) => nested(p.task.validate(), "task"),
+            TaskChoice::ThroughputBenchmark(p) => {
+                check_cadence("sample_frequency", p.sample_frequency, p.max_instances)?;
+                nested(p.learner.validate(), "learner")?;
+                nested(p.stream.validate(), "stream")?;
+                check_targets(&p.stream, &p.learner, None)
+            }
+            TaskChoice::ConvertStream(p) => {
+                check_cadence("sample_frequency", p.sample_frequency, p.max_instances)?;
+                nested(p.stream.validate(), "stream")
+            }
+            TaskChoice::ClusterStream(p) => {
+                check_cadence("sample_frequency", p.sample_frequency, p.max_instances)?;
+                nested(p.clusterer.validate(), "clusterer")?;
+                nested(p.stream.validate(), "stream")
+            }
+            TaskChoice::AnomalyScoring(p) => {
+                check_cadence("sample_frequency", p.sample_frequency, p.max_instances)?;
+                nested(p.detector.validate(), "detector")?;
+                nested(p.stream.validate(), "stream")
+            }
         }
     }
 }
 
+/// A cadence longer than the run would only produce the final sample.
+fn check_cadence(
+    field: &str,
+    every: u64,
+    max_instances: Option<u64>,
+) -> Result<(), ValidationError> {
+    match max_instances {
+        Some(max) if every > max => Err(ValidationError::new(
+            field,
+            format!(
+                "{field} ({every}) is larger than max_instances ({max}), so nothing would be \
+                 recorded before the run ends; use at most {max}"
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The learner and evaluator must handle the kind of class the stream has.
+/// Streams whose header cannot be read are not checked.
+fn check_targets(
+    stream: &StreamChoice,
+    learner: &LearnerChoice,
+    evaluator: Option<&EvaluatorChoice>,
+) -> Result<(), ValidationError> {
+    let Some(target) = stream.target_kind() else {
+        return Ok(());
+    };
+    let mismatch = |field: &str, name: Option<&str>, kind: TargetKind| {
+        ValidationError::new(
+            field,
+            format!(
+                "{} is for {kind} classes, but the class attribute of the stream is {target}",
+                name.unwrap_or(field)
+            ),
+        )
+    };
+    if learner.target_kind() != target {
+        let name = LearnerKind::from(learner).get_message();
+        return Err(mismatch("learner", name, learner.target_kind()));
+    }
+    if let Some(evaluator) = evaluator
+        && evaluator.target_kind() != target
+    {
+        let name = EvaluatorKind::from(evaluator).get_message();
+        return Err(mismatch("evaluator", name, evaluator.target_kind()));
+    }
+    Ok(())
+}
+
+fn check_warmup(warmup: u64, max_instances: Option<u64>) -> Result<(), ValidationError> {
+    match max_instances {
+        Some(max) if warmup >= max => Err(ValidationError::new(
+            "warmup_instances",
+            format!(
+                "warm-up of {warmup} instances covers the whole run (max_instances = {max}), \
+                 so nothing would be evaluated; use fewer than {max}"
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Whether an optional output (log path, test stream) was set in `preset`.
+fn preset_is_set(preset: &Map<String, Value>, key: &str) -> bool {
+    preset.get(key).is_some_and(|v| !v.is_null())
+}
+
+fn preset_str(preset: &Map<String, Value>, key: &str, default: &str) -> String {
+    preset
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or(default)
+        .to_string()
+}
+
+impl UIChoice for ValidationMethodChoice {
+    type Kind = ValidationMethodKind;
+
+    fn schema() -> Schema {
+        schema_for!(ValidationMethodChoice)
+    }
+
+    fn prompt_label() -> &'static str {
+        "Choose a validation methodology:"
+    }
+
+    fn default_params(_kind: Self::Kind) -> Value {
+        json!({})
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ui::types::choices::{
-        EvaluatorChoice, EvaluatorKind, LearnerChoice, LearnerKind, StreamChoice, StreamKind,
-        UIChoice,
+        ClustererKind, DetectorKind, EvaluatorChoice, EvaluatorKind, LearnerChoice, LearnerKind,
+        StreamChoice, StreamKind, UIChoice,
     };
     use schemars::schema_for;
     use serde_json::{Value, json};
@@ -192,9 +1371,60 @@ mod tests {
                     Some("basic-classification")
                 );
             }
+            other => panic!("expected EvaluatePrequential, got {other:?}"),
         }
     }
 
+    #[test]
+    fn from_parts_builds_interleaved_variant() {
+        let params = json!({
+            "learner": make_choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+            "stream": make_choice_json::<StreamChoice>(StreamKind::SeaGenerator),
+            "evaluator": make_choice_json::<EvaluatorChoice>(EvaluatorKind::BasicClassification),
+            "max_instances": null,
+            "max_seconds": null,
+            "sample_frequency": 10u64,
+            "mem_check_frequency": 10u64,
+        });
+        let tc = <TaskChoice as UIChoice>::from_parts(
+            TaskKind::EvaluateInterleavedTestThenTrain,
+            params,
+        )
+        .unwrap();
+        assert!(matches!(
+            tc,
+            TaskChoice::EvaluateInterleavedTestThenTrain(_)
+        ));
+
+        let v = serde_json::to_value(tc).unwrap();
+        assert_eq!(
+            v.get("type").and_then(Value::as_str),
+            Some("evaluate-interleaved-test-then-train")
+        );
+    }
+
+    #[test]
+    fn delayed_prequential_defaults_label_delay() {
+        let params = json!({
+            "learner": make_choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+            "stream": make_choice_json::<StreamChoice>(StreamKind::SeaGenerator),
+            "evaluator": make_choice_json::<EvaluatorChoice>(EvaluatorKind::BasicClassification),
+            "sample_frequency": 10u64,
+            "mem_check_frequency": 10u64,
+        });
+        let tc = <TaskChoice as UIChoice>::from_parts(TaskKind::EvaluatePrequentialDelayed, params)
+            .unwrap();
+        let v = serde_json::to_value(&tc).unwrap();
+        assert_eq!(
+            v.get("type").and_then(Value::as_str),
+            Some("evaluate-prequential-delayed")
+        );
+        let TaskChoice::EvaluatePrequentialDelayed(p) = tc else {
+            panic!("expected EvaluatePrequentialDelayed");
+        };
+        assert_eq!(p.label_delay, default_label_delay());
+    }
+
     #[test]
     fn taskchoice_serializes_as_tagged_enum() {
         let learner_json = make_choice_json::<LearnerChoice>(LearnerKind::NaiveBayes);
@@ -206,6 +1436,10 @@ mod tests {
             learner: serde_json::from_value(learner_json).unwrap(),
             stream: serde_json::from_value(stream_json).unwrap(),
             evaluator: serde_json::from_value(evaluator_json).unwrap(),
+            prediction_log: None,
+            snapshot_schedule: SnapshotSchedule::Fixed,
+            drift_detector: None,
+            warmup_instances: 0,
             max_instances: None,
             max_seconds: None,
             sample_frequency: 1000,
@@ -259,10 +1493,312 @@ mod tests {
 
         assert!(obj.contains_key("max_instances"));
         assert!(obj.contains_key("max_seconds"));
+        assert!(obj.contains_key("warmup_instances"));
+    }
+
+    #[test]
+    fn from_parts_builds_held_out_with_optional_test_stream() {
+        let mut params =
+            <TaskChoice as UIChoice>::default_params(TaskKind::EvaluatePeriodicHeldOutTest);
+        let obj = params.as_object_mut().unwrap();
+        obj.insert(
+            "learner".into(),
+            make_choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+        );
+        obj.insert(
+            "stream".into(),
+            make_choice_json::<StreamChoice>(StreamKind::SeaGenerator),
+        );
+        obj.insert(
+            "evaluator".into(),
+            make_choice_json::<EvaluatorChoice>(EvaluatorKind::BasicClassification),
+        );
+
+        let tc = <TaskChoice as UIChoice>::from_parts(
+            TaskKind::EvaluatePeriodicHeldOutTest,
+            params.clone(),
+        )
+        .unwrap();
+        match tc {
+            TaskChoice::EvaluatePeriodicHeldOutTest(p) => {
+                assert!(p.test_stream.is_none());
+                assert_eq!(p.test_size, 100_000);
+                assert_eq!(p.test_frequency, 100_000);
+            }
+            other => panic!("expected EvaluatePeriodicHeldOutTest, got {other:?}"),
+        }
+
+        params.as_object_mut().unwrap().insert(
+            "test_stream".into(),
+            make_choice_json::<StreamChoice>(StreamKind::AgrawalGenerator),
+        );
+        let tc =
+            <TaskChoice as UIChoice>::from_parts(TaskKind::EvaluatePeriodicHeldOutTest, params)
+                .unwrap();
+        let TaskChoice::EvaluatePeriodicHeldOutTest(p) = tc else {
+            panic!("expected EvaluatePeriodicHeldOutTest");
+        };
+        assert!(matches!(
+            p.test_stream,
+            Some(StreamChoice::AgrawalGenerator(_))
+        ));
+    }
+
+    #[test]
+    fn prequential_cv_defaults_and_tag() {
+        let params = json!({
+            "learner": make_choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+            "stream": make_choice_json::<StreamChoice>(StreamKind::SeaGenerator),
+            "evaluator": make_choice_json::<EvaluatorChoice>(EvaluatorKind::BasicClassification),
+            "sample_frequency": 10u64,
+            "mem_check_frequency": 10u64,
+        });
+        let tc =
+            <TaskChoice as UIChoice>::from_parts(TaskKind::EvaluatePrequentialCV, params).unwrap();
+        let v = serde_json::to_value(&tc).unwrap();
+        assert_eq!(
+            v.get("type").and_then(Value::as_str),
+            Some("evaluate-prequential-cv")
+        );
+        let TaskChoice::EvaluatePrequentialCV(p) = tc else {
+            panic!("expected EvaluatePrequentialCV");
+        };
+        assert_eq!(p.folds, 10);
+        assert_eq!(
+            p.validation,
+            ValidationMethodChoice::CrossValidation(NoParams {})
+        );
+    }
+
+    #[test]
+    fn validation_method_kinds_round_trip() {
+        use strum::IntoEnumIterator;
+        for kind in ValidationMethodKind::iter() {
+            let params = <ValidationMethodChoice as UIChoice>::default_params(kind);
+            let choice = <ValidationMethodChoice as UIChoice>::from_parts(kind, params).unwrap();
+            assert_eq!(ValidationMethodKind::from(&choice), kind);
+        }
+    }
+
+    #[test]
+    fn throughput_benchmark_needs_no_evaluator() {
+        let params = json!({
+            "learner": make_choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+            "stream": make_choice_json::<StreamChoice>(StreamKind::SeaGenerator),
+            "sample_frequency": 10u64,
+            "mem_check_frequency": 10u64,
+        });
+        let tc =
+            <TaskChoice as UIChoice>::from_parts(TaskKind::ThroughputBenchmark, params).unwrap();
+        let v = serde_json::to_value(&tc).unwrap();
+        assert_eq!(
+            v.get("type").and_then(Value::as_str),
+            Some("throughput-benchmark")
+        );
+        assert!(v["params"].get("evaluator").is_none());
+    }
+
+    #[test]
+    fn convert_stream_takes_only_a_stream_and_output() {
+        let mut params = <TaskChoice as UIChoice>::default_params(TaskKind::ConvertStream);
+        params["stream"] = make_choice_json::<StreamChoice>(StreamKind::SeaGenerator);
+        let tc = <TaskChoice as UIChoice>::from_parts(TaskKind::ConvertStream, params).unwrap();
+        let TaskChoice::ConvertStream(p) = &tc else {
+            panic!("expected ConvertStream");
+        };
+        assert_eq!(p.output, PathBuf::from("converted.csv"));
+
+        let v = serde_json::to_value(&tc).unwrap();
+        assert_eq!(
+            v.get("type").and_then(Value::as_str),
+            Some("convert-stream")
+        );
+        assert!(v["params"].get("learner").is_none());
+    }
+
+    #[test]
+    fn cluster_stream_reseeds_stream_and_clusterer() {
+        let mut params = <TaskChoice as UIChoice>::default_params(TaskKind::ClusterStream);
+        params["clusterer"] = make_choice_json::<ClustererChoice>(ClustererKind::CluStream);
+        params["stream"] = make_choice_json::<StreamChoice>(StreamKind::SeaGenerator);
+        let tc = <TaskChoice as UIChoice>::from_parts(TaskKind::ClusterStream, params).unwrap();
+
+        let TaskChoice::ClusterStream(p) = tc.with_seed(11) else {
+            panic!("expected ClusterStream");
+        };
+        assert_eq!(p.window_size, 1_000);
+        let ClustererChoice::CluStream(c) = p.clusterer else {
+            panic!("expected CluStream");
+        };
+        assert_eq!(c.seed, 11);
+        let StreamChoice::SeaGenerator(s) = p.stream else {
+            panic!("expected SEA");
+        };
+        assert_eq!(s.seed, 11);
+    }
+
+    #[test]
+    fn a_seed_sequence_gives_each_component_its_own_seed() {
+        let mut params = <TaskChoice as UIChoice>::default_params(TaskKind::ClusterStream);
+        params["clusterer"] = make_choice_json::<ClustererChoice>(ClustererKind::CluStream);
+        params["stream"] = make_choice_json::<StreamChoice>(StreamKind::SeaGenerator);
+        let tc = <TaskChoice as UIChoice>::from_parts(TaskKind::ClusterStream, params).unwrap();
+
+        let seeds = SeedSequence::new(11);
+        let TaskChoice::ClusterStream(p) = tc.with_seed_sequence(&seeds) else {
+            panic!("expected ClusterStream");
+        };
+        let (StreamChoice::SeaGenerator(s), ClustererChoice::CluStream(c)) =
+            (p.stream, p.clusterer)
+        else {
+            panic!("expected SEA and CluStream");
+        };
+        assert_eq!(s.seed, seeds.derive("stream"));
+        assert_eq!(c.seed, seeds.derive("clusterer"));
+        assert_ne!(s.seed, c.seed);
+    }
+
+    #[test]
+    fn anomaly_scoring_defaults_and_reseeding() {
+        let mut params = <TaskChoice as UIChoice>::default_params(TaskKind::AnomalyScoring);
+        params["detector"] = make_choice_json::<DetectorChoice>(DetectorKind::HalfSpaceTrees);
+        params["stream"] = make_choice_json::<StreamChoice>(StreamKind::SeaGenerator);
+        let tc = <TaskChoice as UIChoice>::from_parts(TaskKind::AnomalyScoring, params).unwrap();
+
+        let TaskChoice::AnomalyScoring(p) = tc.with_seed(6) else {
+            panic!("expected AnomalyScoring");
+        };
+        assert_eq!(p.positive_class, 1);
+        assert!(p.score_log.is_none());
+        let DetectorChoice::HalfSpaceTrees(d) = p.detector;
+        assert_eq!(d.seed, 6);
+    }
+
+    #[test]
+    fn repeat_with_seeds_reseeds_the_inner_stream() {
+        let inner = json!({
+            "type": "evaluate-prequential",
+            "params": {
+                "learner": make_choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+                "stream": make_choice_json::<StreamChoice>(StreamKind::SeaGenerator),
+                "evaluator": make_choice_json::<EvaluatorChoice>(EvaluatorKind::BasicClassification),
+                "sample_frequency": 10u64,
+                "mem_check_frequency": 10u64,
+            }
+        });
+        let tc = <TaskChoice as UIChoice>::from_parts(
+            TaskKind::RepeatWithSeeds,
+            json!({ "task": inner, "base_seed": 3, "repetitions": 2 }),
+        )
+        .unwrap();
+        let TaskChoice::RepeatWithSeeds(p) = tc else {
+            panic!("expected RepeatWithSeeds");
+        };
+        assert_eq!(p.seeds(), vec![3, 4]);
+
+        let TaskChoice::EvaluatePrequential(inner) = p.task.with_seed(4) else {
+            panic!("expected EvaluatePrequential");
+        };
+        let StreamChoice::SeaGenerator(sea) = inner.stream else {
+            panic!("expected SEA");
+        };
+        assert_eq!(sea.seed, 4);
     }
 
     #[test]
     fn prompt_label_is_expected() {
         assert_eq!(<TaskChoice as UIChoice>::prompt_label(), "Choose a task:");
     }
+
+    fn prequential(params: Value) -> TaskChoice {
+        let mut base = json!({
+            "learner": make_choice_json::<LearnerChoice>(LearnerKind::NaiveBayes),
+            "stream": make_choice_json::<StreamChoice>(StreamKind::SeaGenerator),
+            "evaluator": make_choice_json::<EvaluatorChoice>(EvaluatorKind::BasicClassification),
+            "sample_frequency": 100u64,
+            "mem_check_frequency": 100u64,
+        });
+        for (k, v) in params.as_object().unwrap() {
+            base[k] = v.clone();
+        }
+        <TaskChoice as UIChoice>::from_parts(TaskKind::EvaluatePrequential, base).unwrap()
+    }
+
+    #[test]
+    fn validate_rejects_cadences_and_warmups_past_max_instances() {
+        assert_eq!(
+            prequential(json!({ "max_instances": 1000 })).validate(),
+            Ok(())
+        );
+
+        let err = prequential(json!({ "sample_frequency": 5000, "max_instances": 1000 }))
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.field, "sample_frequency");
+        assert!(
+            err.message.contains("max_instances (1000)"),
+            "{}",
+            err.message
+        );
+
+        let err = prequential(json!({ "warmup_instances": 1000, "max_instances": 1000 }))
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.field, "warmup_instances");
+
+        let err = prequential(json!({
+            "snapshot_schedule": { "type": "interval", "seconds": 0.0 }
+        }))
+        .validate()
+        .unwrap_err();
+        assert_eq!(err.field, "snapshot_schedule");
+    }
+
+    #[test]
+    fn validate_reports_nested_errors_under_their_parent() {
+        let mut stream = make_choice_json::<StreamChoice>(StreamKind::SeaGenerator);
+        stream["params"]["noise_pct"] = json!(2.0);
+        let err = prequential(json!({ "stream": stream }))
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.field, "stream.noise_pct");
+        assert_eq!(err.top_field(), "stream");
+    }
+
+    #[test]
+    fn validate_matches_learner_and_evaluator_to_the_class_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reg.arff");
+        std::fs::write(
+            &path,
+            "@relation r\n@attribute x numeric\n@attribute y numeric\n@data\n1,2\n",
+        )
+        .unwrap();
+        let stream = json!({ "type": "arff-file", "params": { "path": path, "class_index": 1 } });
+        let target_mean = make_choice_json::<LearnerChoice>(LearnerKind::TargetMean);
+        let regression = make_choice_json::<EvaluatorChoice>(EvaluatorKind::BasicRegression);
+
+        let err = prequential(json!({ "stream": stream }))
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.field, "learner");
+        assert!(err.message.contains("is numeric"), "{}", err.message);
+
+        let err = prequential(json!({ "stream": stream, "learner": target_mean }))
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.field, "evaluator");
+
+        let ok = prequential(json!({
+            "stream": stream,
+            "learner": target_mean,
+            "evaluator": regression,
+        }));
+        assert_eq!(ok.validate(), Ok(()));
+
+        let err = prequential(json!({ "learner": target_mean }))
+            .validate()
+            .unwrap_err();
+        assert!(err.message.contains("is nominal"), "{}", err.message);
+    }
 }
@@ -5,6 +5,8 @@ use serde::de::DeserializeOwned;
 use serde_json::{Map, Value, json};
 use strum::{EnumMessage, IntoEnumIterator};
 
+use crate::ui::types::choices::{ValidationError, validate_bounds};
+
 /// Contract for any “choice enum”
 pub trait UIChoice: Sized + Serialize + DeserializeOwned + JsonSchema {
     type Kind: Copy + Into<&'static str> + EnumMessage + IntoEnumIterator;
@@ -16,7 +18,7 @@ pub trait UIChoice: Sized + Serialize + DeserializeOwned + JsonSchema {
         "Choose a type:"
     }
     fn prompt_help() -> Option<&'static str> {
-        Some("↑/↓ to navigate, ↵ to select")
+        Some("↑/↓ to navigate, ↵ to select, Esc to go back")
     }
 
     /// Default `params` JSON for a given kind (usually from `*Parameters::default()`).
@@ -24,13 +26,26 @@ pub trait UIChoice: Sized + Serialize + DeserializeOwned + JsonSchema {
 
     /// Optional hook to collect *extra* params via nested wizards
     /// (e.g., learner/stream/evaluator). Default: none.
+    ///
+    /// `preset` holds earlier answers for this choice's params (empty on a
+    /// fresh run) and should seed the nested prompts' defaults.
     fn subprompts<D: crate::ui::cli::drivers::PromptDriver>(
         _driver: &D,
         _kind: Self::Kind,
+        _preset: &Map<String, Value>,
     ) -> Result<Option<Map<String, Value>>> {
         Ok(None)
     }
 
+    /// Rejects parameter values that cannot run, before the task is built.
+    ///
+    /// Default: the schema bounds of the numeric params. Choices with
+    /// cross-field constraints or nested choices override this, calling
+    /// [`validate_bounds`] themselves.
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_bounds(self)
+    }
+
     /// Build the typed enum from kind + params.
     fn from_parts(kind: Self::Kind, params: Value) -> Result<Self> {
         let key: &'static str = kind.into();
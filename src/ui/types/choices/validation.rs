@@ -0,0 +1,106 @@
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::ui::types::choices::{UIChoice, specs_for_kind};
+
+/// A parameter value, or a combination of values, that cannot run.
+///
+/// `field` is the dotted path of the parameter to change, relative to the
+/// validated choice (e.g. `sample_frequency` or `stream.class_index`).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{field}: {message}")]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// The same error for a choice nested under `parent`.
+    pub fn nested(self, parent: &str) -> Self {
+        Self {
+            field: format!("{parent}.{}", self.field),
+            message: self.message,
+        }
+    }
+
+    /// First segment of [`field`](Self::field), i.e. the parameter of the
+    /// validated choice itself.
+    pub fn top_field(&self) -> &str {
+        self.field.split('.').next().unwrap_or_default()
+    }
+}
+
+/// Checks the numeric parameters of `choice` against the minimum and
+/// maximum declared in its schema. Nested choices are not visited.
+pub fn validate_bounds<C: UIChoice>(choice: &C) -> Result<(), ValidationError> {
+    let Ok(value) = serde_json::to_value(choice) else {
+        return Ok(());
+    };
+    let Some(kind) = value.get("type").and_then(Value::as_str) else {
+        return Ok(());
+    };
+    let empty = Map::new();
+    let params = value
+        .get("params")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let Ok(specs) = specs_for_kind(&C::schema(), kind) else {
+        return Ok(());
+    };
+    for s in specs {
+        let Some(v) = params.get(&s.name).and_then(Value::as_f64) else {
+            continue;
+        };
+        let too_low = s.min.is_some_and(|min| v < min);
+        let too_high = s.max.is_some_and(|max| v > max);
+        if !too_low && !too_high {
+            continue;
+        }
+        let range = match (s.min, s.max) {
+            (Some(min), Some(max)) => format!("between {min} and {max}"),
+            (Some(min), None) => format!("at least {min}"),
+            (None, Some(max)) => format!("at most {max}"),
+            (None, None) => unreachable!(),
+        };
+        return Err(ValidationError::new(
+            s.name,
+            format!("{} must be {range}, got {v}", s.title),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::types::choices::{SeaParameters, StreamChoice};
+
+    #[test]
+    fn out_of_range_numbers_name_the_field_and_bounds() {
+        let ok = StreamChoice::SeaGenerator(SeaParameters::default());
+        assert_eq!(validate_bounds(&ok), Ok(()));
+
+        let bad = StreamChoice::SeaGenerator(SeaParameters {
+            noise_pct: 1.5,
+            ..Default::default()
+        });
+        let err = validate_bounds(&bad).unwrap_err();
+        assert_eq!(err.field, "noise_pct");
+        assert!(err.message.contains("between 0 and 1"), "{}", err.message);
+    }
+
+    #[test]
+    fn nested_errors_keep_the_top_level_field() {
+        let err = ValidationError::new("class_index", "out of range").nested("stream");
+        assert_eq!(err.field, "stream.class_index");
+        assert_eq!(err.top_field(), "stream");
+        assert_eq!(err.to_string(), "stream.class_index: out of range");
+    }
+}
@@ -45,6 +45,23 @@ pub fn split_csv_preserving_quotes(line: &str) -> Vec<String> {
     out
 }
 
+/// Wraps `s` in quotes when it would not survive
+/// [`split_csv_preserving_quotes`] and [`strip_surrounding_quotes`] as is.
+///
+/// Single quotes are used unless `s` itself contains one.
+pub fn quote_if_needed(s: &str) -> String {
+    let needs_quotes =
+        s.is_empty() || s == "?" || s.contains([',', ' ', '\t', '%', '{', '}', '\'', '"']);
+    if !needs_quotes {
+        return s.to_string();
+    }
+    if s.contains('\'') {
+        format!("\"{s}\"")
+    } else {
+        format!("'{s}'")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,4 +79,15 @@ mod tests {
         let p = split_csv_preserving_quotes(line);
         assert_eq!(p, vec!["'sunny'", "85", "\"85\"", "FALSE", "no"]);
     }
+
+    #[test]
+    fn quote_only_when_needed() {
+        assert_eq!(quote_if_needed("sunny"), "sunny");
+        assert_eq!(quote_if_needed("over cast"), "'over cast'");
+        assert_eq!(quote_if_needed("a,b"), "'a,b'");
+        assert_eq!(quote_if_needed("it's"), "\"it's\"");
+        assert_eq!(quote_if_needed("it's here"), "\"it's here\"");
+        assert_eq!(quote_if_needed("?"), "'?'");
+        assert_eq!(quote_if_needed(""), "''");
+    }
 }
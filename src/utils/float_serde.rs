@@ -0,0 +1,99 @@
+//! Serde helpers for `f64` fields that may hold non-finite values.
+//!
+//! JSON has no NaN or infinity, and `serde_json` writes them as `null` and
+//! refuses to read `null` back into an `f64`. With
+//! `#[serde(with = "crate::utils::float_serde")]` finite values stay plain
+//! numbers while non-finite ones are written as the strings `"NaN"`, `"inf"`,
+//! and `"-inf"`, so a saved value reads back exactly. `null` reads as NaN.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Repr {
+    Number(f64),
+    Text(String),
+    Null,
+}
+
+fn to_repr(v: f64) -> Repr {
+    match v {
+        v if v.is_finite() => Repr::Number(v),
+        v if v.is_nan() => Repr::Text("NaN".into()),
+        v if v > 0.0 => Repr::Text("inf".into()),
+        _ => Repr::Text("-inf".into()),
+    }
+}
+
+fn from_repr<E: serde::de::Error>(repr: Repr) -> Result<f64, E> {
+    match repr {
+        Repr::Number(v) => Ok(v),
+        Repr::Null => Ok(f64::NAN),
+        Repr::Text(text) => match text.as_str() {
+            "NaN" => Ok(f64::NAN),
+            "inf" => Ok(f64::INFINITY),
+            "-inf" => Ok(f64::NEG_INFINITY),
+            other => Err(E::custom(format!("`{other}` is not a number"))),
+        },
+    }
+}
+
+pub fn serialize<S: Serializer>(v: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    to_repr(*v).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    from_repr(Repr::deserialize(deserializer)?)
+}
+
+/// The same encoding for the values of a map.
+pub mod map {
+    use super::*;
+
+    pub fn serialize<K: Serialize, S: Serializer>(
+        map: &BTreeMap<K, f64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(map.iter().map(|(k, v)| (k, to_repr(*v))))
+    }
+
+    pub fn deserialize<'de, K: Deserialize<'de> + Ord, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<K, f64>, D::Error> {
+        BTreeMap::<K, Repr>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(k, v)| Ok((k, from_repr(v)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Row {
+        #[serde(with = "super")]
+        value: f64,
+        #[serde(with = "super::map")]
+        extras: BTreeMap<String, f64>,
+    }
+
+    #[test]
+    fn non_finite_values_read_back_exactly() {
+        let row = Row {
+            value: f64::NAN,
+            extras: BTreeMap::from([("a".to_string(), 0.1), ("b".to_string(), f64::NEG_INFINITY)]),
+        };
+        let json = serde_json::to_string(&row).unwrap();
+        assert_eq!(json, r#"{"value":"NaN","extras":{"a":0.1,"b":"-inf"}}"#);
+        let back: Row = serde_json::from_str(&json).unwrap();
+        assert!(back.value.is_nan());
+        assert_eq!(back.extras, row.extras);
+
+        let null: Row = serde_json::from_str(r#"{"value":null,"extras":{}}"#).unwrap();
+        assert!(null.value.is_nan());
+        assert!(serde_json::from_str::<Row>(r#"{"value":"x","extras":{}}"#).is_err());
+    }
+}
@@ -1,3 +1,117 @@
 pub fn normal_probability(a: f64) -> f64 {
     0.5 * (1.0 + libm::erf(a / (2.0f64).sqrt()))
 }
+
+/// Mean and population standard deviation of `values` (`NaN` when empty).
+pub fn mean_std(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (f64::NAN, f64::NAN);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+    (mean, var.sqrt())
+}
+
+/// Two-sided exact sign test for paired differences.
+///
+/// Zero differences are dropped. Returns the number of positive
+/// differences and the p-value, or `None` when every difference is zero.
+pub fn sign_test(diffs: &[f64]) -> Option<(usize, f64)> {
+    let pos = diffs.iter().filter(|d| **d > 0.0).count();
+    let neg = diffs.iter().filter(|d| **d < 0.0).count();
+    let n = pos + neg;
+    if n == 0 {
+        return None;
+    }
+    let k = pos.min(neg);
+    let ln_half_n = n as f64 * 0.5f64.ln();
+    let tail: f64 = (0..=k).map(|i| (ln_choose(n, i) + ln_half_n).exp()).sum();
+    Some((pos, (2.0 * tail).min(1.0)))
+}
+
+/// Two-sided Wilcoxon signed-rank test for paired differences, using the
+/// normal approximation with tie correction.
+///
+/// Zero differences are dropped. Returns the signed-rank statistic `W+`
+/// and the p-value, or `None` with fewer than two non-zero differences.
+pub fn wilcoxon_signed_rank(diffs: &[f64]) -> Option<(f64, f64)> {
+    let mut nz: Vec<f64> = diffs
+        .iter()
+        .copied()
+        .filter(|d| d.is_finite() && *d != 0.0)
+        .collect();
+    let n = nz.len();
+    if n < 2 {
+        return None;
+    }
+    nz.sort_by(|a, b| a.abs().total_cmp(&b.abs()));
+
+    let mut w_plus = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < n {
+        let mut j = i;
+        while j + 1 < n && nz[j + 1].abs() == nz[i].abs() {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        let t = (j - i + 1) as f64;
+        tie_term += t * t * t - t;
+        w_plus += nz[i..=j].iter().filter(|d| **d > 0.0).count() as f64 * rank;
+        i = j + 1;
+    }
+
+    let nf = n as f64;
+    let mean = nf * (nf + 1.0) / 4.0;
+    let var = nf * (nf + 1.0) * (2.0 * nf + 1.0) / 24.0 - tie_term / 48.0;
+    if var <= 0.0 {
+        return Some((w_plus, 1.0));
+    }
+    let z = (w_plus - mean) / var.sqrt();
+    let p = 2.0 * (1.0 - normal_probability(z.abs()));
+    Some((w_plus, p.clamp(0.0, 1.0)))
+}
+
+fn ln_choose(n: usize, k: usize) -> f64 {
+    libm::lgamma(n as f64 + 1.0) - libm::lgamma(k as f64 + 1.0) - libm::lgamma((n - k) as f64 + 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_test_matches_the_binomial_tail() {
+        assert!(sign_test(&[0.0, 0.0]).is_none());
+        let (pos, p) = sign_test(&[1.0; 10]).unwrap();
+        assert_eq!(pos, 10);
+        assert!((p - 2.0 / 1024.0).abs() < 1e-12);
+        let (_, p) = sign_test(&[1.0, -1.0, 1.0, -1.0]).unwrap();
+        assert_eq!(p, 1.0);
+    }
+
+    #[test]
+    fn wilcoxon_detects_a_consistent_shift() {
+        let shifted: Vec<f64> = (1..=30).map(|i| i as f64 * 0.01).collect();
+        let (w, p) = wilcoxon_signed_rank(&shifted).unwrap();
+        assert_eq!(w, 465.0);
+        assert!(p < 1e-5);
+
+        let mixed: Vec<f64> = (1..=30)
+            .map(|i| if i % 2 == 0 { i as f64 } else { -(i as f64) })
+            .collect();
+        let (_, p) = wilcoxon_signed_rank(&mixed).unwrap();
+        assert!(p > 0.5);
+        assert!(wilcoxon_signed_rank(&[0.0, 1.0]).is_none());
+    }
+
+    #[test]
+    fn mean_std_handles_empty_and_simple_input() {
+        let (m, s) = mean_std(&[]);
+        assert!(m.is_nan() && s.is_nan());
+        let (m, s) = mean_std(&[1.0, 3.0]);
+        assert_eq!(m, 2.0);
+        assert_eq!(s, 1.0);
+    }
+}
@@ -1,3 +1,5 @@
 pub mod file_parsing;
+pub mod float_serde;
 pub mod math;
+pub mod seed_sequence;
 pub mod system;